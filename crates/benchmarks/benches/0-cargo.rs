@@ -23,6 +23,18 @@ mod toml_parse {
         );
     }
 
+    /// Compare with `events` for the benefit of stopping early
+    #[divan::bench(args=MANIFESTS)]
+    fn find_key(sample: &Data<'static>) -> Option<::toml_parse::Span> {
+        ::toml_parse::find_key(sample.content(), "package.name").map(|(_, span)| span)
+    }
+
+    /// Compare with `toml_edit::document` for the benefit of only indexing the structure
+    #[divan::bench(args=MANIFESTS)]
+    fn index_document(sample: &Data<'static>) -> usize {
+        ::toml_parse::index_document(sample.content()).len()
+    }
+
     #[divan::bench(args=MANIFESTS)]
     fn decoded(sample: &Data<'static>) {
         struct Void<'s> {
@@ -92,6 +104,29 @@ mod toml_edit {
     fn manifest(sample: &Data<'static>) -> manifest::Manifest {
         ::toml_edit::de::from_str(sample.content()).unwrap()
     }
+
+    /// Compare with `manifest` for the cost of parsing, but not visiting, what isn't deserialized
+    #[divan::bench(args=[10, 100, 1000])]
+    fn ignored_metadata(bencher: divan::Bencher, count: usize) {
+        bencher
+            .with_inputs(|| toml_benchmarks::ignored_metadata(count))
+            .bench_refs(|content| {
+                ::toml_edit::de::from_str::<manifest::Manifest>(content).unwrap()
+            });
+    }
+
+    #[divan::bench(args=MANIFESTS)]
+    fn to_string(bencher: divan::Bencher, sample: &Data<'static>) {
+        bencher
+            .counter(divan::counter::BytesCount::of_str(sample.content()))
+            .with_inputs(|| {
+                sample
+                    .content()
+                    .parse::<::toml_edit::DocumentMut>()
+                    .unwrap()
+            })
+            .bench_refs(|doc| doc.to_string());
+    }
 }
 
 mod toml {
@@ -106,6 +141,14 @@ mod toml {
     fn manifest(sample: &Data<'static>) -> manifest::Manifest {
         ::toml::de::from_str(sample.content()).unwrap()
     }
+
+    /// Compare with `manifest` for the cost of parsing, but not visiting, what isn't deserialized
+    #[divan::bench(args=[10, 100, 1000])]
+    fn ignored_metadata(bencher: divan::Bencher, count: usize) {
+        bencher
+            .with_inputs(|| toml_benchmarks::ignored_metadata(count))
+            .bench_refs(|content| ::toml::de::from_str::<manifest::Manifest>(content).unwrap());
+    }
 }
 
 mod toml_v05 {
@@ -154,6 +197,9 @@ mod serde_json {
     }
 }
 
+#[global_allocator]
+static ALLOC: divan::AllocProfiler = divan::AllocProfiler::system();
+
 fn main() {
     divan::main();
 }
@@ -111,6 +111,15 @@ mod toml_edit {
             .input_counter(divan::counter::BytesCount::of_str)
             .bench_values(|sample| sample.parse::<toml_edit::DocumentMut>().unwrap());
     }
+
+    #[divan::bench(args = NUM_ENTRIES)]
+    fn to_string(bencher: divan::Bencher, num_entries: usize) {
+        let sample = gen(num_entries);
+        bencher
+            .counter(divan::counter::BytesCount::of_str(&sample))
+            .with_inputs(|| sample.parse::<toml_edit::DocumentMut>().unwrap())
+            .bench_refs(|doc| doc.to_string());
+    }
 }
 
 mod toml {
@@ -111,6 +111,17 @@ mod toml_edit {
             .input_counter(divan::counter::BytesCount::of_str)
             .bench_values(|sample| sample.parse::<toml_edit::DocumentMut>().unwrap());
     }
+
+    /// Formatting gives each value the same decor, see the allocations
+    #[divan::bench(args = NUM_ENTRIES)]
+    fn fmt(bencher: divan::Bencher, num_entries: usize) {
+        bencher
+            .with_inputs(|| (0..num_entries as i64).collect::<toml_edit::Array>())
+            .bench_values(|mut array| {
+                array.fmt();
+                array
+            });
+    }
 }
 
 mod toml {
@@ -148,6 +159,9 @@ fn gen(num_entries: usize) -> String {
     s
 }
 
+#[global_allocator]
+static ALLOC: divan::AllocProfiler = divan::AllocProfiler::system();
+
 fn main() {
     divan::main();
 }
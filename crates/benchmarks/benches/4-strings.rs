@@ -0,0 +1,98 @@
+#![allow(elided_lifetimes_in_paths)]
+
+const NUM_ENTRIES: &[usize] = &[10, 100];
+
+mod toml_parse {
+    use crate::NUM_ENTRIES;
+
+    #[divan::bench(args = NUM_ENTRIES)]
+    fn plain(bencher: divan::Bencher, num_entries: usize) {
+        bencher
+            .with_inputs(|| crate::gen("text", num_entries))
+            .input_counter(divan::counter::BytesCount::of_str)
+            .bench_values(|sample| {
+                let source = ::toml_parse::Source::new(&sample);
+                source.lex().last()
+            });
+    }
+
+    #[divan::bench(args = NUM_ENTRIES)]
+    fn escapes(bencher: divan::Bencher, num_entries: usize) {
+        bencher
+            .with_inputs(|| crate::gen(r#"\\\""#, num_entries))
+            .input_counter(divan::counter::BytesCount::of_str)
+            .bench_values(|sample| {
+                let source = ::toml_parse::Source::new(&sample);
+                source.lex().last()
+            });
+    }
+}
+
+mod toml_edit {
+    use crate::NUM_ENTRIES;
+
+    #[divan::bench(args = NUM_ENTRIES)]
+    fn escapes(bencher: divan::Bencher, num_entries: usize) {
+        bencher
+            .with_inputs(|| crate::gen(r#"\\\""#, num_entries))
+            .input_counter(divan::counter::BytesCount::of_str)
+            .bench_values(|sample| sample.parse::<toml_edit::DocumentMut>().unwrap());
+    }
+
+    #[divan::bench(args = NUM_ENTRIES)]
+    fn literal(bencher: divan::Bencher, num_entries: usize) {
+        bencher
+            .with_inputs(|| crate::gen_literal("text ünïcödé ", num_entries))
+            .input_counter(divan::counter::BytesCount::of_str)
+            .bench_values(|sample| sample.parse::<toml_edit::DocumentMut>().unwrap());
+    }
+
+    #[divan::bench(args = NUM_ENTRIES)]
+    fn comments(bencher: divan::Bencher, num_entries: usize) {
+        bencher
+            .with_inputs(|| crate::gen_comments("text ünïcödé ", num_entries))
+            .input_counter(divan::counter::BytesCount::of_str)
+            .bench_values(|sample| sample.parse::<toml_edit::DocumentMut>().unwrap());
+    }
+}
+
+/// Basic and multi-line basic strings of `unit` repeated, so backslash-heavy `unit`s stress
+/// escape handling
+fn gen(unit: &str, num_entries: usize) -> String {
+    let content = unit.repeat(1_000);
+    let mut s = String::new();
+    s.push_str("[strings]\n");
+    for i in 0..num_entries {
+        s.push_str(&format!("basic{i} = \"{content}\"\n"));
+        s.push_str(&format!("ml_basic{i} = \"\"\"\n{content}\"\"\"\n"));
+    }
+    s
+}
+
+/// Literal and multi-line literal strings of `unit` repeated
+fn gen_literal(unit: &str, num_entries: usize) -> String {
+    let content = unit.repeat(1_000);
+    let mut s = String::new();
+    s.push_str("[strings]\n");
+    for i in 0..num_entries {
+        s.push_str(&format!("literal{i} = '{content}'\n"));
+        s.push_str(&format!("ml_literal{i} = '''\n{content}'''\n"));
+    }
+    s
+}
+
+/// Comments of `unit` repeated, both on their own line and after a value
+fn gen_comments(unit: &str, num_entries: usize) -> String {
+    let content = unit.repeat(1_000);
+    let mut s = String::new();
+    s.push_str("[comments]\n");
+    for i in 0..num_entries {
+        s.push_str(&format!("# {content}\n"));
+        s.push_str(&format!("value{i} = {i} # {content}\n"));
+    }
+    s
+}
+
+fn main() {
+    divan::main();
+}
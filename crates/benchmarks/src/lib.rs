@@ -32,6 +32,25 @@ edition = "2018"
 [dependencies]
 "#;
 
+/// A new package with `count` tables of metadata, which [`manifest::Manifest`] ignores
+pub fn ignored_metadata(count: usize) -> String {
+    use std::fmt::Write as _;
+
+    let mut manifest = NEW.to_owned();
+    for i in 0..count {
+        let _ = write!(
+            manifest,
+            r#"
+[package.metadata.tool{i}]
+enabled = true
+targets = ["x86_64-unknown-linux-gnu", "wasm32-unknown-unknown"]
+settings = {{ level = {i}, name = "tool", flags = ["a", "b"] }}
+"#
+        );
+    }
+    manifest
+}
+
 const MEDIUM: &str = include_str!("Cargo.cargo.toml");
 const FEATURES: &str = include_str!("Cargo.web-sys.toml");
 
@@ -0,0 +1,45 @@
+//! Conversions from [`toml_parse::ast`]
+
+use toml_parse::ast;
+
+use crate::Table;
+use crate::Value;
+
+/// Drop the spans of an [`ast::Table`], keeping its keys and values
+///
+/// # Examples
+///
+/// ```
+/// let mut errors = Vec::new();
+/// let doc = toml_parse::ast::parse("[package]\nname = 'foo'\n", &mut errors);
+/// let table = toml::Table::from(doc);
+/// assert_eq!(table["package"]["name"].as_str(), Some("foo"));
+/// ```
+impl From<ast::Table> for Table {
+    fn from(table: ast::Table) -> Self {
+        table
+            .into_iter()
+            .map(|(key, node)| (key.into_string(), Value::from(node)))
+            .collect()
+    }
+}
+
+impl From<ast::Node> for Value {
+    fn from(node: ast::Node) -> Self {
+        Value::from(node.into_value())
+    }
+}
+
+impl From<ast::Value> for Value {
+    fn from(value: ast::Value) -> Self {
+        match value {
+            ast::Value::String(value) => Value::String(value),
+            ast::Value::Integer(value) => Value::Integer(value),
+            ast::Value::Float(value) => Value::Float(value),
+            ast::Value::Boolean(value) => Value::Boolean(value),
+            ast::Value::Datetime(value) => Value::Datetime(value),
+            ast::Value::Array(array) => Value::Array(array.into_iter().map(Value::from).collect()),
+            ast::Value::Table(table) => Value::Table(Table::from(table)),
+        }
+    }
+}
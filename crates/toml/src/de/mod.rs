@@ -4,6 +4,14 @@
 //! into Rust structures. Note that some top-level functions here are also
 //! provided at the top of the crate.
 
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+mod reader;
+
+#[cfg(feature = "futures-io")]
+pub use reader::from_async_reader;
+#[cfg(feature = "tokio")]
+pub use reader::from_tokio_reader;
+
 /// Errors that can occur when deserializing a type.
 #[derive(Clone, PartialEq, Eq)]
 pub struct Error {
@@ -19,15 +27,62 @@ impl Error {
         self.inner.add_key(key);
     }
 
+    pub(crate) fn add_index(&mut self, index: usize) {
+        self.inner.add_index(index);
+    }
+
+    pub(crate) fn add_found(&mut self, found: &'static str) {
+        self.inner.add_found(found);
+    }
+
     /// What went wrong
     pub fn message(&self) -> &str {
         self.inner.message()
     }
 
+    /// What the value was being deserialized into, for a type or value mismatch
+    ///
+    /// This is serde's description of the Rust type, like `u16` or `a string`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Server {
+    ///     #[allow(dead_code)]
+    ///     port: u16,
+    /// }
+    ///
+    /// let err = toml::from_str::<Server>("port = '8080'").unwrap_err();
+    /// assert_eq!(err.expected(), Some("u16"));
+    /// assert_eq!(err.found(), Some("string"));
+    /// ```
+    pub fn expected(&self) -> Option<&str> {
+        self.inner.expected()
+    }
+
+    /// The TOML type of the value, for a type or value mismatch
+    pub fn found(&self) -> Option<&str> {
+        self.inner.found()
+    }
+
     /// The start/end index into the original document where the error occurred
     pub fn span(&self) -> Option<std::ops::Range<usize>> {
         self.inner.span()
     }
+
+    /// The keys and array indices leading to the value where the error occurred
+    #[cfg(feature = "parse")]
+    pub fn path(&self) -> &[PathSegment] {
+        self.inner.path()
+    }
+
+    /// Each table, array, and field the deserializer was within when the error occurred,
+    /// outermost first
+    #[cfg(feature = "parse")]
+    pub fn frames(&self) -> &[Frame] {
+        self.inner.frames()
+    }
 }
 
 impl serde::de::Error for Error {
@@ -37,6 +92,14 @@ impl serde::de::Error for Error {
     {
         Error::new(crate::edit::de::Error::custom(msg))
     }
+
+    fn invalid_type(unexp: serde::de::Unexpected<'_>, exp: &dyn serde::de::Expected) -> Self {
+        Error::new(crate::edit::de::Error::invalid_type(unexp, exp))
+    }
+
+    fn invalid_value(unexp: serde::de::Unexpected<'_>, exp: &dyn serde::de::Expected) -> Self {
+        Error::new(crate::edit::de::Error::invalid_value(unexp, exp))
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -53,6 +116,11 @@ impl std::fmt::Debug for Error {
 
 impl std::error::Error for Error {}
 
+#[cfg(feature = "parse")]
+pub use toml_edit::de::Frame;
+#[cfg(feature = "parse")]
+pub use toml_edit::de::PathSegment;
+
 /// Deserializes a string into a type.
 ///
 /// This function will attempt to interpret `s` as a TOML document and
@@ -193,10 +261,19 @@ impl<'de> serde::Deserializer<'de> for Deserializer<'_> {
             .map_err(Error::new)
     }
 
+    // Still parse the whole document, to report syntax errors, with only visiting it skipped
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let inner = toml_edit::de::Deserializer::parse(self.input).map_err(Error::new)?;
+        inner.deserialize_ignored_any(visitor).map_err(Error::new)
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
         bytes byte_buf map unit
-        ignored_any unit_struct tuple_struct tuple identifier
+        unit_struct tuple_struct tuple identifier
     }
 }
 
@@ -320,9 +397,20 @@ impl<'de> serde::Deserializer<'de> for ValueDeserializer<'_> {
             .map_err(Error::new)
     }
 
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let inner = self
+            .input
+            .parse::<toml_edit::de::ValueDeserializer>()
+            .map_err(Error::new)?;
+        inner.deserialize_ignored_any(visitor).map_err(Error::new)
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
         bytes byte_buf map unit
-        ignored_any unit_struct tuple_struct tuple identifier
+        unit_struct tuple_struct tuple identifier
     }
 }
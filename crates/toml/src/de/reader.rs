@@ -0,0 +1,64 @@
+use std::future::poll_fn;
+use std::pin::Pin;
+
+use super::{from_slice, Error};
+
+/// How much to read at a time
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Deserializes a [`futures_io::AsyncRead`] into a type.
+///
+/// The input is read in chunks without blocking the executor and then deserialized like
+/// [`from_slice`], reporting the same errors.
+/// Failures to read are reported as an [`Error`] without a span.
+#[cfg(feature = "futures-io")]
+pub async fn from_async_reader<T, R>(mut reader: R) -> Result<T, Error>
+where
+    T: serde::de::DeserializeOwned,
+    R: futures_io::AsyncRead + Unpin,
+{
+    let mut input = Vec::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        let read = poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut chunk))
+            .await
+            .map_err(io_error)?;
+        if read == 0 {
+            break;
+        }
+        input.extend_from_slice(&chunk[..read]);
+    }
+    from_slice(&input)
+}
+
+/// Deserializes a [`tokio::io::AsyncRead`] into a type.
+///
+/// The input is read in chunks without blocking the runtime and then deserialized like
+/// [`from_slice`], reporting the same errors.
+/// Failures to read are reported as an [`Error`] without a span.
+#[cfg(feature = "tokio")]
+pub async fn from_tokio_reader<T, R>(mut reader: R) -> Result<T, Error>
+where
+    T: serde::de::DeserializeOwned,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let mut input = Vec::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        let mut buf = tokio::io::ReadBuf::new(&mut chunk);
+        poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf))
+            .await
+            .map_err(io_error)?;
+        let read = buf.filled();
+        if read.is_empty() {
+            break;
+        }
+        input.extend_from_slice(read);
+    }
+    from_slice(&input)
+}
+
+fn io_error(e: std::io::Error) -> Error {
+    use serde::de::Error as _;
+    Error::custom(e)
+}
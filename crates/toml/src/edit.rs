@@ -15,11 +15,25 @@ pub(crate) mod de {
         /// Add key while unwinding
         pub(crate) fn add_key(&mut self, _key: String) {}
 
+        /// Add array index while unwinding
+        pub(crate) fn add_index(&mut self, _index: usize) {}
+
+        /// Add the TOML type of the value while unwinding
+        pub(crate) fn add_found(&mut self, _found: &'static str) {}
+
         /// What went wrong
         pub(crate) fn message(&self) -> &str {
             self.inner.as_str()
         }
 
+        pub(crate) fn expected(&self) -> Option<&str> {
+            None
+        }
+
+        pub(crate) fn found(&self) -> Option<&str> {
+            None
+        }
+
         pub(crate) fn span(&self) -> Option<std::ops::Range<usize>> {
             None
         }
@@ -53,14 +53,10 @@ impl toml_edit::visit_mut::VisitMut for DocumentFormatter {
         toml_edit::visit_mut::visit_array_mut(self, node);
 
         if !self.multiline_array || (0..=1).contains(&node.len()) {
-            node.set_trailing("");
+            node.set_trailing("").expect("empty trailing is valid");
             node.set_trailing_comma(false);
         } else {
-            for item in node.iter_mut() {
-                item.decor_mut().set_prefix("\n    ");
-            }
-            node.set_trailing("\n");
-            node.set_trailing_comma(true);
+            node.fmt_multiline();
         }
     }
 }
@@ -47,6 +47,10 @@
 //! traits; the example above uses [`FromStr`] to parse a [`str`] into a
 //! [`Table`].
 //!
+//! By default, [`Table`] iterates over keys in sorted order.  Enable the
+//! `preserve_order` feature to keep keys in the order they appear in the document,
+//! including when serializing a [`Table`] back to TOML.
+//!
 //! ## Deserialization and Serialization
 //!
 //! This crate supports [`serde`] 1.0 with a number of
@@ -153,10 +157,14 @@ pub mod value;
 
 pub mod de;
 pub mod ser;
+#[cfg(feature = "yaml")]
+pub mod yaml;
 
 #[doc(hidden)]
 pub mod macros;
 
+#[cfg(feature = "ast")]
+mod ast;
 mod edit;
 #[cfg(feature = "display")]
 mod fmt;
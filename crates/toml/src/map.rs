@@ -11,6 +11,19 @@
 //! By default the map is backed by a [`BTreeMap`]. Enable the `preserve_order`
 //! feature of toml-rs to use [`IndexMap`] instead.
 //!
+//! # Iteration order
+//!
+//! Without `preserve_order`, entries are always iterated in sorted key order, regardless of how
+//! they were inserted.
+//!
+//! With `preserve_order`, entries are iterated in insertion order.  Deserializing a [`Map`]
+//! inserts entries in the order they appear in the source, so a document round-tripped through
+//! [`Table`][crate::Table] or [`Value`] keeps its key order.  Removing an entry shifts later
+//! entries down rather than reordering them.
+//!
+//! In either case, serializing a map writes tables and arrays of tables after all other values,
+//! as TOML requires.
+//!
 //! [`BTreeMap`]: https://doc.rust-lang.org/std/collections/struct.BTreeMap.html
 //! [`IndexMap`]: https://docs.rs/indexmap
 
@@ -29,6 +42,8 @@ use std::collections::{btree_map, BTreeMap};
 use indexmap::{self, IndexMap};
 
 /// Represents a TOML key/value type.
+///
+/// See the [module docs][self] for iteration order.
 pub struct Map<K, V> {
     map: MapImpl<K, V>,
 }
@@ -140,6 +155,9 @@ impl Map<String, Value> {
     /// Removes a key from the map, returning the value at the key if the key
     /// was previously in the map.
     ///
+    /// With the `preserve_order` feature, the order of the remaining entries is
+    /// kept.
+    ///
     /// The key may be any borrowed form of the map's key type, but the ordering
     /// on the borrowed form *must* match the ordering on the key type.
     #[inline]
@@ -158,6 +176,55 @@ impl Map<String, Value> {
         }
     }
 
+    /// Removes a key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    ///
+    /// With the `preserve_order` feature, the order of the remaining entries is
+    /// kept.
+    ///
+    /// The key may be any borrowed form of the map's key type, but the ordering
+    /// on the borrowed form *must* match the ordering on the key type.
+    #[inline]
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(String, Value)>
+    where
+        String: Borrow<Q>,
+        Q: Ord + Eq + Hash + ?Sized,
+    {
+        #[cfg(not(feature = "preserve_order"))]
+        {
+            self.map.remove_entry(key)
+        }
+        #[cfg(feature = "preserve_order")]
+        {
+            self.map.shift_remove_entry(key)
+        }
+    }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        #[cfg(not(feature = "preserve_order"))]
+        {
+            self.map.append(&mut other.map);
+        }
+        #[cfg(feature = "preserve_order")]
+        {
+            self.map.extend(other.map.drain(..));
+        }
+    }
+
+    /// Sorts the entries of the map by key.
+    ///
+    /// Without the `preserve_order` feature, the map is always sorted and this
+    /// does nothing.
+    #[inline]
+    pub fn sort_keys(&mut self) {
+        #[cfg(feature = "preserve_order")]
+        {
+            self.map.sort_keys();
+        }
+    }
+
     /// Retains only the elements specified by the `keep` predicate.
     ///
     /// In other words, remove all pairs `(k, v)` for which `keep(&k, &mut v)`
@@ -217,6 +284,83 @@ impl Map<String, Value> {
         }
     }
 
+    /// Gets an iterator over the entries whose keys are within `range`, in iteration order.
+    ///
+    /// Without the `preserve_order` feature, the map is sorted by key and only the entries in
+    /// `range` are visited.  With it, every entry is checked, in insertion order.
+    ///
+    /// # Panics
+    ///
+    /// If the start of `range` is after its end, or both are the same and excluded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut map = toml::Table::new();
+    /// map.insert("b".to_owned(), 2.into());
+    /// map.insert("a".to_owned(), 1.into());
+    /// map.insert("c".to_owned(), 3.into());
+    /// let range = "a".to_owned().."c".to_owned();
+    /// let keys = map.range(range).map(|(k, _)| k.as_str()).collect::<Vec<_>>();
+    /// # #[cfg(not(feature = "preserve_order"))]
+    /// assert_eq!(keys, ["a", "b"]);
+    /// # #[cfg(feature = "preserve_order")]
+    /// assert_eq!(keys, ["b", "a"]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Range<'_>
+    where
+        String: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: ops::RangeBounds<Q>,
+    {
+        use ops::Bound::{Excluded, Included};
+        match (range.start_bound(), range.end_bound()) {
+            (Excluded(start), Excluded(end)) if start == end => {
+                panic!("range start and end are equal and excluded in Map")
+            }
+            (Included(start) | Excluded(start), Included(end) | Excluded(end)) if start > end => {
+                panic!("range start is greater than range end in Map")
+            }
+            _ => {}
+        }
+
+        #[cfg(not(feature = "preserve_order"))]
+        let iter = self.map.range(range);
+        #[cfg(feature = "preserve_order")]
+        let iter = self
+            .map
+            .iter()
+            .filter(|(key, _)| range.contains((*key).borrow()))
+            .collect::<Vec<_>>()
+            .into_iter();
+        Range { iter }
+    }
+
+    /// Gets an iterator over the entries in the `range` of positions in the map, if it is within
+    /// the map.
+    ///
+    /// This requires the `preserve_order` feature, for entries to have positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut map = toml::Table::new();
+    /// map.insert("b".to_owned(), 2.into());
+    /// map.insert("a".to_owned(), 1.into());
+    /// map.insert("c".to_owned(), 3.into());
+    /// let keys = map.get_range(1..).unwrap().map(|(k, _)| k.as_str()).collect::<Vec<_>>();
+    /// assert_eq!(keys, ["a", "c"]);
+    /// assert!(map.get_range(2..4).is_none());
+    /// ```
+    #[cfg(feature = "preserve_order")]
+    pub fn get_range<R>(&self, range: R) -> Option<Iter<'_>>
+    where
+        R: ops::RangeBounds<usize>,
+    {
+        let slice = self.map.get_range(range)?;
+        Some(Iter { iter: slice.iter() })
+    }
+
     /// Gets an iterator over the keys of the map.
     #[inline]
     pub fn keys(&self) -> Keys<'_> {
@@ -232,6 +376,30 @@ impl Map<String, Value> {
             iter: self.map.values(),
         }
     }
+
+    /// Gets a mutable iterator over the values of the map.
+    #[inline]
+    pub fn values_mut(&mut self) -> ValuesMut<'_> {
+        ValuesMut {
+            iter: self.map.values_mut(),
+        }
+    }
+
+    /// Gets an owning iterator over the keys of the map.
+    #[inline]
+    pub fn into_keys(self) -> IntoKeys {
+        IntoKeys {
+            iter: self.map.into_keys(),
+        }
+    }
+
+    /// Gets an owning iterator over the values of the map.
+    #[inline]
+    pub fn into_values(self) -> IntoValues {
+        IntoValues {
+            iter: self.map.into_values(),
+        }
+    }
 }
 
 impl Default for Map<String, Value> {
@@ -468,6 +636,21 @@ impl<'a> Entry<'a> {
             Entry::Occupied(entry) => entry.into_mut(),
         }
     }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut Value),
+    {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
 }
 
 impl<'a> VacantEntry<'a> {
@@ -530,6 +713,19 @@ impl<'a> OccupiedEntry<'a> {
             self.occupied.shift_remove()
         }
     }
+
+    /// Takes the key and value of the entry out of the map, and returns them.
+    #[inline]
+    pub fn remove_entry(self) -> (String, Value) {
+        #[cfg(not(feature = "preserve_order"))]
+        {
+            self.occupied.remove_entry()
+        }
+        #[cfg(feature = "preserve_order")]
+        {
+            self.occupied.shift_remove_entry()
+        }
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////////
@@ -609,6 +805,37 @@ delegate_iterator!((IntoIter) => (String, Value));
 
 //////////////////////////////////////////////////////////////////////////////
 
+/// An iterator over a range of a `toml::Map`'s entries, see [`Map::range`].
+pub struct Range<'a> {
+    iter: RangeImpl<'a>,
+}
+
+#[cfg(not(feature = "preserve_order"))]
+type RangeImpl<'a> = btree_map::Range<'a, String, Value>;
+#[cfg(feature = "preserve_order")]
+type RangeImpl<'a> = std::vec::IntoIter<(&'a String, &'a Value)>;
+
+impl<'a> Iterator for Range<'a> {
+    type Item = (&'a String, &'a Value);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Range<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
 /// An iterator over a `toml::Map`'s keys.
 pub struct Keys<'a> {
     iter: KeysImpl<'a>,
@@ -634,3 +861,45 @@ type ValuesImpl<'a> = btree_map::Values<'a, String, Value>;
 type ValuesImpl<'a> = indexmap::map::Values<'a, String, Value>;
 
 delegate_iterator!((Values<'a>) => &'a Value);
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// A mutable iterator over a `toml::Map`'s values.
+pub struct ValuesMut<'a> {
+    iter: ValuesMutImpl<'a>,
+}
+
+#[cfg(not(feature = "preserve_order"))]
+type ValuesMutImpl<'a> = btree_map::ValuesMut<'a, String, Value>;
+#[cfg(feature = "preserve_order")]
+type ValuesMutImpl<'a> = indexmap::map::ValuesMut<'a, String, Value>;
+
+delegate_iterator!((ValuesMut<'a>) => &'a mut Value);
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// An owning iterator over a `toml::Map`'s keys.
+pub struct IntoKeys {
+    iter: IntoKeysImpl,
+}
+
+#[cfg(not(feature = "preserve_order"))]
+type IntoKeysImpl = btree_map::IntoKeys<String, Value>;
+#[cfg(feature = "preserve_order")]
+type IntoKeysImpl = indexmap::map::IntoKeys<String, Value>;
+
+delegate_iterator!((IntoKeys) => String);
+
+//////////////////////////////////////////////////////////////////////////////
+
+/// An owning iterator over a `toml::Map`'s values.
+pub struct IntoValues {
+    iter: IntoValuesImpl,
+}
+
+#[cfg(not(feature = "preserve_order"))]
+type IntoValuesImpl = btree_map::IntoValues<String, Value>;
+#[cfg(feature = "preserve_order")]
+type IntoValuesImpl = indexmap::map::IntoValues<String, Value>;
+
+delegate_iterator!((IntoValues) => Value);
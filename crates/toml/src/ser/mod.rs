@@ -13,11 +13,13 @@ mod ser_value;
 
 #[cfg(feature = "display")]
 pub use ser_value::ValueSerializer;
+#[cfg(feature = "display")]
+pub use toml_edit::ser::KeyQuoting;
 
 /// Serialize the given data structure as a String of TOML.
 ///
 /// Serialization can fail if `T`'s implementation of `Serialize` decides to
-/// fail, if `T` contains a map with non-string keys, or if `T` attempts to
+/// fail, if `T` contains a map with keys that can't be strings, or if `T` attempts to
 /// serialize an unsupported datatype such as an enum, tuple, or tuple struct.
 ///
 /// To serialize TOML values, instead of documents, see [`ValueSerializer`].
@@ -158,6 +160,7 @@ impl std::error::Error for Error {}
 pub struct Serializer<'d> {
     dst: &'d mut String,
     settings: crate::fmt::DocumentFormatter,
+    key_quoting: KeyQuoting,
 }
 
 #[cfg(feature = "display")]
@@ -170,6 +173,7 @@ impl<'d> Serializer<'d> {
         Self {
             dst,
             settings: Default::default(),
+            key_quoting: Default::default(),
         }
     }
 
@@ -182,6 +186,32 @@ impl<'d> Serializer<'d> {
         ser.settings.multiline_array = true;
         ser
     }
+
+    /// Choose how map keys that aren't strings, like integers, are written
+    ///
+    /// TOML only has string keys, so integers, bools, chars, and datetimes are converted to
+    /// strings.  By default, they are written bare when TOML allows it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use serde::Serialize as _;
+    /// use toml::ser::{KeyQuoting, Serializer};
+    ///
+    /// let ports = BTreeMap::from([(80, "http"), (443, "https")]);
+    ///
+    /// let mut toml = String::new();
+    /// ports
+    ///     .serialize(Serializer::new(&mut toml).with_key_quoting(KeyQuoting::Always))
+    ///     .unwrap();
+    /// assert_eq!(toml, "\"80\" = \"http\"\n\"443\" = \"https\"\n");
+    /// ```
+    pub fn with_key_quoting(mut self, quoting: KeyQuoting) -> Self {
+        self.key_quoting = quoting;
+        self
+    }
 }
 
 #[cfg(feature = "display")]
@@ -200,7 +230,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_bool(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_bool(v),
         )
     }
 
@@ -208,7 +240,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_i8(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_i8(v),
         )
     }
 
@@ -216,7 +250,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_i16(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_i16(v),
         )
     }
 
@@ -224,7 +260,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_i32(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_i32(v),
         )
     }
 
@@ -232,7 +270,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_i64(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_i64(v),
         )
     }
 
@@ -240,7 +280,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_u8(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_u8(v),
         )
     }
 
@@ -248,7 +290,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_u16(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_u16(v),
         )
     }
 
@@ -256,7 +300,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_u32(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_u32(v),
         )
     }
 
@@ -264,7 +310,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_u64(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_u64(v),
         )
     }
 
@@ -272,7 +320,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_f32(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_f32(v),
         )
     }
 
@@ -280,7 +330,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_f64(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_f64(v),
         )
     }
 
@@ -288,7 +340,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_char(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_char(v),
         )
     }
 
@@ -296,7 +350,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_str(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_str(v),
         )
     }
 
@@ -304,7 +360,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_bytes(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_bytes(v),
         )
     }
 
@@ -312,7 +370,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_none(),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_none(),
         )
     }
 
@@ -323,7 +383,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_some(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_some(v),
         )
     }
 
@@ -331,7 +393,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_unit(),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_unit(),
         )
     }
 
@@ -339,7 +403,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_unit_struct(name),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_unit_struct(name),
         )
     }
 
@@ -352,11 +418,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_unit_variant(
-                name,
-                variant_index,
-                variant,
-            ),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_unit_variant(name, variant_index, variant),
         )
     }
 
@@ -367,7 +431,9 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_newtype_struct(name, v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_newtype_struct(name, v),
         )
     }
 
@@ -384,17 +450,15 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         write_document(
             self.dst,
             self.settings,
-            toml_edit::ser::ValueSerializer::new().serialize_newtype_variant(
-                name,
-                variant_index,
-                variant,
-                value,
-            ),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_newtype_variant(name, variant_index, variant, value),
         )
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         let ser = toml_edit::ser::ValueSerializer::new()
+            .with_key_quoting(self.key_quoting)
             .serialize_seq(len)
             .map_err(Error::wrap)?;
         let ser = array::SerializeDocumentArray::new(self, ser);
@@ -421,6 +485,7 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
         let ser = toml_edit::ser::ValueSerializer::new()
+            .with_key_quoting(self.key_quoting)
             .serialize_tuple_variant(name, variant_index, variant, len)
             .map_err(Error::wrap)?;
         let ser = array::SerializeDocumentTupleVariant::new(self, ser);
@@ -429,6 +494,7 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         let ser = toml_edit::ser::ValueSerializer::new()
+            .with_key_quoting(self.key_quoting)
             .serialize_map(len)
             .map_err(Error::wrap)?;
         let ser = map::SerializeDocumentTable::new(self, ser);
@@ -451,6 +517,7 @@ impl<'d> serde::ser::Serializer for Serializer<'d> {
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
         let ser = toml_edit::ser::ValueSerializer::new()
+            .with_key_quoting(self.key_quoting)
             .serialize_struct_variant(name, variant_index, variant, len)
             .map_err(Error::wrap)?;
         let ser = map::SerializeDocumentStructVariant::new(self, ser);
@@ -50,6 +50,7 @@ use super::Error;
 #[cfg(feature = "display")]
 pub struct ValueSerializer<'d> {
     dst: &'d mut String,
+    key_quoting: super::KeyQuoting,
 }
 
 impl<'d> ValueSerializer<'d> {
@@ -58,7 +59,17 @@ impl<'d> ValueSerializer<'d> {
     /// The serializer can then be used to serialize a type after which the data
     /// will be present in `dst`.
     pub fn new(dst: &'d mut String) -> Self {
-        Self { dst }
+        Self {
+            dst,
+            key_quoting: Default::default(),
+        }
+    }
+
+    /// Choose how map keys that aren't strings, like integers, are written, see
+    /// [`Serializer::with_key_quoting`][super::Serializer::with_key_quoting]
+    pub fn with_key_quoting(mut self, quoting: super::KeyQuoting) -> Self {
+        self.key_quoting = quoting;
+        self
     }
 }
 
@@ -76,105 +87,135 @@ impl<'d> serde::ser::Serializer for ValueSerializer<'d> {
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_bool(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_bool(v),
         )
     }
 
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_i8(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_i8(v),
         )
     }
 
     fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_i16(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_i16(v),
         )
     }
 
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_i32(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_i32(v),
         )
     }
 
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_i64(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_i64(v),
         )
     }
 
     fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_u8(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_u8(v),
         )
     }
 
     fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_u16(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_u16(v),
         )
     }
 
     fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_u32(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_u32(v),
         )
     }
 
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_u64(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_u64(v),
         )
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_f32(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_f32(v),
         )
     }
 
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_f64(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_f64(v),
         )
     }
 
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_char(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_char(v),
         )
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_str(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_str(v),
         )
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_bytes(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_bytes(v),
         )
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_none(),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_none(),
         )
     }
 
@@ -184,21 +225,27 @@ impl<'d> serde::ser::Serializer for ValueSerializer<'d> {
     {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_some(v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_some(v),
         )
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_unit(),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_unit(),
         )
     }
 
     fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_unit_struct(name),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_unit_struct(name),
         )
     }
 
@@ -210,11 +257,9 @@ impl<'d> serde::ser::Serializer for ValueSerializer<'d> {
     ) -> Result<Self::Ok, Self::Error> {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_unit_variant(
-                name,
-                variant_index,
-                variant,
-            ),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_unit_variant(name, variant_index, variant),
         )
     }
 
@@ -224,7 +269,9 @@ impl<'d> serde::ser::Serializer for ValueSerializer<'d> {
     {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_newtype_struct(name, v),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_newtype_struct(name, v),
         )
     }
 
@@ -240,17 +287,15 @@ impl<'d> serde::ser::Serializer for ValueSerializer<'d> {
     {
         write_value(
             self.dst,
-            toml_edit::ser::ValueSerializer::new().serialize_newtype_variant(
-                name,
-                variant_index,
-                variant,
-                value,
-            ),
+            toml_edit::ser::ValueSerializer::new()
+                .with_key_quoting(self.key_quoting)
+                .serialize_newtype_variant(name, variant_index, variant, value),
         )
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         let ser = toml_edit::ser::ValueSerializer::new()
+            .with_key_quoting(self.key_quoting)
             .serialize_seq(len)
             .map_err(Error::wrap)?;
         let ser = array::SerializeValueArray::new(self, ser);
@@ -277,6 +322,7 @@ impl<'d> serde::ser::Serializer for ValueSerializer<'d> {
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
         let ser = toml_edit::ser::ValueSerializer::new()
+            .with_key_quoting(self.key_quoting)
             .serialize_tuple_variant(name, variant_index, variant, len)
             .map_err(Error::wrap)?;
         let ser = array::SerializeValueTupleVariant::new(self, ser);
@@ -285,6 +331,7 @@ impl<'d> serde::ser::Serializer for ValueSerializer<'d> {
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         let ser = toml_edit::ser::ValueSerializer::new()
+            .with_key_quoting(self.key_quoting)
             .serialize_map(len)
             .map_err(Error::wrap)?;
         let ser = map::SerializeValueTable::new(self, ser);
@@ -307,6 +354,7 @@ impl<'d> serde::ser::Serializer for ValueSerializer<'d> {
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
         let ser = toml_edit::ser::ValueSerializer::new()
+            .with_key_quoting(self.key_quoting)
             .serialize_struct_variant(name, variant_index, variant, len)
             .map_err(Error::wrap)?;
         let ser = map::SerializeValueStructVariant::new(self, ser);
@@ -6,17 +6,18 @@ use crate::Value;
 
 /// Type representing a TOML table, payload of the `Value::Table` variant.
 ///
-/// By default it entries are stored in
+/// By default its entries are stored in
 /// [lexicographic order](https://doc.rust-lang.org/std/primitive.str.html#impl-Ord-for-str)
 /// of the keys. Enable the `preserve_order` feature to store entries in the order they appear in
-/// the source file.
+/// the source file, keeping that order when serializing back to TOML.
+/// See [`map`][crate::map#iteration-order] for details.
 pub type Table = Map<String, Value>;
 
 impl Table {
     /// Convert a `T` into `toml::Table`.
     ///
     /// This conversion can fail if `T`'s implementation of `Serialize` decides to
-    /// fail, or if `T` contains a map with non-string keys.
+    /// fail, or if `T` contains a map with keys that can't be strings.
     pub fn try_from<T>(value: T) -> Result<Self, crate::ser::Error>
     where
         T: ser::Serialize,
@@ -99,10 +100,17 @@ impl<'de> de::Deserializer<'de> for Table {
         Value::Table(self).deserialize_newtype_struct(name, visitor)
     }
 
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, crate::de::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit seq
         bytes byte_buf map unit_struct tuple_struct struct
-        tuple ignored_any identifier
+        tuple identifier
     }
 }
 
@@ -44,7 +44,7 @@ impl Value {
     /// any valid TOML data.
     ///
     /// This conversion can fail if `T`'s implementation of `Serialize` decides to
-    /// fail, or if `T` contains a map with non-string keys.
+    /// fail, or if `T` contains a map with keys that can't be strings.
     pub fn try_from<T>(value: T) -> Result<Value, crate::ser::Error>
     where
         T: ser::Serialize,
@@ -297,6 +297,128 @@ impl_into_value!(Boolean: bool);
 impl_into_value!(Datetime: Datetime);
 impl_into_value!(Table: Table);
 
+/// One of two types, for arrays that mix them
+///
+/// Deserializing tries `A` and then `B`, like a `#[serde(untagged)]` enum, so put the more
+/// specific type first.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "parse")] {
+/// use std::collections::BTreeMap;
+/// use toml::value::Mixed;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Manifest {
+///     features: Vec<Mixed<String, BTreeMap<String, String>>>,
+/// }
+///
+/// let manifest: Manifest = toml::from_str(r#"
+/// features = ["std", { name = "serde", version = "1" }]
+/// "#).unwrap();
+/// assert_eq!(manifest.features[0].first().map(String::as_str), Some("std"));
+/// assert_eq!(manifest.features[1].second().unwrap()["version"], "1");
+/// # }
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum Mixed<A, B> {
+    /// The value is an `A`
+    First(A),
+    /// The value is a `B` and not an `A`
+    Second(B),
+}
+
+impl<A, B> Mixed<A, B> {
+    /// Tests whether this is an `A`
+    pub fn is_first(&self) -> bool {
+        matches!(self, Mixed::First(_))
+    }
+
+    /// Tests whether this is a `B`
+    pub fn is_second(&self) -> bool {
+        matches!(self, Mixed::Second(_))
+    }
+
+    /// The `A`, if this is one
+    pub fn first(&self) -> Option<&A> {
+        match self {
+            Mixed::First(first) => Some(first),
+            Mixed::Second(_) => None,
+        }
+    }
+
+    /// The `B`, if this is one
+    pub fn second(&self) -> Option<&B> {
+        match self {
+            Mixed::First(_) => None,
+            Mixed::Second(second) => Some(second),
+        }
+    }
+
+    /// Take the `A`, if this is one
+    pub fn into_first(self) -> Option<A> {
+        match self {
+            Mixed::First(first) => Some(first),
+            Mixed::Second(_) => None,
+        }
+    }
+
+    /// Take the `B`, if this is one
+    pub fn into_second(self) -> Option<B> {
+        match self {
+            Mixed::First(_) => None,
+            Mixed::Second(second) => Some(second),
+        }
+    }
+
+    /// Convert either type into a `T`, like a [`String`] from a name or a table with a name
+    pub fn unify<T>(self, first: impl FnOnce(A) -> T, second: impl FnOnce(B) -> T) -> T {
+        match self {
+            Mixed::First(value) => first(value),
+            Mixed::Second(value) => second(value),
+        }
+    }
+}
+
+impl<A: ser::Serialize, B: ser::Serialize> ser::Serialize for Mixed<A, B> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match self {
+            Mixed::First(value) => value.serialize(serializer),
+            Mixed::Second(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de, A, B> de::Deserialize<'de> for Mixed<A, B>
+where
+    A: de::DeserializeOwned,
+    B: de::DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let first = match A::deserialize(value.clone()) {
+            Ok(first) => return Ok(Mixed::First(first)),
+            Err(err) => err,
+        };
+        let second = match B::deserialize(value) {
+            Ok(second) => return Ok(Mixed::Second(second)),
+            Err(err) => err,
+        };
+        Err(de::Error::custom(format_args!(
+            "did not match either type: {}; {}",
+            first.message(),
+            second.message()
+        )))
+    }
+}
+
 /// Types that can be used to index a `toml::Value`
 ///
 /// Currently this is implemented for `usize` to index arrays and `str` to index
@@ -521,6 +643,7 @@ impl<'de> de::Deserializer<'de> for Value {
     where
         V: de::Visitor<'de>,
     {
+        let found = self.type_str();
         match self {
             Value::Boolean(v) => visitor.visit_bool(v),
             Value::Integer(n) => visitor.visit_i64(n),
@@ -530,26 +653,32 @@ impl<'de> de::Deserializer<'de> for Value {
             Value::Array(v) => {
                 let len = v.len();
                 let mut deserializer = SeqDeserializer::new(v);
-                let seq = visitor.visit_seq(&mut deserializer)?;
-                let remaining = deserializer.iter.len();
-                if remaining == 0 {
-                    Ok(seq)
-                } else {
-                    Err(de::Error::invalid_length(len, &"fewer elements in array"))
-                }
+                visitor.visit_seq(&mut deserializer).and_then(|seq| {
+                    let remaining = deserializer.iter.len();
+                    if remaining == 0 {
+                        Ok(seq)
+                    } else {
+                        Err(de::Error::invalid_length(len, &"fewer elements in array"))
+                    }
+                })
             }
             Value::Table(v) => {
                 let len = v.len();
                 let mut deserializer = MapDeserializer::new(v);
-                let map = visitor.visit_map(&mut deserializer)?;
-                let remaining = deserializer.iter.len();
-                if remaining == 0 {
-                    Ok(map)
-                } else {
-                    Err(de::Error::invalid_length(len, &"fewer elements in map"))
-                }
+                visitor.visit_map(&mut deserializer).and_then(|map| {
+                    let remaining = deserializer.iter.len();
+                    if remaining == 0 {
+                        Ok(map)
+                    } else {
+                        Err(de::Error::invalid_length(len, &"fewer elements in map"))
+                    }
+                })
             }
         }
+        .map_err(|mut e: crate::de::Error| {
+            e.add_found(found);
+            e
+        })
     }
 
     #[inline]
@@ -606,21 +735,28 @@ impl<'de> de::Deserializer<'de> for Value {
         visitor.visit_newtype_struct(self)
     }
 
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, crate::de::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit seq
         bytes byte_buf map unit_struct tuple_struct struct
-        tuple ignored_any identifier
+        tuple identifier
     }
 }
 
 struct SeqDeserializer {
-    iter: vec::IntoIter<Value>,
+    iter: std::iter::Enumerate<vec::IntoIter<Value>>,
 }
 
 impl SeqDeserializer {
     fn new(vec: Vec<Value>) -> Self {
         SeqDeserializer {
-            iter: vec.into_iter(),
+            iter: vec.into_iter().enumerate(),
         }
     }
 }
@@ -633,7 +769,10 @@ impl<'de> de::SeqAccess<'de> for SeqDeserializer {
         T: de::DeserializeSeed<'de>,
     {
         match self.iter.next() {
-            Some(value) => seed.deserialize(value).map(Some),
+            Some((index, value)) => seed.deserialize(value).map(Some).map_err(|mut error| {
+                error.add_index(index);
+                error
+            }),
             None => Ok(None),
         }
     }
@@ -646,6 +785,87 @@ impl<'de> de::SeqAccess<'de> for SeqDeserializer {
     }
 }
 
+/// Parse keys for map key types that aren't strings, like the integers of `HashMap<u32, T>`
+///
+/// A key that doesn't parse is reported like any other mismatched type.
+struct MapKeyDeserializer {
+    key: String,
+}
+
+macro_rules! parse_key {
+    ($($deserialize:ident => $visit:ident: $ty:ty,)*) => {$(
+        fn $deserialize<V>(self, visitor: V) -> Result<V::Value, crate::de::Error>
+        where
+            V: de::Visitor<'de>,
+        {
+            match self.key.parse::<$ty>() {
+                Ok(key) => visitor.$visit(key),
+                Err(_) => self.deserialize_any(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for MapKeyDeserializer {
+    type Error = crate::de::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, crate::de::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Value::String(self.key).deserialize_any(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, crate::de::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Value::String(self.key).deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, crate::de::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Value::String(self.key).deserialize_option(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, crate::de::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    parse_key! {
+        deserialize_bool => visit_bool: bool,
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_char => visit_char: char,
+    }
+
+    serde::forward_to_deserialize_any! {
+        f32 f64 str string unit seq
+        bytes byte_buf map unit_struct tuple_struct struct
+        tuple ignored_any identifier
+    }
+}
+
 struct MapDeserializer {
     iter: <Table as IntoIterator>::IntoIter,
     value: Option<(String, Value)>,
@@ -670,7 +890,7 @@ impl<'de> de::MapAccess<'de> for MapDeserializer {
         match self.iter.next() {
             Some((key, value)) => {
                 self.value = Some((key.clone(), value));
-                seed.deserialize(Value::String(key)).map(Some)
+                seed.deserialize(MapKeyDeserializer { key }).map(Some)
             }
             None => Ok(None),
         }
@@ -1286,6 +1506,9 @@ impl ser::SerializeMap for SerializeMap {
     {
         match Value::try_from(key)? {
             Value::String(s) => self.next_key = Some(s),
+            Value::Integer(i) => self.next_key = Some(i.to_string()),
+            Value::Boolean(b) => self.next_key = Some(b.to_string()),
+            Value::Datetime(d) => self.next_key = Some(d.to_string()),
             _ => return Err(crate::ser::Error::key_not_string()),
         };
         Ok(())
@@ -0,0 +1,138 @@
+//! Conversions between [`Value`] and YAML
+//!
+//! The data models of TOML and YAML don't line up exactly:
+//! - TOML datetimes are converted to YAML strings
+//! - YAML anchors and aliases are resolved by the YAML parser and `<<` merge keys are applied
+//! - YAML nulls within a mapping are skipped, like `None` fields when serializing, and are an
+//!   error anywhere else
+//! - YAML keys that are booleans or numbers are converted to strings
+//! - YAML tags (`!tag value`) are an error
+//!
+//! YAML is only exchanged as text, so which YAML library is used is not part of the API.
+//!
+//! # Examples
+//!
+//! ```
+//! let toml = toml::yaml::from_yaml_str("
+//! defaults: &defaults
+//!   port: 80
+//! server:
+//!   <<: *defaults
+//!   host: localhost
+//!   proxy: null
+//! ").unwrap();
+//! assert_eq!(toml["server"]["port"].as_integer(), Some(80));
+//! assert!(toml["server"].get("proxy").is_none());
+//!
+//! let yaml = toml::yaml::to_yaml_string(&toml).unwrap();
+//! assert!(yaml.contains("host: localhost"));
+//! ```
+
+use serde_norway as yaml;
+
+use crate::map::Map;
+use crate::Value;
+
+/// Render a TOML [`Value`] as a YAML document
+pub fn to_yaml_string(value: &Value) -> Result<String, Error> {
+    yaml::to_string(&to_yaml(value)).map_err(|e| Error::Syntax(e.to_string()))
+}
+
+/// Parse a YAML document into a TOML [`Value`]
+pub fn from_yaml_str(s: &str) -> Result<Value, Error> {
+    let mut value = yaml::from_str::<yaml::Value>(s).map_err(|e| Error::Syntax(e.to_string()))?;
+    value
+        .apply_merge()
+        .map_err(|e| Error::Merge(e.to_string()))?;
+    from_yaml(value)
+}
+
+fn to_yaml(value: &Value) -> yaml::Value {
+    match value {
+        Value::String(s) => yaml::Value::String(s.clone()),
+        Value::Integer(i) => yaml::Value::Number((*i).into()),
+        Value::Float(f) => yaml::Value::Number((*f).into()),
+        Value::Boolean(b) => yaml::Value::Bool(*b),
+        Value::Datetime(d) => yaml::Value::String(d.to_string()),
+        Value::Array(a) => yaml::Value::Sequence(a.iter().map(to_yaml).collect()),
+        Value::Table(t) => yaml::Value::Mapping(
+            t.iter()
+                .map(|(k, v)| (yaml::Value::String(k.clone()), to_yaml(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn from_yaml(value: yaml::Value) -> Result<Value, Error> {
+    match value {
+        yaml::Value::Null => Err(Error::Null),
+        yaml::Value::Bool(b) => Ok(Value::Boolean(b)),
+        yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Ok(Value::Integer(i))
+            } else if let Some(u) = n.as_u64() {
+                Err(Error::IntegerOutOfRange(u))
+            } else {
+                Ok(Value::Float(
+                    n.as_f64().expect("all numbers are f64-compatible"),
+                ))
+            }
+        }
+        yaml::Value::String(s) => Ok(Value::String(s)),
+        yaml::Value::Sequence(s) => s
+            .into_iter()
+            .map(from_yaml)
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        yaml::Value::Mapping(m) => {
+            let mut table = Map::with_capacity(m.len());
+            for (k, v) in m {
+                if v.is_null() {
+                    continue;
+                }
+                let k = match k {
+                    yaml::Value::String(s) => s,
+                    yaml::Value::Bool(b) => b.to_string(),
+                    yaml::Value::Number(n) => n.to_string(),
+                    _ => return Err(Error::KeyNotScalar),
+                };
+                table.insert(k, from_yaml(v)?);
+            }
+            Ok(Value::Table(table))
+        }
+        yaml::Value::Tagged(t) => Err(Error::Tagged(t.tag.to_string())),
+    }
+}
+
+/// Errors that can occur when converting between YAML and a [`Value`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The YAML could not be parsed or rendered
+    Syntax(String),
+    /// `null` outside of a mapping has no TOML equivalent
+    Null,
+    /// Integer was larger than TOML supports
+    IntegerOutOfRange(u64),
+    /// Mapping key was not a string, boolean, or number
+    KeyNotScalar,
+    /// A `!tag` has no TOML equivalent
+    Tagged(String),
+    /// `<<` merge keys could not be applied
+    Merge(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax(s) => s.fmt(formatter),
+            Self::Null => "unsupported null value".fmt(formatter),
+            Self::IntegerOutOfRange(i) => write!(formatter, "out-of-range integer `{i}`"),
+            Self::KeyNotScalar => "map key was not a scalar".fmt(formatter),
+            Self::Tagged(t) => write!(formatter, "unsupported tag `{t}`"),
+            Self::Merge(s) => s.fmt(formatter),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -246,6 +246,7 @@ TOML parse error at line 1, column 7
 1 | bar = "a"
   |       ^^^
 invalid type: string "a", expected isize
+in `bar`
 
 "#]],
         str![[r#"
@@ -268,6 +269,7 @@ TOML parse error at line 2, column 7
 2 | bar = "a"
   |       ^^^
 invalid type: string "a", expected isize
+in `foo.bar`
 
 "#]],
         str![[r#"
@@ -848,6 +850,7 @@ TOML parse error at line 8, column 9
 8 | debug = 'a'
   |         ^^^
 expected a boolean or an integer
+in `profile.dev.debug`
 
 "#]]
     );
@@ -872,6 +875,7 @@ TOML parse error at line 8, column 17
 8 | dev = { debug = 'a' }
   |                 ^^^
 expected a boolean or an integer
+in `profile.dev.debug`
 
 "#]]
     );
@@ -1593,3 +1597,176 @@ edition = "2021"
     };
     assert_eq!(err.span(), Some(61..66));
 }
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn table_roundtrip_preserves_order() {
+    let raw = r#"z = 1
+a = 2
+
+[tbl]
+y = 1
+
+[tbl.b]
+q = 1
+c = 2
+
+[[arr]]
+x = 1
+w = 2
+"#;
+    let table = t!(crate::from_str::<crate::SerdeTable>(raw));
+    assert_data_eq!(t!(crate::to_string(&table)), raw.raw());
+    let value = t!(crate::from_str::<crate::SerdeValue>(raw));
+    assert_data_eq!(t!(crate::to_string(&value)), raw.raw());
+
+    let mut table = table;
+    table.remove("z");
+    assert_eq!(table.keys().collect::<Vec<_>>(), ["a", "tbl", "arr"]);
+}
+
+#[test]
+fn mixed_arrays() {
+    use toml::value::Mixed;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Feature {
+        name: String,
+        optional: bool,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Foo {
+        features: Vec<Mixed<String, Feature>>,
+        numbers: Vec<Mixed<i64, f64>>,
+    }
+
+    let raw = r#"features = ["std", { name = "serde", optional = true }]
+numbers = [1, 2.5]
+"#;
+    let foo = t!(crate::from_str::<Foo>(raw));
+    assert_eq!(
+        foo,
+        Foo {
+            features: vec![
+                Mixed::First("std".to_owned()),
+                Mixed::Second(Feature {
+                    name: "serde".to_owned(),
+                    optional: true,
+                }),
+            ],
+            numbers: vec![Mixed::First(1), Mixed::Second(2.5)],
+        }
+    );
+    assert_eq!(foo.features[0].first().map(String::as_str), Some("std"));
+    assert!(foo.features[1].is_second());
+    let names = foo
+        .features
+        .into_iter()
+        .map(|f| f.unify(|name| name, |feature| feature.name))
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["std", "serde"]);
+
+    let foo = Foo {
+        features: vec![Mixed::First("std".to_owned())],
+        numbers: vec![Mixed::First(1), Mixed::Second(2.5)],
+    };
+    assert_data_eq!(
+        t!(crate::to_string(&foo)),
+        str![[r#"
+features = ["std"]
+numbers = [1, 2.5]
+
+"#]]
+    );
+
+    let err = crate::from_str::<Foo>("features = [true]\nnumbers = []\n").unwrap_err();
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+TOML parse error at line 1, column 12
+  |
+1 | features = [true]
+  |            ^^^^^^
+did not match either type: invalid type: boolean `true`, expected a string; invalid type: boolean `true`, expected struct Feature
+in `features[0]`
+
+"#]]
+    );
+}
+
+/// Accepts only a skipped value, to check ignored content isn't visited
+#[derive(Debug, PartialEq)]
+struct Skipped;
+
+impl<'de> Deserialize<'de> for Skipped {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SkippedVisitor;
+
+        impl serde::de::Visitor<'_> for SkippedVisitor {
+            type Value = Skipped;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("a skipped value")
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(Skipped)
+            }
+        }
+
+        deserializer.deserialize_ignored_any(SkippedVisitor)
+    }
+}
+
+#[test]
+fn ignored_any_skips_subtrees() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Package {
+        name: String,
+        metadata: Skipped,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Manifest {
+        package: Package,
+        bin: Skipped,
+    }
+
+    let input = "
+[package]
+name = 'app'
+
+[package.metadata.docs]
+targets = ['x86_64-unknown-linux-gnu', { nested = [1, 2.5, true] }]
+
+[[bin]]
+name = 'cli'
+";
+    let expected = Manifest {
+        package: Package {
+            name: "app".to_owned(),
+            metadata: Skipped,
+        },
+        bin: Skipped,
+    };
+    assert_eq!(t!(crate::from_str::<Manifest>(input)), expected);
+    let value = t!(crate::from_str::<crate::SerdeValue>(input));
+    assert_eq!(t!(value.try_into::<Manifest>()), expected);
+
+    // Unknown fields are skipped the same way
+    let package = t!(crate::from_str::<Package>(
+        "name = 'app'\nmetadata = 1\nunknown = { a = [{ b = 1 }] }"
+    ));
+    assert_eq!(package.name, "app");
+    assert_eq!(
+        t!(crate::value_from_str::<Skipped>("{ a = [1, 2] }")),
+        Skipped
+    );
+
+    // Syntax errors are still reported
+    assert!(crate::from_str::<serde::de::IgnoredAny>("a = ").is_err());
+}
@@ -13,10 +13,13 @@ macro_rules! t {
 mod de_enum;
 mod de_errors;
 mod general;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod reader;
 mod ser_enum;
 mod ser_formatting;
 mod ser_formatting_raw;
 mod ser_tables_last;
+mod shapes;
 mod spanned;
 
 use toml::from_str;
@@ -0,0 +1,192 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Package {
+    name: String,
+    version: String,
+}
+
+const INPUT: &[u8] = b"name = 'foo'\nversion = '1.0.0'\n";
+
+fn package() -> Package {
+    Package {
+        name: "foo".to_owned(),
+        version: "1.0.0".to_owned(),
+    }
+}
+
+struct NoopWake;
+
+impl Wake for NoopWake {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(NoopWake));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Reads a few bytes at a time, pending before each read, then fails with `error` if set
+struct Trickle {
+    input: &'static [u8],
+    pending: bool,
+    error: Option<io::ErrorKind>,
+}
+
+impl Trickle {
+    fn new(input: &'static [u8]) -> Self {
+        Self {
+            input,
+            pending: false,
+            error: None,
+        }
+    }
+
+    fn failing(input: &'static [u8], error: io::ErrorKind) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(input)
+        }
+    }
+
+    fn read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.pending = !self.pending;
+        if self.pending {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        if self.input.is_empty() {
+            if let Some(error) = self.error {
+                return Poll::Ready(Err(io::Error::new(error, "connection lost")));
+            }
+        }
+        let len = self.input.len().min(buf.len()).min(3);
+        buf[..len].copy_from_slice(&self.input[..len]);
+        self.input = &self.input[len..];
+        Poll::Ready(Ok(len))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncRead for Trickle {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().read(cx, buf)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for Trickle {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let unfilled = buf.initialize_unfilled();
+        match self.get_mut().read(cx, unfilled) {
+            Poll::Ready(Ok(len)) => {
+                buf.advance(len);
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "futures-io")]
+mod futures {
+    use super::*;
+
+    use toml::de::from_async_reader;
+
+    #[test]
+    fn slice() {
+        let actual: Package = block_on(from_async_reader(INPUT)).unwrap();
+        assert_eq!(actual, package());
+    }
+
+    #[test]
+    fn chunks() {
+        let actual: Package = block_on(from_async_reader(Trickle::new(INPUT))).unwrap();
+        assert_eq!(actual, package());
+
+        let large = format!("name = '{}'\nversion = '1.0.0'\n", "a".repeat(20_000));
+        let actual: Package = block_on(from_async_reader(large.as_bytes())).unwrap();
+        assert_eq!(actual.name.len(), 20_000);
+    }
+
+    #[test]
+    fn read_error() {
+        let reader = Trickle::failing(INPUT, io::ErrorKind::ConnectionReset);
+        let err = block_on(from_async_reader::<Package, _>(reader)).unwrap_err();
+        assert_eq!(err.message(), "connection lost");
+        assert_eq!(err.span(), None);
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let err = block_on(from_async_reader::<Package, _>(&b"name = '\xff'\n"[..])).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "invalid utf-8 sequence of 1 bytes from index 8"
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        let err = block_on(from_async_reader::<Package, _>(&b"name = 'foo'\n"[..])).unwrap_err();
+        assert_eq!(err.message(), "missing field `version`");
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_io {
+    use super::*;
+
+    use toml::de::from_tokio_reader;
+
+    #[test]
+    fn slice() {
+        let actual: Package = block_on(from_tokio_reader(INPUT)).unwrap();
+        assert_eq!(actual, package());
+    }
+
+    #[test]
+    fn chunks() {
+        let actual: Package = block_on(from_tokio_reader(Trickle::new(INPUT))).unwrap();
+        assert_eq!(actual, package());
+    }
+
+    #[test]
+    fn read_error() {
+        let reader = Trickle::failing(INPUT, io::ErrorKind::UnexpectedEof);
+        let err = block_on(from_tokio_reader::<Package, _>(reader)).unwrap_err();
+        assert_eq!(err.message(), "connection lost");
+        assert_eq!(err.span(), None);
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let err = block_on(from_tokio_reader::<Package, _>(&b"name = '\xff'\n"[..])).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "invalid utf-8 sequence of 1 bytes from index 8"
+        );
+    }
+}
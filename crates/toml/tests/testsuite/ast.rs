@@ -0,0 +1,36 @@
+use toml::Table;
+
+#[test]
+fn valid_matches_toml() {
+    let cases = toml_test_data::version("1.0.0").collect::<std::collections::HashSet<_>>();
+    let mut mismatches = Vec::new();
+    for case in toml_test_data::valid().filter(|c| cases.contains(c.name())) {
+        let Ok(input) = std::str::from_utf8(case.fixture()) else {
+            continue;
+        };
+        let mut errors = Vec::new();
+        let actual = Table::from(toml_parse::ast::parse(input, &mut errors));
+        let expected = input.parse::<Table>().unwrap();
+        if !errors.is_empty() || actual.to_string() != expected.to_string() {
+            mismatches.push(format!("{}: {errors:?}", case.name().display()));
+        }
+    }
+    assert_eq!(mismatches, Vec::<String>::new());
+}
+
+#[test]
+fn invalid_is_reported() {
+    let cases = toml_test_data::version("1.0.0").collect::<std::collections::HashSet<_>>();
+    let mut accepted = Vec::new();
+    for case in toml_test_data::invalid().filter(|c| cases.contains(c.name())) {
+        let Ok(input) = std::str::from_utf8(case.fixture()) else {
+            continue;
+        };
+        let mut errors = Vec::new();
+        let _ = toml_parse::ast::parse(input, &mut errors);
+        if errors.is_empty() {
+            accepted.push(case.name().display().to_string());
+        }
+    }
+    assert_eq!(accepted, Vec::<String>::new());
+}
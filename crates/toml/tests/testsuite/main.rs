@@ -7,6 +7,10 @@ macro_rules! map( ($($k:expr => $v:expr),*) => ({
     _m
 }) );
 
+#[cfg(feature = "ast")]
+mod ast;
 mod macros;
 mod table;
 mod value;
+#[cfg(feature = "yaml")]
+mod yaml;
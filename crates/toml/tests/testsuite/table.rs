@@ -63,3 +63,103 @@ fn datetime_offset_issue_496() {
     let output = toml.to_string();
     assert_data_eq!(output, original.raw());
 }
+
+#[test]
+fn map_entry_and_append() {
+    let mut map: Map<_, _> = map! {
+        "b" => Integer(2),
+        "a" => Integer(1)
+    };
+    map.entry("a")
+        .and_modify(|v| *v = Integer(10))
+        .or_insert(Integer(0));
+    map.entry("c")
+        .and_modify(|v| *v = Integer(30))
+        .or_insert(Integer(3));
+
+    let mut other = map! {
+        "d" => Integer(4)
+    };
+    map.append(&mut other);
+    assert!(other.is_empty());
+
+    for value in map.values_mut() {
+        if let Integer(i) = value {
+            *i += 1;
+        }
+    }
+    assert_eq!(map.remove_entry("b"), Some(("b".to_owned(), Integer(3))));
+
+    map.sort_keys();
+    assert_data_eq!(
+        map.to_string(),
+        str![[r#"
+a = 11
+c = 4
+d = 5
+
+"#]]
+        .raw()
+    );
+    assert_eq!(
+        map.clone().into_keys().collect::<Vec<_>>(),
+        vec!["a".to_owned(), "c".to_owned(), "d".to_owned()]
+    );
+    assert_eq!(
+        map.into_values().collect::<Vec<_>>(),
+        vec![Integer(11), Integer(4), Integer(5)]
+    );
+}
+
+#[test]
+fn map_range() {
+    use std::ops::Bound;
+
+    let map: Map<_, _> = map! {
+        "b" => Integer(2),
+        "d" => Integer(4),
+        "a" => Integer(1),
+        "c" => Integer(3)
+    };
+    let keys = |range: (Bound<&str>, Bound<&str>)| {
+        map.range::<str, _>(range)
+            .map(|(k, _)| k.as_str())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        keys((Bound::Included("b"), Bound::Excluded("d"))),
+        ["b", "c"]
+    );
+    assert_eq!(
+        keys((Bound::Included("e"), Bound::Unbounded)),
+        Vec::<&str>::new()
+    );
+    let last = map.range::<str, _>(..).next_back().unwrap();
+    #[cfg(not(feature = "preserve_order"))]
+    {
+        assert_eq!(
+            keys((Bound::Excluded("a"), Bound::Unbounded)),
+            ["b", "c", "d"]
+        );
+        assert_eq!(last, (&"d".to_owned(), &Integer(4)));
+    }
+    #[cfg(feature = "preserve_order")]
+    {
+        assert_eq!(
+            keys((Bound::Excluded("a"), Bound::Unbounded)),
+            ["b", "d", "c"]
+        );
+        assert_eq!(last, (&"c".to_owned(), &Integer(3)));
+
+        let values = map.get_range(1..3).unwrap().map(|(_, v)| v.clone());
+        assert_eq!(values.collect::<Vec<_>>(), [Integer(4), Integer(1)]);
+        assert!(map.get_range(..5).is_none());
+    }
+}
+
+#[test]
+#[should_panic = "range start is greater than range end in Map"]
+fn map_range_backwards() {
+    let map: Map<_, _> = map! { "a" => Integer(1) };
+    let _ = map.range("b".to_owned().."a".to_owned());
+}
@@ -0,0 +1,143 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml::yaml::{from_yaml_str, to_yaml_string, Error};
+use toml::Value;
+
+#[test]
+fn to_yaml() {
+    let value = r#"
+name = "foo"
+count = 3
+ratio = 0.5
+enabled = true
+released = 1979-05-27T07:32:00Z
+tags = ["a", "b"]
+
+[server]
+port = 8080
+"#
+    .parse::<toml::Table>()
+    .unwrap();
+    let yaml = to_yaml_string(&Value::Table(value)).unwrap();
+    assert_data_eq!(
+        yaml,
+        str![[r#"
+name: foo
+count: 3
+ratio: 0.5
+enabled: true
+released: 1979-05-27T07:32:00Z
+tags:
+- a
+- b
+server:
+  port: 8080
+
+"#]]
+    );
+}
+
+#[test]
+fn from_yaml() {
+    let value = from_yaml_str(
+        "
+name: foo
+count: 3
+ratio: 0.5
+enabled: true
+tags: [a, b]
+1: one
+true: yes
+server:
+  port: 8080
+  proxy: ~
+",
+    )
+    .unwrap();
+    assert_data_eq!(
+        toml::to_string(&value).unwrap(),
+        str![[r#"
+name = "foo"
+count = 3
+ratio = 0.5
+enabled = true
+tags = ["a", "b"]
+1 = "one"
+true = "yes"
+
+[server]
+port = 8080
+
+"#]]
+    );
+}
+
+#[test]
+fn round_trip() {
+    let input = r#"
+name = "foo"
+nested = [[1, 2], [3.5]]
+
+[[bin]]
+path = "a.rs"
+
+[[bin]]
+path = "b.rs"
+"#;
+    let value = Value::Table(input.parse::<toml::Table>().unwrap());
+    let yaml = to_yaml_string(&value).unwrap();
+    assert_eq!(from_yaml_str(&yaml).unwrap(), value);
+}
+
+#[test]
+fn datetime_becomes_string() {
+    let value = Value::Table("a = 1979-05-27\n".parse::<toml::Table>().unwrap());
+    let yaml = to_yaml_string(&value).unwrap();
+    assert_eq!(
+        from_yaml_str(&yaml).unwrap()["a"].as_str(),
+        Some("1979-05-27")
+    );
+}
+
+#[test]
+fn anchors_and_merge_keys() {
+    let value = from_yaml_str(
+        "
+base: &base
+  port: 80
+  host: a
+server:
+  <<: *base
+  host: b
+ports: [*base, *base]
+",
+    )
+    .unwrap();
+    assert_eq!(value["server"]["port"].as_integer(), Some(80));
+    assert_eq!(value["server"]["host"].as_str(), Some("b"));
+    assert_eq!(value["ports"][1]["host"].as_str(), Some("a"));
+}
+
+#[test]
+fn errors() {
+    assert_eq!(from_yaml_str("~").unwrap_err(), Error::Null);
+    assert_eq!(from_yaml_str("[1, null]").unwrap_err(), Error::Null);
+    assert_eq!(
+        from_yaml_str("18446744073709551615").unwrap_err(),
+        Error::IntegerOutOfRange(u64::MAX)
+    );
+    assert_eq!(
+        from_yaml_str("? [a]\n: 1\n").unwrap_err(),
+        Error::KeyNotScalar
+    );
+    assert_eq!(
+        from_yaml_str("a: !custom 1\n").unwrap_err(),
+        Error::Tagged("!custom".to_owned())
+    );
+    assert_eq!(
+        from_yaml_str("<<: 1\n").unwrap_err().to_string(),
+        "expected a mapping or list of mappings for merging, but found scalar"
+    );
+    let err = from_yaml_str("a: [1\n").unwrap_err();
+    assert!(matches!(err, Error::Syntax(_)), "{err:?}");
+}
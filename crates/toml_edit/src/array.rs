@@ -2,18 +2,20 @@ use std::iter::FromIterator;
 use std::mem;
 
 use crate::repr::Decor;
-use crate::value::{DEFAULT_LEADING_VALUE_DECOR, DEFAULT_VALUE_DECOR};
-use crate::{Item, RawString, Value};
+use crate::value::{
+    DEFAULT_LEADING_VALUE_DECOR, DEFAULT_MULTILINE_VALUE_DECOR, DEFAULT_VALUE_DECOR,
+};
+use crate::{Item, RawString, SortComments, Value};
 
 /// A TOML [`Value`] that contains a sequence of [`Value`]s
 #[derive(Debug, Default, Clone)]
 pub struct Array {
     // `trailing` represents whitespaces, newlines
     // and comments in an empty array or after the trailing comma
-    trailing: RawString,
+    pub(crate) trailing: RawString,
     trailing_comma: bool,
     // prefix before `[` and suffix after `]`
-    decor: Decor,
+    pub(crate) decor: Decor,
     pub(crate) span: Option<std::ops::Range<usize>>,
     // always Vec<Item::Value>
     pub(crate) values: Vec<Item>,
@@ -41,11 +43,23 @@ impl Array {
         Default::default()
     }
 
+    /// Create an empty `Array` with space for at least `capacity` values
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut arr = toml_edit::Array::with_capacity(2);
+    /// arr.push(1);
+    /// arr.push("foo");
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_vec(Vec::with_capacity(capacity))
+    }
+
     pub(crate) fn with_vec(values: Vec<Item>) -> Self {
-        Self {
-            values,
-            ..Default::default()
-        }
+        let mut array = Self::default();
+        array.values = values;
+        array
     }
 }
 
@@ -56,6 +70,25 @@ impl Array {
         decorate_array(self);
     }
 
+    /// Auto formats the array with one value per line and a trailing comma.
+    ///
+    /// Keeping a trailing comma means appending a value only adds a line, minimizing diffs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// let mut arr = toml_edit::Array::new();
+    /// arr.push(1);
+    /// arr.push("foo");
+    /// arr.fmt_multiline();
+    /// assert_eq!(arr.to_string(), "[\n    1,\n    \"foo\",\n]");
+    /// # }
+    /// ```
+    pub fn fmt_multiline(&mut self) {
+        decorate_multiline_array(self);
+    }
+
     /// Set whether the array will use a trailing comma
     pub fn set_trailing_comma(&mut self, yes: bool) {
         self.trailing_comma = yes;
@@ -67,7 +100,19 @@ impl Array {
     }
 
     /// Set whitespace after last element
-    pub fn set_trailing(&mut self, trailing: impl Into<RawString>) {
+    ///
+    /// Only whitespace, newlines, and newline-terminated comments are allowed.
+    pub fn set_trailing(
+        &mut self,
+        trailing: impl Into<RawString>,
+    ) -> Result<(), crate::StructureError> {
+        let trailing = trailing.into();
+        crate::structure::check_whitespace(&trailing, crate::structure::Space::Lines)?;
+        self.trailing = trailing;
+        Ok(())
+    }
+
+    pub(crate) fn set_trailing_unchecked(&mut self, trailing: impl Into<RawString>) {
         self.trailing = trailing.into();
     }
 
@@ -93,12 +138,14 @@ impl Array {
         self.span.clone()
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
-        self.span = None;
-        self.decor.despan(input);
-        self.trailing.despan(input);
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
+        if !spans {
+            self.span = None;
+        }
+        self.decor.despan(input, spans);
+        self.trailing.despan(input, spans);
         for value in &mut self.values {
-            value.despan(input);
+            value.despan(input, spans);
         }
     }
 }
@@ -152,6 +199,14 @@ impl Array {
         self.values.clear();
     }
 
+    /// Shrinks the capacity of the array, and everything nested under it, as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.values.shrink_to_fit();
+        for value in self.values.iter_mut() {
+            value.shrink_to_fit();
+        }
+    }
+
     /// Returns a reference to the value at the given index, or `None` if the index is out of
     /// bounds.
     pub fn get(&self, index: usize) -> Option<&Value> {
@@ -192,6 +247,45 @@ impl Array {
         self.values.push(Item::Value(v));
     }
 
+    /// Inserts a value before the first value that `compare` orders after it, returning its index
+    ///
+    /// For an array already sorted by `compare`, this keeps it sorted with the new value after
+    /// any equal values.  Whitespace is taken from the surrounding values so an array with one
+    /// value per line, like `features = [...]` in a `Cargo.toml`, stays that way.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut arr = "[\n    'a',\n    'c',\n]".parse::<toml_edit::Value>().unwrap();
+    /// let arr = arr.as_array_mut().unwrap();
+    /// let index = arr.push_sorted("b", |lhs, rhs| lhs.as_str().cmp(&rhs.as_str()));
+    /// assert_eq!(index, 1);
+    /// assert_eq!(arr.to_string(), "[\n    'a',\n    \"b\",\n    'c',\n]");
+    /// # }
+    /// # }
+    /// ```
+    pub fn push_sorted<V, F>(&mut self, v: V, mut compare: F) -> usize
+    where
+        V: Into<Value>,
+        F: FnMut(&Value, &Value) -> std::cmp::Ordering,
+    {
+        let mut value = v.into();
+        let index = self
+            .values
+            .iter()
+            .position(|item| {
+                item.as_value()
+                    .map(|existing| compare(existing, &value) == std::cmp::Ordering::Greater)
+                    .unwrap_or(false)
+            })
+            .unwrap_or(self.values.len());
+        decorate_inserted(self, index, &mut value);
+        self.values.insert(index, Item::Value(value));
+        index
+    }
+
     /// Inserts an element at the given position within the array, applying default formatting to
     /// it and shifting all values after it to the right.
     ///
@@ -320,6 +414,25 @@ impl Array {
             .retain(|item| item.as_value().map(&mut keep).unwrap_or(false));
     }
 
+    /// Returns the indices of values that `same` considers equal to an earlier value
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let arr = toml_edit::Array::from_iter(["serde", "std", "serde"]);
+    /// let duplicates = arr.duplicates_by(|lhs, rhs| lhs.as_str() == rhs.as_str());
+    /// assert_eq!(duplicates, [2]);
+    /// ```
+    pub fn duplicates_by<F>(&self, mut same: F) -> Vec<usize>
+    where
+        F: FnMut(&Value, &Value) -> bool,
+    {
+        let values = self.iter().collect::<Vec<_>>();
+        (0..values.len())
+            .filter(|&i| values[..i].iter().any(|earlier| same(earlier, values[i])))
+            .collect()
+    }
+
     /// Sorts the slice with a comparator function.
     ///
     /// This sort is stable (i.e., does not reorder equal elements) and *O*(*n* \* log(*n*)) worst-case.
@@ -333,42 +446,170 @@ impl Array {
     ///
     /// For example, while [`f64`] doesn't implement [`Ord`] because `NaN != NaN`, we can use
     /// `partial_cmp` as our sort function when we know the slice doesn't contain a `NaN`.
+    ///
+    /// A comment at the end of a value's line moves with it, see [`SortComments::MoveWithEntry`].
     #[inline]
-    pub fn sort_by<F>(&mut self, mut compare: F)
+    pub fn sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&Value, &Value) -> std::cmp::Ordering,
+    {
+        self.sort_by_with_comments(SortComments::MoveWithEntry, compare);
+    }
+
+    /// Sorts the array with a comparator function, placing comments according to `comments`
+    ///
+    /// This sort is stable, see [`Array::sort_by`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::SortComments;
+    ///
+    /// let mut doc = "
+    /// features = [
+    ///     ## Optional
+    ///     'std', # the default
+    ///     'alloc',
+    /// ]
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let features = doc["features"].as_array_mut().unwrap();
+    ///
+    /// features.sort_by_with_comments(SortComments::KeepPosition, |l, r| l.as_str().cmp(&r.as_str()));
+    /// assert_eq!(doc.to_string(), "
+    /// features = [
+    ///     ## Optional
+    ///     'alloc', # the default
+    ///     'std',
+    /// ]
+    /// ");
+    ///
+    /// let features = doc["features"].as_array_mut().unwrap();
+    /// features.sort_by_with_comments(SortComments::MoveWithEntry, |l, r| r.as_str().cmp(&l.as_str()));
+    /// assert_eq!(doc.to_string(), "
+    /// features = [
+    ///     'std',
+    ///     ## Optional
+    ///     'alloc', # the default
+    /// ]
+    /// ");
+    /// # }
+    /// # }
+    /// ```
+    pub fn sort_by_with_comments<F>(&mut self, comments: SortComments, mut compare: F)
     where
         F: FnMut(&Value, &Value) -> std::cmp::Ordering,
     {
-        self.values.sort_by(move |lhs, rhs| {
-            let lhs = lhs.as_value();
-            let rhs = rhs.as_value();
-            match (lhs, rhs) {
-                (None, None) => std::cmp::Ordering::Equal,
-                (Some(_), None) => std::cmp::Ordering::Greater,
-                (None, Some(_)) => std::cmp::Ordering::Less,
-                (Some(lhs), Some(rhs)) => compare(lhs, rhs),
+        let mut compare = move |lhs: &Item, rhs: &Item| match (lhs.as_value(), rhs.as_value()) {
+            (None, None) => std::cmp::Ordering::Equal,
+            (Some(_), None) => std::cmp::Ordering::Greater,
+            (None, Some(_)) => std::cmp::Ordering::Less,
+            (Some(lhs), Some(rhs)) => compare(lhs, rhs),
+        };
+        match comments {
+            SortComments::MoveWithEntry => {
+                // A comment ending a value's line is parsed into what follows its comma, while
+                // one after the `[` stays put
+                let mut ends = Vec::with_capacity(self.values.len());
+                for item in self.values.iter_mut() {
+                    let end = item
+                        .as_value_mut()
+                        .and_then(|v| take_line_end(v.decor_mut()));
+                    ends.push(end);
+                }
+                let open = if ends.is_empty() {
+                    None
+                } else {
+                    ends.remove(0)
+                };
+                if !self.values.is_empty() {
+                    let (end, rest) = split_line_end(&self.trailing);
+                    if end.is_some() {
+                        self.trailing = RawString::from(rest);
+                    }
+                    ends.push(end);
+                }
+
+                let mut entries = mem::take(&mut self.values)
+                    .into_iter()
+                    .zip(ends)
+                    .collect::<Vec<_>>();
+                entries.sort_by(|(lhs, _), (rhs, _)| compare(lhs, rhs));
+                let (values, ends): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+                self.values = values;
+
+                let mut ends = std::iter::once(open).chain(ends);
+                for item in self.values.iter_mut() {
+                    if let (Some(end), Some(value)) = (ends.next().flatten(), item.as_value_mut()) {
+                        let decor = value.decor_mut();
+                        let rest = decor.prefix().and_then(|p| p.as_str()).unwrap_or_default();
+                        let prefix = join_line_end(&end, rest);
+                        decor.set_prefix_unchecked(prefix);
+                    }
+                }
+                if let Some(end) = ends.next().flatten() {
+                    let rest = self.trailing.as_str().unwrap_or_default();
+                    self.trailing = RawString::from(join_line_end(&end, rest));
+                }
+            }
+            SortComments::KeepPosition => {
+                let decors = self
+                    .values
+                    .iter()
+                    .map(|item| item.as_value().map(|v| v.decor().clone()))
+                    .collect::<Vec<_>>();
+                self.values.sort_by(compare);
+                for (item, decor) in self.values.iter_mut().zip(decors) {
+                    if let (Some(value), Some(decor)) = (item.as_value_mut(), decor) {
+                        *value.decor_mut() = decor;
+                    }
+                }
             }
-        });
+        }
     }
 
     /// Sorts the array with a key extraction function.
     ///
     /// This sort is stable (i.e., does not reorder equal elements) and *O*(*m* \* *n* \* log(*n*))
     /// worst-case, where the key function is *O*(*m*).
+    ///
+    /// A comment at the end of a value's line moves with it, see [`SortComments::MoveWithEntry`].
     #[inline]
     pub fn sort_by_key<K, F>(&mut self, mut f: F)
     where
         F: FnMut(&Value) -> K,
         K: Ord,
     {
-        #[allow(clippy::manual_map)] // needed for lifetimes
-        self.values.sort_by_key(move |item| {
-            if let Some(value) = item.as_value() {
-                Some(f(value))
-            } else {
-                None
-            }
-        });
+        self.sort_by(move |lhs, rhs| f(lhs).cmp(&f(rhs)));
+    }
+}
+
+/// Remove the comment that ends the previous line from the start of `decor`'s prefix
+fn take_line_end(decor: &mut Decor) -> Option<String> {
+    let (end, rest) = split_line_end(decor.prefix()?);
+    let end = end?;
+    decor.set_prefix_unchecked(rest);
+    Some(end)
+}
+
+/// Split the comment that ends the previous line from the rest of `raw`
+fn split_line_end(raw: &RawString) -> (Option<String>, String) {
+    let text = raw.as_str().unwrap_or_default();
+    match text.find('\n') {
+        Some(i) if text[..i].contains('#') => (Some(text[..i].to_owned()), text[i..].to_owned()),
+        _ => (None, text.to_owned()),
+    }
+}
+
+/// The comment `end` followed by `rest` on the next line
+fn join_line_end(end: &str, rest: &str) -> String {
+    let mut joined = end.to_owned();
+    if !rest.starts_with(['\r', '\n']) {
+        joined.push('\n');
     }
+    joined.push_str(rest);
+    joined
 }
 
 #[cfg(feature = "display")]
@@ -392,10 +633,7 @@ impl<V: Into<Value>> FromIterator<V> for Array {
         I: IntoIterator<Item = V>,
     {
         let v = iter.into_iter().map(|a| Item::Value(a.into()));
-        Array {
-            values: v.collect(),
-            ..Default::default()
-        }
+        Array::with_vec(v.collect())
     }
 }
 
@@ -403,9 +641,9 @@ impl IntoIterator for Array {
     type Item = Value;
     type IntoIter = ArrayIntoIter;
 
-    fn into_iter(self) -> Self::IntoIter {
+    fn into_iter(mut self) -> Self::IntoIter {
         Box::new(
-            self.values
+            mem::take(&mut self.values)
                 .into_iter()
                 .filter(|v| v.is_value())
                 .map(|v| v.into_value().unwrap()),
@@ -438,5 +676,160 @@ fn decorate_array(array: &mut Array) {
     }
     // Since everything is now on the same line, remove trailing commas and whitespace.
     array.set_trailing_comma(false);
-    array.set_trailing("");
+    array.set_trailing_unchecked("");
+}
+
+fn decorate_multiline_array(array: &mut Array) {
+    // Comments before a value's comma, moved after it
+    let mut carried = Vec::new();
+    for value in array.values.iter_mut().filter_map(Item::as_value_mut) {
+        // [
+        //     value1, # comment
+        //     # comment
+        //     value2,
+        // ]
+        let mut comments = mem::take(&mut carried);
+        comments.extend(decor_comments(prefix(value)));
+        carried.extend(decor_comments(suffix(value)));
+        let mut prefix = comment_lines(&comments);
+        prefix.push_str(DEFAULT_MULTILINE_VALUE_DECOR.0);
+        value.decorate(prefix, DEFAULT_MULTILINE_VALUE_DECOR.1);
+    }
+    carried.extend(decor_comments(array.trailing().as_str().unwrap_or("")));
+    if array.is_empty() && carried.is_empty() {
+        array.set_trailing_comma(false);
+        array.set_trailing_unchecked("");
+    } else {
+        array.set_trailing_comma(!array.is_empty());
+        let mut trailing = comment_lines(&carried);
+        trailing.push('\n');
+        array.set_trailing_unchecked(trailing);
+    }
+}
+
+/// The comments in `raw`, and whether each started its own line
+fn decor_comments(raw: &str) -> impl Iterator<Item = (bool, String)> + '_ {
+    raw.split('\n')
+        .enumerate()
+        .map(|(i, line)| (i != 0, line.trim_matches([' ', '\t', '\r'])))
+        .filter(|(_, line)| line.starts_with('#'))
+        .map(|(own_line, line)| (own_line, line.to_owned()))
+}
+
+/// Lay out `comments` one per line, keeping a leading one on the line before
+fn comment_lines(comments: &[(bool, String)]) -> String {
+    let mut out = String::new();
+    for (i, (own_line, comment)) in comments.iter().enumerate() {
+        if i == 0 && !own_line {
+            out.push(' ');
+        } else {
+            out.push_str(DEFAULT_MULTILINE_VALUE_DECOR.0);
+        }
+        out.push_str(comment);
+    }
+    out
+}
+
+/// Decorate `value`, about to be inserted at `index`, like the values around it
+pub(crate) fn decorate_inserted(array: &mut Array, index: usize, value: &mut Value) {
+    let decor = value.decor();
+    if decor.prefix().is_some() || decor.suffix().is_some() || array.values.is_empty() {
+        return;
+    }
+
+    let len = array.values.len();
+    let multiline = array.iter().any(|value| prefix(value).contains('\n'));
+    if multiline {
+        let neighbor = array.values[index.min(len - 1)].as_value();
+        let indent = neighbor
+            .map(prefix)
+            .and_then(|prefix| prefix.rfind('\n').map(|i| &prefix[i..]))
+            .unwrap_or(DEFAULT_MULTILINE_VALUE_DECOR.0)
+            .to_owned();
+        // A comment after the previous value's comma stays on its line
+        let mut line_end = String::new();
+        if let Some(displaced) = array.values.get_mut(index).and_then(Item::as_value_mut) {
+            let displaced_prefix = prefix(displaced);
+            if let Some(i) = displaced_prefix.find('\n') {
+                line_end = displaced_prefix[..i].to_owned();
+                let rest = displaced_prefix[i..].to_owned();
+                displaced.decor_mut().set_prefix_unchecked(rest);
+            }
+        }
+        line_end.push_str(&indent);
+        value.decorate(line_end, "");
+        move_closing_space(array, index, value, "");
+    } else {
+        // Keep unusual spacing around commas, like `[ 1 , 2 ]`
+        let is_space = |s: &str| s.trim_start_matches([' ', '\t']).is_empty();
+        let comma_count = if array.trailing_comma() { len } else { len - 1 };
+        let before_comma = array.values[..comma_count]
+            .iter()
+            .filter_map(Item::as_value)
+            .map(suffix)
+            .find(|s| is_space(s))
+            .unwrap_or("")
+            .to_owned();
+        let after_comma = array.values[1..]
+            .iter()
+            .filter_map(Item::as_value)
+            .map(prefix)
+            .find(|s| is_space(s))
+            .unwrap_or(DEFAULT_VALUE_DECOR.0)
+            .to_owned();
+        if index == 0 {
+            let first = array.values[0].as_value_mut();
+            let leading = first
+                .as_ref()
+                .map(|first| prefix(first))
+                .filter(|s| is_space(s))
+                .unwrap_or(DEFAULT_LEADING_VALUE_DECOR.0)
+                .to_owned();
+            value.decorate(leading, before_comma.clone());
+            if let Some(first) = first {
+                first.decor_mut().set_prefix_unchecked(after_comma);
+            }
+        } else {
+            value.decorate(after_comma, before_comma.clone());
+        }
+        move_closing_space(array, index, value, &before_comma);
+    }
+}
+
+/// Without a trailing comma, the whitespace before `]` belongs to the last value, so it moves to
+/// a value appended after it, leaving `before_comma`
+fn move_closing_space(array: &mut Array, index: usize, value: &mut Value, before_comma: &str) {
+    let len = array.values.len();
+    if index != len || array.trailing_comma() {
+        return;
+    }
+    if let Some(last) = array.values[len - 1].as_value_mut() {
+        let suffix = last.decor().suffix().and_then(RawString::as_str);
+        if let Some(suffix) = suffix {
+            if suffix
+                .trim_start_matches([' ', '\t', '\r', '\n'])
+                .is_empty()
+            {
+                let suffix = suffix.to_owned();
+                last.decor_mut().set_suffix_unchecked(before_comma);
+                value.decor_mut().set_suffix_unchecked(suffix);
+            }
+        }
+    }
+}
+
+fn prefix(value: &Value) -> &str {
+    value
+        .decor()
+        .prefix()
+        .and_then(RawString::as_str)
+        .unwrap_or("")
+}
+
+fn suffix(value: &Value) -> &str {
+    value
+        .decor()
+        .suffix()
+        .and_then(RawString::as_str)
+        .unwrap_or("")
 }
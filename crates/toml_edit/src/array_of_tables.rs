@@ -1,6 +1,7 @@
+use std::cmp::Ordering;
 use std::iter::FromIterator;
 
-use crate::{Array, Item, Table};
+use crate::{Array, Item, Table, Value};
 
 /// A top-level sequence of [`Table`]s, each under their own header
 #[derive(Clone, Debug, Default)]
@@ -27,7 +28,7 @@ impl ArrayOfTables {
         for value in self.values.iter_mut() {
             value.make_value();
         }
-        let mut a = Array::with_vec(self.values);
+        let mut a = Array::with_vec(std::mem::take(&mut self.values));
         a.fmt();
         a
     }
@@ -39,10 +40,12 @@ impl ArrayOfTables {
         self.span.clone()
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
-        self.span = None;
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
+        if !spans {
+            self.span = None;
+        }
         for value in &mut self.values {
-            value.despan(input);
+            value.despan(input, spans);
         }
     }
 }
@@ -74,6 +77,13 @@ impl ArrayOfTables {
         self.values.clear();
     }
 
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.values.shrink_to_fit();
+        for value in self.values.iter_mut() {
+            value.shrink_to_fit();
+        }
+    }
+
     /// Returns an optional reference to the table.
     pub fn get(&self, index: usize) -> Option<&Table> {
         self.values.get(index).and_then(Item::as_table)
@@ -112,6 +122,216 @@ impl ArrayOfTables {
     }
 }
 
+/// Sorting
+impl ArrayOfTables {
+    /// Sorts the tables with a comparator function
+    ///
+    /// This sort is stable, so tables that compare equal keep their order.  Each table's header
+    /// moves with it, along with the headers of any tables nested within it, like `[bin.metadata]`
+    /// after a `[[bin]]`.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Table, &Table) -> Ordering,
+    {
+        let mut positions = self.iter().flat_map(subtree_positions).collect::<Vec<_>>();
+        positions.sort_unstable();
+
+        self.values
+            .sort_by(|lhs, rhs| match (lhs.as_table(), rhs.as_table()) {
+                (Some(lhs), Some(rhs)) => compare(lhs, rhs),
+                _ => Ordering::Equal,
+            });
+
+        let mut positions = positions.into_iter();
+        for table in self.iter_mut() {
+            // Hand out this subtree's share of positions in its existing order
+            let mut old = Vec::new();
+            visit_positioned(table, &mut |table| old.extend(table.position()));
+            let mut ranks = (0..old.len()).collect::<Vec<_>>();
+            ranks.sort_by_key(|&i| old[i]);
+            let mut new = vec![0; old.len()];
+            for (i, position) in ranks.into_iter().zip(&mut positions) {
+                new[i] = position;
+            }
+            let mut new = new.into_iter();
+            visit_positioned(table, &mut |table| {
+                if let Some(position) = new.next() {
+                    table.set_position(position);
+                }
+            });
+        }
+    }
+
+    /// Sorts the tables with a comparator function, placing comments according to `comments`
+    ///
+    /// The comments and blank lines above each header are what stays or moves, see
+    /// [`ArrayOfTables::sort_by`].
+    pub fn sort_by_with_comments<F>(&mut self, comments: SortComments, compare: F)
+    where
+        F: FnMut(&Table, &Table) -> Ordering,
+    {
+        match comments {
+            SortComments::MoveWithEntry => self.sort_by(compare),
+            SortComments::KeepPosition => {
+                let decors = self.iter().map(|t| t.decor.clone()).collect::<Vec<_>>();
+                self.sort_by(compare);
+                for (table, decor) in self.iter_mut().zip(decors) {
+                    table.decor = decor;
+                }
+            }
+        }
+    }
+
+    /// Sorts the tables by the values of `keys`, comparing by the first key, then the second, and
+    /// so on
+    ///
+    /// Values are compared by type: numbers by value, whether integers or floats, strings
+    /// lexicographically, and booleans and datetimes in their natural order.  Values of different
+    /// types are ordered booleans, numbers, strings, datetimes, then arrays and tables, which
+    /// compare equal.  Tables without a key sort after those with it, in either `order`.
+    ///
+    /// This sort is stable, see [`ArrayOfTables::sort_by`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::SortOrder;
+    ///
+    /// let mut doc = "
+    /// [[package]]
+    /// name = 'serde'
+    /// version = '1.0.0'
+    ///
+    /// [[package]]
+    /// name = 'clap'
+    /// version = '4.0.0'
+    ///
+    /// [[package]]
+    /// name = 'clap'
+    /// version = '3.2.0'
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let packages = doc["package"].as_array_of_tables_mut().unwrap();
+    /// packages.sort_by_keys(&["name", "version"], SortOrder::Ascending);
+    /// assert_eq!(doc.to_string(), "
+    /// [[package]]
+    /// name = 'clap'
+    /// version = '3.2.0'
+    ///
+    /// [[package]]
+    /// name = 'clap'
+    /// version = '4.0.0'
+    ///
+    /// [[package]]
+    /// name = 'serde'
+    /// version = '1.0.0'
+    /// ");
+    /// # }
+    /// # }
+    /// ```
+    pub fn sort_by_keys(&mut self, keys: &[&str], order: SortOrder) {
+        self.sort_by(|lhs, rhs| {
+            keys.iter()
+                .map(|key| {
+                    let lhs = lhs.get(key).and_then(Item::as_value);
+                    let rhs = rhs.get(key).and_then(Item::as_value);
+                    match (lhs, rhs) {
+                        (Some(lhs), Some(rhs)) => match order {
+                            SortOrder::Ascending => compare_values(lhs, rhs),
+                            SortOrder::Descending => compare_values(rhs, lhs),
+                        },
+                        (Some(_), None) => Ordering::Less,
+                        (None, Some(_)) => Ordering::Greater,
+                        (None, None) => Ordering::Equal,
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+}
+
+/// The direction of a sort, see [`ArrayOfTables::sort_by_keys`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    /// Smallest first
+    #[default]
+    Ascending,
+    /// Largest first
+    Descending,
+}
+
+/// Where comments go when a sort moves the entries they are next to
+///
+/// This covers the comments and blank lines above an entry, and a comment at the end of its line.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SortComments {
+    /// Comments move with the entry they describe
+    #[default]
+    MoveWithEntry,
+    /// Comments stay where they are, describing whichever entry is sorted into their place
+    KeepPosition,
+}
+
+fn compare_values(lhs: &Value, rhs: &Value) -> Ordering {
+    match (lhs, rhs) {
+        (Value::Boolean(lhs), Value::Boolean(rhs)) => lhs.value().cmp(rhs.value()),
+        (Value::Integer(lhs), Value::Integer(rhs)) => lhs.value().cmp(rhs.value()),
+        (Value::Float(lhs), Value::Float(rhs)) => lhs.value().total_cmp(rhs.value()),
+        (Value::Integer(lhs), Value::Float(rhs)) => (*lhs.value() as f64).total_cmp(rhs.value()),
+        (Value::Float(lhs), Value::Integer(rhs)) => lhs.value().total_cmp(&(*rhs.value() as f64)),
+        (Value::String(lhs), Value::String(rhs)) => lhs.value().cmp(rhs.value()),
+        (Value::Datetime(lhs), Value::Datetime(rhs)) => lhs.value().cmp(rhs.value()),
+        _ => type_rank(lhs).cmp(&type_rank(rhs)),
+    }
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Boolean(_) => 0,
+        Value::Integer(_) | Value::Float(_) => 1,
+        Value::String(_) => 2,
+        Value::Datetime(_) => 3,
+        Value::Array(_) | Value::InlineTable(_) => 4,
+    }
+}
+
+/// The positions of `table` and the tables nested within it
+fn subtree_positions(table: &Table) -> Vec<usize> {
+    let mut positions = table.position().into_iter().collect::<Vec<_>>();
+    for (_, item) in table.iter() {
+        match item {
+            Item::Table(child) => positions.extend(subtree_positions(child)),
+            Item::ArrayOfTables(children) => {
+                positions.extend(children.iter().flat_map(subtree_positions));
+            }
+            _ => {}
+        }
+    }
+    positions
+}
+
+/// Call `f` on `table` and the tables nested within it that have a position, see
+/// [`subtree_positions`]
+fn visit_positioned(table: &mut Table, f: &mut dyn FnMut(&mut Table)) {
+    if table.position().is_some() {
+        f(table);
+    }
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(child) => visit_positioned(child, f),
+            Item::ArrayOfTables(children) => {
+                for child in children.iter_mut() {
+                    visit_positioned(child, f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 /// An iterator type over [`ArrayOfTables`]'s [`Table`]s
 pub type ArrayOfTablesIter<'a> = Box<dyn Iterator<Item = &'a Table> + 'a>;
 /// An iterator type over [`ArrayOfTables`]'s [`Table`]s
@@ -144,9 +364,9 @@ impl IntoIterator for ArrayOfTables {
     type Item = Table;
     type IntoIter = ArrayOfTablesIntoIter;
 
-    fn into_iter(self) -> Self::IntoIter {
+    fn into_iter(mut self) -> Self::IntoIter {
         Box::new(
-            self.values
+            std::mem::take(&mut self.values)
                 .into_iter()
                 .filter(|v| v.is_table())
                 .map(|v| v.into_table().unwrap()),
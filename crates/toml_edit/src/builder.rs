@@ -0,0 +1,378 @@
+use crate::table::{DEFAULT_KEY_DECOR, DEFAULT_TABLE_DECOR};
+use std::iter::FromIterator;
+
+use crate::{ArrayOfTables, Decor, DocumentMut, Item, Key, Table, Value};
+
+/// Build a [`DocumentMut`] in the order it is written, see [`DocumentMut::builder`]
+#[derive(Clone, Debug)]
+pub struct DocumentBuilder {
+    root: TableBuilder,
+}
+
+impl DocumentMut {
+    /// Build a document one line at a time, with comments
+    ///
+    /// Comments apply to what was added last: a key/value pair, a table, or, before anything is
+    /// added, the table being built or the top of the document.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// let doc = toml_edit::DocumentMut::builder()
+    ///     .comment_above("Generated, do not edit")
+    ///     .table("package", |t| {
+    ///         t.kv("name", "foo")
+    ///             .comment("the name")
+    ///             .kv("version", "0.1.0")
+    ///     })
+    ///     .table("dependencies", |t| {
+    ///         t.comment_above("Keep sorted")
+    ///             .kv("serde", "1.0")
+    ///             .kv("serde_json", "1.0")
+    ///             .blank_line_above()
+    ///     })
+    ///     .array_of_tables("bin", |t| t.kv("name", "cli"))
+    ///     .build();
+    ///
+    /// assert_eq!(doc.to_string(), r#"# Generated, do not edit
+    /// [package]
+    /// name = "foo" # the name
+    /// version = "0.1.0"
+    ///
+    /// ## Keep sorted
+    /// [dependencies]
+    /// serde = "1.0"
+    ///
+    /// serde_json = "1.0"
+    ///
+    /// [[bin]]
+    /// name = "cli"
+    /// "#);
+    /// # }
+    /// ```
+    pub fn builder() -> DocumentBuilder {
+        DocumentBuilder {
+            root: TableBuilder::new(0),
+        }
+    }
+}
+
+impl DocumentBuilder {
+    /// Add a key/value pair, replacing an earlier value of the same key
+    pub fn kv(self, key: impl Into<Key>, value: impl Into<Value>) -> Self {
+        Self {
+            root: self.root.kv(key, value),
+        }
+    }
+
+    /// Add a table with a `[key]` header, built by `build`
+    ///
+    /// See [`TableBuilder::table`]
+    pub fn table(
+        self,
+        key: impl Into<Key>,
+        build: impl FnOnce(TableBuilder) -> TableBuilder,
+    ) -> Self {
+        Self {
+            root: self.root.table(key, build),
+        }
+    }
+
+    /// Add a table with a `[[key]]` header, built by `build`
+    pub fn array_of_tables(
+        self,
+        key: impl Into<Key>,
+        build: impl FnOnce(TableBuilder) -> TableBuilder,
+    ) -> Self {
+        Self {
+            root: self.root.array_of_tables(key, build),
+        }
+    }
+
+    /// Add `# text` at the end of the line last added
+    ///
+    /// Before anything is added, this is a comment at the top of the document.
+    pub fn comment(self, text: &str) -> Self {
+        Self {
+            root: self.root.comment(text),
+        }
+    }
+
+    /// Add `# text` on a line of its own above what was last added
+    ///
+    /// Before anything is added, this is a comment at the top of the document.
+    pub fn comment_above(self, text: &str) -> Self {
+        Self {
+            root: self.root.comment_above(text),
+        }
+    }
+
+    /// Add a blank line above what was last added
+    pub fn blank_line_above(self) -> Self {
+        Self {
+            root: self.root.blank_line_above(),
+        }
+    }
+
+    /// The document
+    pub fn build(self) -> DocumentMut {
+        let mut root = self.root.table;
+        let has_values = root.items.values().any(Item::is_value);
+        if !has_values {
+            // The first header doesn't need a blank line to separate it
+            if let Some(first) = first_table(&mut root) {
+                let prefix = first.decor.prefix().and_then(|p| p.as_str());
+                if let Some(prefix) = prefix.and_then(|p| p.strip_prefix('\n')) {
+                    let prefix = prefix.to_owned();
+                    first.decor.set_prefix_unchecked(prefix);
+                }
+            }
+        }
+        let mut doc = DocumentMut::new();
+        *doc.as_table_mut() = root;
+        doc
+    }
+}
+
+/// Build a [`Table`] in the order it is written, see [`DocumentMut::builder`]
+#[derive(Clone, Debug)]
+pub struct TableBuilder {
+    table: Table,
+    /// The key last added, or `None` for the table itself
+    last: Option<Key>,
+    /// The position of the next table header
+    next_position: usize,
+}
+
+impl TableBuilder {
+    fn new(position: usize) -> Self {
+        Self {
+            table: Table::with_pos(Some(position)),
+            last: None,
+            next_position: position + 1,
+        }
+    }
+
+    /// Add a key/value pair, replacing an earlier value of the same key
+    pub fn kv(mut self, key: impl Into<Key>, value: impl Into<Value>) -> Self {
+        let key = key.into();
+        self.table.items.shift_remove(key.get());
+        self.table
+            .items
+            .insert(key.clone(), Item::Value(value.into()));
+        self.last = Some(key);
+        self
+    }
+
+    /// Add a table with a `[key]` header, built by `build`
+    ///
+    /// A table with only tables under it has no header of its own, like `[a.b]` without `[a]`.
+    pub fn table(
+        mut self,
+        key: impl Into<Key>,
+        build: impl FnOnce(TableBuilder) -> TableBuilder,
+    ) -> Self {
+        let key = key.into();
+        let child = self.child(build);
+        self.table.items.shift_remove(key.get());
+        self.table.items.insert(key.clone(), Item::Table(child));
+        self.last = Some(key);
+        self
+    }
+
+    /// Add a table with a `[[key]]` header, built by `build`
+    ///
+    /// Each call for the same `key` adds another table to the array.
+    pub fn array_of_tables(
+        mut self,
+        key: impl Into<Key>,
+        build: impl FnOnce(TableBuilder) -> TableBuilder,
+    ) -> Self {
+        let key = key.into();
+        let mut child = self.child(build);
+        child.set_implicit(false);
+        match self.table.items.get_mut(key.get()) {
+            Some(Item::ArrayOfTables(array)) => array.push(child),
+            _ => {
+                self.table.items.shift_remove(key.get());
+                let array = ArrayOfTables::from_iter([child]);
+                self.table
+                    .items
+                    .insert(key.clone(), Item::ArrayOfTables(array));
+            }
+        }
+        self.last = Some(key);
+        self
+    }
+
+    /// Add `# text` at the end of the line last added
+    ///
+    /// Before anything is added, this is at the end of the table's header.
+    pub fn comment(mut self, text: &str) -> Self {
+        match self.last_decor(false) {
+            (decor, Place::Root) => add_above(decor, &comment_lines(text), ""),
+            (decor, _) => {
+                let text = text.replace(['\r', '\n'], " ");
+                decor.set_suffix_unchecked(format!(" # {text}"));
+            }
+        }
+        self
+    }
+
+    /// Add `# text` on a line of its own above what was last added
+    ///
+    /// Before anything is added, this is above the table's header.
+    pub fn comment_above(mut self, text: &str) -> Self {
+        let (decor, place) = self.last_decor(true);
+        add_above(decor, &comment_lines(text), place.default_prefix());
+        self
+    }
+
+    /// Add a blank line above what was last added
+    pub fn blank_line_above(mut self) -> Self {
+        let (decor, place) = self.last_decor(true);
+        add_above(decor, "\n", place.default_prefix());
+        self
+    }
+
+    fn child(&mut self, build: impl FnOnce(TableBuilder) -> TableBuilder) -> Table {
+        let child = build(TableBuilder::new(self.next_position));
+        self.next_position = child.next_position;
+        let mut table = child.table;
+        let is_default = table.decor.prefix().is_none() && table.decor.suffix().is_none();
+        if is_default && !table.is_empty() && table.items.values().all(|i| !i.is_value()) {
+            table.set_implicit(true);
+        }
+        table
+    }
+
+    /// Where comments for what was last added go, `above` it or after it
+    fn last_decor(&mut self, above: bool) -> (&mut Decor, Place) {
+        use indexmap::map::MutableKeys;
+
+        let index = self
+            .last
+            .as_ref()
+            .and_then(|key| self.table.items.get_index_of(key.get()));
+        let Some(index) = index else {
+            let place = if self.table.position() == Some(0) {
+                Place::Root
+            } else {
+                Place::Header
+            };
+            return (&mut self.table.decor, place);
+        };
+        let (key, item) = self
+            .table
+            .items
+            .get_index_mut2(index)
+            .expect("index is present");
+        match item {
+            Item::Value(_) if above => (key.leaf_decor_mut(), Place::Line),
+            Item::Value(value) => (value.decor_mut(), Place::Line),
+            Item::Table(table) => (&mut table.decor, Place::Header),
+            Item::ArrayOfTables(array) => {
+                let table = array.values.last_mut().and_then(Item::as_table_mut);
+                (&mut table.expect("never empty").decor, Place::Header)
+            }
+            Item::None => unreachable!("only values and tables are added"),
+        }
+    }
+}
+
+/// What a comment is added to
+enum Place {
+    /// The top of the document
+    Root,
+    Header,
+    /// A key/value pair
+    Line,
+}
+
+impl Place {
+    fn default_prefix(&self) -> &'static str {
+        match self {
+            Self::Root => "",
+            Self::Header => DEFAULT_TABLE_DECOR.0,
+            Self::Line => DEFAULT_KEY_DECOR.0,
+        }
+    }
+}
+
+/// `text` as `#` comments, one per line
+fn comment_lines(text: &str) -> String {
+    let mut comments = String::new();
+    for line in text.lines() {
+        comments.push('#');
+        if !line.is_empty() {
+            comments.push(' ');
+            comments.push_str(line);
+        }
+        comments.push('\n');
+    }
+    if comments.is_empty() {
+        comments.push_str("#\n");
+    }
+    comments
+}
+
+/// Append `lines` to the prefix of `decor`, which starts as `default`
+fn add_above(decor: &mut Decor, lines: &str, default: &str) {
+    let mut prefix = decor
+        .prefix()
+        .and_then(|p| p.as_str())
+        .unwrap_or(default)
+        .to_owned();
+    prefix.push_str(lines);
+    decor.set_prefix_unchecked(prefix);
+}
+
+/// The table with the first header
+fn first_table(root: &mut Table) -> Option<&mut Table> {
+    let position = first_position(root)?;
+    table_at(root, position)
+}
+
+fn first_position(table: &Table) -> Option<usize> {
+    let mut first: Option<usize> = None;
+    for child in child_tables(table) {
+        let position = if child.is_implicit() {
+            first_position(child)
+        } else {
+            child.position()
+        };
+        first = match (first, position) {
+            (Some(first), Some(position)) => Some(first.min(position)),
+            (first, position) => first.or(position),
+        };
+    }
+    first
+}
+
+fn table_at(table: &mut Table, position: usize) -> Option<&mut Table> {
+    for item in table.items.values_mut() {
+        let children: Vec<&mut Table> = match item {
+            Item::Table(child) => vec![child],
+            Item::ArrayOfTables(array) => array.iter_mut().collect(),
+            Item::None | Item::Value(_) => continue,
+        };
+        for child in children {
+            if !child.is_implicit() && child.position() == Some(position) {
+                return Some(child);
+            }
+            if let Some(found) = table_at(child, position) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+fn child_tables(table: &Table) -> impl Iterator<Item = &Table> {
+    table.items.values().flat_map(|item| match item {
+        Item::Table(child) => vec![child],
+        Item::ArrayOfTables(array) => array.iter().collect(),
+        Item::None | Item::Value(_) => vec![],
+    })
+}
@@ -0,0 +1,142 @@
+use crate::Decor;
+
+/// Whether the text of a comment is separated from its `#`, see [`Decor::set_comment_space`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CommentSpace {
+    /// `# comment`
+    Space,
+    /// `#comment`
+    NoSpace,
+}
+
+impl Decor {
+    /// Separate the text of each comment from its `#` according to `space`
+    ///
+    /// With [`CommentSpace::Space`], comments that are already spaced, like indented examples,
+    /// keep their spacing.  Comments with no text, and those starting with `#!` or `##`, like
+    /// banners and commented out comments, are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use toml_edit::{CommentSpace, Decor};
+    ///
+    /// let mut decor = Decor::new("#about\n##### banner\n", " #trailing").unwrap();
+    /// decor.set_comment_space(CommentSpace::Space);
+    /// assert_eq!(decor.prefix().unwrap().as_str(), Some("# about\n##### banner\n"));
+    /// assert_eq!(decor.suffix().unwrap().as_str(), Some(" # trailing"));
+    /// ```
+    pub fn set_comment_space(&mut self, space: CommentSpace) {
+        if let Some(prefix) = self.prefix().and_then(|p| p.as_str()) {
+            let prefix = space_comments(prefix, space);
+            self.set_prefix_unchecked(prefix);
+        }
+        if let Some(suffix) = self.suffix().and_then(|s| s.as_str()) {
+            let suffix = space_comments(suffix, space);
+            self.set_suffix_unchecked(suffix);
+        }
+    }
+
+    /// Split the comments of the prefix that are wider than `width` across lines
+    ///
+    /// Only comments on a line of their own are wrapped, breaking at spaces, with each new line
+    /// indented like the comment.  The prefix is taken to start a line.  Words wider than `width`,
+    /// like a long URL, are left whole.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut decor = toml_edit::Decor::new("  # The name of the package on crates.io\n", "").unwrap();
+    /// decor.wrap_comments(24);
+    /// assert_eq!(
+    ///     decor.prefix().unwrap().as_str(),
+    ///     Some("  # The name of the\n  # package on crates.io\n")
+    /// );
+    /// ```
+    pub fn wrap_comments(&mut self, width: usize) {
+        if let Some(prefix) = self.prefix().and_then(|p| p.as_str()) {
+            let prefix = wrap_comments(prefix, width);
+            self.set_prefix_unchecked(prefix);
+        }
+    }
+}
+
+/// Apply [`CommentSpace`] to each comment in `raw`
+pub(crate) fn space_comments(raw: &str, space: CommentSpace) -> String {
+    let mut output = String::with_capacity(raw.len());
+    for line in raw.split_inclusive('\n') {
+        let Some((before, comment)) = split_comment(line) else {
+            output.push_str(line);
+            continue;
+        };
+        output.push_str(before);
+        output.push('#');
+        let text = comment.trim_start_matches([' ', '\t']);
+        match space {
+            CommentSpace::Space if is_plain(text) && text.len() == comment.len() => {
+                output.push(' ');
+                output.push_str(text);
+            }
+            CommentSpace::NoSpace if is_plain(text) => output.push_str(text),
+            _ => output.push_str(comment),
+        }
+    }
+    output
+}
+
+/// Wrap each comment in `raw` on a line of its own at `width`, taking `raw` to start a line
+pub(crate) fn wrap_comments(raw: &str, width: usize) -> String {
+    let mut output = String::with_capacity(raw.len());
+    for line in raw.split_inclusive('\n') {
+        let comment =
+            split_comment(line).filter(|(indent, _)| indent.chars().all(|c| c == ' ' || c == '\t'));
+        let Some((indent, comment)) = comment else {
+            output.push_str(line);
+            continue;
+        };
+        let (body, newline) = split_newline(comment);
+        let text = body.trim_start_matches([' ', '\t']);
+        let space = &body[..body.len() - text.len()];
+        let text = text.trim_end_matches([' ', '\t']);
+        let leader = format!("{indent}#{space}");
+        if !is_plain(text) || leader.chars().count() + text.chars().count() <= width {
+            output.push_str(line);
+            continue;
+        }
+
+        let mut current = String::new();
+        for word in text.split(' ').filter(|w| !w.is_empty()) {
+            let current_width = leader.chars().count() + current.chars().count();
+            if !current.is_empty() && width < current_width + 1 + word.chars().count() {
+                output.push_str(&leader);
+                output.push_str(&current);
+                output.push_str(if newline.is_empty() { "\n" } else { newline });
+                current.clear();
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        output.push_str(&leader);
+        output.push_str(&current);
+        output.push_str(newline);
+    }
+    output
+}
+
+/// What is before the `#` on `line` and what is after it
+fn split_comment(line: &str) -> Option<(&str, &str)> {
+    let i = line.find('#')?;
+    Some((&line[..i], &line[i + 1..]))
+}
+
+fn split_newline(comment: &str) -> (&str, &str) {
+    let body = comment.trim_end_matches(['\r', '\n']);
+    (body, &comment[body.len()..])
+}
+
+/// Whether the comment is prose, rather than empty, a banner, or a commented out comment
+fn is_plain(text: &str) -> bool {
+    !text.trim_end_matches(['\r', '\n']).is_empty() && !text.starts_with(['#', '!'])
+}
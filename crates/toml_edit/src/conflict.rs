@@ -0,0 +1,386 @@
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{DocumentMut, InlineTable, Item, Key, PathSegment, Table, Value};
+
+/// A table defined by both dotted keys and a table header, like `a.b = 1` and then `[a.b]`
+///
+/// When [parsing lossily][crate::Document::parse_lossy], the dotted keys are merged into the
+/// header's table if they define a table and are dropped otherwise.  See
+/// [`Document::key_conflicts`][crate::Document::key_conflicts] and
+/// [`DocumentMut::resolve_conflict`].
+#[derive(Debug, Clone)]
+pub struct KeyConflict {
+    pub(crate) path: Vec<String>,
+    pub(crate) dotted_span: std::ops::Range<usize>,
+    pub(crate) header_span: std::ops::Range<usize>,
+    pub(crate) dotted_key: Key,
+    pub(crate) dotted: Item,
+}
+
+impl KeyConflict {
+    /// The path to the table, like `["a", "b"]`
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// The location of the dotted key within the original document
+    pub fn dotted_span(&self) -> std::ops::Range<usize> {
+        self.dotted_span.clone()
+    }
+
+    /// The location of the key in the table header within the original document
+    pub fn header_span(&self) -> std::ops::Range<usize> {
+        self.header_span.clone()
+    }
+
+    /// Whether the dotted keys define a table, allowing them to be merged into the header
+    pub fn is_mergeable(&self) -> bool {
+        self.dotted.is_table()
+    }
+
+    #[cfg(feature = "parse")]
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
+        self.dotted_key.despan(input, spans);
+        self.dotted.despan(input, spans);
+    }
+}
+
+impl Display for KeyConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let path = self.path.join(".");
+        write!(
+            f,
+            "`{path}` is defined by both dotted keys and a `[{path}]` table header"
+        )?;
+        if self.is_mergeable() {
+            write!(
+                f,
+                "; move the dotted keys under the header or rename one of them"
+            )
+        } else {
+            write!(f, "; rename one of them")
+        }
+    }
+}
+
+impl StdError for KeyConflict {}
+
+/// How to fix a [`KeyConflict`], see [`DocumentMut::resolve_conflict`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConflictStrategy<'a> {
+    /// Move the dotted keys under the table header
+    ///
+    /// Entries of the dotted keys' table that the header's table is missing are added to it,
+    /// combining tables they both have recursively, while the header's other entries are kept.
+    /// Only applies when the dotted keys define a table, see [`KeyConflict::is_mergeable`].
+    Merge,
+    /// Keep the dotted keys, renaming them to this sibling key
+    Rename(&'a str),
+}
+
+/// How to handle a key in both tables, see [`Table::append`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConflictPolicy {
+    /// Keep the existing entry, dropping the other
+    Keep,
+    /// Replace the existing entry with the other, in the position of the existing one
+    Replace,
+    /// Append tables and inline tables to each other recursively and arrays of tables to each
+    /// other, otherwise [replacing][ConflictPolicy::Replace] the entry
+    Merge,
+}
+
+/// A key given a value more than once within the same table, like `a = 1` and then `a = 2`
+///
+/// When [parsing lossily][crate::Document::parse_lossy], the first value is kept in the
+/// document and the others are dropped.  See
+/// [`Document::duplicate_keys`][crate::Document::duplicate_keys] and
+/// [`DocumentMut::resolve_duplicate`].
+#[derive(Debug, Clone)]
+pub struct DuplicateKey {
+    pub(crate) path: Vec<PathSegment>,
+    pub(crate) spans: Vec<std::ops::Range<usize>>,
+    pub(crate) values: Vec<Value>,
+}
+
+impl DuplicateKey {
+    /// The path to the key, like `[Key("a"), Key("b")]`
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The location of each occurrence of the key within the original document, in order
+    pub fn spans(&self) -> &[std::ops::Range<usize>] {
+        &self.spans
+    }
+
+    /// The value of each occurrence of the key, in order
+    ///
+    /// The first is the one kept in the document.
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Whether all of the values are arrays or all are inline tables, allowing them to be merged
+    pub fn is_mergeable(&self) -> bool {
+        self.values.iter().all(Value::is_array) || self.values.iter().all(Value::is_inline_table)
+    }
+
+    #[cfg(feature = "parse")]
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
+        for value in &mut self.values {
+            value.despan(input, spans);
+        }
+    }
+}
+
+impl Display for DuplicateKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        "`".fmt(f)?;
+        for (i, segment) in self.path.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i != 0 => write!(f, ".{key}")?,
+                PathSegment::Key(key) => key.fmt(f)?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        write!(f, "` is defined {} times", self.values.len())?;
+        if self.is_mergeable() {
+            write!(f, "; keep one of them or merge them")
+        } else {
+            write!(f, "; keep one of them")
+        }
+    }
+}
+
+impl StdError for DuplicateKey {}
+
+/// How to fix a [`DuplicateKey`], see [`DocumentMut::resolve_duplicate`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DuplicateStrategy {
+    /// Keep the value in the document, which [lossy parsing][crate::Document::parse_lossy]
+    /// takes from the first occurrence
+    KeepFirst,
+    /// Keep the last value, in the position of the first
+    KeepLast,
+    /// Combine the other values into the one in the document, appending array elements or
+    /// inserting inline table entries
+    ///
+    /// For inline tables, entries that are inline tables in both are combined recursively and
+    /// otherwise later entries replace earlier ones with the same key.  Only applies when the
+    /// values are all arrays or all inline tables, see [`DuplicateKey::is_mergeable`].
+    Merge,
+}
+
+impl DocumentMut {
+    /// Fix a [`KeyConflict`] from [lossy parsing][crate::Document::parse_lossy]
+    ///
+    /// Returns `false` if the strategy does not apply, the rename is taken, or the table is no
+    /// longer in the document.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{ConflictStrategy, Document};
+    ///
+    /// let input = "a.b = 1\n[a.b]\nc = 2\n";
+    /// let (doc, errors) = Document::parse_lossy(input);
+    /// assert_eq!(errors.len(), 1);
+    /// let conflict = doc.key_conflicts()[0].clone();
+    /// assert_eq!(conflict.to_string(), "`a.b` is defined by both dotted keys and a `[a.b]` table header; rename one of them");
+    ///
+    /// let mut doc = doc.into_mut();
+    /// assert!(doc.resolve_conflict(&conflict, ConflictStrategy::Rename("b-version")));
+    /// assert_eq!(doc.to_string(), "a.b-version = 1\n[a.b]\nc = 2\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn resolve_conflict(
+        &mut self,
+        conflict: &KeyConflict,
+        strategy: ConflictStrategy<'_>,
+    ) -> bool {
+        let Some((key, parents)) = conflict.path.split_last() else {
+            return false;
+        };
+        let mut parent = self.as_table_mut();
+        for parent_key in parents {
+            parent = match parent.get_mut(parent_key) {
+                Some(Item::Table(table)) => table,
+                Some(Item::ArrayOfTables(array)) => match array.iter_mut().last() {
+                    Some(table) => table,
+                    None => return false,
+                },
+                _ => return false,
+            };
+        }
+        if !matches!(parent.get(key), Some(Item::Table(table)) if !table.is_dotted()) {
+            return false;
+        }
+
+        match strategy {
+            ConflictStrategy::Merge => {
+                let Item::Table(dotted) = &conflict.dotted else {
+                    return false;
+                };
+                let header = parent
+                    .get_mut(key)
+                    .and_then(Item::as_table_mut)
+                    .expect("checked earlier");
+                merge_dotted(header, dotted);
+                true
+            }
+            ConflictStrategy::Rename(rename) => {
+                if parent.contains_key(rename) {
+                    return false;
+                }
+                if let Item::Table(dotted) = &conflict.dotted {
+                    let header = parent
+                        .get_mut(key)
+                        .and_then(Item::as_table_mut)
+                        .expect("checked earlier");
+                    remove_dotted(header, dotted);
+                }
+                let renamed = Key::new(rename)
+                    .with_leaf_decor_unchecked(conflict.dotted_key.leaf_decor().clone())
+                    .with_dotted_decor_unchecked(conflict.dotted_key.dotted_decor().clone())
+                    .with_id(conflict.dotted_key.id());
+                parent.insert_formatted(&renamed, conflict.dotted.clone());
+                true
+            }
+        }
+    }
+    /// Fix a [`DuplicateKey`] from [lossy parsing][crate::Document::parse_lossy]
+    ///
+    /// The value in the document keeps its formatting around it.  Returns `false` if the
+    /// strategy does not apply or the key is no longer in the document.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{Document, DuplicateStrategy};
+    ///
+    /// let input = "a = [1]  # first\nb = 2\na = [3]\n";
+    /// let (doc, errors) = Document::parse_lossy(input);
+    /// assert_eq!(errors.len(), 1);
+    /// let duplicate = doc.duplicate_keys()[0].clone();
+    /// assert_eq!(duplicate.to_string(), "`a` is defined 2 times; keep one of them or merge them");
+    /// assert_eq!(duplicate.spans(), [0..1, 23..24]);
+    ///
+    /// let mut doc = doc.into_mut();
+    /// assert_eq!(doc.to_string(), "a = [1]  # first\nb = 2\n");
+    /// assert!(doc.resolve_duplicate(&duplicate, DuplicateStrategy::Merge));
+    /// assert_eq!(doc.to_string(), "a = [1, 3]  # first\nb = 2\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn resolve_duplicate(
+        &mut self,
+        duplicate: &DuplicateKey,
+        strategy: DuplicateStrategy,
+    ) -> bool {
+        let mut item = &mut self.root;
+        for segment in &duplicate.path {
+            let next = match segment {
+                PathSegment::Key(key) => item.get_mut(key.as_str()),
+                PathSegment::Index(index) => item.get_mut(*index),
+            };
+            let Some(next) = next else {
+                return false;
+            };
+            item = next;
+        }
+        let Some(value) = item.as_value_mut() else {
+            return false;
+        };
+
+        match strategy {
+            DuplicateStrategy::KeepFirst => true,
+            DuplicateStrategy::KeepLast => {
+                let last = duplicate.values.last().expect("always at least two");
+                let decor = value.decor().clone();
+                *value = last.clone();
+                *value.decor_mut() = decor;
+                true
+            }
+            DuplicateStrategy::Merge => {
+                if !duplicate.is_mergeable() {
+                    return false;
+                }
+                let values = duplicate.values.iter().skip(1);
+                match value {
+                    Value::Array(array) => {
+                        for other in values.filter_map(Value::as_array) {
+                            for element in other.iter() {
+                                let mut element = element.clone();
+                                element.decor_mut().clear();
+                                crate::array::decorate_inserted(array, array.len(), &mut element);
+                                array.push_formatted(element);
+                            }
+                        }
+                    }
+                    Value::InlineTable(table) => {
+                        for other in values.filter_map(Value::as_inline_table) {
+                            merge_inline(table, other);
+                        }
+                    }
+                    _ => return false,
+                }
+                true
+            }
+        }
+    }
+}
+
+/// Add the entries of the dotted keys' table that are missing from the header's table
+fn merge_dotted(header: &mut Table, dotted: &Table) {
+    for (key, item) in dotted.iter() {
+        match (header.get_mut(key), item) {
+            (Some(Item::Table(header_child)), Item::Table(dotted_child)) => {
+                merge_dotted(header_child, dotted_child);
+            }
+            (Some(_), _) => {}
+            (None, _) => {
+                let key = dotted.key(key).expect("iterating over its keys");
+                header.insert_formatted(key, item.clone());
+            }
+        }
+    }
+}
+
+/// Insert the entries of `other` into `table`, combining inline tables in both
+fn merge_inline(table: &mut InlineTable, other: &InlineTable) {
+    for (key, value) in other.iter() {
+        match (table.get_mut(key), value) {
+            (Some(Value::InlineTable(child)), Value::InlineTable(other_child)) => {
+                merge_inline(child, other_child);
+            }
+            _ => {
+                table.insert(key, value.clone());
+            }
+        }
+    }
+}
+
+/// Remove the entries the dotted keys merged into the header's table
+fn remove_dotted(header: &mut Table, dotted: &Table) {
+    for (key, item) in dotted.iter() {
+        if let (Some(Item::Table(header_child)), Item::Table(dotted_child)) =
+            (header.get_mut(key), item)
+        {
+            remove_dotted(header_child, dotted_child);
+            if !header_child.is_empty() {
+                continue;
+            }
+        }
+        header.remove(key);
+    }
+}
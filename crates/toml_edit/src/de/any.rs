@@ -0,0 +1,99 @@
+use serde::de::{Deserialize, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+
+use crate::{Array, DocumentMut, InlineTable, Item, Value};
+
+/// Deserialize any TOML value from any format
+pub(crate) struct AnyValue(pub(crate) Value);
+
+impl<'de> Deserialize<'de> for AnyValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = AnyValue;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("any valid TOML value")
+            }
+
+            fn visit_bool<E>(self, value: bool) -> Result<AnyValue, E> {
+                Ok(AnyValue(value.into()))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<AnyValue, E> {
+                Ok(AnyValue(value.into()))
+            }
+
+            fn visit_u64<E: Error>(self, value: u64) -> Result<AnyValue, E> {
+                i64::try_from(value)
+                    .map(|v| AnyValue(v.into()))
+                    .map_err(|_| E::custom("u64 value was too large"))
+            }
+
+            fn visit_f64<E>(self, value: f64) -> Result<AnyValue, E> {
+                Ok(AnyValue(value.into()))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<AnyValue, E> {
+                Ok(AnyValue(value.into()))
+            }
+
+            fn visit_some<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<AnyValue, D::Error> {
+                AnyValue::deserialize(deserializer)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<AnyValue, A::Error> {
+                let mut array = Array::new();
+                while let Some(AnyValue(value)) = seq.next_element()? {
+                    array.push(value);
+                }
+                Ok(AnyValue(Value::Array(array)))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<AnyValue, A::Error> {
+                let mut table = InlineTable::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == toml_datetime::__unstable::FIELD {
+                        let datetime = map.next_value::<String>()?;
+                        let datetime = datetime
+                            .parse::<crate::Datetime>()
+                            .map_err(A::Error::custom)?;
+                        return Ok(AnyValue(datetime.into()));
+                    }
+                    if table.contains_key(&key) {
+                        return Err(A::Error::custom(format!("duplicate key: `{key}`")));
+                    }
+                    let AnyValue(value) = map.next_value()?;
+                    table.insert(key, value);
+                }
+                Ok(AnyValue(Value::InlineTable(table)))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// Deserialize a document from the data of any format, like from [`DocumentMut`]'s `Serialize`
+///
+/// Nested tables are deserialized as inline tables.
+impl<'de> Deserialize<'de> for DocumentMut {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let AnyValue(value) = AnyValue::deserialize(deserializer)?;
+        match value {
+            Value::InlineTable(table) => {
+                let root = Item::Value(Value::InlineTable(table))
+                    .into_table()
+                    .expect("inline tables are tables");
+                Ok(root.into())
+            }
+            value => Err(D::Error::invalid_type(
+                serde::de::Unexpected::Other(value.type_name()),
+                &"a table",
+            )),
+        }
+    }
+}
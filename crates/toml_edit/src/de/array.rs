@@ -41,10 +41,17 @@ impl<'de> serde::Deserializer<'de> for ArrayDeserializer {
         self.deserialize_any(visitor)
     }
 
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
         bytes byte_buf map option unit newtype_struct
-        ignored_any unit_struct tuple_struct tuple enum identifier
+        unit_struct tuple_struct tuple enum identifier
     }
 }
 
@@ -57,25 +64,25 @@ impl serde::de::IntoDeserializer<'_, Error> for ArrayDeserializer {
 }
 
 impl crate::Array {
-    pub(crate) fn into_deserializer(self) -> ArrayDeserializer {
-        ArrayDeserializer::new(self.values, self.span)
+    pub(crate) fn into_deserializer(mut self) -> ArrayDeserializer {
+        ArrayDeserializer::new(std::mem::take(&mut self.values), self.span.take())
     }
 }
 
 impl crate::ArrayOfTables {
-    pub(crate) fn into_deserializer(self) -> ArrayDeserializer {
-        ArrayDeserializer::new(self.values, self.span)
+    pub(crate) fn into_deserializer(mut self) -> ArrayDeserializer {
+        ArrayDeserializer::new(std::mem::take(&mut self.values), self.span.take())
     }
 }
 
 pub(crate) struct ArraySeqAccess {
-    iter: std::vec::IntoIter<crate::Item>,
+    iter: std::iter::Enumerate<std::vec::IntoIter<crate::Item>>,
 }
 
 impl ArraySeqAccess {
     pub(crate) fn new(input: Vec<crate::Item>) -> Self {
         Self {
-            iter: input.into_iter(),
+            iter: input.into_iter().enumerate(),
         }
     }
 }
@@ -88,9 +95,15 @@ impl<'de> serde::de::SeqAccess<'de> for ArraySeqAccess {
         T: serde::de::DeserializeSeed<'de>,
     {
         match self.iter.next() {
-            Some(v) => seed
-                .deserialize(crate::de::ValueDeserializer::new(v))
-                .map(Some),
+            Some((i, v)) => {
+                let span = v.span();
+                seed.deserialize(crate::de::ValueDeserializer::new(v))
+                    .map(Some)
+                    .map_err(|mut e: Self::Error| {
+                        e.add_frame(crate::de::PathSegment::Index(i), span);
+                        e
+                    })
+            }
             None => Ok(None),
         }
     }
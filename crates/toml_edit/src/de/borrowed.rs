@@ -0,0 +1,457 @@
+use serde::de::IntoDeserializer as _;
+
+use crate::de::DatetimeDeserializer;
+use crate::de::Error;
+
+/// Deserializes an [`Item`][crate::Item] in place, mirroring
+/// [`ValueDeserializer`][super::ValueDeserializer] without taking ownership
+pub(crate) struct ItemRefDeserializer<'i> {
+    input: &'i crate::Item,
+    validate_struct_keys: bool,
+}
+
+impl<'i> ItemRefDeserializer<'i> {
+    pub(crate) fn new(input: &'i crate::Item) -> Self {
+        Self {
+            input,
+            validate_struct_keys: false,
+        }
+    }
+
+    fn with_struct_key_validation(mut self) -> Self {
+        self.validate_struct_keys = true;
+        self
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for ItemRefDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let span = self.input.span();
+        let found = self.input.type_name();
+        match self.input {
+            crate::Item::None => visitor.visit_none(),
+            crate::Item::Value(crate::Value::String(v)) => visitor.visit_str(v.value()),
+            crate::Item::Value(crate::Value::Integer(v)) => visitor.visit_i64(*v.value()),
+            crate::Item::Value(crate::Value::Float(v)) => visitor.visit_f64(*v.value()),
+            crate::Item::Value(crate::Value::Boolean(v)) => visitor.visit_bool(*v.value()),
+            crate::Item::Value(crate::Value::Datetime(v)) => {
+                visitor.visit_map(DatetimeDeserializer::new(*v.value()))
+            }
+            crate::Item::Value(crate::Value::Array(v)) => {
+                visitor.visit_seq(ArrayRefSeqAccess::new(v.values.iter()))
+            }
+            crate::Item::Value(crate::Value::InlineTable(v)) => {
+                visitor.visit_map(TableRefMapAccess::new(&v.items, v.span()))
+            }
+            crate::Item::Table(v) => visitor.visit_map(TableRefMapAccess::new(&v.items, v.span())),
+            crate::Item::ArrayOfTables(v) => {
+                visitor.visit_seq(ArrayRefSeqAccess::new(v.values.iter()))
+            }
+        }
+        .map_err(|mut e: Self::Error| {
+            if e.span().is_none() {
+                e.set_span(span);
+            }
+            e.add_found(found);
+            e
+        })
+    }
+
+    // `None` is interpreted as a missing field so be sure to implement `Some`
+    // as a present field.
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let span = self.input.span();
+        visitor.visit_some(self).map_err(|mut e: Self::Error| {
+            if e.span().is_none() {
+                e.set_span(span);
+            }
+            e
+        })
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let span = self.input.span();
+        visitor
+            .visit_newtype_struct(self)
+            .map_err(|mut e: Self::Error| {
+                if e.span().is_none() {
+                    e.set_span(span);
+                }
+                e
+            })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        if serde_spanned::__unstable::is_spanned(name, fields) {
+            if let Some(span) = self.input.span() {
+                return visitor.visit_map(super::SpannedDeserializer::new(self, span));
+            }
+        }
+
+        if name == toml_datetime::__unstable::NAME && fields == [toml_datetime::__unstable::FIELD] {
+            let span = self.input.span();
+            if let crate::Item::Value(crate::Value::Datetime(d)) = self.input {
+                return visitor
+                    .visit_map(DatetimeDeserializer::new(*d.value()))
+                    .map_err(|mut e: Self::Error| {
+                        if e.span().is_none() {
+                            e.set_span(span);
+                        }
+                        e
+                    });
+            }
+        }
+
+        if self.validate_struct_keys {
+            let span = self.input.span();
+            match self.input {
+                crate::Item::Table(values) => super::validate_struct_keys(&values.items, fields),
+                crate::Item::Value(crate::Value::InlineTable(values)) => {
+                    super::validate_struct_keys(&values.items, fields)
+                }
+                _ => Ok(()),
+            }
+            .map_err(|mut e: Self::Error| {
+                if e.span().is_none() {
+                    e.set_span(span);
+                }
+                e
+            })?;
+        }
+
+        self.deserialize_any(visitor)
+    }
+
+    // Called when the type to deserialize is an enum, as opposed to a field in the type.
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let span = self.input.span();
+        let (items, table_span) = match self.input {
+            crate::Item::Value(crate::Value::String(v)) => {
+                return visitor
+                    .visit_enum(v.value().as_str().into_deserializer())
+                    .map_err(|mut e: Self::Error| {
+                        if e.span().is_none() {
+                            e.set_span(span);
+                        }
+                        e
+                    });
+            }
+            crate::Item::Value(crate::Value::InlineTable(v)) => (&v.items, v.span()),
+            crate::Item::Table(v) => (&v.items, v.span()),
+            e => return Err(Error::custom("wanted string or table", e.span())),
+        };
+        if items.is_empty() {
+            Err(Error::custom(
+                "wanted exactly 1 element, found 0 elements",
+                table_span,
+            ))
+        } else if items.len() != 1 {
+            Err(Error::custom(
+                "wanted exactly 1 element, more than 1 element",
+                table_span,
+            ))
+        } else {
+            visitor.visit_enum(TableRefMapAccess::new(items, table_span))
+        }
+        .map_err(|mut e: Self::Error| {
+            if e.span().is_none() {
+                e.set_span(span);
+            }
+            e
+        })
+    }
+
+    // Skip unknown fields and the like without visiting everything in them
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
+        bytes byte_buf map unit
+        unit_struct tuple_struct tuple identifier
+    }
+}
+
+impl serde::de::IntoDeserializer<'_, Error> for ItemRefDeserializer<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+struct ArrayRefSeqAccess<I> {
+    iter: std::iter::Enumerate<I>,
+}
+
+impl<'i, I> ArrayRefSeqAccess<I>
+where
+    I: Iterator<Item = &'i crate::Item>,
+{
+    fn new(input: I) -> Self {
+        Self {
+            iter: input.enumerate(),
+        }
+    }
+}
+
+impl<'de, 'i, I> serde::de::SeqAccess<'de> for ArrayRefSeqAccess<I>
+where
+    I: Iterator<Item = &'i crate::Item>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((i, v)) => {
+                let span = v.span();
+                seed.deserialize(ItemRefDeserializer::new(v))
+                    .map(Some)
+                    .map_err(|mut e: Self::Error| {
+                        e.add_frame(crate::de::PathSegment::Index(i), span);
+                        e
+                    })
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+struct TableRefMapAccess<'i> {
+    iter: indexmap::map::Iter<'i, crate::Key, crate::Item>,
+    span: Option<std::ops::Range<usize>>,
+    value: Option<(&'i crate::Key, &'i crate::Item)>,
+}
+
+impl<'i> TableRefMapAccess<'i> {
+    fn new(items: &'i crate::table::KeyValuePairs, span: Option<std::ops::Range<usize>>) -> Self {
+        Self {
+            iter: items.iter(),
+            span,
+            value: None,
+        }
+    }
+}
+
+impl<'de> serde::de::MapAccess<'de> for TableRefMapAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: serde::de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((k, v)) => {
+                let key_span = k.span();
+                let ret = seed
+                    .deserialize(super::KeyDeserializer::new(k.clone(), key_span.clone()))
+                    .map(Some)
+                    .map_err(|mut e: Self::Error| {
+                        if e.span().is_none() {
+                            e.set_span(key_span);
+                        }
+                        e
+                    });
+                self.value = Some((k, v));
+                ret
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some((k, v)) => {
+                let span = v.span().or_else(|| k.span());
+                seed.deserialize(ItemRefDeserializer::new(v))
+                    .map_err(|mut e: Self::Error| {
+                        if e.span().is_none() {
+                            e.set_span(span.clone());
+                        }
+                        e.add_frame(crate::de::PathSegment::Key(k.get().to_owned()), span);
+                        e
+                    })
+            }
+            None => {
+                panic!("no more values in next_value_seed, internal error in ItemRefDeserializer")
+            }
+        }
+    }
+}
+
+impl<'de, 'i> serde::de::EnumAccess<'de> for TableRefMapAccess<'i> {
+    type Error = Error;
+    type Variant = TableRefEnumDeserializer<'i>;
+
+    fn variant_seed<V>(mut self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let Some((key, value)) = self.iter.next() else {
+            return Err(Error::custom(
+                "expected table with exactly 1 entry, found empty table",
+                self.span,
+            ));
+        };
+
+        let val =
+            seed.deserialize(key.get().into_deserializer())
+                .map_err(|mut e: Self::Error| {
+                    if e.span().is_none() {
+                        e.set_span(key.span());
+                    }
+                    e
+                })?;
+
+        Ok((val, TableRefEnumDeserializer { value }))
+    }
+}
+
+/// Deserializes table values into enum variants, see
+/// [`TableEnumDeserializer`][super::TableEnumDeserializer]
+struct TableRefEnumDeserializer<'i> {
+    value: &'i crate::Item,
+}
+
+impl<'de> serde::de::VariantAccess<'de> for TableRefEnumDeserializer<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        let (is_empty, span, expected) = match self.value {
+            crate::Item::ArrayOfTables(values) => {
+                (values.is_empty(), values.span(), "expected empty array")
+            }
+            crate::Item::Value(crate::Value::Array(values)) => {
+                (values.is_empty(), values.span(), "expected empty table")
+            }
+            crate::Item::Table(values) => {
+                (values.is_empty(), values.span(), "expected empty table")
+            }
+            crate::Item::Value(crate::Value::InlineTable(values)) => {
+                (values.is_empty(), values.span(), "expected empty table")
+            }
+            e => {
+                return Err(Error::custom(
+                    format!("expected table, found {}", e.type_name()),
+                    e.span(),
+                ))
+            }
+        };
+        if is_empty {
+            Ok(())
+        } else {
+            Err(Error::custom(expected, span))
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(ItemRefDeserializer::new(self.value))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let (tuple_values, values_span) = match self.value {
+            crate::Item::ArrayOfTables(values) => {
+                (values.values.iter().collect::<Vec<_>>(), values.span())
+            }
+            crate::Item::Value(crate::Value::Array(values)) => {
+                (values.values.iter().collect::<Vec<_>>(), values.span())
+            }
+            crate::Item::Table(values) => (tuple_values(&values.items)?, values.span()),
+            crate::Item::Value(crate::Value::InlineTable(values)) => {
+                (tuple_values(&values.items)?, values.span())
+            }
+            e => {
+                return Err(Error::custom(
+                    format!("expected table, found {}", e.type_name()),
+                    e.span(),
+                ))
+            }
+        };
+
+        if tuple_values.len() == len {
+            visitor.visit_seq(ArrayRefSeqAccess::new(tuple_values.into_iter()))
+        } else {
+            Err(Error::custom(
+                format!("expected tuple with length {len}"),
+                values_span,
+            ))
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        serde::de::Deserializer::deserialize_struct(
+            ItemRefDeserializer::new(self.value).with_struct_key_validation(),
+            "", // TODO: this should be the variant name
+            fields,
+            visitor,
+        )
+    }
+}
+
+fn tuple_values(items: &crate::table::KeyValuePairs) -> Result<Vec<&crate::Item>, Error> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, (key, value))| match key.get().parse::<usize>() {
+            Ok(key_index) if key_index == index => Ok(value),
+            Ok(_) | Err(_) => Err(Error::custom(
+                format!("expected table key `{}`, but was `{}`", index, key.get()),
+                key.span(),
+            )),
+        })
+        .collect()
+}
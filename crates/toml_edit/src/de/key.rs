@@ -21,6 +21,23 @@ impl IntoDeserializer<'_, Error> for KeyDeserializer {
     }
 }
 
+/// Parse keys for map key types that aren't strings, like the integers of `HashMap<u32, T>`
+///
+/// A key that doesn't parse is reported like any other mismatched type.
+macro_rules! parse_key {
+    ($($deserialize:ident => $visit:ident: $ty:ty,)*) => {$(
+        fn $deserialize<V>(self, visitor: V) -> Result<V::Value, Error>
+        where
+            V: serde::de::Visitor<'de>,
+        {
+            match self.key.get().parse::<$ty>() {
+                Ok(key) => visitor.$visit(key),
+                Err(_) => self.deserialize_any(visitor),
+            }
+        }
+    )*};
+}
+
 impl<'de> serde::de::Deserializer<'de> for KeyDeserializer {
     type Error = Error;
 
@@ -28,7 +45,13 @@ impl<'de> serde::de::Deserializer<'de> for KeyDeserializer {
     where
         V: serde::de::Visitor<'de>,
     {
-        self.key.into_deserializer().deserialize_any(visitor)
+        self.key
+            .into_deserializer()
+            .deserialize_any(visitor)
+            .map_err(|mut e: Error| {
+                e.add_found("string");
+                e
+            })
     }
 
     fn deserialize_enum<V>(
@@ -59,6 +82,11 @@ impl<'de> serde::de::Deserializer<'de> for KeyDeserializer {
                 return visitor.visit_map(super::SpannedDeserializer::new(self.key.get(), span));
             }
         }
+        if name == toml_datetime::__unstable::NAME && fields == [toml_datetime::__unstable::FIELD] {
+            if let Ok(date) = self.key.get().parse::<crate::Datetime>() {
+                return visitor.visit_map(super::DatetimeDeserializer::new(date));
+            }
+        }
         self.deserialize_any(visitor)
     }
 
@@ -73,8 +101,21 @@ impl<'de> serde::de::Deserializer<'de> for KeyDeserializer {
         visitor.visit_newtype_struct(self)
     }
 
+    parse_key! {
+        deserialize_bool => visit_bool: bool,
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_char => visit_char: char,
+    }
+
     serde::forward_to_deserialize_any! {
-        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
+        f32 f64 str string seq
         bytes byte_buf map option unit
         ignored_any unit_struct tuple_struct tuple identifier
     }
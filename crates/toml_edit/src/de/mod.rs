@@ -4,7 +4,10 @@
 
 use serde::de::DeserializeOwned;
 
+mod any;
 mod array;
+#[cfg(feature = "parse")]
+mod borrowed;
 mod datetime;
 mod key;
 mod spanned;
@@ -12,7 +15,10 @@ mod table;
 mod table_enum;
 mod value;
 
+pub(crate) use any::AnyValue;
 use array::ArrayDeserializer;
+#[cfg(feature = "parse")]
+use borrowed::ItemRefDeserializer;
 use datetime::DatetimeDeserializer;
 use key::KeyDeserializer;
 use spanned::SpannedDeserializer;
@@ -20,10 +26,25 @@ use table_enum::TableEnumDeserializer;
 
 pub use value::ValueDeserializer;
 
+pub use crate::PathSegment;
+
 /// Errors that can occur when deserializing a type.
 #[derive(Clone, PartialEq, Eq)]
 pub struct Error {
     inner: crate::TomlError,
+    path: Vec<PathSegment>,
+    frames: Vec<Frame>,
+    mismatch: Option<Mismatch>,
+}
+
+/// The value was the wrong type or out of range for what was being deserialized
+#[derive(Clone, PartialEq, Eq)]
+struct Mismatch {
+    kind: &'static str,
+    expected: String,
+    found: Option<&'static str>,
+    /// Whether serde's description of the value is as specific as the TOML type
+    descriptive: bool,
 }
 
 impl Error {
@@ -33,12 +54,126 @@ impl Error {
     {
         Error {
             inner: crate::TomlError::custom(msg.to_string(), span),
+            path: Vec::new(),
+            frames: Vec::new(),
+            mismatch: None,
         }
     }
 
+    fn mismatch(
+        kind: &'static str,
+        unexpected: serde::de::Unexpected<'_>,
+        expected: &dyn serde::de::Expected,
+    ) -> Self {
+        let descriptive = !matches!(
+            unexpected,
+            serde::de::Unexpected::Map | serde::de::Unexpected::Seq
+        );
+        let expected = expected.to_string();
+        let mut error = Error::custom(format!("{kind}: {unexpected}, expected {expected}"), None);
+        error.mismatch = Some(Mismatch {
+            kind,
+            expected,
+            found: None,
+            descriptive,
+        });
+        error
+    }
+
     /// Add key while unwinding
     pub fn add_key(&mut self, key: String) {
-        self.inner.add_key(key);
+        self.add_frame(PathSegment::Key(key), None);
+    }
+
+    /// Add array index while unwinding
+    pub fn add_index(&mut self, index: usize) {
+        self.add_frame(PathSegment::Index(index), None);
+    }
+
+    pub(crate) fn add_frame(&mut self, segment: PathSegment, span: Option<std::ops::Range<usize>>) {
+        match &segment {
+            PathSegment::Key(key) => self.inner.add_key(key.clone()),
+            PathSegment::Index(index) => self.inner.add_index(*index),
+        }
+        self.path.insert(0, segment.clone());
+        self.frames.insert(0, Frame { segment, span });
+    }
+
+    /// Add the TOML type of the value while unwinding, like `"string"`
+    ///
+    /// Only the first call for a type or value mismatch has an effect.
+    pub fn add_found(&mut self, found: &'static str) {
+        let Some(mismatch) = &mut self.mismatch else {
+            return;
+        };
+        if mismatch.found.is_some() {
+            return;
+        }
+        mismatch.found = Some(found);
+        if !mismatch.descriptive {
+            // serde only knows this is a map or sequence
+            let message = format!("{}: {found}, expected {}", mismatch.kind, mismatch.expected);
+            self.inner.set_message(message);
+        }
+    }
+
+    /// The keys and array indices leading to the value where the error occurred
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use toml_edit::de::PathSegment;
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Config {
+    ///     #[allow(dead_code)]
+    ///     dates: Vec<toml_edit::Datetime>,
+    /// }
+    ///
+    /// let err = toml_edit::de::from_str::<Config>("dates = [1979-05-27, 5]").unwrap_err();
+    /// assert_eq!(
+    ///     err.path(),
+    ///     [PathSegment::Key("dates".to_owned()), PathSegment::Index(1)]
+    /// );
+    /// ```
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// Each table, array, and field the deserializer was within when the error occurred,
+    /// outermost first
+    ///
+    /// Unlike [`Error::path`], each frame includes the location of its value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Config {
+    ///     #[allow(dead_code)]
+    ///     servers: Vec<Server>,
+    /// }
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Server {
+    ///     #[allow(dead_code)]
+    ///     port: u16,
+    /// }
+    ///
+    /// let input = "servers = [{ port = 80 }, { port = 'http' }]";
+    /// let err = toml_edit::de::from_str::<Config>(input).unwrap_err();
+    /// let frames = err
+    ///     .frames()
+    ///     .iter()
+    ///     .map(|frame| &input[frame.span().unwrap()])
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     frames,
+    ///     ["[{ port = 80 }, { port = 'http' }]", "{ port = 'http' }", "'http'"]
+    /// );
+    /// ```
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
     }
 
     /// What went wrong
@@ -46,6 +181,34 @@ impl Error {
         self.inner.message()
     }
 
+    /// What the value was being deserialized into, for a type or value mismatch
+    ///
+    /// This is serde's description of the Rust type, like `u16` or `a string`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct Server {
+    ///     #[allow(dead_code)]
+    ///     port: u16,
+    /// }
+    ///
+    /// let err = toml_edit::de::from_str::<Server>("port = '8080'").unwrap_err();
+    /// assert_eq!(err.expected(), Some("u16"));
+    /// assert_eq!(err.found(), Some("string"));
+    /// ```
+    pub fn expected(&self) -> Option<&str> {
+        self.mismatch.as_ref().map(|m| m.expected.as_str())
+    }
+
+    /// The TOML type of the value, for a type or value mismatch
+    ///
+    /// See [`Item::type_name`][crate::Item::type_name].
+    pub fn found(&self) -> Option<&str> {
+        self.mismatch.as_ref().and_then(|m| m.found)
+    }
+
     /// The start/end index into the original document where the error occurred
     pub fn span(&self) -> Option<std::ops::Range<usize>> {
         self.inner.span()
@@ -63,6 +226,14 @@ impl serde::de::Error for Error {
     {
         Error::custom(msg, None)
     }
+
+    fn invalid_type(unexp: serde::de::Unexpected<'_>, exp: &dyn serde::de::Expected) -> Self {
+        Error::mismatch("invalid type", unexp, exp)
+    }
+
+    fn invalid_value(unexp: serde::de::Unexpected<'_>, exp: &dyn serde::de::Expected) -> Self {
+        Error::mismatch("invalid value", unexp, exp)
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -79,7 +250,12 @@ impl std::fmt::Debug for Error {
 
 impl From<crate::TomlError> for Error {
     fn from(e: crate::TomlError) -> Error {
-        Self { inner: e }
+        Self {
+            inner: e,
+            path: Vec::new(),
+            frames: Vec::new(),
+            mismatch: None,
+        }
     }
 }
 
@@ -91,6 +267,25 @@ impl From<Error> for crate::TomlError {
 
 impl std::error::Error for Error {}
 
+/// A value the deserializer was within when an error occurred, see [`Error::frames`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Frame {
+    segment: PathSegment,
+    span: Option<std::ops::Range<usize>>,
+}
+
+impl Frame {
+    /// The key or array index of the value within its parent
+    pub fn segment(&self) -> &PathSegment {
+        &self.segment
+    }
+
+    /// The location of the value, if known
+    pub fn span(&self) -> Option<std::ops::Range<usize>> {
+        self.span.clone()
+    }
+}
+
 /// Deserializes a string into a type.
 ///
 /// This function will attempt to interpret `s` as a TOML document and
@@ -157,6 +352,58 @@ where
     T::deserialize(deserializer)
 }
 
+/// Convert the item at a dotted key `path` within a [`Document`][crate::Document] into `T`.
+///
+/// The item at `path` is deserialized in place, without copying it or the rest of the document,
+/// making this cheaper than deserializing the whole document for large configs.  Error spans are
+/// relative to the original input and the error [path][Error::path] starts from the root of the
+/// document.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Http {
+///     port: u16,
+/// }
+///
+/// let doc = toml_edit::Document::parse(r#"
+/// [server.http]
+/// port = 8080
+///
+/// [database]
+/// url = "postgres://localhost"
+/// "#).unwrap();
+///
+/// let http: Http = toml_edit::de::from_document_path(&doc, "server.http").unwrap();
+/// assert_eq!(http.port, 8080);
+/// ```
+#[cfg(feature = "parse")]
+pub fn from_document_path<T>(doc: &crate::Document<impl AsRef<str>>, path: &str) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let keys = crate::Key::parse(path)?;
+    let mut item = &doc.root;
+    for key in &keys {
+        item = item
+            .get(key.get())
+            .ok_or_else(|| Error::custom(format!("missing `{path}`"), None))?;
+    }
+    T::deserialize(ItemRefDeserializer::new(item)).map_err(|mut e| {
+        for key in keys.iter().rev() {
+            e.add_key(key.get().to_owned());
+        }
+        // Only errors with a span render the input
+        if e.span().is_some() {
+            e.inner.set_raw(Some(doc.raw().to_owned()));
+        }
+        e
+    })
+}
+
 /// Deserialization for TOML [documents][crate::DocumentMut].
 pub struct Deserializer<S = String> {
     root: crate::Item,
@@ -291,10 +538,17 @@ impl<'de, S: Into<String>> serde::Deserializer<'de> for Deserializer<S> {
             })
     }
 
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
         bytes byte_buf map unit
-        ignored_any unit_struct tuple_struct tuple identifier
+        unit_struct tuple_struct tuple identifier
     }
 }
 
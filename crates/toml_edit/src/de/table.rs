@@ -82,10 +82,17 @@ impl<'de> serde::Deserializer<'de> for TableDeserializer {
         }
     }
 
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
         bytes byte_buf map unit
-        ignored_any unit_struct tuple_struct tuple identifier
+        unit_struct tuple_struct tuple identifier
     }
 }
 
@@ -98,19 +105,19 @@ impl IntoDeserializer<'_, Error> for TableDeserializer {
 }
 
 impl crate::Table {
-    pub(crate) fn into_deserializer(self) -> TableDeserializer {
+    pub(crate) fn into_deserializer(mut self) -> TableDeserializer {
         TableDeserializer {
             span: self.span(),
-            items: self.items,
+            items: std::mem::take(&mut self.items),
         }
     }
 }
 
 impl crate::InlineTable {
-    pub(crate) fn into_deserializer(self) -> TableDeserializer {
+    pub(crate) fn into_deserializer(mut self) -> TableDeserializer {
         TableDeserializer {
             span: self.span(),
-            items: self.items,
+            items: std::mem::take(&mut self.items),
         }
     }
 }
@@ -167,9 +174,9 @@ impl<'de> serde::de::MapAccess<'de> for TableMapAccess {
                 seed.deserialize(crate::de::ValueDeserializer::new(v))
                     .map_err(|mut e: Self::Error| {
                         if e.span().is_none() {
-                            e.set_span(span);
+                            e.set_span(span.clone());
                         }
-                        e.add_key(k.get().to_owned());
+                        e.add_frame(crate::de::PathSegment::Key(k.get().to_owned()), span);
                         e
                     })
             }
@@ -63,6 +63,7 @@ impl<'de> serde::Deserializer<'de> for ValueDeserializer {
         V: serde::de::Visitor<'de>,
     {
         let span = self.input.span();
+        let found = self.input.type_name();
         match self.input {
             crate::Item::None => visitor.visit_none(),
             crate::Item::Value(crate::Value::String(v)) => visitor.visit_string(v.into_value()),
@@ -85,6 +86,7 @@ impl<'de> serde::Deserializer<'de> for ValueDeserializer {
             if e.span().is_none() {
                 e.set_span(span);
             }
+            e.add_found(found);
             e
         })
     }
@@ -216,10 +218,19 @@ impl<'de> serde::Deserializer<'de> for ValueDeserializer {
         })
     }
 
+    // Skip unknown fields and the like without visiting everything in them.  They are still
+    // parsed, as any later header could add to them.
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
         bytes byte_buf map unit
-        ignored_any unit_struct tuple_struct tuple identifier
+        unit_struct tuple_struct tuple identifier
     }
 }
 
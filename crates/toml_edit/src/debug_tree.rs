@@ -0,0 +1,219 @@
+use std::fmt::{Formatter, Result};
+
+use crate::{Array, Decor, DocumentMut, InlineTable, Item, Key, RawString, Table, Value};
+
+/// The structure of a [`DocumentMut`], one node per line, see [`DocumentMut::debug_tree`]
+///
+/// Unlike the derived [`Debug`] of the document, this layout is part of the API, so it can be
+/// snapshotted in tests.  Each line is a key or array index, the kind of node, its
+/// [span][Item::span] when known, and any decor that isn't the default or empty.  Scalars are shown as
+/// they will be written.
+#[derive(Copy, Clone)]
+pub struct DebugTree<'d> {
+    doc: &'d DocumentMut,
+}
+
+impl DocumentMut {
+    /// The structure of this document for snapshot tests, see [`DebugTree`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = toml_edit::DocumentMut::parse_with_spans("
+    /// [package]
+    /// name = 'app' # required
+    /// keywords = ['toml', ]
+    /// ").unwrap();
+    ///
+    /// assert_eq!(doc.debug_tree().to_string(), r#"document
+    ///   "package" 2..9 = table position=1 1..10 decor=("\n", "")
+    ///     "name" 11..15 key-decor=("", " ") = string 'app' 18..23 decor=(" ", " # required")
+    ///     "keywords" 35..43 key-decor=("", " ") = array 46..56 decor=(" ", "") trailing=" " trailing-comma
+    ///       [0] string 'toml' 47..53
+    /// "#);
+    /// # }
+    /// ```
+    pub fn debug_tree(&self) -> DebugTree<'_> {
+        DebugTree { doc: self }
+    }
+}
+
+impl std::fmt::Display for DebugTree<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("document")?;
+        write_decor(f, "decor", self.doc.decor())?;
+        if self.doc.has_bom() {
+            f.write_str(" bom")?;
+        }
+        writeln!(f)?;
+        write_table_items(f, self.doc.as_table(), 1)?;
+        if !is_empty(self.doc.trailing()) {
+            writeln!(f, "trailing={}", raw_repr(self.doc.trailing()))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for DebugTree<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+fn write_table_items(f: &mut Formatter<'_>, table: &Table, depth: usize) -> Result {
+    for (key, item) in table.items.iter() {
+        write_key(f, key, depth)?;
+        write_item(f, item, depth)?;
+    }
+    Ok(())
+}
+
+fn write_key(f: &mut Formatter<'_>, key: &Key, depth: usize) -> Result {
+    write_indent(f, depth)?;
+    write!(f, "{:?}", key.get())?;
+    write_span(f, key.span())?;
+    write_decor(f, "key-decor", key.leaf_decor())?;
+    write_decor(f, "dotted-decor", key.dotted_decor())?;
+    f.write_str(" = ")
+}
+
+fn write_item(f: &mut Formatter<'_>, item: &Item, depth: usize) -> Result {
+    match item {
+        Item::None => writeln!(f, "none"),
+        Item::Value(value) => write_value(f, value, depth),
+        Item::Table(table) => write_table(f, table, depth),
+        Item::ArrayOfTables(array) => {
+            f.write_str("array-of-tables")?;
+            write_span(f, array.span())?;
+            writeln!(f)?;
+            for (i, table) in array.iter().enumerate() {
+                write_indent(f, depth + 1)?;
+                write!(f, "[{i}] ")?;
+                write_table(f, table, depth + 1)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn write_table(f: &mut Formatter<'_>, table: &Table, depth: usize) -> Result {
+    f.write_str("table")?;
+    if table.is_implicit() {
+        f.write_str(" implicit")?;
+    }
+    if table.is_dotted() {
+        f.write_str(" dotted")?;
+    }
+    if let Some(position) = table.position() {
+        write!(f, " position={position}")?;
+    }
+    write_span(f, table.span())?;
+    write_decor(f, "decor", table.decor())?;
+    writeln!(f)?;
+    write_table_items(f, table, depth + 1)
+}
+
+fn write_value(f: &mut Formatter<'_>, value: &Value, depth: usize) -> Result {
+    match value {
+        Value::Array(array) => write_array(f, array, depth),
+        Value::InlineTable(table) => write_inline_table(f, table, depth),
+        Value::String(v) => write_scalar(f, value, &v.display_repr()),
+        Value::Integer(v) => write_scalar(f, value, &v.display_repr()),
+        Value::Float(v) => write_scalar(f, value, &v.display_repr()),
+        Value::Boolean(v) => write_scalar(f, value, &v.display_repr()),
+        Value::Datetime(v) => write_scalar(f, value, &v.display_repr()),
+    }
+}
+
+fn write_scalar(f: &mut Formatter<'_>, value: &Value, repr: &str) -> Result {
+    write!(f, "{} {repr}", value.type_name())?;
+    write_span(f, value.span())?;
+    write_decor(f, "decor", value.decor())?;
+    writeln!(f)
+}
+
+fn write_array(f: &mut Formatter<'_>, array: &Array, depth: usize) -> Result {
+    f.write_str("array")?;
+    write_span(f, array.span())?;
+    write_decor(f, "decor", array.decor())?;
+    if !is_empty(array.trailing()) {
+        write!(f, " trailing={}", raw_repr(array.trailing()))?;
+    }
+    if array.trailing_comma() {
+        f.write_str(" trailing-comma")?;
+    }
+    writeln!(f)?;
+    for (i, value) in array.iter().enumerate() {
+        write_indent(f, depth + 1)?;
+        write!(f, "[{i}] ")?;
+        write_value(f, value, depth + 1)?;
+    }
+    Ok(())
+}
+
+fn write_inline_table(f: &mut Formatter<'_>, table: &InlineTable, depth: usize) -> Result {
+    f.write_str("inline-table")?;
+    if table.is_dotted() {
+        f.write_str(" dotted")?;
+    }
+    write_span(f, table.span())?;
+    write_decor(f, "decor", table.decor())?;
+    if !is_empty(table.preamble()) {
+        write!(f, " preamble={}", raw_repr(table.preamble()))?;
+    }
+    if !is_empty(table.trailing()) {
+        write!(f, " trailing={}", raw_repr(table.trailing()))?;
+    }
+    if table.trailing_comma() {
+        f.write_str(" trailing-comma")?;
+    }
+    writeln!(f)?;
+    for (key, item) in table.items.iter() {
+        write_key(f, key, depth + 1)?;
+        write_item(f, item, depth + 1)?;
+    }
+    Ok(())
+}
+
+fn write_indent(f: &mut Formatter<'_>, depth: usize) -> Result {
+    for _ in 0..depth {
+        f.write_str("  ")?;
+    }
+    Ok(())
+}
+
+fn write_span(f: &mut Formatter<'_>, span: Option<std::ops::Range<usize>>) -> Result {
+    if let Some(span) = span {
+        write!(f, " {span:?}")?;
+    }
+    Ok(())
+}
+
+/// Write `decor` unless it is the default or empty
+fn write_decor(f: &mut Formatter<'_>, name: &str, decor: &Decor) -> Result {
+    let is_blank = |raw: Option<&RawString>| raw.map(is_empty).unwrap_or(true);
+    if is_blank(decor.prefix()) && is_blank(decor.suffix()) {
+        return Ok(());
+    }
+    let render =
+        |raw: Option<&RawString>| raw.map(raw_repr).unwrap_or_else(|| "default".to_owned());
+    write!(
+        f,
+        " {name}=({}, {})",
+        render(decor.prefix()),
+        render(decor.suffix())
+    )
+}
+
+/// The text of `raw` quoted, or its span when the text isn't known
+fn raw_repr(raw: &RawString) -> String {
+    match raw.as_str() {
+        Some(s) => format!("{s:?}"),
+        None => format!("{:?}", raw.span().unwrap_or_default()),
+    }
+}
+
+fn is_empty(raw: &RawString) -> bool {
+    raw.as_str().map(str::is_empty).unwrap_or(false)
+}
@@ -1,7 +1,9 @@
 use std::str::FromStr;
 
 use crate::table::Iter;
-use crate::{Item, RawString, Table};
+use crate::{Decor, Item, RawString, Table, Value};
+
+const BOM: char = '\u{feff}';
 
 /// The root TOML [`Table`], containing [`Key`][crate::Key]/[`Value`][crate::Value] pairs and all other logic [`Table`]s
 #[derive(Debug, Clone)]
@@ -10,6 +12,11 @@ pub struct Document<S> {
     // Trailing comments and whitespaces
     pub(crate) trailing: RawString,
     pub(crate) raw: S,
+    pub(crate) conflicts: Vec<crate::KeyConflict>,
+    pub(crate) duplicates: Vec<crate::DuplicateKey>,
+    pub(crate) opaque: Vec<std::ops::Range<usize>>,
+    // Write a lone `\r` as `\n`, see `CarriageReturn::Normalize`
+    pub(crate) normalize_cr: bool,
 }
 
 impl Document<&'static str> {
@@ -23,9 +30,239 @@ impl Document<&'static str> {
 impl<S: AsRef<str>> Document<S> {
     /// Parse a TOML document
     pub fn parse(raw: S) -> Result<Self, crate::TomlError> {
+        Self::parse_with_carriage_return(raw, crate::CarriageReturn::Reject)
+    }
+
+    /// Parse a TOML document, choosing how to handle a carriage return that is not part of a
+    /// `\r\n` line ending
+    ///
+    /// Line endings are written out as `\n`, so [`CarriageReturn::Normalize`][crate::CarriageReturn::Normalize]
+    /// turns a lone carriage return into a `\n`, including within comments.
+    /// [`CarriageReturn::Preserve`][crate::CarriageReturn::Preserve] writes a lone carriage return
+    /// in a comment back out as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{CarriageReturn, Document};
+    ///
+    /// let input = "# old\rcomment\r\na = 1\r";
+    /// assert!(Document::parse(input).is_err());
+    ///
+    /// let doc = Document::parse_with_carriage_return(input, CarriageReturn::Preserve).unwrap();
+    /// assert_eq!(doc.into_mut().to_string(), "# old\rcomment\na = 1\n");
+    ///
+    /// let input = "# old\r# comment\r\na = 1\r";
+    /// let doc = Document::parse_with_carriage_return(input, CarriageReturn::Normalize).unwrap();
+    /// assert_eq!(doc.into_mut().to_string(), "# old\n# comment\na = 1\n");
+    /// # }
+    /// ```
+    pub fn parse_with_carriage_return(
+        raw: S,
+        carriage_return: crate::CarriageReturn,
+    ) -> Result<Self, crate::TomlError> {
+        let options = crate::ParseOptions::new().carriage_return(carriage_return);
+        Self::parse_with_options(raw, &options)
+    }
+
+    /// Parse a TOML document as a specific version of TOML, see [`ParseOptions`][crate::ParseOptions]
+    pub fn parse_with_options(
+        raw: S,
+        options: &crate::ParseOptions,
+    ) -> Result<Self, crate::TomlError> {
+        let carriage_return = options.get_carriage_return();
+        let source = toml_parse::Source::new(raw.as_ref());
+        let mut sink =
+            crate::error::TomlSink::<Option<_>>::new(source).with_spec(options.get_spec());
+        let doc = crate::parser::to_document(source, carriage_return, &mut sink);
+        let parsed = Self::finish_parse(doc, sink, options)?;
+        Ok(Self::from_parsed(raw, parsed, options))
+    }
+
+    /// Parse a TOML document, reporting how far it has gotten to `progress`
+    ///
+    /// `progress` is called every so often while lexing, parsing, and building, and once when each
+    /// [stage][crate::ParseStage] is done, so tools can show progress on large inputs.  Once
+    /// `cancel` is [cancelled][crate::CancellationToken::cancel], from `progress` or another
+    /// thread, parsing stops and an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{CancellationToken, Document, ParseOptions, ParseStage};
+    ///
+    /// let input = "[package]\nname = 'app'\nversion = '1.0.0'\n";
+    /// let mut reports = Vec::new();
+    /// let doc = Document::parse_with_progress(
+    ///     input,
+    ///     &ParseOptions::new(),
+    ///     &mut |progress| reports.push(progress),
+    ///     &CancellationToken::new(),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(doc.into_mut().to_string(), input);
+    ///
+    /// let last = reports.last().unwrap();
+    /// assert_eq!(last.stage, ParseStage::Building);
+    /// assert_eq!(last.bytes, input.len());
+    /// assert_eq!(last.expressions, 3);
+    /// # }
+    /// ```
+    pub fn parse_with_progress(
+        raw: S,
+        options: &crate::ParseOptions,
+        progress: &mut dyn FnMut(crate::Progress),
+        cancel: &crate::CancellationToken,
+    ) -> Result<Self, crate::TomlError> {
+        let source = toml_parse::Source::new(raw.as_ref());
+        let mut sink =
+            crate::error::TomlSink::<Option<_>>::new(source).with_spec(options.get_spec());
+        let mut reporter = crate::progress::Reporter::new(progress, cancel, source.input().len());
+        let Some(doc) = crate::parser::to_document_with_progress(
+            source,
+            options.get_carriage_return(),
+            &mut sink,
+            &mut reporter,
+        ) else {
+            return Err(crate::TomlError::custom(
+                "parsing was cancelled".to_owned(),
+                None,
+            ));
+        };
+        let parsed = Self::finish_parse(doc, sink, options)?;
+        Ok(Self::from_parsed(raw, parsed, options))
+    }
+
+    /// Check what can only be checked once `doc` is built, giving back its root and trailing
+    /// whitespace
+    fn finish_parse(
+        doc: Document<&str>,
+        mut sink: crate::error::TomlSink<'_, Option<crate::TomlError>>,
+        options: &crate::ParseOptions,
+    ) -> Result<(Item, RawString), crate::TomlError> {
+        if options.get_spec() < crate::TomlSpec::V1_0 {
+            if let Some(root) = doc.root.as_table() {
+                crate::parse_options::check_mixed_arrays(root, &mut sink);
+            }
+        }
+        if let Some(err) = sink.into_inner() {
+            Err(err)
+        } else {
+            Ok((doc.root, doc.trailing))
+        }
+    }
+
+    fn from_parsed(
+        raw: S,
+        (root, trailing): (Item, RawString),
+        options: &crate::ParseOptions,
+    ) -> Self {
+        Document {
+            root,
+            trailing,
+            raw,
+            conflicts: Vec::new(),
+            duplicates: Vec::new(),
+            opaque: Vec::new(),
+            normalize_cr: options.get_carriage_return() == crate::CarriageReturn::Normalize,
+        }
+    }
+
+    /// Parse a TOML document, recovering from errors
+    ///
+    /// Invalid characters, like a lone carriage return or other control characters, are kept
+    /// as-is in the decor or [`Repr`][crate::Repr] they were found in so the document displays
+    /// with the original bytes, allowing cleanup tools to fix a file without touching unrelated
+    /// content.  Lines that can't be parsed as a key/value pair are kept as decor.  The returned
+    /// errors point to the invalid regions.
+    ///
+    /// A lone carriage return ending a key/value pair or table header is written out as `\n`
+    /// and other malformed content, like an unclosed array, may still be dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::Document;
+    ///
+    /// let input = "# bad\rcomment = 1\nname = 'foo'\n";
+    /// let (doc, errors) = Document::parse_lossy(input);
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(doc.into_mut().to_string(), input);
+    /// # }
+    /// ```
+    pub fn parse_lossy(raw: S) -> (Self, Vec<crate::TomlError>) {
+        let source = toml_parse::Source::new(raw.as_ref());
+        let mut sink = crate::error::TomlSink::<Vec<_>>::new(source);
+        let doc = crate::parser::to_document(source, crate::CarriageReturn::Reject, &mut sink);
+        let errors = sink.into_inner();
+        let mut conflicts = doc.conflicts;
+        let mut duplicates = doc.duplicates;
+        if !conflicts.is_empty() || !duplicates.is_empty() {
+            let shared = std::sync::Arc::<str>::from(raw.as_ref());
+            for conflict in &mut conflicts {
+                conflict.despan(&shared, false);
+            }
+            for duplicate in &mut duplicates {
+                duplicate.despan(&shared, false);
+            }
+        }
+        let doc = Document {
+            root: doc.root,
+            trailing: doc.trailing,
+            raw,
+            conflicts,
+            duplicates,
+            opaque: Vec::new(),
+            normalize_cr: false,
+        };
+        (doc, errors)
+    }
+
+    /// Parse a TOML document, keeping expressions that can't be parsed as-is
+    ///
+    /// Each key/value pair or table header with an error, like syntax from a newer version of
+    /// TOML, is kept as opaque text in the decor around it rather than failing, so tools can edit
+    /// the rest of the document and write the opaque text back out verbatim.  A table header
+    /// that can't be parsed takes the key/value pairs of its table with it.  See
+    /// [`Document::opaque_spans`] for where they are.
+    ///
+    /// Editing the decor that holds opaque text, like with [`Decor::clear`], drops it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::Document;
+    ///
+    /// let input = "\
+    /// name = 'app'
+    /// colors = { fg = 'red',
+    ///            bg = 'blue' }
+    /// [future.table]]
+    /// key = 1
+    /// [server]
+    /// port = 80
+    /// ";
+    /// let doc = Document::parse_preserving_unknown(input).unwrap();
+    /// let opaque = doc.opaque_spans().iter().map(|s| &input[s.clone()]).collect::<Vec<_>>();
+    /// assert_eq!(opaque, [
+    ///     "colors = { fg = 'red',\n           bg = 'blue' }",
+    ///     "[future.table]]\nkey = 1",
+    /// ]);
+    ///
+    /// let mut doc = doc.into_mut();
+    /// assert!(!doc.contains_key("colors"));
+    /// doc["server"]["port"] = toml_edit::value(8080);
+    /// assert_eq!(doc.to_string(), input.replace("80", "8080"));
+    /// # }
+    /// ```
+    pub fn parse_preserving_unknown(raw: S) -> Result<Self, crate::TomlError> {
         let source = toml_parse::Source::new(raw.as_ref());
         let mut sink = crate::error::TomlSink::<Option<_>>::new(source);
-        let doc = crate::parser::parse_document(source, &mut sink);
+        let (doc, opaque) = crate::parser::opaque::to_document(raw.as_ref(), &mut sink);
         if let Some(err) = sink.into_inner() {
             Err(err)
         } else {
@@ -33,19 +270,50 @@ impl<S: AsRef<str>> Document<S> {
                 root: doc.root,
                 trailing: doc.trailing,
                 raw,
+                conflicts: Vec::new(),
+                duplicates: Vec::new(),
+                opaque,
+                normalize_cr: false,
             })
         }
     }
 }
 
 impl<S: AsRef<str>> Document<S> {
+    /// The raw strings share one copy of the document, rather than each having their own.
+    ///
+    /// With `spans`, `span()` keeps reporting where each part of the document came from.
+    ///
     /// # Panics
     ///
     /// If run on a [`DocumentMut`] not generated by the parser
-    pub(crate) fn despan(&mut self) {
-        self.root.despan(self.raw.as_ref());
-        self.trailing.despan(self.raw.as_ref());
+    pub(crate) fn despan(&mut self, spans: bool) {
+        let raw = if self.normalize_cr {
+            std::sync::Arc::<str>::from(normalize_lone_cr(self.raw.as_ref()))
+        } else {
+            std::sync::Arc::<str>::from(self.raw.as_ref())
+        };
+        self.root.despan(&raw, spans);
+        self.trailing.despan(&raw, spans);
+        if let Some(root) = self.root.as_table_mut() {
+            crate::key::drop_redundant_path_decor(root);
+            #[cfg(all(feature = "parse", feature = "display"))]
+            if spans {
+                crate::patch::record_body_spans(root, &raw);
+            }
+        }
+    }
+}
+
+/// Replace each `\r` not followed by `\n` with `\n`, keeping spans valid
+fn normalize_lone_cr(raw: &str) -> String {
+    let mut bytes = raw.as_bytes().to_vec();
+    for i in 0..bytes.len() {
+        if bytes[i] == b'\r' && bytes.get(i + 1) != Some(&b'\n') {
+            bytes[i] = b'\n';
+        }
     }
+    String::from_utf8(bytes).expect("only ASCII was replaced")
 }
 
 impl<S> Document<S> {
@@ -70,6 +338,29 @@ impl<S> Document<S> {
     pub fn trailing(&self) -> &RawString {
         &self.trailing
     }
+
+    /// Tables defined by both dotted keys and a table header
+    ///
+    /// These are only recorded by [`Document::parse_lossy`] and can be fixed with
+    /// [`DocumentMut::resolve_conflict`].
+    pub fn key_conflicts(&self) -> &[crate::KeyConflict] {
+        &self.conflicts
+    }
+
+    /// Keys given a value more than once within the same table
+    ///
+    /// These are only recorded by [`Document::parse_lossy`] and can be fixed with
+    /// [`DocumentMut::resolve_duplicate`].
+    pub fn duplicate_keys(&self) -> &[crate::DuplicateKey] {
+        &self.duplicates
+    }
+
+    /// Where the expressions that couldn't be parsed are, as whole lines
+    ///
+    /// These are only recorded by [`Document::parse_preserving_unknown`].
+    pub fn opaque_spans(&self) -> &[std::ops::Range<usize>] {
+        &self.opaque
+    }
 }
 
 impl<S: AsRef<str>> Document<S> {
@@ -77,15 +368,56 @@ impl<S: AsRef<str>> Document<S> {
     pub fn raw(&self) -> &str {
         self.raw.as_ref()
     }
+
+    /// Whether the raw document starts with a UTF-8 byte order mark
+    pub fn has_bom(&self) -> bool {
+        self.raw().starts_with(BOM)
+    }
 }
 
 impl<S: AsRef<str>> Document<S> {
     /// Allow editing of the [`DocumentMut`]
     pub fn into_mut(mut self) -> DocumentMut {
-        self.despan();
+        self.despan(false);
+        self.into_mut_unchecked()
+    }
+
+    /// Allow editing of the [`DocumentMut`], keeping the location of each part of the document
+    ///
+    /// `span()`, like [`Item::span`] and [`Key::span`][crate::Key::span], keeps reporting where
+    /// the original text is, for analysis tools that work on the edit tree directly.  Keys and
+    /// values that are edited or added have no span.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::Document;
+    ///
+    /// let input = "[package]\nname = 'foo'\nedition = '2018'\n";
+    /// let mut doc = Document::parse(input).unwrap().into_mut_with_spans();
+    ///
+    /// let package = doc["package"].as_table().unwrap();
+    /// let (key, name) = package.get_key_value("name").unwrap();
+    /// assert_eq!(&input[key.span().unwrap()], "name");
+    /// assert_eq!(&input[name.span().unwrap()], "'foo'");
+    ///
+    /// doc["package"]["edition"] = toml_edit::value("2021");
+    /// assert_eq!(doc["package"]["edition"].span(), None);
+    /// assert_eq!(&input[doc["package"]["name"].span().unwrap()], "'foo'");
+    /// # }
+    /// ```
+    pub fn into_mut_with_spans(mut self) -> DocumentMut {
+        self.despan(true);
+        self.into_mut_unchecked()
+    }
+
+    fn into_mut_unchecked(self) -> DocumentMut {
+        let bom = self.has_bom();
         DocumentMut {
             root: self.root,
             trailing: self.trailing,
+            bom,
         }
     }
 }
@@ -96,6 +428,10 @@ impl Default for Document<&'static str> {
             root: Item::Table(Table::with_pos(Some(0))),
             trailing: Default::default(),
             raw: "",
+            conflicts: Vec::new(),
+            duplicates: Vec::new(),
+            opaque: Vec::new(),
+            normalize_cr: false,
         }
     }
 }
@@ -124,6 +460,7 @@ pub struct DocumentMut {
     pub(crate) root: Item,
     // Trailing comments and whitespaces
     pub(crate) trailing: RawString,
+    pub(crate) bom: bool,
 }
 
 impl DocumentMut {
@@ -132,6 +469,14 @@ impl DocumentMut {
         Default::default()
     }
 
+    /// Parse a TOML document, keeping the location of each part of it
+    ///
+    /// See [`Document::into_mut_with_spans`].
+    #[cfg(feature = "parse")]
+    pub fn parse_with_spans(input: &str) -> Result<Self, crate::TomlError> {
+        Ok(Document::parse(input)?.into_mut_with_spans())
+    }
+
     /// Returns a reference to the root table.
     pub fn as_table(&self) -> &Table {
         self.root.as_table().expect("root should always be a table")
@@ -157,14 +502,95 @@ impl DocumentMut {
     }
 
     /// Set whitespace after last element
-    pub fn set_trailing(&mut self, trailing: impl Into<RawString>) {
-        self.trailing = trailing.into();
+    ///
+    /// Only whitespace, newlines, and comments are allowed.
+    pub fn set_trailing(
+        &mut self,
+        trailing: impl Into<RawString>,
+    ) -> Result<(), crate::StructureError> {
+        let trailing = trailing.into();
+        crate::structure::check_whitespace(&trailing, crate::structure::Space::Any)?;
+        self.trailing = trailing;
+        Ok(())
     }
 
     /// Whitespace after last element
     pub fn trailing(&self) -> &RawString {
         &self.trailing
     }
+
+    /// Set whether the document starts with a UTF-8 byte order mark
+    ///
+    /// Parsing records whether the original input had one so it is written back out unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "\u{feff}a = 1\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert!(doc.has_bom());
+    /// assert_eq!(doc.to_string(), "\u{feff}a = 1\n");
+    ///
+    /// doc.set_bom(false);
+    /// assert_eq!(doc.to_string(), "a = 1\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn set_bom(&mut self, bom: bool) {
+        self.bom = bom;
+    }
+
+    /// Whether the document starts with a UTF-8 byte order mark
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// Shrinks the capacity of every table and array in the document as much as possible.
+    ///
+    /// This is useful for documents that are kept around long after they are done being edited.
+    pub fn shrink_to_fit(&mut self) {
+        self.root.shrink_to_fit();
+    }
+
+    /// Returns an iterator over every [`Decor`] and other whitespace in the document.
+    ///
+    /// Each entry is tagged with a [`DecorKind`] describing where it lives, allowing whitespace
+    /// and comments for the whole document to be rewritten in a single loop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{DecorMut, DocumentMut};
+    ///
+    /// let mut doc = "a =\t1\n".parse::<DocumentMut>().unwrap();
+    /// for (_, decor) in doc.decor_iter_mut() {
+    ///     if let DecorMut::Decor(decor) = decor {
+    ///         if let Some(prefix) = decor.prefix().and_then(|p| p.as_str()) {
+    ///             let prefix = prefix.replace('\t', " ");
+    ///             decor.set_prefix(prefix);
+    ///         }
+    ///     }
+    /// }
+    /// assert_eq!(doc.to_string(), "a = 1\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn decor_iter_mut(&mut self) -> DecorIterMut<'_> {
+        let mut decors = Vec::new();
+        let root = self
+            .root
+            .as_table_mut()
+            .expect("root should always be a table");
+        collect_table_decors(root, DecorKind::Document, &mut decors);
+        decors.push((
+            DecorKind::DocumentTrailing,
+            DecorMut::Raw(&mut self.trailing),
+        ));
+        Box::new(decors.into_iter())
+    }
 }
 
 impl Default for DocumentMut {
@@ -172,6 +598,7 @@ impl Default for DocumentMut {
         Self {
             root: Item::Table(Table::with_pos(Some(0))),
             trailing: Default::default(),
+            bom: false,
         }
     }
 }
@@ -210,6 +637,264 @@ impl From<Table> for DocumentMut {
     }
 }
 
+#[cfg(feature = "parse")]
+impl DocumentMut {
+    /// Removes the table at the dotted key `prefix`, returning it as its own document
+    ///
+    /// The table's key/value pairs become the root of the new document and its sub-tables have
+    /// their headers rewritten relative to it.  Decor and relative table order are preserved.
+    ///
+    /// Returns `Ok(None)` if there is no table at `prefix`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::DocumentMut;
+    ///
+    /// let mut doc = "[tool.myapp]\nname = 'foo'\n\n[tool.myapp.extra]\nflag = true\n"
+    ///     .parse::<DocumentMut>()
+    ///     .unwrap();
+    /// let myapp = doc.extract_prefix("tool.myapp").unwrap().unwrap();
+    /// assert_eq!(myapp.to_string(), "name = 'foo'\n\n[extra]\nflag = true\n");
+    /// # }
+    /// ```
+    pub fn extract_prefix(
+        &mut self,
+        prefix: &str,
+    ) -> Result<Option<DocumentMut>, crate::TomlError> {
+        let keys = crate::Key::parse(prefix)?;
+        let Some((last, parents)) = keys.split_last() else {
+            return Ok(None);
+        };
+
+        let mut parent: &mut dyn crate::TableLike = self.as_table_mut();
+        for key in parents {
+            parent = match parent.get_mut(key.get()).and_then(Item::as_table_like_mut) {
+                Some(table) => table,
+                None => return Ok(None),
+            };
+        }
+        if !parent
+            .get(last.get())
+            .map(Item::is_table_like)
+            .unwrap_or(false)
+        {
+            return Ok(None);
+        }
+        let mut root = parent
+            .remove(last.get())
+            .expect("checked for presence")
+            .into_table()
+            .expect("checked for table-like");
+        root.set_dotted(false);
+        root.set_implicit(false);
+        Ok(Some(DocumentMut::from(root)))
+    }
+
+    /// Inserts the root table of `doc` at the dotted key `prefix`, returning any item it replaced
+    ///
+    /// Missing parent tables are created as implicit tables.  The tables of `doc` have their
+    /// headers rewritten under `prefix` and are ordered after the existing tables.  Decor is
+    /// preserved except for [`DocumentMut::trailing`] which is dropped.
+    ///
+    /// # Errors
+    ///
+    /// If `prefix` is not a valid key or one of its parents is not a table.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::DocumentMut;
+    ///
+    /// let mut doc = "[project]\nname = 'foo'\n".parse::<DocumentMut>().unwrap();
+    /// let myapp = "name = 'bar'\n".parse::<DocumentMut>().unwrap();
+    /// doc.graft_at("tool.myapp", myapp).unwrap();
+    /// assert_eq!(
+    ///     doc.to_string(),
+    ///     "[project]\nname = 'foo'\n\n[tool.myapp]\nname = 'bar'\n"
+    /// );
+    /// # }
+    /// ```
+    pub fn graft_at(
+        &mut self,
+        prefix: &str,
+        doc: DocumentMut,
+    ) -> Result<Option<Item>, crate::TomlError> {
+        let keys = crate::Key::parse(prefix)?;
+        let Some((last, parents)) = keys.split_last() else {
+            return Err(crate::TomlError::custom(
+                "prefix must not be empty".to_owned(),
+                None,
+            ));
+        };
+
+        let offset = max_position(self.as_table())
+            .map(|pos| pos + 1)
+            .unwrap_or(0);
+        let mut root = doc.into_table();
+        shift_positions(&mut root, offset);
+
+        let mut parent = self.as_table_mut();
+        for key in parents {
+            let item = parent.entry_format(key).or_insert_with(|| {
+                let mut table = Table::new();
+                table.set_implicit(true);
+                Item::Table(table)
+            });
+            parent = item.as_table_mut().ok_or_else(|| {
+                crate::TomlError::custom(format!("`{}` is not a table", key.get()), None)
+            })?;
+        }
+        Ok(parent.insert_formatted(last, Item::Table(root)))
+    }
+}
+
+#[cfg(feature = "parse")]
+fn max_position(table: &Table) -> Option<usize> {
+    let nested = table.iter().filter_map(|(_, item)| match item {
+        Item::Table(table) => max_position(table),
+        Item::ArrayOfTables(array) => array.iter().filter_map(max_position).max(),
+        _ => None,
+    });
+    table.position().into_iter().chain(nested).max()
+}
+
+#[cfg(feature = "parse")]
+fn shift_positions(table: &mut Table, offset: usize) {
+    if let Some(pos) = table.position() {
+        table.set_position(pos + offset);
+    }
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(table) => shift_positions(table, offset),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter_mut() {
+                    shift_positions(table, offset);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// An iterator type over every [`Decor`] in a [`DocumentMut`]
+pub type DecorIterMut<'d> = Box<dyn Iterator<Item = (DecorKind, DecorMut<'d>)> + 'd>;
+
+/// Where whitespace and comments live within a [`DocumentMut`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DecorKind {
+    /// Before and after the whole document
+    Document,
+    /// After the last element of the document
+    DocumentTrailing,
+    /// Around a `[table]` or `[[array]]` header
+    TableHeader,
+    /// Around the last key of a key/value pair or table header
+    Key,
+    /// Around a key that is followed by a `.`
+    DottedKey,
+    /// Around the value of a key/value pair
+    Value,
+    /// Around a value within an array
+    ArrayElement,
+    /// After the last value of an array
+    ArrayTrailing,
+    /// Within an empty inline table
+    InlineTablePreamble,
+    /// After the trailing comma of an inline table
+    InlineTableTrailing,
+}
+
+/// Mutable access to whitespace and comments, see [`DocumentMut::decor_iter_mut`]
+#[derive(Debug)]
+pub enum DecorMut<'d> {
+    /// Prefix and suffix around an element
+    Decor(&'d mut Decor),
+    /// Whitespace not attached to any element
+    Raw(&'d mut RawString),
+}
+
+fn collect_table_decors<'d>(
+    table: &'d mut Table,
+    kind: DecorKind,
+    decors: &mut Vec<(DecorKind, DecorMut<'d>)>,
+) {
+    use indexmap::map::MutableKeys;
+
+    let Table {
+        decor,
+        items,
+        body_span,
+        ..
+    } = table;
+    *body_span = None;
+    decors.push((kind, DecorMut::Decor(decor)));
+    for (key, item) in items.iter_mut2() {
+        collect_key_decors(key, decors);
+        match item {
+            Item::None => {}
+            Item::Value(value) => collect_value_decors(value, DecorKind::Value, decors),
+            Item::Table(table) => collect_table_decors(table, DecorKind::TableHeader, decors),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter_mut() {
+                    collect_table_decors(table, DecorKind::TableHeader, decors);
+                }
+            }
+        }
+    }
+}
+
+fn collect_key_decors<'d>(key: &'d mut crate::Key, decors: &mut Vec<(DecorKind, DecorMut<'d>)>) {
+    decors.push((DecorKind::Key, DecorMut::Decor(&mut key.leaf_decor)));
+    decors.push((DecorKind::DottedKey, DecorMut::Decor(&mut key.dotted_decor)));
+}
+
+fn collect_value_decors<'d>(
+    value: &'d mut Value,
+    kind: DecorKind,
+    decors: &mut Vec<(DecorKind, DecorMut<'d>)>,
+) {
+    use indexmap::map::MutableKeys;
+
+    match value {
+        Value::Array(array) => {
+            let crate::Array {
+                decor,
+                values,
+                trailing,
+                ..
+            } = array;
+            decors.push((kind, DecorMut::Decor(decor)));
+            for value in values.iter_mut().filter_map(Item::as_value_mut) {
+                collect_value_decors(value, DecorKind::ArrayElement, decors);
+            }
+            decors.push((DecorKind::ArrayTrailing, DecorMut::Raw(trailing)));
+        }
+        Value::InlineTable(table) => {
+            let crate::InlineTable {
+                decor,
+                preamble,
+                trailing,
+                items,
+                ..
+            } = table;
+            decors.push((kind, DecorMut::Decor(decor)));
+            decors.push((DecorKind::InlineTablePreamble, DecorMut::Raw(preamble)));
+            for (key, item) in items.iter_mut2() {
+                collect_key_decors(key, decors);
+                if let Item::Value(value) = item {
+                    collect_value_decors(value, DecorKind::Value, decors);
+                }
+            }
+            decors.push((DecorKind::InlineTableTrailing, DecorMut::Raw(trailing)));
+        }
+        _ => decors.push((kind, DecorMut::Decor(value.decor_mut()))),
+    }
+}
+
 #[test]
 #[cfg(feature = "parse")]
 #[cfg(feature = "display")]
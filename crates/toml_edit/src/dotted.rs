@@ -0,0 +1,185 @@
+use crate::{Decor, Item, Table};
+
+/// Converting between dotted keys and table headers
+impl Table {
+    /// Move the values of dotted keys under table headers, like `a.b.c = 1` to `[a.b]` with
+    /// `c = 1`
+    ///
+    /// This applies to every table under this one, including those in arrays of tables.
+    /// Comments on a dotted key stay with its value.  Dotted keys within inline tables are left
+    /// as-is, since inline tables can't have headers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "
+    /// [package]
+    /// name = 'app'
+    /// ## Shown on docs.rs
+    /// metadata.docs.rs.all-features = true
+    /// metadata.docs.rs.targets = ['x86_64-unknown-linux-gnu'] # the default
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// doc.as_table_mut().expand_dotted_keys();
+    /// assert_eq!(doc.to_string(), "
+    /// [package]
+    /// name = 'app'
+    ///
+    /// [package.metadata.docs.rs]
+    /// ## Shown on docs.rs
+    /// all-features = true
+    /// targets = ['x86_64-unknown-linux-gnu'] # the default
+    /// ");
+    /// # }
+    /// # }
+    /// ```
+    pub fn expand_dotted_keys(&mut self) {
+        let position = self.position();
+        for item in self.items_mut().values_mut() {
+            match item {
+                Item::Table(child) => {
+                    if child.is_dotted() {
+                        child.set_dotted(false);
+                        child.doc_position = position;
+                        child.decor = Decor::default();
+                        child.implicit = !child.items.values().any(Item::is_value);
+                    }
+                    child.expand_dotted_keys();
+                }
+                Item::ArrayOfTables(array) => {
+                    for child in array.iter_mut() {
+                        child.expand_dotted_keys();
+                    }
+                }
+                Item::None | Item::Value(_) => {}
+            }
+        }
+    }
+
+    /// Move the values of tables under this one into dotted keys, like `[a.b]` with `c = 1` to
+    /// `a.b.c = 1`
+    ///
+    /// A table is collapsed when it, and every table under it, is at most `max_depth` tables
+    /// below this one, so a `max_depth` of 1 turns `[a]` with `b = 1` into `a.b = 1` but leaves
+    /// `[a.b]` as a header.  Tables that are too deep, and arrays of tables, keep their headers and
+    /// have the tables under them collapsed instead.  Empty tables are kept, since dotted keys
+    /// can't express them.
+    ///
+    /// Comments on a header move to the first value of its table.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "
+    /// [package]
+    /// name = 'app'
+    ///
+    /// ## Shown on docs.rs
+    /// [package.metadata.docs.rs] # but not crates.io
+    /// all-features = true
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// doc.as_table_mut().collapse_to_dotted(3);
+    /// assert_eq!(doc.to_string(), "
+    /// [package]
+    /// name = 'app'
+    ///
+    /// ## Shown on docs.rs
+    /// ## but not crates.io
+    /// metadata.docs.rs.all-features = true
+    /// ");
+    /// # }
+    /// # }
+    /// ```
+    pub fn collapse_to_dotted(&mut self, max_depth: usize) {
+        for item in self.items_mut().values_mut() {
+            match item {
+                Item::Table(child) if !child.is_dotted() => {
+                    if is_collapsible(child, max_depth) {
+                        make_dotted(child);
+                    } else {
+                        child.collapse_to_dotted(max_depth);
+                    }
+                }
+                Item::ArrayOfTables(array) => {
+                    for child in array.iter_mut() {
+                        child.collapse_to_dotted(max_depth);
+                    }
+                }
+                Item::None | Item::Value(_) | Item::Table(_) => {}
+            }
+        }
+    }
+}
+
+/// Whether `table` and the tables under it fit within `depth` levels of dotted keys
+fn is_collapsible(table: &Table, depth: usize) -> bool {
+    if depth == 0 || !(table.is_implicit() || has_values(table)) {
+        return false;
+    }
+    table.items.values().all(|item| match item {
+        Item::None | Item::Value(_) => true,
+        Item::Table(child) => is_collapsible(child, depth - 1),
+        Item::ArrayOfTables(_) => false,
+    })
+}
+
+fn has_values(table: &Table) -> bool {
+    table.items.values().any(|item| match item {
+        Item::Value(_) => true,
+        Item::Table(child) => has_values(child),
+        _ => false,
+    })
+}
+
+/// Turn `table` and the tables under it into dotted keys, moving header comments to the first
+/// value of each
+fn make_dotted(table: &mut Table) {
+    for item in table.items.values_mut() {
+        if let Item::Table(child) = item {
+            make_dotted(child);
+        }
+    }
+
+    let mut comments = table
+        .decor
+        .prefix()
+        .and_then(|p| p.as_str())
+        .unwrap_or_default()
+        .to_owned();
+    let suffix = table.decor.suffix().and_then(|s| s.as_str());
+    if let Some(comment) = suffix.and_then(|s| s.find('#').map(|i| &s[i..])) {
+        comments.push_str(comment.trim_end());
+        comments.push('\n');
+    }
+    if let Some(key) = first_value_key(table) {
+        let decor = key.leaf_decor_mut();
+        let prefix = decor.prefix().and_then(|p| p.as_str()).unwrap_or_default();
+        comments.push_str(prefix);
+        decor.set_prefix_unchecked(comments);
+    }
+    table.decor = Decor::default();
+    table.set_dotted(true);
+}
+
+/// The key whose line a table's values start on when written as dotted keys
+fn first_value_key(table: &mut Table) -> Option<&mut crate::Key> {
+    use indexmap::map::MutableKeys;
+
+    for (key, item) in table.items.iter_mut2() {
+        match item {
+            Item::Value(_) => return Some(key),
+            Item::Table(child) => {
+                if let Some(key) = first_value_key(child) {
+                    return Some(key);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,535 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Key, Table, Value};
+
+/// A list of [`Edit`]s to apply to a document, like a patch
+///
+/// With the `serde` feature, this can be deserialized from a list of edits or from a table with
+/// an `edit` list, like:
+/// ```toml
+/// [[edit]]
+/// op = "set"
+/// path = ["package", "version"]
+/// value = "1.1.0"
+///
+/// [[edit]]
+/// op = "rename"
+/// path = ["dependencies", "serde_json"]
+/// to = "json"
+/// ```
+/// Each edit has an `op` and a `path` of keys from the root plus, depending on the `op`, a
+/// `value` or a `to`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "parse")] {
+/// # #[cfg(feature = "display")] {
+/// # #[cfg(feature = "serde")] {
+/// use toml_edit::{DocumentMut, EditScript};
+///
+/// let script: EditScript = toml_edit::de::from_str(r#"
+/// [[edit]]
+/// op = "set"
+/// path = ["package", "version"]
+/// value = "1.1.0"
+///
+/// [[edit]]
+/// op = "append"
+/// path = ["package", "keywords"]
+/// value = "config"
+///
+/// [[edit]]
+/// op = "remove"
+/// path = ["package", "publish"]
+///
+/// [[edit]]
+/// op = "sort"
+/// path = ["dependencies"]
+/// "#).unwrap();
+///
+/// let mut doc = r#"
+/// [package]
+/// name = "foo"
+/// version = "1.0.0"  # bumped by CI
+/// keywords = ["toml"]
+/// publish = false
+///
+/// [dependencies]
+/// toml = "0.8"
+/// serde = "1"
+/// "#.parse::<DocumentMut>().unwrap();
+/// script.apply(&mut doc).unwrap();
+/// assert_eq!(doc.to_string(), r#"
+/// [package]
+/// name = "foo"
+/// version = "1.1.0"  # bumped by CI
+/// keywords = ["toml", "config"]
+///
+/// [dependencies]
+/// serde = "1"
+/// toml = "0.8"
+/// "#);
+/// # }
+/// # }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EditScript {
+    edits: Vec<Edit>,
+}
+
+impl EditScript {
+    /// Creates an empty script
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add an edit to the end of the script
+    pub fn push(&mut self, edit: Edit) {
+        self.edits.push(edit);
+    }
+
+    /// The edits, in the order they are applied
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+
+    /// Apply each edit in order
+    ///
+    /// If an edit fails, `doc` is left unchanged.
+    pub fn apply(&self, doc: &mut DocumentMut) -> Result<(), EditError> {
+        let mut edited = doc.clone();
+        for (index, edit) in self.edits.iter().enumerate() {
+            edit.apply(&mut edited).map_err(|reason| EditError {
+                index,
+                path: edit.path().to_vec(),
+                reason,
+            })?;
+        }
+        *doc = edited;
+        Ok(())
+    }
+}
+
+impl From<Vec<Edit>> for EditScript {
+    fn from(edits: Vec<Edit>) -> Self {
+        Self { edits }
+    }
+}
+
+impl FromIterator<Edit> for EditScript {
+    fn from_iter<I: IntoIterator<Item = Edit>>(iter: I) -> Self {
+        Self {
+            edits: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// An operation in an [`EditScript`]
+///
+/// A `path` is the keys from the root of the document.  Missing tables along the way are created
+/// by `Set` and `Append`.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Edit {
+    /// Insert or replace a value, keeping the formatting around a replaced value
+    Set {
+        /// The key to set
+        path: Vec<String>,
+        /// The new value
+        value: Value,
+    },
+    /// Remove a key and its value, if present
+    Remove {
+        /// The key to remove
+        path: Vec<String>,
+    },
+    /// Rename a key, keeping its position and formatting
+    Rename {
+        /// The key to rename
+        path: Vec<String>,
+        /// The new name, within the same table
+        to: String,
+    },
+    /// Add a value to the end of an array, creating the array if needed
+    ///
+    /// For an array of tables, `value` must be an inline table.  An inline table added to a
+    /// missing key in a standard table creates an array of tables.
+    Append {
+        /// The array to add to
+        path: Vec<String>,
+        /// The value to add
+        value: Value,
+    },
+    /// Sort a table by key or an array of strings
+    Sort {
+        /// The table or array to sort
+        path: Vec<String>,
+    },
+}
+
+impl Edit {
+    /// The keys from the root of the document to what is being edited
+    pub fn path(&self) -> &[String] {
+        match self {
+            Self::Set { path, .. }
+            | Self::Remove { path }
+            | Self::Rename { path, .. }
+            | Self::Append { path, .. }
+            | Self::Sort { path } => path,
+        }
+    }
+
+    fn apply(&self, doc: &mut DocumentMut) -> Result<(), Reason> {
+        let path = self.path();
+        if path.is_empty() {
+            return match self {
+                Self::Sort { .. } => {
+                    doc.sort_values();
+                    Ok(())
+                }
+                _ => Err(Reason::EmptyPath),
+            };
+        }
+        let (key, parents) = path.split_last().expect("checked for empty");
+        let create = matches!(self, Self::Set { .. } | Self::Append { .. });
+        let Some(mut parent) = Parent::find(doc.as_table_mut(), parents, create)? else {
+            return match self {
+                Self::Remove { .. } => Ok(()),
+                _ => Err(Reason::Missing),
+            };
+        };
+
+        match self {
+            Self::Set { value, .. } => {
+                match parent.get_mut(key) {
+                    Some(Item::Value(existing)) => {
+                        let decor = existing.decor().clone();
+                        *existing = value.clone();
+                        *existing.decor_mut() = decor;
+                    }
+                    Some(_) => return Err(Reason::NotAValue),
+                    None => parent.insert(key, value.clone()),
+                }
+                Ok(())
+            }
+            Self::Remove { .. } => {
+                parent.items().shift_remove(key.as_str());
+                Ok(())
+            }
+            Self::Rename { to, .. } => {
+                let items = parent.items();
+                if items.contains_key(to.as_str()) {
+                    return Err(Reason::RenameTaken);
+                }
+                let index = items.get_index_of(key.as_str()).ok_or(Reason::Missing)?;
+                let (old, item) = items.shift_remove_index(index).expect("index is present");
+                let renamed = Key::new(to.as_str())
+                    .with_leaf_decor_unchecked(old.leaf_decor().clone())
+                    .with_dotted_decor_unchecked(old.dotted_decor().clone())
+                    .with_id(old.id());
+                items.shift_insert(index, renamed, item);
+                Ok(())
+            }
+            Self::Append { value, .. } => {
+                match parent.get_mut(key) {
+                    Some(Item::Value(Value::Array(array))) => array.push(value.clone()),
+                    Some(Item::ArrayOfTables(array)) => match value {
+                        Value::InlineTable(table) => array.push(table.clone().into_table()),
+                        _ => return Err(Reason::NotATable),
+                    },
+                    Some(_) => return Err(Reason::NotAnArray),
+                    None => match (&mut parent, value) {
+                        (Parent::Table(parent), Value::InlineTable(table)) => {
+                            let array = ArrayOfTables::from_iter([table.clone().into_table()]);
+                            parent.insert(key, Item::ArrayOfTables(array));
+                        }
+                        _ => parent.insert(key, Value::Array(Array::from_iter([value.clone()]))),
+                    },
+                }
+                Ok(())
+            }
+            Self::Sort { .. } => match parent.get_mut(key) {
+                Some(Item::Table(table)) => {
+                    table.sort_values();
+                    Ok(())
+                }
+                Some(Item::Value(Value::InlineTable(table))) => {
+                    table.sort_values();
+                    Ok(())
+                }
+                Some(Item::Value(Value::Array(array))) => {
+                    if !array.iter().all(|v| v.is_str()) {
+                        return Err(Reason::Unsortable);
+                    }
+                    // Keep the spacing in place rather than moving it with the values
+                    let decors = array.iter().map(|v| v.decor().clone()).collect::<Vec<_>>();
+                    array.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+                    for (value, decor) in array.iter_mut().zip(decors) {
+                        *value.decor_mut() = decor;
+                    }
+                    Ok(())
+                }
+                Some(_) => Err(Reason::Unsortable),
+                None => Err(Reason::Missing),
+            },
+        }
+    }
+}
+
+/// The table or inline table containing the key being edited
+enum Parent<'d> {
+    Table(&'d mut Table),
+    InlineTable(&'d mut InlineTable),
+}
+
+impl<'d> Parent<'d> {
+    fn find(
+        root: &'d mut Table,
+        path: &[String],
+        create: bool,
+    ) -> Result<Option<Parent<'d>>, Reason> {
+        let mut parent = Parent::Table(root);
+        for key in path {
+            if create && parent.get_mut(key).is_none() {
+                match &mut parent {
+                    Parent::Table(table) => {
+                        let mut child = Table::new();
+                        child.set_implicit(true);
+                        table.insert(key, Item::Table(child));
+                    }
+                    Parent::InlineTable(table) => {
+                        table.insert(key, Value::InlineTable(InlineTable::new()));
+                    }
+                }
+            }
+            parent = match parent.into_child(key) {
+                Some(Item::Table(table)) => Parent::Table(table),
+                Some(Item::Value(Value::InlineTable(table))) => Parent::InlineTable(table),
+                Some(_) => return Err(Reason::NotATable),
+                None => return Ok(None),
+            };
+        }
+        Ok(Some(parent))
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut Item> {
+        self.items().get_mut(key)
+    }
+
+    fn into_child(self, key: &str) -> Option<&'d mut Item> {
+        match self {
+            Parent::Table(table) => table.items_mut().get_mut(key),
+            Parent::InlineTable(table) => table.items.get_mut(key),
+        }
+    }
+
+    fn insert(&mut self, key: &str, value: Value) {
+        match self {
+            Parent::Table(table) => {
+                table.insert(key, Item::Value(value));
+            }
+            Parent::InlineTable(table) => {
+                table.insert(key, value);
+            }
+        }
+    }
+
+    fn items(&mut self) -> &mut crate::table::KeyValuePairs {
+        match self {
+            Parent::Table(table) => table.items_mut(),
+            Parent::InlineTable(table) => &mut table.items,
+        }
+    }
+}
+
+/// An [`Edit`] in an [`EditScript`] could not be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditError {
+    index: usize,
+    path: Vec<String>,
+    reason: Reason,
+}
+
+impl EditError {
+    /// The position of the edit within the script
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The path of the edit
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+}
+
+impl Display for EditError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "edit {} at `{}` failed: {}",
+            self.index,
+            self.path.join("."),
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for EditError {}
+
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+enum Reason {
+    EmptyPath,
+    Missing,
+    NotATable,
+    NotAValue,
+    NotAnArray,
+    RenameTaken,
+    Unsortable,
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            Self::EmptyPath => "path is empty",
+            Self::Missing => "key is not present",
+            Self::NotATable => "expected a table",
+            Self::NotAValue => "cannot replace a table or array of tables",
+            Self::NotAnArray => "expected an array",
+            Self::RenameTaken => "new key is already present",
+            Self::Unsortable => "expected a table or an array of strings",
+        };
+        f.write_str(reason)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod de {
+    use serde::de::{Deserialize, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+
+    use super::{Edit, EditScript};
+    use crate::de::AnyValue;
+    use crate::Value;
+
+    impl<'de> Deserialize<'de> for EditScript {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct ScriptVisitor;
+
+            impl<'de> Visitor<'de> for ScriptVisitor {
+                type Value = EditScript;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str("a list of edits or a table with an `edit` list")
+                }
+
+                fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<EditScript, A::Error> {
+                    let edits = Vec::<Edit>::deserialize(
+                        serde::de::value::SeqAccessDeserializer::new(seq),
+                    )?;
+                    Ok(EditScript { edits })
+                }
+
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<EditScript, A::Error> {
+                    let mut edits = None;
+                    while let Some(key) = map.next_key::<String>()? {
+                        match key.as_str() {
+                            "edit" if edits.is_none() => edits = Some(map.next_value()?),
+                            "edit" => return Err(A::Error::duplicate_field("edit")),
+                            _ => return Err(A::Error::unknown_field(&key, &["edit"])),
+                        }
+                    }
+                    let edits = edits.ok_or_else(|| A::Error::missing_field("edit"))?;
+                    Ok(EditScript { edits })
+                }
+            }
+
+            deserializer.deserialize_any(ScriptVisitor)
+        }
+    }
+
+    const OPS: &[&str] = &["set", "remove", "rename", "append", "sort"];
+    const FIELDS: &[&str] = &["op", "path", "value", "to"];
+
+    impl<'de> Deserialize<'de> for Edit {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct EditVisitor;
+
+            impl<'de> Visitor<'de> for EditVisitor {
+                type Value = Edit;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str("a table with an `op` and a `path`")
+                }
+
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Edit, A::Error> {
+                    let mut op = None::<String>;
+                    let mut path = None::<Vec<String>>;
+                    let mut value = None::<AnyValue>;
+                    let mut to = None::<String>;
+                    while let Some(key) = map.next_key::<String>()? {
+                        match key.as_str() {
+                            "op" if op.is_none() => op = Some(map.next_value()?),
+                            "path" if path.is_none() => path = Some(map.next_value()?),
+                            "value" if value.is_none() => value = Some(map.next_value()?),
+                            "to" if to.is_none() => to = Some(map.next_value()?),
+                            "op" => return Err(A::Error::duplicate_field("op")),
+                            "path" => return Err(A::Error::duplicate_field("path")),
+                            "value" => return Err(A::Error::duplicate_field("value")),
+                            "to" => return Err(A::Error::duplicate_field("to")),
+                            _ => return Err(A::Error::unknown_field(&key, FIELDS)),
+                        }
+                    }
+                    let op = op.ok_or_else(|| A::Error::missing_field("op"))?;
+                    let path = path.ok_or_else(|| A::Error::missing_field("path"))?;
+                    let value = value.map(|v| v.0);
+                    let required = |value: Option<Value>| {
+                        value.ok_or_else(|| A::Error::missing_field("value"))
+                    };
+                    let unexpected = |field: &'static str, present: bool| {
+                        if present {
+                            Err(A::Error::custom(format!(
+                                "`{field}` is not used by the `{op}` op"
+                            )))
+                        } else {
+                            Ok(())
+                        }
+                    };
+                    match op.as_str() {
+                        "set" => {
+                            unexpected("to", to.is_some())?;
+                            Ok(Edit::Set {
+                                path,
+                                value: required(value)?,
+                            })
+                        }
+                        "append" => {
+                            unexpected("to", to.is_some())?;
+                            Ok(Edit::Append {
+                                path,
+                                value: required(value)?,
+                            })
+                        }
+                        "rename" => {
+                            unexpected("value", value.is_some())?;
+                            let to = to.ok_or_else(|| A::Error::missing_field("to"))?;
+                            Ok(Edit::Rename { path, to })
+                        }
+                        "remove" | "sort" => {
+                            unexpected("value", value.is_some())?;
+                            unexpected("to", to.is_some())?;
+                            if op == "remove" {
+                                Ok(Edit::Remove { path })
+                            } else {
+                                Ok(Edit::Sort { path })
+                            }
+                        }
+                        _ => Err(A::Error::unknown_variant(&op, OPS)),
+                    }
+                }
+            }
+
+            deserializer.deserialize_map(EditVisitor)
+        }
+    }
+}
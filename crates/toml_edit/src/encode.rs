@@ -1,13 +1,11 @@
 use std::borrow::Cow;
 use std::fmt::{Display, Formatter, Result, Write};
 
-use toml_datetime::Datetime;
-use toml_write::ToTomlValue as _;
 use toml_write::TomlWrite as _;
 
 use crate::inline_table::DEFAULT_INLINE_KEY_DECOR;
-use crate::key::Key;
-use crate::repr::{Formatted, Repr, ValueRepr};
+use crate::key::{Key, PathDecor};
+use crate::repr::{Formatted, ValueRepr};
 use crate::table::{
     DEFAULT_KEY_DECOR, DEFAULT_KEY_PATH_DECOR, DEFAULT_ROOT_DECOR, DEFAULT_TABLE_DECOR,
 };
@@ -26,53 +24,32 @@ pub(crate) fn encode_key(this: &Key, buf: &mut dyn Write, input: Option<&str>) -
         repr.encode(buf, input)?;
     } else {
         let repr = this.display_repr();
-        write!(buf, "{repr}")?;
+        buf.write_str(&repr)?;
     };
 
     Ok(())
 }
 
-fn encode_key_path(
-    this: &[Key],
-    mut buf: &mut dyn Write,
+pub(crate) fn encode_key_path_ref(
+    this: &[&Key],
+    buf: &mut dyn Write,
     input: Option<&str>,
     default_decor: (&str, &str),
 ) -> Result {
-    let leaf_decor = this.last().expect("always at least one key").leaf_decor();
-    for (i, key) in this.iter().enumerate() {
-        let dotted_decor = key.dotted_decor();
-
-        let first = i == 0;
-        let last = i + 1 == this.len();
-
-        if first {
-            leaf_decor.prefix_encode(buf, input, default_decor.0)?;
-        } else {
-            buf.key_sep()?;
-            dotted_decor.prefix_encode(buf, input, DEFAULT_KEY_PATH_DECOR.0)?;
-        }
-
-        encode_key(key, buf, input)?;
-
-        if last {
-            leaf_decor.suffix_encode(buf, input, default_decor.1)?;
-        } else {
-            dotted_decor.suffix_encode(buf, input, DEFAULT_KEY_PATH_DECOR.1)?;
-        }
-    }
-    Ok(())
+    let leaf = this.last().expect("always at least one key");
+    encode_key_path_decor(this, leaf.path_decor.as_deref(), buf, input, default_decor)
 }
 
-pub(crate) fn encode_key_path_ref(
+/// Like [`encode_key_path_ref`] with the decor of the line, if it was kept
+pub(crate) fn encode_key_path_decor(
     this: &[&Key],
+    path_decor: Option<&PathDecor>,
     mut buf: &mut dyn Write,
     input: Option<&str>,
     default_decor: (&str, &str),
 ) -> Result {
-    let leaf_decor = this.last().expect("always at least one key").leaf_decor();
-    for (i, key) in this.iter().enumerate() {
-        let dotted_decor = key.dotted_decor();
-
+    let (dotted_decor, leaf_decor) = PathDecor::for_path(path_decor, this);
+    for (i, (key, dotted_decor)) in this.iter().zip(dotted_decor).enumerate() {
         let first = i == 0;
         let last = i + 1 == this.len();
 
@@ -111,7 +88,7 @@ pub(crate) fn encode_formatted<T: ValueRepr>(
         repr.encode(buf, input)?;
     } else {
         let repr = this.display_repr();
-        write!(buf, "{repr}")?;
+        buf.write_str(&repr)?;
     };
 
     decor.suffix_encode(buf, input, default_decor.1)?;
@@ -166,7 +143,7 @@ pub(crate) fn encode_table(
         if i != 0 {
             buf.val_sep()?;
         }
-        let inner_decor = if i == len - 1 {
+        let inner_decor = if i == len - 1 && !this.trailing_comma() {
             DEFAULT_TRAILING_VALUE_DECOR
         } else {
             DEFAULT_VALUE_DECOR
@@ -175,6 +152,10 @@ pub(crate) fn encode_table(
         buf.keyval_sep()?;
         encode_value(value, buf, input, inner_decor)?;
     }
+    if this.trailing_comma() && len != 0 {
+        buf.val_sep()?;
+        this.trailing().encode_with_default(buf, input, "")?;
+    }
 
     buf.close_inline_table()?;
     decor.suffix_encode(buf, input, default_decor.1)?;
@@ -201,39 +182,185 @@ pub(crate) fn encode_value(
 
 impl Display for DocumentMut {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        // Written out in one piece, rather than growing the destination a fragment at a time
+        let mut buf = String::with_capacity(self.len_hint());
+        self.encode(&mut buf)?;
+        f.write_str(&buf)
+    }
+}
+
+impl DocumentMut {
+    fn encode(&self, buf: &mut dyn Write) -> Result {
+        if self.has_bom() {
+            buf.write_char('\u{feff}')?;
+        }
         let decor = self.decor();
-        decor.prefix_encode(f, None, DEFAULT_ROOT_DECOR.0)?;
-
-        let mut path = Vec::new();
-        let mut last_position = 0;
-        let mut tables = Vec::new();
-        visit_nested_tables(self.as_table(), &mut path, false, &mut |t, p, is_array| {
-            if let Some(pos) = t.position() {
-                last_position = pos;
+        decor.prefix_encode(buf, None, DEFAULT_ROOT_DECOR.0)?;
+        visit_lines(self, &mut |line| line.encode(buf))?;
+        decor.suffix_encode(buf, None, DEFAULT_ROOT_DECOR.1)?;
+        self.trailing().encode_with_default(buf, None, "")
+    }
+
+    /// About how long the text of the document is, from the length of the text it was parsed
+    /// from
+    ///
+    /// Edits since then are not accounted for, as that would take rendering it.  Documents that
+    /// were not parsed have no estimate.
+    pub(crate) fn len_hint(&self) -> usize {
+        self.trailing()
+            .source_len()
+            .or_else(|| first_source_len(self.as_table()))
+            .unwrap_or(0)
+    }
+}
+
+/// The length of the text the first key, going into tables, was parsed from
+fn first_source_len(table: &Table) -> Option<usize> {
+    let (key, item) = table.items.first()?;
+    let key_len = key.as_repr().and_then(|repr| repr.as_raw().source_len());
+    key_len.or_else(|| match item {
+        Item::Table(table) => first_source_len(table),
+        Item::ArrayOfTables(tables) => first_source_len(tables.get(0)?),
+        Item::None | Item::Value(_) => None,
+    })
+}
+
+/// A line of a document as it is written, see [`visit_lines`]
+pub(crate) enum Line<'d, 'p> {
+    /// A `[table]` or `[[array]]` header, with the decor it gets by default where it is
+    Header {
+        table: &'d Table,
+        path: &'p [&'d Key],
+        is_array_of_tables: bool,
+        default_decor: (&'static str, &'static str),
+    },
+    KeyValue {
+        path: &'p [&'d Key],
+        value: &'d Value,
+    },
+}
+
+impl Line<'_, '_> {
+    pub(crate) fn encode(&self, mut buf: &mut dyn Write) -> Result {
+        match *self {
+            Line::Header {
+                table,
+                path,
+                is_array_of_tables,
+                default_decor,
+            } => {
+                table.decor.prefix_encode(buf, None, default_decor.0)?;
+                if is_array_of_tables {
+                    buf.open_array_of_tables_header()?;
+                } else {
+                    buf.open_table_header()?;
+                }
+                encode_key_path_decor(
+                    path,
+                    table.header_decor.as_deref(),
+                    buf,
+                    None,
+                    DEFAULT_KEY_PATH_DECOR,
+                )?;
+                if is_array_of_tables {
+                    buf.close_array_of_tables_header()?;
+                } else {
+                    buf.close_table_header()?;
+                }
+                table.decor.suffix_encode(buf, None, default_decor.1)?;
+                writeln!(buf)
             }
-            tables.push((last_position, t, p.clone(), is_array));
-            Ok(())
-        })
-        .unwrap();
+            Line::KeyValue { path, value } => {
+                encode_key_path_ref(path, buf, None, DEFAULT_KEY_DECOR)?;
+                buf.keyval_sep()?;
+                encode_value(value, buf, None, DEFAULT_VALUE_DECOR)?;
+                writeln!(buf)
+            }
+        }
+    }
+}
 
-        tables.sort_by_key(|&(id, _, _, _)| id);
-        let mut first_table = true;
-        for (_, table, path, is_array) in tables {
-            visit_table(f, None, table, &path, is_array, &mut first_table)?;
+/// Each header and key/value line of `doc`, in the order they are written
+pub(crate) fn visit_lines<'d>(
+    doc: &'d DocumentMut,
+    callback: &mut dyn FnMut(Line<'d, '_>) -> Result,
+) -> Result {
+    visit_sections(doc, &mut |table, header| {
+        if let Some(header) = header {
+            callback(header)?;
+        }
+        visit_body(table, callback)
+    })
+}
+
+/// Each table of `doc` that is not dotted, in the order they are written, with its header line
+/// unless that is hidden
+///
+/// The table's key/value lines, see [`visit_body`], come right after.
+pub(crate) fn visit_sections<'d>(
+    doc: &'d DocumentMut,
+    callback: &mut dyn FnMut(&'d Table, Option<Line<'d, '_>>) -> Result,
+) -> Result {
+    let mut path = Vec::new();
+    let mut last_position = 0;
+    let mut tables = Vec::new();
+    visit_nested_tables(doc.as_table(), &mut path, false, &mut |t, p, is_array| {
+        if let Some(pos) = t.position() {
+            last_position = pos;
+        }
+        tables.push((last_position, t, p.to_vec(), is_array));
+        Ok(())
+    })
+    .unwrap();
+
+    tables.sort_by_key(|&(id, _, _, _)| id);
+    let mut first_table = true;
+    for (_, table, path, is_array_of_tables) in tables {
+        let has_children = has_values(table);
+        // We are intentionally hiding implicit tables without any tables nested under them (ie
+        // `table.is_empty()` which is in contrast to `table.get_values().is_empty()`).  We are
+        // trusting the user that an empty implicit table is not semantically meaningful
+        //
+        // This allows a user to delete all tables under this implicit table and the implicit table
+        // will disappear.
+        //
+        // However, this means that users need to take care in deciding what tables get marked as
+        // implicit.
+        let is_visible_std_table = !table.implicit || has_children;
+
+        let mut header = None;
+        if path.is_empty() {
+            // don't print header for the root node
+            if has_children {
+                first_table = false;
+            }
+        } else if is_array_of_tables || is_visible_std_table {
+            let default_decor = if first_table {
+                first_table = false;
+                ("", DEFAULT_TABLE_DECOR.1)
+            } else {
+                DEFAULT_TABLE_DECOR
+            };
+            header = Some(Line::Header {
+                table,
+                path: &path,
+                is_array_of_tables,
+                default_decor,
+            });
         }
-        decor.suffix_encode(f, None, DEFAULT_ROOT_DECOR.1)?;
-        self.trailing().encode_with_default(f, None, "")
+        callback(table, header)?;
     }
+    Ok(())
 }
 
 fn visit_nested_tables<'t, F>(
     table: &'t Table,
-    path: &mut Vec<Key>,
+    path: &mut Vec<&'t Key>,
     is_array_of_tables: bool,
     callback: &mut F,
 ) -> Result
 where
-    F: FnMut(&'t Table, &Vec<Key>, bool) -> Result,
+    F: FnMut(&'t Table, &[&'t Key], bool) -> Result,
 {
     if !table.is_dotted() {
         callback(table, path, is_array_of_tables)?;
@@ -242,14 +369,12 @@ where
     for (key, value) in table.items.iter() {
         match value {
             Item::Table(ref t) => {
-                let key = key.clone();
                 path.push(key);
                 visit_nested_tables(t, path, false, callback)?;
                 path.pop();
             }
             Item::ArrayOfTables(ref a) => {
                 for t in a.iter() {
-                    let key = key.clone();
                     path.push(key);
                     visit_nested_tables(t, path, true, callback)?;
                     path.pop();
@@ -261,102 +386,75 @@ where
     Ok(())
 }
 
-fn visit_table(
-    mut buf: &mut dyn Write,
-    input: Option<&str>,
-    table: &Table,
-    path: &[Key],
-    is_array_of_tables: bool,
-    first_table: &mut bool,
+/// The key/value lines of `table`, including those of the dotted tables in it
+pub(crate) fn visit_body<'d>(
+    table: &'d Table,
+    callback: &mut dyn FnMut(Line<'d, '_>) -> Result,
 ) -> Result {
-    let children = table.get_values();
-    // We are intentionally hiding implicit tables without any tables nested under them (ie
-    // `table.is_empty()` which is in contrast to `table.get_values().is_empty()`).  We are
-    // trusting the user that an empty implicit table is not semantically meaningful
-    //
-    // This allows a user to delete all tables under this implicit table and the implicit table
-    // will disappear.
-    //
-    // However, this means that users need to take care in deciding what tables get marked as
-    // implicit.
-    let is_visible_std_table = !(table.implicit && children.is_empty());
-
-    if path.is_empty() {
-        // don't print header for the root node
-        if !children.is_empty() {
-            *first_table = false;
-        }
-    } else if is_array_of_tables {
-        let default_decor = if *first_table {
-            *first_table = false;
-            ("", DEFAULT_TABLE_DECOR.1)
-        } else {
-            DEFAULT_TABLE_DECOR
-        };
-        table.decor.prefix_encode(buf, input, default_decor.0)?;
-        buf.open_array_of_tables_header()?;
-        encode_key_path(path, buf, input, DEFAULT_KEY_PATH_DECOR)?;
-        buf.close_array_of_tables_header()?;
-        table.decor.suffix_encode(buf, input, default_decor.1)?;
-        writeln!(buf)?;
-    } else if is_visible_std_table {
-        let default_decor = if *first_table {
-            *first_table = false;
-            ("", DEFAULT_TABLE_DECOR.1)
-        } else {
-            DEFAULT_TABLE_DECOR
-        };
-        table.decor.prefix_encode(buf, input, default_decor.0)?;
-        buf.open_table_header()?;
-        encode_key_path(path, buf, input, DEFAULT_KEY_PATH_DECOR)?;
-        buf.close_table_header()?;
-        table.decor.suffix_encode(buf, input, default_decor.1)?;
-        writeln!(buf)?;
-    }
-    // print table body
-    for (key_path, value) in children {
-        encode_key_path_ref(&key_path, buf, input, DEFAULT_KEY_DECOR)?;
-        buf.keyval_sep()?;
-        encode_value(value, buf, input, DEFAULT_VALUE_DECOR)?;
-        writeln!(buf)?;
-    }
-    Ok(())
-}
-
-impl ValueRepr for String {
-    fn to_repr(&self) -> Repr {
-        let output = toml_write::TomlStringBuilder::new(self.as_str())
-            .as_default()
-            .to_toml_value();
-        Repr::new_unchecked(output)
-    }
+    visit_values(table, &mut Vec::new(), &mut |key_path, value| {
+        callback(Line::KeyValue {
+            path: key_path,
+            value,
+        })
+    })
 }
 
-impl ValueRepr for i64 {
-    fn to_repr(&self) -> Repr {
-        let repr = self.to_toml_value();
-        Repr::new_unchecked(repr)
-    }
+/// Whether [`visit_body`] has any lines for `table`
+fn has_values(table: &Table) -> bool {
+    table.items.values().any(|item| match item {
+        Item::Table(table) if table.is_dotted() => has_values(table),
+        Item::Value(Value::InlineTable(table)) if table.is_dotted() => has_inline_values(table),
+        Item::Value(_) => true,
+        _ => false,
+    })
 }
 
-impl ValueRepr for f64 {
-    fn to_repr(&self) -> Repr {
-        let repr = self.to_toml_value();
-        Repr::new_unchecked(repr)
-    }
+fn has_inline_values(table: &InlineTable) -> bool {
+    table.items.values().any(|item| match item {
+        Item::Value(Value::InlineTable(table)) if table.is_dotted() => has_inline_values(table),
+        Item::Value(_) => true,
+        _ => false,
+    })
 }
 
-impl ValueRepr for bool {
-    fn to_repr(&self) -> Repr {
-        let repr = self.to_toml_value();
-        Repr::new_unchecked(repr)
+/// Like [`Table::get_values`] but reusing `path` rather than allocating one per value
+fn visit_values<'t>(
+    table: &'t Table,
+    path: &mut Vec<&'t Key>,
+    callback: &mut dyn FnMut(&[&'t Key], &'t Value) -> Result,
+) -> Result {
+    for (key, item) in table.items.iter() {
+        path.push(key);
+        match item {
+            Item::Table(table) if table.is_dotted() => visit_values(table, path, callback)?,
+            Item::Value(Value::InlineTable(table)) if table.is_dotted() => {
+                visit_inline_values(table, path, callback)?;
+            }
+            Item::Value(value) => callback(path, value)?,
+            _ => {}
+        }
+        path.pop();
     }
+    Ok(())
 }
 
-impl ValueRepr for Datetime {
-    fn to_repr(&self) -> Repr {
-        Repr::new_unchecked(self.to_string())
+fn visit_inline_values<'t>(
+    table: &'t InlineTable,
+    path: &mut Vec<&'t Key>,
+    callback: &mut dyn FnMut(&[&'t Key], &'t Value) -> Result,
+) -> Result {
+    for (key, item) in table.items.iter() {
+        path.push(key);
+        match item {
+            Item::Value(Value::InlineTable(table)) if table.is_dotted() => {
+                visit_inline_values(table, path, callback)?;
+            }
+            Item::Value(value) => callback(path, value)?,
+            _ => {}
+        }
+        path.pop();
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -407,4 +505,20 @@ key:
             });
         }
     }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn len_hint() {
+        let input = "[[bin]]\nname = 'a'\n";
+        let mut doc = input.parse::<DocumentMut>().unwrap();
+        assert_eq!(doc.len_hint(), input.len());
+        doc.trailing = "# after\n".into();
+        assert_eq!(doc.len_hint(), input.len());
+        doc["bin"][0]["name"] = crate::value("abc");
+        assert_eq!(doc.len_hint(), input.len());
+
+        let mut doc = DocumentMut::new();
+        doc["a"] = crate::value(1);
+        assert_eq!(doc.len_hint(), 0);
+    }
 }
@@ -6,58 +6,44 @@ use std::fmt::{Display, Formatter, Result};
 pub struct TomlError {
     message: String,
     raw: Option<std::sync::Arc<str>>,
-    keys: Vec<String>,
+    keys: Vec<crate::PathSegment>,
     span: Option<std::ops::Range<usize>>,
+    context: Option<std::ops::Range<usize>>,
+    fix: Option<String>,
+    #[cfg(feature = "parse")]
+    source_id: crate::SourceId,
 }
 
 impl TomlError {
     #[cfg(feature = "parse")]
     pub(crate) fn new(raw: std::sync::Arc<str>, error: toml_parse::ParseError) -> Self {
-        let mut message = String::new();
-        message.push_str(error.description());
-        if let Some(expected) = error.expected() {
-            message.push_str(", expected ");
-            if expected.is_empty() {
-                message.push_str("nothing");
-            } else {
-                for (i, expected) in expected.iter().enumerate() {
-                    if i != 0 {
-                        message.push_str(", ");
-                    }
-                    match expected {
-                        toml_parse::Expected::Literal(desc) => {
-                            message.push_str(&render_literal(desc));
-                        }
-                        toml_parse::Expected::Description(desc) => message.push_str(desc),
-                        _ => message.push_str("etc"),
-                    }
-                }
-            }
-        }
-
-        let span = error.unexpected().map(|span| span.start()..span.end());
-
-        Self {
-            message,
-            raw: Some(raw),
-            keys: Vec::new(),
-            span,
-        }
+        let mut err = Self::from(error);
+        err.raw = Some(raw);
+        err
     }
 
-    #[cfg(feature = "serde")]
+    #[cfg(any(feature = "parse", feature = "serde"))]
     pub(crate) fn custom(message: String, span: Option<std::ops::Range<usize>>) -> Self {
         Self {
             message,
             raw: None,
             keys: Vec::new(),
             span,
+            context: None,
+            fix: None,
+            #[cfg(feature = "parse")]
+            source_id: Default::default(),
         }
     }
 
     #[cfg(feature = "serde")]
     pub(crate) fn add_key(&mut self, key: String) {
-        self.keys.insert(0, key);
+        self.keys.insert(0, crate::PathSegment::Key(key));
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn add_index(&mut self, index: usize) {
+        self.keys.insert(0, crate::PathSegment::Index(index));
     }
 
     /// What went wrong
@@ -66,10 +52,64 @@ impl TomlError {
     }
 
     /// The start/end index into the original document where the error occurred
+    ///
+    /// See [`TomlError::source_id`] for which document that is.
     pub fn span(&self) -> Option<std::ops::Range<usize>> {
         self.span.clone()
     }
 
+    /// The start/end index into the original document of a related location
+    ///
+    /// For example, for a duplicate key, this is where the key was first defined.
+    pub fn context_span(&self) -> Option<std::ops::Range<usize>> {
+        self.context.clone()
+    }
+
+    /// What the [context][TomlError::context_span] could be replaced with to resolve the error
+    ///
+    /// For example, `42` for the leading zero in `042`, for editors to offer as a quick fix.
+    pub fn fix(&self) -> Option<&str> {
+        self.fix.as_deref()
+    }
+
+    /// Which document the [spans][TomlError::span] are in, when reporting on several
+    #[cfg(feature = "parse")]
+    pub fn source_id(&self) -> crate::SourceId {
+        self.source_id
+    }
+
+    /// Record which document the [spans][TomlError::span] are in
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{DocumentMut, SourceId};
+    ///
+    /// let files = ["a = 1", "b = "];
+    /// let errors = files
+    ///     .iter()
+    ///     .enumerate()
+    ///     .filter_map(|(i, file)| {
+    ///         let id = SourceId::new(i as u32);
+    ///         file.parse::<DocumentMut>().map_err(|e| e.with_source_id(id)).err()
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(errors[0].source_id(), SourceId::new(1));
+    /// assert_eq!(errors[0].span(), Some(4..4));
+    /// # }
+    /// ```
+    #[cfg(feature = "parse")]
+    pub fn with_source_id(mut self, source_id: crate::SourceId) -> Self {
+        self.source_id = source_id;
+        self
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn set_message(&mut self, message: String) {
+        self.message = message;
+    }
+
     #[cfg(feature = "serde")]
     pub(crate) fn set_span(&mut self, span: Option<std::ops::Range<usize>>) {
         self.span = span;
@@ -81,14 +121,44 @@ impl TomlError {
     }
 }
 
-fn render_literal(literal: &str) -> String {
-    match literal {
-        "\n" => "newline".to_owned(),
-        "`" => "'`'".to_owned(),
-        s if s.chars().all(|c| c.is_ascii_control()) => {
-            format!("`{}`", s.escape_debug())
+/// Convert an error from [`toml_parse`], keeping its spans
+///
+/// The input isn't known to the error, so the [`Display`] of the result can't show where in it
+/// the error is.
+///
+/// # Examples
+///
+/// ```rust
+/// use toml_edit::parser::{parse_document, Event, ParseError, Source};
+/// use toml_edit::TomlError;
+///
+/// fn check(input: &str) -> Result<(), TomlError> {
+///     let tokens = Source::new(input).lex().into_vec();
+///     let mut events = Vec::<Event>::new();
+///     let mut error = None::<ParseError>;
+///     parse_document(&tokens, &mut events, &mut error);
+///     match error {
+///         Some(error) => Err(error)?,
+///         None => Ok(()),
+///     }
+/// }
+///
+/// let err = check("[a\n").unwrap_err();
+/// assert_eq!(err.message(), "unclosed table, expected `]`");
+/// assert_eq!(err.span(), Some(2..2));
+/// ```
+#[cfg(feature = "parse")]
+impl From<toml_parse::ParseError> for TomlError {
+    fn from(error: toml_parse::ParseError) -> Self {
+        Self {
+            message: error.to_string(),
+            raw: None,
+            keys: Vec::new(),
+            span: error.unexpected().map(|span| span.start()..span.end()),
+            context: error.context().map(|span| span.start()..span.end()),
+            fix: error.fix().map(ToOwned::to_owned),
+            source_id: error.source_id(),
         }
-        s => format!("`{s}`"),
     }
 }
 
@@ -106,10 +176,7 @@ fn render_literal(literal: &str) -> String {
 /// While parsing a Date-Time
 impl Display for TomlError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let mut context = false;
         if let (Some(raw), Some(span)) = (&self.raw, self.span()) {
-            context = true;
-
             let (line, column) = translate_position(raw.as_bytes(), span.start);
             let line_num = line + 1;
             let col_num = column + 1;
@@ -147,8 +214,16 @@ impl Display for TomlError {
             writeln!(f)?;
         }
         writeln!(f, "{}", self.message)?;
-        if !context && !self.keys.is_empty() {
-            writeln!(f, "in `{}`", self.keys.join("."))?;
+        if !self.keys.is_empty() {
+            write!(f, "in `")?;
+            for (i, segment) in self.keys.iter().enumerate() {
+                match segment {
+                    crate::PathSegment::Key(key) if i == 0 => write!(f, "{key}")?,
+                    crate::PathSegment::Key(key) => write!(f, ".{key}")?,
+                    crate::PathSegment::Index(index) => write!(f, "[{index}]")?,
+                }
+            }
+            writeln!(f, "`")?;
         }
 
         Ok(())
@@ -161,6 +236,116 @@ impl StdError for TomlError {
     }
 }
 
+/// A value can't be written in the requested encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// Control characters, other than tab and newline, must be escaped
+    ControlCharacter(char),
+    /// Newlines require a multi-line string
+    Newline,
+    /// The content contains the closing delimiter, like `'` or `'''`
+    Delimiter(&'static str),
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::ControlCharacter(c) => {
+                write!(
+                    f,
+                    "control character `{}` must be escaped",
+                    c.escape_debug()
+                )
+            }
+            Self::Newline => "newlines require a multi-line string".fmt(f),
+            Self::Delimiter(d) => write!(f, "`{d}` would end the string"),
+        }
+    }
+}
+
+impl StdError for EncodeError {}
+
+/// An item would have silently replaced an existing item, see [`Table::try_insert`][crate::Table::try_insert]
+#[derive(Debug, Clone)]
+pub struct InsertError {
+    key: String,
+    existing: &'static str,
+    implicit: bool,
+    span: Option<std::ops::Range<usize>>,
+    item: crate::Item,
+}
+
+impl InsertError {
+    pub(crate) fn new(key: &crate::Key, existing: &crate::Item, item: crate::Item) -> Self {
+        Self {
+            key: key.get().to_owned(),
+            existing: existing.type_name(),
+            implicit: existing
+                .as_table()
+                .map(|t| t.is_implicit())
+                .unwrap_or(false),
+            span: existing.span().or_else(|| key.span()),
+            item,
+        }
+    }
+
+    /// The key being inserted
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The type of the existing item, see [`Item::type_name`][crate::Item::type_name]
+    pub fn existing_type(&self) -> &'static str {
+        self.existing
+    }
+
+    /// The type of the rejected item, see [`Item::type_name`][crate::Item::type_name]
+    pub fn new_type(&self) -> &'static str {
+        self.item.type_name()
+    }
+
+    /// Whether the existing item is an implicit table, see [`Table::is_implicit`][crate::Table::is_implicit]
+    pub fn is_implicit_table(&self) -> bool {
+        self.implicit
+    }
+
+    /// The location of the existing item within the original document
+    ///
+    /// This generally requires a [`Document`][crate::Document].
+    pub fn span(&self) -> Option<std::ops::Range<usize>> {
+        self.span.clone()
+    }
+
+    /// The rejected item
+    pub fn into_item(self) -> crate::Item {
+        self.item
+    }
+}
+
+impl Display for InsertError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.implicit {
+            write!(
+                f,
+                "cannot replace implicit table `{}` with {}",
+                self.key,
+                self.new_type()
+            )
+        } else {
+            write!(
+                f,
+                "cannot replace {} `{}` with {}",
+                self.existing,
+                self.key,
+                self.new_type()
+            )
+        }
+    }
+}
+
+impl StdError for InsertError {}
+
 fn translate_position(input: &[u8], index: usize) -> (usize, usize) {
     if input.is_empty() {
         return (0, index);
@@ -194,6 +379,7 @@ fn translate_position(input: &[u8], index: usize) -> (usize, usize) {
 pub(crate) struct TomlSink<'i, S> {
     source: toml_parse::Source<'i>,
     raw: Option<std::sync::Arc<str>>,
+    spec: toml_parse::TomlSpec,
     sink: S,
 }
 
@@ -203,10 +389,17 @@ impl<'i, S: Default> TomlSink<'i, S> {
         Self {
             source,
             raw: None,
+            spec: Default::default(),
             sink: Default::default(),
         }
     }
 
+    /// Skip errors for what `spec` allows
+    pub(crate) fn with_spec(mut self, spec: toml_parse::TomlSpec) -> Self {
+        self.spec = spec;
+        self
+    }
+
     pub(crate) fn into_inner(self) -> S {
         self.sink
     }
@@ -215,10 +408,14 @@ impl<'i, S: Default> TomlSink<'i, S> {
 #[cfg(feature = "parse")]
 impl<'i> toml_parse::ErrorSink for TomlSink<'i, Option<TomlError>> {
     fn report_error(&mut self, error: toml_parse::ParseError) {
-        if self.sink.is_none() {
+        if self.sink.is_none()
+            && error.severity() == toml_parse::Severity::Error
+            && error.applies_to(self.spec)
+        {
             let raw = self
                 .raw
                 .get_or_insert_with(|| std::sync::Arc::from(self.source.input()));
+            let error = error.with_source_id(self.source.id());
             let error = TomlError::new(raw.clone(), error);
             self.sink = Some(error);
         }
@@ -228,9 +425,13 @@ impl<'i> toml_parse::ErrorSink for TomlSink<'i, Option<TomlError>> {
 #[cfg(feature = "parse")]
 impl<'i> toml_parse::ErrorSink for TomlSink<'i, Vec<TomlError>> {
     fn report_error(&mut self, error: toml_parse::ParseError) {
+        if error.severity() != toml_parse::Severity::Error || !error.applies_to(self.spec) {
+            return;
+        }
         let raw = self
             .raw
             .get_or_insert_with(|| std::sync::Arc::from(self.source.input()));
+        let error = error.with_source_id(self.source.id());
         let error = TomlError::new(raw.clone(), error);
         self.sink.push(error);
     }
@@ -0,0 +1,119 @@
+use crate::{DocumentMut, Item, PathSegment, TableLike, Value};
+
+/// How [`DocumentMut::flatten`] handles arrays, including arrays of tables
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FlattenArrays {
+    /// Flatten each element, with its index in the path
+    #[default]
+    Index,
+    /// Keep arrays as one value, skipping arrays of tables
+    Whole,
+    /// Skip arrays and arrays of tables
+    Skip,
+}
+
+impl DocumentMut {
+    /// Each value with its path from the root, in document order
+    ///
+    /// Tables and inline tables are flattened into the paths of their values, so they, like
+    /// empty arrays, have no entry of their own.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{DocumentMut, FlattenArrays};
+    ///
+    /// let doc = "
+    /// name = 'app'
+    /// server = { port = 8080 }
+    /// [log]
+    /// targets = ['stderr', 'file']
+    /// ".parse::<DocumentMut>().unwrap();
+    ///
+    /// let dotted = |arrays| {
+    ///     doc.flatten(arrays)
+    ///         .map(|(path, value)| {
+    ///             let path = path.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    ///             format!("{}={}", path.join("."), value.to_string().trim())
+    ///         })
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(
+    ///     dotted(FlattenArrays::Index),
+    ///     ["name='app'", "server.port=8080", "log.targets.0='stderr'", "log.targets.1='file'"]
+    /// );
+    /// assert_eq!(
+    ///     dotted(FlattenArrays::Whole),
+    ///     ["name='app'", "server.port=8080", "log.targets=['stderr', 'file']"]
+    /// );
+    /// assert_eq!(dotted(FlattenArrays::Skip), ["name='app'", "server.port=8080"]);
+    /// # }
+    /// # }
+    /// ```
+    pub fn flatten(
+        &self,
+        arrays: FlattenArrays,
+    ) -> impl Iterator<Item = (Vec<PathSegment>, &Value)> + '_ {
+        let mut flattener = Flattener {
+            arrays,
+            path: Vec::new(),
+            values: Vec::new(),
+        };
+        flattener.table(self.as_table());
+        flattener.values.into_iter()
+    }
+}
+
+struct Flattener<'d> {
+    arrays: FlattenArrays,
+    path: Vec<PathSegment>,
+    values: Vec<(Vec<PathSegment>, &'d Value)>,
+}
+
+impl<'d> Flattener<'d> {
+    fn table(&mut self, table: &'d dyn TableLike) {
+        for (key, item) in table.iter() {
+            self.path.push(PathSegment::Key(key.to_owned()));
+            self.item(item);
+            self.path.pop();
+        }
+    }
+
+    fn item(&mut self, item: &'d Item) {
+        match item {
+            Item::None => {}
+            Item::Value(value) => self.value(value),
+            Item::Table(table) => self.table(table),
+            Item::ArrayOfTables(array) => {
+                if self.arrays == FlattenArrays::Index {
+                    for (i, table) in array.iter().enumerate() {
+                        self.path.push(PathSegment::Index(i));
+                        self.table(table);
+                        self.path.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    fn value(&mut self, value: &'d Value) {
+        match value {
+            Value::InlineTable(table) => self.table(table),
+            Value::Array(array) => match self.arrays {
+                FlattenArrays::Index => {
+                    for (i, value) in array.iter().enumerate() {
+                        self.path.push(PathSegment::Index(i));
+                        self.value(value);
+                        self.path.pop();
+                    }
+                }
+                FlattenArrays::Whole => self.values.push((self.path.clone(), value)),
+                FlattenArrays::Skip => {}
+            },
+            _ => self.values.push((self.path.clone(), value)),
+        }
+    }
+}
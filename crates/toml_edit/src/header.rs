@@ -0,0 +1,152 @@
+use crate::{DocumentMut, Item, KeyMut, Table};
+
+/// Table header formatting
+///
+/// The keys in a header like `[ foo . bar ]` are owned by their parent tables, so their
+/// formatting is reached through the document:
+/// - The last key's [leaf decor][crate::Key::leaf_decor] is the spacing inside the brackets
+/// - The other keys' [dotted decor][crate::Key::dotted_decor] is the spacing around the dots
+///
+/// A parsed header that is spaced differently than its keys keeps its own spacing until the
+/// keys are changed through [`DocumentMut::table_header_keys_mut`].
+impl DocumentMut {
+    /// The header for the table at `path`, as it will be written
+    ///
+    /// This does not include the table's [`Decor`][crate::Decor], like comments before the
+    /// header.  Returns `None` if `path` is not a standard table or array of tables or the table
+    /// is [implicit][crate::Table::is_implicit].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "[ foo . bar ]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert_eq!(doc.table_header(&["foo", "bar"]).unwrap(), "[ foo . bar ]");
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn table_header(&self, path: &[&str]) -> Option<String> {
+        let mut keys = Vec::with_capacity(path.len());
+        let mut table = self.as_table();
+        let mut is_array = false;
+        for (i, key) in path.iter().enumerate() {
+            let (key, item) = table.get_key_value(key)?;
+            keys.push(key);
+            let last = i + 1 == path.len();
+            table = match item {
+                Item::Table(child) if !child.is_dotted() => child,
+                Item::ArrayOfTables(children) => {
+                    is_array = last;
+                    children.iter().last()?
+                }
+                _ => return None,
+            };
+        }
+        if keys.is_empty() || table.is_implicit() {
+            return None;
+        }
+
+        let mut header = String::new();
+        let (open, close) = if is_array { ("[[", "]]") } else { ("[", "]") };
+        header.push_str(open);
+        crate::encode::encode_key_path_decor(
+            &keys,
+            table.header_decor.as_deref(),
+            &mut header,
+            None,
+            crate::table::DEFAULT_KEY_PATH_DECOR,
+        )
+        .ok()?;
+        header.push_str(close);
+        Some(header)
+    }
+
+    /// The keys making up the header for the table at `path`, for adjusting their spacing
+    ///
+    /// A header spaced unlike other headers through the same tables, like `[ foo . bar . baz ]`
+    /// after `[foo.bar]`, keeps its own spacing when parsed.  That is dropped here, so the header
+    /// is written from these keys.
+    ///
+    /// Returns `None` if `path` is not a standard table or array of tables.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "[foo.bar]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let mut keys = doc.table_header_keys_mut(&["foo", "bar"]).unwrap();
+    /// keys[0].dotted_decor_mut().set_suffix(" ");
+    /// keys[1].dotted_decor_mut().set_prefix(" ");
+    /// keys[1].leaf_decor_mut().set_prefix(" ");
+    /// keys[1].leaf_decor_mut().set_suffix(" ");
+    /// assert_eq!(doc.to_string(), "[ foo . bar ]\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn table_header_keys_mut(&mut self, path: &[&str]) -> Option<Vec<KeyMut<'_>>> {
+        let mut keys = Vec::with_capacity(path.len());
+        let mut table = self.as_table_mut();
+        for key in path {
+            let (key, item) = table.iter_mut().find(|(k, _)| k.get() == *key)?;
+            keys.push(key);
+            table = match item {
+                Item::Table(child) if !child.is_dotted() => child,
+                Item::ArrayOfTables(children) => children.iter_mut().last()?,
+                _ => return None,
+            };
+        }
+        if keys.is_empty() {
+            return None;
+        }
+        table.header_decor = None;
+        Some(keys)
+    }
+
+    /// Remove custom spacing within every table header, like `[ foo . bar ]` to `[foo.bar]`
+    ///
+    /// Comments and whitespace around headers and the formatting of key/value pairs are
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "[ foo . bar ]  # comment\nkey . sub = 1\n[[ bin ]]\n"
+    ///     .parse::<toml_edit::DocumentMut>()
+    ///     .unwrap();
+    /// doc.normalize_table_headers();
+    /// assert_eq!(doc.to_string(), "[foo.bar]  # comment\nkey . sub = 1\n[[bin]]\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn normalize_table_headers(&mut self) {
+        normalize_headers(self.as_table_mut());
+    }
+}
+
+fn normalize_headers(table: &mut Table) {
+    for (mut key, item) in table.iter_mut() {
+        match item {
+            Item::Table(child) if !child.is_dotted() => {
+                clear_header_key(&mut key);
+                child.header_decor = None;
+                normalize_headers(child);
+            }
+            Item::ArrayOfTables(children) => {
+                clear_header_key(&mut key);
+                for child in children.iter_mut() {
+                    child.header_decor = None;
+                    normalize_headers(child);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn clear_header_key(key: &mut KeyMut<'_>) {
+    key.leaf_decor_mut().clear();
+    key.dotted_decor_mut().clear();
+}
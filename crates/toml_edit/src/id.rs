@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{Array, DocumentMut, InlineTable, Item, PathSegment, Table, Value};
+
+/// The identity of a key/value pair, see [`DocumentMut::assign_ids`]
+///
+/// IDs are unique within the process and stay with the [`Key`][crate::Key] as the document is
+/// edited, so they can be used to correlate findings across passes where paths may change.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemId(NonZeroU64);
+
+impl ItemId {
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+        Self(NonZeroU64::new(id).expect("starts at 1 and won't overflow"))
+    }
+}
+
+impl DocumentMut {
+    /// Give every key/value pair without an [`ItemId`] a new one
+    ///
+    /// This includes keys in arrays of tables and in inline tables, including inline tables
+    /// within arrays.  Key/value pairs added later, like with [`Table::insert`], do not have an
+    /// ID until this is called again.  A cloned [`Key`][crate::Key] keeps its ID, so copying a
+    /// key/value pair within the document duplicates the ID.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{DocumentMut, ItemMetadata, Key, PathSegment};
+    ///
+    /// let mut doc = "[dependencies]\nserde_json = '1'\n".parse::<DocumentMut>().unwrap();
+    /// doc.assign_ids();
+    ///
+    /// // Analysis
+    /// let mut findings = ItemMetadata::new();
+    /// let id = doc["dependencies"].as_table().unwrap().key("serde_json").unwrap().id().unwrap();
+    /// findings.insert(id, "outdated");
+    ///
+    /// // An unrelated edit
+    /// let deps = doc["dependencies"].as_table_mut().unwrap();
+    /// let (key, item) = deps.remove_entry("serde_json").unwrap();
+    /// deps.insert_formatted(&Key::new("json").with_id(key.id()), item);
+    ///
+    /// // Correlate
+    /// let (id, finding) = findings.iter().next().unwrap();
+    /// let path = doc.path_of(*id).unwrap();
+    /// assert_eq!(path, [PathSegment::Key("dependencies".into()), PathSegment::Key("json".into())]);
+    /// assert_eq!(*finding, "outdated");
+    /// # }
+    /// ```
+    pub fn assign_ids(&mut self) {
+        assign_table(self.as_table_mut());
+    }
+
+    /// Find the key/value pair with `id`
+    ///
+    /// Returns the first match in document order or `None` if it was removed.
+    pub fn path_of(&self, id: ItemId) -> Option<Vec<PathSegment>> {
+        let mut path = Vec::new();
+        find_table(self.as_table(), id, &mut path).then_some(path)
+    }
+}
+
+fn assign_table(table: &mut Table) {
+    use indexmap::map::MutableKeys;
+    for (key, item) in table.items.iter_mut2() {
+        if key.id.is_none() {
+            key.id = Some(ItemId::next());
+        }
+        match item {
+            Item::None => {}
+            Item::Value(value) => assign_value(value),
+            Item::Table(table) => assign_table(table),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter_mut() {
+                    assign_table(table);
+                }
+            }
+        }
+    }
+}
+
+fn assign_value(value: &mut Value) {
+    use indexmap::map::MutableKeys;
+    match value {
+        Value::Array(array) => {
+            for value in array.iter_mut() {
+                assign_value(value);
+            }
+        }
+        Value::InlineTable(table) => {
+            for (key, item) in table.items.iter_mut2() {
+                if key.id.is_none() {
+                    key.id = Some(ItemId::next());
+                }
+                if let Item::Value(value) = item {
+                    assign_value(value);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn find_table(table: &Table, id: ItemId, path: &mut Vec<PathSegment>) -> bool {
+    for (key, item) in table.items.iter() {
+        path.push(PathSegment::Key(key.get().to_owned()));
+        if key.id == Some(id) {
+            return true;
+        }
+        let found = match item {
+            Item::None => false,
+            Item::Value(value) => find_value(value, id, path),
+            Item::Table(table) => find_table(table, id, path),
+            Item::ArrayOfTables(array) => array.iter().enumerate().any(|(i, table)| {
+                path.push(PathSegment::Index(i));
+                let found = find_table(table, id, path);
+                if !found {
+                    path.pop();
+                }
+                found
+            }),
+        };
+        if found {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+fn find_value(value: &Value, id: ItemId, path: &mut Vec<PathSegment>) -> bool {
+    match value {
+        Value::Array(array) => find_array(array, id, path),
+        Value::InlineTable(table) => find_inline_table(table, id, path),
+        _ => false,
+    }
+}
+
+fn find_array(array: &Array, id: ItemId, path: &mut Vec<PathSegment>) -> bool {
+    for (i, value) in array.iter().enumerate() {
+        path.push(PathSegment::Index(i));
+        if find_value(value, id, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+fn find_inline_table(table: &InlineTable, id: ItemId, path: &mut Vec<PathSegment>) -> bool {
+    for (key, item) in table.items.iter() {
+        path.push(PathSegment::Key(key.get().to_owned()));
+        if key.id == Some(id) {
+            return true;
+        }
+        if let Item::Value(value) = item {
+            if find_value(value, id, path) {
+                return true;
+            }
+        }
+        path.pop();
+    }
+    false
+}
+
+/// User data attached to key/value pairs by their [`ItemId`]
+///
+/// See [`DocumentMut::assign_ids`].
+#[derive(Clone, Debug)]
+pub struct ItemMetadata<T> {
+    entries: HashMap<ItemId, T>,
+}
+
+impl<T> ItemMetadata<T> {
+    /// Creates an empty side-table
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Attach `data` to `id`, returning what was previously attached
+    pub fn insert(&mut self, id: ItemId, data: T) -> Option<T> {
+        self.entries.insert(id, data)
+    }
+
+    /// The data attached to `id`
+    pub fn get(&self, id: ItemId) -> Option<&T> {
+        self.entries.get(&id)
+    }
+
+    /// The data attached to `id`
+    pub fn get_mut(&mut self, id: ItemId) -> Option<&mut T> {
+        self.entries.get_mut(&id)
+    }
+
+    /// Detach and return the data for `id`
+    pub fn remove(&mut self, id: ItemId) -> Option<T> {
+        self.entries.remove(&id)
+    }
+
+    /// The number of key/value pairs with data
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no key/value pairs have data
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// All attached data, in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = (&ItemId, &T)> + '_ {
+        self.entries.iter()
+    }
+
+    /// Drop data for key/value pairs no longer in `doc`
+    pub fn retain_present(&mut self, doc: &DocumentMut) {
+        let mut present = std::collections::HashSet::new();
+        collect_table(doc.as_table(), &mut present);
+        self.entries.retain(|id, _| present.contains(id));
+    }
+}
+
+impl<T> Default for ItemMetadata<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn collect_table(table: &Table, ids: &mut std::collections::HashSet<ItemId>) {
+    for (key, item) in table.items.iter() {
+        ids.extend(key.id);
+        match item {
+            Item::None => {}
+            Item::Value(value) => collect_value(value, ids),
+            Item::Table(table) => collect_table(table, ids),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter() {
+                    collect_table(table, ids);
+                }
+            }
+        }
+    }
+}
+
+fn collect_value(value: &Value, ids: &mut std::collections::HashSet<ItemId>) {
+    match value {
+        Value::Array(array) => {
+            for value in array.iter() {
+                collect_value(value, ids);
+            }
+        }
+        Value::InlineTable(table) => {
+            for (key, item) in table.items.iter() {
+                ids.extend(key.id);
+                if let Item::Value(value) = item {
+                    collect_value(value, ids);
+                }
+            }
+        }
+        _ => {}
+    }
+}
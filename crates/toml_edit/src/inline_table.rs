@@ -9,11 +9,14 @@ use crate::{InternalString, Item, KeyMut, RawString, Table, Value};
 #[derive(Debug, Default, Clone)]
 pub struct InlineTable {
     // `preamble` represents whitespaces in an empty table
-    preamble: RawString,
+    pub(crate) preamble: RawString,
+    // `trailing` represents whitespaces after the trailing comma
+    pub(crate) trailing: RawString,
+    trailing_comma: bool,
     // Whether to hide an empty table
     pub(crate) implicit: bool,
     // prefix before `{` and suffix after `}`
-    decor: Decor,
+    pub(crate) decor: Decor,
     pub(crate) span: Option<std::ops::Range<usize>>,
     // whether this is a proxy for dotted keys
     dotted: bool,
@@ -29,16 +32,20 @@ impl InlineTable {
         Default::default()
     }
 
+    /// Creates an empty table with space for at least `capacity` key/value pairs.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_pairs(KeyValuePairs::with_capacity(capacity))
+    }
+
     pub(crate) fn with_pairs(items: KeyValuePairs) -> Self {
-        Self {
-            items,
-            ..Default::default()
-        }
+        let mut table = Self::default();
+        table.items = items;
+        table
     }
 
     /// Convert to a table
-    pub fn into_table(self) -> Table {
-        let mut t = Table::with_pairs(self.items);
+    pub fn into_table(mut self) -> Table {
+        let mut t = Table::with_pairs(std::mem::take(&mut self.items));
         t.fmt();
         t
     }
@@ -207,7 +214,19 @@ impl InlineTable {
     }
 
     /// Set whitespace after before element
-    pub fn set_preamble(&mut self, preamble: impl Into<RawString>) {
+    ///
+    /// Only spaces and tabs are allowed.
+    pub fn set_preamble(
+        &mut self,
+        preamble: impl Into<RawString>,
+    ) -> Result<(), crate::StructureError> {
+        let preamble = preamble.into();
+        crate::structure::check_whitespace(&preamble, crate::structure::Space::Inline)?;
+        self.preamble = preamble;
+        Ok(())
+    }
+
+    pub(crate) fn set_preamble_unchecked(&mut self, preamble: impl Into<RawString>) {
         self.preamble = preamble.into();
     }
 
@@ -216,6 +235,41 @@ impl InlineTable {
         &self.preamble
     }
 
+    /// Set whether the table will use a trailing comma
+    ///
+    /// Trailing commas in inline tables are only allowed starting with TOML 1.1, see
+    /// [`TomlSpec::V1_1Draft`][crate::TomlSpec::V1_1Draft].
+    pub fn set_trailing_comma(&mut self, yes: bool) {
+        self.trailing_comma = yes;
+    }
+
+    /// Whether the table will use a trailing comma
+    pub fn trailing_comma(&self) -> bool {
+        self.trailing_comma
+    }
+
+    /// Set whitespace after the trailing comma
+    ///
+    /// Only spaces and tabs are allowed.
+    pub fn set_trailing(
+        &mut self,
+        trailing: impl Into<RawString>,
+    ) -> Result<(), crate::StructureError> {
+        let trailing = trailing.into();
+        crate::structure::check_whitespace(&trailing, crate::structure::Space::Inline)?;
+        self.trailing = trailing;
+        Ok(())
+    }
+
+    pub(crate) fn set_trailing_unchecked(&mut self, trailing: impl Into<RawString>) {
+        self.trailing = trailing.into();
+    }
+
+    /// Whitespace after the trailing comma
+    pub fn trailing(&self) -> &RawString {
+        &self.trailing
+    }
+
     /// The location within the original document
     ///
     /// This generally requires an [`ImDocument`][crate::ImDocument].
@@ -223,14 +277,17 @@ impl InlineTable {
         self.span.clone()
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
         use indexmap::map::MutableKeys;
-        self.span = None;
-        self.decor.despan(input);
-        self.preamble.despan(input);
+        if !spans {
+            self.span = None;
+        }
+        self.decor.despan(input, spans);
+        self.preamble.despan(input, spans);
+        self.trailing.despan(input, spans);
         for (key, value) in self.items.iter_mut2() {
-            key.despan(input);
-            value.despan(input);
+            key.despan(input, spans);
+            value.despan(input, spans);
         }
     }
 }
@@ -272,6 +329,14 @@ impl InlineTable {
         self.items.clear();
     }
 
+    /// Shrinks the capacity of the table, and everything nested under it, as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.items.shrink_to_fit();
+        for value in self.items.values_mut() {
+            value.shrink_to_fit();
+        }
+    }
+
     /// Gets the given key's corresponding entry in the Table for in-place manipulation.
     pub fn entry(&'_ mut self, key: impl Into<InternalString>) -> InlineEntry<'_> {
         match self.items.entry(key.into().into()) {
@@ -326,6 +391,114 @@ impl InlineTable {
             .and_then(|value| value.as_value_mut())
     }
 
+    /// Return an optional reference to the value at the given path of keys
+    ///
+    /// Nested inline tables are looked through, whether written with dotted keys, like
+    /// `{ b.c = 1 }`, or not, like `{ b = { c = 1 } }`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let value = "{ b.c = 1, d = { e = 2 } }".parse::<toml_edit::Value>().unwrap();
+    /// let table = value.as_inline_table().unwrap();
+    /// assert_eq!(table.get_path(&["b", "c"]).unwrap().as_integer(), Some(1));
+    /// assert_eq!(table.get_path(&["d", "e"]).unwrap().as_integer(), Some(2));
+    /// assert!(table.get_path(&["b", "missing"]).is_none());
+    /// # }
+    /// ```
+    pub fn get_path(&self, path: &[&str]) -> Option<&Value> {
+        let (last, parents) = path.split_last()?;
+        let mut table = self;
+        for key in parents {
+            table = table.get(key)?.as_inline_table()?;
+        }
+        table.get(last)
+    }
+
+    /// Return an optional mutable reference to the value at the given path of keys
+    ///
+    /// See [`InlineTable::get_path`].
+    pub fn get_path_mut(&mut self, path: &[&str]) -> Option<&mut Value> {
+        let (last, parents) = path.split_last()?;
+        let mut table = self;
+        for key in parents {
+            table = table.get_mut(key)?.as_inline_table_mut()?;
+        }
+        table.get_mut(last)
+    }
+
+    /// Inserts a value at the given path of keys
+    ///
+    /// Missing tables along the path are [dotted][InlineTable::set_dotted], so the value is
+    /// written with a dotted key, like `b.c = 1`.  Returns the value previously at the path or
+    /// an error if a value other than an inline table is along the path.
+    ///
+    /// # Panics
+    ///
+    /// If `path` is empty
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// let mut table = toml_edit::InlineTable::new();
+    /// table.insert_path(&["b", "c"], 1.into()).unwrap();
+    /// table.insert_path(&["b", "d"], 2.into()).unwrap();
+    /// assert_eq!(table.to_string(), "{ b.c = 1, b.d = 2 }");
+    ///
+    /// let err = table.insert_path(&["b", "c", "e"], 3.into()).unwrap_err();
+    /// assert_eq!(err.to_string(), "cannot replace integer `c` with integer");
+    /// # }
+    /// ```
+    pub fn insert_path(
+        &mut self,
+        path: &[&str],
+        value: Value,
+    ) -> Result<Option<Value>, crate::InsertError> {
+        let (last, parents) = path.split_last().expect("path must not be empty");
+        let mut table = self;
+        for parent in parents {
+            let key = Key::new(*parent);
+            if !table.items.contains_key(*parent) {
+                let mut dotted = InlineTable::new();
+                dotted.set_dotted(true);
+                table
+                    .items
+                    .insert(key.clone(), Item::Value(Value::InlineTable(dotted)));
+                table.clear_value_whitespace();
+            }
+            table = match table.items.get_mut(*parent).expect("inserted if missing") {
+                Item::Value(Value::InlineTable(table)) => table,
+                existing => {
+                    return Err(crate::InsertError::new(&key, existing, Item::Value(value)))
+                }
+            };
+        }
+        let old = table.insert(*last, value);
+        if old.is_none() {
+            table.clear_value_whitespace();
+        }
+        Ok(old)
+    }
+
+    /// Reset whitespace-only [`Decor`] on values, as the default depends on whether a value is
+    /// last, like after adding to the end
+    pub(crate) fn clear_value_whitespace(&mut self) {
+        let is_whitespace = |raw: Option<&RawString>| {
+            raw.map(|r| r.as_str().map(|r| r.trim().is_empty()).unwrap_or(false))
+                .unwrap_or(true)
+        };
+        for (_, item) in self.items.iter_mut() {
+            if let Some(value) = item.as_value_mut() {
+                let decor = value.decor_mut();
+                if is_whitespace(decor.prefix()) && is_whitespace(decor.suffix()) {
+                    decor.clear();
+                }
+            }
+        }
+    }
+
     /// Return references to the key-value pair stored for key, if it is present, else None.
     pub fn get_key_value<'a>(&'a self, key: &str) -> Option<(&'a Key, &'a Item)> {
         self.items.get_full(key).and_then(|(_, key, value)| {
@@ -472,9 +645,9 @@ impl IntoIterator for InlineTable {
     type Item = (InternalString, Value);
     type IntoIter = InlineTableIntoIter;
 
-    fn into_iter(self) -> Self::IntoIter {
+    fn into_iter(mut self) -> Self::IntoIter {
         Box::new(
-            self.items
+            std::mem::take(&mut self.items)
                 .into_iter()
                 .filter(|(_, value)| value.is_value())
                 .map(|(key, value)| (key.into(), value.into_value().unwrap())),
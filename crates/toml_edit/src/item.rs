@@ -4,7 +4,7 @@ use toml_datetime::Datetime;
 
 use crate::array_of_tables::ArrayOfTables;
 use crate::table::TableLike;
-use crate::{Array, InlineTable, Table, Value};
+use crate::{Array, Document, InlineTable, RawString, Table, Value};
 
 /// Type representing either a value, a table, an array of tables, or none.
 #[derive(Debug, Default)]
@@ -165,12 +165,12 @@ impl Item {
     pub fn into_array_of_tables(self) -> Result<ArrayOfTables, Self> {
         match self {
             Item::ArrayOfTables(a) => Ok(a),
-            Item::Value(Value::Array(a)) => {
+            Item::Value(Value::Array(mut a)) => {
                 if a.is_empty() {
                     Err(Item::Value(Value::Array(a)))
                 } else if a.iter().all(|v| v.is_inline_table()) {
                     let mut aot = ArrayOfTables::new();
-                    aot.values = a.values;
+                    aot.values = std::mem::take(&mut a.values);
                     for value in aot.values.iter_mut() {
                         value.make_item();
                     }
@@ -333,16 +333,193 @@ impl Item {
         }
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
+    /// The text of `self` within `doc`, exactly as the user wrote it
+    ///
+    /// This is the text at [`Item::span`], so for a table it is the header.  With `with_decor`,
+    /// the whitespace and comments that are part of it are included, like a comment after a
+    /// value or the comments before a table header.  Comments before a key belong to the key.
+    ///
+    /// `doc` must be the document `self` was parsed from.  Returns `None` for items without a
+    /// span, like ones added after parsing or implicit tables.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = toml_edit::Document::parse("
+    /// [dependencies]
+    /// serde = { version = '1.0', features = ['derive'] }  # for config
+    /// ").unwrap();
+    ///
+    /// let serde = &doc["dependencies"]["serde"];
+    /// assert_eq!(
+    ///     serde.original_text(&doc, false),
+    ///     Some("{ version = '1.0', features = ['derive'] }")
+    /// );
+    /// assert_eq!(
+    ///     serde.original_text(&doc, true),
+    ///     Some(" { version = '1.0', features = ['derive'] }  # for config")
+    /// );
+    /// # }
+    /// ```
+    pub fn original_text<'d, S: AsRef<str>>(
+        &self,
+        doc: &'d Document<S>,
+        with_decor: bool,
+    ) -> Option<&'d str> {
+        let mut span = self.span()?;
+        if with_decor {
+            let (prefix, suffix) = match self {
+                Item::None => (None, None),
+                Item::Value(v) => (v.decor().prefix(), v.decor().suffix()),
+                Item::Table(t) => (t.decor().prefix(), t.decor().suffix()),
+                Item::ArrayOfTables(a) => (
+                    a.iter().next().and_then(|t| t.decor().prefix()),
+                    a.iter().last().and_then(|t| t.decor().suffix()),
+                ),
+            };
+            if let Some(prefix) = prefix.and_then(RawString::span) {
+                span.start = prefix.start;
+            }
+            if let Some(suffix) = suffix.and_then(RawString::span) {
+                span.end = suffix.end;
+            }
+        }
+        doc.raw().get(span)
+    }
+
+    /// Hash the content of `self`, ignoring how it is written
+    ///
+    /// Two items hash the same when they hold the same data, so a cache can tell whether a
+    /// subtree changed between versions of a document.  The content is canonicalized as:
+    /// - Decor, comments, and the representation of values, like quoting or `0x` integers, are
+    ///   ignored
+    /// - Tables, inline tables, and dotted keys are all tables, hashed with their keys in sorted
+    ///   order
+    /// - Arrays and arrays of tables are both arrays, hashed in order
+    /// - Integers and floats are different, with floats hashed by their bits and every NaN
+    ///   hashed the same
+    /// - Datetimes are hashed by their [`Display`][std::fmt::Display] form
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    ///
+    /// fn hash(item: &toml_edit::Item) -> u64 {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     item.content_hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// let before = "
+    /// [dependencies]
+    /// serde = '1.0'  # for config
+    /// toml = { version = '0.8' }
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let after = r#"
+    /// [dependencies.toml]
+    /// version = "0.8"
+    ///
+    /// [dependencies]
+    /// serde = "1.0"
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert_eq!(hash(&before["dependencies"]), hash(&after["dependencies"]));
+    /// # }
+    /// ```
+    pub fn content_hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        use std::hash::Hash as _;
+
+        match self {
+            Item::None => 0_u8.hash(state),
+            Item::Value(v) => content_hash_value(v, state),
+            Item::Table(v) => content_hash_table(v, state),
+            Item::ArrayOfTables(v) => {
+                6_u8.hash(state);
+                v.len().hash(state);
+                for table in v.iter() {
+                    content_hash_table(table, state);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
         match self {
             Item::None => {}
-            Item::Value(v) => v.despan(input),
-            Item::Table(v) => v.despan(input),
-            Item::ArrayOfTables(v) => v.despan(input),
+            Item::Value(v) => v.despan(input, spans),
+            Item::Table(v) => v.despan(input, spans),
+            Item::ArrayOfTables(v) => v.despan(input, spans),
+        }
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        match self {
+            Item::None => {}
+            Item::Value(Value::Array(v)) => v.shrink_to_fit(),
+            Item::Value(Value::InlineTable(v)) => v.shrink_to_fit(),
+            Item::Value(_) => {}
+            Item::Table(v) => v.shrink_to_fit(),
+            Item::ArrayOfTables(v) => v.shrink_to_fit(),
         }
     }
 }
 
+fn content_hash_value<H: std::hash::Hasher>(value: &Value, state: &mut H) {
+    use std::hash::Hash as _;
+
+    match value {
+        Value::String(v) => {
+            1_u8.hash(state);
+            v.value().hash(state);
+        }
+        Value::Integer(v) => {
+            2_u8.hash(state);
+            v.value().hash(state);
+        }
+        Value::Float(v) => {
+            3_u8.hash(state);
+            let v = if v.value().is_nan() {
+                f64::NAN
+            } else {
+                *v.value()
+            };
+            v.to_bits().hash(state);
+        }
+        Value::Boolean(v) => {
+            4_u8.hash(state);
+            v.value().hash(state);
+        }
+        Value::Datetime(v) => {
+            5_u8.hash(state);
+            v.value().to_string().hash(state);
+        }
+        Value::Array(v) => {
+            6_u8.hash(state);
+            v.len().hash(state);
+            for value in v.iter() {
+                content_hash_value(value, state);
+            }
+        }
+        Value::InlineTable(v) => content_hash_table(v, state),
+    }
+}
+
+fn content_hash_table<H: std::hash::Hasher>(table: &dyn TableLike, state: &mut H) {
+    use std::hash::Hash as _;
+
+    let mut items = table.iter().collect::<Vec<_>>();
+    items.sort_unstable_by_key(|(key, _)| *key);
+    7_u8.hash(state);
+    items.len().hash(state);
+    for (key, item) in items {
+        key.hash(state);
+        item.content_hash(state);
+    }
+}
+
 impl Clone for Item {
     #[inline(never)]
     fn clone(&self) -> Self {
@@ -402,6 +579,98 @@ impl std::fmt::Display for Item {
     }
 }
 
+#[cfg(feature = "parse")]
+#[cfg(feature = "display")]
+impl Item {
+    /// Render as a standalone document, with the item at the dotted key `path`
+    ///
+    /// Parent tables are written as part of the headers, like `[path.to]` for a value or
+    /// `[path.to.table]` for a table.  An empty `path` is only allowed for a table, which becomes
+    /// the root.
+    ///
+    /// # Errors
+    ///
+    /// If `path` is not a valid key or is empty for anything but a table.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use toml_edit::DocumentMut;
+    ///
+    /// let doc = "[package]\nname = 'foo'\n\n[package.metadata]\nflag = true\n"
+    ///     .parse::<DocumentMut>()
+    ///     .unwrap();
+    /// let package = &doc["package"];
+    /// assert_eq!(
+    ///     package.to_string_with_path("package").unwrap(),
+    ///     "[package]\nname = 'foo'\n\n[package.metadata]\nflag = true\n"
+    /// );
+    /// assert_eq!(
+    ///     package["name"].to_string_with_path("package.name").unwrap(),
+    ///     "[package]\nname = 'foo'\n"
+    /// );
+    /// ```
+    pub fn to_string_with_path(&self, path: &str) -> Result<String, crate::TomlError> {
+        let keys = if path.is_empty() {
+            Vec::new()
+        } else {
+            crate::Key::parse(path)?
+        };
+        let mut item = self.clone();
+        match &mut item {
+            Item::Table(table) => {
+                table.set_dotted(false);
+                table.set_implicit(false);
+                trim_leading_blank_lines(table.decor_mut());
+            }
+            Item::ArrayOfTables(array) => {
+                if let Some(table) = array.get_mut(0) {
+                    trim_leading_blank_lines(table.decor_mut());
+                }
+            }
+            _ => {}
+        }
+        let Some((last, parents)) = keys.split_last() else {
+            let root = item.into_table().map_err(|item| {
+                crate::TomlError::custom(
+                    format!("{} requires a non-empty path", item.type_name()),
+                    None,
+                )
+            })?;
+            return Ok(crate::DocumentMut::from(root).to_string());
+        };
+
+        let mut root = Table::new();
+        let mut parent = &mut root;
+        for key in parents {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            parent.insert_formatted(key, Item::Table(table));
+            parent = parent
+                .get_mut(key.get())
+                .and_then(Item::as_table_mut)
+                .expect("just inserted");
+        }
+        parent.insert_formatted(last, item);
+        Ok(crate::DocumentMut::from(root).to_string())
+    }
+}
+
+/// Drop the separation from whatever came before the item in its original document
+#[cfg(feature = "parse")]
+#[cfg(feature = "display")]
+fn trim_leading_blank_lines(decor: &mut crate::Decor) {
+    let Some(prefix) = decor.prefix().and_then(|p| p.as_str()) else {
+        return;
+    };
+    let content = prefix.trim_start_matches([' ', '\t', '\r', '\n']);
+    let whitespace = &prefix[..prefix.len() - content.len()];
+    if let Some(i) = whitespace.rfind('\n') {
+        let prefix = prefix[i + 1..].to_owned();
+        decor.set_prefix_unchecked(prefix);
+    }
+}
+
 /// Returns a formatted value.
 ///
 /// Since formatting is part of a `Value`, the right hand side of the
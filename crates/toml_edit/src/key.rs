@@ -6,6 +6,7 @@ use toml_write::ToTomlKey as _;
 
 use crate::repr::{Decor, Repr};
 use crate::InternalString;
+use crate::{InlineTable, Item, Table, Value};
 
 /// For Key/[`Value`][crate::Value] pairs under a [`Table`][crate::Table] header or inside an
 /// [`InlineTable`][crate::InlineTable]
@@ -36,6 +37,9 @@ pub struct Key {
     pub(crate) repr: Option<Repr>,
     pub(crate) leaf_decor: Decor,
     pub(crate) dotted_decor: Decor,
+    // How the dotted key ending in this key was written, see `PathDecor`
+    pub(crate) path_decor: Option<Box<PathDecor>>,
+    pub(crate) id: Option<crate::ItemId>,
 }
 
 impl Key {
@@ -46,6 +50,8 @@ impl Key {
             repr: None,
             leaf_decor: Default::default(),
             dotted_decor: Default::default(),
+            path_decor: None,
+            id: None,
         }
     }
 
@@ -63,14 +69,41 @@ impl Key {
     }
 
     /// While creating the `Key`, add `Decor` to it for the line entry
-    pub fn with_leaf_decor(mut self, decor: Decor) -> Self {
+    ///
+    /// Only spaces and tabs are allowed in the suffix, between the key and the `=`.
+    pub fn with_leaf_decor(mut self, decor: Decor) -> Result<Self, crate::StructureError> {
+        if let Some(suffix) = decor.suffix() {
+            crate::structure::check_whitespace(suffix, crate::structure::Space::Inline)?;
+        }
         self.leaf_decor = decor;
-        self
+        Ok(self)
     }
 
     /// While creating the `Key`, add `Decor` to it for between dots
-    pub fn with_dotted_decor(mut self, decor: Decor) -> Self {
+    ///
+    /// Only spaces and tabs are allowed.
+    pub fn with_dotted_decor(mut self, decor: Decor) -> Result<Self, crate::StructureError> {
+        for raw in [decor.prefix(), decor.suffix()].into_iter().flatten() {
+            crate::structure::check_whitespace(raw, crate::structure::Space::Inline)?;
+        }
         self.dotted_decor = decor;
+        Ok(self)
+    }
+
+    pub(crate) fn with_leaf_decor_unchecked(mut self, decor: Decor) -> Self {
+        self.leaf_decor = decor;
+        self
+    }
+
+    pub(crate) fn with_dotted_decor_unchecked(mut self, decor: Decor) -> Self {
+        self.dotted_decor = decor;
+        self
+    }
+
+    /// While creating the `Key`, give it the [`ItemId`][crate::ItemId] of another key, like
+    /// when renaming it
+    pub fn with_id(mut self, id: Option<crate::ItemId>) -> Self {
+        self.id = id;
         self
     }
 
@@ -84,11 +117,36 @@ impl Key {
         &self.key
     }
 
+    /// Whether the parsed key value matches a glob `pattern`
+    ///
+    /// `*` matches any number of characters and `?` matches exactly one, with everything else
+    /// matched literally.  Matching is by character, not byte, and ignores how the key is quoted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use toml_edit::Key;
+    ///
+    /// assert!(Key::new("serde_json").matches_glob("serde*"));
+    /// assert!(Key::new("café").matches_glob("caf?"));
+    /// assert!(!Key::new("tokio").matches_glob("serde*"));
+    /// ```
+    pub fn matches_glob(&self, pattern: &str) -> bool {
+        glob_match(pattern, self.get())
+    }
+
     /// Returns key raw representation, if available.
     pub fn as_repr(&self) -> Option<&Repr> {
         self.repr.as_ref()
     }
 
+    /// How the key was quoted, if it has a [`Repr`] and isn't a bare key
+    pub fn string_kind(&self) -> Option<crate::StringKind> {
+        self.as_repr()
+            .and_then(|r| r.as_raw().as_str())
+            .and_then(crate::StringKind::from_raw)
+    }
+
     /// Returns the default raw representation.
     #[cfg(feature = "display")]
     pub fn default_repr(&self) -> Repr {
@@ -129,6 +187,11 @@ impl Key {
         &self.dotted_decor
     }
 
+    /// The identity of this key/value pair, see [`DocumentMut::assign_ids`][crate::DocumentMut::assign_ids]
+    pub fn id(&self) -> Option<crate::ItemId> {
+        self.id
+    }
+
     /// The location within the original document
     ///
     /// This generally requires an [`ImDocument`][crate::ImDocument].
@@ -136,11 +199,14 @@ impl Key {
         self.repr.as_ref().and_then(|r| r.span())
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
-        self.leaf_decor.despan(input);
-        self.dotted_decor.despan(input);
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
+        self.leaf_decor.despan(input, spans);
+        self.dotted_decor.despan(input, spans);
+        if let Some(path_decor) = &mut self.path_decor {
+            path_decor.despan(input, spans);
+        }
         if let Some(repr) = &mut self.repr {
-            repr.despan(input);
+            repr.despan(input, spans);
         }
     }
 
@@ -149,17 +215,18 @@ impl Key {
         self.repr = None;
         self.leaf_decor.clear();
         self.dotted_decor.clear();
+        self.path_decor = None;
     }
 
     #[cfg(feature = "parse")]
     fn try_parse_simple(s: &str) -> Result<Key, crate::TomlError> {
         let source = toml_parse::Source::new(s);
         let mut sink = crate::error::TomlSink::<Option<_>>::new(source);
-        let mut key = crate::parser::parse_key(source, &mut sink);
+        let mut key = crate::parser::to_key(source, &mut sink);
         if let Some(err) = sink.into_inner() {
             Err(err)
         } else {
-            key.despan(s);
+            key.despan(&std::sync::Arc::from(s), false);
             Ok(key)
         }
     }
@@ -168,12 +235,13 @@ impl Key {
     fn try_parse_path(s: &str) -> Result<Vec<Key>, crate::TomlError> {
         let source = toml_parse::Source::new(s);
         let mut sink = crate::error::TomlSink::<Option<_>>::new(source);
-        let mut keys = crate::parser::parse_key_path(source, &mut sink);
+        let mut keys = crate::parser::to_key_path(source, &mut sink);
         if let Some(err) = sink.into_inner() {
             Err(err)
         } else {
+            let shared = std::sync::Arc::from(s);
             for key in &mut keys {
-                key.despan(s);
+                key.despan(&shared, false);
             }
             Ok(keys)
         }
@@ -188,6 +256,92 @@ impl Clone for Key {
             repr: self.repr.clone(),
             leaf_decor: self.leaf_decor.clone(),
             dotted_decor: self.dotted_decor.clone(),
+            path_decor: self.path_decor.clone(),
+            id: self.id,
+        }
+    }
+}
+
+/// The decor of the keys of one header or dotted key, as written
+///
+/// The keys of a path are shared by every header and dotted key going through the same tables,
+/// so `[a.b]` and `[ a . b . c ]` can't both be written from the decor of `a` and `b`.  When a
+/// line differs from its keys, its own decor is kept here, on the table for a header and on the
+/// last key for a dotted key.  It is used while the path has as many keys as when parsed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct PathDecor {
+    /// The [`Key::dotted_decor`] of each key
+    pub(crate) dotted: Vec<Decor>,
+    /// The [`Key::leaf_decor`] of the last key, for a header
+    ///
+    /// A dotted key is the last key, so its own leaf decor is used.
+    pub(crate) leaf: Option<Decor>,
+}
+
+impl PathDecor {
+    /// The decor of the header `path` followed by `key`, as parsed
+    #[cfg(feature = "parse")]
+    pub(crate) fn header(path: &[Key], key: &Key) -> Self {
+        Self {
+            leaf: Some(key.leaf_decor.clone()),
+            ..Self::dotted_key(path, key)
+        }
+    }
+
+    /// The decor of the dotted key `path` followed by `key`, as parsed
+    #[cfg(feature = "parse")]
+    pub(crate) fn dotted_key(path: &[Key], key: &Key) -> Self {
+        let dotted = path
+            .iter()
+            .chain(std::iter::once(key))
+            .map(|k| k.dotted_decor.clone())
+            .collect();
+        Self { dotted, leaf: None }
+    }
+
+    /// The dotted and leaf decor to write `path` with
+    pub(crate) fn for_path<'p>(
+        path_decor: Option<&'p Self>,
+        path: &[&'p Key],
+    ) -> (Vec<&'p Decor>, &'p Decor) {
+        let leaf = path.last().expect("always at least one key");
+        match path_decor.filter(|d| d.dotted.len() == path.len()) {
+            Some(path_decor) => (
+                path_decor.dotted.iter().collect(),
+                path_decor.leaf.as_ref().unwrap_or(&leaf.leaf_decor),
+            ),
+            None => (
+                path.iter().map(|k| &k.dotted_decor).collect(),
+                &leaf.leaf_decor,
+            ),
+        }
+    }
+
+    /// Whether `dotted` and `leaf`, from the keys of the path, write the same as this
+    ///
+    /// The prefix of the first key and suffix of the last key are not between dots, so they
+    /// aren't written.
+    pub(crate) fn is_redundant(&self, dotted: &[&Decor], leaf: &Decor) -> bool {
+        let last = self.dotted.len().saturating_sub(1);
+        self.dotted.len() == dotted.len()
+            && self.leaf.as_ref().map(|own| own == leaf).unwrap_or(true)
+            && self
+                .dotted
+                .iter()
+                .zip(dotted)
+                .enumerate()
+                .all(|(i, (own, shared))| {
+                    (i == 0 || own.prefix() == shared.prefix())
+                        && (i == last || own.suffix() == shared.suffix())
+                })
+    }
+
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
+        for decor in &mut self.dotted {
+            decor.despan(input, spans);
+        }
+        if let Some(leaf) = &mut self.leaf {
+            leaf.despan(input, spans);
         }
     }
 }
@@ -207,12 +361,23 @@ impl std::borrow::Borrow<str> for Key {
     }
 }
 
+impl AsRef<str> for Key {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.get()
+    }
+}
+
 impl std::hash::Hash for Key {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.get().hash(state);
     }
 }
 
+/// Orders by the parsed key value, by Unicode code point
+///
+/// How the key is quoted or decorated is ignored, so `"a"` and `a` are equal, consistent with
+/// [`Eq`] and [`Hash`][std::hash::Hash].
 impl Ord for Key {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.get().cmp(other.get())
@@ -255,6 +420,13 @@ impl PartialEq<String> for Key {
     }
 }
 
+impl PartialEq<InternalString> for Key {
+    #[inline]
+    fn eq(&self, other: &InternalString) -> bool {
+        PartialEq::eq(self.get(), other.as_str())
+    }
+}
+
 #[cfg(feature = "display")]
 impl std::fmt::Display for Key {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -298,7 +470,7 @@ impl From<InternalString> for Key {
     }
 }
 
-#[doc(hidden)]
+/// The parsed key value, without copying it
 impl From<Key> for InternalString {
     fn from(key: Key) -> InternalString {
         key.key
@@ -317,11 +489,21 @@ impl KeyMut<'_> {
         self.key.get()
     }
 
+    /// The identity of this key/value pair, see [`DocumentMut::assign_ids`][crate::DocumentMut::assign_ids]
+    pub fn id(&self) -> Option<crate::ItemId> {
+        self.key.id()
+    }
+
     /// Returns the raw representation, if available.
     pub fn as_repr(&self) -> Option<&Repr> {
         self.key.as_repr()
     }
 
+    /// How the key was quoted, if it has a [`Repr`] and isn't a bare key
+    pub fn string_kind(&self) -> Option<crate::StringKind> {
+        self.key.string_kind()
+    }
+
     /// Returns the default raw representation.
     #[cfg(feature = "display")]
     pub fn default_repr(&self) -> Repr {
@@ -358,6 +540,11 @@ impl KeyMut<'_> {
     pub fn fmt(&mut self) {
         self.key.fmt();
     }
+
+    /// Whether the parsed key value matches a glob `pattern`, see [`Key::matches_glob`]
+    pub fn matches_glob(&self, pattern: &str) -> bool {
+        self.key.matches_glob(pattern)
+    }
 }
 
 impl std::ops::Deref for KeyMut<'_> {
@@ -402,3 +589,150 @@ impl std::fmt::Display for KeyMut<'_> {
 fn string_roundtrip() {
     Key::new("hello").to_string().parse::<Key>().unwrap();
 }
+
+/// Match `*` against any run of characters and `?` against one, backtracking to the last `*`
+fn glob_match(pattern: &str, key: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let key = key.chars().collect::<Vec<_>>();
+    let (mut p, mut k) = (0, 0);
+    // Where the last `*` was and how much of `key` it has matched up to
+    let mut star = None;
+    while k < key.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, k));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == key[k] => {
+                p += 1;
+                k += 1;
+            }
+            _ => {
+                let Some((star_p, star_k)) = star else {
+                    return false;
+                };
+                star = Some((star_p, star_k + 1));
+                p = star_p + 1;
+                k = star_k + 1;
+            }
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[test]
+fn glob() {
+    let cases = [
+        ("", "", true),
+        ("", "a", false),
+        ("*", "", true),
+        ("*", "anything", true),
+        ("a*", "abc", true),
+        ("*c", "abc", true),
+        ("a*c", "abbbc", true),
+        ("a*c", "abbbd", false),
+        ("a?c", "abc", true),
+        ("a?c", "ac", false),
+        ("*.*", "a.b", true),
+        ("*a*b", "xaaab", true),
+        ("*a*b", "xaaabx", false),
+        ("?", "é", true),
+        ("abc", "abc", true),
+        ("abc", "abd", false),
+    ];
+    for (pattern, key, expected) in cases {
+        assert_eq!(glob_match(pattern, key), expected, "{pattern:?} {key:?}");
+    }
+}
+
+/// Drop the [`PathDecor`] of lines written the same as their keys, after parsing
+pub(crate) fn drop_redundant_path_decor(table: &mut Table) {
+    drop_table_path_decor(table, &mut Vec::new(), &mut Vec::new());
+}
+
+/// With the dotted decor of the keys to `table`, from the root and from its header
+fn drop_table_path_decor(table: &mut Table, header: &mut Vec<Decor>, line: &mut Vec<Decor>) {
+    use indexmap::map::MutableKeys;
+    for (key, item) in table.items.iter_mut2() {
+        match item {
+            Item::None => {}
+            Item::Value(value) => {
+                drop_key_path_decor(key, line);
+                drop_value_path_decor(value);
+            }
+            Item::Table(child) => {
+                header.push(key.dotted_decor.clone());
+                if child.is_dotted() {
+                    line.push(key.dotted_decor.clone());
+                    drop_table_path_decor(child, header, line);
+                    line.pop();
+                } else {
+                    drop_header_decor(child, header, key);
+                    drop_table_path_decor(child, header, &mut Vec::new());
+                }
+                header.pop();
+            }
+            Item::ArrayOfTables(children) => {
+                header.push(key.dotted_decor.clone());
+                for child in children.iter_mut() {
+                    drop_header_decor(child, header, key);
+                    drop_table_path_decor(child, header, &mut Vec::new());
+                }
+                header.pop();
+            }
+        }
+    }
+}
+
+fn drop_value_path_decor(value: &mut Value) {
+    match value {
+        Value::Array(array) => {
+            for value in array.iter_mut() {
+                drop_value_path_decor(value);
+            }
+        }
+        Value::InlineTable(table) => drop_inline_path_decor(table, &mut Vec::new()),
+        _ => {}
+    }
+}
+
+fn drop_inline_path_decor(table: &mut InlineTable, line: &mut Vec<Decor>) {
+    use indexmap::map::MutableKeys;
+    for (key, item) in table.items.iter_mut2() {
+        let Item::Value(value) = item else {
+            continue;
+        };
+        match value {
+            Value::InlineTable(child) if child.is_dotted() => {
+                line.push(key.dotted_decor.clone());
+                drop_inline_path_decor(child, line);
+                line.pop();
+            }
+            _ => {
+                drop_key_path_decor(key, line);
+                drop_value_path_decor(value);
+            }
+        }
+    }
+}
+
+fn drop_key_path_decor(key: &mut Key, line: &[Decor]) {
+    if let Some(path_decor) = &key.path_decor {
+        let dotted = line
+            .iter()
+            .chain(std::iter::once(&key.dotted_decor))
+            .collect::<Vec<_>>();
+        if path_decor.is_redundant(&dotted, &key.leaf_decor) {
+            key.path_decor = None;
+        }
+    }
+}
+
+fn drop_header_decor(table: &mut Table, header: &[Decor], key: &Key) {
+    if let Some(header_decor) = &table.header_decor {
+        let dotted = header.iter().collect::<Vec<_>>();
+        if header_decor.is_redundant(&dotted, &key.leaf_decor) {
+            table.header_decor = None;
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use crate::{DocumentMut, Item, PathSegment, TableLike, Value};
+
+/// The items at each key name within a document, see [`DocumentMut::key_index`]
+#[derive(Clone, Debug, Default)]
+pub struct KeyIndex<'d> {
+    items: HashMap<&'d str, Vec<(Vec<PathSegment>, &'d Item)>>,
+}
+
+impl<'d> KeyIndex<'d> {
+    /// The items at a key named `key`, with their paths from the root, in document order
+    pub fn get(&self, key: &str) -> &[(Vec<PathSegment>, &'d Item)] {
+        self.items.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The key names within the document, in no particular order
+    pub fn keys(&self) -> impl Iterator<Item = &'d str> + '_ {
+        self.items.keys().copied()
+    }
+}
+
+impl DocumentMut {
+    /// Each item at a key named `key`, with its path from the root, in document order
+    ///
+    /// Keys are found at any depth, including within inline tables, arrays, and arrays of tables.
+    /// For looking up several key names, see [`DocumentMut::key_index`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let doc = "
+    /// [package]
+    /// version = '1.0.0'
+    ///
+    /// [dependencies]
+    /// serde = { version = '1' }
+    ///
+    /// [[bin]]
+    /// name = 'app'
+    /// metadata.version = 2
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let versions = doc
+    ///     .find_key_occurrences("version")
+    ///     .into_iter()
+    ///     .map(|(path, item)| {
+    ///         let path = path.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    ///         (path.join("."), item.to_string().trim().to_owned())
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(versions, [
+    ///     ("package.version".to_owned(), "'1.0.0'".to_owned()),
+    ///     ("dependencies.serde.version".to_owned(), "'1'".to_owned()),
+    ///     ("bin.0.metadata.version".to_owned(), "2".to_owned()),
+    /// ]);
+    /// # }
+    /// # }
+    /// ```
+    pub fn find_key_occurrences(&self, key: &str) -> Vec<(Vec<PathSegment>, &Item)> {
+        let mut found = Vec::new();
+        walk(self.as_table(), &mut Vec::new(), &mut |name, path, item| {
+            if name == key {
+                found.push((path.to_vec(), item));
+            }
+        });
+        found
+    }
+
+    /// Index the items at every key name, for looking up several with one walk of the document
+    ///
+    /// See [`DocumentMut::find_key_occurrences`].
+    pub fn key_index(&self) -> KeyIndex<'_> {
+        let mut index = KeyIndex::default();
+        walk(self.as_table(), &mut Vec::new(), &mut |name, path, item| {
+            index
+                .items
+                .entry(name)
+                .or_default()
+                .push((path.to_vec(), item));
+        });
+        index
+    }
+}
+
+fn walk<'d>(
+    table: &'d dyn TableLike,
+    path: &mut Vec<PathSegment>,
+    f: &mut dyn FnMut(&'d str, &[PathSegment], &'d Item),
+) {
+    for (key, item) in table.iter() {
+        path.push(PathSegment::Key(key.to_owned()));
+        f(key, path, item);
+        match item {
+            Item::None => {}
+            Item::Value(value) => walk_value(value, path, f),
+            Item::Table(table) => walk(table, path, f),
+            Item::ArrayOfTables(array) => {
+                for (i, table) in array.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    walk(table, path, f);
+                    path.pop();
+                }
+            }
+        }
+        path.pop();
+    }
+}
+
+fn walk_value<'d>(
+    value: &'d Value,
+    path: &mut Vec<PathSegment>,
+    f: &mut dyn FnMut(&'d str, &[PathSegment], &'d Item),
+) {
+    match value {
+        Value::InlineTable(table) => walk(table, path, f),
+        Value::Array(array) => {
+            for (i, value) in array.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                walk_value(value, path, f);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
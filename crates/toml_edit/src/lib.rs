@@ -78,40 +78,103 @@
 
 mod array;
 mod array_of_tables;
+mod builder;
+mod comment;
+mod conflict;
+#[cfg(feature = "display")]
+mod debug_tree;
 mod document;
+mod dotted;
+mod edit_script;
 #[cfg(feature = "display")]
 mod encode;
 mod error;
+mod flatten;
+mod header;
+mod id;
 mod index;
 mod inline_table;
 mod internal_string;
 mod item;
 mod key;
+mod key_index;
+mod line_edit;
+mod line_index;
+mod memory;
+#[cfg(feature = "parse")]
+mod pairs;
 #[cfg(feature = "parse")]
-mod parser;
+mod parse_options;
+mod placement;
+#[cfg(feature = "parse")]
+mod progress;
+mod quoting;
 mod raw_string;
+mod remove;
+mod replace;
 mod repr;
+#[cfg(feature = "display")]
+mod stable;
+mod structure;
+#[cfg(feature = "display")]
+mod style;
 mod table;
+mod table_kind;
+mod teardown;
+#[cfg(feature = "parse")]
+mod tokens;
 mod value;
+#[cfg(feature = "parse")]
+#[cfg(feature = "display")]
+mod verify;
 
 #[cfg(feature = "serde")]
 pub mod de;
 #[cfg(feature = "serde")]
 pub mod ser;
 
+#[cfg(feature = "parse")]
+#[cfg(feature = "display")]
+pub mod nested;
+#[cfg(feature = "parse")]
+pub mod parser;
+#[cfg(feature = "display")]
+pub mod patch;
+#[cfg(feature = "reference")]
+pub mod reference;
+pub mod schema;
+#[cfg(feature = "unstable-sync")]
+pub mod sync;
+#[cfg(feature = "template")]
+pub mod template;
 pub mod visit;
 pub mod visit_mut;
+#[cfg(feature = "watch")]
+pub mod watch;
+#[cfg(feature = "workspace")]
+pub mod workspace;
 
 pub use crate::array::{Array, ArrayIntoIter, ArrayIter, ArrayIterMut};
 pub use crate::array_of_tables::{
-    ArrayOfTables, ArrayOfTablesIntoIter, ArrayOfTablesIter, ArrayOfTablesIterMut,
+    ArrayOfTables, ArrayOfTablesIntoIter, ArrayOfTablesIter, ArrayOfTablesIterMut, SortComments,
+    SortOrder,
+};
+pub use crate::builder::{DocumentBuilder, TableBuilder};
+pub use crate::comment::CommentSpace;
+pub use crate::conflict::{
+    ConflictPolicy, ConflictStrategy, DuplicateKey, DuplicateStrategy, KeyConflict,
 };
-pub use crate::document::DocumentMut;
+#[cfg(feature = "display")]
+pub use crate::debug_tree::DebugTree;
+pub use crate::document::{DecorIterMut, DecorKind, DecorMut, DocumentMut};
+pub use crate::edit_script::{Edit, EditError, EditScript};
 /// Type representing a parsed TOML document
 #[deprecated(since = "0.23.0", note = "Replaced with `Document`")]
 pub type ImDocument<S> = Document<S>;
 pub use crate::document::Document;
-pub use crate::error::TomlError;
+pub use crate::error::{EncodeError, InsertError, TomlError};
+pub use crate::flatten::FlattenArrays;
+pub use crate::id::{ItemId, ItemMetadata};
 pub use crate::inline_table::{
     InlineEntry, InlineOccupiedEntry, InlineTable, InlineTableIntoIter, InlineTableIter,
     InlineTableIterMut, InlineVacantEntry,
@@ -119,13 +182,42 @@ pub use crate::inline_table::{
 pub use crate::internal_string::InternalString;
 pub use crate::item::{array, table, value, Item};
 pub use crate::key::{Key, KeyMut};
+pub use crate::key_index::KeyIndex;
+pub use crate::line_index::{LineEntry, LineIndex};
+pub use crate::memory::MemoryUsage;
+#[cfg(feature = "parse")]
+pub use crate::pairs::FromPairsError;
+#[cfg(feature = "parse")]
+pub use crate::parse_options::ParseOptions;
+pub use crate::placement::TablePlacement;
+#[cfg(feature = "parse")]
+pub use crate::progress::{CancellationToken, ParseStage, Progress};
+pub use crate::quoting::{KeyRename, QuoteReason, QuotedKey, Transliteration};
 pub use crate::raw_string::RawString;
-pub use crate::repr::{Decor, Formatted, Repr};
+pub use crate::replace::{PathSegment, ValueChange};
+pub use crate::repr::{Decor, Formatted, Repr, StringKind};
+#[cfg(feature = "display")]
+pub use crate::stable::OutputStyle;
+pub use crate::structure::StructureError;
+#[cfg(feature = "display")]
+pub use crate::style::{ArrayStyle, DatetimeSeparator, StringStyle, StyleProfile, UtcOffsetStyle};
 pub use crate::table::{
     Entry, IntoIter, Iter, IterMut, OccupiedEntry, Table, TableLike, VacantEntry,
 };
+pub use crate::table_kind::{ConversionLoss, TableKind};
+#[cfg(feature = "parse")]
+pub use crate::tokens::TokenStream;
 pub use crate::value::Value;
+#[cfg(feature = "parse")]
+#[cfg(feature = "display")]
+pub use crate::verify::{Discrepancy, VerifyError};
 pub use toml_datetime::*;
+#[cfg(feature = "parse")]
+pub use toml_parse::decoder::CarriageReturn;
+#[cfg(feature = "parse")]
+pub use toml_parse::SourceId;
+#[cfg(feature = "parse")]
+pub use toml_parse::TomlSpec;
 
 // Prevent users from some traits.
 pub(crate) mod private {
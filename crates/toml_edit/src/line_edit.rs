@@ -0,0 +1,163 @@
+use std::ops::Range;
+
+use crate::{Document, Item, RawString, Table};
+
+/// Line-oriented edits
+///
+/// These operate on the source lines of the document, making them a good fit for tools that want
+/// to present minimal, patch-like changes.
+impl<S: AsRef<str>> Document<S> {
+    /// The full source lines of the key/value pair or table that `span` points into
+    ///
+    /// This includes comments on the lines directly before it and, for a table, its key/value
+    /// pairs but not its sub-tables.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let input = "
+    /// [package]
+    /// ## The name
+    /// name = 'foo'
+    /// version = '1.0.0'
+    /// ";
+    /// let doc = toml_edit::Document::parse(input).unwrap();
+    /// let span = doc["package"]["name"].span().unwrap();
+    /// let lines = doc.item_lines(span).unwrap();
+    /// assert_eq!(&input[lines], "# The name\nname = 'foo'\n");
+    /// # }
+    /// ```
+    pub fn item_lines(&self, span: Range<usize>) -> Option<Range<usize>> {
+        let raw = self.raw();
+        let mut candidates = Vec::new();
+        collect_table_lines(self.as_table(), raw, &mut candidates, true);
+        candidates
+            .into_iter()
+            .filter(|lines| lines.start <= span.start && span.start < lines.end)
+            .min_by_key(|lines| lines.len())
+    }
+
+    /// Remove the [`Document::item_lines`] for `span`
+    ///
+    /// Returns the edited source and the removed text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let input = "
+    /// [package]
+    /// ## The name
+    /// name = 'foo'
+    /// version = '1.0.0'
+    /// ";
+    /// let doc = toml_edit::Document::parse(input).unwrap();
+    /// let span = doc["package"]["name"].span().unwrap();
+    /// let (edited, removed) = doc.remove_item_lines(span).unwrap();
+    /// assert_eq!(edited, "
+    /// [package]
+    /// version = '1.0.0'
+    /// ");
+    /// assert_eq!(removed, "# The name\nname = 'foo'\n");
+    /// # }
+    /// ```
+    pub fn remove_item_lines(&self, span: Range<usize>) -> Option<(String, &str)> {
+        self.replace_item_lines(span, "")
+    }
+
+    /// Replace the [`Document::item_lines`] for `span` with `replacement`
+    ///
+    /// Returns the edited source and the replaced text.
+    /// `replacement` should end with a newline, like the lines it replaces.
+    pub fn replace_item_lines(
+        &self,
+        span: Range<usize>,
+        replacement: &str,
+    ) -> Option<(String, &str)> {
+        let lines = self.item_lines(span)?;
+        let raw = self.raw();
+        let mut edited = String::with_capacity(raw.len() - lines.len() + replacement.len());
+        edited.push_str(&raw[..lines.start]);
+        edited.push_str(replacement);
+        edited.push_str(&raw[lines.end..]);
+        Some((edited, &raw[lines]))
+    }
+}
+
+fn collect_table_lines(table: &Table, raw: &str, lines: &mut Vec<Range<usize>>, is_root: bool) {
+    let mut body_end = 0;
+    collect_key_value_lines(table, raw, lines, &mut body_end);
+    if !is_root && !table.is_implicit() {
+        if let Some(header) = table.span() {
+            let end = header.end.max(body_end);
+            lines.push(item_lines(raw, header.start..end, table.decor().prefix()));
+        }
+    }
+
+    for (_, item) in table.items.iter() {
+        match item {
+            Item::Table(child) if !child.is_dotted() => {
+                collect_table_lines(child, raw, lines, false);
+            }
+            Item::ArrayOfTables(array) => {
+                for child in array.iter() {
+                    collect_table_lines(child, raw, lines, false);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_key_value_lines(
+    table: &Table,
+    raw: &str,
+    lines: &mut Vec<Range<usize>>,
+    body_end: &mut usize,
+) {
+    for (key, item) in table.items.iter() {
+        match item {
+            Item::Value(value) => {
+                if let Some(span) = value.span() {
+                    *body_end = (*body_end).max(span.end);
+                    lines.push(item_lines(raw, span, key.leaf_decor().prefix()));
+                }
+            }
+            Item::Table(child) if child.is_dotted() => {
+                collect_key_value_lines(child, raw, lines, body_end);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Expand `span` to full lines, including the comment lines directly before it
+fn item_lines(raw: &str, span: Range<usize>, prefix: Option<&RawString>) -> Range<usize> {
+    let mut start = line_start(raw, span.start);
+    if let Some(prefix) = prefix.and_then(RawString::span) {
+        let text = &raw[prefix.clone()];
+        let mut offset = text.len();
+        for line in text.split_inclusive('\n').rev() {
+            offset -= line.len();
+            if !line.ends_with('\n') {
+                // Indentation on the item's own line
+                continue;
+            }
+            if line.trim_start_matches([' ', '\t']).starts_with('#') {
+                start = start.min(line_start(raw, prefix.start + offset));
+            } else {
+                break;
+            }
+        }
+    }
+    let end = raw[span.end..]
+        .find('\n')
+        .map(|i| span.end + i + 1)
+        .unwrap_or(raw.len());
+    start..end
+}
+
+fn line_start(raw: &str, index: usize) -> usize {
+    raw[..index].rfind('\n').map(|i| i + 1).unwrap_or(0)
+}
@@ -0,0 +1,223 @@
+use std::ops::Range;
+
+use crate::{Document, InlineTable, Item, Key, PathSegment, Table, Value};
+
+/// What is defined on each line of a document, see [`Document::line_index`]
+#[derive(Clone, Debug, Default)]
+pub struct LineIndex {
+    entries: Vec<LineEntry>,
+}
+
+impl LineIndex {
+    /// The items whose source includes the zero-based `line`, outermost first
+    ///
+    /// A multi-line array is included for each of its lines, along with the element on that line.
+    pub fn items_at(&self, line: usize) -> impl Iterator<Item = &LineEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.lines.contains(&line))
+    }
+
+    /// All items, in document order
+    pub fn iter(&self) -> std::slice::Iter<'_, LineEntry> {
+        self.entries.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a LineIndex {
+    type Item = &'a LineEntry;
+    type IntoIter = std::slice::Iter<'a, LineEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An item within a [`LineIndex`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineEntry {
+    path: Vec<PathSegment>,
+    span: Range<usize>,
+    lines: Range<usize>,
+}
+
+impl LineEntry {
+    /// Where the item is within the document
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The location of the item, from its key to the end of its value
+    ///
+    /// For tables and arrays of tables, this is the header.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// The zero-based lines the item spans
+    pub fn lines(&self) -> Range<usize> {
+        self.lines.clone()
+    }
+}
+
+impl<S: AsRef<str>> Document<S> {
+    /// Map each line to the tables, key/value pairs, and array elements defined on it
+    ///
+    /// Items without a span, like implicit tables, are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let input = "[package]
+    /// name = 'foo'
+    /// authors = [
+    ///   'bar',
+    /// ]
+    /// ";
+    /// let doc = toml_edit::Document::parse(input).unwrap();
+    /// let index = doc.line_index();
+    /// let paths = index
+    ///     .items_at(3)
+    ///     .map(|entry| entry.path().iter().map(|s| s.to_string()).collect::<Vec<_>>().join("."))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(paths, ["package.authors", "package.authors.0"]);
+    /// # }
+    /// ```
+    pub fn line_index(&self) -> LineIndex {
+        let raw = self.raw();
+        let line_starts = std::iter::once(0)
+            .chain(raw.match_indices('\n').map(|(i, _)| i + 1))
+            .collect::<Vec<_>>();
+        let mut indexer = Indexer {
+            line_starts,
+            path: Vec::new(),
+            entries: Vec::new(),
+        };
+        indexer.table(self.as_table(), true);
+        let mut entries = indexer.entries;
+        entries.sort_by_key(|entry| (entry.span.start, std::cmp::Reverse(entry.span.end)));
+        LineIndex { entries }
+    }
+}
+
+#[cfg(all(feature = "parse", feature = "display"))]
+impl crate::DocumentMut {
+    /// Map each line of the [rendered document][std::fmt::Display] to the items defined on it
+    ///
+    /// See [`Document::line_index`].  The lines match the source the document was parsed from
+    /// until it is edited.
+    pub fn line_index(&self) -> LineIndex {
+        Document::parse(self.to_string())
+            .map(|doc| doc.line_index())
+            .unwrap_or_default()
+    }
+}
+
+struct Indexer {
+    line_starts: Vec<usize>,
+    path: Vec<PathSegment>,
+    entries: Vec<LineEntry>,
+}
+
+impl Indexer {
+    fn table(&mut self, table: &Table, is_root: bool) {
+        if !is_root && !table.is_implicit() && !table.is_dotted() {
+            if let Some(span) = table.span() {
+                self.push(span);
+            }
+        }
+        self.key_values(table, None);
+        self.sub_tables(table);
+    }
+
+    fn sub_tables(&mut self, table: &Table) {
+        for (key, item) in table.items.iter() {
+            self.path.push(PathSegment::Key(key.get().to_owned()));
+            match item {
+                Item::Table(child) if child.is_dotted() => self.sub_tables(child),
+                Item::Table(child) => self.table(child, false),
+                Item::ArrayOfTables(array) => {
+                    for (i, child) in array.iter().enumerate() {
+                        self.path.push(PathSegment::Index(i));
+                        self.table(child, false);
+                        self.path.pop();
+                    }
+                }
+                _ => {}
+            }
+            self.path.pop();
+        }
+    }
+
+    fn key_values(&mut self, table: &Table, dotted_start: Option<usize>) {
+        for (key, item) in table.items.iter() {
+            let start = dotted_start.or_else(|| key_start(key));
+            self.path.push(PathSegment::Key(key.get().to_owned()));
+            match item {
+                Item::Value(value) => self.key_value(start, value),
+                Item::Table(child) if child.is_dotted() => self.key_values(child, start),
+                _ => {}
+            }
+            self.path.pop();
+        }
+    }
+
+    fn inline_table(&mut self, table: &InlineTable, dotted_start: Option<usize>) {
+        for (key, item) in table.items.iter() {
+            let start = dotted_start.or_else(|| key_start(key));
+            self.path.push(PathSegment::Key(key.get().to_owned()));
+            match item {
+                Item::Value(Value::InlineTable(child)) if child.is_dotted() => {
+                    self.inline_table(child, start);
+                }
+                Item::Value(value) => self.key_value(start, value),
+                _ => {}
+            }
+            self.path.pop();
+        }
+    }
+
+    fn key_value(&mut self, key_start: Option<usize>, value: &Value) {
+        if let Some(span) = value.span() {
+            let start = key_start.unwrap_or(span.start).min(span.start);
+            self.push(start..span.end);
+        }
+        self.value(value);
+    }
+
+    fn value(&mut self, value: &Value) {
+        match value {
+            Value::Array(array) => {
+                for (i, element) in array.iter().enumerate() {
+                    self.path.push(PathSegment::Index(i));
+                    if let Some(span) = element.span() {
+                        self.push(span);
+                    }
+                    self.value(element);
+                    self.path.pop();
+                }
+            }
+            Value::InlineTable(table) => self.inline_table(table, None),
+            _ => {}
+        }
+    }
+
+    fn push(&mut self, span: Range<usize>) {
+        let first = self.line(span.start);
+        let last = self.line(span.end.saturating_sub(1).max(span.start));
+        self.entries.push(LineEntry {
+            path: self.path.clone(),
+            span,
+            lines: first..last + 1,
+        });
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset) - 1
+    }
+}
+
+fn key_start(key: &Key) -> Option<usize> {
+    key.span().map(|span| span.start)
+}
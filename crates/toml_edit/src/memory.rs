@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::key::PathDecor;
+use crate::{Decor, DocumentMut, InlineTable, Item, Key, Table, Value};
+
+/// Memory used for the raw text of a document, see [`DocumentMut::memory_usage`]
+///
+/// Raw text is the whitespace, comments, and representation of keys and values.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    shared: usize,
+    owned: usize,
+}
+
+impl MemoryUsage {
+    /// Bytes of the input the document was parsed from
+    ///
+    /// Raw text from parsing is a slice of the input, so the input is kept, once, for as long
+    /// as any of it is in use.
+    pub fn shared(&self) -> usize {
+        self.shared
+    }
+
+    /// Bytes of raw text that were not parsed, like after an edit
+    ///
+    /// Common whitespace, like a single space or newline, is not allocated and is not counted.
+    pub fn owned(&self) -> usize {
+        self.owned
+    }
+
+    /// Bytes of raw text
+    pub fn total(&self) -> usize {
+        self.shared + self.owned
+    }
+}
+
+impl DocumentMut {
+    /// Measure the memory used for the raw text of the document
+    ///
+    /// This is an estimate for comparing documents and does not include allocator overhead,
+    /// unused capacity, nor the decoded keys and values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let input = "# comment\na = 1\nb = 2\n";
+    /// let mut doc = input.parse::<toml_edit::DocumentMut>().unwrap();
+    /// let usage = doc.memory_usage();
+    /// assert_eq!(usage.shared(), input.len());
+    /// assert_eq!(usage.owned(), 0);
+    ///
+    /// let mut key = doc.key_mut("a").unwrap();
+    /// key.leaf_decor_mut().set_prefix("# new\n").unwrap();
+    /// let usage = doc.memory_usage();
+    /// assert_eq!(usage.shared(), input.len());
+    /// assert_eq!(usage.owned(), 6);
+    /// # }
+    /// ```
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut measure = Measure::default();
+        measure.table(self.as_table());
+        self.trailing().measure(&mut measure);
+        measure.usage
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Measure {
+    usage: MemoryUsage,
+    inputs: HashSet<*const u8>,
+}
+
+impl Measure {
+    pub(crate) fn add_shared(&mut self, input: &Arc<str>) {
+        if self.inputs.insert(input.as_ptr()) {
+            self.usage.shared += input.len();
+        }
+    }
+
+    pub(crate) fn add_owned(&mut self, raw: &str) {
+        self.usage.owned += raw.len();
+    }
+
+    fn table(&mut self, table: &Table) {
+        self.decor(table.decor());
+        if let Some(header_decor) = &table.header_decor {
+            self.path_decor(header_decor);
+        }
+        for (key, item) in table.items.iter() {
+            self.key(key);
+            self.item(item);
+        }
+    }
+
+    fn inline_table(&mut self, table: &InlineTable) {
+        table.preamble().measure(self);
+        table.trailing().measure(self);
+        for (key, item) in table.items.iter() {
+            self.key(key);
+            self.item(item);
+        }
+    }
+
+    fn item(&mut self, item: &Item) {
+        match item {
+            Item::None => {}
+            Item::Value(value) => self.value(value),
+            Item::Table(table) => self.table(table),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter() {
+                    self.table(table);
+                }
+            }
+        }
+    }
+
+    fn value(&mut self, value: &Value) {
+        self.decor(value.decor());
+        let repr = match value {
+            Value::String(v) => v.as_repr(),
+            Value::Integer(v) => v.as_repr(),
+            Value::Float(v) => v.as_repr(),
+            Value::Boolean(v) => v.as_repr(),
+            Value::Datetime(v) => v.as_repr(),
+            Value::Array(array) => {
+                array.trailing().measure(self);
+                for value in array.iter() {
+                    self.value(value);
+                }
+                None
+            }
+            Value::InlineTable(table) => {
+                self.inline_table(table);
+                None
+            }
+        };
+        if let Some(repr) = repr {
+            repr.as_raw().measure(self);
+        }
+    }
+
+    fn key(&mut self, key: &Key) {
+        self.decor(key.leaf_decor());
+        self.decor(key.dotted_decor());
+        if let Some(path_decor) = &key.path_decor {
+            self.path_decor(path_decor);
+        }
+        if let Some(repr) = key.as_repr() {
+            repr.as_raw().measure(self);
+        }
+    }
+
+    fn path_decor(&mut self, path_decor: &PathDecor) {
+        for decor in &path_decor.dotted {
+            self.decor(decor);
+        }
+        if let Some(leaf) = &path_decor.leaf {
+            self.decor(leaf);
+        }
+    }
+
+    fn decor(&mut self, decor: &Decor) {
+        for raw in [decor.prefix(), decor.suffix()].into_iter().flatten() {
+            raw.measure(self);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Edit TOML embedded within a string value
+//!
+//! Some configs carry a TOML snippet inside a multi-line string.  A [`NestedDocument`] parses
+//! the string's content as its own document, which can be edited and then written back into the
+//! string with [`NestedDocument::write_to`], keeping the string's quoting and indentation.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(all(feature = "parse", feature = "display"))] {
+//! use toml_edit::nested::NestedDocument;
+//! use toml_edit::{value, DocumentMut, Value};
+//!
+//! let mut doc = r#"
+//! [tool.generator]
+//! template = '''
+//!     [package]
+//!     name = "app"
+//!     '''
+//! "#.parse::<DocumentMut>().unwrap();
+//!
+//! let Some(Value::String(string)) = doc["tool"]["generator"]["template"].as_value_mut() else {
+//!     panic!("the template is a string");
+//! };
+//! let mut nested = NestedDocument::parse(string).unwrap();
+//! nested.as_document_mut()["package"]["edition"] = value("2021");
+//! nested.write_to(string);
+//!
+//! assert_eq!(doc.to_string(), r#"
+//! [tool.generator]
+//! template = '''
+//!     [package]
+//!     name = "app"
+//!     edition = "2021"
+//!     '''
+//! "#);
+//! # }
+//! ```
+
+use std::ops::Range;
+
+use toml_write::ToTomlValue as _;
+
+use crate::repr::Repr;
+use crate::{Document, DocumentMut, Formatted, TomlError};
+
+/// A TOML document parsed from the content of a string value, see the [module docs][self]
+#[derive(Clone, Debug)]
+pub struct NestedDocument {
+    doc: DocumentMut,
+    indent: String,
+    offset: Option<usize>,
+}
+
+impl NestedDocument {
+    /// Parse the content of `string` as a TOML document
+    ///
+    /// The spans of the document, and of any error, are relative to the string's content.
+    /// See [`NestedDocument::outer_span`] for where they are in the document `string` is from.
+    pub fn parse(string: &Formatted<String>) -> Result<Self, TomlError> {
+        let content = string.value();
+        let doc = Document::parse(content.clone())?.into_mut_with_spans();
+        Ok(Self {
+            doc,
+            indent: indent(content).to_owned(),
+            offset: content_offset(string),
+        })
+    }
+
+    /// The embedded document
+    pub fn as_document(&self) -> &DocumentMut {
+        &self.doc
+    }
+
+    /// The embedded document, for editing
+    pub fn as_document_mut(&mut self) -> &mut DocumentMut {
+        &mut self.doc
+    }
+
+    /// Where `span`, relative to the string's content, is in the document the string is from
+    ///
+    /// This is only known when the string has a [span][Formatted::span], like from
+    /// [`DocumentMut::parse_with_spans`], and its content is written as-is, without escapes.
+    pub fn outer_span(&self, span: Range<usize>) -> Option<Range<usize>> {
+        let offset = self.offset?;
+        Some(offset + span.start..offset + span.end)
+    }
+
+    /// Replace the content of `string` with this document
+    ///
+    /// The string keeps its quoting when the new content allows it, like a multi-line literal
+    /// string staying one unless the content has `'''` in it.  Lines added since parsing are
+    /// indented like the content was, so a snippet indented within a config stays that way.
+    pub fn write_to(&self, string: &mut Formatted<String>) {
+        let mut content = String::new();
+        for line in self.doc.to_string().split_inclusive('\n') {
+            if !line.trim().is_empty() && !line.starts_with(&self.indent) {
+                content.push_str(&self.indent);
+            }
+            content.push_str(line);
+        }
+
+        let quotes = string.raw().map(quotes).unwrap_or_default();
+        let builder = toml_write::TomlStringBuilder::new(&content);
+        let repr = match quotes {
+            "'''" => builder
+                .as_ml_literal()
+                .unwrap_or_else(|| builder.as_ml_basic()),
+            "\"\"\"" => builder
+                .as_ml_basic_pretty()
+                .unwrap_or_else(|| builder.as_ml_basic()),
+            "'" => builder.as_literal().unwrap_or_else(|| builder.as_default()),
+            "\"" => builder.as_basic(),
+            _ => builder.as_default(),
+        }
+        .to_toml_value();
+
+        let decor = string.decor().clone();
+        *string = Formatted::new(content);
+        string.set_repr_unchecked(Repr::new_unchecked(repr));
+        *string.decor_mut() = decor;
+    }
+}
+
+/// The whitespace every non-blank line of `content` starts with
+fn indent(content: &str) -> &str {
+    let mut indent: Option<&str> = None;
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let line_indent = &line[..line.len() - line.trim_start().len()];
+        indent = Some(match indent {
+            None => line_indent,
+            Some(indent) => {
+                let common = indent
+                    .char_indices()
+                    .zip(line_indent.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map(|((i, _), _)| i)
+                    .unwrap_or_else(|| indent.len().min(line_indent.len()));
+                &indent[..common]
+            }
+        });
+    }
+    indent.unwrap_or_default()
+}
+
+/// Where the content of `string` starts in the document it is from, when written as-is
+fn content_offset(string: &Formatted<String>) -> Option<usize> {
+    let span = string.span()?;
+    let raw = string.raw()?;
+    let quotes = quotes(raw);
+    let mut start = quotes.len();
+    if quotes.len() == 3 {
+        // A newline right after the opening quotes isn't part of the content
+        if raw[start..].starts_with("\r\n") {
+            start += 2;
+        } else if raw[start..].starts_with('\n') {
+            start += 1;
+        }
+    }
+    let end = raw.len().checked_sub(quotes.len())?;
+    let body = raw.get(start..end)?;
+    (body == string.value()).then(|| span.start + start)
+}
+
+/// The quotes `raw` is delimited with
+fn quotes(raw: &str) -> &'static str {
+    ["'''", "\"\"\"", "'", "\""]
+        .into_iter()
+        .find(|quotes| raw.starts_with(quotes))
+        .unwrap_or_default()
+}
@@ -0,0 +1,162 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{DocumentMut, Item, Key, Table, TablePlacement, Value};
+
+impl DocumentMut {
+    /// Build a document from dotted keys and their values
+    ///
+    /// Each value goes in the table for its key's parent, like `server.port` in `[server]`, with
+    /// keys without a parent at the root.  Tables are ordered by `placement`, as if each were
+    /// [placed][DocumentMut::place_table] when a key first needed it.
+    ///
+    /// Keys are parsed as TOML, so a `.` within a key needs quotes, like `'a.b'.c`.  Values are
+    /// written with the default [`Decor`][crate::Decor].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{DocumentMut, TablePlacement};
+    ///
+    /// // From `APP_SERVER_PORT=8080`-style environment variables
+    /// let doc = DocumentMut::from_pairs(
+    ///     [
+    ///         ("name", "app".into()),
+    ///         ("server.port", 8080.into()),
+    ///         ("log.level", "debug".into()),
+    ///         ("server.host", "localhost".into()),
+    ///     ],
+    ///     TablePlacement::Sorted,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(doc.to_string(), r#"name = "app"
+    ///
+    /// [log]
+    /// level = "debug"
+    ///
+    /// [server]
+    /// port = 8080
+    /// host = "localhost"
+    /// "#);
+    ///
+    /// let err = DocumentMut::from_pairs(
+    ///     [("server", "localhost".into()), ("server.port", 8080.into())],
+    ///     TablePlacement::End,
+    /// )
+    /// .unwrap_err();
+    /// assert_eq!(err.to_string(), "pair 1 at `server.port` failed: `server` is a string");
+    /// # }
+    /// ```
+    pub fn from_pairs<K: AsRef<str>>(
+        pairs: impl IntoIterator<Item = (K, Value)>,
+        placement: TablePlacement<'_>,
+    ) -> Result<Self, FromPairsError> {
+        let mut doc = DocumentMut::new();
+        let mut tables = Vec::<Vec<String>>::new();
+        for (index, (path, mut value)) in pairs.into_iter().enumerate() {
+            let path = path.as_ref();
+            let error = |reason| FromPairsError {
+                index,
+                path: path.to_owned(),
+                reason,
+            };
+            let mut keys = Key::parse(path)
+                .map_err(|err| error(Reason::InvalidKey(err.message().trim_end().to_owned())))?;
+            for key in &mut keys {
+                key.leaf_decor_mut().clear();
+                key.dotted_decor_mut().clear();
+            }
+            let key = keys.pop().ok_or_else(|| error(Reason::EmptyPath))?;
+
+            let mut table = doc.as_table_mut();
+            for (i, parent) in keys.iter().enumerate() {
+                let name = keys[..=i].iter().map(|k| k.get()).collect::<Vec<_>>();
+                let item = table.entry_format(parent).or_insert_with(|| {
+                    tables.push(name.iter().map(|k| (*k).to_owned()).collect());
+                    let mut table = Table::new();
+                    table.set_implicit(true);
+                    Item::Table(table)
+                });
+                table = match item {
+                    Item::Table(table) => table,
+                    item => return Err(error(Reason::NotATable(name.join("."), item.type_name()))),
+                };
+            }
+            if let Some(existing) = table.get(key.get()) {
+                return Err(error(Reason::Taken(existing.type_name())));
+            }
+            value.decor_mut().clear();
+            table.insert_formatted(&key, Item::Value(value));
+
+            if !keys.is_empty() {
+                table.set_implicit(false);
+            }
+        }
+
+        for table in &tables {
+            let path = table.iter().map(String::as_str).collect::<Vec<_>>();
+            doc.place_table(&path, placement);
+        }
+        Ok(doc)
+    }
+}
+
+/// A pair passed to [`DocumentMut::from_pairs`] could not be added
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FromPairsError {
+    index: usize,
+    path: String,
+    reason: Reason,
+}
+
+impl FromPairsError {
+    /// The position of the pair
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The dotted key of the pair
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Display for FromPairsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "pair {} at `{}` failed: {}",
+            self.index, self.path, self.reason
+        )
+    }
+}
+
+impl std::error::Error for FromPairsError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Reason {
+    InvalidKey(String),
+    EmptyPath,
+    NotATable(String, &'static str),
+    Taken(&'static str),
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKey(message) => write!(f, "invalid key: {message}"),
+            Self::EmptyPath => f.write_str("path is empty"),
+            Self::NotATable(key, existing) => write!(f, "`{key}` is {}", article(existing)),
+            Self::Taken(existing) => write!(f, "key is already {}", article(existing)),
+        }
+    }
+}
+
+fn article(type_name: &str) -> String {
+    let article = if type_name.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    };
+    format!("{article} {type_name}")
+}
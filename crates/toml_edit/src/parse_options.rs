@@ -0,0 +1,135 @@
+use toml_parse::{ErrorSink, ParseError, Span, TomlSpec};
+
+use crate::{Array, InlineTable, Item, Table, Value};
+
+/// How to parse a TOML document, see [`Document::parse_with_options`][crate::Document::parse_with_options]
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "display")] {
+/// use toml_edit::{Document, ParseOptions, TomlSpec};
+///
+/// let input = "point = { x = 1, y = 2, }\n";
+/// assert!(Document::parse(input).is_err());
+///
+/// let options = ParseOptions::new().spec(TomlSpec::V1_1Draft);
+/// let doc = Document::parse_with_options(input, &options).unwrap();
+/// assert_eq!(doc.into_mut().to_string(), input);
+///
+/// let input = "ports = [8080, '8081']\n";
+/// assert!(Document::parse(input).is_ok());
+///
+/// let options = ParseOptions::new().spec(TomlSpec::V0_5);
+/// assert!(Document::parse_with_options(input, &options).is_err());
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    spec: TomlSpec,
+    carriage_return: crate::CarriageReturn,
+}
+
+impl ParseOptions {
+    /// Parse TOML 1.0, rejecting lone carriage returns
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The version of TOML to accept
+    ///
+    /// Defaults to [`TomlSpec::V1_0`].  Older versions reject what was added since, like
+    /// mixed-type arrays for [`TomlSpec::V0_5`], and newer ones accept what was added, like
+    /// trailing commas and newlines in inline tables for [`TomlSpec::V1_1Draft`].
+    pub fn spec(mut self, spec: TomlSpec) -> Self {
+        self.spec = spec;
+        self
+    }
+
+    /// How to handle a carriage return that is not part of a `\r\n` line ending, see
+    /// [`Document::parse_with_carriage_return`][crate::Document::parse_with_carriage_return]
+    pub fn carriage_return(mut self, carriage_return: crate::CarriageReturn) -> Self {
+        self.carriage_return = carriage_return;
+        self
+    }
+
+    pub(crate) fn get_spec(&self) -> TomlSpec {
+        self.spec
+    }
+
+    pub(crate) fn get_carriage_return(&self) -> crate::CarriageReturn {
+        self.carriage_return
+    }
+}
+
+/// Report arrays mixing types, which TOML only allows since 1.0
+///
+/// All kinds of strings are one type, as are all arrays and all inline tables.
+pub(crate) fn check_mixed_arrays(table: &Table, errors: &mut dyn ErrorSink) {
+    for item in table.items.values() {
+        match item {
+            Item::None => {}
+            Item::Value(value) => check_value(value, errors),
+            Item::Table(table) => check_mixed_arrays(table, errors),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter() {
+                    check_mixed_arrays(table, errors);
+                }
+            }
+        }
+    }
+}
+
+fn check_value(value: &Value, errors: &mut dyn ErrorSink) {
+    match value {
+        Value::Array(array) => check_array(array, errors),
+        Value::InlineTable(table) => check_inline_table(table, errors),
+        _ => {}
+    }
+}
+
+fn check_inline_table(table: &InlineTable, errors: &mut dyn ErrorSink) {
+    for item in table.items.values() {
+        if let Item::Value(value) = item {
+            check_value(value, errors);
+        }
+    }
+}
+
+fn check_array(array: &Array, errors: &mut dyn ErrorSink) {
+    let mut values = array.iter();
+    if let Some(first) = values.next() {
+        let kind = value_kind(first);
+        if let Some(mixed) = values.find(|value| value_kind(value) != kind) {
+            let mut error =
+                ParseError::new("mixed types in arrays are not allowed before TOML 1.0")
+                    .with_allowed_since(TomlSpec::V1_0);
+            if let Some(span) = array.span() {
+                error = error.with_context(Span::new_unchecked(span.start, span.end));
+            }
+            if let Some(span) = mixed.span() {
+                error = error.with_unexpected(Span::new_unchecked(span.start, span.end));
+            }
+            errors.report_error(error);
+        }
+    }
+    for value in array.iter() {
+        check_value(value, errors);
+    }
+}
+
+/// The TOML 0.5 type of `value`, where each kind of date-time is its own type
+fn value_kind(value: &Value) -> (&'static str, bool, bool, bool) {
+    match value {
+        Value::Datetime(datetime) => {
+            let datetime = datetime.value();
+            (
+                "datetime",
+                datetime.date.is_some(),
+                datetime.time.is_some(),
+                datetime.offset.is_some(),
+            )
+        }
+        _ => (value.type_name(), false, false, false),
+    }
+}
@@ -19,7 +19,7 @@ pub(crate) fn on_array(
 ) -> Value {
     #[cfg(feature = "debug")]
     let _scope = TraceScope::new("array::on_array");
-    let mut result = Array::new();
+    let mut result = Array::with_capacity(super::count_values(input));
 
     let mut state = State::default();
     while let Some(event) = input.next_token() {
@@ -119,8 +119,8 @@ impl State {
                 .take()
                 .unwrap_or_else(|| event.span().before());
             let decor = value.decor_mut();
-            decor.set_prefix(RawString::with_span(prefix.start()..prefix.end()));
-            decor.set_suffix(RawString::with_span(suffix.start()..suffix.end()));
+            decor.set_prefix_unchecked(RawString::with_span(prefix.start()..prefix.end()));
+            decor.set_suffix_unchecked(RawString::with_span(suffix.start()..suffix.end()));
             result.push_formatted(value);
         }
     }
@@ -145,7 +145,7 @@ impl State {
         let trailing_end = close_event.span().start();
 
         result.set_trailing_comma(trailing_comma);
-        result.set_trailing(RawString::with_span(trailing_start..trailing_end));
+        result.set_trailing_unchecked(RawString::with_span(trailing_start..trailing_end));
         result.span = Some(span.start()..span.end());
     }
 }
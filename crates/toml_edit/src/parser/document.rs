@@ -1,9 +1,11 @@
-use crate::key::Key;
+use crate::conflict::{DuplicateKey, KeyConflict};
+use crate::key::{Key, PathDecor};
 use crate::parser::key::on_key;
 use crate::parser::prelude::*;
 use crate::parser::value::value;
 use crate::repr::Decor;
 use crate::Item;
+use crate::PathSegment;
 use crate::RawString;
 use crate::Value;
 use crate::{ArrayOfTables, Document, Table};
@@ -22,11 +24,17 @@ pub(crate) fn document<'s>(
     input: &mut Input<'_>,
     source: toml_parse::Source<'s>,
     errors: &mut dyn ErrorSink,
-) -> Document<&'s str> {
+    mut progress: Option<&mut crate::progress::StageProgress<'_, '_>>,
+) -> Option<Document<&'s str>> {
     #[cfg(feature = "debug")]
     let _scope = TraceScope::new("document::document");
     let mut state = State::default();
     while let Some(event) = input.next_token() {
+        if let Some(progress) = progress.as_deref_mut() {
+            if !progress.advance(event.span().end()) {
+                return None;
+            }
+        }
         match event.kind() {
             EventKind::InlineTableOpen
             | EventKind::InlineTableClose
@@ -54,25 +62,31 @@ pub(crate) fn document<'s>(
                 let suffix = ws_comment_newline(input)
                     .map(|s| RawString::with_span(s.start()..s.end()))
                     .unwrap_or_default();
-                let decor = Decor::new(prefix, suffix);
+                let decor = Decor::new_unchecked(prefix, suffix);
 
                 state.start_table(header, decor, errors);
             }
             EventKind::SimpleKey => {
-                let key_prefix = state.take_trailing();
+                let checkpoint = input.checkpoint();
                 let (path, key) = on_key(event, input, source, errors);
                 let Some(mut key) = key else {
-                    break;
+                    input.reset(&checkpoint);
+                    state.capture_trailing(skip_invalid_line(event, input));
+                    continue;
                 };
                 let Some(next_event) = input.next_token() else {
-                    break;
+                    input.reset(&checkpoint);
+                    state.capture_trailing(skip_invalid_line(event, input));
+                    continue;
                 };
                 let keyval_event;
                 let key_suffix;
                 if next_event.kind() == EventKind::Whitespace {
                     key_suffix = Some(next_event);
                     let Some(next_event) = input.next_token() else {
-                        break;
+                        input.reset(&checkpoint);
+                        state.capture_trailing(skip_invalid_line(event, input));
+                        continue;
                     };
                     keyval_event = next_event;
                 } else {
@@ -80,13 +94,16 @@ pub(crate) fn document<'s>(
                     keyval_event = next_event;
                 }
                 if keyval_event.kind() != EventKind::KeyValSep {
-                    break;
+                    input.reset(&checkpoint);
+                    state.capture_trailing(skip_invalid_line(event, input));
+                    continue;
                 }
+                let key_prefix = state.take_trailing();
                 let key_suffix = key_suffix
                     .map(|e| RawString::with_span(e.span().start()..e.span().end()))
                     .unwrap_or_default();
-                key.leaf_decor.set_prefix(key_prefix);
-                key.leaf_decor.set_suffix(key_suffix);
+                key.leaf_decor.set_prefix_unchecked(key_prefix);
+                key.leaf_decor.set_suffix_unchecked(key_suffix);
 
                 let value_prefix = if input
                     .first()
@@ -105,13 +122,13 @@ pub(crate) fn document<'s>(
                     .map(|s| RawString::with_span(s.start()..s.end()))
                     .unwrap_or_default();
                 let decor = value.decor_mut();
-                decor.set_prefix(value_prefix);
-                decor.set_suffix(value_suffix);
+                decor.set_prefix_unchecked(value_prefix);
+                decor.set_suffix_unchecked(value_suffix);
 
                 state.capture_key_value(path, key, value, errors);
             }
             EventKind::Whitespace | EventKind::Comment | EventKind::Newline => {
-                state.capture_trailing(event);
+                state.capture_trailing(event.span());
             }
         }
     }
@@ -119,11 +136,15 @@ pub(crate) fn document<'s>(
     state.finish_table(errors);
 
     let trailing = state.take_trailing();
-    Document {
+    Some(Document {
         root: Item::Table(state.root),
         trailing,
         raw: source.input(),
-    }
+        conflicts: state.conflicts,
+        duplicates: state.duplicates,
+        opaque: Vec::new(),
+        normalize_cr: false,
+    })
 }
 
 /// ```bnf
@@ -202,13 +223,18 @@ fn on_table(
     if let Some(last_key) = current_key.as_mut() {
         let prefix = RawString::with_span(prefix.start()..prefix.end());
         let suffix = RawString::with_span(suffix.start()..suffix.end());
-        let leaf_decor = Decor::new(prefix, suffix);
+        let leaf_decor = Decor::new_unchecked(prefix, suffix);
         *last_key.leaf_decor_mut() = leaf_decor;
     }
 
+    let path = current_path.unwrap_or_default();
+    let path_decor = current_key
+        .as_ref()
+        .map(|key| Box::new(PathDecor::header(&path, key)));
     TableHeader {
-        path: current_path.unwrap_or_default(),
+        path,
         key: current_key,
+        path_decor,
         span: current_span,
         is_array,
     }
@@ -217,10 +243,26 @@ fn on_table(
 struct TableHeader {
     path: Vec<Key>,
     key: Option<Key>,
+    path_decor: Option<Box<PathDecor>>,
     span: toml_parse::Span,
     is_array: bool,
 }
 
+/// Skip the rest of a line that could not be parsed, returning its span
+///
+/// The span is kept as decor so the invalid content is written back out as-is.
+fn skip_invalid_line(start: &toml_parse::parser::Event, input: &mut Input<'_>) -> toml_parse::Span {
+    let mut span = start.span();
+    while let Some(event) = input.first() {
+        if event.kind() == EventKind::Newline {
+            break;
+        }
+        span = span.append(event.span());
+        input.next_token();
+    }
+    span
+}
+
 fn ws_comment_newline(input: &mut Input<'_>) -> Option<toml_parse::Span> {
     let mut current_span = None;
     while let Some(event) = input.next_token() {
@@ -265,18 +307,20 @@ struct State {
     current_trailing: Option<toml_parse::Span>,
     current_header: Option<TableHeader>,
     current_position: usize,
+    conflicts: Vec<KeyConflict>,
+    duplicates: Vec<DuplicateKey>,
 }
 
 impl State {
-    fn capture_trailing(&mut self, event: &toml_parse::parser::Event) {
-        let decor = self.current_trailing.get_or_insert(event.span());
-        *decor = decor.append(event.span());
+    fn capture_trailing(&mut self, span: toml_parse::Span) {
+        let decor = self.current_trailing.get_or_insert(span);
+        *decor = decor.append(span);
     }
 
     fn capture_key_value(
         &mut self,
         path: Vec<Key>,
-        key: Key,
+        mut key: Key,
         value: Value,
         errors: &mut dyn ErrorSink,
     ) {
@@ -301,6 +345,9 @@ impl State {
             anstyle::AnsiColor::Blue.on_default(),
         );
 
+        if !path.is_empty() {
+            key.path_decor = Some(Box::new(PathDecor::dotted_key(&path, &key)));
+        }
         let dotted = true;
         let Some(parent_table) = descend_path(&mut self.current_table, &path, dotted, errors)
         else {
@@ -321,6 +368,26 @@ impl State {
             indexmap::map::Entry::Occupied(existing) => {
                 // "Since tables cannot be defined more than once, redefining such tables using a [table] header is not allowed"
                 let old_span = existing.key().span().expect("all items have spans");
+                if let Item::Value(old_value) = existing.get() {
+                    let path = duplicate_path(
+                        &self.root,
+                        self.current_header.as_ref(),
+                        &path,
+                        existing.key(),
+                    );
+                    let key_span = key_span.start()..key_span.end();
+                    match self.duplicates.iter_mut().find(|d| d.path == path) {
+                        Some(duplicate) => {
+                            duplicate.spans.push(key_span);
+                            duplicate.values.push(value);
+                        }
+                        None => self.duplicates.push(DuplicateKey {
+                            path,
+                            spans: vec![old_span.clone(), key_span],
+                            values: vec![old_value.clone(), value],
+                        }),
+                    }
+                }
                 let old_span = toml_parse::Span::new_unchecked(old_span.start, old_span.end);
                 errors.report_error(
                     ParseError::new("duplicate key")
@@ -387,7 +454,7 @@ impl State {
         }
     }
 
-    fn start_table(&mut self, header: TableHeader, decor: Decor, errors: &mut dyn ErrorSink) {
+    fn start_table(&mut self, mut header: TableHeader, decor: Decor, errors: &mut dyn ErrorSink) {
         if !header.is_array {
             // 1. Look up the table on start to ensure the duplicate_key error points to the right line
             // 2. Ensure any child tables from an implicit table are preserved
@@ -403,9 +470,29 @@ impl State {
                         // Since tables cannot be defined more than once, redefining such tables using a [table] header is not allowed. Likewise, using dotted keys to redefine tables already defined in [table] form is not allowed.
                         old_value => {
                             let old_span = old_key.span().expect("all items have spans");
+                            let key_span = get_key_span(key).expect("all keys have spans");
+                            let is_dotted = match &old_value {
+                                Item::Value(_) => parent_table.is_dotted(),
+                                Item::Table(t) => t.is_dotted(),
+                                Item::ArrayOfTables(_) | Item::None => false,
+                            };
+                            if is_dotted {
+                                let mut path = header
+                                    .path
+                                    .iter()
+                                    .map(|k| k.get().to_owned())
+                                    .collect::<Vec<_>>();
+                                path.push(key.get().to_owned());
+                                self.conflicts.push(KeyConflict {
+                                    path,
+                                    dotted_span: old_span.clone(),
+                                    header_span: key_span.start()..key_span.end(),
+                                    dotted_key: old_key,
+                                    dotted: old_value.clone(),
+                                });
+                            }
                             let old_span =
                                 toml_parse::Span::new_unchecked(old_span.start, old_span.end);
-                            let key_span = get_key_span(key).expect("all keys have spans");
                             errors.report_error(
                                 ParseError::new("duplicate key")
                                     .with_unexpected(key_span)
@@ -427,6 +514,7 @@ impl State {
         self.current_table.set_dotted(false);
         self.current_table.set_position(self.current_position);
         self.current_table.span = Some(header.span.start()..header.span.end());
+        self.current_table.header_decor = header.path_decor.take();
         self.current_header = Some(header);
     }
 
@@ -438,6 +526,45 @@ impl State {
     }
 }
 
+/// The path to a key/value pair in the current table, with the index of each array of tables
+fn duplicate_path(
+    root: &Table,
+    header: Option<&TableHeader>,
+    dotted: &[Key],
+    key: &Key,
+) -> Vec<PathSegment> {
+    let mut path = Vec::new();
+    if let Some(header) = header {
+        let mut table = Some(root);
+        for parent in &header.path {
+            path.push(PathSegment::Key(parent.get().to_owned()));
+            table = match table.and_then(|t| t.get(parent.get())) {
+                Some(Item::Table(t)) => Some(t),
+                Some(Item::ArrayOfTables(array)) => {
+                    path.push(PathSegment::Index(array.len().saturating_sub(1)));
+                    array.values.last().and_then(Item::as_table)
+                }
+                _ => None,
+            };
+        }
+        if let Some(header_key) = &header.key {
+            path.push(PathSegment::Key(header_key.get().to_owned()));
+            if header.is_array {
+                // The current table is added to the array once it is finished
+                let index = table
+                    .and_then(|t| t.get(header_key.get()))
+                    .and_then(Item::as_array_of_tables)
+                    .map(|array| array.len())
+                    .unwrap_or(0);
+                path.push(PathSegment::Index(index));
+            }
+        }
+    }
+    path.extend(dotted.iter().map(|k| PathSegment::Key(k.get().to_owned())));
+    path.push(PathSegment::Key(key.get().to_owned()));
+    path
+}
+
 fn descend_path<'t>(
     mut table: &'t mut Table,
     path: &[Key],
@@ -1,4 +1,4 @@
-use crate::key::Key;
+use crate::key::{Key, PathDecor};
 use crate::parser::array::on_array;
 use crate::parser::key::on_key;
 use crate::parser::prelude::*;
@@ -21,7 +21,7 @@ pub(crate) fn on_inline_table(
 ) -> Value {
     #[cfg(feature = "debug")]
     let _scope = TraceScope::new("inline_table::on_inline_table");
-    let mut result = InlineTable::new();
+    let mut result = InlineTable::with_capacity(super::count_values(input));
 
     let mut state = State::default();
     while let Some(event) = input.next_token() {
@@ -68,6 +68,7 @@ pub(crate) fn on_inline_table(
             }
             EventKind::ValueSep => {
                 state.finish_value(event, &mut result, errors);
+                state.seen_value_sep = true;
             }
             EventKind::Whitespace | EventKind::Comment | EventKind::Newline => {
                 state.whitespace(event);
@@ -90,6 +91,7 @@ struct State {
     seen_keyval_sep: bool,
     current_value: Option<Value>,
     current_suffix: Option<toml_parse::Span>,
+    seen_value_sep: bool,
 }
 
 impl State {
@@ -111,6 +113,7 @@ impl State {
     }
 
     fn capture_key(&mut self, event: &toml_parse::parser::Event, path: Vec<Key>, key: Option<Key>) {
+        self.seen_value_sep = false;
         self.current_prefix
             .get_or_insert_with(|| event.span().before());
         if let Some(key) = key {
@@ -131,7 +134,7 @@ impl State {
                 .unwrap_or_else(|| event.span().before());
             let prefix = RawString::with_span(prefix.start()..prefix.end());
             let suffix = RawString::with_span(suffix.start()..suffix.end());
-            let leaf_decor = Decor::new(prefix, suffix);
+            let leaf_decor = Decor::new_unchecked(prefix, suffix);
             *last_key.leaf_decor_mut() = leaf_decor;
         }
     }
@@ -151,9 +154,12 @@ impl State {
         #[cfg(feature = "debug")]
         let _scope = TraceScope::new("inline_table::finish_value");
         self.seen_keyval_sep = false;
-        if let (Some((path, key)), Some(mut value)) =
+        if let (Some((path, mut key)), Some(mut value)) =
             (self.current_key.take(), self.current_value.take())
         {
+            if !path.is_empty() {
+                key.path_decor = Some(Box::new(PathDecor::dotted_key(&path, &key)));
+            }
             let prefix = self
                 .current_prefix
                 .take()
@@ -167,8 +173,8 @@ impl State {
             };
 
             let decor = value.decor_mut();
-            decor.set_prefix(RawString::with_span(prefix.start()..prefix.end()));
-            decor.set_suffix(RawString::with_span(suffix.start()..suffix.end()));
+            decor.set_prefix_unchecked(RawString::with_span(prefix.start()..prefix.end()));
+            decor.set_suffix_unchecked(RawString::with_span(suffix.start()..suffix.end()));
 
             // "Likewise, using dotted keys to redefine tables already defined in [table] form is not allowed"
             let mixed_table_types = table.is_dotted() == path.is_empty();
@@ -209,8 +215,12 @@ impl State {
             .map(|prefix| RawString::with_span(prefix.start()..prefix.end()));
 
         result.span = Some(span.start()..span.end());
-        if let Some(preamble) = preamble {
-            result.set_preamble(preamble);
+        if self.seen_value_sep && !result.items.is_empty() {
+            // A TOML 1.1 trailing comma, with `preamble` being what follows it
+            result.set_trailing_comma(true);
+            result.set_trailing_unchecked(preamble.unwrap_or_default());
+        } else if let Some(preamble) = preamble {
+            result.set_preamble_unchecked(preamble);
         }
     }
 }
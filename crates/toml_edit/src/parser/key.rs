@@ -133,16 +133,14 @@ impl State {
         let key_span = key.span();
         let key_raw = RawString::with_span(key_span.start()..key_span.end());
 
-        #[cfg(feature = "unsafe")] // SAFETY: lexing and parsing all with same source
-        let raw = unsafe { source.get_unchecked(key) };
-        #[cfg(not(feature = "unsafe"))]
-        let raw = source.get(key).unwrap();
         let mut decoded = std::borrow::Cow::Borrowed("");
-        raw.decode_key(&mut decoded, errors);
+        if let Some(raw) = super::get_raw(source, &key, errors) {
+            raw.decode_key(&mut decoded, errors);
+        }
 
         let key = Key::new(decoded)
             .with_repr_unchecked(Repr::new_unchecked(key_raw))
-            .with_dotted_decor(Decor::new(prefix, suffix));
+            .with_dotted_decor_unchecked(Decor::new_unchecked(prefix, suffix));
         if let Some(last_key) = result_key.replace(key) {
             result_path.push(last_key);
         }
@@ -160,13 +158,10 @@ pub(crate) fn on_simple_key(
 ) -> (RawString, InternalString) {
     #[cfg(feature = "debug")]
     let _scope = TraceScope::new("key::on_simple_key");
-    #[cfg(feature = "unsafe")] // SAFETY: lexing and parsing all with same source
-    let raw = unsafe { source.get_unchecked(event) };
-    #[cfg(not(feature = "unsafe"))]
-    let raw = source.get(event).unwrap();
-
     let mut key = std::borrow::Cow::Borrowed("");
-    raw.decode_key(&mut key, errors);
+    if let Some(raw) = super::get_raw(source, event, errors) {
+        raw.decode_key(&mut key, errors);
+    }
 
     let span = event.span();
     let raw = RawString::with_span(span.start()..span.end());
@@ -1,44 +1,142 @@
+//! Lexing and parsing TOML into events, from [`toml_parse`]
+//!
+//! These are what [`DocumentMut`][crate::DocumentMut] is parsed with, re-exported for when
+//! both editing and the lower-level [events][Event] or [tokens][lexer::Token] are needed.
+//! They are covered by the semver of `toml_edit`, so they can't drift from what it uses.
+//!
+//! # Example
+//!
+//! ```rust
+//! use toml_edit::parser::{parse_document, Event, EventKind, Source};
+//!
+//! let source = Source::new("a = 1 # one\n");
+//! let tokens = source.lex().into_vec();
+//! let mut events = Vec::<Event>::new();
+//! let mut errors = Vec::new();
+//! parse_document(&tokens, &mut events, &mut errors);
+//! assert!(errors.is_empty());
+//!
+//! let comment = events.iter().find(|e| e.kind() == EventKind::Comment).unwrap();
+//! assert_eq!(source.get(comment).unwrap().as_str(), "# one");
+//! ```
+
 #![allow(clippy::type_complexity)]
 
 use crate::RawString;
-#[cfg(not(feature = "unbounded"))]
-use toml_parse::parser::RecursionGuard;
-use toml_parse::parser::ValidateWhitespace;
 use winnow::stream::Stream as _;
 
+pub use toml_parse::decoder;
+pub use toml_parse::lexer;
+pub use toml_parse::parser::*;
+pub use toml_parse::{
+    ErrorSink, Expected, ParseError, Raw, Severity, Source, SourceId, Span, ValueKind,
+};
+
 pub(crate) mod array;
 #[cfg(feature = "debug")]
 pub(crate) mod debug;
 pub(crate) mod document;
 pub(crate) mod inline_table;
 pub(crate) mod key;
+pub(crate) mod opaque;
 pub(crate) mod value;
 
-pub(crate) fn parse_document<'s>(
-    source: toml_parse::Source<'s>,
-    errors: &mut dyn prelude::ErrorSink,
+pub(crate) fn to_document<'s>(
+    source: Source<'s>,
+    carriage_return: decoder::CarriageReturn,
+    errors: &mut dyn ErrorSink,
 ) -> crate::Document<&'s str> {
-    let tokens = source.lex().into_vec();
+    build_document(source, carriage_return, errors, None)
+        .expect("only cancelled through a reporter")
+}
+
+/// Like [`to_document`] but reporting progress, returning `None` once cancelled
+pub(crate) fn to_document_with_progress<'s>(
+    source: Source<'s>,
+    carriage_return: decoder::CarriageReturn,
+    errors: &mut dyn ErrorSink,
+    reporter: &mut crate::progress::Reporter<'_>,
+) -> Option<crate::Document<&'s str>> {
+    build_document(source, carriage_return, errors, Some(reporter))
+}
+
+fn build_document<'s>(
+    source: Source<'s>,
+    carriage_return: decoder::CarriageReturn,
+    errors: &mut dyn ErrorSink,
+    mut reporter: Option<&mut crate::progress::Reporter<'_>>,
+) -> Option<crate::Document<&'s str>> {
+    let lexer = source.lex().with_carriage_return(carriage_return);
+    let tokens = match reporter.as_deref_mut() {
+        Some(reporter) => {
+            let mut tokens = Vec::with_capacity(source.input().len());
+            let mut lexing = reporter.lexing();
+            for token in lexer {
+                let end = token.span().end();
+                tokens.push(token);
+                if !lexing.advance(end) {
+                    return None;
+                }
+            }
+            if !lexing.finish() {
+                return None;
+            }
+            tokens
+        }
+        None => lexer.into_vec(),
+    };
 
     let mut events = Vec::with_capacity(tokens.len());
-    let mut receiver = ValidateWhitespace::new(&mut events, source);
+    let mut receiver =
+        ValidateWhitespace::new(&mut events, source).with_carriage_return(carriage_return);
     #[cfg(not(feature = "unbounded"))]
     let mut receiver = RecursionGuard::new(&mut receiver, LIMIT);
-    #[cfg(not(feature = "unbounded"))]
-    let receiver = &mut receiver;
-    #[cfg(feature = "unbounded")]
-    let receiver = &mut receiver;
-    toml_parse::parser::parse_document(&tokens, receiver, errors);
+    match reporter.as_deref_mut() {
+        Some(reporter) => {
+            let mut receiver = crate::progress::ParsingProgress::new(&mut receiver, reporter);
+            parse_document(&tokens, &mut receiver, errors);
+            if !receiver.finish() {
+                return None;
+            }
+        }
+        None => parse_document(&tokens, &mut receiver, errors),
+    }
 
     let mut input = prelude::Input::new(&events);
-    let doc = document::document(&mut input, source, errors);
-    doc
+    match reporter {
+        Some(reporter) => {
+            let mut building = reporter.building();
+            let doc = document::document(&mut input, source, errors, Some(&mut building))?;
+            building.finish().then_some(doc)
+        }
+        None => document::document(&mut input, source, errors, None),
+    }
 }
 
-pub(crate) fn parse_key(
-    source: toml_parse::Source<'_>,
-    errors: &mut dyn prelude::ErrorSink,
-) -> crate::Key {
+/// Look up an event's content
+///
+/// Without the `unsafe` feature, a span outside of `source` or off of a UTF-8 boundary is
+/// reported as an error rather than trusted.
+pub(crate) fn get_raw<'s>(
+    source: Source<'s>,
+    event: &Event,
+    errors: &mut dyn ErrorSink,
+) -> Option<Raw<'s>> {
+    #[cfg(feature = "unsafe")] // SAFETY: lexing and parsing all with same source
+    let raw = Some(unsafe { source.get_unchecked(event) });
+    #[cfg(not(feature = "unsafe"))]
+    let raw = source.get(event);
+    if raw.is_none() {
+        errors.report_error(
+            ParseError::new("invalid token span")
+                .with_context(event.span())
+                .with_unexpected(event.span()),
+        );
+    }
+    raw
+}
+
+pub(crate) fn to_key(source: Source<'_>, errors: &mut dyn ErrorSink) -> crate::Key {
     let tokens = source.lex().into_vec();
 
     let mut events = Vec::with_capacity(tokens.len());
@@ -49,12 +147,9 @@ pub(crate) fn parse_key(
     let receiver = &mut receiver;
     #[cfg(feature = "unbounded")]
     let receiver = &mut receiver;
-    toml_parse::parser::parse_simple_key(&tokens, receiver, errors);
+    parse_simple_key(&tokens, receiver, errors);
 
-    if let Some(event) = events
-        .iter()
-        .find(|e| e.kind() == toml_parse::parser::EventKind::SimpleKey)
-    {
+    if let Some(event) = events.iter().find(|e| e.kind() == EventKind::SimpleKey) {
         let (raw, key) = key::on_simple_key(event, source, errors);
         crate::Key::new(key).with_repr_unchecked(crate::Repr::new_unchecked(raw))
     } else {
@@ -64,10 +159,7 @@ pub(crate) fn parse_key(
     }
 }
 
-pub(crate) fn parse_key_path(
-    source: toml_parse::Source<'_>,
-    errors: &mut dyn prelude::ErrorSink,
-) -> Vec<crate::Key> {
+pub(crate) fn to_key_path(source: Source<'_>, errors: &mut dyn ErrorSink) -> Vec<crate::Key> {
     let tokens = source.lex().into_vec();
 
     let mut events = Vec::with_capacity(tokens.len());
@@ -78,7 +170,7 @@ pub(crate) fn parse_key_path(
     let receiver = &mut receiver;
     #[cfg(feature = "unbounded")]
     let receiver = &mut receiver;
-    toml_parse::parser::parse_key(&tokens, receiver, errors);
+    parse_key(&tokens, receiver, errors);
 
     let mut input = prelude::Input::new(&events);
     let mut prefix = None;
@@ -87,7 +179,7 @@ pub(crate) fn parse_key_path(
     let mut suffix = None;
     while let Some(event) = input.next_token() {
         match event.kind() {
-            toml_parse::parser::EventKind::Whitespace => {
+            EventKind::Whitespace => {
                 let raw = RawString::with_span(event.span().start()..event.span().end());
                 if prefix.is_none() {
                     prefix = Some(raw);
@@ -104,10 +196,10 @@ pub(crate) fn parse_key_path(
     }
     if let Some(mut key) = key {
         if let Some(prefix) = prefix {
-            key.leaf_decor.set_prefix(prefix);
+            key.leaf_decor.set_prefix_unchecked(prefix);
         }
         if let Some(suffix) = suffix {
-            key.leaf_decor.set_suffix(suffix);
+            key.leaf_decor.set_suffix_unchecked(suffix);
         }
         let mut path = path.unwrap_or_default();
         path.push(key);
@@ -117,10 +209,7 @@ pub(crate) fn parse_key_path(
     }
 }
 
-pub(crate) fn parse_value(
-    source: toml_parse::Source<'_>,
-    errors: &mut dyn prelude::ErrorSink,
-) -> crate::Value {
+pub(crate) fn to_value(source: Source<'_>, errors: &mut dyn ErrorSink) -> crate::Value {
     let tokens = source.lex().into_vec();
 
     let mut events = Vec::with_capacity(tokens.len());
@@ -131,13 +220,42 @@ pub(crate) fn parse_value(
     let receiver = &mut receiver;
     #[cfg(feature = "unbounded")]
     let receiver = &mut receiver;
-    toml_parse::parser::parse_value(&tokens, receiver, errors);
+    parse_value(&tokens, receiver, errors);
 
     let mut input = prelude::Input::new(&events);
     let value = value::value(&mut input, source, errors);
     value
 }
 
+/// The number of values in the array or inline table whose contents start `input`
+///
+/// Counting ahead lets the container be allocated once, rather than grown as values are pushed.
+/// Each nesting level re-scans its contents, which the recursion limit keeps bounded.
+fn count_values(input: &[Event]) -> usize {
+    let mut count = 0;
+    let mut depth = 0_usize;
+    for event in input {
+        match event.kind() {
+            EventKind::ArrayOpen | EventKind::InlineTableOpen => {
+                if depth == 0 {
+                    count += 1;
+                }
+                depth += 1;
+            }
+            EventKind::ArrayClose | EventKind::InlineTableClose => {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            }
+            EventKind::Scalar if depth == 0 => count += 1,
+            EventKind::StdTableOpen | EventKind::ArrayTableOpen => break,
+            _ => {}
+        }
+    }
+    count
+}
+
 #[cfg(not(feature = "unbounded"))]
 const LIMIT: u32 = 80;
 
@@ -0,0 +1,176 @@
+use std::ops::Range;
+
+use crate::parser::prelude::*;
+use crate::Document;
+
+/// Parse `input`, blanking out each expression with an error until the rest parses
+///
+/// The blanked text is parsed as whitespace, so it ends up in decor whose spans, once
+/// despanned against `input`, display the original text.
+pub(crate) fn to_document(
+    input: &str,
+    errors: &mut dyn ErrorSink,
+) -> (Document<()>, Vec<Range<usize>>) {
+    let mut opaque = Vec::<Range<usize>>::new();
+    loop {
+        let blanked = blank(input, &opaque);
+        let source = toml_parse::Source::new(&blanked);
+        let tokens = source.lex().into_vec();
+        let mut found = Vec::<ParseError>::new();
+        let mut events = Vec::with_capacity(tokens.len());
+        let mut receiver = super::ValidateWhitespace::new(&mut events, source);
+        #[cfg(not(feature = "unbounded"))]
+        let mut receiver = super::RecursionGuard::new(&mut receiver, super::LIMIT);
+        #[cfg(not(feature = "unbounded"))]
+        let receiver = &mut receiver;
+        #[cfg(feature = "unbounded")]
+        let receiver = &mut receiver;
+        super::parse_document(&tokens, receiver, &mut found);
+        let mut events_input = Input::new(&events);
+        let doc = super::document::document(&mut events_input, source, &mut found, None)
+            .expect("only cancelled through progress");
+
+        let expressions = expressions(&events);
+        let mut progress = false;
+        let mut unplaced = Vec::new();
+        for error in found {
+            let Some(span) = error.unexpected().or_else(|| error.context()) else {
+                unplaced.push(error);
+                continue;
+            };
+            let region = region(input, &expressions, span.start());
+            if opaque
+                .iter()
+                .any(|o| o.start <= region.start && region.end <= o.end)
+            {
+                unplaced.push(error);
+            } else {
+                insert(&mut opaque, region);
+                progress = true;
+            }
+        }
+        if !progress {
+            for error in unplaced {
+                errors.report_error(error);
+            }
+            let doc = Document {
+                root: doc.root,
+                trailing: doc.trailing,
+                raw: (),
+                conflicts: Vec::new(),
+                duplicates: Vec::new(),
+                opaque: Vec::new(),
+                normalize_cr: false,
+            };
+            return (doc, opaque);
+        }
+    }
+}
+
+/// A top-level key/value pair or table header, with any comment on its last line
+struct Expression {
+    span: Range<usize>,
+    is_header: bool,
+}
+
+fn expressions(events: &[toml_parse::parser::Event]) -> Vec<Expression> {
+    let mut expressions = Vec::new();
+    let mut current: Option<Expression> = None;
+    let mut depth = 0_usize;
+    for event in events {
+        let span = event.span();
+        match event.kind() {
+            EventKind::Newline if depth == 0 => {
+                expressions.extend(current.take());
+                continue;
+            }
+            EventKind::Whitespace | EventKind::Comment | EventKind::Newline
+                if current.is_none() =>
+            {
+                continue;
+            }
+            EventKind::ArrayOpen | EventKind::InlineTableOpen => depth += 1,
+            EventKind::ArrayClose | EventKind::InlineTableClose => {
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        let current = current.get_or_insert_with(|| Expression {
+            span: span.start()..span.end(),
+            is_header: matches!(
+                event.kind(),
+                EventKind::StdTableOpen | EventKind::ArrayTableOpen
+            ),
+        });
+        current.span.end = current.span.end.max(span.end());
+    }
+    expressions.extend(current);
+    expressions
+}
+
+/// The whole lines to keep as-is for an error at `pos`
+///
+/// This is the expression the error is in, or the line for an error outside of one, like in a
+/// comment.  A table header takes its section with it, as its keys have nowhere to go.
+fn region(input: &str, expressions: &[Expression], pos: usize) -> Range<usize> {
+    let Some(i) = expressions
+        .iter()
+        .position(|e| e.span.start <= pos && pos <= e.span.end)
+    else {
+        return line_start(input, pos)..line_end(input, pos);
+    };
+    let start = line_start(input, expressions[i].span.start);
+    let mut end = expressions[i].span.end;
+    if expressions[i].is_header {
+        end = expressions[i + 1..]
+            .iter()
+            .take_while(|e| !e.is_header)
+            .last()
+            .map(|e| e.span.end)
+            .unwrap_or(end);
+    }
+    start..line_end(input, end)
+}
+
+fn line_start(input: &str, pos: usize) -> usize {
+    input[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0)
+}
+
+fn line_end(input: &str, pos: usize) -> usize {
+    let end = input[pos..]
+        .find('\n')
+        .map(|i| pos + i)
+        .unwrap_or(input.len());
+    if input[..end].ends_with('\r') {
+        end - 1
+    } else {
+        end
+    }
+}
+
+/// Add `region` to the sorted, non-overlapping `opaque`
+fn insert(opaque: &mut Vec<Range<usize>>, mut region: Range<usize>) {
+    opaque.retain(|o| {
+        let overlaps = o.start <= region.end && region.start <= o.end;
+        if overlaps {
+            region = o.start.min(region.start)..o.end.max(region.end);
+        }
+        !overlaps
+    });
+    let i = opaque.partition_point(|o| o.start < region.start);
+    opaque.insert(i, region);
+}
+
+/// Replace the text of each region with spaces, keeping line endings and byte offsets
+fn blank(input: &str, opaque: &[Range<usize>]) -> String {
+    let mut bytes = input.as_bytes().to_vec();
+    for region in opaque {
+        for byte in &mut bytes[region.clone()] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+    String::from_utf8(bytes)
+        .expect("regions are whole lines, so only whole characters were replaced")
+}
@@ -68,15 +68,17 @@ pub(crate) fn on_scalar(
     let value_span = event.span();
     let value_raw = RawString::with_span(value_span.start()..value_span.end());
 
-    #[cfg(feature = "unsafe")] // SAFETY: lexing and parsing all with same source
-    let raw = unsafe { source.get_unchecked(event) };
-    #[cfg(not(feature = "unsafe"))]
-    let raw = source.get(event).unwrap();
-    let mut decoded = std::borrow::Cow::Borrowed("");
+    let Some(raw) = super::get_raw(source, event, errors) else {
+        let mut f = Formatted::new(0);
+        f.set_repr_unchecked(Repr::new_unchecked(value_raw));
+        return Value::Integer(f);
+    };
+    let mut decoded = ScalarBuffer::Borrowed("");
     let kind = raw.decode_scalar(&mut decoded, errors);
+    let decoded = &mut decoded;
     match kind {
         toml_parse::decoder::ScalarKind::String => {
-            let mut f = Formatted::new(decoded.into());
+            let mut f = Formatted::new(decoded.take_string());
             f.set_repr_unchecked(Repr::new_unchecked(value_raw));
             Value::String(f)
         }
@@ -86,7 +88,7 @@ pub(crate) fn on_scalar(
             Value::Boolean(f)
         }
         toml_parse::decoder::ScalarKind::DateTime => {
-            let value = match decoded.parse::<toml_datetime::Datetime>() {
+            let value = match decoded.as_str().parse::<toml_datetime::Datetime>() {
                 Ok(value) => value,
                 Err(err) => {
                     errors.report_error(
@@ -104,12 +106,13 @@ pub(crate) fn on_scalar(
             Value::Datetime(f)
         }
         toml_parse::decoder::ScalarKind::Float => {
+            let decoded = decoded.as_str();
             let value = match decoded.parse::<f64>() {
                 Ok(value) => {
                     if value.is_infinite()
                         && !(decoded
                             .strip_prefix(['+', '-'])
-                            .unwrap_or(&decoded)
+                            .unwrap_or(decoded)
                             .chars()
                             .all(|c| c.is_ascii_alphabetic()))
                     {
@@ -132,7 +135,7 @@ pub(crate) fn on_scalar(
             Value::Float(f)
         }
         toml_parse::decoder::ScalarKind::Integer(radix) => {
-            let value = match i64::from_str_radix(&decoded, radix.value()) {
+            let value = match i64::from_str_radix(decoded.as_str(), radix.value()) {
                 Ok(value) => value,
                 Err(_) => {
                     // Assuming the decoder fully validated it, leaving only overflow errors
@@ -148,3 +151,79 @@ pub(crate) fn on_scalar(
         }
     }
 }
+
+/// Numbers with `_` are decoded a piece at a time, so short ones are kept on the stack
+const INLINE_LEN: usize = 40;
+
+/// A decoded scalar, only allocating for long text that can't be borrowed
+enum ScalarBuffer<'i> {
+    Borrowed(&'i str),
+    Inline([u8; INLINE_LEN], usize),
+    Owned(String),
+}
+
+impl ScalarBuffer<'_> {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Borrowed(s) => s,
+            Self::Inline(bytes, len) => {
+                let bytes = &bytes[..*len];
+                #[cfg(feature = "unsafe")] // SAFETY: only whole `str`s are copied in
+                let s = unsafe { std::str::from_utf8_unchecked(bytes) };
+                #[cfg(not(feature = "unsafe"))]
+                let s = std::str::from_utf8(bytes).expect("only whole `str`s are copied in");
+                s
+            }
+            Self::Owned(s) => s,
+        }
+    }
+
+    fn copy(&mut self, append: &str) {
+        if let Self::Owned(s) = self {
+            s.push_str(append);
+            return;
+        }
+        let current = self.as_str();
+        let len = current.len() + append.len();
+        if len <= INLINE_LEN {
+            let mut bytes = [0; INLINE_LEN];
+            bytes[..current.len()].copy_from_slice(current.as_bytes());
+            bytes[current.len()..len].copy_from_slice(append.as_bytes());
+            *self = Self::Inline(bytes, len);
+        } else {
+            let mut s = String::with_capacity(len);
+            s.push_str(current);
+            s.push_str(append);
+            *self = Self::Owned(s);
+        }
+    }
+
+    fn take_string(&mut self) -> String {
+        match std::mem::replace(self, Self::Borrowed("")) {
+            Self::Owned(s) => s,
+            other => other.as_str().to_owned(),
+        }
+    }
+}
+
+impl<'i> toml_parse::decoder::StringBuilder<'i> for ScalarBuffer<'i> {
+    fn clear(&mut self) {
+        match self {
+            Self::Owned(s) => s.clear(),
+            _ => *self = Self::Borrowed(""),
+        }
+    }
+
+    fn push_str(&mut self, append: &'i str) -> bool {
+        match self {
+            Self::Borrowed(s) if s.is_empty() => *s = append,
+            _ => self.copy(append),
+        }
+        true
+    }
+
+    fn push_char(&mut self, append: char) -> bool {
+        self.copy(append.encode_utf8(&mut [0; 4]));
+        true
+    }
+}
@@ -0,0 +1,1053 @@
+//! Describe changes to a document as edits to its text
+//!
+//! When a document is shown in an editor, replacing the whole buffer after each change loses
+//! the cursor, folds, and undo history.  [`make_text_patch`] instead finds the byte ranges of
+//! the old text that changed, like an LSP `TextEdit`, so only those are touched.
+//!
+//! Both documents are rendered, with their comments and whitespace, and compared line by line,
+//! with each changed run of lines narrowed down to the bytes that differ.  This works best when
+//! `new` is an edited copy of `old`, as unchanged items render the same.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "parse")] {
+//! use toml_edit::patch::{apply_text_patch, make_text_patch};
+//! use toml_edit::DocumentMut;
+//!
+//! let text = "
+//! [package]
+//! name = 'foo'  # the name
+//! version = '1.0.0'
+//!
+//! [dependencies]
+//! serde = '1'
+//! ";
+//! let old = text.parse::<DocumentMut>().unwrap();
+//! let mut new = old.clone();
+//! new["package"]["version"] = toml_edit::value("1.1.0");
+//! new["dependencies"]["regex"] = toml_edit::value("1");
+//!
+//! let edits = make_text_patch(&old, &new);
+//! assert_eq!(edits.len(), 2);
+//! assert_eq!(&text[edits[0].range()], "'1.0.0'");
+//! assert_eq!(edits[0].new_text(), "\"1.1.0\"");
+//! assert_eq!(edits[1].new_text(), "regex = \"1\"\n");
+//! assert_eq!(apply_text_patch(text, &edits), new.to_string());
+//! # }
+//! ```
+
+use std::ops::Range;
+
+use crate::encode::{visit_body, visit_sections, Line};
+#[cfg(feature = "parse")]
+use crate::table::KeyValuePairs;
+use crate::table::DEFAULT_ROOT_DECOR;
+use crate::{DocumentMut, RawString};
+#[cfg(feature = "parse")]
+use crate::{Item, Table, Value};
+
+/// Replace a range of the old text, see [`make_text_patch`] and
+/// [`DocumentMut::apply_text_edits`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    range: Range<usize>,
+    new_text: String,
+}
+
+impl TextEdit {
+    /// Replace `range` of the old text with `new_text`
+    pub fn new(range: Range<usize>, new_text: impl Into<String>) -> Self {
+        Self {
+            range,
+            new_text: new_text.into(),
+        }
+    }
+
+    /// The bytes of the old text to replace, empty for an insertion
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// What to replace the range with, empty for a deletion
+    pub fn new_text(&self) -> &str {
+        &self.new_text
+    }
+}
+
+/// The edits that turn the text of `old` into the text of `new`
+///
+/// Edits are in order and do not overlap.  Their ranges are into `old.to_string()`, which is
+/// the original text when `old` was parsed and not changed since.
+pub fn make_text_patch(old: &DocumentMut, new: &DocumentMut) -> Vec<TextEdit> {
+    diff_text(&old.to_string(), &new.to_string())
+}
+
+/// Apply the [`make_text_patch`] edits to `text`
+///
+/// # Panics
+///
+/// If an edit is outside of `text` or the edits overlap.
+pub fn apply_text_patch(text: &str, edits: &[TextEdit]) -> String {
+    patch_range(text, 0..text.len(), edits)
+}
+
+/// Apply `edits`, which are within `range`, to that range of `text`
+fn patch_range(text: &str, range: Range<usize>, edits: &[TextEdit]) -> String {
+    let mut patched = String::with_capacity(range.len());
+    let mut end = range.start;
+    for edit in edits {
+        patched.push_str(&text[end..edit.range.start]);
+        patched.push_str(&edit.new_text);
+        end = edit.range.end;
+    }
+    patched.push_str(&text[end..range.end]);
+    patched
+}
+
+#[cfg(feature = "parse")]
+impl DocumentMut {
+    /// Apply edits to the text of the document, like from an editor
+    ///
+    /// `edits` are into `self.to_string()`, see [`make_text_patch`].  When the edits are all
+    /// within the key/value lines of one table, only those lines are parsed again, replacing the
+    /// key/value pairs of that table, while every other item is left as it is.  Otherwise, like
+    /// when a header or the comments before it are edited, the whole edited text is parsed.
+    ///
+    /// Either way, each key/value pair that is at the same path as before keeps its
+    /// [`ItemId`][crate::ItemId], even when its value changed, so findings attached with
+    /// [`ItemMetadata`][crate::ItemMetadata] stay with the pairs the edits did not remove.
+    ///
+    /// The document is still rendered to find where the edits are.  Spans, from
+    /// [`DocumentMut::parse_with_spans`], are dropped as they are into the text from before the
+    /// edits.
+    ///
+    /// On error, including for edits that are outside of the text, overlap, or split a character,
+    /// the document is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use toml_edit::patch::make_text_patch;
+    /// use toml_edit::DocumentMut;
+    ///
+    /// let mut doc = "[dependencies]\nserde = '1'\nregex = '1'\n".parse::<DocumentMut>().unwrap();
+    /// doc.assign_ids();
+    /// let id = doc["dependencies"].as_table().unwrap().key("regex").unwrap().id();
+    ///
+    /// let mut edited = doc.clone();
+    /// edited["dependencies"].as_table_mut().unwrap().remove("serde");
+    /// edited["dependencies"]["regex"] = toml_edit::value("1.10");
+    /// doc.apply_text_edits(&make_text_patch(&doc, &edited)).unwrap();
+    ///
+    /// assert_eq!(doc.to_string(), "[dependencies]\nregex = \"1.10\"\n");
+    /// assert_eq!(doc["dependencies"].as_table().unwrap().key("regex").unwrap().id(), id);
+    /// ```
+    pub fn apply_text_edits(&mut self, edits: &[TextEdit]) -> Result<(), crate::TomlError> {
+        if edits.is_empty() {
+            return Ok(());
+        }
+        let (text, sections) = self.render_sections();
+        check_edits(&text, edits)?;
+
+        let section = sections.into_iter().find(|(_, body)| {
+            edits
+                .iter()
+                .all(|edit| body.start <= edit.range.start && edit.range.end <= body.end)
+        });
+        if let Some((section, body)) = section {
+            let body = patch_range(&text, body, edits);
+            // Without values, the root would no longer separate the first header from the top
+            let is_root = std::ptr::eq(section, self.as_table());
+            let table = find_table_mut(self.as_table_mut(), section).expect("section is in `self`");
+            if !(is_root && body.is_empty()) && reparse_body(table, &body) {
+                // Only shared strings remain, which don't need the input
+                let input = std::sync::Arc::<str>::from("");
+                self.root.despan(&input, false);
+                self.trailing.despan(&input, false);
+                return Ok(());
+            }
+        }
+
+        let mut doc = apply_text_patch(&text, edits).parse::<DocumentMut>()?;
+        keep_table_ids(doc.as_table_mut(), self.as_table());
+        *self = doc;
+        Ok(())
+    }
+
+    /// `self.to_string()`, with the range of the key/value lines of each table they can be parsed
+    /// again for, see [`reparse_body`]
+    fn render_sections(&self) -> (String, Vec<(*const Table, Range<usize>)>) {
+        let mut text = String::with_capacity(self.len_hint());
+        let mut sections = Vec::new();
+        if self.has_bom() {
+            text.push('\u{feff}');
+        }
+        let decor = self.decor();
+        decor
+            .prefix_encode(&mut text, None, DEFAULT_ROOT_DECOR.0)
+            .unwrap();
+        let root = self.as_table();
+        visit_sections(self, &mut |table, header| {
+            // Which tables are shown, and how, depends on whether these have values
+            let is_root = std::ptr::eq(table, root);
+            let fixed = header.is_some() && !table.is_implicit();
+            if let Some(header) = header {
+                header.encode(&mut text)?;
+            }
+            let start = text.len();
+            visit_body(table, &mut |line| line.encode(&mut text))?;
+            if fixed || is_root && start < text.len() {
+                sections.push((table as *const Table, start..text.len()));
+            }
+            Ok(())
+        })
+        .unwrap();
+        decor
+            .suffix_encode(&mut text, None, DEFAULT_ROOT_DECOR.1)
+            .unwrap();
+        self.trailing()
+            .encode_with_default(&mut text, None, "")
+            .unwrap();
+        (text, sections)
+    }
+}
+
+/// Errors for edits that are outside of `text`, overlap, or split a character
+#[cfg(feature = "parse")]
+fn check_edits(text: &str, edits: &[TextEdit]) -> Result<(), crate::TomlError> {
+    let mut end = 0;
+    for edit in edits {
+        let range = edit.range();
+        let problem = if range.end < range.start || text.len() < range.end {
+            "is outside of the text"
+        } else if range.start < end {
+            "overlaps the edit before it"
+        } else if !text.is_char_boundary(range.start) || !text.is_char_boundary(range.end) {
+            "splits a character"
+        } else {
+            end = range.end;
+            continue;
+        };
+        return Err(crate::TomlError::custom(
+            format!("text edit {range:?} {problem}"),
+            None,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "parse")]
+fn find_table_mut(table: &mut Table, target: *const Table) -> Option<&mut Table> {
+    if std::ptr::eq(table, target) {
+        return Some(table);
+    }
+    // Not through `items_mut` as this does not change them
+    for item in table.items.values_mut() {
+        let found = match item {
+            Item::Table(child) => find_table_mut(child, target),
+            Item::ArrayOfTables(children) => children
+                .iter_mut()
+                .find_map(|child| find_table_mut(child, target)),
+            Item::None | Item::Value(_) => None,
+        };
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+/// Replace the key/value pairs of `table` by parsing `text`, its edited key/value lines, on
+/// their own
+///
+/// Returns `false`, leaving `table` unchanged, when `text` might not parse the same within the
+/// whole document, like when it has a header, ends in a comment, or adds a key that one of
+/// `table`'s headers is for.
+#[cfg(feature = "parse")]
+fn reparse_body(table: &mut Table, text: &str) -> bool {
+    let Ok(mut doc) = text.parse::<DocumentMut>() else {
+        return false;
+    };
+    // Anything outside of the key/value lines belongs to what comes next in the document
+    let is_lines = !doc.has_bom() && doc.trailing().as_str() == Some("") && doc.to_string() == text;
+    let body = std::mem::take(&mut doc.as_table_mut().items);
+    let is_whole = body.values().all(is_body)
+        && table.items.iter().all(|(key, item)| {
+            let keeps = !is_header(item) || !body.contains_key(key.get());
+            keeps && (is_body(item) || is_header(item))
+        });
+    if !is_lines || !is_whole {
+        return false;
+    }
+
+    let mut new = Table::with_pairs(body);
+    let mut old = Table::new();
+    let mut kept = KeyValuePairs::with_capacity(table.items.len());
+    let mut at = None;
+    for (key, item) in std::mem::take(table.items_mut()) {
+        if is_header(&item) {
+            kept.insert(key, item);
+        } else {
+            at.get_or_insert(kept.len());
+            old.items.insert(key, item);
+        }
+    }
+    keep_table_ids(&mut new, &old);
+
+    let mut kept = kept.into_iter();
+    table.items.extend(kept.by_ref().take(at.unwrap_or(0)));
+    table.items.extend(std::mem::take(&mut new.items));
+    table.items.extend(kept);
+    true
+}
+
+/// Whether `item` is written as key/value lines, with nothing under it having a header
+#[cfg(feature = "parse")]
+fn is_body(item: &Item) -> bool {
+    match item {
+        Item::None | Item::Value(_) => true,
+        Item::Table(table) => table.is_dotted() && table.items.values().all(is_body),
+        Item::ArrayOfTables(_) => false,
+    }
+}
+
+/// Whether `item` is written with headers, rather than in the lines of the table it is in
+#[cfg(feature = "parse")]
+fn is_header(item: &Item) -> bool {
+    match item {
+        Item::None | Item::Value(_) => false,
+        Item::Table(table) => !table.is_dotted(),
+        Item::ArrayOfTables(_) => true,
+    }
+}
+
+impl DocumentMut {
+    /// The edits that turn `original` into the text of this document
+    ///
+    /// This is for documents from [`DocumentMut::parse_with_spans`] of `original`.  Each table
+    /// remembers where its key/value lines are in `original` until it is changed, and those lines
+    /// are kept as-is without being rendered.  Only headers and the key/value lines of changed
+    /// tables are rendered, with each line that still has its spans, and still renders the same
+    /// as its text in `original`, kept as well.  The other lines are spliced in between them.
+    ///
+    /// A table is changed by any `&mut` access to its key/value pairs, like
+    /// [`Table::get_mut`][crate::Table::get_mut] or `doc["package"]["version"] = ...`, even when
+    /// that only reaches into a value or a table under it.  Tables moved in from a document parsed
+    /// from other text, other than with [`Table::append`][crate::Table::append], are taken to
+    /// still match `original` and need to be changed first.
+    ///
+    /// Kept lines are left byte for byte, including `\r\n`, while rendered lines use `\n`.
+    /// Applying the edits with [`apply_text_patch`] otherwise gives the same text as
+    /// `self.to_string()`.  When lines were reordered, this falls back to diffing that text
+    /// against `original`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::patch::apply_text_patch;
+    /// use toml_edit::DocumentMut;
+    ///
+    /// let text = "
+    /// [package]
+    /// name = 'foo'  # the name
+    /// version = '1.0.0'
+    /// ";
+    /// let mut doc = DocumentMut::parse_with_spans(text).unwrap();
+    /// doc["package"]["version"] = toml_edit::value("1.1.0");
+    ///
+    /// let edits = doc.text_edits_from(text);
+    /// assert_eq!(edits.len(), 1);
+    /// assert_eq!(&text[edits[0].range()], "'1.0.0'");
+    /// assert_eq!(edits[0].new_text(), "\"1.1.0\"");
+    /// assert_eq!(apply_text_patch(text, &edits), doc.to_string());
+    /// # }
+    /// ```
+    pub fn text_edits_from(&self, original: &str) -> Vec<TextEdit> {
+        let mut splice = Splice::new(original);
+        if self.has_bom() {
+            let bom = original.starts_with('\u{feff}').then_some(0..3);
+            splice.piece(bom, "\u{feff}");
+        }
+        let decor = self.decor();
+        let mut rendered = String::new();
+        decor
+            .prefix_encode(&mut rendered, None, DEFAULT_ROOT_DECOR.0)
+            .unwrap();
+        splice.piece(raw_span(decor.prefix()), &rendered);
+
+        let in_order = visit_sections(self, &mut |table, header| {
+            let mut header_kept = true;
+            if let Some(header) = header {
+                rendered.clear();
+                header.encode(&mut rendered)?;
+                header_kept = splice.line(line_span(&header), &rendered)?;
+            }
+            match table.body_span.clone() {
+                Some(body) if header_kept => splice.lines(body),
+                _ => visit_body(table, &mut |line| {
+                    rendered.clear();
+                    line.encode(&mut rendered)?;
+                    splice.line(line_span(&line), &rendered).map(|_| ())
+                }),
+            }
+        });
+        if in_order.is_err() {
+            return diff_text(original, &self.to_string());
+        }
+
+        rendered.clear();
+        decor
+            .suffix_encode(&mut rendered, None, DEFAULT_ROOT_DECOR.1)
+            .unwrap();
+        splice.piece(raw_span(decor.suffix()), &rendered);
+        rendered.clear();
+        self.trailing()
+            .encode_with_default(&mut rendered, None, "")
+            .unwrap();
+        splice.piece(self.trailing().span(), &rendered);
+        splice.finish()
+    }
+}
+
+/// Builds [`DocumentMut::text_edits_from`]'s edits from kept and rendered text, in order
+struct Splice<'o> {
+    original: &'o str,
+    /// The end of the last text kept from `original`
+    cursor: usize,
+    /// What was rendered since then
+    pending: String,
+    edits: Vec<TextEdit>,
+}
+
+impl<'o> Splice<'o> {
+    fn new(original: &'o str) -> Self {
+        Self {
+            original,
+            cursor: 0,
+            pending: String::new(),
+            edits: Vec::new(),
+        }
+    }
+
+    /// Keep `span` of the original when it renders as `rendered`, otherwise add `rendered`
+    fn piece(&mut self, span: Option<Range<usize>>, rendered: &str) {
+        let original = self.original;
+        match span {
+            Some(span) if is_same(original.get(span.clone()), rendered) => {
+                if span.start < self.cursor {
+                    self.pending.push_str(rendered);
+                } else {
+                    self.keep(span);
+                }
+            }
+            _ => self.pending.push_str(rendered),
+        }
+    }
+
+    /// Like [`Splice::piece`] for a line ending with `\n`, where `span` is without the newline,
+    /// returning whether it was kept
+    ///
+    /// Errors when the line is kept from before the cursor, as lines were reordered.
+    fn line(
+        &mut self,
+        span: Option<Range<usize>>,
+        rendered: &str,
+    ) -> Result<bool, std::fmt::Error> {
+        let original = self.original;
+        let content = rendered.strip_suffix('\n').unwrap_or(rendered);
+        let span = span.and_then(|span| {
+            let rest = original.get(span.end..)?;
+            let newline = ["\n", "\r\n"].into_iter().find(|n| rest.starts_with(n))?;
+            is_same(original.get(span.clone()), content)
+                .then(|| span.start..span.end + newline.len())
+        });
+        match span {
+            Some(span) => {
+                self.lines(span)?;
+                Ok(true)
+            }
+            None => {
+                self.pending.push_str(rendered);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Keep lines of the original that are known to be unchanged
+    ///
+    /// Errors when they are from before the cursor, as lines were reordered.
+    fn lines(&mut self, span: Range<usize>) -> std::fmt::Result {
+        if span.start < self.cursor || self.original.get(span.clone()).is_none() {
+            return Err(std::fmt::Error);
+        }
+        self.keep(span);
+        Ok(())
+    }
+
+    fn keep(&mut self, span: Range<usize>) {
+        if self.cursor < span.start || !self.pending.is_empty() {
+            let edit = narrow(self.original, self.cursor..span.start, &self.pending);
+            if !edit.range.is_empty() || !edit.new_text.is_empty() {
+                self.edits.push(edit);
+            }
+            self.pending.clear();
+        }
+        self.cursor = span.end;
+    }
+
+    fn finish(mut self) -> Vec<TextEdit> {
+        let end = self.original.len();
+        self.keep(end..end);
+        self.edits
+    }
+}
+
+/// Whether `original` renders as `rendered`, as newlines are written as `\n`
+fn is_same(original: Option<&str>, rendered: &str) -> bool {
+    match original {
+        Some(original) if original.contains('\r') => original.replace("\r\n", "\n") == rendered,
+        Some(original) => original == rendered,
+        None => false,
+    }
+}
+
+fn raw_span(raw: Option<&RawString>) -> Option<Range<usize>> {
+    raw.and_then(RawString::span)
+}
+
+/// The span of `line` in the original text, without its newline
+fn line_span(line: &Line<'_, '_>) -> Option<Range<usize>> {
+    match *line {
+        Line::Header { table, .. } => {
+            let header = table.span()?;
+            let decor = table.decor();
+            let start = raw_span(decor.prefix())
+                .map(|s| s.start)
+                .unwrap_or(header.start);
+            let end = raw_span(decor.suffix())
+                .map(|s| s.end)
+                .unwrap_or(header.end);
+            Some(start..end)
+        }
+        Line::KeyValue { path, value } => {
+            let leaf = path.last()?;
+            let start = raw_span(leaf.leaf_decor().prefix())
+                .map(|s| s.start)
+                .or_else(|| path.first()?.span().map(|s| s.start))?;
+            let end = raw_span(value.decor().suffix())
+                .map(|s| s.end)
+                .or_else(|| value.span().map(|s| s.end))?;
+            Some(start..end)
+        }
+    }
+}
+
+/// Remember where the key/value lines of `table`, and of the tables under it, are in
+/// `original`, see [`DocumentMut::text_edits_from`]
+///
+/// Tables whose lines are not in the same order as they are written, like with dotted keys
+/// that other keys split up, are left to be rendered.
+#[cfg(feature = "parse")]
+pub(crate) fn record_body_spans(table: &mut Table, original: &str) {
+    if !table.is_dotted() {
+        table.body_span = body_span(table, original);
+    }
+    // Not through `items_mut` as this does not change them
+    for item in table.items.values_mut() {
+        match item {
+            Item::Table(child) => record_body_spans(child, original),
+            Item::ArrayOfTables(children) => {
+                for child in children.iter_mut() {
+                    record_body_spans(child, original);
+                }
+            }
+            Item::None | Item::Value(_) => {}
+        }
+    }
+}
+
+/// Where the key/value lines of `table` are in `original`, if they are one after another
+#[cfg(feature = "parse")]
+fn body_span(table: &Table, original: &str) -> Option<Range<usize>> {
+    let mut body: Option<Range<usize>> = None;
+    visit_body(table, &mut |line| {
+        let span = line_span(&line).ok_or(std::fmt::Error)?;
+        let newline = original
+            .get(span.end..)
+            .and_then(|rest| ["\n", "\r\n"].into_iter().find(|n| rest.starts_with(n)))
+            .ok_or(std::fmt::Error)?;
+        let end = span.end + newline.len();
+        match &mut body {
+            Some(body) if body.end == span.start => body.end = end,
+            Some(_) => return Err(std::fmt::Error),
+            None => body = Some(span.start..end),
+        }
+        Ok(())
+    })
+    .ok()?;
+    body
+}
+
+#[cfg(feature = "parse")]
+fn keep_table_ids(new: &mut Table, old: &Table) {
+    use indexmap::map::MutableKeys;
+    for (key, item) in new.items.iter_mut2() {
+        if let Some((old_key, old_item)) = old.items.get_key_value(key.get()) {
+            key.id = old_key.id;
+            keep_item_ids(item, old_item);
+        }
+    }
+}
+
+#[cfg(feature = "parse")]
+fn keep_item_ids(new: &mut Item, old: &Item) {
+    match (new, old) {
+        (Item::Value(new), Item::Value(old)) => keep_value_ids(new, old),
+        (Item::Table(new), Item::Table(old)) => keep_table_ids(new, old),
+        (Item::ArrayOfTables(new), Item::ArrayOfTables(old)) => {
+            for (new, old) in new.iter_mut().zip(old.iter()) {
+                keep_table_ids(new, old);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(feature = "parse")]
+fn keep_value_ids(new: &mut Value, old: &Value) {
+    use indexmap::map::MutableKeys;
+    match (new, old) {
+        (Value::Array(new), Value::Array(old)) => {
+            for (new, old) in new.iter_mut().zip(old.iter()) {
+                keep_value_ids(new, old);
+            }
+        }
+        (Value::InlineTable(new), Value::InlineTable(old)) => {
+            for (key, item) in new.items.iter_mut2() {
+                if let Some((old_key, old_item)) = old.items.get_key_value(key.get()) {
+                    key.id = old_key.id;
+                    keep_item_ids(item, old_item);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn diff_text(old: &str, new: &str) -> Vec<TextEdit> {
+    let old_lines = old.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new.split_inclusive('\n').collect::<Vec<_>>();
+    let old_offsets = offsets(&old_lines);
+    let new_offsets = offsets(&new_lines);
+
+    let mut edits = Vec::new();
+    let (mut old_line, mut new_line) = (0, 0);
+    let matches = matching_lines(&old_lines, &new_lines);
+    for (old_match, new_match) in matches
+        .into_iter()
+        .chain(std::iter::once((old_lines.len(), new_lines.len())))
+    {
+        if old_line < old_match || new_line < new_match {
+            let old_range = old_offsets[old_line]..old_offsets[old_match];
+            let new_range = new_offsets[new_line]..new_offsets[new_match];
+            edits.push(narrow(old, old_range, &new[new_range]));
+        }
+        old_line = old_match + 1;
+        new_line = new_match + 1;
+    }
+    edits
+}
+
+/// Byte offset of the start of each line, and the end of the last
+fn offsets(lines: &[&str]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(lines.len() + 1);
+    let mut offset = 0;
+    offsets.push(offset);
+    for line in lines {
+        offset += line.len();
+        offsets.push(offset);
+    }
+    offsets
+}
+
+/// Trim what the replaced and replacement text have in common
+fn narrow(old: &str, mut range: Range<usize>, mut new_text: &str) -> TextEdit {
+    let old_text = &old[range.clone()];
+    let mut prefix = old_text
+        .bytes()
+        .zip(new_text.bytes())
+        .take_while(|(o, n)| o == n)
+        .count();
+    while !old_text.is_char_boundary(prefix) || !new_text.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let old_text = &old_text[prefix..];
+    new_text = &new_text[prefix..];
+    range.start += prefix;
+
+    let mut suffix = old_text
+        .bytes()
+        .rev()
+        .zip(new_text.bytes().rev())
+        .take_while(|(o, n)| o == n)
+        .count();
+    while !old_text.is_char_boundary(old_text.len() - suffix)
+        || !new_text.is_char_boundary(new_text.len() - suffix)
+    {
+        suffix -= 1;
+    }
+    range.end -= suffix;
+    new_text = &new_text[..new_text.len() - suffix];
+
+    TextEdit {
+        range,
+        new_text: new_text.to_owned(),
+    }
+}
+
+/// The lines kept between `old` and `new`, as `(old index, new index)`, using Myers' diff
+fn matching_lines(old: &[&str], new: &[&str]) -> Vec<(usize, usize)> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = n + m;
+    let index = |k: isize| (k + max) as usize;
+    let mut v = vec![0isize; 2 * max as usize + 2];
+    let mut trace = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if n <= x && m <= y {
+                break 'search;
+            }
+        }
+    }
+
+    let mut matches = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = if d == 0 { 0 } else { v[index(prev_k)] };
+        let prev_y = prev_x - prev_k;
+        while prev_x < x && prev_y < y {
+            x -= 1;
+            y -= 1;
+            matches.push((x as usize, y as usize));
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    matches.reverse();
+    matches
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[track_caller]
+    fn t(old: &str, new: &str, expected: &[(Range<usize>, &str)]) {
+        let edits = diff_text(old, new);
+        let actual = edits
+            .iter()
+            .map(|e| (e.range(), e.new_text()))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+        assert_eq!(apply_text_patch(old, &edits), new);
+    }
+
+    #[test]
+    fn unchanged() {
+        t("", "", &[]);
+        t("a = 1\n", "a = 1\n", &[]);
+    }
+
+    #[test]
+    fn whole() {
+        t("", "a = 1\n", &[(0..0, "a = 1\n")]);
+        t("a = 1\n", "", &[(0..6, "")]);
+    }
+
+    #[test]
+    fn lines() {
+        t("a = 1\nb = 2\nc = 3\n", "a = 1\nc = 3\n", &[(6..12, "")]);
+        t(
+            "a = 1\nc = 3\n",
+            "a = 1\nb = 2\nc = 3\nd = 4\n",
+            &[(6..6, "b = 2\n"), (12..12, "d = 4\n")],
+        );
+        t(
+            "a = 1\nb = 2\n",
+            "b = 2\na = 1\n",
+            &[(0..6, ""), (12..12, "a = 1\n")],
+        );
+    }
+
+    #[test]
+    fn within_line() {
+        t("a = 1\nb = 'x'\n", "a = 1\nb = 'y'\n", &[(11..12, "y")]);
+        t("a = 'é'\n", "a = 'è'\n", &[(5..7, "è")]);
+        t("a = 1", "a = 12", &[(5..5, "2")]);
+    }
+
+    #[cfg(feature = "parse")]
+    #[track_caller]
+    fn t_edits_from(
+        original: &str,
+        edit: impl FnOnce(&mut DocumentMut),
+        expected: &[(Range<usize>, &str)],
+    ) {
+        let mut doc = DocumentMut::parse_with_spans(original).unwrap();
+        edit(&mut doc);
+        let edits = doc.text_edits_from(original);
+        let actual = edits
+            .iter()
+            .map(|e| (e.range(), e.new_text()))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+        assert_eq!(apply_text_patch(original, &edits), doc.to_string());
+    }
+
+    #[cfg(feature = "parse")]
+    const ORIGINAL: &str = "# top
+[package]
+name = 'foo'  # the name
+version = '1.0.0'
+
+[dependencies]
+serde = '1'
+";
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn edits_from_unchanged() {
+        t_edits_from("", |_| {}, &[]);
+        t_edits_from(ORIGINAL, |_| {}, &[]);
+    }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn edits_from_value() {
+        t_edits_from(
+            ORIGINAL,
+            |doc| doc["package"]["version"] = crate::value("1.1.0"),
+            &[(51..58, "\"1.1.0\"")],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn edits_from_insert_remove() {
+        t_edits_from(
+            ORIGINAL,
+            |doc| doc["dependencies"]["regex"] = crate::value("1"),
+            &[(87..87, "regex = \"1\"\n")],
+        );
+        t_edits_from(
+            ORIGINAL,
+            |doc| {
+                doc["package"].as_table_mut().unwrap().remove("name");
+            },
+            &[(16..41, "")],
+        );
+        t_edits_from(
+            ORIGINAL,
+            |doc| {
+                doc["dev-dependencies"] = crate::table();
+                doc["dev-dependencies"]["snapbox"] = crate::value("0.6");
+            },
+            &[(87..87, "\n[dev-dependencies]\nsnapbox = \"0.6\"\n")],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn edits_from_reordered() {
+        t_edits_from(
+            ORIGINAL,
+            |doc| {
+                let package = doc["package"].as_table_mut().unwrap();
+                package.sort_values_by(|k1, _, k2, _| k2.cmp(k1));
+            },
+            &[(16..41, ""), (59..59, "name = 'foo'  # the name\n")],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn edits_from_tracks_changes() {
+        let body = |doc: &DocumentMut, key: &str| doc[key].as_table().unwrap().body_span.clone();
+        let mut doc = DocumentMut::parse_with_spans(ORIGINAL).unwrap();
+        assert_eq!(doc.as_table().body_span, None);
+        assert_eq!(body(&doc, "package"), Some(16..59));
+        assert_eq!(body(&doc, "dependencies"), Some(75..87));
+
+        doc["package"]["version"] = crate::value("1.1.0");
+        assert_eq!(body(&doc, "package"), None);
+        assert_eq!(body(&doc, "dependencies"), Some(75..87));
+
+        let _ = doc.get_mut("dependencies");
+        assert_eq!(body(&doc, "dependencies"), Some(75..87));
+        let _ = doc["dependencies"].as_table_mut().unwrap().get_mut("serde");
+        assert_eq!(body(&doc, "dependencies"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn edits_from_out_of_order() {
+        let original = "a.x = 1\nb = 2\na.y = 3\n[t]\nc = 4\n";
+        let doc = DocumentMut::parse_with_spans(original).unwrap();
+        assert_eq!(doc.as_table().body_span, None);
+        assert_eq!(doc["t"].as_table().unwrap().body_span, Some(26..32));
+        t_edits_from(original, |_| {}, &[(8..8, "a.y = 3\n"), (14..22, "")]);
+        t_edits_from(
+            original,
+            |doc| doc["t"]["c"] = crate::value(5),
+            &[(8..8, "a.y = 3\n"), (14..22, ""), (30..31, "5")],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn edits_from_document_wide() {
+        t_edits_from(
+            ORIGINAL,
+            |doc| {
+                for (kind, decor) in doc.decor_iter_mut() {
+                    if let (crate::DecorKind::Value, crate::DecorMut::Decor(decor)) = (kind, decor)
+                    {
+                        decor.set_suffix("").unwrap();
+                    }
+                }
+            },
+            &[(28..40, "")],
+        );
+        t_edits_from(
+            "[a]\nb = 1\n[c]\nd = 2\n",
+            |doc| doc.as_table_mut().collapse_to_dotted(1),
+            &[(0..14, "a.b = 1\nc.")],
+        );
+    }
+
+    #[cfg(feature = "parse")]
+    #[track_caller]
+    fn t_apply(original: &str, from: &str, to: &str, in_place: bool) {
+        // Parsing the whole text again would number the positions from 0
+        let renumber = |doc: &mut DocumentMut| {
+            doc["t"].as_table_mut().unwrap().set_position(10);
+            doc["t"]["u"].as_table_mut().unwrap().set_position(11);
+        };
+        let mut doc = original.parse::<DocumentMut>().unwrap();
+        renumber(&mut doc);
+        let start = original.find(from).unwrap();
+        let edit = TextEdit::new(start..start + from.len(), to);
+        let expected = apply_text_patch(original, std::slice::from_ref(&edit));
+
+        doc.apply_text_edits(&[edit]).unwrap();
+        assert_eq!(doc.to_string(), expected);
+        assert_eq!(
+            doc["t"].as_table().unwrap().position() == Some(10),
+            in_place
+        );
+        if in_place {
+            let mut parsed = expected.parse::<DocumentMut>().unwrap();
+            renumber(&mut parsed);
+            assert_eq!(format!("{doc:#?}"), format!("{parsed:#?}"));
+        }
+    }
+
+    #[cfg(feature = "parse")]
+    const SECTIONS: &str = "a = 1
+[t]
+# about b
+b = 2
+c.d = 3
+
+[t.u]
+e = 4
+";
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn apply_in_place() {
+        t_apply(SECTIONS, "2", "20", true);
+        t_apply(SECTIONS, "# about b\n", "", true);
+        t_apply(SECTIONS, "c.d = 3\n", "c.d = 3\nc.e = 5\nf = 6\n", true);
+        t_apply(SECTIONS, "# about b\nb = 2\nc.d = 3\n", "", true);
+        t_apply(SECTIONS, "1", "10", true);
+        t_apply(SECTIONS, "a = 1\n", "a = 1\nz.y = 0\n", true);
+    }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn apply_whole() {
+        // Comments after the last line go with the next header
+        t_apply(SECTIONS, "c.d = 3\n", "c.d = 3\n# about u\n", false);
+        t_apply(SECTIONS, "b = 2\nc.d = 3\n", "", false);
+        t_apply(SECTIONS, "c.d = 3\n", "c.d = 3\n[v]\n", false);
+        t_apply(SECTIONS, "[t]", "[ t ]", false);
+        t_apply(SECTIONS, "2\nc.d = 3\n\n[t.u]\ne = 4", "3", false);
+        t_apply(SECTIONS, "a = 1\n", "", false);
+    }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn apply_errors() {
+        let mut doc = SECTIONS.parse::<DocumentMut>().unwrap();
+        let mut check = |edits: &[TextEdit], message: &str| {
+            let err = doc.apply_text_edits(edits).unwrap_err();
+            assert_eq!(err.message(), message);
+            assert_eq!(doc.to_string(), SECTIONS);
+        };
+        check(
+            &[TextEdit::new(0..100, "")],
+            "text edit 0..100 is outside of the text",
+        );
+        check(
+            &[TextEdit::new(4..5, "2"), TextEdit::new(0..5, "")],
+            "text edit 0..5 overlaps the edit before it",
+        );
+        check(
+            &[TextEdit::new(16..17, "é"), TextEdit::new(4..5, "2")],
+            "text edit 4..5 overlaps the edit before it",
+        );
+        let end = SECTIONS.find("\n[t.u]").unwrap();
+        check(&[TextEdit::new(end..end, "u = 1\n")], "duplicate key");
+
+        let mut doc = "a = 'é'\n".parse::<DocumentMut>().unwrap();
+        let err = doc
+            .apply_text_edits(&[TextEdit::new(5..6, "")])
+            .unwrap_err();
+        assert_eq!(err.message(), "text edit 5..6 splits a character");
+    }
+
+    #[test]
+    #[cfg(feature = "parse")]
+    fn edits_from_keeps_crlf() {
+        let original = "a = 1\r\nb = 2\r\nc = 3\r\n";
+        let mut doc = DocumentMut::parse_with_spans(original).unwrap();
+        doc["b"] = crate::value(4);
+        let edits = doc.text_edits_from(original);
+        assert_eq!(
+            apply_text_patch(original, &edits),
+            "a = 1\r\nb = 4\nc = 3\r\n"
+        );
+    }
+}
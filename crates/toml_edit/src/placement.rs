@@ -0,0 +1,240 @@
+use crate::{DocumentMut, Item, Table};
+
+/// Where to move a table within a [`DocumentMut`], see [`DocumentMut::place_table`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TablePlacement<'a> {
+    /// After every other table
+    End,
+    /// Directly after the table at this path and its sub-tables
+    After(&'a [&'a str]),
+    /// After the last table within the same parent, like `[dependencies.x]` after the other
+    /// `[dependencies.*]` tables
+    Grouped,
+    /// Among the tables within the same parent, in key order
+    Sorted,
+}
+
+impl DocumentMut {
+    /// Move the table at `path`, along with its sub-tables, to `placement`
+    ///
+    /// New tables are written after all existing tables by default.  This instead lets them be
+    /// placed where a person would have put them.
+    ///
+    /// If the table has no [`Decor`][crate::Decor] prefix, the blank line separating it from
+    /// its neighbor is matched.
+    ///
+    /// Returns `false` if `path` is not a standard table or, for [`TablePlacement::After`], the
+    /// sibling is not found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{DocumentMut, TablePlacement};
+    ///
+    /// let mut doc = "
+    /// [dependencies.a]
+    /// version = '1'
+    ///
+    /// [dependencies.c]
+    /// version = '1'
+    ///
+    /// [features]
+    /// default = []
+    /// ".parse::<DocumentMut>().unwrap();
+    ///
+    /// let mut b = toml_edit::Table::new();
+    /// b["version"] = toml_edit::value("1");
+    /// doc["dependencies"]["b"] = toml_edit::Item::Table(b);
+    /// assert!(doc.place_table(&["dependencies", "b"], TablePlacement::Sorted));
+    /// assert_eq!(doc.to_string(), "
+    /// [dependencies.a]
+    /// version = '1'
+    ///
+    /// [dependencies.b]
+    /// version = \"1\"
+    ///
+    /// [dependencies.c]
+    /// version = '1'
+    ///
+    /// [features]
+    /// default = []
+    /// ");
+    /// # }
+    /// # }
+    /// ```
+    pub fn place_table(&mut self, path: &[&str], placement: TablePlacement<'_>) -> bool {
+        if path.is_empty() {
+            return false;
+        }
+        let mut parent = self.as_table();
+        for key in &path[..path.len() - 1] {
+            match parent.get(key) {
+                Some(Item::Table(table)) => parent = table,
+                _ => return false,
+            }
+        }
+        match parent.get(path[path.len() - 1]) {
+            Some(Item::Table(table)) if !table.is_dotted() => {}
+            _ => return false,
+        }
+
+        let mut entries = Vec::new();
+        let mut last_position = 0;
+        collect_tables(
+            self.as_table(),
+            &mut Vec::new(),
+            &mut last_position,
+            &mut entries,
+        );
+        let mut order = (0..entries.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| entries[i].position);
+        // The root is always written first
+        order.retain(|&i| i != 0);
+
+        let is_moved = |i: &usize| starts_with(&entries[*i].path, path);
+        let block = order.iter().copied().filter(is_moved).collect::<Vec<_>>();
+        let mut rest = order
+            .iter()
+            .copied()
+            .filter(|i| !is_moved(i))
+            .collect::<Vec<_>>();
+
+        let parent = &path[..path.len() - 1];
+        let key = path[path.len() - 1];
+        let last_within = |prefix: &[&str]| {
+            rest.iter()
+                .rposition(|&i| starts_with(&entries[i].path, prefix))
+                .map(|index| index + 1)
+        };
+        let index = match placement {
+            TablePlacement::End => rest.len(),
+            TablePlacement::After(sibling) => match last_within(sibling) {
+                Some(index) => index,
+                None => return false,
+            },
+            TablePlacement::Grouped if parent.is_empty() => rest.len(),
+            TablePlacement::Grouped => last_within(parent).unwrap_or(rest.len()),
+            TablePlacement::Sorted => rest
+                .iter()
+                .position(|&i| {
+                    let sibling = &entries[i].path;
+                    parent.len() < sibling.len()
+                        && starts_with(sibling, parent)
+                        && key < sibling[parent.len()].as_str()
+                })
+                .or_else(|| last_within(parent))
+                .unwrap_or(rest.len()),
+        };
+        let neighbor = rest
+            .get(index)
+            .or_else(|| index.checked_sub(1).and_then(|i| rest.get(i)));
+        let blank_line = neighbor.and_then(|&i| entries[i].blank_line);
+        rest.splice(index..index, block);
+
+        let mut positions = vec![0; entries.len()];
+        for (position, i) in rest.into_iter().enumerate() {
+            positions[i] = position + 1;
+        }
+        let root = self.as_table_mut();
+        root.set_position(0);
+        let mut index = 1;
+        assign_positions(root, &positions, &mut index);
+
+        let mut table = self.as_table_mut();
+        for key in path {
+            table = table
+                .get_mut(key)
+                .and_then(Item::as_table_mut)
+                .expect("checked earlier");
+        }
+        if table.decor().prefix().is_none() {
+            if let Some(blank_line) = blank_line {
+                table
+                    .decor_mut()
+                    .set_prefix_unchecked(if blank_line { "\n" } else { "" });
+            }
+        }
+        true
+    }
+}
+
+struct Entry {
+    path: Vec<String>,
+    position: usize,
+    /// Whether the table's prefix starts with a blank line, if known
+    blank_line: Option<bool>,
+}
+
+/// Collect tables in the order [`DocumentMut`]'s `Display` visits them
+fn collect_tables(
+    table: &Table,
+    path: &mut Vec<String>,
+    last_position: &mut usize,
+    entries: &mut Vec<Entry>,
+) {
+    if !table.is_dotted() {
+        if let Some(position) = table.position() {
+            *last_position = position;
+        }
+        let blank_line = table
+            .decor()
+            .prefix()
+            .and_then(|p| p.as_str())
+            .map(|p| p.starts_with(['\n', '\r']));
+        entries.push(Entry {
+            path: path.clone(),
+            position: *last_position,
+            blank_line,
+        });
+    }
+
+    for (key, item) in table.iter() {
+        match item {
+            Item::Table(child) => {
+                path.push(key.to_owned());
+                collect_tables(child, path, last_position, entries);
+                path.pop();
+            }
+            Item::ArrayOfTables(children) => {
+                path.push(key.to_owned());
+                for child in children.iter() {
+                    collect_tables(child, path, last_position, entries);
+                }
+                path.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Walk tables in the same order as [`collect_tables`]
+fn assign_positions(table: &mut Table, positions: &[usize], index: &mut usize) {
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(child) => {
+                if !child.is_dotted() {
+                    child.set_position(positions[*index]);
+                    *index += 1;
+                }
+                assign_positions(child, positions, index);
+            }
+            Item::ArrayOfTables(children) => {
+                for child in children.iter_mut() {
+                    if !child.is_dotted() {
+                        child.set_position(positions[*index]);
+                        *index += 1;
+                    }
+                    assign_positions(child, positions, index);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn starts_with(path: &[String], prefix: &[&str]) -> bool {
+    prefix.len() <= path.len() && path.iter().zip(prefix).all(|(p, k)| p == k)
+}
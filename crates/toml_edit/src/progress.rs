@@ -0,0 +1,300 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use toml_parse::decoder::Encoding;
+use toml_parse::parser::EventReceiver;
+use toml_parse::{ErrorSink, Span};
+
+/// How many bytes to get through between reports
+const INTERVAL: usize = 64 * 1024;
+
+/// How far a parse has gotten, see [`Document::parse_with_progress`][crate::Document::parse_with_progress]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Progress {
+    /// What the parser is working through
+    pub stage: ParseStage,
+    /// How far into the input the current stage is
+    pub bytes: usize,
+    /// The length of the input, which each stage ends at
+    pub total_bytes: usize,
+    /// Table headers and key/value pairs parsed so far, not counting those within inline tables
+    pub expressions: usize,
+}
+
+/// The passes a document is parsed in, each going through the whole input
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ParseStage {
+    /// Splitting the input into tokens
+    Lexing,
+    /// Checking the tokens against the grammar
+    Parsing,
+    /// Putting the parsed keys and values into tables
+    Building,
+}
+
+/// Stop a parse early, from another thread or from a progress callback
+///
+/// Clones share the same state, so one can be kept to [`cancel`][CancellationToken::cancel] the
+/// parse given another.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "parse")] {
+/// use toml_edit::{CancellationToken, Document, ParseOptions};
+///
+/// let token = CancellationToken::new();
+/// token.cancel();
+///
+/// let result =
+///     Document::parse_with_progress("a = 1\n", &ParseOptions::new(), &mut |_| {}, &token);
+/// assert!(result.is_err());
+/// assert!(token.is_cancelled());
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// A token that hasn't been cancelled
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Stop any parse watching this token
+    ///
+    /// Parsing stops before the next token, expression, or key/value it gets to.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`][CancellationToken::cancel] was called on this token or a clone of it
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+pub(crate) struct Reporter<'p> {
+    callback: &'p mut dyn FnMut(Progress),
+    cancel: &'p CancellationToken,
+    total_bytes: usize,
+    expressions: usize,
+}
+
+impl<'p> Reporter<'p> {
+    pub(crate) fn new(
+        callback: &'p mut dyn FnMut(Progress),
+        cancel: &'p CancellationToken,
+        total_bytes: usize,
+    ) -> Self {
+        Self {
+            callback,
+            cancel,
+            total_bytes,
+            expressions: 0,
+        }
+    }
+
+    /// Report `bytes` into `stage`, returning whether to keep going
+    pub(crate) fn report(&mut self, stage: ParseStage, bytes: usize, expressions: usize) -> bool {
+        if self.is_cancelled() {
+            return false;
+        }
+        self.expressions = expressions;
+        (self.callback)(Progress {
+            stage,
+            bytes,
+            total_bytes: self.total_bytes,
+            expressions,
+        });
+        !self.is_cancelled()
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    pub(crate) fn lexing(&mut self) -> StageProgress<'_, 'p> {
+        StageProgress::new(self, ParseStage::Lexing)
+    }
+
+    pub(crate) fn building(&mut self) -> StageProgress<'_, 'p> {
+        StageProgress::new(self, ParseStage::Building)
+    }
+}
+
+/// Report as a stage gets through the input, for stages other than parsing
+pub(crate) struct StageProgress<'r, 'p> {
+    reporter: &'r mut Reporter<'p>,
+    stage: ParseStage,
+    next_report: usize,
+}
+
+impl<'r, 'p> StageProgress<'r, 'p> {
+    fn new(reporter: &'r mut Reporter<'p>, stage: ParseStage) -> Self {
+        Self {
+            reporter,
+            stage,
+            next_report: INTERVAL,
+        }
+    }
+
+    /// Note that the stage got up to `offset`, returning whether to keep going
+    pub(crate) fn advance(&mut self, offset: usize) -> bool {
+        if offset < self.next_report {
+            return !self.reporter.is_cancelled();
+        }
+        self.next_report = offset + INTERVAL;
+        let expressions = self.reporter.expressions;
+        self.reporter.report(self.stage, offset, expressions)
+    }
+
+    pub(crate) fn finish(self) -> bool {
+        let total_bytes = self.reporter.total_bytes;
+        let expressions = self.reporter.expressions;
+        self.reporter.report(self.stage, total_bytes, expressions)
+    }
+}
+
+/// Report as events are parsed, aborting the parse once cancelled
+pub(crate) struct ParsingProgress<'r, 'p> {
+    receiver: &'r mut dyn EventReceiver,
+    reporter: &'r mut Reporter<'p>,
+    next_report: usize,
+    depth: usize,
+    expressions: usize,
+    stopped: bool,
+}
+
+impl<'r, 'p> ParsingProgress<'r, 'p> {
+    pub(crate) fn new(receiver: &'r mut dyn EventReceiver, reporter: &'r mut Reporter<'p>) -> Self {
+        Self {
+            receiver,
+            reporter,
+            next_report: INTERVAL,
+            depth: 0,
+            expressions: 0,
+            stopped: false,
+        }
+    }
+
+    /// Report once parsing is done, returning whether to keep going
+    pub(crate) fn finish(self) -> bool {
+        let total_bytes = self.reporter.total_bytes;
+        !self.stopped
+            && self
+                .reporter
+                .report(ParseStage::Parsing, total_bytes, self.expressions)
+    }
+
+    fn advance(&mut self, span: Span) {
+        if self.stopped || span.end() < self.next_report {
+            return;
+        }
+        self.next_report = span.end() + INTERVAL;
+        self.stopped = !self
+            .reporter
+            .report(ParseStage::Parsing, span.end(), self.expressions);
+    }
+
+    fn open(&mut self) {
+        self.depth += 1;
+    }
+
+    fn close(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    fn expression(&mut self) {
+        if self.depth == 0 {
+            self.expressions += 1;
+        }
+    }
+}
+
+impl EventReceiver for ParsingProgress<'_, '_> {
+    fn std_table_open(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.expression();
+        self.receiver.std_table_open(span, error);
+        self.advance(span);
+    }
+    fn std_table_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.std_table_close(span, error);
+        self.advance(span);
+    }
+    fn array_table_open(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.expression();
+        self.receiver.array_table_open(span, error);
+        self.advance(span);
+    }
+    fn array_table_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.array_table_close(span, error);
+        self.advance(span);
+    }
+    fn inline_table_open(&mut self, span: Span, error: &mut dyn ErrorSink) -> bool {
+        self.open();
+        let allowed = self.receiver.inline_table_open(span, error);
+        self.advance(span);
+        allowed
+    }
+    fn inline_table_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.close();
+        self.receiver.inline_table_close(span, error);
+        self.advance(span);
+    }
+    fn array_open(&mut self, span: Span, error: &mut dyn ErrorSink) -> bool {
+        self.open();
+        let allowed = self.receiver.array_open(span, error);
+        self.advance(span);
+        allowed
+    }
+    fn array_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.close();
+        self.receiver.array_close(span, error);
+        self.advance(span);
+    }
+    fn simple_key(&mut self, span: Span, encoding: Option<Encoding>, error: &mut dyn ErrorSink) {
+        self.receiver.simple_key(span, encoding, error);
+        self.advance(span);
+    }
+    fn key_sep(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.key_sep(span, error);
+        self.advance(span);
+    }
+    fn key_val_sep(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.expression();
+        self.receiver.key_val_sep(span, error);
+        self.advance(span);
+    }
+    fn scalar(&mut self, span: Span, encoding: Option<Encoding>, error: &mut dyn ErrorSink) {
+        self.receiver.scalar(span, encoding, error);
+        self.advance(span);
+    }
+    fn value_sep(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.value_sep(span, error);
+        self.advance(span);
+    }
+    fn whitespace(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.whitespace(span, error);
+        self.advance(span);
+    }
+    fn comment(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.comment(span, error);
+        self.advance(span);
+    }
+    fn newline(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.newline(span, error);
+        self.advance(span);
+    }
+    fn error(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.error(span, error);
+        self.advance(span);
+    }
+    fn is_aborted(&self) -> bool {
+        self.stopped || self.reporter.is_cancelled() || self.receiver.is_aborted()
+    }
+}
@@ -0,0 +1,446 @@
+use std::collections::BTreeMap;
+
+use crate::table::KeyValuePairs;
+use crate::{DocumentMut, Item, Key, PathSegment, Table, Value};
+
+/// Why a key can't be written bare, see [`Key::quote_reasons`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum QuoteReason {
+    /// `""`
+    Empty,
+    /// A `.`, which would be read as a dotted key
+    Dot,
+    /// A space or tab
+    Whitespace,
+    /// A control character, like a newline
+    Control,
+    /// ASCII punctuation other than `-` and `_`, like `/` or `@`
+    Punctuation,
+    /// A character outside of ASCII, like `é`
+    NonAscii,
+}
+
+/// A key that is written quoted, see [`DocumentMut::quoted_keys`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuotedKey {
+    path: Vec<PathSegment>,
+    reasons: Vec<QuoteReason>,
+}
+
+impl QuotedKey {
+    /// Where the key is within the document, ending with the key
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The key
+    pub fn key(&self) -> &str {
+        match self.path.last() {
+            Some(PathSegment::Key(key)) => key,
+            _ => unreachable!("always ends with a key"),
+        }
+    }
+
+    /// Each kind of character that needs quoting, in order
+    pub fn reasons(&self) -> &[QuoteReason] {
+        &self.reasons
+    }
+}
+
+/// A key renamed by [`DocumentMut::rename_quoted_keys`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyRename {
+    path: Vec<PathSegment>,
+    from: String,
+    to: String,
+}
+
+impl KeyRename {
+    /// Where the table or inline table with the key is, using the keys it was renamed to
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The key before it was renamed
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// The bare key it was renamed to
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+}
+
+/// How to turn a key into a bare key, see [`Transliteration::bare_key`]
+///
+/// By default, accented Latin letters lose their accents, like `é` to `e` and `ß` to `ss`, and
+/// runs of other characters become a single `_`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transliteration {
+    separator: char,
+    chars: BTreeMap<char, String>,
+}
+
+impl Transliteration {
+    /// The default transliteration
+    pub fn new() -> Self {
+        let chars = LATIN
+            .iter()
+            .flat_map(|(to, from)| from.chars().map(move |c| (c, (*to).to_owned())))
+            .collect();
+        Self {
+            separator: '_',
+            chars,
+        }
+    }
+
+    /// What characters without a transliteration are replaced with
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    /// Set what characters without a transliteration are replaced with
+    ///
+    /// # Panics
+    ///
+    /// If `separator` is not `-` or `_`
+    pub fn set_separator(&mut self, separator: char) {
+        assert!(
+            separator == '-' || separator == '_',
+            "`{separator}` is not allowed in a bare key"
+        );
+        self.separator = separator;
+    }
+
+    /// Replace `from` with `to`, like `ä` with `ae`
+    ///
+    /// Characters of `to` that aren't allowed in bare keys are replaced with the separator.
+    pub fn set_char(&mut self, from: char, to: impl Into<String>) {
+        self.chars.insert(from, to.into());
+    }
+
+    /// `key` as a bare key, if anything of it is left
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use toml_edit::Transliteration;
+    ///
+    /// let mut transliteration = Transliteration::new();
+    /// assert_eq!(transliteration.bare_key("Café au lait").as_deref(), Some("Cafe_au_lait"));
+    /// assert_eq!(transliteration.bare_key("docs.rs/targets").as_deref(), Some("docs_rs_targets"));
+    /// assert_eq!(transliteration.bare_key("日本"), None);
+    ///
+    /// transliteration.set_separator('-');
+    /// transliteration.set_char('ä', "ae");
+    /// assert_eq!(transliteration.bare_key("Bär (alt)").as_deref(), Some("Baer-alt"));
+    /// ```
+    pub fn bare_key(&self, key: &str) -> Option<String> {
+        let mut bare = String::with_capacity(key.len());
+        let mut push = |c: char| {
+            if is_bare_char(c) {
+                bare.push(c);
+            } else if !bare.is_empty() && !bare.ends_with(self.separator) {
+                bare.push(self.separator);
+            }
+        };
+        for c in key.chars() {
+            match self.chars.get(&c) {
+                Some(to) => {
+                    for c in to.chars() {
+                        push(c);
+                    }
+                }
+                None => push(c),
+            }
+        }
+        let bare = bare.trim_end_matches(self.separator);
+        (!bare.is_empty()).then(|| bare.to_owned())
+    }
+}
+
+impl Default for Transliteration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The default transliterations, as the letters each replaces
+const LATIN: &[(&str, &str)] = &[
+    ("A", "ÀÁÂÃÄÅĀĂĄ"),
+    ("a", "àáâãäåāăą"),
+    ("AE", "Æ"),
+    ("ae", "æ"),
+    ("C", "ÇĆĈĊČ"),
+    ("c", "çćĉċč"),
+    ("D", "ĎĐ"),
+    ("d", "ďđ"),
+    ("E", "ÈÉÊËĒĔĖĘĚ"),
+    ("e", "èéêëēĕėęě"),
+    ("G", "ĜĞĠĢ"),
+    ("g", "ĝğġģ"),
+    ("I", "ÌÍÎÏĨĪĬĮİ"),
+    ("i", "ìíîïĩīĭįı"),
+    ("L", "ĹĻĽĿŁ"),
+    ("l", "ĺļľŀł"),
+    ("N", "ÑŃŅŇ"),
+    ("n", "ñńņň"),
+    ("O", "ÒÓÔÕÖØŌŎŐ"),
+    ("o", "òóôõöøōŏő"),
+    ("OE", "Œ"),
+    ("oe", "œ"),
+    ("R", "ŔŖŘ"),
+    ("r", "ŕŗř"),
+    ("S", "ŚŜŞŠ"),
+    ("s", "śŝşš"),
+    ("ss", "ß"),
+    ("T", "ŢŤ"),
+    ("t", "ţť"),
+    ("U", "ÙÚÛÜŨŪŬŮŰŲ"),
+    ("u", "ùúûüũūŭůűų"),
+    ("Y", "ÝŸ"),
+    ("y", "ýÿ"),
+    ("Z", "ŹŻŽ"),
+    ("z", "źżž"),
+];
+
+impl Key {
+    /// Why this key can't be written bare, empty when it can
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use toml_edit::{Key, QuoteReason};
+    ///
+    /// assert_eq!(Key::new("serde_json").quote_reasons(), []);
+    /// assert_eq!(
+    ///     Key::new("docs.rs targets").quote_reasons(),
+    ///     [QuoteReason::Dot, QuoteReason::Whitespace]
+    /// );
+    /// ```
+    pub fn quote_reasons(&self) -> Vec<QuoteReason> {
+        quote_reasons(self.get())
+    }
+}
+
+fn quote_reasons(key: &str) -> Vec<QuoteReason> {
+    if key.is_empty() {
+        return vec![QuoteReason::Empty];
+    }
+    let mut reasons = Vec::new();
+    for c in key.chars() {
+        let reason = match c {
+            _ if is_bare_char(c) => continue,
+            '.' => QuoteReason::Dot,
+            ' ' | '\t' => QuoteReason::Whitespace,
+            _ if c.is_ascii_control() => QuoteReason::Control,
+            _ if c.is_ascii() => QuoteReason::Punctuation,
+            _ => QuoteReason::NonAscii,
+        };
+        if !reasons.contains(&reason) {
+            reasons.push(reason);
+        }
+    }
+    reasons
+}
+
+fn is_bare_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+impl DocumentMut {
+    /// Every key that has to be quoted, in document order
+    ///
+    /// This includes keys within inline tables and arrays.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::QuoteReason;
+    ///
+    /// let doc = r#"
+    /// [package.metadata."docs.rs"]
+    /// all-features = true
+    /// "Target List" = ["x86_64-unknown-linux-gnu"]
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let keys = doc.quoted_keys();
+    /// assert_eq!(keys.len(), 2);
+    /// assert_eq!(keys[0].key(), "docs.rs");
+    /// assert_eq!(keys[0].reasons(), [QuoteReason::Dot]);
+    /// assert_eq!(keys[1].path().len(), 4);
+    /// assert_eq!(keys[1].reasons(), [QuoteReason::Whitespace]);
+    /// # }
+    /// ```
+    pub fn quoted_keys(&self) -> Vec<QuotedKey> {
+        let mut keys = Vec::new();
+        find_table(self.as_table(), &mut Vec::new(), &mut keys);
+        keys
+    }
+
+    /// Rename every key that has to be quoted to its [`Transliteration::bare_key`]
+    ///
+    /// Renamed keys keep their place, comments, and [`ItemId`][crate::ItemId].  A key is left
+    /// as-is when nothing of it is left as a bare key or its bare key is already taken in its
+    /// table, so it is still listed by [`DocumentMut::quoted_keys`].
+    ///
+    /// Returns the renames in the order they were made.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = r#"
+    /// [servers."eu west"]  # primary
+    /// "ip address" = "10.0.0.1"
+    /// ip_address = "10.0.0.2"
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let renames = doc.rename_quoted_keys(&toml_edit::Transliteration::new());
+    /// assert_eq!(renames.len(), 1);
+    /// assert_eq!(renames[0].to(), "eu_west");
+    /// assert_eq!(doc.to_string(), r#"
+    /// [servers.eu_west]  # primary
+    /// "ip address" = "10.0.0.1"
+    /// ip_address = "10.0.0.2"
+    /// "#);
+    /// # }
+    /// # }
+    /// ```
+    pub fn rename_quoted_keys(&mut self, transliteration: &Transliteration) -> Vec<KeyRename> {
+        let mut renamer = Renamer {
+            transliteration,
+            path: Vec::new(),
+            renames: Vec::new(),
+        };
+        renamer.items(self.as_table_mut().items_mut());
+        renamer.renames
+    }
+}
+
+fn find_table(table: &Table, path: &mut Vec<PathSegment>, keys: &mut Vec<QuotedKey>) {
+    for (key, item) in table.items.iter() {
+        path.push(PathSegment::Key(key.get().to_owned()));
+        let reasons = key.quote_reasons();
+        if !reasons.is_empty() {
+            keys.push(QuotedKey {
+                path: path.clone(),
+                reasons,
+            });
+        }
+        match item {
+            Item::None => {}
+            Item::Value(value) => find_value(value, path, keys),
+            Item::Table(table) => find_table(table, path, keys),
+            Item::ArrayOfTables(array) => {
+                for (i, table) in array.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    find_table(table, path, keys);
+                    path.pop();
+                }
+            }
+        }
+        path.pop();
+    }
+}
+
+fn find_value(value: &Value, path: &mut Vec<PathSegment>, keys: &mut Vec<QuotedKey>) {
+    match value {
+        Value::Array(array) => {
+            for (i, value) in array.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                find_value(value, path, keys);
+                path.pop();
+            }
+        }
+        Value::InlineTable(table) => {
+            for (key, value) in table.iter() {
+                path.push(PathSegment::Key(key.to_owned()));
+                let reasons = quote_reasons(key);
+                if !reasons.is_empty() {
+                    keys.push(QuotedKey {
+                        path: path.clone(),
+                        reasons,
+                    });
+                }
+                find_value(value, path, keys);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+struct Renamer<'t> {
+    transliteration: &'t Transliteration,
+    path: Vec<PathSegment>,
+    renames: Vec<KeyRename>,
+}
+
+impl Renamer<'_> {
+    fn items(&mut self, items: &mut KeyValuePairs) {
+        for index in 0..items.len() {
+            let (key, _) = items.get_index(index).expect("in bounds");
+            let key = key.get();
+            if quote_reasons(key).is_empty() {
+                continue;
+            }
+            let Some(to) = self.transliteration.bare_key(key) else {
+                continue;
+            };
+            if items.contains_key(to.as_str()) {
+                continue;
+            }
+            let (old, item) = items.shift_remove_index(index).expect("in bounds");
+            let renamed = Key::new(to.as_str())
+                .with_leaf_decor_unchecked(old.leaf_decor().clone())
+                .with_dotted_decor_unchecked(old.dotted_decor().clone())
+                .with_id(old.id());
+            items.shift_insert(index, renamed, item);
+            self.renames.push(KeyRename {
+                path: self.path.clone(),
+                from: old.get().to_owned(),
+                to,
+            });
+        }
+
+        for (key, item) in items.iter_mut() {
+            self.path.push(PathSegment::Key(key.get().to_owned()));
+            self.item(item);
+            self.path.pop();
+        }
+    }
+
+    fn item(&mut self, item: &mut Item) {
+        match item {
+            Item::None => {}
+            Item::Value(value) => self.value(value),
+            Item::Table(table) => self.items(table.items_mut()),
+            Item::ArrayOfTables(array) => {
+                for (i, table) in array.iter_mut().enumerate() {
+                    self.path.push(PathSegment::Index(i));
+                    self.items(table.items_mut());
+                    self.path.pop();
+                }
+            }
+        }
+    }
+
+    fn value(&mut self, value: &mut Value) {
+        match value {
+            Value::Array(array) => {
+                for (i, value) in array.iter_mut().enumerate() {
+                    self.path.push(PathSegment::Index(i));
+                    self.value(value);
+                    self.path.pop();
+                }
+            }
+            Value::InlineTable(table) => self.items(&mut table.items),
+            _ => {}
+        }
+    }
+}
@@ -1,17 +1,39 @@
+use std::sync::Arc;
+
 use crate::InternalString;
 
 /// Opaque string storage for raw TOML; internal to `toml_edit`
-#[derive(PartialEq, Eq, Clone, Hash)]
+#[derive(Clone)]
 pub struct RawString(RawStringInner);
 
-#[derive(PartialEq, Eq, Clone, Hash)]
+#[derive(Clone)]
 enum RawStringInner {
     Empty,
+    /// A [common fragment][COMMON], rather than allocating for each decor
+    Static(&'static str),
     Explicit(InternalString),
     Spanned(std::ops::Range<usize>),
+    /// A slice of the document it was parsed from, shared with the rest of the document
+    ///
+    /// The span is `u32` to keep this no larger than [`InternalString`].  It is only reported
+    /// as [`RawString::span`] when spans were kept, see [`crate::Document::into_mut_with_spans`].
+    Shared(Arc<str>, std::ops::Range<u32>, bool),
 }
 
+/// Raw text that is frequent enough in decor to not allocate for, see [`RawString::interned`]
+const COMMON: &[&str] = &[" ", "\n", "\n\n", "  ", "    ", "\t", "\r\n"];
+
 impl RawString {
+    /// Reuse a [common fragment][COMMON] for `s`, if it is one
+    fn interned(s: &str) -> Option<Self> {
+        if s.is_empty() {
+            Some(Self(RawStringInner::Empty))
+        } else {
+            let s = COMMON.iter().find(|c| **c == s)?;
+            Some(Self(RawStringInner::Static(s)))
+        }
+    }
+
     pub(crate) fn with_span(span: std::ops::Range<usize>) -> Self {
         RawString(RawStringInner::Spanned(span))
     }
@@ -22,8 +44,10 @@ impl RawString {
     pub fn as_str(&self) -> Option<&str> {
         match &self.0 {
             RawStringInner::Empty => Some(""),
+            RawStringInner::Static(s) => Some(s),
             RawStringInner::Explicit(s) => Some(s.as_str()),
             RawStringInner::Spanned(_) => None,
+            RawStringInner::Shared(input, span, _) => Some(shared_str(input, span)),
         }
     }
 
@@ -33,18 +57,24 @@ impl RawString {
     pub fn span(&self) -> Option<std::ops::Range<usize>> {
         match &self.0 {
             RawStringInner::Empty => None,
+            RawStringInner::Static(_) => None,
             RawStringInner::Explicit(_) => None,
             RawStringInner::Spanned(span) => Some(span.clone()),
+            RawStringInner::Shared(_, span, spans) => {
+                spans.then(|| span.start as usize..span.end as usize)
+            }
         }
     }
 
     pub(crate) fn to_str<'s>(&'s self, input: &'s str) -> &'s str {
         match &self.0 {
             RawStringInner::Empty => "",
+            RawStringInner::Static(s) => s,
             RawStringInner::Explicit(s) => s.as_str(),
             RawStringInner::Spanned(span) => input
                 .get(span.clone())
                 .unwrap_or_else(|| panic!("span {span:?} should be in input:\n```\n{input}\n```")),
+            RawStringInner::Shared(input, span, _) => shared_str(input, span),
         }
     }
 
@@ -55,6 +85,7 @@ impl RawString {
     ) -> &'s str {
         match &self.0 {
             RawStringInner::Empty => "",
+            RawStringInner::Static(s) => s,
             RawStringInner::Explicit(s) => s.as_str(),
             RawStringInner::Spanned(span) => {
                 if let Some(input) = input {
@@ -65,32 +96,57 @@ impl RawString {
                     default
                 }
             }
+            RawStringInner::Shared(input, span, _) => shared_str(input, span),
         }
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
-        match &self.0 {
+    /// Replace a span with a slice of `input`, sharing `input` rather than copying from it
+    pub(crate) fn despan(&mut self, input: &Arc<str>, spans: bool) {
+        match &mut self.0 {
             RawStringInner::Empty => {}
+            RawStringInner::Static(_) => {}
             RawStringInner::Explicit(_) => {}
+            RawStringInner::Shared(_, _, kept) => *kept &= spans,
             RawStringInner::Spanned(span) => {
-                if span.start == span.end {
-                    *self = RawString(RawStringInner::Empty);
-                } else {
-                    *self = Self::from(input.get(span.clone()).unwrap_or_else(|| {
-                        panic!("span {span:?} should be in input:\n```\n{input}\n```")
-                    }));
-                }
+                let raw = input.get(span.clone()).unwrap_or_else(|| {
+                    panic!("span {span:?} should be in input:\n```\n{input}\n```")
+                });
+                *self = match (u32::try_from(span.start), u32::try_from(span.end)) {
+                    _ if raw.is_empty() => RawString(RawStringInner::Empty),
+                    (Ok(start), Ok(end)) => {
+                        RawString(RawStringInner::Shared(input.clone(), start..end, spans))
+                    }
+                    _ => Self::from(raw),
+                };
             }
         }
     }
 
+    /// The length of the document this is a slice of, see [`RawStringInner::Shared`]
+    #[cfg(feature = "display")]
+    pub(crate) fn source_len(&self) -> Option<usize> {
+        match &self.0 {
+            RawStringInner::Empty
+            | RawStringInner::Static(_)
+            | RawStringInner::Explicit(_)
+            | RawStringInner::Spanned(_) => None,
+            RawStringInner::Shared(input, _, _) => Some(input.len()),
+        }
+    }
+
+    /// Record the memory used by the string
+    pub(crate) fn measure(&self, measure: &mut crate::memory::Measure) {
+        match &self.0 {
+            RawStringInner::Empty | RawStringInner::Static(_) | RawStringInner::Spanned(_) => {}
+            RawStringInner::Explicit(s) => measure.add_owned(s.as_str()),
+            RawStringInner::Shared(input, _, _) => measure.add_shared(input),
+        }
+    }
+
     #[cfg(feature = "display")]
     pub(crate) fn encode(&self, buf: &mut dyn std::fmt::Write, input: &str) -> std::fmt::Result {
         let raw = self.to_str(input);
-        for part in raw.split('\r') {
-            write!(buf, "{part}")?;
-        }
-        Ok(())
+        encode_normalized_newlines(buf, raw)
     }
 
     #[cfg(feature = "display")]
@@ -101,10 +157,53 @@ impl RawString {
         default: &str,
     ) -> std::fmt::Result {
         let raw = self.to_str_with_default(input, default);
-        for part in raw.split('\r') {
-            write!(buf, "{part}")?;
+        encode_normalized_newlines(buf, raw)
+    }
+}
+
+fn shared_str<'s>(input: &'s str, span: &std::ops::Range<u32>) -> &'s str {
+    &input[span.start as usize..span.end as usize]
+}
+
+/// Write `raw` with `\r\n` normalized to `\n`
+///
+/// A lone `\r` is invalid and is kept as-is so [lossy parsing][crate::Document::parse_lossy]
+/// doesn't change content around it.
+#[cfg(feature = "display")]
+fn encode_normalized_newlines(buf: &mut dyn std::fmt::Write, raw: &str) -> std::fmt::Result {
+    if raw.is_empty() {
+        return Ok(());
+    }
+    let mut parts = raw.split("\r\n");
+    if let Some(first) = parts.next() {
+        buf.write_str(first)?;
+    }
+    for part in parts {
+        buf.write_char('\n')?;
+        buf.write_str(part)?;
+    }
+    Ok(())
+}
+
+impl PartialEq for RawString {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.as_str(), other.as_str()) {
+            (Some(s), Some(other)) => s == other,
+            (None, None) => self.span() == other.span(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for RawString {}
+
+impl std::hash::Hash for RawString {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let raw = self.as_str();
+        raw.hash(state);
+        if raw.is_none() {
+            self.span().hash(state);
         }
-        Ok(())
     }
 }
 
@@ -119,8 +218,12 @@ impl std::fmt::Debug for RawString {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         match &self.0 {
             RawStringInner::Empty => write!(formatter, "empty"),
+            RawStringInner::Static(s) => write!(formatter, "{s:?}"),
             RawStringInner::Explicit(s) => write!(formatter, "{s:?}"),
             RawStringInner::Spanned(s) => write!(formatter, "{s:?}"),
+            RawStringInner::Shared(input, span, _) => {
+                write!(formatter, "{:?}", shared_str(input, span))
+            }
         }
     }
 }
@@ -128,61 +231,41 @@ impl std::fmt::Debug for RawString {
 impl From<&str> for RawString {
     #[inline]
     fn from(s: &str) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::interned(s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
 
 impl From<String> for RawString {
     #[inline]
     fn from(s: String) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::interned(&s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
 
 impl From<&String> for RawString {
     #[inline]
     fn from(s: &String) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::interned(s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
 
 impl From<InternalString> for RawString {
     #[inline]
     fn from(inner: InternalString) -> Self {
-        Self(RawStringInner::Explicit(inner))
+        Self::interned(&inner).unwrap_or(Self(RawStringInner::Explicit(inner)))
     }
 }
 
 impl From<&InternalString> for RawString {
     #[inline]
     fn from(s: &InternalString) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::interned(s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
 
 impl From<Box<str>> for RawString {
     #[inline]
     fn from(s: Box<str>) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::interned(&s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
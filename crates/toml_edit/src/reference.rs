@@ -0,0 +1,377 @@
+//! Reuse values by reference, like YAML anchors
+//!
+//! A table whose only key is `ref` is replaced by the item at that dotted key:
+//! ```toml
+//! [shared.values]
+//! port = 8080
+//!
+//! [server]
+//! port = { ref = "shared.values.port" }
+//! ```
+//! An optional `document` key looks the reference up in another document given to the
+//! [`Resolver`].  References within the target are resolved as well, relative to the document
+//! the target is in.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "display")] {
+//! use toml_edit::DocumentMut;
+//! use toml_edit::reference::Resolver;
+//!
+//! let common = "
+//! [ports]
+//! http = 8080
+//! ".parse::<DocumentMut>().unwrap();
+//! let doc = "
+//! [defaults]
+//! host = 'localhost'
+//!
+//! [server]
+//! host = { ref = 'defaults.host' }
+//! port = { ref = 'ports.http', document = 'common' }  # shared
+//! ".parse::<DocumentMut>().unwrap();
+//!
+//! let resolved = Resolver::new()
+//!     .document("common", common.as_table())
+//!     .resolve(doc.as_table())
+//!     .unwrap();
+//! let resolved = DocumentMut::from(resolved);
+//! assert_eq!(resolved.to_string(), "
+//! [defaults]
+//! host = 'localhost'
+//!
+//! [server]
+//! host = 'localhost'
+//! port = 8080  # shared
+//! ");
+//!
+//! let cycle = "a = { ref = 'b' }\nb = { ref = 'a' }\n".parse::<DocumentMut>().unwrap();
+//! let err = toml_edit::reference::resolve(cycle.as_table()).unwrap_err();
+//! assert_eq!(err.to_string(), "reference cycle: b -> a -> b");
+//! # }
+//! ```
+
+use crate::{Item, Key, PathSegment, Table, TableLike, Value};
+
+const REF: &str = "ref";
+const DOCUMENT: &str = "document";
+
+/// A copy of `table` with all references within it resolved
+///
+/// See [`Resolver`] for referencing other documents.
+pub fn resolve(table: &Table) -> Result<Table, RefError> {
+    Resolver::new().resolve(table)
+}
+
+/// Resolves references, including to other documents, see the [module docs][self]
+#[derive(Clone, Debug, Default)]
+pub struct Resolver<'d> {
+    documents: Vec<(&'d str, &'d Table)>,
+}
+
+impl<'d> Resolver<'d> {
+    /// Only resolve references within the same document
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow references to `table` with `document = "<name>"`
+    pub fn document(mut self, name: &'d str, table: &'d Table) -> Self {
+        self.documents.push((name, table));
+        self
+    }
+
+    /// A copy of `table` with all references within it resolved
+    ///
+    /// The resolved items keep the formatting of the reference they replace.  Spans, like for
+    /// [`RefError::span`], are only available if `table` is from a [`Document`][crate::Document].
+    pub fn resolve(&self, table: &Table) -> Result<Table, RefError> {
+        let mut state = State {
+            path: Vec::new(),
+            stack: Vec::new(),
+        };
+        let root = DocId::Root;
+        self.resolve_table(table, root, table, &mut state)
+    }
+
+    fn table<'r>(&'r self, root: &'r Table, doc: DocId) -> &'r Table {
+        match doc {
+            DocId::Root => root,
+            DocId::Other(i) => self.documents[i].1,
+        }
+    }
+
+    fn resolve_table(
+        &self,
+        root: &Table,
+        doc: DocId,
+        table: &Table,
+        state: &mut State,
+    ) -> Result<Table, RefError> {
+        let mut resolved = table.clone();
+        for (key, item) in resolved.iter_mut() {
+            state.path.push(PathSegment::Key(key.get().to_owned()));
+            *item = self.resolve_item(root, doc, item, state)?;
+            state.path.pop();
+        }
+        Ok(resolved)
+    }
+
+    fn resolve_item(
+        &self,
+        root: &Table,
+        doc: DocId,
+        item: &Item,
+        state: &mut State,
+    ) -> Result<Item, RefError> {
+        if let Some((reference, document)) = as_reference(item) {
+            let target = self.resolve_reference(root, doc, reference, document, item, state)?;
+            return Ok(match item {
+                Item::Value(value) => {
+                    let mut target = target.into_value().expect("references are never empty");
+                    *target.decor_mut() = value.decor().clone();
+                    Item::Value(target)
+                }
+                Item::Table(table) => match target {
+                    Item::Table(mut target) => {
+                        *target.decor_mut() = table.decor().clone();
+                        if let Some(position) = table.position() {
+                            target.set_position(position);
+                        }
+                        Item::Table(target)
+                    }
+                    target => target,
+                },
+                _ => unreachable!("only tables are references"),
+            });
+        }
+
+        match item {
+            Item::None => Ok(Item::None),
+            Item::Value(value) => self.resolve_value(root, doc, value, state).map(Item::Value),
+            Item::Table(table) => self.resolve_table(root, doc, table, state).map(Item::Table),
+            Item::ArrayOfTables(array) => {
+                let mut resolved = array.clone();
+                for (i, table) in resolved.iter_mut().enumerate() {
+                    state.path.push(PathSegment::Index(i));
+                    *table = self.resolve_table(root, doc, table, state)?;
+                    state.path.pop();
+                }
+                Ok(Item::ArrayOfTables(resolved))
+            }
+        }
+    }
+
+    fn resolve_value(
+        &self,
+        root: &Table,
+        doc: DocId,
+        value: &Value,
+        state: &mut State,
+    ) -> Result<Value, RefError> {
+        match value {
+            Value::Array(array) => {
+                let mut resolved = array.clone();
+                for (i, value) in resolved.iter_mut().enumerate() {
+                    state.path.push(PathSegment::Index(i));
+                    let item = self.resolve_item(root, doc, &Item::Value(value.clone()), state)?;
+                    *value = item.into_value().expect("values resolve to values");
+                    state.path.pop();
+                }
+                Ok(Value::Array(resolved))
+            }
+            Value::InlineTable(table) => {
+                let mut resolved = table.clone();
+                for (key, value) in resolved.iter_mut() {
+                    state.path.push(PathSegment::Key(key.get().to_owned()));
+                    let item = self.resolve_item(root, doc, &Item::Value(value.clone()), state)?;
+                    *value = item.into_value().expect("values resolve to values");
+                    state.path.pop();
+                }
+                Ok(Value::InlineTable(resolved))
+            }
+            value => Ok(value.clone()),
+        }
+    }
+
+    fn resolve_reference(
+        &self,
+        root: &Table,
+        doc: DocId,
+        reference: &str,
+        document: Option<&str>,
+        item: &Item,
+        state: &mut State,
+    ) -> Result<Item, RefError> {
+        let error = |state: &State, kind| RefError {
+            key: path_string(&state.path),
+            reference: reference.to_owned(),
+            span: item.span(),
+            kind,
+        };
+
+        let target_doc = match document {
+            Some(name) => self
+                .documents
+                .iter()
+                .position(|(n, _)| *n == name)
+                .map(DocId::Other)
+                .ok_or_else(|| error(state, RefErrorKind::UnknownDocument(name.to_owned())))?,
+            None => doc,
+        };
+        let keys = Key::parse(reference).map_err(|_| error(state, RefErrorKind::Invalid))?;
+        let mut target = None;
+        let mut current: &dyn TableLike = self.table(root, target_doc);
+        for (i, key) in keys.iter().enumerate() {
+            let Some(next) = current.get(key.get()) else {
+                break;
+            };
+            if i + 1 == keys.len() {
+                target = Some(next);
+            } else if let Some(next) = next.as_table_like() {
+                current = next;
+            } else {
+                break;
+            }
+        }
+        let target = target.ok_or_else(|| error(state, RefErrorKind::NotFound))?;
+
+        let name = match target_doc {
+            DocId::Root => reference.to_owned(),
+            DocId::Other(i) => format!("{}:{reference}", self.documents[i].0),
+        };
+        if let Some(start) = state.stack.iter().position(|(_, n)| *n == name) {
+            let mut cycle = state.stack[start..]
+                .iter()
+                .map(|(_, n)| n.clone())
+                .collect::<Vec<_>>();
+            cycle.push(name);
+            return Err(error(state, RefErrorKind::Cycle(cycle)));
+        }
+
+        // Report errors within the target relative to the target
+        let path = std::mem::replace(
+            &mut state.path,
+            keys.iter()
+                .map(|key| PathSegment::Key(key.get().to_owned()))
+                .collect(),
+        );
+        state.stack.push((target_doc, name));
+        let resolved = self.resolve_item(root, target_doc, target, state);
+        state.stack.pop();
+        state.path = path;
+        resolved
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum DocId {
+    Root,
+    Other(usize),
+}
+
+struct State {
+    path: Vec<PathSegment>,
+    stack: Vec<(DocId, String)>,
+}
+
+fn as_reference(item: &Item) -> Option<(&str, Option<&str>)> {
+    let table = match item {
+        Item::Table(table) => table as &dyn TableLike,
+        Item::Value(Value::InlineTable(table)) => table as &dyn TableLike,
+        _ => return None,
+    };
+    let reference = table.get(REF)?.as_str()?;
+    let document = match table.get(DOCUMENT) {
+        Some(document) => Some(document.as_str()?),
+        None => None,
+    };
+    let expected_len = 1 + usize::from(document.is_some());
+    (table.len() == expected_len).then_some((reference, document))
+}
+
+fn path_string(path: &[PathSegment]) -> String {
+    let mut string = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) => {
+                if !string.is_empty() {
+                    string.push('.');
+                }
+                string.push_str(key);
+            }
+            PathSegment::Index(index) => string.push_str(&format!("[{index}]")),
+        }
+    }
+    string
+}
+
+/// A reference could not be resolved, see [`Resolver::resolve`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefError {
+    key: String,
+    reference: String,
+    span: Option<std::ops::Range<usize>>,
+    kind: RefErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RefErrorKind {
+    Invalid,
+    NotFound,
+    UnknownDocument(String),
+    Cycle(Vec<String>),
+}
+
+impl RefError {
+    /// The dotted key of the reference, relative to the document it is in
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The dotted key being referenced
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    /// The location of the reference within the original document
+    ///
+    /// This generally requires a [`Document`][crate::Document].
+    pub fn span(&self) -> Option<std::ops::Range<usize>> {
+        self.span.clone()
+    }
+
+    /// The references that refer back to themselves, starting and ending with the same one
+    ///
+    /// References to another document are prefixed with its name, like `common:ports.http`.
+    pub fn cycle(&self) -> Option<&[String]> {
+        match &self.kind {
+            RefErrorKind::Cycle(cycle) => Some(cycle),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RefError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            RefErrorKind::Invalid => write!(
+                f,
+                "`{}` refers to `{}`, which is not a dotted key",
+                self.key, self.reference
+            ),
+            RefErrorKind::NotFound => write!(
+                f,
+                "`{}` refers to `{}`, which does not exist",
+                self.key, self.reference
+            ),
+            RefErrorKind::UnknownDocument(document) => {
+                write!(f, "`{}` refers to unknown document `{document}`", self.key)
+            }
+            RefErrorKind::Cycle(cycle) => write!(f, "reference cycle: {}", cycle.join(" -> ")),
+        }
+    }
+}
+
+impl std::error::Error for RefError {}
@@ -0,0 +1,59 @@
+use crate::{DocumentMut, Item, TableLike};
+
+impl DocumentMut {
+    /// Remove the item at `path`, returning it
+    ///
+    /// `path` may go through tables and inline tables.  Returns `None`, leaving the document
+    /// unchanged, if there is nothing at `path`.
+    ///
+    /// With `remove_empty_parents`, each table the removal left empty is removed as well, working
+    /// up from the removed item, so no `[a.b]` header is left with nothing under it.  Comments on
+    /// the removed headers go with them.  Inline tables are values and are kept, even when
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "
+    /// [package]
+    /// name = 'app'
+    ///
+    /// [package.metadata.docs]
+    /// all-features = true
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let removed = doc
+    ///     .remove_path(&["package", "metadata", "docs", "all-features"], true)
+    ///     .unwrap();
+    /// assert_eq!(removed.as_bool(), Some(true));
+    /// assert_eq!(doc.to_string(), "
+    /// [package]
+    /// name = 'app'
+    /// ");
+    /// # }
+    /// # }
+    /// ```
+    pub fn remove_path(&mut self, path: &[&str], remove_empty_parents: bool) -> Option<Item> {
+        remove(self.as_table_mut(), path, remove_empty_parents)
+    }
+}
+
+fn remove(table: &mut dyn TableLike, path: &[&str], remove_empty_parents: bool) -> Option<Item> {
+    let (key, rest) = path.split_first()?;
+    if rest.is_empty() {
+        return table.remove(key);
+    }
+
+    let child = table.get_mut(key)?;
+    let removed = remove(child.as_table_like_mut()?, rest, remove_empty_parents)?;
+    if remove_empty_parents {
+        if let Item::Table(child) = child {
+            if child.is_empty() {
+                table.remove(key);
+            }
+        }
+    }
+    Some(removed)
+}
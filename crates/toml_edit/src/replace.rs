@@ -0,0 +1,167 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{Array, DocumentMut, InlineTable, Item, Table, Value};
+
+/// A step in the path to a value, like from [`DocumentMut::replace_values`] or a
+/// deserialization error
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PathSegment {
+    /// A key within a table or inline table
+    Key(String),
+    /// An index within an array or array of tables
+    Index(usize),
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Key(key) => key.fmt(f),
+            Self::Index(index) => index.fmt(f),
+        }
+    }
+}
+
+/// A value changed by [`DocumentMut::replace_values`]
+#[derive(Clone, Debug)]
+pub struct ValueChange {
+    path: Vec<PathSegment>,
+    old: Value,
+    new: Value,
+}
+
+impl ValueChange {
+    /// Where the value is within the document
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The value before it was replaced
+    pub fn old_value(&self) -> &Value {
+        &self.old
+    }
+
+    /// The value it was replaced with
+    pub fn new_value(&self) -> &Value {
+        &self.new
+    }
+}
+
+impl DocumentMut {
+    /// Replace values throughout the document
+    ///
+    /// `replace` is called with the path to each value, including arrays and inline tables
+    /// before their contents, and returns the value to replace it with, if any.  The contents of
+    /// a replaced value are not visited.  The replacement keeps the [`Decor`][crate::Decor] of
+    /// the value it replaces.
+    ///
+    /// Returns the changes in the order they were made.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = r#"
+    /// [registries.internal]
+    /// index = "https://old.example.com/index"  # moved in 2024
+    ///
+    /// [dependencies]
+    /// foo = { version = "1", registry-index = "https://old.example.com/index" }
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let changes = doc.replace_values(|_, value| {
+    ///     let url = value.as_str()?;
+    ///     let url = url.strip_prefix("https://old.example.com/")?;
+    ///     Some(format!("https://new.example.com/{url}").into())
+    /// });
+    /// assert_eq!(changes.len(), 2);
+    /// assert_eq!(doc.to_string(), r#"
+    /// [registries.internal]
+    /// index = "https://new.example.com/index"  # moved in 2024
+    ///
+    /// [dependencies]
+    /// foo = { version = "1", registry-index = "https://new.example.com/index" }
+    /// "#);
+    /// # }
+    /// # }
+    /// ```
+    pub fn replace_values<F>(&mut self, mut replace: F) -> Vec<ValueChange>
+    where
+        F: FnMut(&[PathSegment], &Value) -> Option<Value>,
+    {
+        let mut replacer = Replacer {
+            replace: &mut replace,
+            path: Vec::new(),
+            changes: Vec::new(),
+        };
+        replacer.table(self.as_table_mut());
+        replacer.changes
+    }
+}
+
+struct Replacer<'r> {
+    replace: &'r mut dyn FnMut(&[PathSegment], &Value) -> Option<Value>,
+    path: Vec<PathSegment>,
+    changes: Vec<ValueChange>,
+}
+
+impl Replacer<'_> {
+    fn table(&mut self, table: &mut Table) {
+        for (key, item) in table.iter_mut() {
+            self.path.push(PathSegment::Key(key.get().to_owned()));
+            self.item(item);
+            self.path.pop();
+        }
+    }
+
+    fn item(&mut self, item: &mut Item) {
+        match item {
+            Item::None => {}
+            Item::Value(value) => self.value(value),
+            Item::Table(table) => self.table(table),
+            Item::ArrayOfTables(array) => {
+                for (i, table) in array.iter_mut().enumerate() {
+                    self.path.push(PathSegment::Index(i));
+                    self.table(table);
+                    self.path.pop();
+                }
+            }
+        }
+    }
+
+    fn value(&mut self, value: &mut Value) {
+        if let Some(mut new) = (self.replace)(&self.path, value) {
+            *new.decor_mut() = value.decor().clone();
+            let old = std::mem::replace(value, new);
+            self.changes.push(ValueChange {
+                path: self.path.clone(),
+                old,
+                new: value.clone(),
+            });
+            return;
+        }
+
+        match value {
+            Value::Array(array) => self.array(array),
+            Value::InlineTable(table) => self.inline_table(table),
+            _ => {}
+        }
+    }
+
+    fn array(&mut self, array: &mut Array) {
+        for (i, value) in array.iter_mut().enumerate() {
+            self.path.push(PathSegment::Index(i));
+            self.value(value);
+            self.path.pop();
+        }
+    }
+
+    fn inline_table(&mut self, table: &mut InlineTable) {
+        for (key, value) in table.iter_mut() {
+            self.path.push(PathSegment::Key(key.get().to_owned()));
+            self.value(value);
+            self.path.pop();
+        }
+    }
+}
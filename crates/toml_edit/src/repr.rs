@@ -1,5 +1,8 @@
 use std::borrow::Cow;
 
+#[cfg(feature = "display")]
+use toml_write::ToTomlValue as _;
+
 use crate::RawString;
 
 /// A scalar TOML [`Value`][crate::Value]'s logical value and its representation in a `&str`
@@ -29,7 +32,11 @@ where
         self.repr = Some(repr);
     }
 
-    /// The wrapped value
+    /// The wrapped value, decoded from its [representation][Self::raw]
+    ///
+    /// This was decoded when the value was parsed or last given a representation with
+    /// [`Formatted::set_repr`], and is kept with it from then on.  So calling this is cheap, and
+    /// there is no separate accessor for the decoded value.
     pub fn value(&self) -> &T {
         &self.value
     }
@@ -44,6 +51,45 @@ where
         self.repr.as_ref()
     }
 
+    /// The value as written, like `0x10` for `16`
+    ///
+    /// Returns `None` if the value will be written with the
+    /// [default representation][Self::default_repr] or the representation is still a span into a
+    /// [`Document`][crate::Document]'s source.
+    pub fn raw(&self) -> Option<&str> {
+        self.repr.as_ref().and_then(|r| r.as_raw().as_str())
+    }
+
+    /// Change how the value is written, decoding `raw` to update the [value][Self::value]
+    ///
+    /// The [`Decor`] is unchanged.  Errors if `raw` is not a TOML value of the same type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut number = toml_edit::Formatted::new(16);
+    /// number.set_repr("0x20").unwrap();
+    /// assert_eq!(number.raw(), Some("0x20"));
+    /// assert_eq!(*number.value(), 32);
+    ///
+    /// assert!(number.set_repr("'0x20'").is_err());
+    /// assert_eq!(*number.value(), 32);
+    /// ```
+    #[cfg(feature = "parse")]
+    pub fn set_repr(&mut self, raw: &str) -> Result<(), crate::TomlError> {
+        let value = raw.parse::<crate::Value>()?;
+        let type_name = value.type_name();
+        let Some(formatted) = T::from_value(value) else {
+            return Err(crate::TomlError::custom(
+                format!("unexpected {type_name}"),
+                Some(0..raw.len()),
+            ));
+        };
+        self.value = formatted.value;
+        self.repr = formatted.repr;
+        Ok(())
+    }
+
     /// Returns the default raw representation.
     #[cfg(feature = "display")]
     pub fn default_repr(&self) -> Repr {
@@ -68,10 +114,10 @@ where
         self.repr.as_ref().and_then(|r| r.span())
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
-        self.decor.despan(input);
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
+        self.decor.despan(input, spans);
         if let Some(repr) = &mut self.repr {
-            repr.despan(input);
+            repr.despan(input, spans);
         }
     }
 
@@ -91,6 +137,364 @@ where
     }
 }
 
+impl Formatted<String> {
+    /// How the string was quoted, if it has a [`Repr`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{DocumentMut, StringKind, Value};
+    ///
+    /// let doc = "a = 'literal'\nb = \"\"\"\nmulti-line\"\"\"\n".parse::<DocumentMut>().unwrap();
+    /// let Some(Value::String(a)) = doc["a"].as_value() else { unreachable!() };
+    /// assert_eq!(a.string_kind(), Some(StringKind::Literal));
+    /// let Some(Value::String(b)) = doc["b"].as_value() else { unreachable!() };
+    /// assert_eq!(b.string_kind(), Some(StringKind::MlBasic));
+    /// # }
+    /// ```
+    pub fn string_kind(&self) -> Option<StringKind> {
+        self.raw().and_then(StringKind::from_raw)
+    }
+}
+
+/// Explicit string encodings
+///
+/// [`Formatted::new`] picks the encoding based on the content, which may not be the one you want.
+#[cfg(feature = "display")]
+impl Formatted<String> {
+    /// Encode as a `"basic string"`, escaping as needed
+    pub fn basic(value: impl Into<String>) -> Self {
+        let value = value.into();
+        let repr = toml_write::TomlStringBuilder::new(&value)
+            .as_basic()
+            .to_toml_value();
+        let mut formatted = Self::new(value);
+        formatted.set_repr_unchecked(Repr::new_unchecked(repr));
+        formatted
+    }
+
+    /// Encode as a `"""multi-line basic string"""`, escaping as needed
+    pub fn ml_basic(value: impl Into<String>) -> Self {
+        let value = value.into();
+        let repr = toml_write::TomlStringBuilder::new(&value)
+            .as_ml_basic()
+            .to_toml_value();
+        let mut formatted = Self::new(value);
+        formatted.set_repr_unchecked(Repr::new_unchecked(repr));
+        formatted
+    }
+
+    /// Encode as a `'literal string'`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use toml_edit::{EncodeError, Formatted};
+    /// let s = Formatted::<String>::literal(r"C:\Users").unwrap();
+    /// assert_eq!(s.to_string(), r"'C:\Users'");
+    ///
+    /// let err = Formatted::<String>::literal("it's").unwrap_err();
+    /// assert_eq!(err, EncodeError::Delimiter("'"));
+    /// ```
+    pub fn literal(value: impl Into<String>) -> Result<Self, crate::EncodeError> {
+        let value = value.into();
+        validate_literal(&value, false)?;
+        let repr = toml_write::TomlStringBuilder::new(&value)
+            .as_literal()
+            .expect("validated")
+            .to_toml_value();
+        let mut formatted = Self::new(value);
+        formatted.set_repr_unchecked(Repr::new_unchecked(repr));
+        Ok(formatted)
+    }
+
+    /// Encode as a `'''multi-line literal string'''`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use toml_edit::{EncodeError, Formatted};
+    /// let s = Formatted::<String>::ml_literal("a\nb").unwrap();
+    /// assert_eq!(s.to_string(), "'''\na\nb'''");
+    ///
+    /// let err = Formatted::<String>::ml_literal("'''").unwrap_err();
+    /// assert_eq!(err, EncodeError::Delimiter("'''"));
+    ///
+    /// let s = Formatted::<String>::ml_literal("a\r\nb").unwrap();
+    /// assert_eq!(s.to_string(), "'''\na\r\nb'''");
+    /// let err = Formatted::<String>::ml_literal("a\rb").unwrap_err();
+    /// assert_eq!(err, EncodeError::ControlCharacter('\r'));
+    /// ```
+    pub fn ml_literal(value: impl Into<String>) -> Result<Self, crate::EncodeError> {
+        let value = value.into();
+        validate_literal(&value, true)?;
+        let repr = if value.contains('\r') {
+            // `toml_write` treats any `\r` as needing an escape, but here each is part of a `\r\n`
+            format!("'''\n{value}'''")
+        } else {
+            toml_write::TomlStringBuilder::new(&value)
+                .as_ml_literal()
+                .expect("validated")
+                .to_toml_value()
+        };
+        let mut formatted = Self::new(value);
+        formatted.set_repr_unchecked(Repr::new_unchecked(repr));
+        Ok(formatted)
+    }
+}
+
+#[cfg(feature = "display")]
+fn validate_literal(value: &str, multiline: bool) -> Result<(), crate::EncodeError> {
+    // ```bnf
+    // literal-char = %x09 / %x20-26 / %x28-7E / non-ascii
+    // mll-content = literal-char / newline
+    // newline = %x0A / %x0D.0A
+    // ```
+    let mut seq_single_quotes = 0;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            seq_single_quotes += 1;
+            if !multiline {
+                return Err(crate::EncodeError::Delimiter("'"));
+            } else if 2 < seq_single_quotes {
+                return Err(crate::EncodeError::Delimiter("'''"));
+            }
+        } else {
+            seq_single_quotes = 0;
+        }
+        match c {
+            '\t' => {}
+            '\n' if multiline => {}
+            '\r' if multiline && chars.peek() == Some(&'\n') => {}
+            '\n' => return Err(crate::EncodeError::Newline),
+            c if c.is_ascii_control() => return Err(crate::EncodeError::ControlCharacter(c)),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Datetime components
+///
+/// Each component is `None` when the datetime does not have it, like the time of a local date.
+impl Formatted<toml_datetime::Datetime> {
+    /// Year: four digits
+    pub fn year(&self) -> Option<u16> {
+        self.value.date.map(|d| d.year)
+    }
+
+    /// Month: 1 to 12
+    pub fn month(&self) -> Option<u8> {
+        self.value.date.map(|d| d.month)
+    }
+
+    /// Day: 1 to {28, 29, 30, 31} (based on month/year)
+    pub fn day(&self) -> Option<u8> {
+        self.value.date.map(|d| d.day)
+    }
+
+    /// Hour: 0 to 23
+    pub fn hour(&self) -> Option<u8> {
+        self.value.time.map(|t| t.hour)
+    }
+
+    /// Minute: 0 to 59
+    pub fn minute(&self) -> Option<u8> {
+        self.value.time.map(|t| t.minute)
+    }
+
+    /// Second: 0 to {58, 59, 60} (based on leap second rules)
+    pub fn second(&self) -> Option<u8> {
+        self.value.time.map(|t| t.second)
+    }
+
+    /// Nanosecond: 0 to `999_999_999`
+    pub fn nanosecond(&self) -> Option<u32> {
+        self.value.time.map(|t| t.nanosecond)
+    }
+
+    /// Offset from UTC in minutes, with `Z` being `0`
+    pub fn offset_minutes(&self) -> Option<i16> {
+        self.value.offset.map(|o| match o {
+            toml_datetime::Offset::Z => 0,
+            toml_datetime::Offset::Custom { minutes } => minutes,
+        })
+    }
+}
+
+/// Datetime component mutators
+///
+/// Only the component is rewritten, keeping the rest of the [`Repr`], like a lowercase `t` or
+/// the precision of the fractional seconds.  These error if the datetime does not have the
+/// component or if the result is not a valid datetime.
+///
+/// # Examples
+///
+/// ```rust
+/// # use toml_edit::{DocumentMut, Value};
+/// let mut doc = "ts = 1979-05-27t07:32:00.000z".parse::<DocumentMut>().unwrap();
+/// let Some(Value::Datetime(ts)) = doc["ts"].as_value_mut() else { unreachable!() };
+/// ts.set_day(28).unwrap();
+/// ts.set_offset_minutes(-420).unwrap();
+/// assert_eq!(ts.raw(), Some("1979-05-28t07:32:00.000-07:00"));
+///
+/// assert!(ts.set_month(13).is_err());
+/// assert!(ts.set_day(31).is_ok());
+/// assert!(ts.set_month(6).is_err());
+/// assert_eq!(ts.month(), Some(5));
+/// ```
+#[cfg(feature = "parse")]
+impl Formatted<toml_datetime::Datetime> {
+    /// Change the year, keeping the formatting
+    pub fn set_year(&mut self, year: u16) -> Result<(), crate::TomlError> {
+        self.set_date_component(0..4, format!("{year:04}"))
+    }
+
+    /// Change the month, keeping the formatting
+    pub fn set_month(&mut self, month: u8) -> Result<(), crate::TomlError> {
+        self.set_date_component(5..7, format!("{month:02}"))
+    }
+
+    /// Change the day, keeping the formatting
+    pub fn set_day(&mut self, day: u8) -> Result<(), crate::TomlError> {
+        self.set_date_component(8..10, format!("{day:02}"))
+    }
+
+    /// Change the hour, keeping the formatting
+    pub fn set_hour(&mut self, hour: u8) -> Result<(), crate::TomlError> {
+        self.set_time_component(0..2, format!("{hour:02}"))
+    }
+
+    /// Change the minute, keeping the formatting
+    pub fn set_minute(&mut self, minute: u8) -> Result<(), crate::TomlError> {
+        self.set_time_component(3..5, format!("{minute:02}"))
+    }
+
+    /// Change the second, keeping the formatting
+    pub fn set_second(&mut self, second: u8) -> Result<(), crate::TomlError> {
+        self.set_time_component(6..8, format!("{second:02}"))
+    }
+
+    /// Change the nanosecond, keeping the written precision when it is enough
+    pub fn set_nanosecond(&mut self, nanosecond: u32) -> Result<(), crate::TomlError> {
+        if 999_999_999 < nanosecond {
+            return Err(crate::TomlError::custom(
+                format!("nanosecond {nanosecond} is out of range"),
+                None,
+            ));
+        }
+        let (raw, time_start) = self.component_raw(self.value.time.is_some(), "time")?;
+        let start = time_start + 8;
+        let end = secfrac_end(&raw, start);
+        let precision = (end - start).saturating_sub(1);
+        let digits = format!("{nanosecond:09}");
+        let digits = digits.trim_end_matches('0');
+        let mut secfrac = String::new();
+        if !digits.is_empty() || precision != 0 {
+            secfrac.push('.');
+            secfrac.push_str(digits);
+            for _ in digits.len()..precision {
+                secfrac.push('0');
+            }
+        }
+        self.splice_repr(raw, start..end, &secfrac)
+    }
+
+    /// Change the offset from UTC, keeping the formatting
+    ///
+    /// `0` is written as `Z`, unless the offset already was `0`.
+    pub fn set_offset_minutes(&mut self, minutes: i16) -> Result<(), crate::TomlError> {
+        let (raw, time_start) = self.component_raw(self.value.offset.is_some(), "offset")?;
+        let start = secfrac_end(&raw, time_start + 8);
+        if minutes == 0 && self.offset_minutes() == Some(0) {
+            return Ok(());
+        }
+        if !(-1440 < minutes && minutes < 1440) {
+            return Err(crate::TomlError::custom(
+                format!("offset {minutes} minutes is out of range"),
+                None,
+            ));
+        }
+        let offset = if minutes == 0 {
+            toml_datetime::Offset::Z
+        } else {
+            toml_datetime::Offset::Custom { minutes }
+        };
+        let end = raw.len();
+        self.splice_repr(raw, start..end, &offset.to_string())
+    }
+
+    fn set_date_component(
+        &mut self,
+        range: std::ops::Range<usize>,
+        component: String,
+    ) -> Result<(), crate::TomlError> {
+        let (raw, _) = self.component_raw(self.value.date.is_some(), "date")?;
+        self.splice_repr(raw, range, &component)
+    }
+
+    fn set_time_component(
+        &mut self,
+        range: std::ops::Range<usize>,
+        component: String,
+    ) -> Result<(), crate::TomlError> {
+        let (raw, time_start) = self.component_raw(self.value.time.is_some(), "time")?;
+        self.splice_repr(
+            raw,
+            time_start + range.start..time_start + range.end,
+            &component,
+        )
+    }
+
+    /// The current representation and where its time starts
+    fn component_raw(
+        &self,
+        present: bool,
+        component: &str,
+    ) -> Result<(String, usize), crate::TomlError> {
+        if !present {
+            return Err(crate::TomlError::custom(
+                format!("datetime has no {component}"),
+                None,
+            ));
+        }
+        let raw = self
+            .raw()
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| self.value.to_string());
+        let time_start = if self.value.date.is_some() { 11 } else { 0 };
+        Ok((raw, time_start))
+    }
+
+    fn splice_repr(
+        &mut self,
+        mut raw: String,
+        range: std::ops::Range<usize>,
+        component: &str,
+    ) -> Result<(), crate::TomlError> {
+        raw.replace_range(range, component);
+        self.set_repr(&raw)
+    }
+}
+
+/// End of the fractional seconds that may start at `start`
+#[cfg(feature = "parse")]
+fn secfrac_end(raw: &str, start: usize) -> usize {
+    let rest = &raw[start..];
+    match rest.strip_prefix('.') {
+        Some(digits) => {
+            let len = digits.len()
+                - digits
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .len();
+            start + 1 + len
+        }
+        None => start,
+    }
+}
+
 impl<T> std::fmt::Debug for Formatted<T>
 where
     T: std::fmt::Debug,
@@ -122,17 +526,116 @@ pub trait ValueRepr: crate::private::Sealed {
     /// The TOML representation of the value
     #[cfg(feature = "display")]
     fn to_repr(&self) -> Repr;
+
+    #[doc(hidden)]
+    fn from_value(value: crate::Value) -> Option<Formatted<Self>>
+    where
+        Self: Sized;
+}
+
+impl ValueRepr for String {
+    #[cfg(feature = "display")]
+    fn to_repr(&self) -> Repr {
+        let output = toml_write::TomlStringBuilder::new(self.as_str())
+            .as_default()
+            .to_toml_value();
+        Repr::new_unchecked(output)
+    }
+
+    fn from_value(value: crate::Value) -> Option<Formatted<Self>> {
+        match value {
+            crate::Value::String(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
+impl ValueRepr for i64 {
+    #[cfg(feature = "display")]
+    fn to_repr(&self) -> Repr {
+        let repr = self.to_toml_value();
+        Repr::new_unchecked(repr)
+    }
+
+    fn from_value(value: crate::Value) -> Option<Formatted<Self>> {
+        match value {
+            crate::Value::Integer(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
+impl ValueRepr for f64 {
+    #[cfg(feature = "display")]
+    fn to_repr(&self) -> Repr {
+        let repr = self.to_toml_value();
+        Repr::new_unchecked(repr)
+    }
+
+    fn from_value(value: crate::Value) -> Option<Formatted<Self>> {
+        match value {
+            crate::Value::Float(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
+impl ValueRepr for bool {
+    #[cfg(feature = "display")]
+    fn to_repr(&self) -> Repr {
+        let repr = self.to_toml_value();
+        Repr::new_unchecked(repr)
+    }
+
+    fn from_value(value: crate::Value) -> Option<Formatted<Self>> {
+        match value {
+            crate::Value::Boolean(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
+impl ValueRepr for toml_datetime::Datetime {
+    #[cfg(feature = "display")]
+    fn to_repr(&self) -> Repr {
+        Repr::new_unchecked(self.to_string())
+    }
+
+    fn from_value(value: crate::Value) -> Option<Formatted<Self>> {
+        match value {
+            crate::Value::Datetime(f) => Some(f),
+            _ => None,
+        }
+    }
 }
 
-#[cfg(not(feature = "display"))]
-mod inner {
-    use super::ValueRepr;
+/// The syntax used to quote a string or key
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StringKind {
+    /// `"basic string"`
+    Basic,
+    /// `'literal string'`
+    Literal,
+    /// `"""multi-line basic string"""`
+    MlBasic,
+    /// `'''multi-line literal string'''`
+    MlLiteral,
+}
 
-    impl ValueRepr for String {}
-    impl ValueRepr for i64 {}
-    impl ValueRepr for f64 {}
-    impl ValueRepr for bool {}
-    impl ValueRepr for toml_datetime::Datetime {}
+impl StringKind {
+    pub(crate) fn from_raw(raw: &str) -> Option<Self> {
+        if raw.starts_with(r#"""""#) {
+            Some(Self::MlBasic)
+        } else if raw.starts_with("'''") {
+            Some(Self::MlLiteral)
+        } else if raw.starts_with('"') {
+            Some(Self::Basic)
+        } else if raw.starts_with('\'') {
+            Some(Self::Literal)
+        } else {
+            None
+        }
+    }
 }
 
 /// A TOML [`Value`][crate::Value] encoded as a `&str`
@@ -160,8 +663,8 @@ impl Repr {
         self.raw_value.span()
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
-        self.raw_value.despan(input);
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
+        self.raw_value.despan(input, spans);
     }
 
     #[cfg(feature = "display")]
@@ -188,7 +691,22 @@ pub struct Decor {
 
 impl Decor {
     /// Creates a new decor from the given prefix and suffix.
-    pub fn new(prefix: impl Into<RawString>, suffix: impl Into<RawString>) -> Self {
+    ///
+    /// See [`Decor::set_prefix`] for what is allowed.
+    pub fn new(
+        prefix: impl Into<RawString>,
+        suffix: impl Into<RawString>,
+    ) -> Result<Self, crate::StructureError> {
+        let mut decor = Self::default();
+        decor.set_prefix(prefix)?;
+        decor.set_suffix(suffix)?;
+        Ok(decor)
+    }
+
+    pub(crate) fn new_unchecked(
+        prefix: impl Into<RawString>,
+        suffix: impl Into<RawString>,
+    ) -> Self {
         Self {
             prefix: Some(prefix.into()),
             suffix: Some(suffix.into()),
@@ -216,15 +734,91 @@ impl Decor {
         if let Some(prefix) = self.prefix() {
             prefix.encode_with_default(buf, input, default)
         } else {
-            write!(buf, "{default}")
+            buf.write_str(default)
         }
     }
 
     /// Set the prefix.
-    pub fn set_prefix(&mut self, prefix: impl Into<RawString>) {
+    ///
+    /// Only whitespace, newlines, and comments are allowed.  Where these may appear depends on
+    /// what the decor is attached to, like newlines not being allowed between a key and its `=`,
+    /// which is checked by [`DocumentMut::from_root_table`][crate::DocumentMut::from_root_table]
+    /// and [`DocumentMut::verify`][crate::DocumentMut::verify].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut decor = toml_edit::Decor::default();
+    /// decor.set_prefix("# comment\n").unwrap();
+    ///
+    /// let err = decor.set_prefix("x = 1\n").unwrap_err();
+    /// assert_eq!(err.to_string(), "only whitespace and comments are allowed in decor");
+    /// ```
+    pub fn set_prefix(
+        &mut self,
+        prefix: impl Into<RawString>,
+    ) -> Result<(), crate::StructureError> {
+        let prefix = prefix.into();
+        crate::structure::check_whitespace(&prefix, crate::structure::Space::Any)?;
+        self.prefix = Some(prefix);
+        Ok(())
+    }
+
+    pub(crate) fn set_prefix_unchecked(&mut self, prefix: impl Into<RawString>) {
         self.prefix = Some(prefix.into());
     }
 
+    pub(crate) fn clear_prefix(&mut self) {
+        self.prefix = None;
+    }
+
+    /// The number of blank lines at the start of the prefix
+    ///
+    /// These separate what the decor is attached to, like a key or a table header, from the
+    /// entry before it.  Blank lines after a comment in the prefix are not counted.
+    ///
+    /// Returns `None` for the default prefix.
+    pub fn blank_lines(&self) -> Option<usize> {
+        let prefix = self.prefix()?.as_str()?;
+        Some(split_blank_lines(prefix).0)
+    }
+
+    /// Set the number of blank lines at the start of the prefix, keeping comments and indentation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{DocumentMut, Item};
+    ///
+    /// let mut doc = "a = 1\n\n\nb = 2\n[t]\nc = 3\n\n# about d\nd = 4\n"
+    ///     .parse::<DocumentMut>()
+    ///     .unwrap();
+    /// assert_eq!(doc.key("b").unwrap().leaf_decor().blank_lines(), Some(2));
+    ///
+    /// // One blank line between sections, none within
+    /// for (mut key, item) in doc.iter_mut() {
+    ///     if let Item::Table(table) = item {
+    ///         table.decor_mut().set_blank_lines(1);
+    ///         for (mut key, _) in table.iter_mut() {
+    ///             key.leaf_decor_mut().set_blank_lines(0);
+    ///         }
+    ///     } else {
+    ///         key.leaf_decor_mut().set_blank_lines(0);
+    ///     }
+    /// }
+    /// assert_eq!(doc.to_string(), "a = 1\nb = 2\n\n[t]\nc = 3\n# about d\nd = 4\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn set_blank_lines(&mut self, count: usize) {
+        let prefix = self.prefix().and_then(|p| p.as_str()).unwrap_or_default();
+        let (_, rest) = split_blank_lines(prefix);
+        let prefix = format!("{}{rest}", "\n".repeat(count));
+        self.set_prefix_unchecked(prefix);
+    }
+
     /// Get the suffix.
     pub fn suffix(&self) -> Option<&RawString> {
         self.suffix.as_ref()
@@ -240,23 +834,49 @@ impl Decor {
         if let Some(suffix) = self.suffix() {
             suffix.encode_with_default(buf, input, default)
         } else {
-            write!(buf, "{default}")
+            buf.write_str(default)
         }
     }
 
     /// Set the suffix.
-    pub fn set_suffix(&mut self, suffix: impl Into<RawString>) {
+    ///
+    /// See [`Decor::set_prefix`] for what is allowed.
+    pub fn set_suffix(
+        &mut self,
+        suffix: impl Into<RawString>,
+    ) -> Result<(), crate::StructureError> {
+        let suffix = suffix.into();
+        crate::structure::check_whitespace(&suffix, crate::structure::Space::Any)?;
+        self.suffix = Some(suffix);
+        Ok(())
+    }
+
+    pub(crate) fn set_suffix_unchecked(&mut self, suffix: impl Into<RawString>) {
         self.suffix = Some(suffix.into());
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
         if let Some(prefix) = &mut self.prefix {
-            prefix.despan(input);
+            prefix.despan(input, spans);
         }
         if let Some(suffix) = &mut self.suffix {
-            suffix.despan(input);
+            suffix.despan(input, spans);
+        }
+    }
+}
+
+/// The number of blank lines at the start of `prefix` and what follows them
+fn split_blank_lines(prefix: &str) -> (usize, &str) {
+    let mut count = 0;
+    let mut rest = prefix;
+    while let Some((line, next)) = rest.split_once('\n') {
+        if !line.trim_matches([' ', '\t', '\r']).is_empty() {
+            break;
         }
+        count += 1;
+        rest = next;
     }
+    (count, rest)
 }
 
 impl std::fmt::Debug for Decor {
@@ -0,0 +1,648 @@
+//! Describe the expected shape of a document
+//!
+//! A [`TableSchema`] can generate a commented template with [`TableSchema::to_template`],
+//! allowing projects to provide an example config file that stays in sync with what they
+//! accept.  Loaded documents can then be checked against it with [`TableSchema::validate`], or
+//! have their defaults filled in with [`TableSchema::apply`].
+
+use indexmap::IndexMap;
+
+use crate::{
+    Array, ArrayOfTables, Datetime, DocumentMut, InlineTable, Item, Key, PathSegment, Table,
+    TableLike, Value,
+};
+
+/// The expected type, default, and documentation for a value
+#[derive(Clone, Debug)]
+pub struct Schema {
+    kind: SchemaKind,
+    description: Option<String>,
+    default: Option<Value>,
+}
+
+impl Schema {
+    /// Expect a string
+    pub fn string() -> Self {
+        Self::new(SchemaKind::String)
+    }
+
+    /// Expect an integer
+    pub fn integer() -> Self {
+        Self::new(SchemaKind::Integer)
+    }
+
+    /// Expect a float
+    pub fn float() -> Self {
+        Self::new(SchemaKind::Float)
+    }
+
+    /// Expect a boolean
+    pub fn boolean() -> Self {
+        Self::new(SchemaKind::Boolean)
+    }
+
+    /// Expect a datetime
+    pub fn datetime() -> Self {
+        Self::new(SchemaKind::Datetime)
+    }
+
+    /// Expect an array with every element matching `element`
+    pub fn array(element: impl Into<Schema>) -> Self {
+        Self::new(SchemaKind::Array(Box::new(element.into())))
+    }
+
+    /// Expect a table, see [`TableSchema::with_field`]
+    pub fn table() -> TableSchema {
+        TableSchema {
+            fields: IndexMap::new(),
+            description: None,
+            default: None,
+        }
+    }
+
+    /// Accept any value
+    pub fn any() -> Self {
+        Self::new(SchemaKind::Any)
+    }
+
+    fn new(kind: SchemaKind) -> Self {
+        Self {
+            kind,
+            description: None,
+            default: None,
+        }
+    }
+
+    /// Document what the value is for
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// The value to use when none is specified
+    pub fn with_default(mut self, default: impl Into<Value>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+
+    /// The expected type
+    pub fn kind(&self) -> &SchemaKind {
+        &self.kind
+    }
+
+    /// What the value is for
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// The value to use when none is specified
+    pub fn default(&self) -> Option<&Value> {
+        self.default.as_ref()
+    }
+
+    /// The expected keys, if a [table][Schema::table]
+    pub fn fields(&self) -> Option<&IndexMap<String, Schema>> {
+        match &self.kind {
+            SchemaKind::Table(fields) => Some(fields),
+            _ => None,
+        }
+    }
+
+    fn placeholder(&self) -> Value {
+        if let Some(default) = &self.default {
+            let mut default = default.clone();
+            default.decor_mut().clear();
+            return default;
+        }
+        match &self.kind {
+            SchemaKind::String | SchemaKind::Any => Value::from(""),
+            SchemaKind::Integer => Value::from(0),
+            SchemaKind::Float => Value::from(0.0),
+            SchemaKind::Boolean => Value::from(false),
+            SchemaKind::Datetime => {
+                let datetime = "1979-05-27T07:32:00Z"
+                    .parse::<Datetime>()
+                    .expect("valid datetime");
+                Value::from(datetime)
+            }
+            SchemaKind::Array(element) => {
+                let mut array = Array::new();
+                array.push(element.placeholder());
+                Value::Array(array)
+            }
+            SchemaKind::Table(fields) => {
+                let mut table = InlineTable::new();
+                for (key, field) in fields {
+                    table.insert(key, field.placeholder());
+                }
+                Value::InlineTable(table)
+            }
+        }
+    }
+}
+
+/// The expected keys of a table, see [`Schema::table`]
+///
+/// Only a table can describe a whole document, so templates are generated and documents checked
+/// from this rather than any [`Schema`].
+#[derive(Clone, Debug)]
+pub struct TableSchema {
+    fields: IndexMap<String, Schema>,
+    description: Option<String>,
+    default: Option<Value>,
+}
+
+impl TableSchema {
+    /// Document what the table is for
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// The value to use when none is specified
+    pub fn with_default(mut self, default: impl Into<Value>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+
+    /// Add an expected key
+    pub fn with_field(mut self, key: impl Into<String>, field: impl Into<Schema>) -> Self {
+        self.fields.insert(key.into(), field.into());
+        self
+    }
+
+    /// What the table is for
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// The value to use when none is specified
+    pub fn default(&self) -> Option<&Value> {
+        self.default.as_ref()
+    }
+
+    /// The expected keys
+    pub fn fields(&self) -> &IndexMap<String, Schema> {
+        &self.fields
+    }
+
+    /// Generate an example document with every field filled in
+    ///
+    /// Fields use their [default][Schema::with_default] or a placeholder for their type, with
+    /// their [description][Schema::with_description] as a comment.
+    /// Tables within a table are written as `[table]`s and arrays of tables as `[[array]]`s
+    /// with one element.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::schema::Schema;
+    ///
+    /// let schema = Schema::table()
+    ///     .with_field("name", Schema::string().with_description("Name of the package"))
+    ///     .with_field(
+    ///         "server",
+    ///         Schema::table()
+    ///             .with_description("Where to listen")
+    ///             .with_field("port", Schema::integer().with_default(8080)),
+    ///     );
+    /// assert_eq!(schema.to_template().to_string(), r#"# Name of the package
+    /// name = ""
+    ///
+    /// ## Where to listen
+    /// [server]
+    /// port = 8080
+    /// "#);
+    /// # }
+    /// ```
+    pub fn to_template(&self) -> DocumentMut {
+        let mut root = Table::new();
+        let mut position = 0;
+        fill_table(&mut root, &self.fields, &mut position);
+        if let Some(description) = self.description() {
+            root.decor_mut()
+                .set_prefix_unchecked(format!("{}\n", comment(description)));
+        }
+        DocumentMut::from(root)
+    }
+
+    /// Type-check `doc`, finding the defaults for its missing keys
+    ///
+    /// The document is left as-is, with the defaults in the report for the caller to use.  See
+    /// [`TableSchema::apply`] to write them into the document.
+    ///
+    /// An integer is accepted where a float is expected, and reported as
+    /// [coerced][SchemaReport::coerced].  A table that is missing is checked as empty, so the
+    /// defaults of its fields are found.  Keys not in the schema are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::schema::Schema;
+    ///
+    /// let schema = Schema::table()
+    ///     .with_field("name", Schema::string())
+    ///     .with_field("port", Schema::integer().with_default(8080))
+    ///     .with_field("timeout", Schema::float());
+    /// let doc = "name = 1\ntimeout = 30\n".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let report = schema.validate(&doc);
+    /// assert_eq!(report.defaulted()[0].1.as_integer(), Some(8080));
+    /// assert_eq!(report.coerced().len(), 1);
+    /// assert_eq!(
+    ///     report.mismatches()[0].to_string(),
+    ///     "`name` expects string, found integer"
+    /// );
+    /// # }
+    /// ```
+    pub fn validate(&self, doc: &DocumentMut) -> SchemaReport {
+        let mut report = SchemaReport::default();
+        validate_table(doc.as_table(), &self.fields, &mut Vec::new(), &mut report);
+        report
+    }
+
+    /// Type-check `doc`, writing in the defaults for its missing keys
+    ///
+    /// This is [`TableSchema::validate`] followed by inserting each [defaulted][SchemaReport::defaulted]
+    /// key, creating any tables missing on the way, and replacing each
+    /// [coerced][SchemaReport::coerced] integer with a float.  With a `marker`, each inserted key
+    /// outside of an inline table gets it as a comment, so readers can tell the defaults apart
+    /// from what was written.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::schema::Schema;
+    ///
+    /// let schema = Schema::table()
+    ///     .with_field("name", Schema::string())
+    ///     .with_field(
+    ///         "server",
+    ///         Schema::table()
+    ///             .with_field("port", Schema::integer().with_default(8080))
+    ///             .with_field("timeout", Schema::float()),
+    ///     );
+    /// let mut doc = "name = 'app'\n\n[server]\ntimeout = 30\n"
+    ///     .parse::<toml_edit::DocumentMut>()
+    ///     .unwrap();
+    ///
+    /// let report = schema.apply(&mut doc, Some("default"));
+    /// assert!(report.is_valid());
+    /// assert_eq!(doc.to_string(), "name = 'app'
+    ///
+    /// [server]
+    /// timeout = 30.0
+    /// ## default
+    /// port = 8080
+    /// ");
+    /// # }
+    /// # }
+    /// ```
+    pub fn apply(&self, doc: &mut DocumentMut, marker: Option<&str>) -> SchemaReport {
+        let report = self.validate(doc);
+        for (path, default) in &report.defaulted {
+            insert_default(&mut doc.root, path, default.clone(), marker);
+        }
+        for path in &report.coerced {
+            let Some(Item::Value(value)) = lookup(&mut doc.root, path) else {
+                continue;
+            };
+            if let Some(integer) = value.as_integer() {
+                let decor = value.decor().clone();
+                *value = Value::from(integer as f64);
+                *value.decor_mut() = decor;
+            }
+        }
+        report
+    }
+}
+
+impl From<TableSchema> for Schema {
+    fn from(table: TableSchema) -> Self {
+        Self {
+            kind: SchemaKind::Table(table.fields),
+            description: table.description,
+            default: table.default,
+        }
+    }
+}
+
+/// The expected type, see [`Schema::kind`]
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum SchemaKind {
+    /// See [`Schema::string`]
+    String,
+    /// See [`Schema::integer`]
+    Integer,
+    /// See [`Schema::float`]
+    Float,
+    /// See [`Schema::boolean`]
+    Boolean,
+    /// See [`Schema::datetime`]
+    Datetime,
+    /// See [`Schema::array`]
+    Array(Box<Schema>),
+    /// See [`Schema::table`]
+    Table(IndexMap<String, Schema>),
+    /// See [`Schema::any`]
+    Any,
+}
+
+impl SchemaKind {
+    /// Whether `value` has this type
+    ///
+    /// Array elements and table fields are not checked.
+    pub fn matches(&self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (SchemaKind::Any, _)
+                | (SchemaKind::String, Value::String(_))
+                | (SchemaKind::Integer, Value::Integer(_))
+                | (SchemaKind::Float, Value::Float(_))
+                | (SchemaKind::Boolean, Value::Boolean(_))
+                | (SchemaKind::Datetime, Value::Datetime(_))
+                | (SchemaKind::Array(_), Value::Array(_))
+                | (SchemaKind::Table(_), Value::InlineTable(_))
+        )
+    }
+
+    /// The name of the type, like [`Value::type_name`]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SchemaKind::String => "string",
+            SchemaKind::Integer => "integer",
+            SchemaKind::Float => "float",
+            SchemaKind::Boolean => "boolean",
+            SchemaKind::Datetime => "datetime",
+            SchemaKind::Array(_) => "array",
+            SchemaKind::Table(_) => "table",
+            SchemaKind::Any => "any",
+        }
+    }
+}
+
+/// The result of checking a document against a [`TableSchema`], see [`TableSchema::validate`]
+#[derive(Clone, Debug, Default)]
+pub struct SchemaReport {
+    defaulted: Vec<(Vec<PathSegment>, Value)>,
+    coerced: Vec<Vec<PathSegment>>,
+    mismatches: Vec<SchemaMismatch>,
+}
+
+impl SchemaReport {
+    /// Each missing key with a default, and that default, in schema order
+    pub fn defaulted(&self) -> &[(Vec<PathSegment>, Value)] {
+        &self.defaulted
+    }
+
+    /// Each integer accepted where a float is expected
+    pub fn coerced(&self) -> &[Vec<PathSegment>] {
+        &self.coerced
+    }
+
+    /// Each value with the wrong type
+    pub fn mismatches(&self) -> &[SchemaMismatch] {
+        &self.mismatches
+    }
+
+    /// Whether every value has the expected type
+    pub fn is_valid(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// A value with the wrong type for its [`Schema`], see [`SchemaReport::mismatches`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    path: Vec<PathSegment>,
+    expected: &'static str,
+    found: &'static str,
+}
+
+impl SchemaMismatch {
+    /// The location of the value
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The expected type, like [`SchemaKind::type_name`]
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+
+    /// The type that was found, like [`Item::type_name`]
+    pub fn found(&self) -> &'static str {
+        self.found
+    }
+}
+
+impl std::fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        "`".fmt(f)?;
+        for (i, segment) in self.path.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i != 0 => write!(f, ".{key}")?,
+                PathSegment::Key(key) => key.fmt(f)?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        write!(f, "` expects {}, found {}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for SchemaMismatch {}
+
+fn validate_table(
+    table: &dyn TableLike,
+    fields: &IndexMap<String, Schema>,
+    path: &mut Vec<PathSegment>,
+    report: &mut SchemaReport,
+) {
+    for (key, field) in fields {
+        path.push(PathSegment::Key(key.clone()));
+        match (table.get(key), &field.default, &field.kind) {
+            (Some(item), _, _) => validate_item(item, field, path, report),
+            (None, Some(default), _) => report.defaulted.push((path.clone(), default.clone())),
+            (None, None, SchemaKind::Table(children)) => {
+                validate_table(&Table::new(), children, path, report);
+            }
+            (None, None, _) => {}
+        }
+        path.pop();
+    }
+}
+
+fn validate_item(
+    item: &Item,
+    schema: &Schema,
+    path: &mut Vec<PathSegment>,
+    report: &mut SchemaReport,
+) {
+    match (item, &schema.kind) {
+        (Item::None, _) => {}
+        (Item::Value(value), _) => validate_value(value, schema, path, report),
+        (_, SchemaKind::Any) => {}
+        (Item::Table(table), SchemaKind::Table(fields)) => {
+            validate_table(table, fields, path, report);
+        }
+        (Item::ArrayOfTables(array), SchemaKind::Array(element)) => {
+            for (i, table) in array.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                match &element.kind {
+                    SchemaKind::Any => {}
+                    SchemaKind::Table(fields) => validate_table(table, fields, path, report),
+                    kind => mismatch(path, kind, "table", report),
+                }
+                path.pop();
+            }
+        }
+        (item, kind) => mismatch(path, kind, item.type_name(), report),
+    }
+}
+
+fn validate_value(
+    value: &Value,
+    schema: &Schema,
+    path: &mut Vec<PathSegment>,
+    report: &mut SchemaReport,
+) {
+    match (value, &schema.kind) {
+        (Value::InlineTable(table), SchemaKind::Table(fields)) => {
+            validate_table(table, fields, path, report);
+        }
+        (Value::Array(array), SchemaKind::Array(element)) => {
+            for (i, value) in array.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                validate_value(value, element, path, report);
+                path.pop();
+            }
+        }
+        (Value::Integer(_), SchemaKind::Float) => report.coerced.push(path.clone()),
+        (value, kind) if !kind.matches(value) => {
+            mismatch(path, kind, value.type_name(), report);
+        }
+        _ => {}
+    }
+}
+
+fn mismatch(
+    path: &[PathSegment],
+    kind: &SchemaKind,
+    found: &'static str,
+    report: &mut SchemaReport,
+) {
+    report.mismatches.push(SchemaMismatch {
+        path: path.to_vec(),
+        expected: kind.type_name(),
+        found,
+    });
+}
+
+fn insert_default(item: &mut Item, path: &[PathSegment], default: Value, marker: Option<&str>) {
+    match path.split_first() {
+        Some((PathSegment::Index(index), rest)) => {
+            if let Some(element) = item.get_mut(*index) {
+                insert_default(element, rest, default, marker);
+            }
+        }
+        Some((PathSegment::Key(key), rest)) => {
+            let inline = item.is_inline_table();
+            let Some(table) = item.as_table_like_mut() else {
+                return;
+            };
+            if rest.is_empty() {
+                let mut key = Key::new(key.as_str());
+                if let (Some(marker), false) = (marker, inline) {
+                    key.leaf_decor_mut().set_prefix_unchecked(comment(marker));
+                }
+                table.entry_format(&key).or_insert(Item::Value(default));
+            } else {
+                let child = table.entry(key).or_insert_with(|| {
+                    if inline {
+                        Item::Value(Value::InlineTable(InlineTable::new()))
+                    } else {
+                        Item::Table(Table::new())
+                    }
+                });
+                insert_default(child, rest, default, marker);
+            }
+        }
+        None => {}
+    }
+}
+
+fn lookup<'i>(item: &'i mut Item, path: &[PathSegment]) -> Option<&'i mut Item> {
+    path.iter().try_fold(item, |item, segment| match segment {
+        PathSegment::Key(key) => item.as_table_like_mut()?.get_mut(key),
+        PathSegment::Index(index) => item.get_mut(*index),
+    })
+}
+
+fn fill_table(table: &mut Table, fields: &IndexMap<String, Schema>, position: &mut usize) {
+    table.set_position(*position);
+    *position += 1;
+
+    for (key, field) in fields {
+        let item = match &field.kind {
+            SchemaKind::Table(children) if field.default.is_none() => {
+                let mut child = Table::new();
+                fill_table(&mut child, children, position);
+                child
+                    .decor_mut()
+                    .set_prefix_unchecked(match field.description() {
+                        Some(description) => format!("\n{}", comment(description)),
+                        None => "\n".to_owned(),
+                    });
+                if field.description.is_none() && children.values().all(is_table_field) {
+                    child.set_implicit(true);
+                }
+                Item::Table(child)
+            }
+            SchemaKind::Array(element) if field.default.is_none() && is_table_field(element) => {
+                let children = element.fields().expect("checked by `is_table_field`");
+                let mut child = Table::new();
+                fill_table(&mut child, children, position);
+                let description = field.description().or_else(|| element.description());
+                child.decor_mut().set_prefix_unchecked(match description {
+                    Some(description) => format!("\n{}", comment(description)),
+                    None => "\n".to_owned(),
+                });
+                let mut array = ArrayOfTables::new();
+                array.push(child);
+                Item::ArrayOfTables(array)
+            }
+            _ => Item::Value(field.placeholder()),
+        };
+        let mut key = Key::new(key.as_str());
+        if item.is_value() {
+            if let Some(description) = field.description() {
+                key.leaf_decor_mut()
+                    .set_prefix_unchecked(comment(description));
+            }
+        }
+        table.insert_formatted(&key, item);
+    }
+}
+
+fn is_table_field(field: &Schema) -> bool {
+    matches!(field.kind, SchemaKind::Table(_)) && field.default.is_none()
+}
+
+fn comment(description: &str) -> String {
+    let mut comment = String::new();
+    for line in description.lines() {
+        if line.is_empty() {
+            comment.push_str("#\n");
+        } else {
+            comment.push_str("# ");
+            comment.push_str(line);
+            comment.push('\n');
+        }
+    }
+    comment
+}
@@ -1,17 +1,22 @@
 use super::Error;
+use super::KeyQuoting;
 
 #[doc(hidden)]
 pub struct SerializeValueArray {
     values: Vec<crate::Item>,
+    key_quoting: KeyQuoting,
 }
 
 impl SerializeValueArray {
-    pub(crate) fn seq(len: Option<usize>) -> Self {
+    pub(crate) fn seq(len: Option<usize>, key_quoting: KeyQuoting) -> Self {
         let mut values = Vec::new();
         if let Some(len) = len {
             values.reserve(len);
         }
-        Self { values }
+        Self {
+            values,
+            key_quoting,
+        }
     }
 }
 
@@ -23,7 +28,8 @@ impl serde::ser::SerializeSeq for SerializeValueArray {
     where
         T: serde::ser::Serialize + ?Sized,
     {
-        let value = value.serialize(super::ValueSerializer {})?;
+        let value =
+            value.serialize(super::ValueSerializer::new().with_key_quoting(self.key_quoting))?;
         self.values.push(crate::Item::Value(value));
         Ok(())
     }
@@ -71,10 +77,10 @@ pub struct SerializeTupleVariant {
 }
 
 impl SerializeTupleVariant {
-    pub(crate) fn tuple(variant: &'static str, len: usize) -> Self {
+    pub(crate) fn tuple(variant: &'static str, len: usize, key_quoting: KeyQuoting) -> Self {
         Self {
             variant,
-            inner: SerializeValueArray::seq(Some(len)),
+            inner: SerializeValueArray::seq(Some(len), key_quoting),
         }
     }
 }
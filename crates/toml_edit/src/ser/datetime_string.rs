@@ -0,0 +1,69 @@
+//! Serialize a [`Datetime`] as a string
+//!
+//! TOML has native datetimes but other formats sharing the same types, or older files, may
+//! store them as [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339) strings.  Deserializing
+//! accepts either.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "parse")] {
+//! # #[cfg(feature = "display")] {
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Release {
+//!     #[serde(with = "toml_edit::ser::datetime_string")]
+//!     date: toml_edit::Datetime,
+//! }
+//!
+//! let release: Release = toml_edit::de::from_str("date = 1979-05-27").unwrap();
+//! assert_eq!(toml_edit::ser::to_string(&release).unwrap(), "date = \"1979-05-27\"\n");
+//!
+//! let release: Release = toml_edit::de::from_str("date = '1979-05-27'").unwrap();
+//! assert_eq!(release.date.to_string(), "1979-05-27");
+//! # }
+//! # }
+//! ```
+
+use crate::Datetime;
+
+/// See [module-level docs][self]
+pub fn serialize<S>(datetime: &Datetime, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_str(datetime)
+}
+
+/// See [module-level docs][self]
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Datetime, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct DatetimeVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for DatetimeVisitor {
+        type Value = Datetime;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("a datetime or a string containing one")
+        }
+
+        fn visit_str<E>(self, s: &str) -> Result<Datetime, E>
+        where
+            E: serde::de::Error,
+        {
+            s.parse().map_err(E::custom)
+        }
+
+        fn visit_map<M>(self, map: M) -> Result<Datetime, M::Error>
+        where
+            M: serde::de::MapAccess<'de>,
+        {
+            serde::Deserialize::deserialize(serde::de::value::MapAccessDeserializer::new(map))
+        }
+    }
+
+    deserializer.deserialize_any(DatetimeVisitor)
+}
@@ -0,0 +1,69 @@
+use serde::ser::{Serialize, SerializeMap as _, SerializeSeq as _, Serializer};
+
+use crate::{DocumentMut, Item, TableLike, Value};
+
+/// Serialize the data in the document, dropping formatting
+///
+/// Tables, including inline tables, are maps and arrays, including arrays of tables, are
+/// sequences.  [`Datetime`][crate::Datetime]s use their own [`Serialize`] so they can be told
+/// apart from strings when deserialized again.
+impl Serialize for DocumentMut {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeTable(self.as_table()).serialize(serializer)
+    }
+}
+
+struct SerializeTable<'t>(&'t dyn TableLike);
+
+impl Serialize for SerializeTable<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, item) in self.0.iter() {
+            if !item.is_none() {
+                map.serialize_entry(key, &SerializeItem(item))?;
+            }
+        }
+        map.end()
+    }
+}
+
+struct SerializeItem<'i>(&'i Item);
+
+impl Serialize for SerializeItem<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Item::None => serializer.serialize_none(),
+            Item::Value(value) => SerializeValue(value).serialize(serializer),
+            Item::Table(table) => SerializeTable(table).serialize(serializer),
+            Item::ArrayOfTables(array) => {
+                let mut seq = serializer.serialize_seq(Some(array.len()))?;
+                for table in array.iter() {
+                    seq.serialize_element(&SerializeTable(table))?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+struct SerializeValue<'v>(&'v Value);
+
+impl Serialize for SerializeValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::String(v) => serializer.serialize_str(v.value()),
+            Value::Integer(v) => serializer.serialize_i64(*v.value()),
+            Value::Float(v) => serializer.serialize_f64(*v.value()),
+            Value::Boolean(v) => serializer.serialize_bool(*v.value()),
+            Value::Datetime(v) => v.value().serialize(serializer),
+            Value::Array(array) => {
+                let mut seq = serializer.serialize_seq(Some(array.len()))?;
+                for value in array.iter() {
+                    seq.serialize_element(&SerializeValue(value))?;
+                }
+                seq.end()
+            }
+            Value::InlineTable(table) => SerializeTable(table).serialize(serializer),
+        }
+    }
+}
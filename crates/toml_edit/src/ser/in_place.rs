@@ -0,0 +1,156 @@
+use crate::{Array, ArrayOfTables, InlineTable, Item, Table, Value};
+
+/// What [`to_item_in_place_with`][super::to_item_in_place_with] does with keys that are
+/// serialized but missing from the item being updated
+///
+/// When the value was deserialized from the same item, these are the fields filled in by
+/// `#[serde(default)]`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MissingKeys {
+    /// Add them, like any other new key
+    #[default]
+    Add,
+    /// Add them with a trailing `# default` comment, so the defaults can be found and tuned in
+    /// the document
+    ///
+    /// Inline tables can't hold comments, so keys added to them are left unmarked.
+    AddAsDefault,
+    /// Leave them out, keeping the document to the keys that were written
+    Skip,
+}
+
+/// Replace `old` with `new`, reusing the formatting of whatever is shared between them
+pub(crate) fn update_item(old: &mut Item, new: Value, missing: MissingKeys) {
+    match old {
+        Item::Table(table) => match new {
+            Value::InlineTable(new) => update_table(table, new, missing),
+            new => *old = Item::Value(new),
+        },
+        Item::ArrayOfTables(tables) => match new {
+            Value::Array(new) if new.iter().all(Value::is_inline_table) => {
+                update_array_of_tables(tables, new, missing);
+            }
+            new => *old = Item::Value(new),
+        },
+        Item::Value(value) => update_value(value, new, missing),
+        Item::None => *old = Item::Value(new),
+    }
+}
+
+fn update_table(old: &mut Table, mut new: InlineTable, missing: MissingKeys) {
+    old.retain(|key, _| new.contains_key(key));
+    for (key, item) in std::mem::take(&mut new.items) {
+        let Item::Value(mut new) = item else {
+            continue;
+        };
+        match old.get_mut(key.get()) {
+            Some(old) => update_item(old, new, missing),
+            None => match missing {
+                MissingKeys::Add => {
+                    old.items.insert(key, Item::Value(new));
+                }
+                MissingKeys::AddAsDefault => {
+                    new.decorate(" ", " # default");
+                    old.items.insert(key, Item::Value(new));
+                }
+                MissingKeys::Skip => {}
+            },
+        }
+    }
+}
+
+fn update_array_of_tables(old: &mut ArrayOfTables, new: Array, missing: MissingKeys) {
+    let len = new.len();
+    let position = old.iter().last().and_then(Table::position);
+    for (i, new) in new.into_iter().enumerate() {
+        let Value::InlineTable(new) = new else {
+            continue;
+        };
+        match old.get_mut(i) {
+            Some(old) => update_table(old, new, missing),
+            None => {
+                let mut table = new.into_table();
+                if let Some(position) = position {
+                    table.set_position(position);
+                }
+                old.push(table);
+            }
+        }
+    }
+    old.values.truncate(len);
+}
+
+fn update_value(old: &mut Value, new: Value, missing: MissingKeys) {
+    match (old, new) {
+        (Value::InlineTable(old), Value::InlineTable(new)) => {
+            update_inline_table(old, new, missing);
+        }
+        (Value::Array(old), Value::Array(new)) => update_array(old, new, missing),
+        (old, mut new) => {
+            if !is_same_scalar(old, &new) {
+                *new.decor_mut() = old.decor().clone();
+                *old = new;
+            }
+        }
+    }
+}
+
+fn update_inline_table(old: &mut InlineTable, mut new: InlineTable, missing: MissingKeys) {
+    old.retain(|key, _| new.contains_key(key));
+    for (key, item) in std::mem::take(&mut new.items) {
+        let Item::Value(new) = item else {
+            continue;
+        };
+        match old.get_mut(key.get()) {
+            Some(old) => update_value(old, new, missing),
+            None if missing == MissingKeys::Skip => {}
+            None => {
+                old.items.insert(key, Item::Value(new));
+            }
+        }
+    }
+}
+
+fn update_array(old: &mut Array, new: Array, missing: MissingKeys) {
+    let len = new.len();
+    for (i, new) in new.into_iter().enumerate() {
+        if let Some(old) = old.get_mut(i) {
+            update_value(old, new, missing);
+            continue;
+        }
+
+        // Lay out new values like the last one, unless it is the first value on the same line
+        // as the `[` which has different spacing than the values that follow it
+        let prefix = old
+            .iter()
+            .last()
+            .and_then(|last| last.decor().prefix())
+            .and_then(|p| p.as_str())
+            .and_then(|prefix| match prefix.rfind('\n') {
+                // Skip comments for the previous value
+                Some(i) => Some(format!("\n{}", &prefix[i + 1..])),
+                None => (1 < i).then(|| prefix.to_owned()),
+            });
+        let mut new = new;
+        if let Some(prefix) = prefix {
+            new.decorate(prefix, "");
+        }
+        old.push_formatted(new);
+    }
+    old.values.truncate(len);
+    if old.is_empty() {
+        old.set_trailing_comma(false);
+    }
+}
+
+fn is_same_scalar(old: &Value, new: &Value) -> bool {
+    match (old, new) {
+        (Value::String(old), Value::String(new)) => old.value() == new.value(),
+        (Value::Integer(old), Value::Integer(new)) => old.value() == new.value(),
+        (Value::Float(old), Value::Float(new)) => old.value().to_bits() == new.value().to_bits(),
+        (Value::Boolean(old), Value::Boolean(new)) => old.value() == new.value(),
+        (Value::Datetime(old), Value::Datetime(new)) => old.value() == new.value(),
+        _ => false,
+    }
+}
@@ -1,8 +1,49 @@
+#[cfg(feature = "display")]
+use toml_write::ToTomlKey as _;
+
 use crate::Key;
 
 use super::Error;
+use super::SerializeDatetime;
+
+/// How map keys that aren't strings, like integers, are written
+///
+/// TOML only has string keys, so integers, bools, chars, and datetimes are converted to strings.
+/// See [`ValueSerializer::with_key_quoting`][super::ValueSerializer::with_key_quoting].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KeyQuoting {
+    /// Write the key bare when TOML allows it, like `80 = "http"`
+    #[default]
+    BareWhenValid,
+    /// Always quote the key, like `"80" = "http"`, making it clear it was converted
+    Always,
+}
+
+pub(crate) struct KeySerializer {
+    quoting: KeyQuoting,
+}
 
-pub(crate) struct KeySerializer;
+impl KeySerializer {
+    pub(crate) fn new(quoting: KeyQuoting) -> Self {
+        Self { quoting }
+    }
+
+    fn stringified(&self, key: impl std::fmt::Display) -> Result<Key, Error> {
+        let key = Key::new(key.to_string());
+        #[cfg(feature = "display")]
+        if self.quoting == KeyQuoting::Always {
+            let builder = toml_write::TomlKeyBuilder::new(key.get());
+            let repr = builder
+                .as_basic_pretty()
+                .or_else(|| builder.as_literal())
+                .unwrap_or_else(|| builder.as_basic())
+                .to_toml_key();
+            return Ok(key.with_repr_unchecked(crate::Repr::new_unchecked(repr)));
+        }
+        Ok(key)
+    }
+}
 
 impl serde::ser::Serializer for KeySerializer {
     type Ok = Key;
@@ -12,43 +53,43 @@ impl serde::ser::Serializer for KeySerializer {
     type SerializeTupleStruct = serde::ser::Impossible<Self::Ok, Error>;
     type SerializeTupleVariant = serde::ser::Impossible<Self::Ok, Error>;
     type SerializeMap = serde::ser::Impossible<Self::Ok, Error>;
-    type SerializeStruct = serde::ser::Impossible<Self::Ok, Error>;
+    type SerializeStruct = SerializeKeyDatetime;
     type SerializeStructVariant = serde::ser::Impossible<Self::Ok, Error>;
 
-    fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
-    fn serialize_i8(self, _v: i8) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
-    fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
-    fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
-    fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
-    fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
-    fn serialize_u16(self, _v: u16) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
-    fn serialize_u32(self, _v: u32) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
-    fn serialize_u64(self, _v: u64) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
     fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
@@ -59,8 +100,8 @@ impl serde::ser::Serializer for KeySerializer {
         Err(Error::key_not_string())
     }
 
-    fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
-        Err(Error::key_not_string())
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.stringified(v)
     }
 
     fn serialize_str(self, value: &str) -> Result<Self::Ok, Self::Error> {
@@ -155,10 +196,17 @@ impl serde::ser::Serializer for KeySerializer {
 
     fn serialize_struct(
         self,
-        _name: &'static str,
+        name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        Err(Error::key_not_string())
+        if name == toml_datetime::__unstable::NAME {
+            Ok(SerializeKeyDatetime {
+                key: self,
+                inner: SerializeDatetime::new(),
+            })
+        } else {
+            Err(Error::key_not_string())
+        }
     }
 
     fn serialize_struct_variant(
@@ -171,3 +219,25 @@ impl serde::ser::Serializer for KeySerializer {
         Err(Error::key_not_string())
     }
 }
+
+pub(crate) struct SerializeKeyDatetime {
+    key: KeySerializer,
+    inner: SerializeDatetime,
+}
+
+impl serde::ser::SerializeStruct for SerializeKeyDatetime {
+    type Ok = Key;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: serde::ser::Serialize + ?Sized,
+    {
+        serde::ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        let datetime = serde::ser::SerializeStruct::end(self.inner)?;
+        self.key.stringified(datetime)
+    }
+}
@@ -0,0 +1,105 @@
+//! Serialize unit enum variants as lowercase strings
+//!
+//! This is for enums from other crates that can't have `#[serde(rename_all = "lowercase")]`
+//! added.  Deserializing matches variants case-insensitively.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "parse")] {
+//! # #[cfg(feature = "display")] {
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! enum Level {
+//!     Warn,
+//!     Deny,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Lint {
+//!     #[serde(with = "toml_edit::ser::lowercase")]
+//!     level: Level,
+//! }
+//!
+//! let lint: Lint = toml_edit::de::from_str("level = 'deny'").unwrap();
+//! assert_eq!(lint.level, Level::Deny);
+//! assert_eq!(toml_edit::ser::to_string(&lint).unwrap(), "level = \"deny\"\n");
+//! # }
+//! # }
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::ser::ValueSerializer;
+use crate::Value;
+
+/// See [module-level docs][self]
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: serde::Serialize,
+    S: serde::Serializer,
+{
+    match value.serialize(ValueSerializer::new()) {
+        Ok(Value::String(variant)) => serializer.serialize_str(&variant.value().to_lowercase()),
+        Ok(value) => Err(serde::ser::Error::custom(format!(
+            "expected a unit variant, found {}",
+            value.type_name()
+        ))),
+        Err(err) => Err(serde::ser::Error::custom(err)),
+    }
+}
+
+/// See [module-level docs][self]
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    let variant = <String as serde::Deserialize>::deserialize(deserializer)?;
+    T::deserialize(VariantDeserializer {
+        variant,
+        marker: PhantomData,
+    })
+}
+
+/// Deserialize `variant` as whichever enum variant it matches, ignoring case
+struct VariantDeserializer<E> {
+    variant: String,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E: serde::de::Error> serde::Deserializer<'de> for VariantDeserializer<E> {
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_string(self.variant)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let variant = variants
+            .iter()
+            .find(|v| v.eq_ignore_ascii_case(&self.variant))
+            .copied()
+            .unwrap_or(&self.variant);
+        serde::de::value::StrDeserializer::<E>::new(variant)
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
+        bytes byte_buf map unit newtype_struct option
+        ignored_any unit_struct tuple_struct tuple identifier struct
+    }
+}
@@ -3,6 +3,7 @@ use super::array::SerializeValueArray;
 use super::key::KeySerializer;
 use super::value::ValueSerializer;
 use super::Error;
+use super::KeyQuoting;
 
 #[doc(hidden)]
 #[allow(clippy::large_enum_variant)]
@@ -12,15 +13,15 @@ pub enum SerializeMap {
 }
 
 impl SerializeMap {
-    pub(crate) fn map(len: Option<usize>) -> Self {
-        Self::Table(SerializeInlineTable::map(len))
+    pub(crate) fn map(len: Option<usize>, key_quoting: KeyQuoting) -> Self {
+        Self::Table(SerializeInlineTable::map(len, key_quoting))
     }
 
-    pub(crate) fn struct_(name: &'static str, len: Option<usize>) -> Self {
+    pub(crate) fn struct_(name: &'static str, len: Option<usize>, key_quoting: KeyQuoting) -> Self {
         if name == toml_datetime::__unstable::NAME {
             Self::Datetime(SerializeDatetime::new())
         } else {
-            Self::map(len)
+            Self::map(len, key_quoting)
         }
     }
 }
@@ -137,16 +138,21 @@ impl serde::ser::SerializeStruct for SerializeDatetime {
 pub struct SerializeInlineTable {
     items: crate::table::KeyValuePairs,
     key: Option<crate::Key>,
+    key_quoting: KeyQuoting,
 }
 
 impl SerializeInlineTable {
-    pub(crate) fn map(len: Option<usize>) -> Self {
+    pub(crate) fn map(len: Option<usize>, key_quoting: KeyQuoting) -> Self {
         let mut items: crate::table::KeyValuePairs = Default::default();
         let key = Default::default();
         if let Some(len) = len {
             items.reserve(len);
         }
-        Self { items, key }
+        Self {
+            items,
+            key,
+            key_quoting,
+        }
     }
 }
 
@@ -158,7 +164,7 @@ impl serde::ser::SerializeMap for SerializeInlineTable {
     where
         T: serde::ser::Serialize + ?Sized,
     {
-        self.key = Some(input.serialize(KeySerializer)?);
+        self.key = Some(input.serialize(KeySerializer::new(self.key_quoting))?);
         Ok(())
     }
 
@@ -167,7 +173,7 @@ impl serde::ser::SerializeMap for SerializeInlineTable {
         T: serde::ser::Serialize + ?Sized,
     {
         let mut is_none = false;
-        let value_serializer = MapValueSerializer::new(&mut is_none);
+        let value_serializer = MapValueSerializer::new(&mut is_none, self.key_quoting);
         let res = value.serialize(value_serializer);
         match res {
             Ok(item) => {
@@ -198,7 +204,7 @@ impl serde::ser::SerializeStruct for SerializeInlineTable {
         T: serde::ser::Serialize + ?Sized,
     {
         let mut is_none = false;
-        let value_serializer = MapValueSerializer::new(&mut is_none);
+        let value_serializer = MapValueSerializer::new(&mut is_none, self.key_quoting);
         let res = value.serialize(value_serializer);
         match res {
             Ok(item) => {
@@ -392,11 +398,19 @@ impl serde::ser::Serializer for DatetimeFieldSerializer {
 
 struct MapValueSerializer<'d> {
     is_none: &'d mut bool,
+    key_quoting: KeyQuoting,
 }
 
 impl<'d> MapValueSerializer<'d> {
-    fn new(is_none: &'d mut bool) -> Self {
-        Self { is_none }
+    fn new(is_none: &'d mut bool, key_quoting: KeyQuoting) -> Self {
+        Self {
+            is_none,
+            key_quoting,
+        }
+    }
+
+    fn value_serializer(&self) -> ValueSerializer {
+        ValueSerializer::new().with_key_quoting(self.key_quoting)
     }
 }
 
@@ -412,59 +426,59 @@ impl serde::ser::Serializer for MapValueSerializer<'_> {
     type SerializeStructVariant = SerializeStructVariant;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_bool(v)
+        self.value_serializer().serialize_bool(v)
     }
 
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_i8(v)
+        self.value_serializer().serialize_i8(v)
     }
 
     fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_i16(v)
+        self.value_serializer().serialize_i16(v)
     }
 
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_i32(v)
+        self.value_serializer().serialize_i32(v)
     }
 
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_i64(v)
+        self.value_serializer().serialize_i64(v)
     }
 
     fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_u8(v)
+        self.value_serializer().serialize_u8(v)
     }
 
     fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_u16(v)
+        self.value_serializer().serialize_u16(v)
     }
 
     fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_u32(v)
+        self.value_serializer().serialize_u32(v)
     }
 
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_u64(v)
+        self.value_serializer().serialize_u64(v)
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_f32(v)
+        self.value_serializer().serialize_f32(v)
     }
 
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_f64(v)
+        self.value_serializer().serialize_f64(v)
     }
 
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_char(v)
+        self.value_serializer().serialize_char(v)
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_str(v)
+        self.value_serializer().serialize_str(v)
     }
 
     fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_bytes(value)
+        self.value_serializer().serialize_bytes(value)
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
@@ -476,15 +490,15 @@ impl serde::ser::Serializer for MapValueSerializer<'_> {
     where
         T: serde::ser::Serialize + ?Sized,
     {
-        ValueSerializer::new().serialize_some(value)
+        self.value_serializer().serialize_some(value)
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_unit()
+        self.value_serializer().serialize_unit()
     }
 
     fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_unit_struct(name)
+        self.value_serializer().serialize_unit_struct(name)
     }
 
     fn serialize_unit_variant(
@@ -493,7 +507,8 @@ impl serde::ser::Serializer for MapValueSerializer<'_> {
         variant_index: u32,
         variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        ValueSerializer::new().serialize_unit_variant(name, variant_index, variant)
+        self.value_serializer()
+            .serialize_unit_variant(name, variant_index, variant)
     }
 
     fn serialize_newtype_struct<T>(
@@ -517,15 +532,16 @@ impl serde::ser::Serializer for MapValueSerializer<'_> {
     where
         T: serde::ser::Serialize + ?Sized,
     {
-        ValueSerializer::new().serialize_newtype_variant(name, variant_index, variant, value)
+        self.value_serializer()
+            .serialize_newtype_variant(name, variant_index, variant, value)
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        ValueSerializer::new().serialize_seq(len)
+        self.value_serializer().serialize_seq(len)
     }
 
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        ValueSerializer::new().serialize_tuple(len)
+        self.value_serializer().serialize_tuple(len)
     }
 
     fn serialize_tuple_struct(
@@ -533,7 +549,7 @@ impl serde::ser::Serializer for MapValueSerializer<'_> {
         name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        ValueSerializer::new().serialize_tuple_struct(name, len)
+        self.value_serializer().serialize_tuple_struct(name, len)
     }
 
     fn serialize_tuple_variant(
@@ -543,11 +559,12 @@ impl serde::ser::Serializer for MapValueSerializer<'_> {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        ValueSerializer::new().serialize_tuple_variant(name, variant_index, variant, len)
+        self.value_serializer()
+            .serialize_tuple_variant(name, variant_index, variant, len)
     }
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        ValueSerializer::new().serialize_map(len)
+        self.value_serializer().serialize_map(len)
     }
 
     fn serialize_struct(
@@ -555,7 +572,7 @@ impl serde::ser::Serializer for MapValueSerializer<'_> {
         name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        ValueSerializer::new().serialize_struct(name, len)
+        self.value_serializer().serialize_struct(name, len)
     }
 
     fn serialize_struct_variant(
@@ -565,7 +582,8 @@ impl serde::ser::Serializer for MapValueSerializer<'_> {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        ValueSerializer::new().serialize_struct_variant(name, variant_index, variant, len)
+        self.value_serializer()
+            .serialize_struct_variant(name, variant_index, variant, len)
     }
 }
 
@@ -575,10 +593,10 @@ pub struct SerializeStructVariant {
 }
 
 impl SerializeStructVariant {
-    pub(crate) fn struct_(variant: &'static str, len: usize) -> Self {
+    pub(crate) fn struct_(variant: &'static str, len: usize, key_quoting: KeyQuoting) -> Self {
         Self {
             variant,
-            inner: SerializeInlineTable::map(Some(len)),
+            inner: SerializeInlineTable::map(Some(len), key_quoting),
         }
     }
 }
@@ -1,25 +1,36 @@
 //! Serializing Rust structures into TOML.
 //!
 //! This module contains all the Serde support for serializing Rust structures into TOML.
+//!
+//! [`datetime_string`], [`lowercase`], and [`stringified_keys`] are for use with
+//! `#[serde(with = "...")]`, converting fields both ways.
 
 mod array;
+mod document;
+mod in_place;
 mod key;
 mod map;
 mod pretty;
 mod value;
 
+pub mod datetime_string;
+pub mod lowercase;
+pub mod stringified_keys;
+
 use crate::visit_mut::VisitMut as _;
 #[allow(clippy::wildcard_imports)]
 use array::*;
 #[allow(clippy::wildcard_imports)]
 use map::*;
 
+pub use in_place::MissingKeys;
+pub use key::KeyQuoting;
 pub use value::ValueSerializer;
 
 /// Serialize the given data structure as a TOML byte vector.
 ///
 /// Serialization can fail if `T`'s implementation of `Serialize` decides to
-/// fail, if `T` contains a map with non-string keys, or if `T` attempts to
+/// fail, if `T` contains a map with keys that can't be strings, or if `T` attempts to
 /// serialize an unsupported datatype such as an enum, tuple, or tuple struct.
 #[cfg(feature = "display")]
 pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, Error>
@@ -32,7 +43,7 @@ where
 /// Serialize the given data structure as a String of TOML.
 ///
 /// Serialization can fail if `T`'s implementation of `Serialize` decides to
-/// fail, if `T` contains a map with non-string keys, or if `T` attempts to
+/// fail, if `T` contains a map with keys that can't be strings, or if `T` attempts to
 /// serialize an unsupported datatype such as an enum, tuple, or tuple struct.
 ///
 /// # Examples
@@ -102,6 +113,108 @@ where
     Ok(root.into())
 }
 
+/// Serialize the given data structure over an existing [`Item`][crate::Item], preserving its
+/// formatting.
+///
+/// Keys, comments, and whitespace are kept for the parts of `item` that are still present in
+/// `value` and values that are unchanged keep their original representation.
+/// Serializing a table into a `[table]` keeps it as a `[table]`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// # #[cfg(feature = "display")] {
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let mut doc = r#"
+/// [server]
+/// host = 'localhost'  # the host
+/// port = 0x50
+/// "#.parse::<toml_edit::DocumentMut>().unwrap();
+///
+/// let server = Server {
+///     host: "localhost".to_owned(),
+///     port: 8080,
+/// };
+/// toml_edit::ser::to_item_in_place(&server, &mut doc["server"]).unwrap();
+/// assert_eq!(doc.to_string(), r#"
+/// [server]
+/// host = 'localhost'  # the host
+/// port = 8080
+/// "#);
+/// # }
+/// # }
+/// ```
+pub fn to_item_in_place<T>(value: &T, item: &mut crate::Item) -> Result<(), Error>
+where
+    T: serde::ser::Serialize + ?Sized,
+{
+    to_item_in_place_with(value, item, MissingKeys::Add)
+}
+
+/// Serialize the given data structure over an existing [`Item`][crate::Item], like
+/// [`to_item_in_place`], choosing what to do with keys that `item` doesn't have yet
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// # #[cfg(feature = "display")] {
+/// use serde::{Deserialize, Serialize};
+/// use toml_edit::ser::MissingKeys;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     server: Server,
+/// }
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Server {
+///     host: String,
+///     #[serde(default = "default_port")]
+///     port: u16,
+/// }
+///
+/// fn default_port() -> u16 {
+///     8080
+/// }
+///
+/// let input = r#"
+/// [server]
+/// host = 'localhost'  # the host
+/// "#;
+/// let config: Config = toml_edit::de::from_str(input).unwrap();
+/// let mut doc = input.parse::<toml_edit::DocumentMut>().unwrap();
+/// toml_edit::ser::to_item_in_place_with(&config.server, &mut doc["server"], MissingKeys::AddAsDefault)
+///     .unwrap();
+/// assert_eq!(doc.to_string(), r#"
+/// [server]
+/// host = 'localhost'  # the host
+/// port = 8080 # default
+/// "#);
+/// # }
+/// # }
+/// ```
+pub fn to_item_in_place_with<T>(
+    value: &T,
+    item: &mut crate::Item,
+    missing: MissingKeys,
+) -> Result<(), Error>
+where
+    T: serde::ser::Serialize + ?Sized,
+{
+    let value = value.serialize(ValueSerializer::new())?;
+    in_place::update_item(item, value, missing);
+    Ok(())
+}
+
 /// Errors that can occur when deserializing a type.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -45,14 +45,10 @@ impl crate::visit_mut::VisitMut for Pretty {
         crate::visit_mut::visit_array_mut(self, node);
 
         if (0..=1).contains(&node.len()) {
-            node.set_trailing("");
+            node.set_trailing_unchecked("");
             node.set_trailing_comma(false);
         } else {
-            for item in node.iter_mut() {
-                item.decor_mut().set_prefix("\n    ");
-            }
-            node.set_trailing("\n");
-            node.set_trailing_comma(true);
+            node.fmt_multiline();
         }
     }
 }
@@ -0,0 +1,89 @@
+//! Serialize maps with non-string keys by converting the keys to strings
+//!
+//! TOML only has string keys.  Integers, bools, chars, and datetimes are converted without this;
+//! for other keys, like IP addresses, this uses [`Display`][std::fmt::Display] and
+//! [`FromStr`][std::str::FromStr] to convert them.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "parse")] {
+//! # #[cfg(feature = "display")] {
+//! use std::collections::BTreeMap;
+//! use std::net::IpAddr;
+//!
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "toml_edit::ser::stringified_keys")]
+//!     hosts: BTreeMap<IpAddr, String>,
+//! }
+//!
+//! let input = "hosts = { '127.0.0.1' = 'localhost', '::1' = 'ip6-localhost' }";
+//! let config: Config = toml_edit::de::from_str(input).unwrap();
+//! assert_eq!(config.hosts[&IpAddr::from([127, 0, 0, 1])], "localhost");
+//! assert_eq!(
+//!     toml_edit::ser::to_string(&config).unwrap(),
+//!     "hosts = { \"127.0.0.1\" = \"localhost\", \"::1\" = \"ip6-localhost\" }\n"
+//! );
+//! # }
+//! # }
+//! ```
+
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// See [module-level docs][self]
+pub fn serialize<'m, M, K, V, S>(map: &'m M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'m M: IntoIterator<Item = (&'m K, &'m V)>,
+    K: Display + 'm,
+    V: serde::Serialize + 'm,
+    S: serde::Serializer,
+{
+    serializer.collect_map(map.into_iter().map(|(k, v)| (k.to_string(), v)))
+}
+
+/// See [module-level docs][self]
+pub fn deserialize<'de, M, K, V, D>(deserializer: D) -> Result<M, D::Error>
+where
+    M: FromIterator<(K, V)>,
+    K: FromStr,
+    K::Err: Display,
+    V: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    struct MapVisitor<M, K, V>(PhantomData<(M, K, V)>);
+
+    impl<'de, M, K, V> serde::de::Visitor<'de> for MapVisitor<M, K, V>
+    where
+        M: FromIterator<(K, V)>,
+        K: FromStr,
+        K::Err: Display,
+        V: serde::Deserialize<'de>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("a table")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<M, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let mut entries = Vec::new();
+            while let Some((key, value)) = map.next_entry::<String, V>()? {
+                let key = key
+                    .parse::<K>()
+                    .map_err(|e| serde::de::Error::custom(format!("invalid key `{key}`: {e}")))?;
+                entries.push((key, value));
+            }
+            Ok(entries.into_iter().collect())
+        }
+    }
+
+    deserializer.deserialize_map(MapVisitor(PhantomData))
+}
@@ -1,4 +1,5 @@
 use super::Error;
+use super::KeyQuoting;
 use super::SerializeMap;
 use super::SerializeStructVariant;
 use super::SerializeTupleVariant;
@@ -51,14 +52,44 @@ use super::SerializeValueArray;
 /// # }
 /// # }
 /// ```
-#[derive(Default)]
+#[derive(Copy, Clone, Default)]
 #[non_exhaustive]
-pub struct ValueSerializer {}
+pub struct ValueSerializer {
+    key_quoting: KeyQuoting,
+}
 
 impl ValueSerializer {
     /// Creates a new serializer generate a TOML document.
     pub fn new() -> Self {
-        Self {}
+        Self {
+            key_quoting: KeyQuoting::default(),
+        }
+    }
+
+    /// Choose how map keys that aren't strings, like integers, are written
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "display")] {
+    /// use std::collections::BTreeMap;
+    ///
+    /// use serde::Serialize as _;
+    /// use toml_edit::ser::{KeyQuoting, ValueSerializer};
+    ///
+    /// let ports = BTreeMap::from([(80, "http"), (443, "https")]);
+    ///
+    /// let value = ports.serialize(ValueSerializer::new()).unwrap();
+    /// assert_eq!(value.to_string(), r#"{ 80 = "http", 443 = "https" }"#);
+    ///
+    /// let serializer = ValueSerializer::new().with_key_quoting(KeyQuoting::Always);
+    /// let value = ports.serialize(serializer).unwrap();
+    /// assert_eq!(value.to_string(), r#"{ "80" = "http", "443" = "https" }"#);
+    /// # }
+    /// ```
+    pub fn with_key_quoting(mut self, quoting: KeyQuoting) -> Self {
+        self.key_quoting = quoting;
+        self
     }
 }
 
@@ -200,7 +231,7 @@ impl serde::ser::Serializer for ValueSerializer {
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        Ok(SerializeValueArray::seq(len))
+        Ok(SerializeValueArray::seq(len, self.key_quoting))
     }
 
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
@@ -222,11 +253,11 @@ impl serde::ser::Serializer for ValueSerializer {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        Ok(SerializeTupleVariant::tuple(variant, len))
+        Ok(SerializeTupleVariant::tuple(variant, len, self.key_quoting))
     }
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        Ok(SerializeMap::map(len))
+        Ok(SerializeMap::map(len, self.key_quoting))
     }
 
     fn serialize_struct(
@@ -234,7 +265,7 @@ impl serde::ser::Serializer for ValueSerializer {
         name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        Ok(SerializeMap::struct_(name, Some(len)))
+        Ok(SerializeMap::struct_(name, Some(len), self.key_quoting))
     }
 
     fn serialize_struct_variant(
@@ -244,6 +275,10 @@ impl serde::ser::Serializer for ValueSerializer {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        Ok(SerializeStructVariant::struct_(variant, len))
+        Ok(SerializeStructVariant::struct_(
+            variant,
+            len,
+            self.key_quoting,
+        ))
     }
 }
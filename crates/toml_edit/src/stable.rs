@@ -0,0 +1,251 @@
+use std::fmt::Write as _;
+
+use toml_datetime::{Datetime, Offset};
+
+use crate::{DocumentMut, InlineTable, Item, Table, Value};
+
+/// A versioned output format whose bytes never change between releases
+///
+/// The default [`Display`][std::fmt::Display] of a [`DocumentMut`] may be improved over time,
+/// producing diffs in generated files, like lockfiles, when the library is updated.  Each
+/// `OutputStyle` is frozen once released; improvements are added as new variants.
+///
+/// See [`DocumentMut::to_stable_string`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OutputStyle {
+    /// Normalized formatting with no comments
+    ///
+    /// - Existing whitespace, comments, and value representations are ignored
+    /// - Keys and values are written in insertion order with key/value pairs before tables
+    /// - Keys are bare when possible, otherwise basic strings
+    /// - Strings are basic strings on a single line
+    /// - Floats are written with the fewest digits that read back the same, in scientific
+    ///   notation like `1e16` when below `1e-4` or at least `1e16` in magnitude and otherwise with
+    ///   a decimal point, like `1.0`
+    /// - Datetimes are written as RFC 3339 with a `T` separator, an uppercase `Z`, and
+    ///   fractional seconds without trailing zeros
+    /// - Arrays and inline tables are written on a single line
+    /// - Tables without key/value pairs are only written if they have no sub-tables
+    /// - Each table header is preceded by a blank line, unless it is the first line
+    V1,
+}
+
+impl DocumentMut {
+    /// Write the document in an [`OutputStyle`] that is stable across releases
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use toml_edit::{DocumentMut, OutputStyle};
+    ///
+    /// let mut doc = DocumentMut::new();
+    /// doc["version"] = toml_edit::value(3);
+    /// let mut package = toml_edit::ArrayOfTables::new();
+    /// let mut entry = toml_edit::Table::new();
+    /// entry["name"] = toml_edit::value("foo");
+    /// entry["checksum"] = toml_edit::value("abc");
+    /// package.push(entry);
+    /// doc["package"] = toml_edit::Item::ArrayOfTables(package);
+    ///
+    /// assert_eq!(doc.to_stable_string(OutputStyle::V1), r#"version = 3
+    ///
+    /// [[package]]
+    /// name = "foo"
+    /// checksum = "abc"
+    /// "#);
+    /// ```
+    pub fn to_stable_string(&self, style: OutputStyle) -> String {
+        let mut output = String::new();
+        match style {
+            OutputStyle::V1 => {
+                let mut path = Vec::new();
+                write_v1_table(&mut output, self.as_table(), &mut path, None);
+            }
+        }
+        output
+    }
+}
+
+/// `header` is `None` for the root and `Some(is_array)` otherwise
+fn write_v1_table<'t>(
+    output: &mut String,
+    table: &'t Table,
+    path: &mut Vec<&'t str>,
+    header: Option<bool>,
+) {
+    let has_values = table.iter().any(|(_, item)| item.is_value());
+    let has_tables = table
+        .iter()
+        .any(|(_, item)| item.is_table() || item.is_array_of_tables());
+    if let Some(is_array) = header {
+        if has_values || !has_tables || is_array {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            let (open, close) = if is_array { ("[[", "]]") } else { ("[", "]") };
+            output.push_str(open);
+            write_v1_path(output, path);
+            output.push_str(close);
+            output.push('\n');
+        }
+    }
+
+    for (key, item) in table.iter() {
+        if let Item::Value(value) = item {
+            write_v1_key(output, key);
+            output.push_str(" = ");
+            write_v1_value(output, value);
+            output.push('\n');
+        }
+    }
+
+    for (key, item) in table.iter() {
+        match item {
+            Item::Table(child) => {
+                path.push(key);
+                write_v1_table(output, child, path, Some(false));
+                path.pop();
+            }
+            Item::ArrayOfTables(children) => {
+                path.push(key);
+                for child in children.iter() {
+                    write_v1_table(output, child, path, Some(true));
+                }
+                path.pop();
+            }
+            Item::Value(_) | Item::None => {}
+        }
+    }
+}
+
+fn write_v1_path(output: &mut String, path: &[&str]) {
+    for (i, key) in path.iter().enumerate() {
+        if i != 0 {
+            output.push('.');
+        }
+        write_v1_key(output, key);
+    }
+}
+
+fn write_v1_key(output: &mut String, key: &str) {
+    let is_bare = !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if is_bare {
+        output.push_str(key);
+    } else {
+        write_v1_string(output, key);
+    }
+}
+
+fn write_v1_value(output: &mut String, value: &Value) {
+    match value {
+        Value::String(s) => write_v1_string(output, s.value()),
+        Value::Integer(i) => {
+            let _ = write!(output, "{}", i.value());
+        }
+        Value::Float(f) => write_v1_float(output, *f.value()),
+        Value::Boolean(b) => {
+            let _ = write!(output, "{}", b.value());
+        }
+        Value::Datetime(d) => write_v1_datetime(output, d.value()),
+        Value::Array(array) => {
+            output.push('[');
+            for (i, value) in array.iter().enumerate() {
+                if i != 0 {
+                    output.push_str(", ");
+                }
+                write_v1_value(output, value);
+            }
+            output.push(']');
+        }
+        Value::InlineTable(table) => write_v1_inline_table(output, table),
+    }
+}
+
+fn write_v1_inline_table(output: &mut String, table: &InlineTable) {
+    if table.is_empty() {
+        output.push_str("{}");
+        return;
+    }
+    output.push_str("{ ");
+    for (i, (key, value)) in table.iter().enumerate() {
+        if i != 0 {
+            output.push_str(", ");
+        }
+        write_v1_key(output, key);
+        output.push_str(" = ");
+        write_v1_value(output, value);
+    }
+    output.push_str(" }");
+}
+
+fn write_v1_float(output: &mut String, f: f64) {
+    if f.is_nan() {
+        output.push_str(if f.is_sign_negative() { "-nan" } else { "nan" });
+    } else if f.is_infinite() {
+        output.push_str(if f.is_sign_negative() { "-inf" } else { "inf" });
+    } else if f != 0.0 && !(1e-4..1e16).contains(&f.abs()) {
+        // `LowerExp` writes the shortest digits that round-trip, like `1.5e-5`
+        let _ = write!(output, "{f:e}");
+    } else {
+        // `Display` writes the shortest digits that round-trip without an exponent
+        let start = output.len();
+        let _ = write!(output, "{f}");
+        if !output[start..].contains('.') {
+            output.push_str(".0");
+        }
+    }
+}
+
+fn write_v1_datetime(output: &mut String, datetime: &Datetime) {
+    if let Some(date) = &datetime.date {
+        let _ = write!(output, "{:04}-{:02}-{:02}", date.year, date.month, date.day);
+    }
+    if let Some(time) = &datetime.time {
+        if datetime.date.is_some() {
+            output.push('T');
+        }
+        let _ = write!(
+            output,
+            "{:02}:{:02}:{:02}",
+            time.hour, time.minute, time.second
+        );
+        if time.nanosecond != 0 {
+            let fraction = format!("{:09}", time.nanosecond);
+            output.push('.');
+            output.push_str(fraction.trim_end_matches('0'));
+        }
+    }
+    match datetime.offset {
+        Some(Offset::Z) => output.push('Z'),
+        Some(Offset::Custom { minutes }) => {
+            let sign = if minutes < 0 { '-' } else { '+' };
+            let minutes = i32::from(minutes).abs();
+            let _ = write!(output, "{sign}{:02}:{:02}", minutes / 60, minutes % 60);
+        }
+        None => {}
+    }
+}
+
+fn write_v1_string(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\u{8}' => output.push_str("\\b"),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\u{c}' => output.push_str("\\f"),
+            '\r' => output.push_str("\\r"),
+            c if c.is_control() => {
+                let _ = write!(output, "\\u{:04X}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
@@ -0,0 +1,265 @@
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{
+    Array, Decor, DocumentMut, InlineTable, Item, Key, PathSegment, RawString, Table, Value,
+};
+
+/// Whitespace, comments, or nesting that can't be written out as valid TOML
+///
+/// See [`DocumentMut::from_root_table`] and [`Decor::set_prefix`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructureError {
+    path: Vec<PathSegment>,
+    reason: &'static str,
+}
+
+impl StructureError {
+    fn new(path: &[PathSegment], reason: &'static str) -> Self {
+        Self {
+            path: path.to_vec(),
+            reason,
+        }
+    }
+
+    /// The location of the offending item
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+}
+
+impl Display for StructureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.path.is_empty() {
+            return self.reason.fmt(f);
+        }
+        write!(f, "{} at `", self.reason)?;
+        for (i, segment) in self.path.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i != 0 => write!(f, ".{key}")?,
+                PathSegment::Key(key) => key.fmt(f)?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        "`".fmt(f)
+    }
+}
+
+impl StdError for StructureError {}
+
+impl DocumentMut {
+    /// Creates a document from a programmatically built [`Table`], checking that it can be
+    /// written out as valid TOML
+    ///
+    /// Unlike [`From<Table>`][DocumentMut#impl-From<Table>-for-DocumentMut], this rejects:
+    /// - [`Item::Table`] or [`Item::ArrayOfTables`] nested within an [`InlineTable`]
+    /// - [`Decor`] and other whitespace containing anything besides whitespace and comments, or
+    ///   containing newlines or comments where the syntax doesn't allow them, like between a key
+    ///   and its `=`
+    ///
+    /// Whitespace that refers back to a parsed document's input is not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{value, DocumentMut, Table, Value};
+    ///
+    /// let mut root = Table::new();
+    /// root.insert("a", value(1));
+    /// let doc = DocumentMut::from_root_table(root.clone()).unwrap();
+    /// assert_eq!(doc.to_string(), "a = 1\n");
+    ///
+    /// let b = Value::from(2).decorated(" ", " # comment\n").unwrap();
+    /// root.insert("b", value(b));
+    /// let err = DocumentMut::from_root_table(root).unwrap_err();
+    /// assert_eq!(err.to_string(), "newline is not allowed here at `b`");
+    /// # }
+    /// ```
+    pub fn from_root_table(table: Table) -> std::result::Result<Self, StructureError> {
+        let mut path = Vec::new();
+        check_decor(&table.decor, Space::Lines, Space::Lines, &path)?;
+        check_table(&table, &mut path)?;
+        Ok(Self::from(table))
+    }
+}
+
+/// What may appear in a piece of whitespace, based on what surrounds it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Space {
+    /// Spaces and tabs
+    Inline,
+    /// Spaces and tabs, optionally followed by a comment that a newline will end
+    LineEnd,
+    /// Spaces, tabs, newlines, and newline-terminated comments
+    Lines,
+    /// Anything allowed by one of the above, for when what surrounds it isn't known
+    Any,
+}
+
+/// Check whitespace being set through the API
+pub(crate) fn check_whitespace(
+    raw: &RawString,
+    space: Space,
+) -> std::result::Result<(), StructureError> {
+    check_raw(raw, space, &[])
+}
+
+fn check_table(
+    table: &Table,
+    path: &mut Vec<PathSegment>,
+) -> std::result::Result<(), StructureError> {
+    for (key, item) in table.items.iter() {
+        path.push(PathSegment::Key(key.get().to_owned()));
+        match item {
+            Item::None => {}
+            Item::Value(value) => {
+                check_key(key, Space::Lines, path)?;
+                check_value(value, Space::Inline, Space::LineEnd, path)?;
+            }
+            Item::Table(table) => {
+                check_key(key, Space::Inline, path)?;
+                check_decor(&table.decor, Space::Lines, Space::LineEnd, path)?;
+                check_table(table, path)?;
+            }
+            Item::ArrayOfTables(array) => {
+                check_key(key, Space::Inline, path)?;
+                for (i, table) in array.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    check_decor(&table.decor, Space::Lines, Space::LineEnd, path)?;
+                    check_table(table, path)?;
+                    path.pop();
+                }
+            }
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+fn check_key(
+    key: &Key,
+    prefix: Space,
+    path: &[PathSegment],
+) -> std::result::Result<(), StructureError> {
+    check_decor(&key.leaf_decor, prefix, Space::Inline, path)?;
+    check_decor(&key.dotted_decor, Space::Inline, Space::Inline, path)
+}
+
+fn check_value(
+    value: &Value,
+    prefix: Space,
+    suffix: Space,
+    path: &mut Vec<PathSegment>,
+) -> std::result::Result<(), StructureError> {
+    check_decor(value.decor(), prefix, suffix, path)?;
+    match value {
+        Value::Array(array) => check_array(array, path),
+        Value::InlineTable(table) => check_inline_table(table, path),
+        _ => Ok(()),
+    }
+}
+
+fn check_array(
+    array: &Array,
+    path: &mut Vec<PathSegment>,
+) -> std::result::Result<(), StructureError> {
+    for (i, value) in array.iter().enumerate() {
+        path.push(PathSegment::Index(i));
+        check_value(value, Space::Lines, Space::Lines, path)?;
+        path.pop();
+    }
+    check_raw(array.trailing(), Space::Lines, path)
+}
+
+fn check_inline_table(
+    table: &InlineTable,
+    path: &mut Vec<PathSegment>,
+) -> std::result::Result<(), StructureError> {
+    check_raw(table.preamble(), Space::Inline, path)?;
+    check_raw(table.trailing(), Space::Inline, path)?;
+    for (key, item) in table.items.iter() {
+        path.push(PathSegment::Key(key.get().to_owned()));
+        match item {
+            Item::None => {}
+            Item::Value(value) => {
+                check_key(key, Space::Inline, path)?;
+                check_value(value, Space::Inline, Space::Inline, path)?;
+            }
+            Item::Table(_) | Item::ArrayOfTables(_) => {
+                return Err(StructureError::new(
+                    path,
+                    "inline tables may only contain values",
+                ));
+            }
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+fn check_decor(
+    decor: &Decor,
+    prefix: Space,
+    suffix: Space,
+    path: &[PathSegment],
+) -> std::result::Result<(), StructureError> {
+    if let Some(raw) = decor.prefix() {
+        check_raw(raw, prefix, path)?;
+    }
+    if let Some(raw) = decor.suffix() {
+        check_raw(raw, suffix, path)?;
+    }
+    Ok(())
+}
+
+fn check_raw(
+    raw: &RawString,
+    space: Space,
+    path: &[PathSegment],
+) -> std::result::Result<(), StructureError> {
+    let Some(raw) = raw.as_str() else {
+        return Ok(());
+    };
+    check_space(raw, space).map_err(|reason| StructureError::new(path, reason))
+}
+
+fn check_space(raw: &str, space: Space) -> std::result::Result<(), &'static str> {
+    let mut rest = raw;
+    loop {
+        rest = rest.trim_start_matches([' ', '\t']);
+        if rest.is_empty() {
+            return Ok(());
+        }
+        if let Some(comment) = rest.strip_prefix('#') {
+            if space == Space::Inline {
+                return Err("comment is not allowed here");
+            }
+            let end = comment.find('\n').unwrap_or(comment.len());
+            let (comment, after) = comment.split_at(end);
+            let comment = comment.strip_suffix('\r').unwrap_or(comment);
+            if comment
+                .chars()
+                .any(|c| c != '\t' && (c <= '\u{1f}' || c == '\u{7f}'))
+            {
+                return Err("comment contains a control character");
+            }
+            if after.is_empty() {
+                return if matches!(space, Space::LineEnd | Space::Any) {
+                    Ok(())
+                } else {
+                    Err("comment must end with a newline")
+                };
+            }
+            rest = after;
+        }
+        let newline = rest
+            .strip_prefix('\n')
+            .or_else(|| rest.strip_prefix("\r\n"));
+        match newline {
+            Some(after) if matches!(space, Space::Lines | Space::Any) => rest = after,
+            Some(_) => return Err("newline is not allowed here"),
+            None => return Err("only whitespace and comments are allowed in decor"),
+        }
+    }
+}
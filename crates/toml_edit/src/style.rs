@@ -0,0 +1,969 @@
+use toml_write::ToTomlKey as _;
+use toml_write::ToTomlValue as _;
+
+use crate::comment::{self, CommentSpace};
+use crate::table::DEFAULT_KEY_DECOR;
+use crate::visit::{self, Visit};
+use crate::{
+    Array, Datetime, DecorKind, DecorMut, DocumentMut, Formatted, InlineTable, Item, Key, Offset,
+    Repr, Table, Value,
+};
+
+/// Formatting conventions inferred from an existing document
+///
+/// Automated edits can use this to imitate the style of the file they are editing, whether
+/// formatting values with [`StyleProfile::fmt_value`], a whole document with
+/// [`StyleProfile::fmt_document`], or inserting with [`Table::insert_styled`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "parse")] {
+/// let mut doc = "
+/// [package]
+///   name    = 'foo'
+///   authors = [
+///     'me',
+///   ]
+/// ".parse::<toml_edit::DocumentMut>().unwrap();
+/// let style = doc.style_profile();
+///
+/// let package = doc["package"].as_table_mut().unwrap();
+/// let mut keywords = toml_edit::Array::new();
+/// keywords.push("toml");
+/// package.insert_styled("keywords", toml_edit::value(keywords), &style);
+/// assert_eq!(doc.to_string(), "
+/// [package]
+///   name     = 'foo'
+///   authors  = [
+///     'me',
+///   ]
+///   keywords = [
+///     'toml',
+///   ]
+/// ");
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StyleProfile {
+    indent: String,
+    array_style: ArrayStyle,
+    array_indent: String,
+    array_trailing_comma: bool,
+    string_style: StringStyle,
+    key_quoting: Option<StringStyle>,
+    align_values: bool,
+    max_width: Option<usize>,
+    datetime_separator: Option<DatetimeSeparator>,
+    utc_offset_style: Option<UtcOffsetStyle>,
+    comment_space: Option<CommentSpace>,
+    comment_width: Option<usize>,
+}
+
+impl StyleProfile {
+    /// The default style, matching the auto formatting of values
+    pub fn new() -> Self {
+        Self {
+            indent: String::new(),
+            array_style: ArrayStyle::Inline,
+            array_indent: DEFAULT_ARRAY_INDENT.to_owned(),
+            array_trailing_comma: true,
+            string_style: StringStyle::Basic,
+            key_quoting: None,
+            align_values: false,
+            max_width: None,
+            datetime_separator: None,
+            utc_offset_style: None,
+            comment_space: None,
+            comment_width: None,
+        }
+    }
+
+    /// Infer the style of `doc`
+    ///
+    /// Each setting is decided by majority; settings with no evidence in `doc` use the
+    /// [default][StyleProfile::new].
+    pub fn from_document(doc: &DocumentMut) -> Self {
+        let mut stats = Stats::default();
+        stats.visit_document(doc);
+        stats.into_profile()
+    }
+
+    /// Whitespace before keys in `[table]`s
+    pub fn indent(&self) -> &str {
+        &self.indent
+    }
+
+    /// Set the whitespace before keys in `[table]`s
+    pub fn set_indent(&mut self, indent: impl Into<String>) {
+        self.indent = indent.into();
+    }
+
+    /// Whether arrays are written on one line or one value per line
+    pub fn array_style(&self) -> ArrayStyle {
+        self.array_style
+    }
+
+    /// Set whether arrays are written on one line or one value per line
+    pub fn set_array_style(&mut self, style: ArrayStyle) {
+        self.array_style = style;
+    }
+
+    /// Whitespace before each value of a [multi-line][ArrayStyle::Multiline] array
+    pub fn array_indent(&self) -> &str {
+        &self.array_indent
+    }
+
+    /// Set the whitespace before each value of a [multi-line][ArrayStyle::Multiline] array
+    pub fn set_array_indent(&mut self, indent: impl Into<String>) {
+        self.array_indent = indent.into();
+    }
+
+    /// Whether [multi-line][ArrayStyle::Multiline] arrays end with a comma
+    pub fn array_trailing_comma(&self) -> bool {
+        self.array_trailing_comma
+    }
+
+    /// Set whether [multi-line][ArrayStyle::Multiline] arrays end with a comma
+    pub fn set_array_trailing_comma(&mut self, yes: bool) {
+        self.array_trailing_comma = yes;
+    }
+
+    /// The preferred quoting for strings
+    pub fn string_style(&self) -> StringStyle {
+        self.string_style
+    }
+
+    /// Set the preferred quoting for strings
+    pub fn set_string_style(&mut self, style: StringStyle) {
+        self.string_style = style;
+    }
+
+    /// The preferred quoting for keys that can't be bare, if keys should be re-quoted
+    ///
+    /// When `None`, keys are written as they were parsed.  Otherwise, keys are bare when possible,
+    /// like after being renamed, and quoted in this style when not.
+    pub fn key_quoting(&self) -> Option<StringStyle> {
+        self.key_quoting
+    }
+
+    /// Set the preferred quoting for keys that can't be bare, see [`StyleProfile::key_quoting`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let mut doc = r#"
+    /// "name" = 'foo'
+    /// "C:\\Users" = 'home'
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let mut style = doc.style_profile();
+    /// style.set_key_quoting(Some(toml_edit::StringStyle::Literal));
+    /// style.fmt_document(&mut doc);
+    /// assert_eq!(doc.to_string(), r#"
+    /// name = 'foo'
+    /// 'C:\Users' = 'home'
+    /// "#);
+    /// # }
+    /// ```
+    pub fn set_key_quoting(&mut self, style: Option<StringStyle>) {
+        self.key_quoting = style;
+    }
+
+    /// Whether the `=` of key/value pairs within a table line up
+    pub fn align_values(&self) -> bool {
+        self.align_values
+    }
+
+    /// Set whether the `=` of key/value pairs within a table line up
+    pub fn set_align_values(&mut self, yes: bool) {
+        self.align_values = yes;
+    }
+
+    /// The column key/value pairs are wrapped at, if any
+    pub fn max_width(&self) -> Option<usize> {
+        self.max_width
+    }
+
+    /// Set the column key/value pairs are wrapped at
+    ///
+    /// When a key/value pair is wider than this, in characters, an array is written with one
+    /// value per line, a string with newlines as a multi-line string, and another string split
+    /// across lines at spaces with line-ending backslashes.  Values that can't be split, like
+    /// a long URL, are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let mut doc = r#"
+    /// description = "A library for reading, writing, and editing TOML while keeping comments"
+    /// keywords = ["toml", "config", "parser", "encoding", "serde", "format-preserving"]
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let mut style = doc.style_profile();
+    /// style.set_max_width(Some(60));
+    /// style.fmt_document(&mut doc);
+    /// assert_eq!(doc.to_string(), r#"
+    /// description = """
+    /// A library for reading, writing, and editing TOML while \
+    /// keeping comments"""
+    /// keywords = [
+    ///     "toml",
+    ///     "config",
+    ///     "parser",
+    ///     "encoding",
+    ///     "serde",
+    ///     "format-preserving",
+    /// ]
+    /// "#);
+    /// # }
+    /// ```
+    pub fn set_max_width(&mut self, width: Option<usize>) {
+        self.max_width = width;
+    }
+
+    /// How the date and time of datetimes are separated, if they should be rewritten
+    ///
+    /// When `None`, datetimes are written as they were parsed.
+    pub fn datetime_separator(&self) -> Option<DatetimeSeparator> {
+        self.datetime_separator
+    }
+
+    /// Set how the date and time of datetimes are separated, see
+    /// [`StyleProfile::datetime_separator`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{DatetimeSeparator, UtcOffsetStyle};
+    ///
+    /// let mut doc = "
+    /// created = 1979-05-27 07:32:00.000Z
+    /// updated = 2024-01-01T00:00:00+00:00
+    /// local = 1979-05-27t07:32:00
+    /// day = 1979-05-27
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let mut style = doc.style_profile();
+    /// style.set_datetime_separator(Some(DatetimeSeparator::T));
+    /// style.set_utc_offset_style(Some(UtcOffsetStyle::Z));
+    /// style.fmt_document(&mut doc);
+    /// assert_eq!(doc.to_string(), "
+    /// created = 1979-05-27T07:32:00.000Z
+    /// updated = 2024-01-01T00:00:00Z
+    /// local = 1979-05-27T07:32:00
+    /// day = 1979-05-27
+    /// ");
+    /// # }
+    /// ```
+    pub fn set_datetime_separator(&mut self, separator: Option<DatetimeSeparator>) {
+        self.datetime_separator = separator;
+    }
+
+    /// How a UTC offset is written, if it should be rewritten
+    ///
+    /// When `None`, offsets are written as they were parsed.  Other offsets, like `+01:00`, are
+    /// always numeric.
+    pub fn utc_offset_style(&self) -> Option<UtcOffsetStyle> {
+        self.utc_offset_style
+    }
+
+    /// Set how a UTC offset is written, see [`StyleProfile::utc_offset_style`]
+    pub fn set_utc_offset_style(&mut self, style: Option<UtcOffsetStyle>) {
+        self.utc_offset_style = style;
+    }
+
+    /// How the text of comments is separated from their `#`, if it should be rewritten
+    ///
+    /// When `None`, comments are written as they were parsed.
+    pub fn comment_space(&self) -> Option<CommentSpace> {
+        self.comment_space
+    }
+
+    /// Set how the text of comments is separated from their `#`, see
+    /// [`Decor::set_comment_space`][crate::Decor::set_comment_space]
+    pub fn set_comment_space(&mut self, space: Option<CommentSpace>) {
+        self.comment_space = space;
+    }
+
+    /// The column comments are wrapped at, if any
+    pub fn comment_width(&self) -> Option<usize> {
+        self.comment_width
+    }
+
+    /// Set the column comments are wrapped at, see
+    /// [`Decor::wrap_comments`][crate::Decor::wrap_comments]
+    ///
+    /// Only comments on a line of their own are wrapped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::CommentSpace;
+    ///
+    /// let mut doc = "
+    /// [package]
+    /// ##The name of the package, as published to crates.io
+    /// name = 'foo' #required
+    ///   ###### banner
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let mut style = doc.style_profile();
+    /// style.set_comment_space(Some(CommentSpace::Space));
+    /// style.set_comment_width(Some(30));
+    /// style.fmt_document(&mut doc);
+    /// assert_eq!(doc.to_string(), "
+    /// [package]
+    /// ## The name of the package, as
+    /// ## published to crates.io
+    /// name = 'foo' # required
+    ///   ###### banner
+    /// ");
+    /// # }
+    /// ```
+    pub fn set_comment_width(&mut self, width: Option<usize>) {
+        self.comment_width = width;
+    }
+
+    /// Auto formats `value` in this style
+    ///
+    /// Nested arrays are always written on one line.
+    pub fn fmt_value(&self, value: &mut Value) {
+        self.fmt_value_at(value, "");
+    }
+
+    /// Auto formats the keys and values of `doc` in this style
+    ///
+    /// Comments and blank lines between key/value pairs are preserved, with comments spaced and
+    /// wrapped according to [`StyleProfile::comment_space`] and [`StyleProfile::comment_width`].
+    pub fn fmt_document(&self, doc: &mut DocumentMut) {
+        self.fmt_table(doc.as_table_mut(), "");
+        if self.comment_space.is_some() || self.comment_width.is_some() {
+            for (kind, decor) in doc.decor_iter_mut() {
+                self.fmt_comments(kind, decor);
+            }
+        }
+    }
+
+    fn fmt_comments(&self, kind: DecorKind, decor: DecorMut<'_>) {
+        match decor {
+            DecorMut::Decor(decor) => {
+                if let Some(space) = self.comment_space {
+                    decor.set_comment_space(space);
+                }
+                if let Some(width) = self.comment_width {
+                    match kind {
+                        DecorKind::Document | DecorKind::TableHeader | DecorKind::Key => {
+                            decor.wrap_comments(width);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            DecorMut::Raw(raw) => {
+                let Some(mut s) = raw.as_str().map(ToOwned::to_owned) else {
+                    return;
+                };
+                if let Some(space) = self.comment_space {
+                    s = comment::space_comments(&s, space);
+                }
+                if let (Some(width), DecorKind::DocumentTrailing) = (self.comment_width, kind) {
+                    s = comment::wrap_comments(&s, width);
+                }
+                *raw = s.into();
+            }
+        }
+    }
+
+    /// Format a `value` whose key is indented by `indent`
+    fn fmt_value_at(&self, value: &mut Value, indent: &str) {
+        match value {
+            Value::Array(array) => {
+                match self.array_style {
+                    ArrayStyle::Inline => array.fmt(),
+                    ArrayStyle::Multiline => self.fmt_multiline_array(array, indent),
+                }
+                for value in array.iter_mut() {
+                    self.fmt_nested_value(value);
+                }
+            }
+            _ => self.fmt_nested_value(value),
+        }
+    }
+
+    fn fmt_table(&self, table: &mut Table, indent: &str) {
+        use indexmap::map::MutableKeys;
+
+        for (key, item) in table.items_mut().iter_mut2() {
+            self.fmt_key(key);
+            match item {
+                Item::Value(value) => {
+                    reindent(key, indent);
+                    self.fmt_value_at(value, indent);
+                }
+                Item::Table(child) => {
+                    // The keys of dotted tables are in the body of this table
+                    if child.is_dotted() {
+                        self.fmt_table(child, indent);
+                    } else {
+                        self.fmt_table(child, &self.indent);
+                    }
+                }
+                Item::ArrayOfTables(array) => {
+                    for child in array.iter_mut() {
+                        self.fmt_table(child, &self.indent);
+                    }
+                }
+                Item::None => {}
+            }
+        }
+        if self.align_values {
+            align_keys(table);
+        }
+        // The keys of dotted tables are wrapped with their parent's
+        if self.max_width.is_some() && !table.is_dotted() {
+            self.wrap_table(table, 0);
+        }
+    }
+
+    /// Wrap the key/value pairs of `table` that are wider than [`StyleProfile::max_width`]
+    ///
+    /// `column` is where the keys start, after the parent keys of a dotted table.
+    fn wrap_table(&self, table: &mut Table, column: usize) {
+        use indexmap::map::MutableKeys;
+
+        for (key, item) in table.items_mut().iter_mut2() {
+            match item {
+                Item::Value(value) => self.wrap_value(key, value, column),
+                Item::Table(child) if child.is_dotted() => {
+                    let column = column + width(key_prefix(key)) + width(&key.display_repr()) + 1;
+                    self.wrap_table(child, column);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn wrap_value(&self, key: &Key, value: &mut Value, column: usize) {
+        let Some(max_width) = self.max_width else {
+            return;
+        };
+        let key_suffix = key
+            .leaf_decor()
+            .suffix()
+            .and_then(|s| s.as_str())
+            .unwrap_or(DEFAULT_KEY_DECOR.1);
+        let column =
+            column + width(key_prefix(key)) + width(&key.display_repr()) + width(key_suffix) + 1;
+        let rendered = value.to_string();
+        let too_wide = !rendered.contains('\n') && max_width < column + width(&rendered);
+        match value {
+            Value::Array(array) => {
+                if too_wide {
+                    let indent = key_indent(key).unwrap_or_default().to_owned();
+                    self.fmt_multiline_array(array, &indent);
+                }
+                for value in array.iter_mut() {
+                    let prefix = value.decor().prefix().and_then(|p| p.as_str());
+                    let Some((_, line)) = prefix.and_then(|p| p.rsplit_once('\n')) else {
+                        continue;
+                    };
+                    let column = width(line);
+                    if let Value::String(string) = value {
+                        // Leave room for the `,`
+                        if max_width < column + width(&string.display_repr()) + 1 {
+                            self.wrap_string(string, max_width);
+                        }
+                    }
+                }
+            }
+            Value::String(string) if too_wide => self.wrap_string(string, max_width),
+            _ => {}
+        }
+    }
+
+    /// Write `string` across lines, each no wider than `max_width` when possible
+    fn wrap_string(&self, string: &mut Formatted<String>, max_width: usize) {
+        let value = string.value();
+        let repr = if value.contains('\n') {
+            let builder = toml_write::TomlStringBuilder::new(value);
+            let repr = match self.string_style {
+                StringStyle::Basic => builder.as_ml_basic_pretty(),
+                StringStyle::Literal => builder.as_ml_literal(),
+            };
+            repr.unwrap_or_else(|| builder.as_ml_basic())
+                .to_toml_value()
+        } else {
+            let lines = continuation_lines(value, max_width);
+            if lines.len() < 2 {
+                return;
+            }
+            format!("\"\"\"\n{}\"\"\"", lines.join("\\\n"))
+        };
+        string.set_repr_unchecked(Repr::new_unchecked(repr));
+    }
+
+    fn fmt_nested_value(&self, value: &mut Value) {
+        match value {
+            Value::String(string) => self.fmt_string(string),
+            Value::Array(array) => {
+                array.fmt();
+                for value in array.iter_mut() {
+                    self.fmt_nested_value(value);
+                }
+            }
+            Value::InlineTable(table) => self.fmt_inline_table(table),
+            Value::Datetime(datetime) => self.fmt_datetime(datetime),
+            _ => {}
+        }
+    }
+
+    fn fmt_multiline_array(&self, array: &mut Array, indent: &str) {
+        let prefix = format!("\n{}", self.array_indent);
+        for value in array.iter_mut() {
+            value.decorate(prefix.as_str(), "");
+        }
+        if array.is_empty() {
+            array.set_trailing_comma(false);
+            array.set_trailing_unchecked("");
+        } else {
+            array.set_trailing_comma(self.array_trailing_comma);
+            array.set_trailing_unchecked(format!("\n{indent}"));
+        }
+    }
+
+    fn fmt_inline_table(&self, table: &mut InlineTable) {
+        use indexmap::map::MutableKeys;
+
+        table.fmt();
+        for (key, item) in table.items.iter_mut2() {
+            self.fmt_key(key);
+            if let Item::Value(value) = item {
+                self.fmt_nested_value(value);
+            }
+        }
+    }
+
+    fn fmt_key(&self, key: &mut Key) {
+        let Some(style) = self.key_quoting else {
+            return;
+        };
+        let builder = toml_write::TomlKeyBuilder::new(key.get());
+        let repr = match style {
+            StringStyle::Basic => builder.as_default(),
+            StringStyle::Literal => builder
+                .as_unquoted()
+                .or_else(|| builder.as_literal())
+                .unwrap_or_else(|| builder.as_default()),
+        };
+        key.repr = Some(Repr::new_unchecked(repr.to_toml_key()));
+    }
+
+    fn fmt_datetime(&self, datetime: &mut Formatted<Datetime>) {
+        if self.datetime_separator.is_none() && self.utc_offset_style.is_none() {
+            return;
+        }
+        let mut value = *datetime.value();
+        let mut raw = datetime
+            .raw()
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| value.to_string());
+        if let (Some(separator), Some(_), Some(_)) =
+            (self.datetime_separator, value.date, value.time)
+        {
+            // Dates are always `YYYY-MM-DD`
+            let separator = match separator {
+                DatetimeSeparator::T => "T",
+                DatetimeSeparator::Space => " ",
+            };
+            raw.replace_range(10..11, separator);
+        }
+        if let (Some(style), Some(offset)) = (self.utc_offset_style, value.offset) {
+            if matches!(offset, Offset::Z | Offset::Custom { minutes: 0 }) {
+                let len = if raw.ends_with(['Z', 'z']) { 1 } else { 6 };
+                raw.truncate(raw.len() - len);
+                let offset = match style {
+                    UtcOffsetStyle::Z => Offset::Z,
+                    UtcOffsetStyle::Numeric => Offset::Custom { minutes: 0 },
+                };
+                raw.push_str(&offset.to_string());
+                value.offset = Some(offset);
+            }
+        }
+        let mut formatted = Formatted::new(value);
+        formatted.set_repr_unchecked(Repr::new_unchecked(raw));
+        *formatted.decor_mut() = datetime.decor().clone();
+        *datetime = formatted;
+    }
+
+    fn fmt_string(&self, string: &mut Formatted<String>) {
+        let builder = toml_write::TomlStringBuilder::new(string.value());
+        let repr = match self.string_style {
+            StringStyle::Basic => builder.as_default(),
+            StringStyle::Literal => builder.as_literal().unwrap_or_else(|| builder.as_default()),
+        };
+        string.set_repr_unchecked(Repr::new_unchecked(repr.to_toml_value()));
+    }
+}
+
+impl Default for StyleProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How [`Array`]s are laid out
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ArrayStyle {
+    /// `[1, 2, 3]`
+    Inline,
+    /// One value per line
+    Multiline,
+}
+
+/// How the date and time of a datetime are separated
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DatetimeSeparator {
+    /// `1979-05-27T07:32:00`
+    T,
+    /// `1979-05-27 07:32:00`
+    Space,
+}
+
+/// How a UTC offset is written
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UtcOffsetStyle {
+    /// `1979-05-27T07:32:00Z`
+    Z,
+    /// `1979-05-27T07:32:00+00:00`
+    Numeric,
+}
+
+/// How strings are quoted
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StringStyle {
+    /// `"value"`, falling back as needed
+    Basic,
+    /// `'value'`, falling back as needed
+    Literal,
+}
+
+impl DocumentMut {
+    /// Infer the style of this document, see [`StyleProfile`]
+    pub fn style_profile(&self) -> StyleProfile {
+        StyleProfile::from_document(self)
+    }
+}
+
+impl Table {
+    /// Inserts a key-value pair into the map, formatted to match `style`
+    ///
+    /// Values are formatted with [`StyleProfile::fmt_value`].
+    /// The key is indented like the other keys in this table, quoted according to
+    /// [`StyleProfile::key_quoting`], and, if [`StyleProfile::align_values`], the table's keys are
+    /// re-aligned.  The value is then wrapped at [`StyleProfile::max_width`].
+    pub fn insert_styled(
+        &mut self,
+        key: &str,
+        mut item: Item,
+        style: &StyleProfile,
+    ) -> Option<Item> {
+        use indexmap::map::MutableKeys;
+
+        let indent = self
+            .items
+            .iter()
+            .rev()
+            .filter(|(_, item)| item.is_value())
+            .find_map(|(key, _)| key_indent(key))
+            .unwrap_or_default()
+            .to_owned();
+        if let Item::Value(value) = &mut item {
+            style.fmt_value_at(value, &indent);
+        }
+        let old = self.insert(key, item);
+        if let Some((_, key, _)) = self.items.get_full_mut2(key) {
+            style.fmt_key(key);
+            key.leaf_decor_mut().set_prefix_unchecked(indent);
+        }
+        if style.align_values {
+            align_keys(self);
+        }
+        if let Some((_, key, Item::Value(value))) = self.items.get_full_mut2(key) {
+            style.wrap_value(key, value, 0);
+        }
+        old
+    }
+}
+
+const DEFAULT_ARRAY_INDENT: &str = "    ";
+
+#[derive(Default)]
+struct Stats {
+    indented: bool,
+    indents: Votes<String>,
+    multiline_arrays: Votes<bool>,
+    array_indents: Votes<String>,
+    trailing_commas: Votes<bool>,
+    literal_strings: Votes<bool>,
+    aligned: Votes<bool>,
+}
+
+impl Stats {
+    fn into_profile(self) -> StyleProfile {
+        let default = StyleProfile::new();
+        StyleProfile {
+            indent: self.indents.winner().unwrap_or(default.indent),
+            array_style: match self.multiline_arrays.winner() {
+                Some(true) => ArrayStyle::Multiline,
+                Some(false) => ArrayStyle::Inline,
+                None => default.array_style,
+            },
+            array_indent: self.array_indents.winner().unwrap_or(default.array_indent),
+            array_trailing_comma: self
+                .trailing_commas
+                .winner()
+                .unwrap_or(default.array_trailing_comma),
+            string_style: match self.literal_strings.winner() {
+                Some(true) => StringStyle::Literal,
+                Some(false) => StringStyle::Basic,
+                None => default.string_style,
+            },
+            key_quoting: default.key_quoting,
+            align_values: self.aligned.winner().unwrap_or(default.align_values),
+            max_width: default.max_width,
+            datetime_separator: default.datetime_separator,
+            utc_offset_style: default.utc_offset_style,
+            comment_space: default.comment_space,
+            comment_width: default.comment_width,
+        }
+    }
+}
+
+impl Stats {
+    fn scan_table(&mut self, node: &Table) {
+        let values = || node.items.iter().filter(|(_, item)| item.is_value());
+        if self.indented {
+            for (key, _) in values() {
+                if let Some(indent) = key_indent(key) {
+                    self.indents.add(indent.to_owned());
+                }
+            }
+        }
+
+        let widths = values()
+            .map(|(key, _)| {
+                let suffix = key
+                    .leaf_decor()
+                    .suffix()
+                    .and_then(|s| s.as_str())
+                    .unwrap_or(DEFAULT_KEY_DECOR.1);
+                (key.display_repr().len(), suffix)
+            })
+            .collect::<Vec<_>>();
+        if let Some((first_len, first_suffix)) = widths.first() {
+            let same_len = widths.iter().all(|(len, _)| len == first_len);
+            let same_suffix = widths.iter().all(|(_, suffix)| suffix == first_suffix);
+            let aligned = widths
+                .iter()
+                .all(|(len, suffix)| len + suffix.len() == first_len + first_suffix.len());
+            if 2 <= widths.len() && !same_len {
+                if same_suffix {
+                    self.aligned.add(false);
+                } else if aligned {
+                    self.aligned.add(true);
+                }
+            }
+        }
+    }
+}
+
+impl<'doc> Visit<'doc> for Stats {
+    fn visit_document(&mut self, node: &'doc DocumentMut) {
+        let root = node.as_table();
+        self.indented = false;
+        self.scan_table(root);
+        visit::visit_table(self, root);
+    }
+
+    fn visit_table(&mut self, node: &'doc Table) {
+        // Dotted tables are part of their parent's body
+        let parent_indented = self.indented;
+        self.indented = !node.is_dotted() || parent_indented;
+        self.scan_table(node);
+        visit::visit_table(self, node);
+        self.indented = parent_indented;
+    }
+
+    fn visit_array(&mut self, node: &'doc Array) {
+        if !node.is_empty() {
+            let multiline = node.iter().any(|value| {
+                value
+                    .decor()
+                    .prefix()
+                    .and_then(|p| p.as_str())
+                    .map(|p| p.contains('\n'))
+                    .unwrap_or(false)
+            });
+            self.multiline_arrays.add(multiline);
+            if multiline {
+                self.trailing_commas.add(node.trailing_comma());
+                let indent = node
+                    .iter()
+                    .filter_map(|value| value.decor().prefix().and_then(|p| p.as_str()))
+                    .find_map(line_indent);
+                if let Some(indent) = indent {
+                    self.array_indents.add(indent.to_owned());
+                }
+            }
+        }
+        visit::visit_array(self, node);
+    }
+
+    fn visit_string(&mut self, node: &'doc Formatted<String>) {
+        let raw = node.as_repr().and_then(|r| r.as_raw().as_str());
+        if let Some(raw) = raw {
+            if raw.starts_with('\'') {
+                self.literal_strings.add(true);
+            } else if raw.starts_with('"') {
+                self.literal_strings.add(false);
+            }
+        }
+    }
+}
+
+struct Votes<T> {
+    counts: Vec<(T, usize)>,
+}
+
+impl<T: PartialEq> Votes<T> {
+    fn add(&mut self, value: T) {
+        if let Some((_, count)) = self.counts.iter_mut().find(|(v, _)| *v == value) {
+            *count += 1;
+        } else {
+            self.counts.push((value, 1));
+        }
+    }
+
+    /// The most common value, preferring the first seen on ties
+    fn winner(self) -> Option<T> {
+        let mut winner: Option<(T, usize)> = None;
+        for (value, count) in self.counts {
+            if winner.as_ref().map(|(_, c)| *c < count).unwrap_or(true) {
+                winner = Some((value, count));
+            }
+        }
+        winner.map(|(value, _)| value)
+    }
+}
+
+impl<T> Default for Votes<T> {
+    fn default() -> Self {
+        Self { counts: Vec::new() }
+    }
+}
+
+/// Split `value` into escaped lines of a multi-line basic string, breaking after spaces
+///
+/// Each line but the last ends with a backslash, which trims the whitespace that starts the
+/// next line, so lines only break before a character that isn't whitespace.
+fn continuation_lines(value: &str, max_width: usize) -> Vec<String> {
+    let breaks = value
+        .char_indices()
+        .zip(value.chars().skip(1))
+        .filter(|((_, c), next)| *c == ' ' && !next.is_whitespace())
+        .map(|((i, _), _)| i + 1)
+        .chain(std::iter::once(value.len()));
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut start = 0;
+    for end in breaks {
+        let word = escape_basic(&value[start..end]);
+        start = end;
+        // Leave room for the `\`
+        if !line.is_empty() && max_width < width(&line) + width(&word) + 1 {
+            lines.push(std::mem::take(&mut line));
+        }
+        line.push_str(&word);
+    }
+    lines.push(line);
+    lines
+}
+
+/// The content of `value` as a basic string, without the quotes
+fn escape_basic(value: &str) -> String {
+    let quoted = toml_write::TomlStringBuilder::new(value)
+        .as_basic()
+        .to_toml_value();
+    quoted[1..quoted.len() - 1].to_owned()
+}
+
+fn width(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Everything on the key's line before the key
+fn key_prefix(key: &Key) -> &str {
+    match key.leaf_decor().prefix() {
+        Some(prefix) => prefix
+            .as_str()
+            .map(|p| p.rsplit('\n').next().unwrap_or(p))
+            .unwrap_or_default(),
+        None => DEFAULT_KEY_DECOR.0,
+    }
+}
+
+/// Whitespace on the key's line before the key
+fn key_indent(key: &Key) -> Option<&str> {
+    match key.leaf_decor().prefix() {
+        Some(prefix) => prefix.as_str().and_then(line_indent),
+        None => Some(DEFAULT_KEY_DECOR.0),
+    }
+}
+
+fn line_indent(prefix: &str) -> Option<&str> {
+    let line = prefix.rsplit('\n').next().unwrap_or(prefix);
+    is_whitespace(line).then_some(line)
+}
+
+fn is_whitespace(s: &str) -> bool {
+    s.chars().all(|c| c == ' ' || c == '\t')
+}
+
+/// Replace the whitespace on the key's line, keeping any comments above it
+fn reindent(key: &mut Key, indent: &str) {
+    let prefix = key.leaf_decor().prefix().and_then(|p| p.as_str());
+    let prefix = match prefix {
+        Some(prefix) => match prefix.rfind('\n') {
+            Some(i) if is_whitespace(&prefix[i + 1..]) => format!("{}{indent}", &prefix[..=i]),
+            Some(_) => return,
+            None if is_whitespace(prefix) => indent.to_owned(),
+            None => return,
+        },
+        None => indent.to_owned(),
+    };
+    key.leaf_decor_mut().set_prefix_unchecked(prefix);
+}
+
+fn align_keys(table: &mut Table) {
+    use indexmap::map::MutableKeys;
+
+    let width = table
+        .items
+        .iter()
+        .filter(|(_, item)| item.is_value())
+        .map(|(key, _)| key.display_repr().len())
+        .max()
+        .unwrap_or(0);
+    for (key, item) in table.items_mut().iter_mut2() {
+        if item.is_value() {
+            let padding = width - key.display_repr().len() + 1;
+            key.leaf_decor_mut()
+                .set_suffix_unchecked(" ".repeat(padding));
+        }
+    }
+}
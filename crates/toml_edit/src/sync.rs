@@ -0,0 +1,205 @@
+//! Edit a document from several threads at once
+//!
+//! **Experimental:** this may change or be removed in a minor release.
+//!
+//! [`SyncDocument`] splits a [`DocumentMut`] by its top-level keys, like `[server]` and
+//! `[database]`, each behind its own lock.  Edits to different top-level keys can happen
+//! concurrently while edits to the same one wait on each other, or fail with
+//! [`SyncDocument::try_write`].  Adding or removing top-level keys needs `&mut`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "parse")] {
+//! # #[cfg(feature = "display")] {
+//! use toml_edit::sync::SyncDocument;
+//!
+//! let doc = "
+//! [server]
+//! port = 80
+//!
+//! [database]
+//! url = 'postgres://localhost'
+//! ".parse::<toml_edit::DocumentMut>().unwrap();
+//! let doc = SyncDocument::new(doc);
+//!
+//! std::thread::scope(|s| {
+//!     s.spawn(|| {
+//!         let mut server = doc.write("server").unwrap();
+//!         server["port"] = toml_edit::value(8080);
+//!     });
+//!     s.spawn(|| {
+//!         let mut database = doc.write("database").unwrap();
+//!         database["pool"] = toml_edit::value(4);
+//!     });
+//! });
+//!
+//! assert_eq!(doc.into_document().to_string(), "
+//! [server]
+//! port = 8080
+//!
+//! [database]
+//! url = 'postgres://localhost'
+//! pool = 4
+//! ");
+//! # }
+//! # }
+//! ```
+
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+
+use indexmap::IndexMap;
+
+use crate::{DocumentMut, Item, Key};
+
+/// A [`DocumentMut`] with a lock for each top-level key, see the [module docs][self]
+#[derive(Debug)]
+pub struct SyncDocument {
+    // Everything but the top-level items
+    doc: DocumentMut,
+    items: IndexMap<Key, RwLock<Item>>,
+}
+
+impl SyncDocument {
+    /// Split `doc` by its top-level keys
+    pub fn new(mut doc: DocumentMut) -> Self {
+        let items = std::mem::take(&mut doc.as_table_mut().items)
+            .into_iter()
+            .map(|(key, item)| (key, RwLock::new(item)))
+            .collect();
+        Self { doc, items }
+    }
+
+    /// Put the document back together
+    pub fn into_document(self) -> DocumentMut {
+        let mut doc = self.doc;
+        doc.as_table_mut().items = self
+            .items
+            .into_iter()
+            .map(|(key, item)| {
+                (
+                    key,
+                    item.into_inner().unwrap_or_else(PoisonError::into_inner),
+                )
+            })
+            .collect();
+        doc
+    }
+
+    /// A copy of the document
+    ///
+    /// Each top-level item is copied in turn, so this waits on any in-progress writes and
+    /// may see later writes to items it has not reached yet.
+    pub fn to_document(&self) -> DocumentMut {
+        let mut doc = self.doc.clone();
+        doc.as_table_mut().items = self
+            .items
+            .iter()
+            .map(|(key, item)| {
+                let item = item.read().unwrap_or_else(PoisonError::into_inner);
+                (key.clone(), item.clone())
+            })
+            .collect();
+        doc
+    }
+
+    /// The top-level keys, in order
+    pub fn keys(&self) -> impl Iterator<Item = &str> + '_ {
+        self.items.keys().map(|key| key.get())
+    }
+
+    /// Read the item at `key`, waiting for any write to it to finish
+    pub fn read(&self, key: &str) -> Option<RwLockReadGuard<'_, Item>> {
+        let item = self.items.get(key)?;
+        Some(item.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Edit the item at `key`, waiting for any other use of it to finish
+    pub fn write(&self, key: &str) -> Option<RwLockWriteGuard<'_, Item>> {
+        let item = self.items.get(key)?;
+        Some(item.write().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Read the item at `key` if it is not being written to
+    pub fn try_read(&self, key: &str) -> Result<RwLockReadGuard<'_, Item>, SyncError> {
+        let item = self.items.get(key).ok_or_else(|| SyncError::missing(key))?;
+        match item.try_read() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => Ok(err.into_inner()),
+            Err(TryLockError::WouldBlock) => Err(SyncError::in_use(key)),
+        }
+    }
+
+    /// Edit the item at `key` if nothing else is using it
+    pub fn try_write(&self, key: &str) -> Result<RwLockWriteGuard<'_, Item>, SyncError> {
+        let item = self.items.get(key).ok_or_else(|| SyncError::missing(key))?;
+        match item.try_write() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => Ok(err.into_inner()),
+            Err(TryLockError::WouldBlock) => Err(SyncError::in_use(key)),
+        }
+    }
+
+    /// Add or replace a top-level item, returning the old one
+    pub fn insert(&mut self, key: &str, item: Item) -> Option<Item> {
+        let old = self.items.insert(Key::new(key), RwLock::new(item))?;
+        Some(old.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Remove a top-level item
+    pub fn remove(&mut self, key: &str) -> Option<Item> {
+        let old = self.items.shift_remove(key)?;
+        Some(old.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl From<DocumentMut> for SyncDocument {
+    fn from(doc: DocumentMut) -> Self {
+        Self::new(doc)
+    }
+}
+
+/// A top-level item could not be used, see [`SyncDocument::try_write`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncError {
+    key: String,
+    in_use: bool,
+}
+
+impl SyncError {
+    fn missing(key: &str) -> Self {
+        Self {
+            key: key.to_owned(),
+            in_use: false,
+        }
+    }
+
+    fn in_use(key: &str) -> Self {
+        Self {
+            key: key.to_owned(),
+            in_use: true,
+        }
+    }
+
+    /// The top-level key
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Whether the item is being used elsewhere, rather than missing
+    pub fn is_in_use(&self) -> bool {
+        self.in_use
+    }
+}
+
+impl std::fmt::Display for SyncError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.in_use {
+            write!(f, "`{}` is in use", self.key)
+        } else {
+            write!(f, "`{}` is not present", self.key)
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
@@ -2,14 +2,16 @@ use std::iter::FromIterator;
 
 use indexmap::map::IndexMap;
 
-use crate::key::Key;
+use crate::key::{Key, PathDecor};
 use crate::repr::Decor;
 use crate::value::DEFAULT_VALUE_DECOR;
-use crate::{InlineTable, InternalString, Item, KeyMut, Value};
+use crate::{
+    ConflictPolicy, InlineTable, InsertError, InternalString, Item, KeyMut, SortComments, Value,
+};
 
 /// A TOML table, a top-level collection of key/[`Value`] pairs under a header and logical
 /// sub-tables
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Default)]
 pub struct Table {
     // Comments/spaces before and after the header
     pub(crate) decor: Decor,
@@ -20,9 +22,14 @@ pub struct Table {
     // Used for putting tables back in their original order when serialising.
     //
     // `None` for user created tables (can be overridden with `set_position`)
-    doc_position: Option<usize>,
+    pub(crate) doc_position: Option<usize>,
     pub(crate) span: Option<std::ops::Range<usize>>,
+    // How the header was written, when it differs from the keys leading to this table
+    pub(crate) header_decor: Option<Box<PathDecor>>,
     pub(crate) items: KeyValuePairs,
+    // Where the key/value lines under the header are in the original document, until the table
+    // is changed, see `DocumentMut::text_edits_from`
+    pub(crate) body_span: Option<std::ops::Range<usize>>,
 }
 
 /// Constructors
@@ -34,18 +41,21 @@ impl Table {
         Default::default()
     }
 
+    /// Creates an empty table with space for at least `capacity` key/value pairs.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_pairs(KeyValuePairs::with_capacity(capacity))
+    }
+
     pub(crate) fn with_pos(doc_position: Option<usize>) -> Self {
-        Self {
-            doc_position,
-            ..Default::default()
-        }
+        let mut table = Self::default();
+        table.doc_position = doc_position;
+        table
     }
 
     pub(crate) fn with_pairs(items: KeyValuePairs) -> Self {
-        Self {
-            items,
-            ..Default::default()
-        }
+        let mut table = Self::default();
+        table.items = items;
+        table
     }
 
     /// Convert to an inline table
@@ -53,7 +63,7 @@ impl Table {
         for (_, value) in self.items.iter_mut() {
             value.make_value();
         }
-        let mut t = InlineTable::with_pairs(self.items);
+        let mut t = InlineTable::with_pairs(std::mem::take(&mut self.items));
         t.fmt();
         t
     }
@@ -116,8 +126,9 @@ impl Table {
     /// </div>
     pub fn sort_values(&mut self) {
         // Assuming standard tables have their doc_position set and this won't negatively impact them
-        self.items.sort_keys();
-        for value in self.items.values_mut() {
+        let items = self.items_mut();
+        items.sort_keys();
+        for value in items.values_mut() {
             match value {
                 Item::Table(table) if table.is_dotted() => {
                     table.sort_values();
@@ -131,6 +142,7 @@ impl Table {
     ///
     /// The comparison function receives two key and value pairs to compare (you can sort by keys or
     /// values or their combination as needed).
+    /// Comments above a pair move with it, see [`Table::sort_values_by_with_comments`].
     ///
     /// <div class="warning">
     ///
@@ -147,6 +159,63 @@ impl Table {
         self.sort_values_by_internal(&mut compare);
     }
 
+    /// Sort [Key]/[Value]-pairs of the table using the comparison function `compare`, placing
+    /// comments according to `comments`
+    ///
+    /// The comments and blank lines above each pair are what stays or moves, see
+    /// [`Table::sort_values_by`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::SortComments;
+    ///
+    /// let mut doc = "
+    /// [dependencies]
+    /// ## Serialization
+    /// serde = '1'
+    /// ## Errors
+    /// anyhow = '1'
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let deps = doc["dependencies"].as_table_mut().unwrap();
+    ///
+    /// deps.sort_values_by_with_comments(SortComments::KeepPosition, |k1, _, k2, _| k1.cmp(k2));
+    /// assert_eq!(doc.to_string(), "
+    /// [dependencies]
+    /// ## Serialization
+    /// anyhow = '1'
+    /// ## Errors
+    /// serde = '1'
+    /// ");
+    /// # }
+    /// # }
+    /// ```
+    pub fn sort_values_by_with_comments<F>(&mut self, comments: SortComments, mut compare: F)
+    where
+        F: FnMut(&Key, &Item, &Key, &Item) -> std::cmp::Ordering,
+    {
+        match comments {
+            SortComments::MoveWithEntry => self.sort_values_by_internal(&mut compare),
+            SortComments::KeepPosition => {
+                let mut prefixes = Vec::new();
+                visit_line_keys(self, &mut |key| {
+                    prefixes.push(key.leaf_decor().prefix().cloned());
+                });
+                self.sort_values_by_internal(&mut compare);
+                let mut prefixes = prefixes.into_iter();
+                visit_line_keys(self, &mut |key| {
+                    if let Some(prefix) = prefixes.next().flatten() {
+                        key.leaf_decor_mut().set_prefix_unchecked(prefix);
+                    } else {
+                        key.leaf_decor_mut().clear_prefix();
+                    }
+                });
+            }
+        }
+    }
+
     fn sort_values_by_internal<F>(&mut self, compare: &mut F)
     where
         F: FnMut(&Key, &Item, &Key, &Item) -> std::cmp::Ordering,
@@ -156,7 +225,7 @@ impl Table {
                 compare(key1, val1, key2, val2)
             };
 
-        self.items.sort_by(modified_cmp);
+        self.items_mut().sort_by(modified_cmp);
 
         for value in self.items.values_mut() {
             match value {
@@ -240,7 +309,7 @@ impl Table {
     /// Returns an accessor to a key's formatting
     pub fn key_mut(&mut self, key: &str) -> Option<KeyMut<'_>> {
         use indexmap::map::MutableKeys;
-        self.items
+        self.items_mut()
             .get_full_mut2(key)
             .map(|(_, key, _)| key.as_mut())
     }
@@ -250,13 +319,28 @@ impl Table {
         self.span.clone()
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
+    /// The key/value pairs, for changing them
+    ///
+    /// This forgets where the key/value lines were in the original document, so all changes to
+    /// the pairs of a table, or to what is nested in them, must go through here.
+    pub(crate) fn items_mut(&mut self) -> &mut KeyValuePairs {
+        self.body_span = None;
+        &mut self.items
+    }
+
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
         use indexmap::map::MutableKeys;
-        self.span = None;
-        self.decor.despan(input);
+        if !spans {
+            self.span = None;
+            self.body_span = None;
+        }
+        self.decor.despan(input, spans);
+        if let Some(header_decor) = &mut self.header_decor {
+            header_decor.despan(input, spans);
+        }
         for (key, value) in self.items.iter_mut2() {
-            key.despan(input);
-            value.despan(input);
+            key.despan(input, spans);
+            value.despan(input, spans);
         }
     }
 }
@@ -276,7 +360,7 @@ impl Table {
     pub fn iter_mut(&mut self) -> IterMut<'_> {
         use indexmap::map::MutableKeys;
         Box::new(
-            self.items
+            self.items_mut()
                 .iter_mut2()
                 .filter(|(_, value)| !value.is_none())
                 .map(|(key, value)| (key.as_mut(), value)),
@@ -295,13 +379,21 @@ impl Table {
 
     /// Clears the table, removing all key-value pairs. Keeps the allocated memory for reuse.
     pub fn clear(&mut self) {
-        self.items.clear();
+        self.items_mut().clear();
+    }
+
+    /// Shrinks the capacity of the table, and everything nested under it, as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.items.shrink_to_fit();
+        for value in self.items.values_mut() {
+            value.shrink_to_fit();
+        }
     }
 
     /// Gets the given key's corresponding entry in the Table for in-place manipulation.
     pub fn entry<'a>(&'a mut self, key: &str) -> Entry<'a> {
         // Accept a `&str` rather than an owned type to keep `InternalString`, well, internal
-        match self.items.entry(key.into()) {
+        match self.items_mut().entry(key.into()) {
             indexmap::map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry { entry }),
             indexmap::map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry { entry }),
         }
@@ -310,7 +402,7 @@ impl Table {
     /// Gets the given key's corresponding entry in the Table for in-place manipulation.
     pub fn entry_format<'a>(&'a mut self, key: &Key) -> Entry<'a> {
         // Accept a `&Key` to be consistent with `entry`
-        match self.items.entry(key.clone()) {
+        match self.items_mut().entry(key.clone()) {
             indexmap::map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry { entry }),
             indexmap::map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry { entry }),
         }
@@ -325,7 +417,7 @@ impl Table {
 
     /// Returns an optional mutable reference to an item given the key.
     pub fn get_mut<'a>(&'a mut self, key: &str) -> Option<&'a mut Item> {
-        self.items
+        self.items_mut()
             .get_mut(key)
             .and_then(|value| if !value.is_none() { Some(value) } else { None })
     }
@@ -344,13 +436,15 @@ impl Table {
     /// Return mutable references to the key-value pair stored for key, if it is present, else None.
     pub fn get_key_value_mut<'a>(&'a mut self, key: &str) -> Option<(KeyMut<'a>, &'a mut Item)> {
         use indexmap::map::MutableKeys;
-        self.items.get_full_mut2(key).and_then(|(_, key, value)| {
-            if !value.is_none() {
-                Some((key.as_mut(), value))
-            } else {
-                None
-            }
-        })
+        self.items_mut()
+            .get_full_mut2(key)
+            .and_then(|(_, key, value)| {
+                if !value.is_none() {
+                    Some((key.as_mut(), value))
+                } else {
+                    None
+                }
+            })
     }
 
     /// Returns true if the table contains an item with the given key.
@@ -393,7 +487,7 @@ impl Table {
     pub fn insert(&mut self, key: &str, item: Item) -> Option<Item> {
         use indexmap::map::MutableEntryKey;
         let key = Key::new(key);
-        match self.items.entry(key.clone()) {
+        match self.items_mut().entry(key.clone()) {
             indexmap::map::Entry::Occupied(mut entry) => {
                 entry.key_mut().fmt();
                 let old = std::mem::replace(entry.get_mut(), item);
@@ -409,7 +503,7 @@ impl Table {
     /// Inserts a key-value pair into the map.
     pub fn insert_formatted(&mut self, key: &Key, item: Item) -> Option<Item> {
         use indexmap::map::MutableEntryKey;
-        match self.items.entry(key.clone()) {
+        match self.items_mut().entry(key.clone()) {
             indexmap::map::Entry::Occupied(mut entry) => {
                 *entry.key_mut() = key.clone();
                 let old = std::mem::replace(entry.get_mut(), item);
@@ -422,14 +516,100 @@ impl Table {
         }
     }
 
+    /// Inserts a key-value pair into the map, unless it would replace an item of a different type
+    ///
+    /// This also refuses to replace an [implicit][Table::is_implicit] table as its sub-tables
+    /// would be lost.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let mut doc = "
+    /// name = 'foo'
+    /// [target.x86_64.dependencies]
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// let old = doc.try_insert("name", toml_edit::value("bar")).unwrap();
+    /// assert_eq!(old.unwrap().as_str(), Some("foo"));
+    ///
+    /// let err = doc.try_insert("name", toml_edit::value(1)).unwrap_err();
+    /// assert_eq!(err.to_string(), "cannot replace string `name` with integer");
+    ///
+    /// let err = doc.try_insert("target", toml_edit::table()).unwrap_err();
+    /// assert_eq!(err.to_string(), "cannot replace implicit table `target` with table");
+    /// # }
+    /// ```
+    pub fn try_insert(&mut self, key: &str, item: Item) -> Result<Option<Item>, InsertError> {
+        if let Some((existing_key, existing)) = self.items.get_key_value(key) {
+            let implicit = existing
+                .as_table()
+                .map(|t| t.is_implicit())
+                .unwrap_or(false);
+            if !existing.is_none() && (implicit || existing.type_name() != item.type_name()) {
+                return Err(InsertError::new(existing_key, existing, item));
+            }
+        }
+        Ok(self.insert(key, item))
+    }
+
+    /// Move all entries from `other` into this table, leaving `other` empty
+    ///
+    /// Entries keep their keys and [`Decor`] and new ones are added after the existing ones,
+    /// with tables written after the table they are within.
+    /// Returns the path to each key in both tables, like `["a", "b"]`, after handling it
+    /// according to `policy`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{ConflictPolicy, DocumentMut};
+    ///
+    /// let mut doc = "
+    /// [server]
+    /// host = 'localhost'
+    /// port = 80
+    /// ".parse::<DocumentMut>().unwrap();
+    /// let mut other = "
+    /// [server]
+    /// port = 8080  # from the environment
+    /// tls = true
+    /// ".parse::<DocumentMut>().unwrap();
+    ///
+    /// let conflicts = doc.append(&mut other, ConflictPolicy::Merge);
+    /// assert_eq!(conflicts, [["server", "port"]]);
+    /// assert_eq!(doc.to_string(), "
+    /// [server]
+    /// host = 'localhost'
+    /// port = 8080  # from the environment
+    /// tls = true
+    /// ");
+    /// assert!(other.is_empty());
+    /// # }
+    /// # }
+    /// ```
+    pub fn append(&mut self, other: &mut Table, policy: ConflictPolicy) -> Vec<Vec<String>> {
+        let mut conflicts = Vec::new();
+        append_pairs(
+            self.items_mut(),
+            std::mem::take(other.items_mut()),
+            policy,
+            &mut Vec::new(),
+            &mut conflicts,
+        );
+        conflicts
+    }
+
     /// Removes an item given the key.
     pub fn remove(&mut self, key: &str) -> Option<Item> {
-        self.items.shift_remove(key)
+        self.items_mut().shift_remove(key)
     }
 
     /// Removes a key from the map, returning the stored key and value if the key was previously in the map.
     pub fn remove_entry(&mut self, key: &str) -> Option<(Key, Item)> {
-        self.items.shift_remove_entry(key)
+        self.items_mut().shift_remove_entry(key)
     }
 
     /// Retains only the elements specified by the `keep` predicate.
@@ -442,7 +622,22 @@ impl Table {
     where
         F: FnMut(&str, &mut Item) -> bool,
     {
-        self.items.retain(|key, value| keep(key, value));
+        self.items_mut().retain(|key, value| keep(key, value));
+    }
+}
+
+impl std::fmt::Debug for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // `body_span` is bookkeeping for `DocumentMut::text_edits_from`
+        f.debug_struct("Table")
+            .field("decor", &self.decor)
+            .field("implicit", &self.implicit)
+            .field("dotted", &self.dotted)
+            .field("doc_position", &self.doc_position)
+            .field("span", &self.span)
+            .field("header_decor", &self.header_decor)
+            .field("items", &self.items)
+            .finish()
     }
 }
 
@@ -466,7 +661,7 @@ impl<K: Into<Key>, V: Into<Item>> Extend<(K, V)> for Table {
         for (key, value) in iter {
             let key = key.into();
             let value = value.into();
-            self.items.insert(key, value);
+            self.items_mut().insert(key, value);
         }
     }
 }
@@ -486,8 +681,12 @@ impl IntoIterator for Table {
     type Item = (InternalString, Item);
     type IntoIter = IntoIter;
 
-    fn into_iter(self) -> Self::IntoIter {
-        Box::new(self.items.into_iter().map(|(k, value)| (k.into(), value)))
+    fn into_iter(mut self) -> Self::IntoIter {
+        Box::new(
+            std::mem::take(&mut self.items)
+                .into_iter()
+                .map(|(k, value)| (k.into(), value)),
+        )
     }
 }
 
@@ -502,10 +701,81 @@ impl<'s> IntoIterator for &'s Table {
 
 pub(crate) type KeyValuePairs = IndexMap<Key, Item>;
 
+fn append_pairs(
+    items: &mut KeyValuePairs,
+    other: KeyValuePairs,
+    policy: ConflictPolicy,
+    path: &mut Vec<String>,
+    conflicts: &mut Vec<Vec<String>>,
+) {
+    use indexmap::map::MutableKeys;
+    for (key, mut item) in other {
+        if item.is_none() {
+            continue;
+        }
+        // Positions are from the other document
+        clear_positions(&mut item);
+        let index = match items.get_index_of(&key) {
+            Some(index) if !items[index].is_none() => index,
+            _ => {
+                items.insert(key, item);
+                continue;
+            }
+        };
+
+        path.push(key.get().to_owned());
+        let (existing_key, existing) = items.get_index_mut2(index).expect("index is present");
+        match (policy, existing, item) {
+            (ConflictPolicy::Merge, Item::Table(existing), Item::Table(mut other)) => {
+                if !other.is_implicit() {
+                    existing.set_implicit(false);
+                }
+                let other = std::mem::take(&mut other.items);
+                append_pairs(existing.items_mut(), other, policy, path, conflicts);
+            }
+            (
+                ConflictPolicy::Merge,
+                Item::Value(Value::InlineTable(existing)),
+                Item::Value(Value::InlineTable(mut other)),
+            ) => {
+                let other = std::mem::take(&mut other.items);
+                append_pairs(&mut existing.items, other, policy, path, conflicts);
+                existing.clear_value_whitespace();
+            }
+            (ConflictPolicy::Merge, Item::ArrayOfTables(existing), Item::ArrayOfTables(other)) => {
+                existing.extend(other);
+            }
+            (ConflictPolicy::Keep, _, _) => {
+                conflicts.push(path.clone());
+            }
+            (ConflictPolicy::Replace | ConflictPolicy::Merge, existing, item) => {
+                conflicts.push(path.clone());
+                *existing_key = key;
+                *existing = item;
+            }
+        }
+        path.pop();
+    }
+}
+
+fn clear_positions(item: &mut Item) {
+    let tables: Box<dyn Iterator<Item = &mut Table>> = match item {
+        Item::Table(table) => Box::new(std::iter::once(table)),
+        Item::ArrayOfTables(array) => Box::new(array.iter_mut()),
+        Item::None | Item::Value(_) => return,
+    };
+    for table in tables {
+        table.doc_position = None;
+        for (_, item) in table.items_mut().iter_mut() {
+            clear_positions(item);
+        }
+    }
+}
+
 fn decorate_table(table: &mut Table) {
     use indexmap::map::MutableKeys;
     for (mut key, value) in table
-        .items
+        .items_mut()
         .iter_mut2()
         .filter(|(_, value)| value.is_value())
         .map(|(key, value)| (key.as_mut(), value.as_value_mut().unwrap()))
@@ -783,3 +1053,17 @@ impl<'a> VacantEntry<'a> {
         entry.insert(value)
     }
 }
+
+/// Visit the keys of each line under `table`'s header, in order, which hold the comments above
+/// the line
+fn visit_line_keys(table: &mut Table, callback: &mut dyn FnMut(&mut Key)) {
+    use indexmap::map::MutableKeys;
+
+    for (key, item) in table.items.iter_mut2() {
+        match item {
+            Item::Value(_) => callback(key),
+            Item::Table(child) if child.is_dotted() => visit_line_keys(child, callback),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{Decor, Item, Table, Value};
+
+/// How a table is written, see [`Item::table_kind`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TableKind {
+    /// Under its own `[table]` header
+    Standard,
+    /// Under a `[[array]]` header, as one of an [`ArrayOfTables`][crate::ArrayOfTables]
+    ArrayElement,
+    /// As a `{ .. }` value
+    Inline,
+    /// Without a header of its own, either through dotted keys like `a.b = 1` or as an implicit
+    /// table like `a` in `[a.b]`
+    DottedImplicit,
+}
+
+/// Why converting a table to another [`TableKind`] changes more than how it is written, see
+/// [`Item::conversion_losses`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ConversionLoss {
+    /// An array of tables holds this many tables, but only one fits in any other kind of table
+    MultipleTables(usize),
+    /// Comments have nowhere to go, like those on a header when there is no header or those
+    /// within a table when it becomes inline
+    Comments,
+    /// Tables under it with headers of their own would become inline, or can't follow dotted
+    /// keys
+    NestedHeaders,
+    /// Without a header or values, an empty table isn't written at all
+    Empty,
+}
+
+impl Display for ConversionLoss {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::MultipleTables(count) => {
+                write!(f, "only one of {count} tables in the array can be kept")
+            }
+            Self::Comments => f.write_str("comments would be dropped"),
+            Self::NestedHeaders => f.write_str("tables under it would lose their headers"),
+            Self::Empty => f.write_str("an empty table without a header is not written"),
+        }
+    }
+}
+
+impl Item {
+    /// How the table in this item is written, or `None` if it isn't a table
+    ///
+    /// Each table of an [`Item::ArrayOfTables`] is an [`TableKind::ArrayElement`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::TableKind;
+    ///
+    /// let doc = "
+    /// package.name = 'app'
+    /// dependencies = { serde = '1' }
+    ///
+    /// [target.'cfg(unix)'.dependencies]
+    ///
+    /// [[bin]]
+    /// name = 'cli'
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// assert_eq!(doc["package"].table_kind(), Some(TableKind::DottedImplicit));
+    /// assert_eq!(doc["dependencies"].table_kind(), Some(TableKind::Inline));
+    /// assert_eq!(doc["target"].table_kind(), Some(TableKind::DottedImplicit));
+    /// assert_eq!(doc["target"]["cfg(unix)"]["dependencies"].table_kind(), Some(TableKind::Standard));
+    /// assert_eq!(doc["bin"].table_kind(), Some(TableKind::ArrayElement));
+    /// assert_eq!(doc["bin"][0]["name"].table_kind(), None);
+    /// # }
+    /// ```
+    pub fn table_kind(&self) -> Option<TableKind> {
+        match self {
+            Item::Table(table) => Some(standard_kind(table)),
+            Item::ArrayOfTables(_) => Some(TableKind::ArrayElement),
+            Item::Value(Value::InlineTable(_)) => Some(TableKind::Inline),
+            Item::None | Item::Value(_) => None,
+        }
+    }
+
+    /// What would be lost converting the table in this item to `kind`, beyond whitespace
+    ///
+    /// No losses means the conversion keeps everything that is written.  This doesn't apply to
+    /// items that aren't tables, which have nothing to convert.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{ConversionLoss, TableKind};
+    ///
+    /// let doc = "
+    /// [package]
+    /// name = 'app' # the crate name
+    ///
+    /// [[bin]]
+    /// name = 'cli'
+    ///
+    /// [[bin]]
+    /// name = 'server'
+    /// ".parse::<toml_edit::DocumentMut>().unwrap();
+    ///
+    /// assert_eq!(doc["package"].conversion_losses(TableKind::DottedImplicit), []);
+    /// assert_eq!(doc["package"].conversion_losses(TableKind::Inline), [ConversionLoss::Comments]);
+    /// assert_eq!(
+    ///     doc["bin"].conversion_losses(TableKind::Standard),
+    ///     [ConversionLoss::MultipleTables(2)]
+    /// );
+    /// # }
+    /// ```
+    pub fn conversion_losses(&self, kind: TableKind) -> Vec<ConversionLoss> {
+        let mut losses = Vec::new();
+        match self {
+            Item::Table(table) => table_losses(table, standard_kind(table), kind, &mut losses),
+            Item::ArrayOfTables(array) => {
+                if kind != TableKind::ArrayElement && array.len() > 1 {
+                    losses.push(ConversionLoss::MultipleTables(array.len()));
+                }
+                for table in array.iter() {
+                    table_losses(table, TableKind::ArrayElement, kind, &mut losses);
+                }
+            }
+            Item::Value(Value::InlineTable(table)) if kind != TableKind::Inline => {
+                // `a = { .. } # comment` has no line left for the comment
+                if has_comment(table.decor()) {
+                    losses.push(ConversionLoss::Comments);
+                }
+                if kind == TableKind::DottedImplicit && table.is_empty() {
+                    losses.push(ConversionLoss::Empty);
+                }
+            }
+            Item::None | Item::Value(_) => {}
+        }
+        losses.sort();
+        losses.dedup();
+        losses
+    }
+}
+
+fn standard_kind(table: &Table) -> TableKind {
+    if is_headed(table) {
+        TableKind::Standard
+    } else {
+        TableKind::DottedImplicit
+    }
+}
+
+fn table_losses(table: &Table, from: TableKind, to: TableKind, losses: &mut Vec<ConversionLoss>) {
+    if from == to {
+        return;
+    }
+    let has_values = !table.get_values().is_empty();
+    let has_headers = has_headed(table);
+    match to {
+        TableKind::Standard | TableKind::ArrayElement => {}
+        TableKind::Inline => {
+            if has_headers {
+                losses.push(ConversionLoss::NestedHeaders);
+            }
+            if has_comment(&table.decor) || has_inner_comment(table) {
+                losses.push(ConversionLoss::Comments);
+            }
+        }
+        TableKind::DottedImplicit => {
+            if from != TableKind::DottedImplicit && has_comment(&table.decor) {
+                losses.push(ConversionLoss::Comments);
+            }
+            if has_values && has_headers {
+                losses.push(ConversionLoss::NestedHeaders);
+            }
+            if table.is_empty() {
+                losses.push(ConversionLoss::Empty);
+            }
+        }
+    }
+}
+
+/// Whether `table` is written under a header of its own
+///
+/// An implicit table still gets a header once it has values.
+fn is_headed(table: &Table) -> bool {
+    let is_hidden = table.is_implicit() && table.get_values().is_empty();
+    !table.is_dotted() && !is_hidden
+}
+
+/// Whether any table under `table` is written under a header of its own
+fn has_headed(table: &Table) -> bool {
+    table.items.values().any(|item| match item {
+        Item::Table(child) => is_headed(child) || has_headed(child),
+        Item::ArrayOfTables(_) => true,
+        Item::None | Item::Value(_) => false,
+    })
+}
+
+/// Whether the keys, values, or tables under `table` have comments
+fn has_inner_comment(table: &Table) -> bool {
+    table.items.iter().any(|(key, item)| {
+        has_comment(key.leaf_decor())
+            || has_comment(key.dotted_decor())
+            || match item {
+                Item::None => false,
+                Item::Value(value) => has_comment(value.decor()),
+                Item::Table(child) => has_comment(&child.decor) || has_inner_comment(child),
+                Item::ArrayOfTables(array) => array
+                    .iter()
+                    .any(|child| has_comment(&child.decor) || has_inner_comment(child)),
+            }
+    })
+}
+
+fn has_comment(decor: &Decor) -> bool {
+    [decor.prefix(), decor.suffix()]
+        .into_iter()
+        .flatten()
+        .any(|raw| raw.as_str().map(|s| s.contains('#')).unwrap_or(false))
+}
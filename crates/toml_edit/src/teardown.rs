@@ -0,0 +1,75 @@
+use crate::{Array, ArrayOfTables, InlineTable, Item, Table, Value};
+
+// Dropping a container drops its children before returning, so a tree nested deeper than the
+// stack allows would overflow it while being dropped, even if it couldn't have been parsed.
+// Instead, the children of nested containers are moved onto a stack on the heap so each
+// container is empty by the time it is dropped.
+
+impl Drop for Array {
+    fn drop(&mut self) {
+        if !self.values.iter().all(is_shallow) {
+            drop_items(std::mem::take(&mut self.values));
+        }
+    }
+}
+
+impl Drop for InlineTable {
+    fn drop(&mut self) {
+        if !self.items.values().all(is_shallow) {
+            drop_items(std::mem::take(&mut self.items).into_values());
+        }
+    }
+}
+
+impl Drop for Table {
+    fn drop(&mut self) {
+        if !self.items.values().all(is_shallow) {
+            drop_items(std::mem::take(&mut self.items).into_values());
+        }
+    }
+}
+
+impl Drop for ArrayOfTables {
+    fn drop(&mut self) {
+        if !self.values.iter().all(is_shallow) {
+            drop_items(std::mem::take(&mut self.values));
+        }
+    }
+}
+
+/// Drop `items` one level of nesting at a time
+fn drop_items(items: impl IntoIterator<Item = Item>) {
+    let mut stack = items.into_iter().collect::<Vec<_>>();
+    while let Some(mut item) = stack.pop() {
+        match &mut item {
+            Item::None => {}
+            Item::Value(value) => take_value_children(value, &mut stack),
+            Item::Table(table) => stack.extend(std::mem::take(&mut table.items).into_values()),
+            Item::ArrayOfTables(array) => stack.extend(std::mem::take(&mut array.values)),
+        }
+    }
+}
+
+fn take_value_children(value: &mut Value, stack: &mut Vec<Item>) {
+    match value {
+        Value::Array(array) => stack.extend(std::mem::take(&mut array.values)),
+        Value::InlineTable(table) => stack.extend(std::mem::take(&mut table.items).into_values()),
+        Value::String(_)
+        | Value::Integer(_)
+        | Value::Float(_)
+        | Value::Boolean(_)
+        | Value::Datetime(_) => {}
+    }
+}
+
+/// Whether dropping `item` can't recurse any further
+fn is_shallow(item: &Item) -> bool {
+    match item {
+        Item::None => true,
+        Item::Value(Value::Array(array)) => array.values.is_empty(),
+        Item::Value(Value::InlineTable(table)) => table.items.is_empty(),
+        Item::Value(_) => true,
+        Item::Table(table) => table.items.is_empty(),
+        Item::ArrayOfTables(array) => array.values.is_empty(),
+    }
+}
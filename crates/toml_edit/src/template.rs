@@ -0,0 +1,328 @@
+//! Fill in placeholders within a TOML template
+//!
+//! A value is a placeholder when the comment on the line before its key, or after the value,
+//! is `# @placeholder <type>`.  The type is one of `string`, `integer`, `float`, `boolean`,
+//! `datetime`, `array`, `table`, or `any`, see [`Schema`].  Other comments on the lines before
+//! the key become the [description][Schema::description].
+//!
+//! [`Template::fill`] replaces every placeholder with a substitution of the expected type,
+//! keeping the formatting of the value it replaces.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(all(feature = "parse", feature = "display"))] {
+//! use toml_edit::template::Template;
+//!
+//! let template = r#"
+//! [package]
+//! ## Name of the package
+//! ## @placeholder string
+//! name = ""
+//! version = "0.1.0"
+//!
+//! [server]
+//! port = 0  # @placeholder integer
+//! "#;
+//! let template = Template::new(template.parse().unwrap()).unwrap();
+//! assert_eq!(template.placeholders()[0].key(), "package.name");
+//! assert_eq!(
+//!     template.placeholders()[0].schema().description(),
+//!     Some("Name of the package")
+//! );
+//!
+//! let doc = template.fill([("package.name", "foo".into()), ("server.port", 8080.into())]).unwrap();
+//! assert_eq!(doc.to_string(), r#"
+//! [package]
+//! ## Name of the package
+//! name = "foo"
+//! version = "0.1.0"
+//!
+//! [server]
+//! port = 8080
+//! "#);
+//!
+//! let err = template.fill([("package.name", "foo".into()), ("server.port", "80".into())]);
+//! assert_eq!(err.unwrap_err().to_string(), "`server.port` expects integer, found string");
+//! # }
+//! ```
+
+use indexmap::IndexMap;
+
+use crate::schema::Schema;
+use crate::{Decor, DocumentMut, Item, PathSegment, Table, Value};
+
+const MARKER: &str = "@placeholder";
+
+/// A document with placeholders to fill in, see the [module docs][self]
+#[derive(Clone, Debug)]
+pub struct Template {
+    doc: DocumentMut,
+    placeholders: Vec<Placeholder>,
+}
+
+impl Template {
+    /// Find the placeholders in `doc`
+    ///
+    /// The `@placeholder` comments are removed from the document.
+    pub fn new(mut doc: DocumentMut) -> Result<Self, TemplateError> {
+        let mut placeholders = Vec::new();
+        find_in_table(doc.as_table_mut(), &mut Vec::new(), &mut placeholders)?;
+        Ok(Self { doc, placeholders })
+    }
+
+    /// The placeholders, in document order
+    pub fn placeholders(&self) -> &[Placeholder] {
+        &self.placeholders
+    }
+
+    /// The template without its `@placeholder` comments
+    pub fn as_document(&self) -> &DocumentMut {
+        &self.doc
+    }
+
+    /// A copy of the template with each placeholder replaced
+    ///
+    /// `substitutions` are looked up by [`Placeholder::key`].  Every placeholder must have a
+    /// substitution of its type and every substitution must be for a placeholder.
+    pub fn fill<K: Into<String>>(
+        &self,
+        substitutions: impl IntoIterator<Item = (K, Value)>,
+    ) -> Result<DocumentMut, TemplateError> {
+        let mut substitutions = substitutions
+            .into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect::<IndexMap<String, Value>>();
+        let mut doc = self.doc.clone();
+        for placeholder in &self.placeholders {
+            let error = |kind| TemplateError {
+                key: placeholder.key.clone(),
+                kind,
+            };
+            let mut value = substitutions
+                .shift_remove(&placeholder.key)
+                .ok_or_else(|| error(TemplateErrorKind::Missing))?;
+            let kind = placeholder.schema.kind();
+            if !kind.matches(&value) {
+                return Err(error(TemplateErrorKind::Mismatch {
+                    expected: kind.type_name(),
+                    found: value.type_name(),
+                }));
+            }
+
+            let target = lookup(doc.as_table_mut(), &placeholder.path)
+                .expect("placeholders are found within the template");
+            *value.decor_mut() = target.decor().clone();
+            *target = value;
+        }
+        if let Some(key) = substitutions.into_keys().next() {
+            return Err(TemplateError {
+                key,
+                kind: TemplateErrorKind::Unused,
+            });
+        }
+        Ok(doc)
+    }
+}
+
+/// A value to be filled in, see [`Template::placeholders`]
+#[derive(Clone, Debug)]
+pub struct Placeholder {
+    path: Vec<PathSegment>,
+    key: String,
+    schema: Schema,
+}
+
+impl Placeholder {
+    /// Where the value is within the document
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The [path][Placeholder::path] as a dotted key, with array indices in brackets
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The expected type and description of the value
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+}
+
+/// A template could not be read or filled in, see [`Template::new`] and [`Template::fill`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateError {
+    key: String,
+    kind: TemplateErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TemplateErrorKind {
+    UnknownType(String),
+    Missing,
+    Mismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    Unused,
+}
+
+impl TemplateError {
+    /// The dotted key of the placeholder or substitution
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            TemplateErrorKind::UnknownType(name) => {
+                write!(f, "`{}` has unknown placeholder type `{name}`", self.key)
+            }
+            TemplateErrorKind::Missing => write!(f, "`{}` has no substitution", self.key),
+            TemplateErrorKind::Mismatch { expected, found } => {
+                write!(f, "`{}` expects {expected}, found {found}", self.key)
+            }
+            TemplateErrorKind::Unused => write!(f, "`{}` is not a placeholder", self.key),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+fn find_in_table(
+    table: &mut Table,
+    path: &mut Vec<PathSegment>,
+    placeholders: &mut Vec<Placeholder>,
+) -> Result<(), TemplateError> {
+    for (mut key, item) in table.iter_mut() {
+        path.push(PathSegment::Key(key.get().to_owned()));
+        match item {
+            Item::Value(value) => {
+                let from_key = take_marker(key.leaf_decor_mut(), path)?;
+                let from_value = take_marker(value.decor_mut(), path)?;
+                if let Some(mut schema) = from_key.or(from_value) {
+                    if let Some(description) = description(key.leaf_decor()) {
+                        schema = schema.with_description(description);
+                    }
+                    placeholders.push(Placeholder {
+                        path: path.clone(),
+                        key: path_string(path),
+                        schema,
+                    });
+                }
+            }
+            Item::Table(table) => find_in_table(table, path, placeholders)?,
+            Item::ArrayOfTables(array) => {
+                for (i, table) in array.iter_mut().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    find_in_table(table, path, placeholders)?;
+                    path.pop();
+                }
+            }
+            Item::None => {}
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+fn lookup<'t>(table: &'t mut Table, path: &[PathSegment]) -> Option<&'t mut Value> {
+    let (PathSegment::Key(first), rest) = path.split_first()? else {
+        return None;
+    };
+    let item = rest
+        .iter()
+        .try_fold(table.get_mut(first)?, |item, segment| match segment {
+            PathSegment::Key(key) => item.get_mut(key.as_str()),
+            PathSegment::Index(index) => item.get_mut(*index),
+        })?;
+    item.as_value_mut()
+}
+
+/// Remove the `@placeholder` comment from `decor`, returning its type
+fn take_marker(decor: &mut Decor, path: &[PathSegment]) -> Result<Option<Schema>, TemplateError> {
+    let mut found = None;
+    if let Some(prefix) = decor.prefix().and_then(|p| p.as_str()) {
+        if let Some((line, name)) = prefix.split_inclusive('\n').find_map(|line| {
+            let name = marker_type(line)?;
+            Some((line, name))
+        }) {
+            let remaining = prefix.replacen(line, "", 1);
+            found = Some(name.to_owned());
+            decor.set_prefix_unchecked(remaining);
+        }
+    }
+    if found.is_none() {
+        if let Some(name) = decor
+            .suffix()
+            .and_then(|s| s.as_str())
+            .and_then(marker_type)
+        {
+            found = Some(name.to_owned());
+            decor.set_suffix_unchecked("");
+        }
+    }
+
+    let Some(name) = found else {
+        return Ok(None);
+    };
+    let schema = match name.as_str() {
+        "string" => Schema::string(),
+        "integer" => Schema::integer(),
+        "float" => Schema::float(),
+        "boolean" => Schema::boolean(),
+        "datetime" => Schema::datetime(),
+        "array" => Schema::array(Schema::any()),
+        "table" => Schema::table().into(),
+        "any" => Schema::any(),
+        _ => {
+            return Err(TemplateError {
+                key: path_string(path),
+                kind: TemplateErrorKind::UnknownType(name),
+            })
+        }
+    };
+    Ok(Some(schema))
+}
+
+/// The type named by a `# @placeholder <type>` comment
+fn marker_type(line: &str) -> Option<&str> {
+    let comment = line.trim().strip_prefix('#')?.trim_start();
+    let name = comment.strip_prefix(MARKER)?;
+    if !name.starts_with([' ', '\t']) {
+        return None;
+    }
+    Some(name.trim())
+}
+
+/// The comments on the lines before a key
+fn description(decor: &Decor) -> Option<String> {
+    let prefix = decor.prefix()?.as_str()?;
+    let lines = prefix
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('#'))
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let description = lines.trim_matches('\n');
+    (!description.is_empty()).then(|| description.to_owned())
+}
+
+fn path_string(path: &[PathSegment]) -> String {
+    let mut string = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) => {
+                if !string.is_empty() {
+                    string.push('.');
+                }
+                string.push_str(key);
+            }
+            PathSegment::Index(index) => string.push_str(&format!("[{index}]")),
+        }
+    }
+    string
+}
@@ -0,0 +1,129 @@
+use std::ops::Range;
+
+use crate::parser::lexer::Token;
+use crate::parser::Source;
+use crate::Document;
+
+/// The tokens of a [`Document`], each with where it is in the raw document, see
+/// [`Document::tokens_with_spans`]
+///
+/// This is a snapshot of the raw document, so it is unaffected by edits made after it was taken.
+/// Its [`Display`][std::fmt::Display] is one token per line, for comparing against in tests.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TokenStream<'d> {
+    raw: &'d str,
+    tokens: Vec<(Token, Range<usize>)>,
+}
+
+impl<'d> TokenStream<'d> {
+    /// The tokens, in document order, ending with [`TokenKind::Eof`][crate::parser::lexer::TokenKind::Eof]
+    pub fn as_slice(&self) -> &[(Token, Range<usize>)] {
+        &self.tokens
+    }
+
+    /// Iterate over the tokens, in document order
+    pub fn iter(&self) -> std::slice::Iter<'_, (Token, Range<usize>)> {
+        self.tokens.iter()
+    }
+
+    /// Iterate over the tokens with the text of each
+    pub fn iter_text(&self) -> impl Iterator<Item = (Token, &'d str)> + '_ {
+        let raw = self.raw;
+        self.tokens
+            .iter()
+            .map(move |(token, span)| (*token, &raw[span.clone()]))
+    }
+
+    /// The number of tokens
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Whether there are no tokens
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// The tokens, in document order
+    pub fn into_vec(self) -> Vec<(Token, Range<usize>)> {
+        self.tokens
+    }
+}
+
+impl<'s, 'd> IntoIterator for &'s TokenStream<'d> {
+    type Item = &'s (Token, Range<usize>);
+    type IntoIter = std::slice::Iter<'s, (Token, Range<usize>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'d> IntoIterator for TokenStream<'d> {
+    type Item = (Token, Range<usize>);
+    type IntoIter = std::vec::IntoIter<(Token, Range<usize>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tokens.into_iter()
+    }
+}
+
+impl std::fmt::Display for TokenStream<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (token, span) in &self.tokens {
+            writeln!(
+                f,
+                "{:?} {:?} {:?}",
+                token.kind(),
+                span,
+                &self.raw[span.clone()]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: AsRef<str>> Document<S> {
+    /// Lex the raw document, pairing each token with its byte range
+    ///
+    /// Tokens are lexed like [`Document::parse`] does, so a lone carriage return is always a
+    /// [`Newline`][crate::parser::lexer::TokenKind::Newline].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use toml_edit::parser::lexer::TokenKind;
+    /// use toml_edit::Document;
+    ///
+    /// let doc = Document::parse("a = 'b' # c\n").unwrap();
+    /// let tokens = doc.tokens_with_spans();
+    ///
+    /// let comment = tokens
+    ///     .iter()
+    ///     .find(|(token, _)| token.kind() == TokenKind::Comment)
+    ///     .unwrap();
+    /// assert_eq!(comment.1, 8..11);
+    ///
+    /// assert_eq!(tokens.to_string(), r##"Atom 0..1 "a"
+    /// Whitespace 1..2 " "
+    /// Equals 2..3 "="
+    /// Whitespace 3..4 " "
+    /// LiteralString 4..7 "'b'"
+    /// Whitespace 7..8 " "
+    /// Comment 8..11 "# c"
+    /// Newline 11..12 "\n"
+    /// Eof 12..12 ""
+    /// "##);
+    /// ```
+    pub fn tokens_with_spans(&self) -> TokenStream<'_> {
+        let raw = self.raw();
+        let tokens = Source::new(raw)
+            .lex()
+            .map(|token| {
+                let span = token.span();
+                (token, span.start()..span.end())
+            })
+            .collect();
+        TokenStream { raw, tokens }
+    }
+}
@@ -193,18 +193,24 @@ impl Value {
     /// # #[cfg(feature = "display")] {
     /// let mut v = toml_edit::Value::from(42);
     /// assert_eq!(&v.to_string(), "42");
-    /// let d = v.decorated(" ", " ");
+    /// let d = v.decorated(" ", " ").unwrap();
     /// assert_eq!(&d.to_string(), " 42 ");
     /// # }
     /// ```
-    pub fn decorated(mut self, prefix: impl Into<RawString>, suffix: impl Into<RawString>) -> Self {
-        self.decorate(prefix, suffix);
-        self
+    ///
+    /// See [`Decor::set_prefix`] for what is allowed.
+    pub fn decorated(
+        mut self,
+        prefix: impl Into<RawString>,
+        suffix: impl Into<RawString>,
+    ) -> Result<Self, crate::StructureError> {
+        *self.decor_mut() = Decor::new(prefix, suffix)?;
+        Ok(self)
     }
 
     pub(crate) fn decorate(&mut self, prefix: impl Into<RawString>, suffix: impl Into<RawString>) {
         let decor = self.decor_mut();
-        *decor = Decor::new(prefix, suffix);
+        *decor = Decor::new_unchecked(prefix, suffix);
     }
 
     /// The location within the original document
@@ -222,16 +228,109 @@ impl Value {
         }
     }
 
-    pub(crate) fn despan(&mut self, input: &str) {
+    pub(crate) fn despan(&mut self, input: &std::sync::Arc<str>, spans: bool) {
         match self {
-            Value::String(f) => f.despan(input),
-            Value::Integer(f) => f.despan(input),
-            Value::Float(f) => f.despan(input),
-            Value::Boolean(f) => f.despan(input),
-            Value::Datetime(f) => f.despan(input),
-            Value::Array(a) => a.despan(input),
-            Value::InlineTable(t) => t.despan(input),
+            Value::String(f) => f.despan(input, spans),
+            Value::Integer(f) => f.despan(input, spans),
+            Value::Float(f) => f.despan(input, spans),
+            Value::Boolean(f) => f.despan(input, spans),
+            Value::Datetime(f) => f.despan(input, spans),
+            Value::Array(a) => a.despan(input, spans),
+            Value::InlineTable(t) => t.despan(input, spans),
+        }
+    }
+
+    /// Whether the values hold the same data, ignoring formatting
+    #[cfg(any(feature = "watch", all(feature = "parse", feature = "display")))]
+    pub(crate) fn same_data(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::String(a), Value::String(b)) => a.value() == b.value(),
+            (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+            (Value::Float(a), Value::Float(b)) => {
+                let (a, b) = (a.value(), b.value());
+                a.to_bits() == b.to_bits() || (a.is_nan() && b.is_nan())
+            }
+            (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+            (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.same_data(b))
+            }
+            (Value::InlineTable(a), Value::InlineTable(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .all(|(key, a)| b.get(key).map(|b| a.same_data(b)).unwrap_or(false))
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "parse")]
+impl Value {
+    /// An integer written as `repr`, like `0x2A` for `42`
+    ///
+    /// Errors if `repr` is not an integer equal to `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::Value;
+    ///
+    /// let value = Value::integer_with_repr(42, "0x2A").unwrap();
+    /// assert_eq!(value.as_integer(), Some(42));
+    /// assert_eq!(value.to_string(), "0x2A");
+    ///
+    /// let err = Value::integer_with_repr(42, "0x2B").unwrap_err();
+    /// assert_eq!(err.message(), "`0x2B` is 43, not 42");
+    /// assert!(Value::integer_with_repr(42, "42.0").is_err());
+    /// # }
+    /// ```
+    pub fn integer_with_repr(value: i64, repr: &str) -> Result<Self, crate::TomlError> {
+        let mut formatted = Formatted::new(value);
+        formatted.set_repr(repr)?;
+        let parsed = *formatted.value();
+        if parsed != value {
+            return Err(crate::TomlError::custom(
+                format!("`{repr}` is {parsed}, not {value}"),
+                Some(0..repr.len()),
+            ));
+        }
+        Ok(Value::Integer(formatted))
+    }
+
+    /// A float written as `repr`, like `1e3` for `1000.0`
+    ///
+    /// Errors if `repr` is not a float equal to `value`, including its sign.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::Value;
+    ///
+    /// let value = Value::float_with_repr(1000.0, "1_000.0").unwrap();
+    /// assert_eq!(value.to_string(), "1_000.0");
+    /// assert!(Value::float_with_repr(f64::NAN, "nan").is_ok());
+    /// assert!(Value::float_with_repr(0.0, "-0.0").is_err());
+    /// # }
+    /// ```
+    pub fn float_with_repr(value: f64, repr: &str) -> Result<Self, crate::TomlError> {
+        let mut formatted = Formatted::new(value);
+        formatted.set_repr(repr)?;
+        let parsed = *formatted.value();
+        let same = if parsed.is_nan() {
+            value.is_nan() && parsed.is_sign_negative() == value.is_sign_negative()
+        } else {
+            parsed.to_bits() == value.to_bits()
+        };
+        if !same {
+            return Err(crate::TomlError::custom(
+                format!("`{repr}` is {parsed:?}, not {value:?}"),
+                Some(0..repr.len()),
+            ));
         }
+        Ok(Value::Float(formatted))
     }
 }
 
@@ -243,13 +342,13 @@ impl FromStr for Value {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let source = toml_parse::Source::new(s);
         let mut sink = crate::error::TomlSink::<Option<_>>::new(source);
-        let mut value = crate::parser::parse_value(source, &mut sink);
+        let mut value = crate::parser::to_value(source, &mut sink);
         if let Some(err) = sink.into_inner() {
             Err(err)
         } else {
             // Only take the repr and not decor, as its probably not intended
             value.decor_mut().clear();
-            value.despan(s);
+            value.despan(&std::sync::Arc::from(s), false);
             Ok(value)
         }
     }
@@ -375,6 +474,8 @@ pub(crate) const DEFAULT_VALUE_DECOR: (&str, &str) = (" ", "");
 pub(crate) const DEFAULT_TRAILING_VALUE_DECOR: (&str, &str) = (" ", " ");
 // `[value1, value2]`
 pub(crate) const DEFAULT_LEADING_VALUE_DECOR: (&str, &str) = ("", "");
+// `[\n    value1,\n    value2,\n]`
+pub(crate) const DEFAULT_MULTILINE_VALUE_DECOR: (&str, &str) = ("\n    ", "");
 
 #[cfg(test)]
 #[cfg(feature = "parse")]
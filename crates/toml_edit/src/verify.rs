@@ -0,0 +1,247 @@
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{DocumentMut, Item, PathSegment, TableLike};
+
+/// A [`DocumentMut`] does not survive being written out and parsed again, see
+/// [`DocumentMut::verify`]
+#[derive(Debug, Clone)]
+pub struct VerifyError {
+    kind: VerifyErrorKind,
+}
+
+#[derive(Debug, Clone)]
+enum VerifyErrorKind {
+    Parse(crate::TomlError),
+    Mismatch(Vec<Discrepancy>),
+}
+
+impl VerifyError {
+    /// The written out document is not valid TOML
+    pub fn parse_error(&self) -> Option<&crate::TomlError> {
+        match &self.kind {
+            VerifyErrorKind::Parse(err) => Some(err),
+            VerifyErrorKind::Mismatch(_) => None,
+        }
+    }
+
+    /// Where the written out document parses to different data, in document order
+    ///
+    /// This is empty when there is a [`VerifyError::parse_error`].
+    pub fn discrepancies(&self) -> &[Discrepancy] {
+        match &self.kind {
+            VerifyErrorKind::Parse(_) => &[],
+            VerifyErrorKind::Mismatch(discrepancies) => discrepancies,
+        }
+    }
+}
+
+impl Display for VerifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.kind {
+            VerifyErrorKind::Parse(err) => write!(f, "written document does not parse: {err}"),
+            VerifyErrorKind::Mismatch(discrepancies) => {
+                write!(f, "written document parses differently")?;
+                for discrepancy in discrepancies {
+                    write!(f, "\n{discrepancy}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl StdError for VerifyError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.kind {
+            VerifyErrorKind::Parse(err) => Some(err),
+            VerifyErrorKind::Mismatch(_) => None,
+        }
+    }
+}
+
+/// An item that changed when written out and parsed again, see [`VerifyError::discrepancies`]
+#[derive(Debug, Clone)]
+pub struct Discrepancy {
+    path: Vec<PathSegment>,
+    expected: Option<Item>,
+    found: Option<Item>,
+}
+
+impl Discrepancy {
+    /// The location of the item
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The item in the [`DocumentMut`], if any
+    pub fn expected(&self) -> Option<&Item> {
+        self.expected.as_ref()
+    }
+
+    /// The item after parsing the written out document, if any
+    pub fn found(&self) -> Option<&Item> {
+        self.found.as_ref()
+    }
+}
+
+impl Display for Discrepancy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        "`".fmt(f)?;
+        for (i, segment) in self.path.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i != 0 => write!(f, ".{key}")?,
+                PathSegment::Key(key) => key.fmt(f)?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        let reason = match (&self.expected, &self.found) {
+            (Some(_), None) => "is missing",
+            (None, Some(_)) => "is unexpected",
+            _ => "changed",
+        };
+        write!(f, "` {reason}")
+    }
+}
+
+impl DocumentMut {
+    /// Check that the document can be written out and parsed back to the same data
+    ///
+    /// [`Decor`][crate::Decor] is only checked for what is valid somewhere as it is set, so a
+    /// document built from untrusted strings may still render as invalid TOML or as different
+    /// data.  Formatting differences are ignored, as are empty implicit tables and empty arrays of
+    /// tables as they are not written out.
+    ///
+    /// This re-parses the whole document, so it is best suited to tests, debug assertions, or
+    /// after building a document, rather than after each edit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use toml_edit::{value, DocumentMut};
+    ///
+    /// let mut doc = "a = 1\n".parse::<DocumentMut>().unwrap();
+    /// doc["b"] = value("two");
+    /// debug_assert!(doc.verify().is_ok());
+    ///
+    /// // The comment is missing a newline
+    /// let mut key = doc.key_mut("b").unwrap();
+    /// key.leaf_decor_mut().set_prefix("# the second").unwrap();
+    /// let err = doc.verify().unwrap_err();
+    /// assert_eq!(err.to_string(), "written document parses differently\n`b` is missing");
+    /// ```
+    pub fn verify(&self) -> std::result::Result<(), VerifyError> {
+        let reparsed = self
+            .to_string()
+            .parse::<DocumentMut>()
+            .map_err(|err| VerifyError {
+                kind: VerifyErrorKind::Parse(err),
+            })?;
+        let mut discrepancies = Vec::new();
+        compare_table(
+            self.as_table(),
+            reparsed.as_table(),
+            &mut Vec::new(),
+            &mut discrepancies,
+        );
+        if discrepancies.is_empty() {
+            Ok(())
+        } else {
+            Err(VerifyError {
+                kind: VerifyErrorKind::Mismatch(discrepancies),
+            })
+        }
+    }
+}
+
+fn compare_table(
+    expected: &dyn TableLike,
+    found: &dyn TableLike,
+    path: &mut Vec<PathSegment>,
+    discrepancies: &mut Vec<Discrepancy>,
+) {
+    for (key, expected_item) in expected.iter() {
+        path.push(PathSegment::Key(key.to_owned()));
+        match found.get(key) {
+            Some(found_item) => compare_item(expected_item, found_item, path, discrepancies),
+            None if is_unwritten(expected_item) => {}
+            None => discrepancies.push(Discrepancy {
+                path: path.clone(),
+                expected: Some(expected_item.clone()),
+                found: None,
+            }),
+        }
+        path.pop();
+    }
+    for (key, found_item) in found.iter() {
+        if expected.get(key).is_none() {
+            let mut path = path.clone();
+            path.push(PathSegment::Key(key.to_owned()));
+            discrepancies.push(Discrepancy {
+                path,
+                expected: None,
+                found: Some(found_item.clone()),
+            });
+        }
+    }
+}
+
+fn compare_item(
+    expected: &Item,
+    found: &Item,
+    path: &mut Vec<PathSegment>,
+    discrepancies: &mut Vec<Discrepancy>,
+) {
+    let same = match (expected, found) {
+        (Item::Table(expected), Item::Table(found)) => {
+            compare_table(expected, found, path, discrepancies);
+            true
+        }
+        (Item::Value(expected), Item::Value(found)) => expected.same_data(found),
+        (Item::ArrayOfTables(expected), Item::ArrayOfTables(found)) => {
+            for (i, expected_table) in expected.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                match found.get(i) {
+                    Some(found_table) => {
+                        compare_table(expected_table, found_table, path, discrepancies);
+                    }
+                    None => discrepancies.push(Discrepancy {
+                        path: path.clone(),
+                        expected: Some(Item::Table(expected_table.clone())),
+                        found: None,
+                    }),
+                }
+                path.pop();
+            }
+            for (i, found_table) in found.iter().enumerate().skip(expected.len()) {
+                let mut path = path.clone();
+                path.push(PathSegment::Index(i));
+                discrepancies.push(Discrepancy {
+                    path,
+                    expected: None,
+                    found: Some(Item::Table(found_table.clone())),
+                });
+            }
+            true
+        }
+        _ => false,
+    };
+    if !same {
+        discrepancies.push(Discrepancy {
+            path: path.clone(),
+            expected: Some(expected.clone()),
+            found: Some(found.clone()),
+        });
+    }
+}
+
+/// Items that are not written out, so can't be parsed back
+fn is_unwritten(item: &Item) -> bool {
+    match item {
+        Item::Table(table) if table.is_implicit() || table.is_dotted() => {
+            table.iter().all(|(_, item)| is_unwritten(item))
+        }
+        Item::ArrayOfTables(array) => array.is_empty(),
+        _ => false,
+    }
+}
@@ -0,0 +1,388 @@
+//! Reload a TOML file when it changes
+//!
+//! [`Watcher`] keeps the last successfully parsed version of a file.  Each [`Watcher::poll`]
+//! re-reads the file and, if its content changed, re-parses it and reports what changed with
+//! [`diff`].  [`Watcher::spawn`] polls on a background thread, calling back on each change.
+//!
+//! Files are polled rather than relying on platform notifications, so changes are seen within
+//! the [interval][Watcher::with_interval] and this works the same everywhere, including on
+//! network filesystems.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use toml_edit::watch::{Change, Watcher};
+//!
+//! let path = std::env::temp_dir().join(format!("toml_edit-watch-{}.toml", std::process::id()));
+//! std::fs::write(&path, "port = 8080\nhost = 'localhost'\n").unwrap();
+//!
+//! let mut watcher = Watcher::new(&path).unwrap();
+//! assert_eq!(watcher.document()["port"].as_integer(), Some(8080));
+//! assert!(watcher.poll().unwrap().is_empty());
+//!
+//! // Formatting-only edits are not changes
+//! std::fs::write(&path, "port = 8081  # moved\nhost = \"localhost\"\n").unwrap();
+//! let changes = watcher.poll().unwrap();
+//! assert_eq!(changes.len(), 1);
+//! assert!(matches!(&changes[0], Change::Modified { .. }));
+//! assert_eq!(changes[0].path_string(), "port");
+//! assert_eq!(watcher.document()["port"].as_integer(), Some(8081));
+//!
+//! // The last good document is kept on error
+//! std::fs::write(&path, "port = ").unwrap();
+//! assert!(watcher.poll().is_err());
+//! assert_eq!(watcher.document()["port"].as_integer(), Some(8081));
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{DocumentMut, Item, PathSegment, Table, TableLike};
+
+/// Watches a TOML file for changes, see the [module docs][self]
+#[derive(Debug)]
+pub struct Watcher {
+    path: PathBuf,
+    interval: Duration,
+    raw: String,
+    document: DocumentMut,
+}
+
+impl Watcher {
+    /// Read and parse the file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, WatchError> {
+        let path = path.into();
+        let raw = read(&path)?;
+        let document = parse(&path, &raw)?;
+        Ok(Self {
+            path,
+            interval: Duration::from_secs(1),
+            raw,
+            document,
+        })
+    }
+
+    /// How long [`Watcher::spawn`] waits between polls, defaulting to one second
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// The file being watched
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The last successfully parsed version of the file
+    pub fn document(&self) -> &DocumentMut {
+        &self.document
+    }
+
+    /// Re-read the file, returning what changed since the last successful poll
+    ///
+    /// Changes that only affect formatting, like comments or quoting, are not reported.  On
+    /// error, the previous [`Watcher::document`] is kept.
+    pub fn poll(&mut self) -> Result<Vec<Change>, WatchError> {
+        let raw = read(&self.path)?;
+        if raw == self.raw {
+            return Ok(Vec::new());
+        }
+        let document = parse(&self.path, &raw)?;
+        let changes = diff(self.document.as_table(), document.as_table());
+        self.raw = raw;
+        self.document = document;
+        Ok(changes)
+    }
+
+    /// Poll on a background thread until the returned [`WatchHandle`] is stopped or dropped
+    ///
+    /// `on_change` is called with the new document when there are [changes][Watcher::poll] and
+    /// with any errors.
+    pub fn spawn<F>(mut self, mut on_change: F) -> WatchHandle
+    where
+        F: FnMut(Result<(&DocumentMut, &[Change]), WatchError>) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                loop {
+                    std::thread::park_timeout(self.interval);
+                    if stop.load(Ordering::Acquire) {
+                        break;
+                    }
+                    match self.poll() {
+                        Ok(changes) if changes.is_empty() => {}
+                        Ok(changes) => on_change(Ok((&self.document, &changes))),
+                        Err(err) => on_change(Err(err)),
+                    }
+                }
+                self
+            })
+        };
+        WatchHandle {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, WatchError> {
+    std::fs::read_to_string(path).map_err(|err| WatchError {
+        path: path.to_owned(),
+        kind: WatchErrorKind::Io(err),
+    })
+}
+
+fn parse(path: &Path, raw: &str) -> Result<DocumentMut, WatchError> {
+    raw.parse::<DocumentMut>().map_err(|err| WatchError {
+        path: path.to_owned(),
+        kind: WatchErrorKind::Parse(err),
+    })
+}
+
+/// A [`Watcher`] running on a background thread, see [`Watcher::spawn`]
+#[derive(Debug)]
+pub struct WatchHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<std::thread::JoinHandle<Watcher>>,
+}
+
+impl WatchHandle {
+    /// Stop polling, returning the [`Watcher`]
+    ///
+    /// This waits for a call to `on_change` that is in progress to finish.
+    pub fn stop(mut self) -> Watcher {
+        self.signal();
+        let thread = self.thread.take().expect("only taken on stop");
+        match thread.join() {
+            Ok(watcher) => watcher,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+
+    fn signal(&self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = &self.thread {
+            thread.thread().unpark();
+        }
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.signal();
+    }
+}
+
+/// A [`Watcher`] failed to read or parse its file
+#[derive(Debug)]
+pub struct WatchError {
+    path: PathBuf,
+    kind: WatchErrorKind,
+}
+
+#[derive(Debug)]
+enum WatchErrorKind {
+    Io(std::io::Error),
+    Parse(crate::TomlError),
+}
+
+impl WatchError {
+    /// The file being watched
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The file could not be read
+    pub fn io_error(&self) -> Option<&std::io::Error> {
+        match &self.kind {
+            WatchErrorKind::Io(err) => Some(err),
+            WatchErrorKind::Parse(_) => None,
+        }
+    }
+
+    /// The file is not valid TOML
+    pub fn parse_error(&self) -> Option<&crate::TomlError> {
+        match &self.kind {
+            WatchErrorKind::Io(_) => None,
+            WatchErrorKind::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl std::fmt::Display for WatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            WatchErrorKind::Io(err) => {
+                write!(f, "failed to read `{}`: {err}", self.path.display())
+            }
+            WatchErrorKind::Parse(err) => {
+                write!(f, "failed to parse `{}`: {err}", self.path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for WatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            WatchErrorKind::Io(err) => Some(err),
+            WatchErrorKind::Parse(err) => Some(err),
+        }
+    }
+}
+
+/// A difference between two versions of a document, see [`diff`]
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
+pub enum Change {
+    /// An item only in the new version
+    Added {
+        /// Where the item is
+        path: Vec<PathSegment>,
+        /// The new item
+        item: Item,
+    },
+    /// An item only in the old version
+    Removed {
+        /// Where the item was
+        path: Vec<PathSegment>,
+        /// The old item
+        item: Item,
+    },
+    /// A value, or an item that changed type
+    Modified {
+        /// Where the item is
+        path: Vec<PathSegment>,
+        /// The item in the old version
+        old: Item,
+        /// The item in the new version
+        new: Item,
+    },
+}
+
+impl Change {
+    /// Where the change is within the document
+    pub fn path(&self) -> &[PathSegment] {
+        match self {
+            Self::Added { path, .. } | Self::Removed { path, .. } | Self::Modified { path, .. } => {
+                path
+            }
+        }
+    }
+
+    /// The [path][Change::path] as a dotted key, with array indices in brackets
+    pub fn path_string(&self) -> String {
+        let mut path = String::new();
+        for segment in self.path() {
+            match segment {
+                PathSegment::Key(key) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                }
+                PathSegment::Index(index) => path.push_str(&format!("[{index}]")),
+            }
+        }
+        path
+    }
+}
+
+/// Compare the data in two versions of a document, ignoring formatting
+///
+/// Tables, including inline tables, and arrays of tables are compared entry by entry while
+/// other arrays are compared as a whole.  Changes are in document order, with removals before
+/// additions within a table.
+///
+/// # Examples
+///
+/// ```rust
+/// use toml_edit::DocumentMut;
+///
+/// let old = "[server]\nport = 8080\nhost = 'a'\n".parse::<DocumentMut>().unwrap();
+/// let new = "server = { port = 8080, tls = true }\n".parse::<DocumentMut>().unwrap();
+/// let changes = toml_edit::watch::diff(old.as_table(), new.as_table());
+/// let paths = changes.iter().map(|c| c.path_string()).collect::<Vec<_>>();
+/// assert_eq!(paths, ["server.host", "server.tls"]);
+/// ```
+pub fn diff(old: &Table, new: &Table) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut path = Vec::new();
+    diff_table(old, new, &mut path, &mut changes);
+    changes
+}
+
+fn diff_table(
+    old: &dyn TableLike,
+    new: &dyn TableLike,
+    path: &mut Vec<PathSegment>,
+    changes: &mut Vec<Change>,
+) {
+    for (key, old_item) in old.iter() {
+        path.push(PathSegment::Key(key.to_owned()));
+        match new.get(key) {
+            Some(new_item) => diff_item(old_item, new_item, path, changes),
+            None => changes.push(Change::Removed {
+                path: path.clone(),
+                item: old_item.clone(),
+            }),
+        }
+        path.pop();
+    }
+    for (key, new_item) in new.iter() {
+        if old.get(key).is_none() {
+            let mut path = path.clone();
+            path.push(PathSegment::Key(key.to_owned()));
+            changes.push(Change::Added {
+                path,
+                item: new_item.clone(),
+            });
+        }
+    }
+}
+
+fn diff_item(old: &Item, new: &Item, path: &mut Vec<PathSegment>, changes: &mut Vec<Change>) {
+    if let (Some(old), Some(new)) = (old.as_table_like(), new.as_table_like()) {
+        diff_table(old, new, path, changes);
+    } else if let (Item::ArrayOfTables(old), Item::ArrayOfTables(new)) = (old, new) {
+        for (i, old_table) in old.iter().enumerate() {
+            path.push(PathSegment::Index(i));
+            match new.get(i) {
+                Some(new_table) => diff_table(old_table, new_table, path, changes),
+                None => changes.push(Change::Removed {
+                    path: path.clone(),
+                    item: Item::Table(old_table.clone()),
+                }),
+            }
+            path.pop();
+        }
+        for (i, new_table) in new.iter().enumerate().skip(old.len()) {
+            let mut path = path.clone();
+            path.push(PathSegment::Index(i));
+            changes.push(Change::Added {
+                path,
+                item: Item::Table(new_table.clone()),
+            });
+        }
+    } else if !same_item(old, new) {
+        changes.push(Change::Modified {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        });
+    }
+}
+
+fn same_item(old: &Item, new: &Item) -> bool {
+    match (old, new) {
+        (Item::None, Item::None) => true,
+        (Item::Value(old), Item::Value(new)) => old.same_data(new),
+        _ => false,
+    }
+}
@@ -0,0 +1,445 @@
+//! Resolve Cargo-style workspace inheritance
+//!
+//! Cargo lets a package manifest defer a field to its workspace manifest:
+//! ```toml
+//! [package]
+//! version.workspace = true
+//!
+//! [dependencies]
+//! serde = { workspace = true, features = ["derive"] }
+//!
+//! [lints]
+//! workspace = true
+//! ```
+//! These are looked up in `[workspace.package]`, `[workspace.dependencies]`, and
+//! `[workspace.lints]`, respectively.  For dependencies, `features` are merged and any other
+//! keys, like `optional`, are kept from the package manifest.
+//!
+//! The workspace manifest is only requested when something is inherited.  Relative `path`s in
+//! `[workspace.dependencies]` are copied as-is and are not rebased onto the package.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "parse")] {
+//! # #[cfg(feature = "display")] {
+//! use toml_edit::DocumentMut;
+//!
+//! let workspace = "
+//! [workspace.package]
+//! version = '1.2.0'
+//!
+//! [workspace.dependencies]
+//! serde = { version = '1', features = ['std'] }
+//! ".parse::<DocumentMut>().unwrap();
+//! let mut manifest = "
+//! [package]
+//! name = 'foo'
+//! version.workspace = true
+//!
+//! [dependencies]
+//! serde = { workspace = true, features = ['derive'] }
+//! ".parse::<DocumentMut>().unwrap();
+//!
+//! let resolved = toml_edit::workspace::resolve(&manifest, || Some(&workspace)).unwrap();
+//! assert_eq!(resolved["package"]["version"].as_str(), Some("1.2.0"));
+//!
+//! assert!(toml_edit::workspace::materialize(&mut manifest, || Some(&workspace)).unwrap());
+//! assert_eq!(manifest.to_string(), "
+//! [package]
+//! name = 'foo'
+//! version = '1.2.0'
+//!
+//! [dependencies]
+//! serde = { version = '1', features = ['derive', 'std'] }
+//! ");
+//! # }
+//! # }
+//! ```
+
+use std::borrow::{Borrow, Cow};
+
+use crate::{Decor, DocumentMut, Item, Key, Table, TableLike, Value};
+
+const DEPENDENCY_TABLES: &[&str] = &[
+    "dependencies",
+    "dev-dependencies",
+    "dev_dependencies",
+    "build-dependencies",
+    "build_dependencies",
+];
+
+/// A copy of `manifest` with everything inherited from the workspace filled in
+///
+/// `manifest` is borrowed as-is, without calling `workspace`, if nothing is inherited.
+/// `workspace` returns the workspace manifest or `None` if there isn't one.
+pub fn resolve<'m, W>(
+    manifest: &'m DocumentMut,
+    workspace: impl FnOnce() -> Option<W>,
+) -> Result<Cow<'m, DocumentMut>, InheritError>
+where
+    W: Borrow<DocumentMut>,
+{
+    let replacements = replacements(manifest, workspace)?;
+    if replacements.is_empty() {
+        return Ok(Cow::Borrowed(manifest));
+    }
+    let mut manifest = manifest.clone();
+    apply(&mut manifest, replacements);
+    Ok(Cow::Owned(manifest))
+}
+
+/// Edit `manifest` to replace everything inherited from the workspace with its value
+///
+/// The formatting of the rest of the manifest is preserved, including the keys and comments of
+/// inherited entries.  On error, `manifest` is left unchanged.
+///
+/// `workspace` returns the workspace manifest or `None` if there isn't one.  Returns whether
+/// anything was inherited.
+pub fn materialize<W>(
+    manifest: &mut DocumentMut,
+    workspace: impl FnOnce() -> Option<W>,
+) -> Result<bool, InheritError>
+where
+    W: Borrow<DocumentMut>,
+{
+    let replacements = replacements(manifest, workspace)?;
+    let changed = !replacements.is_empty();
+    apply(manifest, replacements);
+    Ok(changed)
+}
+
+/// A field is inherited from a workspace that is missing or does not define it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InheritError {
+    key: String,
+    workspace_key: String,
+    has_workspace: bool,
+}
+
+impl InheritError {
+    /// The dotted key in the package manifest, like `package.version`
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The dotted key in the workspace manifest, like `workspace.package.version`
+    pub fn workspace_key(&self) -> &str {
+        &self.workspace_key
+    }
+
+    /// Whether the workspace manifest was found
+    pub fn has_workspace(&self) -> bool {
+        self.has_workspace
+    }
+}
+
+impl std::fmt::Display for InheritError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.has_workspace {
+            write!(
+                f,
+                "`{}` is inherited but `{}` is not defined in the workspace",
+                self.key, self.workspace_key
+            )
+        } else {
+            write!(f, "`{}` is inherited but there is no workspace", self.key)
+        }
+    }
+}
+
+impl std::error::Error for InheritError {}
+
+struct Replacement {
+    path: Vec<String>,
+    item: Item,
+    /// For `key.workspace = true`, the formatting of the line moves to `key`
+    leaf_decor: Option<Decor>,
+}
+
+struct Site {
+    path: Vec<String>,
+    kind: SiteKind,
+}
+
+enum SiteKind {
+    Package,
+    Dependency,
+    Lints,
+}
+
+impl Site {
+    fn workspace_path(&self) -> Vec<&str> {
+        let last = self.path.last().expect("sites are never the root").as_str();
+        match self.kind {
+            SiteKind::Package => vec!["workspace", "package", last],
+            SiteKind::Dependency => vec!["workspace", "dependencies", last],
+            SiteKind::Lints => vec!["workspace", "lints"],
+        }
+    }
+
+    fn error(&self, has_workspace: bool) -> InheritError {
+        InheritError {
+            key: self.path.join("."),
+            workspace_key: self.workspace_path().join("."),
+            has_workspace,
+        }
+    }
+}
+
+fn replacements<W>(
+    manifest: &DocumentMut,
+    workspace: impl FnOnce() -> Option<W>,
+) -> Result<Vec<Replacement>, InheritError>
+where
+    W: Borrow<DocumentMut>,
+{
+    let sites = sites(manifest);
+    let Some(first) = sites.first() else {
+        return Ok(Vec::new());
+    };
+    let Some(workspace) = workspace() else {
+        return Err(first.error(false));
+    };
+    let workspace = workspace.borrow();
+
+    let mut replacements = Vec::with_capacity(sites.len());
+    for site in &sites {
+        let inherited = get(workspace.as_table(), &site.workspace_path())
+            .filter(|item| !item.is_none())
+            .ok_or_else(|| site.error(true))?;
+        let current = get(manifest.as_table(), &site.path).expect("sites come from the manifest");
+        let replacement = match site.kind {
+            SiteKind::Package | SiteKind::Lints => inherit_item(current, inherited),
+            SiteKind::Dependency => inherit_dependency(current, inherited),
+        };
+        let item = replacement.ok_or_else(|| site.error(true))?;
+        let leaf_decor = match (current, &item) {
+            (Item::Table(table), Item::Value(_)) if table.is_dotted() => {
+                table.key("workspace").map(|key| key.leaf_decor().clone())
+            }
+            _ => None,
+        };
+        replacements.push(Replacement {
+            path: site.path.clone(),
+            item,
+            leaf_decor,
+        });
+    }
+    Ok(replacements)
+}
+
+fn apply(manifest: &mut DocumentMut, replacements: Vec<Replacement>) {
+    for replacement in replacements {
+        let (last, parents) = replacement
+            .path
+            .split_last()
+            .expect("sites are never the root");
+        let mut table: &mut dyn TableLike = manifest.as_table_mut();
+        for key in parents {
+            table = table
+                .get_mut(key)
+                .and_then(Item::as_table_like_mut)
+                .expect("replacements come from the manifest");
+        }
+        let (mut key, item) = table
+            .get_key_value_mut(last)
+            .expect("replacements come from the manifest");
+        *item = replacement.item;
+        if let Some(leaf_decor) = replacement.leaf_decor {
+            *key.leaf_decor_mut() = leaf_decor;
+        }
+    }
+}
+
+fn sites(manifest: &DocumentMut) -> Vec<Site> {
+    let mut sites = Vec::new();
+    if let Some(package) = manifest.get("package").and_then(Item::as_table_like) {
+        for (key, item) in package.iter() {
+            if key != "workspace" && is_inherited(item) {
+                sites.push(Site {
+                    path: vec!["package".to_owned(), key.to_owned()],
+                    kind: SiteKind::Package,
+                });
+            }
+        }
+    }
+    dependency_sites(manifest.as_table(), &[], &mut sites);
+    if let Some(target) = manifest.get("target").and_then(Item::as_table_like) {
+        for (platform, item) in target.iter() {
+            if let Some(platform_table) = item.as_table_like() {
+                dependency_sites(platform_table, &["target", platform], &mut sites);
+            }
+        }
+    }
+    if manifest.get("lints").map(is_inherited).unwrap_or(false) {
+        sites.push(Site {
+            path: vec!["lints".to_owned()],
+            kind: SiteKind::Lints,
+        });
+    }
+    sites
+}
+
+fn dependency_sites(table: &dyn TableLike, parent: &[&str], sites: &mut Vec<Site>) {
+    for name in DEPENDENCY_TABLES {
+        let Some(dependencies) = table.get(name).and_then(Item::as_table_like) else {
+            continue;
+        };
+        for (key, item) in dependencies.iter() {
+            if is_inherited(item) {
+                let mut path = parent.iter().map(|k| (*k).to_owned()).collect::<Vec<_>>();
+                path.push((*name).to_owned());
+                path.push(key.to_owned());
+                sites.push(Site {
+                    path,
+                    kind: SiteKind::Dependency,
+                });
+            }
+        }
+    }
+}
+
+fn is_inherited(item: &Item) -> bool {
+    item.get("workspace")
+        .and_then(Item::as_bool)
+        .unwrap_or(false)
+}
+
+fn get<'t>(table: &'t Table, path: &[impl AsRef<str>]) -> Option<&'t Item> {
+    let (first, rest) = path.split_first()?;
+    let mut item = table.get(first.as_ref())?;
+    for key in rest {
+        item = item.get(key.as_ref())?;
+    }
+    Some(item)
+}
+
+/// Replace `current` with `inherited`, keeping the shape and decor of `current`
+fn inherit_item(current: &Item, inherited: &Item) -> Option<Item> {
+    match current {
+        Item::Table(current) if !current.is_dotted() => {
+            let mut table = match inherited.clone() {
+                Item::Table(table) => table,
+                Item::Value(Value::InlineTable(table)) => table.into_table(),
+                _ => return None,
+            };
+            table.set_dotted(false);
+            *table.decor_mut() = current.decor().clone();
+            if let Some(position) = current.position() {
+                set_positions(&mut table, position);
+            }
+            Some(Item::Table(table))
+        }
+        _ => {
+            let mut value = inherited.clone().into_value().ok()?;
+            clear_decor(&mut value);
+            match current {
+                Item::Value(current) => *value.decor_mut() = current.decor().clone(),
+                // For `key.workspace = true`, keep any trailing comment
+                _ => {
+                    if let Some(suffix) = current
+                        .get("workspace")
+                        .and_then(Item::as_value)
+                        .and_then(|v| v.decor().suffix())
+                    {
+                        value.decor_mut().set_suffix_unchecked(suffix.clone());
+                    }
+                }
+            }
+            Some(Item::Value(value))
+        }
+    }
+}
+
+/// Merge the workspace's dependency into the package's, see [`inherit_item`]
+fn inherit_dependency(current: &Item, inherited: &Item) -> Option<Item> {
+    let current_table = current.as_table_like()?;
+    let overrides = current_table
+        .iter()
+        .filter(|(key, _)| *key != "workspace")
+        .map(|(key, item)| {
+            let key = current_table.key(key).expect("iterated").clone();
+            (key, item.clone())
+        })
+        .collect::<Vec<_>>();
+    if overrides.is_empty() {
+        return inherit_item(current, inherited);
+    }
+
+    let mut fields = Vec::new();
+    match inherited {
+        Item::Value(Value::String(version)) => {
+            let mut version = Value::String(version.clone());
+            clear_decor(&mut version);
+            fields.push(("version".to_owned(), version));
+        }
+        _ => {
+            for (key, item) in inherited.as_table_like()?.iter() {
+                let mut value = item.clone().into_value().ok()?;
+                clear_decor(&mut value);
+                fields.push((key.to_owned(), value));
+            }
+        }
+    }
+
+    let mut merged = current.clone();
+    let merged_table = merged.as_table_like_mut().expect("checked earlier");
+    merged_table.clear();
+    for (key, value) in &fields {
+        if !overrides.iter().any(|(k, _)| k.get() == key) {
+            merged_table.insert(key, Item::Value(value.clone()));
+        }
+    }
+    for (key, mut item) in overrides {
+        if key.get() == "features" {
+            let inherited_features = fields
+                .iter()
+                .find(|(k, _)| k == "features")
+                .and_then(|(_, v)| v.as_array());
+            if let (Some(features), Some(inherited_features)) =
+                (item.as_array_mut(), inherited_features)
+            {
+                for feature in inherited_features.iter() {
+                    if !features.iter().any(|f| f.as_str() == feature.as_str()) {
+                        let mut feature = feature.clone();
+                        clear_decor(&mut feature);
+                        features.push_formatted(feature);
+                    }
+                }
+            }
+        }
+        insert_formatted(merged_table, &key, item);
+    }
+    Some(merged)
+}
+
+fn insert_formatted(table: &mut dyn TableLike, key: &Key, item: Item) {
+    match table.entry_format(key) {
+        crate::Entry::Occupied(mut entry) => {
+            entry.insert(item);
+        }
+        crate::Entry::Vacant(entry) => {
+            entry.insert(item);
+        }
+    }
+}
+
+fn clear_decor(value: &mut Value) {
+    value.decor_mut().clear();
+}
+
+fn set_positions(table: &mut Table, position: usize) {
+    table.set_position(position);
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(child) => set_positions(child, position),
+            Item::ArrayOfTables(children) => {
+                for child in children.iter_mut() {
+                    set_positions(child, position);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -178,3 +178,215 @@ fn fuzzed_68144_error_span() {
     // atm bad escape values are reported as missing escape values
     assert_eq!(actual, "");
 }
+
+#[test]
+fn number_fix() {
+    let input = "key = 042";
+    dbg!(input);
+    let err = input.parse::<crate::RustDocument>().unwrap_err();
+    dbg!(&err);
+    assert_eq!(&input[err.context_span().unwrap()], "042");
+    assert_eq!(err.fix(), Some("42"));
+
+    let input = "key = 1__0";
+    let err = input.parse::<crate::RustDocument>().unwrap_err();
+    assert_eq!(err.fix(), Some("1_0"));
+
+    let input = "key = asdf";
+    let err = input.parse::<crate::RustDocument>().unwrap_err();
+    assert_eq!(err.fix(), None);
+}
+
+#[test]
+fn keyword_fix() {
+    let input = "key = True";
+    let err = input.parse::<crate::RustDocument>().unwrap_err();
+    assert_eq!(&input[err.context_span().unwrap()], "True");
+    assert_eq!(err.fix(), Some("true"));
+    assert_eq!(err.message(), "booleans must be lowercase, expected `true`");
+
+    let input = "key = null";
+    let err = input.parse::<crate::RustDocument>().unwrap_err();
+    assert_eq!(err.fix(), None);
+    assert_eq!(
+        err.message(),
+        "TOML has no null value, leave out the key instead"
+    );
+}
+
+#[test]
+fn lossy_roundtrip() {
+    let cases = [
+        "# stray\rcarriage return\nname = 'foo'\n",
+        "a = 1 # c\u{1}omment\nb = 'x\u{7f}y'\n",
+        "[t]\nk = \"\u{1}\" # \u{7f}\n\u{c}\nm = '''\r'''\n",
+        "a = [1,\u{1} 2]\nx = { y = \"\u{2}\" }\n",
+        "bad key here\n[t]\nx\n",
+    ];
+    for input in cases {
+        dbg!(input);
+        let (doc, errors) = toml_edit::Document::parse_lossy(input);
+        assert!(!errors.is_empty());
+        assert_eq!(doc.into_mut().to_string(), input);
+    }
+}
+
+#[test]
+fn lossy_unclosed_table_header() {
+    let (doc, errors) = toml_edit::Document::parse_lossy("[a.b.\nx = 1\n");
+    assert_eq!(errors.len(), 1);
+    let doc = doc.into_mut();
+    assert_eq!(doc["a"]["b"][""]["x"].as_integer(), Some(1));
+
+    let (doc, errors) = toml_edit::Document::parse_lossy("[[a.b\nx = 1\n");
+    assert_eq!(errors.len(), 1);
+    let doc = doc.into_mut();
+    assert_eq!(doc["a"]["b"][0]["x"].as_integer(), Some(1));
+}
+
+#[test]
+fn lossy_dotted_key_header_conflict() {
+    let input = "a.b.x = 1\n[a.b]\nc = 2\n";
+    let (doc, errors) = toml_edit::Document::parse_lossy(input);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(&input[errors[0].span().unwrap()], "b");
+    assert_eq!(errors[0].context_span(), Some(2..3));
+
+    let conflict = doc.key_conflicts()[0].clone();
+    assert_eq!(conflict.path(), ["a", "b"]);
+    assert_eq!(conflict.dotted_span(), 2..3);
+    assert_eq!(conflict.header_span(), 13..14);
+    assert!(conflict.is_mergeable());
+
+    let mut merged = doc.clone().into_mut();
+    assert!(merged.resolve_conflict(&conflict, toml_edit::ConflictStrategy::Merge));
+    assert_eq!(merged.to_string(), "[a.b]\nx = 1\nc = 2\n");
+
+    let mut renamed = doc.into_mut();
+    assert!(!renamed.resolve_conflict(&conflict, toml_edit::ConflictStrategy::Rename("b")));
+    assert!(renamed.resolve_conflict(&conflict, toml_edit::ConflictStrategy::Rename("old")));
+    assert_eq!(renamed.to_string(), "a.old.x = 1\n[a.b]\nc = 2\n");
+}
+
+#[test]
+fn lossy_dotted_key_header_conflict_merge_restores() {
+    let input = "a.b.x.y = 1\n[a.b]\nc = 2\n";
+    let (doc, _) = toml_edit::Document::parse_lossy(input);
+    let conflict = doc.key_conflicts()[0].clone();
+
+    let mut doc = doc.into_mut();
+    doc["a"]["b"]["x"].as_table_mut().unwrap().remove("y");
+    doc["a"]["b"]["x"]["z"] = toml_edit::value(3);
+    doc["a"]["b"]["c"] = toml_edit::value(4);
+    assert!(doc.resolve_conflict(&conflict, toml_edit::ConflictStrategy::Merge));
+    assert_eq!(doc.to_string(), "[a.b]\nx.z = 3\nx.y = 1\nc = 4\n");
+}
+
+#[test]
+fn lossy_duplicate_keys_merge_nested() {
+    use toml_edit::DuplicateStrategy;
+
+    let input = "a = { b = { c = 1 }, d = 2 }\na = { b = { e = 3 }, d = 4 }\n";
+    let (doc, _) = toml_edit::Document::parse_lossy(input);
+    let duplicate = doc.duplicate_keys()[0].clone();
+
+    let mut doc = doc.into_mut();
+    assert!(doc.resolve_duplicate(&duplicate, DuplicateStrategy::Merge));
+    assert_eq!(doc.to_string(), "a = { b = { c = 1 , e = 3 }, d = 4 }\n");
+}
+
+#[test]
+fn lossy_duplicate_keys() {
+    use toml_edit::{DuplicateStrategy, PathSegment};
+
+    let input = "[[p]]\nx = 1\n[[p]]\nx = [2]\nx = [3]\nx = [4]\n";
+    let (doc, errors) = toml_edit::Document::parse_lossy(input);
+    assert_eq!(errors.len(), 2);
+
+    let duplicate = doc.duplicate_keys()[0].clone();
+    assert_eq!(
+        duplicate.path(),
+        [
+            PathSegment::Key("p".to_owned()),
+            PathSegment::Index(1),
+            PathSegment::Key("x".to_owned()),
+        ]
+    );
+    assert_eq!(duplicate.spans(), [18..19, 26..27, 34..35]);
+    assert_eq!(duplicate.values().len(), 3);
+    assert!(duplicate.is_mergeable());
+
+    let mut first = doc.clone().into_mut();
+    assert!(first.resolve_duplicate(&duplicate, DuplicateStrategy::KeepFirst));
+    assert_eq!(first.to_string(), "[[p]]\nx = 1\n[[p]]\nx = [2]\n");
+
+    let mut last = doc.clone().into_mut();
+    assert!(last.resolve_duplicate(&duplicate, DuplicateStrategy::KeepLast));
+    assert_eq!(last.to_string(), "[[p]]\nx = 1\n[[p]]\nx = [4]\n");
+
+    let mut merged = doc.into_mut();
+    assert!(merged.resolve_duplicate(&duplicate, DuplicateStrategy::Merge));
+    assert_eq!(merged.to_string(), "[[p]]\nx = 1\n[[p]]\nx = [2, 3, 4]\n");
+}
+
+#[test]
+fn preserving_unknown_roundtrip() {
+    let cases = [
+        "# stray\rcarriage return\nname = 'foo'\n",
+        "a = 1 # c\u{1}omment\nb = 'x\u{7f}y'\n",
+        "a = [1,\u{1} 2]\nx = { y = \"\u{2}\" }\n",
+        "bad key here\n[t]\nx\n",
+        "a = \"\\e\"\nb = 1\n[t]\nc = 1979-05-27T07:32\n",
+        "a = 1\na = 2\n[t]\nx = 1\n[t]\ny = 2\n",
+        "ключ = 'знач' 'ение'\nb = 1\n",
+        "a = [1,\n2\nb = 1\n",
+    ];
+    for input in cases {
+        dbg!(input);
+        let doc = toml_edit::Document::parse_preserving_unknown(input).unwrap();
+        assert!(!doc.opaque_spans().is_empty());
+        assert_eq!(doc.into_mut().to_string(), input);
+    }
+}
+
+#[test]
+fn preserving_unknown_spans() {
+    let input = "a = 1\nb = 0x\n\n[t]\nc = 2\n[t]\nd = 3\n\n# end\n";
+    let doc = toml_edit::Document::parse_preserving_unknown(input).unwrap();
+    let opaque = doc
+        .opaque_spans()
+        .iter()
+        .map(|s| &input[s.clone()])
+        .collect::<Vec<_>>();
+    assert_eq!(opaque, ["b = 0x", "[t]\nd = 3"]);
+    assert_eq!(doc["a"].as_integer(), Some(1));
+    assert_eq!(doc["t"]["c"].as_integer(), Some(2));
+    assert!(doc["t"].get("d").is_none());
+
+    let input = "a = 1\n[t]\nb = 2\n";
+    let doc = toml_edit::Document::parse_preserving_unknown(input).unwrap();
+    assert!(doc.opaque_spans().is_empty());
+    assert_eq!(doc.into_mut().to_string(), input);
+}
+
+#[test]
+fn mixed_line_endings() {
+    use toml_edit::{CarriageReturn, Document};
+
+    let input = "# a\r# b\r\nkey = 1\r[t] # c\r\nx = 'y'\n";
+    assert!(Document::parse(input).is_err());
+
+    let doc = Document::parse_with_carriage_return(input, CarriageReturn::Preserve).unwrap();
+    assert_eq!(doc["key"].as_integer(), Some(1));
+    assert_eq!(doc["t"]["x"].as_str(), Some("y"));
+    assert_eq!(
+        doc.into_mut().to_string(),
+        "# a\r# b\nkey = 1\n[t] # c\nx = 'y'\n"
+    );
+
+    let doc = Document::parse_with_carriage_return(input, CarriageReturn::Normalize).unwrap();
+    assert_eq!(
+        doc.into_mut().to_string(),
+        "# a\n# b\nkey = 1\n[t] # c\nx = 'y'\n"
+    );
+}
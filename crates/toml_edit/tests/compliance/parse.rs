@@ -388,6 +388,31 @@ fn number_underscores() {
     t!("1_000", 1000);
     t!("+1_000", 1000);
     t!("-1_000", -1000);
+    t!("0xdead_beef", 0xdead_beef);
+    t!("9_223_372_036_854_775_807", i64::MAX);
+    t!("-9_223_372_036_854_775_808", i64::MIN);
+    t!(
+        "0b1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111",
+        0xffff_ffff_ffff
+    );
+}
+
+#[test]
+fn float_underscores() {
+    macro_rules! t {
+        ($actual:expr, $expected:expr) => {{
+            let f = format!("foo = {}", $actual);
+            let table = f.parse::<crate::RustDocument>().unwrap();
+            assert_eq!(table["foo"].as_float().unwrap(), $expected);
+        }};
+    }
+
+    t!("1_0.5", 10.5);
+    t!("-1_000.000_5e1_0", -1000.0005e10);
+    t!(
+        "1_1.1_1_1_1_1_1_1_1_1_1_1_1_1_1_1_1_1_1_1_1",
+        11.111_111_111_111_11
+    );
 }
 
 #[test]
@@ -418,6 +443,24 @@ fn datetimes() {
     t!("2016-09-09T09:09:09.123456789-02:00");
 }
 
+#[test]
+fn space_separated_datetimes() {
+    for (input, expected) in [
+        ("2016-09-09 09:09:09Z", "2016-09-09T09:09:09Z"),
+        ("2016-09-09 09:09:09.1Z", "2016-09-09T09:09:09.1Z"),
+        ("2016-09-09 09:09:09.2+10:00", "2016-09-09T09:09:09.2+10:00"),
+        (
+            "2016-09-09 09:09:09.123456789",
+            "2016-09-09T09:09:09.123456789",
+        ),
+    ] {
+        let toml = format!("foo = {input} # comment\n")
+            .parse::<crate::RustDocument>()
+            .unwrap();
+        assert_eq!(toml["foo"].as_datetime().unwrap().to_string(), expected);
+    }
+}
+
 #[test]
 fn dont_use_dotted_key_prefix_on_table_fuzz_57049() {
     // This could generate
@@ -44,6 +44,7 @@ TOML parse error at line 1, column 7
 1 | val = "NonExistent"
   |       ^^^^^^^^^^^^^
 unknown variant `NonExistent`, expected one of `Plain`, `Tuple`, `NewType`, `Struct`
+in `val`
 
 "#]]
     .raw();
@@ -69,6 +70,7 @@ TOML parse error at line 1, column 9
 1 | val = { NonExistent = {} }
   |         ^^^^^^^^^^^
 unknown variant `NonExistent`, expected one of `Plain`, `Tuple`, `NewType`, `Struct`
+in `val`
 
 "#]]
     .raw();
@@ -156,6 +158,7 @@ TOML parse error at line 1, column 17
 1 | val = { Plain = { extra_field = 404 } }
   |                 ^^^^^^^^^^^^^^^^^^^^^
 expected empty table
+in `val`
 
 "#]]
         .raw();
@@ -355,6 +358,7 @@ TOML parse error at line 1, column 33
 1 | val = { Struct = { value = 123, extra_0 = 0, extra_1 = 1 } }
   |                                 ^^^^^^^
 unexpected keys in table: extra_0, extra_1, available keys: value
+in `val`
 
 "#]]
         .raw();
@@ -89,6 +89,7 @@ TOML parse error at line 2, column 19
 2 |             p_a = ''
   |                   ^^
 invalid length 0, expected a non-empty string
+in `p_a`
 
 "#]],
     );
@@ -122,6 +123,7 @@ TOML parse error at line 3, column 19
 3 |             p_b = 1
   |                   ^
 invalid type: integer `1`, expected a sequence
+in `p_b`
 
 "#]],
     );
@@ -141,6 +143,7 @@ TOML parse error at line 4, column 17
 4 |                 {c_a = 'a'}
   |                 ^^^^^^^^^^^
 missing field `c_b`
+in `p_b[0]`
 
 "#]],
     );
@@ -160,6 +163,7 @@ TOML parse error at line 4, column 35
 4 |                 {c_a = 'a', c_b = '*'}
   |                                   ^^^
 invalid value: string "*", expected all lowercase or all uppercase
+in `p_b[0].c_b`
 
 "#]],
     );
@@ -180,6 +184,7 @@ TOML parse error at line 5, column 17
 5 |                 {c_a = 'aa'}
   |                 ^^^^^^^^^^^^
 missing field `c_b`
+in `p_b[1]`
 
 "#]],
     );
@@ -201,6 +206,7 @@ TOML parse error at line 5, column 17
 5 |                 {c_a = 'aa'},
   |                 ^^^^^^^^^^^^
 missing field `c_b`
+in `p_b[1]`
 
 "#]],
     );
@@ -222,6 +228,7 @@ TOML parse error at line 5, column 36
 5 |                 {c_a = 'aa', c_b = 1},
   |                                    ^
 invalid type: integer `1`, expected a string
+in `p_b[1].c_b`
 
 "#]],
     );
@@ -244,6 +251,7 @@ TOML parse error at line 5, column 42
 5 |                 {c_a = 'aa', c_b = 'bb', c_d = 'd'},
   |                                          ^^^
 unknown field `c_d`, expected `c_a` or `c_b`
+in `p_b[1]`
 
 "#]],
     );
@@ -273,6 +281,7 @@ TOML parse error at line 6, column 13
 6 |             [[p_b]]
   |             ^^^^^^^
 missing field `c_b`
+in `p_b[1]`
 
 "#]],
     );
@@ -298,6 +307,7 @@ TOML parse error at line 8, column 19
 8 |             c_b = '*'
   |                   ^^^
 invalid value: string "*", expected all lowercase or all uppercase
+in `p_b[1].c_b`
 
 "#]],
     );
@@ -326,6 +336,7 @@ TOML parse error at line 8, column 13
 8 |             c_d = 'dd' # unknown field
   |             ^^^
 unknown field `c_d`, expected `c_a` or `c_b`
+in `p_b[1]`
 
 "#]],
     );
@@ -362,6 +373,7 @@ TOML parse error at line 4, column 17
 4 |                 {c_a = ''}
   |                 ^^^^^^^^^^
 missing field `c_b`
+in `p_b[0]`
 
 "#]],
     );
@@ -380,6 +392,7 @@ TOML parse error at line 4, column 34
 4 |                 {c_a = '', c_b = 1}
   |                                  ^
 invalid type: integer `1`, expected a string
+in `p_b[0].c_b`
 
 "#]],
     );
@@ -399,6 +412,7 @@ TOML parse error at line 4, column 38
 4 |                 {c_a = '', c_b = '', c_d = ''},
   |                                      ^^^
 unknown field `c_d`, expected `c_a` or `c_b`
+in `p_b[0]`
 
 "#]],
     );
@@ -417,7 +431,146 @@ TOML parse error at line 4, column 34
 4 |                 {c_a = '', c_b = 1, c_d = ''},
   |                                  ^
 invalid type: integer `1`, expected a string
+in `p_b[0].c_b`
 
 "#]],
     );
 }
+
+#[test]
+fn error_path() {
+    let err = crate::from_str::<Parent<String>>(
+        "
+            p_a = 'a'
+            p_b = [
+                {c_a = '', c_b = ''},
+                {c_a = '', c_b = 1},
+            ]
+        ",
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.path(),
+        [
+            toml_edit::de::PathSegment::Key("p_b".to_owned()),
+            toml_edit::de::PathSegment::Index(1),
+            toml_edit::de::PathSegment::Key("c_b".to_owned()),
+        ]
+    );
+    assert_eq!(err.span(), Some(114..115));
+}
+
+#[test]
+fn error_frames() {
+    let input = "
+            p_a = 'a'
+            p_b = [
+                {c_a = '', c_b = ''},
+                {c_a = '', c_b = 1},
+            ]
+        ";
+    let err = crate::from_str::<Parent<String>>(input).unwrap_err();
+    let frames = err
+        .frames()
+        .iter()
+        .map(|frame| {
+            format!(
+                "{:?} {:?}\n",
+                frame.segment(),
+                &input[frame.span().unwrap()]
+            )
+        })
+        .collect::<String>();
+    assert_data_eq!(
+        frames,
+        str![[r#"
+Key("p_b") "[\n                {c_a = '', c_b = ''},\n                {c_a = '', c_b = 1},\n            ]"
+Index(1) "{c_a = '', c_b = 1}"
+Key("c_b") "1"
+
+"#]]
+        .raw()
+    );
+}
+
+#[test]
+fn error_found_expected() {
+    let err = crate::from_str::<Parent<String>>("p_a = 1979-05-27\np_b = []\n").unwrap_err();
+    assert_eq!(err.expected(), Some("a string"));
+    assert_eq!(err.found(), Some("datetime"));
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+TOML parse error at line 1, column 7
+  |
+1 | p_a = 1979-05-27
+  |       ^^^^^^^^^^
+invalid type: datetime, expected a string
+in `p_a`
+
+"#]]
+        .raw()
+    );
+
+    let err =
+        crate::from_str::<Parent<String>>("p_a = ''\np_b = { c_a = '', c_b = '' }\n").unwrap_err();
+    assert_eq!(err.expected(), Some("a sequence"));
+    assert_eq!(err.found(), Some("inline table"));
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+TOML parse error at line 2, column 7
+  |
+2 | p_b = { c_a = '', c_b = '' }
+  |       ^^^^^^^^^^^^^^^^^^^^^^
+invalid type: inline table, expected a sequence
+in `p_b`
+
+"#]]
+        .raw()
+    );
+}
+
+#[test]
+fn document_path() {
+    let doc = toml_edit::Document::parse(
+        "
+[server.http]
+c_a = 'a'
+c_b = 1
+",
+    )
+    .unwrap();
+    let err = toml_edit::de::from_document_path::<Child<String>>(&doc, "server.http").unwrap_err();
+    assert_eq!(
+        err.path(),
+        [
+            toml_edit::de::PathSegment::Key("server".to_owned()),
+            toml_edit::de::PathSegment::Key("http".to_owned()),
+            toml_edit::de::PathSegment::Key("c_b".to_owned()),
+        ]
+    );
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+TOML parse error at line 4, column 7
+  |
+4 | c_b = 1
+  |       ^
+invalid type: integer `1`, expected a string
+in `server.http.c_b`
+
+"#]]
+        .raw()
+    );
+
+    let err = toml_edit::de::from_document_path::<Child<String>>(&doc, "server.ftp").unwrap_err();
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+missing `server.ftp`
+
+"#]]
+        .raw()
+    );
+}
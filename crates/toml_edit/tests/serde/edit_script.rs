@@ -0,0 +1,116 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::{DocumentMut, Edit, EditScript};
+
+#[test]
+fn apply_script() {
+    let script: EditScript = t!(crate::from_str(
+        r#"
+[[edit]]
+op = "rename"
+path = ["dependencies", "serde_json"]
+to = "json"
+
+[[edit]]
+op = "set"
+path = ["package", "metadata", "released"]
+value = 2024-05-01
+
+[[edit]]
+op = "append"
+path = ["bin"]
+value = { name = "cli", path = "src/cli.rs" }
+
+[[edit]]
+op = "sort"
+path = ["features", "default"]
+"#
+    ));
+    assert_eq!(script.edits().len(), 4);
+
+    let mut doc = t!(r#"
+[package]
+name = "foo"
+
+[features]
+default = ["std", "alloc"]
+
+[dependencies]
+serde_json = { version = "1", optional = true }  # for output
+toml = "0.8"
+"#
+    .parse::<DocumentMut>());
+    t!(script.apply(&mut doc));
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[package]
+name = "foo"
+
+[package.metadata]
+released = 2024-05-01
+
+[features]
+default = ["alloc", "std"]
+
+[dependencies]
+json = { version = "1", optional = true }  # for output
+toml = "0.8"
+
+[[bin]]
+name = "cli"
+path = "src/cli.rs"
+
+"#]]
+    );
+}
+
+#[test]
+fn failed_script_is_not_applied() {
+    let script = EditScript::from(vec![
+        Edit::Remove {
+            path: vec!["name".to_owned()],
+        },
+        Edit::Rename {
+            path: vec!["version".to_owned()],
+            to: "edition".to_owned(),
+        },
+    ]);
+    let mut doc = t!("name = 'foo'\nversion = '1.0.0'\nedition = '2021'\n".parse::<DocumentMut>());
+    let err = script.apply(&mut doc).unwrap_err();
+    assert_eq!(err.index(), 1);
+    assert_data_eq!(
+        err.to_string(),
+        str!["edit 1 at `version` failed: new key is already present"]
+    );
+    assert_eq!(
+        doc.to_string(),
+        "name = 'foo'\nversion = '1.0.0'\nedition = '2021'\n"
+    );
+}
+
+#[test]
+fn invalid_script() {
+    let err = crate::from_str::<EditScript>(
+        r#"
+[[edit]]
+op = "remove"
+path = ["package"]
+value = 1
+"#,
+    )
+    .unwrap_err();
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+TOML parse error at line 2, column 1
+  |
+2 | [[edit]]
+  | ^^^^^^^^
+`value` is not used by the `remove` op
+in `edit[0]`
+
+"#]]
+    );
+}
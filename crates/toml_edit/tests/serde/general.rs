@@ -227,6 +227,7 @@ TOML parse error at line 1, column 7
 1 | bar = "a"
   |       ^^^
 invalid type: string "a", expected isize
+in `bar`
 
 "#]],
         str![[r#"
@@ -249,6 +250,7 @@ TOML parse error at line 1, column 15
 1 | foo = { bar = "a" }
   |               ^^^
 invalid type: string "a", expected isize
+in `foo.bar`
 
 "#]],
         str![[r#"
@@ -814,6 +816,7 @@ TOML parse error at line 8, column 9
 8 | debug = 'a'
   |         ^^^
 expected a boolean or an integer
+in `profile.dev.debug`
 
 "#]]
     );
@@ -838,6 +841,7 @@ TOML parse error at line 8, column 17
 8 | dev = { debug = 'a' }
   |                 ^^^
 expected a boolean or an integer
+in `profile.dev.debug`
 
 "#]]
     );
@@ -1532,3 +1536,244 @@ edition = "2021"
     };
     assert_eq!(err.span(), Some(61..66));
 }
+
+#[test]
+fn serialize_in_place() {
+    #[derive(Serialize)]
+    struct Server {
+        host: String,
+        port: u16,
+        tags: Vec<String>,
+        backends: Vec<Backend>,
+    }
+
+    #[derive(Serialize)]
+    struct Backend {
+        url: String,
+    }
+
+    let mut doc = r#"
+[server]
+host = 'localhost'  # the host
+port = 0x50
+tags = [
+  "a",  # first
+  "b",
+]
+removed = 1
+
+[[server.backends]]
+url = 'x'
+
+[other]
+key = 1
+"#
+    .parse::<toml_edit::DocumentMut>()
+    .unwrap();
+    let server = Server {
+        host: "localhost".to_owned(),
+        port: 8080,
+        tags: vec!["a".to_owned(), "c".to_owned(), "d".to_owned()],
+        backends: vec![
+            Backend {
+                url: "x".to_owned(),
+            },
+            Backend {
+                url: "y".to_owned(),
+            },
+        ],
+    };
+    toml_edit::ser::to_item_in_place(&server, &mut doc["server"]).unwrap();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[server]
+host = 'localhost'  # the host
+port = 8080
+tags = [
+  "a",  # first
+  "c",
+  "d",
+]
+
+[[server.backends]]
+url = 'x'
+
+[[server.backends]]
+url = "y"
+
+[other]
+key = 1
+
+"#]]
+    );
+}
+
+#[test]
+fn serialize_in_place_defaults() {
+    #[derive(Deserialize, Serialize)]
+    struct Config {
+        server: Server,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    struct Server {
+        host: String,
+        #[serde(default)]
+        verbose: bool,
+        #[serde(default)]
+        limits: Limits,
+    }
+
+    #[derive(Default, Deserialize, Serialize)]
+    struct Limits {
+        #[serde(default)]
+        retries: u32,
+        #[serde(default)]
+        size: u32,
+    }
+
+    let input = r#"
+[server]
+host = 'localhost'  # the host
+
+[server.limits]
+retries = 3
+"#;
+    let config = crate::from_str::<Config>(input).unwrap();
+
+    let mut doc = input.parse::<toml_edit::DocumentMut>().unwrap();
+    toml_edit::ser::to_item_in_place_with(
+        &config.server,
+        &mut doc["server"],
+        toml_edit::ser::MissingKeys::AddAsDefault,
+    )
+    .unwrap();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[server]
+host = 'localhost'  # the host
+verbose = false # default
+
+[server.limits]
+retries = 3
+size = 0 # default
+
+"#]]
+    );
+
+    let mut doc = input.parse::<toml_edit::DocumentMut>().unwrap();
+    toml_edit::ser::to_item_in_place_with(
+        &config.server,
+        &mut doc["server"],
+        toml_edit::ser::MissingKeys::Skip,
+    )
+    .unwrap();
+    assert_data_eq!(doc.to_string(), input.raw());
+}
+
+#[test]
+fn document_serde_roundtrip() {
+    let doc = r#"
+title = "example"  # comment
+dob = 1979-05-27T07:32:00-08:00
+ports = [8000, 8001]
+
+[owner]
+name = 'Tom'
+nested = { enabled = true, ratio = 0.5 }
+
+[[products]]
+name = "Hammer"
+"#
+    .parse::<toml_edit::DocumentMut>()
+    .unwrap();
+
+    let json = serde_json::to_string(&doc).unwrap();
+    assert_data_eq!(
+        &json,
+        str![[
+            r#"{"title":"example","dob":{"$__toml_private_datetime":"1979-05-27T07:32:00-08:00"},"ports":[8000,8001],"owner":{"name":"Tom","nested":{"enabled":true,"ratio":0.5}},"products":[{"name":"Hammer"}]}"#
+        ]]
+    );
+
+    let from_json = serde_json::from_str::<toml_edit::DocumentMut>(&json).unwrap();
+    assert_data_eq!(
+        from_json.to_string(),
+        str![[r#"
+title = "example"
+dob = 1979-05-27T07:32:00-08:00
+ports = [8000, 8001]
+owner = { name = "Tom", nested = { enabled = true, ratio = 0.5 } }
+products = [{ name = "Hammer" }]
+
+"#]]
+    );
+    assert_eq!(
+        serde_json::to_string(&from_json).unwrap(),
+        json,
+        "data is stable across round-trips"
+    );
+
+    let err = serde_json::from_str::<toml_edit::DocumentMut>("[1]").unwrap_err();
+    assert_eq!(err.to_string(), "invalid type: array, expected a table");
+}
+
+#[test]
+fn document_path() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Mode {
+        Plain,
+        Level(u8),
+        Range(u8, u8),
+        Named { name: String },
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Http {
+        host: serde_spanned::Spanned<String>,
+        port: u16,
+        since: toml_edit::Datetime,
+        modes: Vec<Mode>,
+        limits: Option<BTreeMap<String, u32>>,
+        routes: Vec<Route>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Route {
+        path: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server {
+        http: Http,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Root {
+        server: Server,
+    }
+
+    let input = r#"
+[server.http]
+host = "localhost"
+port = 8080
+since = 1979-05-27
+modes = ["Plain", { Level = 3 }, { Range = [1, 2] }, { Named = { name = "x" } }]
+limits = { body = 1024 }
+
+[[server.http.routes]]
+path = "/"
+
+[[server.http.routes]]
+path = "/api"
+"#;
+    let doc = toml_edit::Document::parse(input).unwrap();
+    let http = toml_edit::de::from_document_path::<Http>(&doc, "server.http").unwrap();
+    let root = crate::from_str::<Root>(input).unwrap();
+    assert_eq!(http, root.server.http);
+    assert_eq!(&input[http.host.span()], r#""localhost""#);
+}
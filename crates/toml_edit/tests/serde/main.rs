@@ -12,11 +12,13 @@ macro_rules! t {
 
 mod de_enum;
 mod de_errors;
+mod edit_script;
 mod general;
 mod ser_enum;
 mod ser_formatting;
 mod ser_formatting_raw;
 mod ser_tables_last;
+mod shapes;
 mod spanned;
 
 use serde_spanned::Spanned;
@@ -0,0 +1,398 @@
+//! Each shape of type serde can describe, in the style of `serde_test`
+//!
+//! A shape either round-trips, as a field of a document and as a standalone value, or fails with
+//! the error recorded here.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Document<T> {
+    field: T,
+}
+
+/// `value` serializes to `expected` as a field and deserializes back to itself
+#[track_caller]
+fn roundtrip<T>(value: T, expected: impl IntoData)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let doc = Document { field: value };
+    let output = t!(crate::to_string(&doc));
+    assert_data_eq!(&output, expected.raw());
+    let actual = t!(crate::from_str::<Document<T>>(&output));
+    assert_eq!(actual, doc);
+
+    let output = t!(crate::to_string_value(&doc.field));
+    let actual = t!(crate::value_from_str::<T>(&output));
+    assert_eq!(actual, doc.field);
+}
+
+/// `value` can't be serialized as a field
+#[track_caller]
+fn ser_error<T: Serialize>(value: T, expected: impl IntoData) {
+    let doc = Document { field: value };
+    let err = crate::to_string(&doc).unwrap_err();
+    assert_data_eq!(err.to_string(), expected.raw());
+}
+
+/// `value` serializes as a field but deserializes to something else, or not at all
+#[track_caller]
+fn lossy<T>(value: T, expected: impl IntoData)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let doc = Document { field: value };
+    let output = t!(crate::to_string(&doc));
+    let actual = match crate::from_str::<Document<T>>(&output) {
+        Ok(actual) => {
+            assert_ne!(actual, doc);
+            format!("{output}deserialized: {actual:?}")
+        }
+        Err(err) => format!("{output}error: {err}"),
+    };
+    assert_data_eq!(actual, expected.raw());
+}
+
+#[test]
+fn options() {
+    roundtrip(
+        Some(1),
+        str![[r#"
+field = 1
+
+"#]],
+    );
+    roundtrip(
+        Document { field: None::<i64> },
+        str![[r#"
+field = {}
+
+"#]],
+    );
+    roundtrip(
+        vec![Some(1), Some(2)],
+        str![[r#"
+field = [1, 2]
+
+"#]],
+    );
+    roundtrip(
+        Document {
+            field: Some("a".to_owned()),
+        },
+        str![[r#"
+field = { field = "a" }
+
+"#]],
+    );
+
+    lossy(
+        BTreeMap::from([("a".to_owned(), None::<i64>)]),
+        str![[r#"
+field = {}
+deserialized: Document { field: {} }
+"#]],
+    );
+    ser_error(Some(None::<i64>), str![[r#"unsupported None value"#]]);
+    ser_error(vec![Some(1), None], str![[r#"unsupported None value"#]]);
+}
+
+#[test]
+fn units() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Unit;
+
+    ser_error((), str![[r#"unsupported unit type"#]]);
+    ser_error(Unit, str![[r#"unsupported Unit type"#]]);
+    ser_error(Some(()), str![[r#"unsupported unit type"#]]);
+}
+
+#[test]
+fn tuples() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Newtype(i64);
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Tuple(i64, String);
+
+    roundtrip(
+        (1, "a".to_owned(), true),
+        str![[r#"
+field = [1, "a", true]
+
+"#]],
+    );
+    roundtrip(
+        Newtype(1),
+        str![[r#"
+field = 1
+
+"#]],
+    );
+    roundtrip(
+        Tuple(1, "a".to_owned()),
+        str![[r#"
+field = [1, "a"]
+
+"#]],
+    );
+    roundtrip(
+        vec![(1, 2), (3, 4)],
+        str![[r#"
+field = [[1, 2], [3, 4]]
+
+"#]],
+    );
+    roundtrip(
+        ((1,), ((2, 3),)),
+        str![[r#"
+field = [[1], [[2, 3]]]
+
+"#]],
+    );
+}
+
+#[test]
+fn nested_enums() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Outer {
+        Unit,
+        Inner(Inner),
+        Tuple(Inner, Inner),
+        Struct { inner: Inner },
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Inner {
+        Unit,
+        Newtype(i64),
+        Tuple(i64, String),
+        Struct { a: i64 },
+    }
+
+    roundtrip(
+        Outer::Unit,
+        str![[r#"
+field = "Unit"
+
+"#]],
+    );
+    roundtrip(
+        Outer::Inner(Inner::Unit),
+        str![[r#"
+field = { Inner = "Unit" }
+
+"#]],
+    );
+    roundtrip(
+        Outer::Inner(Inner::Newtype(1)),
+        str![[r#"
+field = { Inner = { Newtype = 1 } }
+
+"#]],
+    );
+    roundtrip(
+        Outer::Inner(Inner::Tuple(1, "a".to_owned())),
+        str![[r#"
+field = { Inner = { Tuple = [1, "a"] } }
+
+"#]],
+    );
+    roundtrip(
+        Outer::Inner(Inner::Struct { a: 1 }),
+        str![[r#"
+field = { Inner = { Struct = { a = 1 } } }
+
+"#]],
+    );
+    roundtrip(
+        Outer::Tuple(Inner::Unit, Inner::Newtype(1)),
+        str![[r#"
+field = { Tuple = ["Unit", { Newtype = 1 }] }
+
+"#]],
+    );
+    roundtrip(
+        Outer::Struct {
+            inner: Inner::Struct { a: 1 },
+        },
+        str![[r#"
+field = { Struct = { inner = { Struct = { a = 1 } } } }
+
+"#]],
+    );
+    roundtrip(
+        vec![Inner::Unit, Inner::Newtype(1)],
+        str![[r#"
+field = ["Unit", { Newtype = 1 }]
+
+"#]],
+    );
+}
+
+#[test]
+fn map_keys() {
+    roundtrip(
+        BTreeMap::from([("a.b".to_owned(), 1), (String::new(), 2)]),
+        str![[r#"
+field = { "" = 2, "a.b" = 1 }
+
+"#]],
+    );
+
+    roundtrip(
+        BTreeMap::from([(1_i64, 1), (-2, 2)]),
+        str![[r#"
+field = { -2 = 2, 1 = 1 }
+
+"#]],
+    );
+    roundtrip(
+        BTreeMap::from([(true, 1), (false, 2)]),
+        str![[r#"
+field = { false = 2, true = 1 }
+
+"#]],
+    );
+    roundtrip(
+        BTreeMap::from([('a', 1), ('.', 2)]),
+        str![[r#"
+field = { "." = 2, a = 1 }
+
+"#]],
+    );
+    roundtrip(
+        BTreeMap::from([("1979-05-27".parse::<crate::Datetime>().unwrap(), 1)]),
+        str![[r#"
+field = { 1979-05-27 = 1 }
+
+"#]],
+    );
+
+    ser_error(
+        BTreeMap::from([(vec![1], 1)]),
+        str![[r#"map key was not a string"#]],
+    );
+    ser_error(
+        BTreeMap::from([((1, 2), 1)]),
+        str![[r#"map key was not a string"#]],
+    );
+    ser_error(
+        BTreeMap::from([(None::<i64>, 1)]),
+        str![[r#"map key was not a string"#]],
+    );
+}
+
+#[test]
+fn map_key_quoting() {
+    let ports = BTreeMap::from([(80_u16, "http".to_owned()), (443, "https".to_owned())]);
+    let serializer =
+        toml_edit::ser::ValueSerializer::new().with_key_quoting(toml_edit::ser::KeyQuoting::Always);
+    let value = t!(ports.serialize(serializer));
+    assert_data_eq!(
+        value.to_string(),
+        str![[r#"{ "80" = "http", "443" = "https" }"#]].raw()
+    );
+    let actual = t!(crate::value_from_str::<BTreeMap<u16, String>>(
+        &value.to_string()
+    ));
+    assert_eq!(actual, ports);
+}
+
+#[test]
+fn numbers() {
+    roundtrip(
+        i64::MIN,
+        str![[r#"
+field = -9223372036854775808
+
+"#]],
+    );
+    roundtrip(
+        u8::MAX,
+        str![[r#"
+field = 255
+
+"#]],
+    );
+    roundtrip(
+        1.5_f32,
+        str![[r#"
+field = 1.5
+
+"#]],
+    );
+    roundtrip(
+        f64::INFINITY,
+        str![[r#"
+field = inf
+
+"#]],
+    );
+    roundtrip(
+        -0.0_f64,
+        str![[r#"
+field = -0.0
+
+"#]],
+    );
+    roundtrip(
+        'a',
+        str![[r#"
+field = "a"
+
+"#]],
+    );
+
+    ser_error(u64::MAX, str![[r#"out-of-range value for u64 type"#]]);
+}
+
+#[test]
+fn sequences() {
+    roundtrip(
+        Vec::<i64>::new(),
+        str![[r#"
+field = []
+
+"#]],
+    );
+    roundtrip(
+        vec![vec![1], vec![]],
+        str![[r#"
+field = [[1], []]
+
+"#]],
+    );
+    roundtrip(
+        vec![Document { field: 1 }],
+        str![[r#"
+field = [{ field = 1 }]
+
+"#]],
+    );
+    roundtrip(
+        [1, 2, 3],
+        str![[r#"
+field = [1, 2, 3]
+
+"#]],
+    );
+    roundtrip(
+        vec![BTreeMap::from([(
+            "a".to_owned(),
+            vec![Document { field: 1 }],
+        )])],
+        str![[r#"
+field = [{ a = [{ field = 1 }] }]
+
+"#]],
+    );
+}
@@ -0,0 +1,250 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::{DocumentMut, SortComments, SortOrder};
+
+#[test]
+fn test_array_of_tables_sort_by_keys() {
+    let mut doc = r#"
+[[bin]]
+name = "b"
+priority = 2
+
+[bin.metadata]
+tag = "moves with b"
+
+[[bin]]
+name = "a"
+priority = 10
+
+[[bin]]
+name = "c"
+
+[[bin]]
+name = "a"
+priority = 1.5
+
+[other]
+key = "stays"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let bins = doc["bin"].as_array_of_tables_mut().unwrap();
+    bins.sort_by_keys(&["priority"], SortOrder::Ascending);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[[bin]]
+name = "a"
+priority = 1.5
+
+[[bin]]
+name = "b"
+priority = 2
+
+[bin.metadata]
+tag = "moves with b"
+
+[[bin]]
+name = "a"
+priority = 10
+
+[[bin]]
+name = "c"
+
+[other]
+key = "stays"
+
+"#]]
+    );
+
+    let bins = doc["bin"].as_array_of_tables_mut().unwrap();
+    bins.sort_by_keys(&["name"], SortOrder::Descending);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[[bin]]
+name = "c"
+
+[[bin]]
+name = "b"
+priority = 2
+
+[bin.metadata]
+tag = "moves with b"
+
+[[bin]]
+name = "a"
+priority = 1.5
+
+[[bin]]
+name = "a"
+priority = 10
+
+[other]
+key = "stays"
+
+"#]]
+    );
+}
+
+#[test]
+fn test_array_push_sorted() {
+    let mut doc = r#"
+features = [
+    # comment
+    "b", # trailing
+    "d",
+]
+members = ["b", "d"]
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    for key in ["features", "members"] {
+        let array = doc[key].as_array_mut().unwrap();
+        for feature in ["a", "c", "e"] {
+            array.push_sorted(feature, |lhs, rhs| lhs.as_str().cmp(&rhs.as_str()));
+        }
+        array.push("b");
+        let duplicates = array.duplicates_by(|lhs, rhs| lhs.as_str() == rhs.as_str());
+        assert_eq!(duplicates, [5]);
+        array.remove(5);
+    }
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+features = [
+    "a",
+    # comment
+    "b", # trailing
+    "c",
+    "d",
+    "e",
+]
+members = ["a", "b", "c", "d", "e"]
+
+"#]]
+    );
+}
+
+#[test]
+fn test_sort_with_comments() {
+    let input = r#"
+values = [
+    3, # three
+    # before one
+    1, # one
+    2,
+]
+
+[table]
+# about c
+c = 3
+b.x = 2 # b
+# about a
+a = 1
+
+# first bin
+[[bin]]
+name = "z"
+
+# second bin
+[[bin]]
+name = "y"
+"#;
+
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    doc["values"]
+        .as_array_mut()
+        .unwrap()
+        .sort_by_with_comments(SortComments::MoveWithEntry, |l, r| {
+            l.as_integer().cmp(&r.as_integer())
+        });
+    doc["table"]
+        .as_table_mut()
+        .unwrap()
+        .sort_values_by_with_comments(SortComments::MoveWithEntry, |k1, _, k2, _| k1.cmp(k2));
+    doc["bin"]
+        .as_array_of_tables_mut()
+        .unwrap()
+        .sort_by_with_comments(SortComments::MoveWithEntry, |l, r| {
+            l["name"].as_str().cmp(&r["name"].as_str())
+        });
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+values = [
+    # before one
+    1, # one
+    2,
+    3, # three
+]
+
+[table]
+# about a
+a = 1
+b.x = 2 # b
+# about c
+c = 3
+
+# second bin
+[[bin]]
+name = "y"
+
+# first bin
+[[bin]]
+name = "z"
+
+"#]]
+    );
+
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    doc["values"]
+        .as_array_mut()
+        .unwrap()
+        .sort_by_with_comments(SortComments::KeepPosition, |l, r| {
+            l.as_integer().cmp(&r.as_integer())
+        });
+    doc["table"]
+        .as_table_mut()
+        .unwrap()
+        .sort_values_by_with_comments(SortComments::KeepPosition, |k1, _, k2, _| k1.cmp(k2));
+    doc["bin"]
+        .as_array_of_tables_mut()
+        .unwrap()
+        .sort_by_with_comments(SortComments::KeepPosition, |l, r| {
+            l["name"].as_str().cmp(&r["name"].as_str())
+        });
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+values = [
+    1, # three
+    # before one
+    2, # one
+    3,
+]
+
+[table]
+# about c
+a = 1
+b.x = 2 # b
+# about a
+c = 3
+
+# first bin
+[[bin]]
+name = "y"
+
+# second bin
+[[bin]]
+name = "z"
+
+"#]]
+    );
+}
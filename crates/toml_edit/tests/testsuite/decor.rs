@@ -0,0 +1,165 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::{value, DocumentMut, Key};
+
+#[test]
+fn test_decor_blank_lines() {
+    let mut doc = "[t]\n  a = 1\n \r\n\t\n  # a comment\n\n  b = 2\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let table = doc["t"].as_table_mut().unwrap();
+    assert_eq!(table.key("a").unwrap().leaf_decor().blank_lines(), Some(0));
+    assert_eq!(table.key("b").unwrap().leaf_decor().blank_lines(), Some(2));
+
+    let mut b = table.key_mut("b").unwrap();
+    b.leaf_decor_mut().set_blank_lines(1);
+    assert_eq!(b.leaf_decor().blank_lines(), Some(1));
+    table.insert("c", value(3));
+    assert_eq!(table.key("c").unwrap().leaf_decor().blank_lines(), None);
+    table
+        .key_mut("c")
+        .unwrap()
+        .leaf_decor_mut()
+        .set_blank_lines(1);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+[t]
+  a = 1
+
+  # a comment
+
+  b = 2
+
+c = 3
+
+"#]]
+    );
+}
+
+#[test]
+fn test_decor_validation() {
+    let mut decor = toml_edit::Decor::default();
+    decor.set_prefix("\n# c\n  ").unwrap();
+    decor.set_suffix(" # c").unwrap();
+    assert_data_eq!(
+        decor.set_suffix(" 'a").unwrap_err().to_string(),
+        str!["only whitespace and comments are allowed in decor"]
+    );
+    assert_data_eq!(
+        decor.set_prefix("# \u{7}\n").unwrap_err().to_string(),
+        str!["comment contains a control character"]
+    );
+    assert_eq!(decor.prefix().and_then(|p| p.as_str()), Some("\n# c\n  "));
+
+    let key = Key::new("a")
+        .with_leaf_decor(toml_edit::Decor::new("# c\n", " ").unwrap())
+        .unwrap();
+    assert_data_eq!(
+        key.with_dotted_decor(toml_edit::Decor::new("", "\n").unwrap())
+            .unwrap_err()
+            .to_string(),
+        str!["newline is not allowed here"]
+    );
+
+    let mut array = toml_edit::Array::new();
+    array.set_trailing("\n# c\n").unwrap();
+    assert_data_eq!(
+        array.set_trailing(" # c").unwrap_err().to_string(),
+        str!["comment must end with a newline"]
+    );
+
+    let mut table = toml_edit::InlineTable::new();
+    table.set_preamble(" ").unwrap();
+    assert_data_eq!(
+        table.set_preamble("\n").unwrap_err().to_string(),
+        str!["newline is not allowed here"]
+    );
+}
+
+#[test]
+fn test_unusual_whitespace_round_trip() {
+    let inputs = [
+        "a = [ 1 , 2 ,3 , ]\n",
+        "a = [ 1 ,\n  2 ,\n]\n",
+        "[ x . y ]\n[ x . y . z ]\n",
+        "[x.y]\n[ x . y . z ]\n",
+        "[\tx\t.\ty\t]\n",
+        "[[ a . b ]]\n[[a.b]]\n[[ a .b]]\n",
+        "[ a ]\nb . c = 1\n[ a . b . d ]\n",
+        "a.b = 1\na . c = 2\n",
+        "a.b.c = 1\na . b . d = 2\n[x]\ny . z = 1\ny.w = 2\n",
+        "x = { a.b = 1 , a . c = 2 }\n",
+        "x = [ { a . b = 1 } , { a.b = 2 } ]\n",
+    ];
+    for input in inputs {
+        let doc = input.parse::<DocumentMut>().unwrap();
+        assert_eq!(doc.to_string(), input);
+        let doc = DocumentMut::parse_with_spans(input).unwrap();
+        assert_eq!(doc.to_string(), input);
+    }
+}
+
+#[test]
+fn test_unusual_whitespace_edits() {
+    let mut doc = r#"
+[ x . y ]
+[ x . y . z ]
+a = [ 1 , 3 ]
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    doc["x"]["y"]["z"]["a"]
+        .as_array_mut()
+        .unwrap()
+        .push_sorted(2, |l, r| l.as_integer().cmp(&r.as_integer()));
+    doc["x"]["y"]["z"]["a"]
+        .as_array_mut()
+        .unwrap()
+        .push_sorted(4, |l, r| l.as_integer().cmp(&r.as_integer()));
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[ x . y ]
+[ x . y . z ]
+a = [ 1 , 2 , 3 , 4 ]
+
+"#]]
+    );
+
+    // Respacing the keys applies to each header through them, dropping what was kept for one
+    let keys = doc.table_header_keys_mut(&["x", "y", "z"]).unwrap();
+    for mut key in keys {
+        key.dotted_decor_mut().clear();
+        key.leaf_decor_mut().clear();
+    }
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[x.y]
+[x.y.z]
+a = [ 1 , 2 , 3 , 4 ]
+
+"#]]
+    );
+}
+
+#[test]
+fn test_unusual_whitespace_leaf_decor_edits() {
+    let mut doc = "a . b = 1\n".parse::<DocumentMut>().unwrap();
+    let a = doc["a"].as_table_like_mut().unwrap();
+    a.key_mut("b")
+        .unwrap()
+        .leaf_decor_mut()
+        .set_suffix("  ")
+        .unwrap();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+a . b  = 1
+
+"#]]
+    );
+}
@@ -83,6 +83,7 @@ DocumentMut {
             dotted: false,
             doc_position: None,
             span: None,
+            header_decor: None,
             items: {
                 Key {
                     key: "grandparent",
@@ -97,6 +98,8 @@ DocumentMut {
                         prefix: empty,
                         suffix: " ",
                     },
+                    path_decor: None,
+                    id: None,
                 }: Table(
                     Table {
                         decor: Decor {
@@ -107,6 +110,7 @@ DocumentMut {
                         dotted: false,
                         doc_position: None,
                         span: None,
+                        header_decor: None,
                         items: {
                             Key {
                                 key: "parent",
@@ -121,6 +125,8 @@ DocumentMut {
                                     prefix: " ",
                                     suffix: empty,
                                 },
+                                path_decor: None,
+                                id: None,
                             }: Table(
                                 Table {
                                     decor: Decor {
@@ -133,6 +139,7 @@ DocumentMut {
                                         1,
                                     ),
                                     span: None,
+                                    header_decor: None,
                                     items: {
                                         Key {
                                             key: "key",
@@ -147,6 +154,8 @@ DocumentMut {
                                                 prefix: empty,
                                                 suffix: " ",
                                             },
+                                            path_decor: None,
+                                            id: None,
                                         }: Table(
                                             Table {
                                                 decor: Decor {
@@ -157,6 +166,7 @@ DocumentMut {
                                                 dotted: true,
                                                 doc_position: None,
                                                 span: None,
+                                                header_decor: None,
                                                 items: {
                                                     Key {
                                                         key: "child",
@@ -171,6 +181,8 @@ DocumentMut {
                                                             prefix: " ",
                                                             suffix: empty,
                                                         },
+                                                        path_decor: None,
+                                                        id: None,
                                                     }: Value(
                                                         String(
                                                             Formatted {
@@ -205,6 +217,8 @@ DocumentMut {
                         prefix: empty,
                         suffix: " ",
                     },
+                    path_decor: None,
+                    id: None,
                 }: Table(
                     Table {
                         decor: Decor {
@@ -215,6 +229,7 @@ DocumentMut {
                         dotted: false,
                         doc_position: None,
                         span: None,
+                        header_decor: None,
                         items: {
                             Key {
                                 key: "table",
@@ -229,6 +244,8 @@ DocumentMut {
                                     prefix: " ",
                                     suffix: empty,
                                 },
+                                path_decor: None,
+                                id: None,
                             }: Table(
                                 Table {
                                     decor: Decor {
@@ -241,6 +258,7 @@ DocumentMut {
                                         2,
                                     ),
                                     span: None,
+                                    header_decor: None,
                                     items: {},
                                 },
                             ),
@@ -251,6 +269,7 @@ DocumentMut {
         },
     ),
     trailing: " # final comment\n",
+    bom: false,
 }
 
 "#]]
@@ -277,9 +296,12 @@ name = "delete me please"
 path = "src/bin/dmp/main.rs""#,
     )
     .running_on_doc(|document| {
-        document.decor_mut().set_prefix("# Some Header\n\n");
-        document.decor_mut().set_suffix("# Some Footer");
-        document.set_trailing("\n\ntrailing...");
+        document
+            .decor_mut()
+            .set_prefix("# Some Header\n\n")
+            .unwrap();
+        document.decor_mut().set_suffix("# Some Footer").unwrap();
+        document.set_trailing("\n\n# trailing...").unwrap();
     })
     .produces_display(str![[r#"
 # Some Header
@@ -300,7 +322,7 @@ name = "delete me please"
 path = "src/bin/dmp/main.rs"
 # Some Footer
 
-trailing...
+# trailing...
 "#]]);
 }
 
@@ -326,7 +348,7 @@ path = "src/bin/dmp/main.rs""#,
     .running_on_doc(|document| {
         assert!(document.decor().prefix().is_none());
         assert!(document.decor().suffix().is_none());
-        document.set_trailing("\n\ntrailing...");
+        document.set_trailing("\n\n# trailing...").unwrap();
     })
     .produces_display(str![[r#"
 [package]
@@ -345,7 +367,7 @@ name = "delete me please"
 path = "src/bin/dmp/main.rs"
 
 
-trailing...
+# trailing...
 "#]]);
 }
 
@@ -905,6 +927,72 @@ macro_rules! as_array {
     }};
 }
 
+#[test]
+fn test_shrink_to_fit() {
+    given(
+        r#"
+        [package]
+        name = "foo"
+        keywords = ["a", "b"]
+        metadata = { x = 1, y = [2, 3] }
+
+        [[bin]]
+        name = "bar""#,
+    )
+    .running_on_doc(|doc| {
+        doc["package"]["keywords"].as_array_mut().unwrap().remove(1);
+        doc.shrink_to_fit();
+    })
+    .produces_display(str![[r#"
+
+        [package]
+        name = "foo"
+        keywords = ["a"]
+        metadata = { x = 1, y = [2, 3] }
+
+        [[bin]]
+        name = "bar"
+
+"#]]);
+}
+
+#[test]
+fn test_decor_iter_mut() {
+    given(
+        "\t# header\n[package]\nname =\t\"foo\"\t# name\nkeywords = [\t\"a\",\t\"b\"\t]\n\t[[bin]]\n\tname\t= { x =\t1 }\n",
+    )
+    .running_on_doc(|doc| {
+        for (_, decor) in doc.decor_iter_mut() {
+            match decor {
+                toml_edit::DecorMut::Decor(decor) => {
+                    if let Some(prefix) = decor.prefix().and_then(|p| p.as_str()) {
+                        let prefix = prefix.replace('\t', " ");
+                        decor.set_prefix(prefix).unwrap();
+                    }
+                    if let Some(suffix) = decor.suffix().and_then(|p| p.as_str()) {
+                        let suffix = suffix.replace('\t', " ");
+                        decor.set_suffix(suffix).unwrap();
+                    }
+                }
+                toml_edit::DecorMut::Raw(raw) => {
+                    if let Some(s) = raw.as_str() {
+                        *raw = s.replace('\t', " ").into();
+                    }
+                }
+            }
+        }
+    })
+    .produces_display(str![[r#"
+ # header
+[package]
+name = "foo" # name
+keywords = [ "a", "b" ]
+ [[bin]]
+ name = { x = 1 }
+
+"#]]);
+}
+
 #[test]
 fn test_insert_replace_into_array() {
     given(
@@ -928,17 +1016,17 @@ fn test_insert_replace_into_array() {
         b.push("hello");
         assert_eq!(b.len(), 1);
 
-        b.push_formatted(Value::from("world").decorated("\n", "\n"));
-        b.push_formatted(Value::from("test").decorated("", ""));
+        b.push_formatted(Value::from("world").decorated("\n", "\n").unwrap());
+        b.push_formatted(Value::from("test").decorated("", "").unwrap());
 
         b.insert(1, "beep");
-        b.insert_formatted(2, Value::from("boop").decorated("   ", "   "));
+        b.insert_formatted(2, Value::from("boop").decorated("   ", "   ").unwrap());
 
         // This should preserve formatting.
         assert_eq!(b.replace(2, "zoink").as_str(), Some("boop"));
         // This should replace formatting.
         assert_eq!(
-            b.replace_formatted(4, Value::from("yikes").decorated("  ", ""))
+            b.replace_formatted(4, Value::from("yikes").decorated("  ", "").unwrap())
                 .as_str(),
             Some("test")
         );
@@ -1007,6 +1095,70 @@ fn test_format_array() {
     "#]]);
 }
 
+#[test]
+fn test_format_array_multiline() {
+    given(
+        r#"
+    a = [1, "2", 3.0]
+    b = []
+    "#,
+    )
+    .running(|root| {
+        for (_, v) in root.iter_mut() {
+            if let Item::Value(Value::Array(array)) = v {
+                array.fmt_multiline();
+            }
+        }
+    })
+    .produces_display(str![[r#"
+
+    a = [
+    1,
+    "2",
+    3.0,
+]
+    b = []
+    "#]]);
+}
+
+#[test]
+fn test_format_array_multiline_comments() {
+    given(
+        r#"
+a = [ # start
+  1, # one
+  # before two
+  2 # after two
+  , 3 # three
+  # end
+]
+b = [1 # one
+]
+"#,
+    )
+    .running(|root| {
+        for (_, v) in root.iter_mut() {
+            if let Item::Value(Value::Array(array)) = v {
+                array.fmt_multiline();
+            }
+        }
+    })
+    .produces_display(str![[r#"
+
+a = [ # start
+    1, # one
+    # before two
+    2, # after two
+    3, # three
+    # end
+]
+b = [
+    1, # one
+]
+
+"#]]);
+}
+
 macro_rules! as_inline_table {
     ($entry:ident) => {{
         assert!($entry.is_value());
@@ -0,0 +1,272 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::{value, DocumentMut, Item, Key, Table, Value};
+
+#[test]
+fn test_memory_usage() {
+    let input = r#"
+# dependencies
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }  # serialization
+"#;
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    let usage = doc.memory_usage();
+    assert_eq!(usage.shared(), input.len());
+    assert_eq!(usage.owned(), 0);
+
+    // Copies share the input rather than the raw text being copied
+    let mut other = DocumentMut::new();
+    other["serde"] = doc["dependencies"]["serde"].clone();
+    assert_eq!(other.memory_usage().shared(), input.len());
+
+    doc["dependencies"]["serde"]["version"] = value("2.0");
+    doc["dependencies"]["anyhow"] = value("1.0");
+    let usage = doc.memory_usage();
+    assert_eq!(usage.shared(), input.len());
+    assert_eq!(usage.owned(), 0, "new items use the default formatting");
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+# dependencies
+[dependencies]
+serde = { version = "2.0", features = ["derive"] }  # serialization
+anyhow = "1.0"
+
+"#]]
+    );
+}
+
+#[test]
+fn test_item_ids() {
+    let mut doc = r#"
+[package]
+name = "foo"
+
+[dependencies]
+serde_json = { version = "1", features = ["std"] }
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    doc.assign_ids();
+
+    let deps = doc["dependencies"].as_table().unwrap();
+    let dep_id = deps.key("serde_json").unwrap().id().unwrap();
+    let features_id = deps["serde_json"]
+        .as_inline_table()
+        .unwrap()
+        .key("features")
+        .unwrap()
+        .id()
+        .unwrap();
+    assert_ne!(dep_id, features_id);
+    let mut notes = toml_edit::ItemMetadata::new();
+    notes.insert(dep_id, "rename");
+    notes.insert(features_id, "unused");
+
+    let deps = doc["dependencies"].as_table_mut().unwrap();
+    let (key, item) = deps.remove_entry("serde_json").unwrap();
+    deps.insert_formatted(&Key::new("json").with_id(key.id()), item);
+    doc.as_table_mut().insert("edition", value("2021"));
+    doc.assign_ids();
+
+    let path = doc.path_of(features_id).unwrap();
+    let path = path.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    assert_eq!(path, ["dependencies", "json", "features"]);
+    assert!(doc.as_table().key("edition").unwrap().id().is_some());
+
+    doc["dependencies"]["json"]
+        .as_inline_table_mut()
+        .unwrap()
+        .remove("features");
+    notes.retain_present(&doc);
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes.get(dep_id), Some(&"rename"));
+}
+
+#[test]
+fn test_key_index() {
+    let doc = r#"
+version = 1
+[a]
+version = 2
+deps = [{ version = 3, name = "x" }, [{ version = 4 }]]
+[[b]]
+name = "y"
+[b.version]
+major = 5
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let index = doc.key_index();
+    let paths = |found: &[(Vec<toml_edit::PathSegment>, &Item)]| {
+        found
+            .iter()
+            .map(|(path, _)| {
+                let path = path.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+                path.join(".")
+            })
+            .collect::<Vec<_>>()
+    };
+    let expected = [
+        "version",
+        "a.version",
+        "a.deps.0.version",
+        "a.deps.1.0.version",
+        "b.0.version",
+    ];
+    assert_eq!(paths(&doc.find_key_occurrences("version")), expected);
+    assert_eq!(paths(index.get("version")), expected);
+    assert_eq!(paths(index.get("name")), ["a.deps.0.name", "b.0.name"]);
+    assert!(index.get("missing").is_empty());
+    assert!(doc.find_key_occurrences("missing").is_empty());
+    assert!(index.get("version")[4].1.is_table());
+
+    let mut keys = index.keys().collect::<Vec<_>>();
+    keys.sort_unstable();
+    assert_eq!(keys, ["a", "b", "deps", "major", "name", "version"]);
+}
+
+#[test]
+fn test_item_content_hash() {
+    fn hash(item: &Item) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        item.content_hash(&mut hasher);
+        std::hash::Hasher::finish(&hasher)
+    }
+
+    let doc = r#"
+[package]
+name = "foo"  # comment
+version = '1.0.0'
+tags = ["a", "b"]
+when = 1979-05-27T07:32:00Z
+ratio = nan
+
+[[bin]]
+name = "foo"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let other = r#"
+[[bin]]
+name = 'foo'
+
+[package]
+version = "1.0.0"
+when = 1979-05-27 07:32:00Z
+ratio = -nan
+tags = [
+  "a",
+  "b",
+]
+name = "foo"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    assert_eq!(hash(&doc["bin"]), hash(&other["bin"]));
+    assert_eq!(hash(&doc["package"]), hash(&other["package"]));
+
+    let other = r#"
+package = { name = "foo", version = "1.0.0", tags = ["a", "b"], when = 1979-05-27T07:32:00Z, ratio = -nan }
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    assert_eq!(hash(&doc["package"]), hash(&other["package"]));
+
+    let changed = [
+        r#"package = { name = "foo", version = "1.0.1", tags = ["a", "b"], when = 1979-05-27T07:32:00Z, ratio = nan }"#,
+        r#"package = { name = "foo", version = "1.0.0", tags = ["b", "a"], when = 1979-05-27T07:32:00Z, ratio = nan }"#,
+        r#"package = { name = "foo", version = "1.0.0", tags = ["a", "b"], when = 1979-05-27T07:32:00Z, ratio = 1.0 }"#,
+        r#"package = { name = "foo", version = "1.0.0", tags = ["a", "b"], when = 1979-05-27T07:32:00Z }"#,
+    ];
+    for changed in changed {
+        let changed = changed.parse::<DocumentMut>().unwrap();
+        assert_ne!(
+            hash(&doc["package"]),
+            hash(&changed["package"]),
+            "{changed}"
+        );
+    }
+    assert_ne!(hash(&value(1)), hash(&value(1.0)));
+    assert_ne!(hash(&value("1")), hash(&value(1)));
+}
+
+#[test]
+fn test_debug_tree() {
+    let mut doc = r#"
+a.b = { c = 1, d.e = [] }
+[x.y]
+[[bin]]
+name = "app"
+# end
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    doc["x"]["y"]["new"] = value(42);
+    assert_data_eq!(
+        doc.debug_tree().to_string(),
+        str![[r##"
+document
+  "a" = table implicit dotted
+    "b" key-decor=("\n", " ") = inline-table decor=(" ", "")
+      "c" key-decor=(" ", " ") = integer 1 decor=(" ", "")
+      "d" = inline-table dotted
+        "e" key-decor=(" ", " ") = array decor=(" ", " ")
+  "x" = table implicit
+    "y" = table position=1
+      "new" = integer 42
+  "bin" = array-of-tables
+    [0] table position=2
+      "name" key-decor=("", " ") = string "app" decor=(" ", "")
+trailing="# end\n"
+
+"##]]
+    );
+}
+
+#[test]
+fn test_drop_deeply_nested() {
+    const DEPTH: usize = 100_000;
+
+    // Small enough that dropping recursively would overflow it
+    let small_stack = std::thread::Builder::new().stack_size(256 * 1024);
+    small_stack
+        .spawn(|| {
+            let mut value = Value::from(1);
+            for _ in 0..DEPTH {
+                let mut array = toml_edit::Array::new();
+                array.push(value);
+                value = Value::Array(array);
+            }
+            drop(value);
+
+            let mut value = Value::from(1);
+            for _ in 0..DEPTH {
+                let mut table = toml_edit::InlineTable::new();
+                table.insert("a", value);
+                value = Value::InlineTable(table);
+            }
+            drop(value);
+
+            let mut item = Item::Value(Value::from(1));
+            for i in 0..DEPTH {
+                if i % 2 == 0 {
+                    let mut table = Table::new();
+                    table.insert("a", item);
+                    item = Item::Table(table);
+                } else {
+                    let mut tables = toml_edit::ArrayOfTables::new();
+                    tables.push(item.into_table().unwrap());
+                    item = Item::ArrayOfTables(tables);
+                }
+            }
+            let mut doc = DocumentMut::new();
+            doc.insert("root", item);
+            drop(doc);
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
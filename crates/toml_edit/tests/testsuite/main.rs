@@ -1,4 +1,23 @@
 #![recursion_limit = "256"]
 #![allow(clippy::dbg_macro)]
 
+mod array;
+mod decor;
 mod edit;
+mod item;
+mod parse;
+mod patch;
+#[cfg(feature = "reference")]
+mod reference;
+mod schema;
+mod structure;
+mod style;
+#[cfg(feature = "unstable-sync")]
+mod sync;
+#[cfg(feature = "template")]
+mod template;
+mod value;
+#[cfg(feature = "watch")]
+mod watch;
+#[cfg(feature = "workspace")]
+mod workspace;
@@ -0,0 +1,478 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::{table, value, DocumentMut, Item, ParseOptions, ParseStage, TomlSpec, Value};
+
+#[test]
+fn test_parse_with_spans() {
+    let input = r#"name = "app"
+ports = [80, 443]
+server = { host = "localhost" }
+
+[[bin]]
+path = "main.rs"
+"#;
+    let mut doc = DocumentMut::parse_with_spans(input).unwrap();
+    let span_of = |item: &Item| &input[item.span().unwrap()];
+    assert_eq!(span_of(&doc["name"]), r#""app""#);
+    assert_eq!(span_of(&doc["ports"]), "[80, 443]");
+    assert_eq!(
+        &input[doc["ports"][1].as_value().unwrap().span().unwrap()],
+        "443"
+    );
+    assert_eq!(span_of(&doc["server"]), r#"{ host = "localhost" }"#);
+    assert_eq!(span_of(&doc["server"]["host"]), r#""localhost""#);
+    assert_eq!(span_of(&doc["bin"]), "[[bin]]");
+    assert_eq!(span_of(&doc["bin"][0]["path"]), r#""main.rs""#);
+    let key = doc.as_table().key("server").unwrap();
+    assert_eq!(&input[key.span().unwrap()], "server");
+
+    doc["name"] = value("other");
+    doc.insert("edition", value("2021"));
+    assert_eq!(doc["name"].span(), None);
+    assert_eq!(doc["edition"].span(), None);
+    assert_eq!(doc.as_table().key("edition").unwrap().span(), None);
+    assert_eq!(span_of(&doc["ports"]), "[80, 443]");
+
+    let plain = input.parse::<DocumentMut>().unwrap();
+    assert_eq!(plain["name"].span(), None);
+    assert_eq!(plain.as_table().key("server").unwrap().span(), None);
+    let spanned = DocumentMut::parse_with_spans(input).unwrap();
+    assert_eq!(
+        plain.as_table().key("server"),
+        spanned.as_table().key("server")
+    );
+}
+
+#[test]
+fn test_original_text() {
+    let input = r#"# deps
+[dependencies]  # direct
+serde = { version = "1.0" }
+# the parser
+toml = "0.8"   # pinned
+ports = [ 80,   443 ]
+
+[[bin]]
+name = "a"
+
+[[bin]]
+name = "b"
+"#;
+    let doc = toml_edit::Document::parse(input).unwrap();
+    let text = |item: &Item, with_decor| item.original_text(&doc, with_decor);
+
+    assert_eq!(text(&doc["dependencies"]["toml"], false), Some(r#""0.8""#));
+    assert_eq!(
+        text(&doc["dependencies"]["toml"], true),
+        Some(r#" "0.8"   # pinned"#)
+    );
+    assert_eq!(
+        text(&doc["dependencies"]["ports"], false),
+        Some("[ 80,   443 ]")
+    );
+    assert_eq!(
+        text(&doc["dependencies"]["serde"]["version"], true),
+        Some(r#" "1.0" "#)
+    );
+    assert_eq!(text(&doc["dependencies"], false), Some("[dependencies]"));
+    assert_eq!(
+        text(&doc["dependencies"], true),
+        Some(
+            "# deps
+[dependencies]  # direct"
+        )
+    );
+    assert_eq!(text(&doc["bin"][1]["name"], false), Some(r#""b""#));
+    assert_eq!(text(&Item::None, false), None);
+
+    let mut edited = doc.clone().into_mut();
+    edited["dependencies"]["toml"] = value("0.9");
+    assert_eq!(
+        edited["dependencies"]["toml"].original_text(&doc, false),
+        None
+    );
+}
+
+#[test]
+fn test_nested_document() {
+    use toml_edit::nested::NestedDocument;
+
+    let input = r#"[tool]
+snippet = '''
+  [package]
+  name = "app"  # the name
+  '''
+escaped = "a = 1\nb = \"two\"\n"
+broken = "a = "
+"#;
+    let mut doc = DocumentMut::parse_with_spans(input).unwrap();
+    let string = |doc: &mut DocumentMut, key: &str| match doc["tool"][key].as_value_mut() {
+        Some(Value::String(string)) => string.clone(),
+        _ => panic!("`{key}` is a string"),
+    };
+
+    let mut snippet = string(&mut doc, "snippet");
+    let mut nested = NestedDocument::parse(&snippet).unwrap();
+    let name = nested.as_document()["package"]["name"].span().unwrap();
+    let outer = nested.outer_span(name).unwrap();
+    assert_eq!(&input[outer], r#""app""#);
+
+    nested.as_document_mut()["package"]["version"] = value("1.0.0");
+    nested.as_document_mut()["features"] = table();
+    nested.as_document_mut()["features"]["default"] = value(Value::Array(Default::default()));
+    nested.write_to(&mut snippet);
+    doc["tool"]["snippet"] = value(Value::String(snippet));
+
+    let mut escaped = string(&mut doc, "escaped");
+    let mut nested = NestedDocument::parse(&escaped).unwrap();
+    assert_eq!(nested.as_document()["b"].as_str(), Some("two"));
+    let b = nested.as_document()["b"].span().unwrap();
+    assert_eq!(nested.outer_span(b), None);
+    nested.as_document_mut()["c"] = value(3);
+    nested.write_to(&mut escaped);
+    doc["tool"]["escaped"] = value(Value::String(escaped));
+
+    let err = NestedDocument::parse(&string(&mut doc, "broken")).unwrap_err();
+    assert_eq!(err.span(), Some(4..4));
+
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+[tool]
+snippet = '''
+  [package]
+  name = "app"  # the name
+  version = "1.0.0"
+
+  [features]
+  default = []
+  '''
+escaped = "a = 1\nb = \"two\"\nc = 3\n"
+broken = "a = "
+
+"#]]
+    );
+}
+
+#[test]
+fn test_tokens_with_spans() {
+    let input = "\u{feff}[a]\nb = \"é\" # ü\n";
+    let doc = toml_edit::Document::parse(input).unwrap();
+    let tokens = doc.tokens_with_spans();
+    for (token, text) in tokens.iter_text() {
+        let span = token.span();
+        assert_eq!(&input[span.start()..span.end()], text);
+    }
+    assert_data_eq!(
+        tokens.to_string(),
+        str![[r##"
+LeftSquareBracket 3..4 "["
+Atom 4..5 "a"
+RightSquareBracket 5..6 "]"
+Newline 6..7 "\n"
+Atom 7..8 "b"
+Whitespace 8..9 " "
+Equals 9..10 "="
+Whitespace 10..11 " "
+BasicString 11..15 "\"é\""
+Whitespace 15..16 " "
+Comment 16..20 "# ü"
+Newline 20..21 "\n"
+Eof 21..21 ""
+
+"##]]
+    );
+
+    let doc = toml_edit::Document::parse("").unwrap();
+    assert_eq!(doc.tokens_with_spans().into_vec().len(), 1);
+}
+
+#[test]
+fn test_line_index() {
+    let input = r#"a.b = 1 # comment
+[package]
+name = "foo"
+authors = [
+  "bar",
+  { x.y = 1, z = [2] },
+]
+
+[[bin]]
+k = 1
+"#;
+    let doc = input.parse::<DocumentMut>().unwrap();
+    let index = doc.line_index();
+    let actual = (0..11)
+        .map(|line| {
+            let paths = index
+                .items_at(line)
+                .map(|entry| {
+                    let path = entry
+                        .path()
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect::<Vec<_>>();
+                    format!(" {}", path.join("."))
+                })
+                .collect::<String>();
+            format!("{line}:{paths}\n")
+        })
+        .collect::<String>();
+    assert_data_eq!(
+        actual,
+        str![[r#"
+0: a.b
+1: package
+2: package.name
+3: package.authors
+4: package.authors package.authors.0
+5: package.authors package.authors.1 package.authors.1.x.y package.authors.1.z package.authors.1.z.0
+6: package.authors
+7:
+8: bin.0
+9: bin.0.k
+10:
+
+"#]]
+    );
+}
+
+#[test]
+fn test_verify() {
+    let mut doc = "[a.b]\nc = 1\n".parse::<DocumentMut>().unwrap();
+    doc["d"] = value("e");
+    doc["a"]["b"].as_table_mut().unwrap().remove("c");
+    doc["f"] = table();
+    doc["f"].as_table_mut().unwrap().set_implicit(true);
+    assert!(doc.verify().is_ok());
+
+    let decor = doc["d"].as_value_mut().unwrap().decor_mut();
+    decor.set_prefix("\n").unwrap();
+    let err = doc.verify().unwrap_err();
+    assert!(err.discrepancies().is_empty());
+    assert!(err.parse_error().is_some());
+
+    let decor = doc["d"].as_value_mut().unwrap().decor_mut();
+    decor.set_prefix(" ").unwrap();
+    doc.insert("g", value(2));
+    let mut key = doc.key_mut("g").unwrap();
+    key.leaf_decor_mut().set_prefix("# e").unwrap();
+    let err = doc.verify().unwrap_err();
+    let paths = err
+        .discrepancies()
+        .iter()
+        .map(|d| {
+            (
+                d.path().to_vec(),
+                d.expected().is_some(),
+                d.found().is_some(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [(vec![toml_edit::PathSegment::Key("g".into())], true, false)]
+    );
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+written document parses differently
+`g` is missing
+"#]]
+    );
+}
+
+#[test]
+fn test_parse_with_spec() {
+    let legacy = r#"ports = [8080, "8081"]
+nested = [[1, 2], ["a", 'b'], [{ x = 1 }, { y = "2" }]]
+times = [1979-05-27, 07:32:00]
+"#;
+    let modern = r#"point = { x = 1, y = 2, }
+config = {
+  name = "app", # the name
+  tags = [
+    "cli",
+  ],
+}
+empty = {
+}
+"#;
+
+    let v0_5 = ParseOptions::new().spec(TomlSpec::V0_5);
+    let v1_0 = ParseOptions::new();
+    let v1_1 = ParseOptions::new().spec(TomlSpec::V1_1Draft);
+
+    let err = toml_edit::Document::parse_with_options(legacy, &v0_5).unwrap_err();
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+TOML parse error at line 1, column 16
+  |
+1 | ports = [8080, "8081"]
+  |                ^^^^^^
+mixed types in arrays are not allowed before TOML 1.0
+
+"#]]
+    );
+    let ok = "nested = [[1, 2], ['a', \"b\"], [{ x = 1 }, { y = '2' }]]\n";
+    assert!(toml_edit::Document::parse_with_options(ok, &v0_5).is_ok());
+    assert!(toml_edit::Document::parse_with_options(legacy, &v1_0).is_ok());
+    assert!(toml_edit::Document::parse_with_options(legacy, &v1_1).is_ok());
+
+    assert!(toml_edit::Document::parse_with_options(modern, &v0_5).is_err());
+    assert!(toml_edit::Document::parse_with_options(modern, &v1_0).is_err());
+    let doc = toml_edit::Document::parse_with_options(modern, &v1_1).unwrap();
+    assert_eq!(doc.into_mut().to_string(), modern);
+
+    let mut doc = toml_edit::Document::parse_with_options(modern, &v1_1)
+        .unwrap()
+        .into_mut();
+    let point = doc["point"].as_inline_table_mut().unwrap();
+    assert!(point.trailing_comma());
+    point.set_trailing_comma(false);
+    point.insert("z", 3.into());
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+point = { x = 1, y = 2, z = 3 }
+config = {
+  name = "app", # the name
+  tags = [
+    "cli",
+  ],
+}
+empty = {
+}
+
+"#]]
+    );
+}
+
+#[test]
+fn test_parse_with_progress() {
+    let mut input = String::new();
+    for i in 0..2_000 {
+        input.push_str(&format!(
+            "[package{i}]\nname = \"app{i}\"\nversions = [1, 2, 3]\nmeta = {{ a = 1, b = 2 }}\n\n"
+        ));
+    }
+    let options = ParseOptions::new();
+
+    let mut reports = Vec::new();
+    let doc = toml_edit::Document::parse_with_progress(
+        input.as_str(),
+        &options,
+        &mut |progress| reports.push(progress),
+        &toml_edit::CancellationToken::new(),
+    )
+    .unwrap();
+    assert_eq!(doc.len(), 2_000);
+    assert!(reports.len() > 4, "too few reports: {}", reports.len());
+    for pair in reports.windows(2) {
+        let (before, after) = (pair[0], pair[1]);
+        assert!((before.stage, before.bytes) < (after.stage, after.bytes));
+        assert!(before.expressions <= after.expressions);
+    }
+    for report in &reports {
+        assert_eq!(report.total_bytes, input.len());
+    }
+    let lexed = reports
+        .iter()
+        .rfind(|p| p.stage == ParseStage::Lexing)
+        .unwrap();
+    assert_eq!(lexed.bytes, input.len());
+    let parsed = reports
+        .iter()
+        .rfind(|p| p.stage == ParseStage::Parsing)
+        .unwrap();
+    assert_eq!(parsed.bytes, input.len());
+    assert_eq!(parsed.expressions, 4 * 2_000);
+    let built = reports.last().unwrap();
+    assert_eq!(built.stage, ParseStage::Building);
+    assert_eq!(built.bytes, input.len());
+    assert_eq!(built.expressions, 4 * 2_000);
+
+    let token = toml_edit::CancellationToken::new();
+    let mut parsing_reports = 0;
+    let err = toml_edit::Document::parse_with_progress(
+        input.as_str(),
+        &options,
+        &mut |progress| {
+            if progress.stage == ParseStage::Parsing {
+                parsing_reports += 1;
+                token.cancel();
+            }
+        },
+        &token,
+    )
+    .unwrap_err();
+    assert_eq!(err.message(), "parsing was cancelled");
+    assert_eq!(parsing_reports, 1);
+    assert!(token.is_cancelled());
+
+    let token = toml_edit::CancellationToken::new();
+    let mut building_reports = 0;
+    let err = toml_edit::Document::parse_with_progress(
+        input.as_str(),
+        &options,
+        &mut |progress| {
+            if progress.stage == ParseStage::Building {
+                building_reports += 1;
+                if progress.bytes < progress.total_bytes {
+                    token.cancel();
+                }
+            }
+        },
+        &token,
+    )
+    .unwrap_err();
+    assert_eq!(err.message(), "parsing was cancelled");
+    assert_eq!(building_reports, 1);
+}
+
+#[test]
+fn test_parse_error_source_id() {
+    use toml_edit::SourceId;
+
+    let err = "a = 1\nb = \n".parse::<DocumentMut>().unwrap_err();
+    assert_eq!(err.source_id(), SourceId::default());
+    let message = err.to_string();
+    let span = err.span();
+
+    let err = err.with_source_id(SourceId::new(7));
+    assert_eq!(err.source_id(), SourceId::new(7));
+    assert_eq!(err.source_id().get(), 7);
+    assert_eq!(err.span(), span);
+    assert_eq!(err.to_string(), message);
+
+    // Each file's errors can be told apart
+    let files = ["a = 1\n", "a = \n", "a = 1\na = 2\n"];
+    let errors = files
+        .iter()
+        .enumerate()
+        .flat_map(|(i, file)| {
+            let id = SourceId::new(i as u32);
+            let (_, errors) = toml_edit::Document::parse_lossy(*file);
+            errors.into_iter().map(move |e| e.with_source_id(id))
+        })
+        .map(|e| (e.source_id().get(), e.span()))
+        .collect::<Vec<_>>();
+    assert_eq!(errors, [(1, Some(4..4)), (2, Some(6..7))]);
+
+    // Errors not from the parser default too
+    let token = toml_edit::CancellationToken::new();
+    token.cancel();
+    let err = toml_edit::Document::parse_with_progress(
+        "a = 1\n",
+        &ParseOptions::new(),
+        &mut |_| {},
+        &token,
+    )
+    .unwrap_err();
+    assert_eq!(err.source_id(), SourceId::default());
+    assert_eq!(
+        err.with_source_id(SourceId::new(2)).source_id(),
+        SourceId::new(2)
+    );
+}
@@ -0,0 +1,124 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::{value, DocumentMut, Value};
+
+#[test]
+fn test_text_patch() {
+    use toml_edit::patch::{apply_text_patch, make_text_patch};
+
+    let text = r#"# Project
+[package]
+name = "foo" # keep me
+edition = "2018"
+
+# Crates
+[dependencies]
+regex = "1"
+serde = "1"
+"#;
+    let old = text.parse::<DocumentMut>().unwrap();
+    let mut new = old.clone();
+    new["package"]["edition"] = value("2021");
+    new["dependencies"].as_table_mut().unwrap().remove("regex");
+
+    let edits = make_text_patch(&old, &new);
+    let edits = edits
+        .iter()
+        .map(|edit| (&text[edit.range()], edit.new_text()))
+        .collect::<Vec<_>>();
+    assert_eq!(edits, [("18", "21"), ("regex = \"1\"\n", "")]);
+    assert_eq!(
+        apply_text_patch(text, &make_text_patch(&old, &new)),
+        new.to_string()
+    );
+    assert!(make_text_patch(&old, &old.clone()).is_empty());
+}
+
+#[test]
+fn test_apply_text_edits() {
+    use toml_edit::patch::{make_text_patch, TextEdit};
+
+    let mut doc = r#"name = "app"
+
+[[bin]]
+name = "a"
+opts = { debug = true }
+
+[[bin]]
+name = "b"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    doc.assign_ids();
+    let id_of = |doc: &DocumentMut, i: usize, key: &str| {
+        let bin = doc["bin"].as_array_of_tables().unwrap().get(i).unwrap();
+        bin.key(key).unwrap().id().unwrap()
+    };
+    let name_id = doc.as_table().key("name").unwrap().id();
+    let a_id = id_of(&doc, 0, "opts");
+    let b_id = id_of(&doc, 1, "name");
+
+    let mut edited = doc.clone();
+    edited["bin"][0]["opts"]["debug"] = value(false);
+    edited["bin"][1]["path"] = value("b.rs");
+    doc.apply_text_edits(&make_text_patch(&doc, &edited))
+        .unwrap();
+    assert_eq!(doc.to_string(), edited.to_string());
+    assert_eq!(doc.as_table().key("name").unwrap().id(), name_id);
+    assert_eq!(id_of(&doc, 0, "opts"), a_id);
+    assert_eq!(id_of(&doc, 1, "name"), b_id);
+    let path = doc["bin"][1].as_table().unwrap().key("path").unwrap().id();
+    assert_eq!(path, None);
+
+    let before = doc.to_string();
+    let edit = TextEdit::new(0..4, "[name");
+    let err = doc.apply_text_edits(&[edit]).unwrap_err();
+    assert!(err.span().is_some());
+    assert_eq!(doc.to_string(), before);
+}
+
+#[test]
+fn test_replace_values() {
+    let mut doc = r#"
+registry = "https://old.example.com" # comment
+mirrors = ["https://old.example.com", 'https://other.example.com']
+
+[[source]]
+url = "https://old.example.com"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let changes = doc.replace_values(|_, value| {
+        (value.as_str() == Some("https://old.example.com"))
+            .then(|| Value::from("https://new.example.com"))
+    });
+    let paths = changes
+        .iter()
+        .map(|c| {
+            c.path()
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(".")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(paths, ["registry", "mirrors.0", "source.0.url"]);
+    assert_eq!(
+        changes[0].old_value().as_str(),
+        Some("https://old.example.com")
+    );
+
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+registry = "https://new.example.com" # comment
+mirrors = ["https://new.example.com", 'https://other.example.com']
+
+[[source]]
+url = "https://new.example.com"
+
+"#]]
+    );
+}
@@ -0,0 +1,169 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::reference::{resolve, Resolver};
+use toml_edit::{Document, DocumentMut};
+
+#[test]
+fn resolve_within_document() {
+    let doc = r#"
+[shared]
+port = 8080
+ports = [{ ref = "shared.port" }, 9090]
+
+[server]
+port = { ref = "shared.port" }  # from shared
+alias = { ref = "server.port" }
+
+[database]
+ref = "shared"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let resolved = DocumentMut::from(resolve(doc.as_table()).unwrap());
+    assert_data_eq!(
+        resolved.to_string(),
+        str![[r#"
+
+[shared]
+port = 8080
+ports = [8080, 9090]
+
+[server]
+port = 8080  # from shared
+alias = 8080
+
+[database]
+port = 8080
+ports = [8080, 9090]
+
+"#]]
+    );
+}
+
+#[test]
+fn resolve_other_document() {
+    let common = r#"
+[ports]
+http = 8080
+alias = { ref = "ports.http" }
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let doc = r#"
+port = { ref = "ports.alias", document = "common" }
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let resolved = Resolver::new()
+        .document("common", common.as_table())
+        .resolve(doc.as_table())
+        .unwrap();
+    assert_eq!(resolved["port"].as_integer(), Some(8080));
+}
+
+#[test]
+fn resolve_without_references_is_unchanged() {
+    let input = "a = 1\n[b]\nc = { d = 'e' }\n";
+    let doc = input.parse::<DocumentMut>().unwrap();
+    let resolved = DocumentMut::from(resolve(doc.as_table()).unwrap());
+    assert_eq!(resolved.to_string(), input);
+}
+
+#[test]
+fn missing_reference() {
+    let input = "[server]\nport = { ref = 'shared.port' }\n";
+    let doc = Document::parse(input).unwrap();
+    let err = resolve(doc.as_table()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`server.port` refers to `shared.port`, which does not exist"
+    );
+    assert_eq!(err.key(), "server.port");
+    assert_eq!(err.reference(), "shared.port");
+    assert_eq!(&input[err.span().unwrap()], "{ ref = 'shared.port' }");
+    assert_eq!(err.cycle(), None);
+}
+
+#[test]
+fn reference_through_value() {
+    let doc = "a = 1\nb = { ref = 'a.c' }\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let err = resolve(doc.as_table()).unwrap_err();
+    assert_eq!(err.to_string(), "`b` refers to `a.c`, which does not exist");
+}
+
+#[test]
+fn invalid_reference() {
+    let doc = "a = { ref = 'not a key' }\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let err = resolve(doc.as_table()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`a` refers to `not a key`, which is not a dotted key"
+    );
+}
+
+#[test]
+fn unknown_document() {
+    let doc = "[[bin]]\npath = { ref = 'a', document = 'common' }\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let err = resolve(doc.as_table()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`bin[0].path` refers to unknown document `common`"
+    );
+}
+
+#[test]
+fn missing_in_other_document_is_relative_to_it() {
+    let common = "a = { ref = 'b' }\n".parse::<DocumentMut>().unwrap();
+    let doc = "x = { ref = 'a', document = 'common' }\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let err = Resolver::new()
+        .document("common", common.as_table())
+        .resolve(doc.as_table())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "`a` refers to `b`, which does not exist");
+}
+
+#[test]
+fn self_cycle() {
+    let doc = "a = { ref = 'a' }\n".parse::<DocumentMut>().unwrap();
+    let err = resolve(doc.as_table()).unwrap_err();
+    assert_eq!(err.to_string(), "reference cycle: a -> a");
+    assert_eq!(err.cycle(), Some(&["a".to_owned(), "a".to_owned()][..]));
+}
+
+#[test]
+fn cycle_through_table() {
+    let input = "[a]\nb = { ref = 'c' }\n[c]\nref = 'a'\n";
+    let doc = Document::parse(input).unwrap();
+    let err = resolve(doc.as_table()).unwrap_err();
+    assert_eq!(err.to_string(), "reference cycle: c -> a -> c");
+    assert_eq!(err.key(), "a.b");
+}
+
+#[test]
+fn cycle_across_documents() {
+    let common = "a = { ref = 'b', document = 'main' }\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let main = "b = { ref = 'a', document = 'common' }\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let err = Resolver::new()
+        .document("common", common.as_table())
+        .document("main", main.as_table())
+        .resolve(main.as_table())
+        .unwrap_err();
+    assert_eq!(
+        err.cycle().unwrap(),
+        ["common:a", "main:b", "common:a"].map(String::from)
+    );
+}
@@ -0,0 +1,146 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::DocumentMut;
+
+#[test]
+fn test_schema_template() {
+    use toml_edit::schema::Schema;
+
+    let schema = Schema::table()
+        .with_description("Example config")
+        .with_field(
+            "name",
+            Schema::string().with_description("Name of the package\n\nMust be unique"),
+        )
+        .with_field("tags", Schema::array(Schema::string()))
+        .with_field("released", Schema::datetime())
+        .with_field(
+            "server",
+            Schema::table()
+                .with_description("Where to listen")
+                .with_field("port", Schema::integer().with_default(8080))
+                .with_field("tls", Schema::table().with_field("cert", Schema::string())),
+        )
+        .with_field(
+            "deps",
+            Schema::table().with_field("a", Schema::table().with_field("v", Schema::string())),
+        )
+        .with_field(
+            "bin",
+            Schema::array(
+                Schema::table()
+                    .with_field("path", Schema::string().with_description("Entry point")),
+            )
+            .with_description("Binaries"),
+        );
+    assert_data_eq!(
+        schema.to_template().to_string(),
+        str![[r#"
+# Example config
+
+# Name of the package
+#
+# Must be unique
+name = ""
+tags = [""]
+released = 1979-05-27T07:32:00Z
+
+# Where to listen
+[server]
+port = 8080
+
+[server.tls]
+cert = ""
+
+[deps.a]
+v = ""
+
+# Binaries
+[[bin]]
+# Entry point
+path = ""
+
+"#]]
+    );
+}
+
+#[test]
+fn test_schema_apply() {
+    use toml_edit::schema::Schema;
+
+    let schema = Schema::table()
+        .with_field("name", Schema::string())
+        .with_field("weights", Schema::array(Schema::float()))
+        .with_field(
+            "server",
+            Schema::table()
+                .with_field("host", Schema::string().with_default("localhost"))
+                .with_field("port", Schema::integer().with_default(8080)),
+        )
+        .with_field(
+            "limits",
+            Schema::table().with_field("retries", Schema::integer().with_default(3)),
+        )
+        .with_field(
+            "bin",
+            Schema::array(
+                Schema::table()
+                    .with_field("path", Schema::string())
+                    .with_field("test", Schema::boolean().with_default(true)),
+            ),
+        );
+    let input = r#"name = "app"
+weights = [1, 0.5, "2"]
+server = { port = 80 }
+
+[[bin]]
+path = "main.rs"
+
+[[bin]]
+path = 1
+test = false
+"#;
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+
+    let report = schema.validate(&doc);
+    assert_eq!(doc.to_string(), input);
+    let mismatches = report
+        .mismatches()
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        mismatches,
+        [
+            "`weights[2]` expects float, found string",
+            "`bin[1].path` expects string, found integer"
+        ]
+    );
+    assert!(!report.is_valid());
+
+    let report = schema.apply(&mut doc, Some("default"));
+    assert_eq!(report.defaulted().len(), 3);
+    assert_eq!(report.coerced().len(), 1);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+name = "app"
+weights = [1.0, 0.5, "2"]
+server = { port = 80 , host = "localhost" }
+
+[[bin]]
+path = "main.rs"
+# default
+test = true
+
+[[bin]]
+path = 1
+test = false
+
+[limits]
+# default
+retries = 3
+
+"#]]
+    );
+}
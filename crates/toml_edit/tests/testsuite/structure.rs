@@ -0,0 +1,981 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::{table, value, DocumentMut, Key, Table, TableKind, Value};
+
+#[test]
+fn test_extract_prefix_and_graft_at() {
+    let mut doc = r#"
+[project]
+name = "foo"
+
+# myapp settings
+[tool.myapp]
+level = 1
+
+[tool.myapp.paths]
+src = "src"
+
+[tool.other]
+x = 1
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let myapp = doc.extract_prefix("tool.myapp").unwrap().unwrap();
+    assert_data_eq!(
+        myapp.to_string(),
+        str![[r#"
+
+# myapp settings
+level = 1
+
+[paths]
+src = "src"
+
+"#]]
+    );
+    assert!(doc.extract_prefix("tool.missing").unwrap().is_none());
+
+    doc.graft_at("tool.renamed", myapp).unwrap();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[project]
+name = "foo"
+
+[tool.other]
+x = 1
+
+# myapp settings
+[tool.renamed]
+level = 1
+
+[tool.renamed.paths]
+src = "src"
+
+"#]]
+    );
+
+    let other = DocumentMut::new();
+    assert!(doc.graft_at("project.name.nested", other).is_err());
+}
+
+#[test]
+fn test_remove_item_lines() {
+    let input = r#"
+[package]
+name = "foo"
+
+# separated
+
+# deps
+[dependencies]
+a.b = 1  # comment
+c = 2
+
+[[bin]]
+name = "x"
+"#;
+    let doc = toml_edit::Document::parse(input).unwrap();
+
+    let span = doc["dependencies"]["a"]["b"].span().unwrap();
+    let (edited, removed) = doc.remove_item_lines(span).unwrap();
+    assert_data_eq!(
+        removed,
+        str![[r#"
+a.b = 1  # comment
+
+"#]]
+    );
+    assert_data_eq!(
+        edited,
+        str![[r#"
+
+[package]
+name = "foo"
+
+# separated
+
+# deps
+[dependencies]
+c = 2
+
+[[bin]]
+name = "x"
+
+"#]]
+    );
+
+    let span = doc["dependencies"].span().unwrap();
+    let (edited, removed) = doc.remove_item_lines(span).unwrap();
+    assert_data_eq!(
+        removed,
+        str![[r#"
+# deps
+[dependencies]
+a.b = 1  # comment
+c = 2
+
+"#]]
+    );
+    assert_data_eq!(
+        edited,
+        str![[r#"
+
+[package]
+name = "foo"
+
+# separated
+
+
+[[bin]]
+name = "x"
+
+"#]]
+    );
+}
+
+#[test]
+fn test_try_insert() {
+    let doc = toml_edit::Document::parse(
+        r#"
+name = "foo"
+[target.x86_64.dependencies]
+"#,
+    )
+    .unwrap();
+    let mut root = doc.as_table().clone();
+
+    let old = root.try_insert("name", value("bar")).unwrap();
+    assert_eq!(old.unwrap().as_str(), Some("foo"));
+    assert!(root.try_insert("version", value("1.0")).unwrap().is_none());
+
+    let err = root.try_insert("version", value(1)).unwrap_err();
+    assert_eq!(err.key(), "version");
+    assert_eq!(err.existing_type(), "string");
+    assert_eq!(err.new_type(), "integer");
+    assert_eq!(err.into_item().as_integer(), Some(1));
+
+    let err = root.try_insert("target", table()).unwrap_err();
+    assert!(err.is_implicit_table());
+    assert_eq!(err.span(), Some(15..21));
+    assert_data_eq!(
+        err.to_string(),
+        str!["cannot replace implicit table `target` with table"]
+    );
+    assert!(root.contains_table("target"));
+}
+
+#[test]
+fn test_place_table() {
+    let mut doc = r#"[dependencies.a]
+version = "1"
+[dependencies.c]
+version = "1"
+[features]
+default = []
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    doc["dependencies"]["b"] = table();
+    doc["dependencies"]["b"]["version"] = value("2");
+    assert!(doc.place_table(&["dependencies", "b"], toml_edit::TablePlacement::Grouped));
+    doc["lints"] = table();
+    doc["lints"]["rust"] = value("warn");
+    assert!(doc.place_table(
+        &["lints"],
+        toml_edit::TablePlacement::After(&["dependencies"])
+    ));
+    assert!(!doc.place_table(&["missing"], toml_edit::TablePlacement::End));
+
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+[dependencies.a]
+version = "1"
+[dependencies.c]
+version = "1"
+[dependencies.b]
+version = "2"
+[lints]
+rust = "warn"
+[features]
+default = []
+
+"#]]
+    );
+}
+
+#[test]
+fn test_from_pairs() {
+    let pairs = [
+        ("package.name", Value::from("foo")),
+        ("dependencies.a.version", Value::from("1")),
+        ("features.default", Value::from(toml_edit::Array::new())),
+        ("dependencies.b", Value::from("2")),
+        ("dependencies.'c.d'.version", Value::from("3")),
+    ];
+    let doc = DocumentMut::from_pairs(pairs, toml_edit::TablePlacement::Grouped).unwrap();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+[package]
+name = "foo"
+
+[dependencies]
+b = "2"
+
+[dependencies.a]
+version = "1"
+
+[dependencies.'c.d']
+version = "3"
+
+[features]
+default = []
+
+"#]]
+    );
+
+    let err = DocumentMut::from_pairs([("a b", Value::from(1))], toml_edit::TablePlacement::End)
+        .unwrap_err();
+    assert_eq!(err.index(), 0);
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"pair 0 at `a b` failed: invalid key: unexpected content, expected nothing"#]]
+    );
+
+    let err = DocumentMut::from_pairs(
+        [("a.b", Value::from(1)), ("a.b", Value::from(2))],
+        toml_edit::TablePlacement::End,
+    )
+    .unwrap_err();
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"pair 1 at `a.b` failed: key is already an integer"#]]
+    );
+}
+
+#[test]
+fn test_flatten() {
+    let doc = r#"
+version = 1
+a = { b = [1, { c = 2 }], d = [] }
+[[bin]]
+name = "foo"
+[[bin]]
+name = "bar"
+[empty]
+[target.'cfg(unix)']
+x = "y"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let flatten = |arrays| {
+        doc.flatten(arrays)
+            .map(|(path, value)| {
+                let path = path.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+                format!("{} = {}\n", path.join("."), value.to_string().trim())
+            })
+            .collect::<String>()
+    };
+    assert_data_eq!(
+        flatten(toml_edit::FlattenArrays::Index),
+        str![[r#"
+version = 1
+a.b.0 = 1
+a.b.1.c = 2
+bin.0.name = "foo"
+bin.1.name = "bar"
+target.cfg(unix).x = "y"
+
+"#]]
+    );
+    assert_data_eq!(
+        flatten(toml_edit::FlattenArrays::Whole),
+        str![[r#"
+version = 1
+a.b = [1, { c = 2 }]
+a.d = []
+target.cfg(unix).x = "y"
+
+"#]]
+    );
+    assert_data_eq!(
+        flatten(toml_edit::FlattenArrays::Skip),
+        str![[r#"
+version = 1
+target.cfg(unix).x = "y"
+
+"#]]
+    );
+
+    let pairs = doc
+        .flatten(toml_edit::FlattenArrays::Whole)
+        .map(|(path, value)| {
+            let path = path.iter().map(|s| Key::new(s.to_string()).to_string());
+            (path.collect::<Vec<_>>().join("."), value.clone())
+        });
+    let doc = DocumentMut::from_pairs(pairs, toml_edit::TablePlacement::End).unwrap();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+version = 1
+
+[a]
+b = [1, { c = 2 }]
+d = []
+
+[target."cfg(unix)"]
+x = "y"
+
+"#]]
+    );
+}
+
+#[test]
+fn test_inline_table_dotted_keys() {
+    let mut doc = r#"dep = { version = "1", features.std = true, target = { os = "linux" } }
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let dep = doc["dep"].as_inline_table_mut().unwrap();
+    assert_eq!(
+        dep.get_path(&["features", "std"]).and_then(|v| v.as_bool()),
+        Some(true)
+    );
+    assert_eq!(
+        dep.get_path(&["target", "os"]).and_then(|v| v.as_str()),
+        Some("linux")
+    );
+    assert!(dep.get_path(&["version", "major"]).is_none());
+    assert!(dep.get_path(&[]).is_none());
+
+    *dep.get_path_mut(&["features", "std"]).unwrap() = false.into();
+    assert!(dep
+        .insert_path(&["features", "serde"], true.into())
+        .unwrap()
+        .is_none());
+    assert!(dep
+        .insert_path(&["target", "arch"], "x86_64".into())
+        .unwrap()
+        .is_none());
+    assert!(dep
+        .insert_path(&["registry", "name"], "internal".into())
+        .unwrap()
+        .is_none());
+    let err = dep
+        .insert_path(&["version", "major"], 1.into())
+        .unwrap_err();
+    assert_eq!(err.key(), "version");
+
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+dep = { version = "1", features.std = false, features.serde = true, target = { os = "linux", arch = "x86_64" }, registry.name = "internal" }
+
+"#]]
+    );
+}
+
+#[test]
+fn test_append() {
+    let base = r#"
+name = "base"
+deps = { a = "1" }
+
+[a]
+x = 1
+
+[[bin]]
+name = "first"
+
+[c]
+y = 2
+"#;
+    let other = r#"
+# overridden
+name = "other"
+deps = { b = "2", a = "3" }
+extra = true
+
+[a.sub]
+w = 4
+
+[[bin]]
+name = "second"
+"#;
+
+    let mut doc = base.parse::<DocumentMut>().unwrap();
+    let mut table = other.parse::<DocumentMut>().unwrap();
+    let conflicts = doc.append(&mut table, toml_edit::ConflictPolicy::Keep);
+    assert_eq!(conflicts, [["name"], ["deps"], ["a"], ["bin"]]);
+    assert!(table.is_empty());
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+name = "base"
+deps = { a = "1" }
+extra = true
+
+[a]
+x = 1
+
+[[bin]]
+name = "first"
+
+[c]
+y = 2
+
+"#]]
+    );
+
+    let mut doc = base.parse::<DocumentMut>().unwrap();
+    let mut table = other.parse::<DocumentMut>().unwrap();
+    let conflicts = doc.append(&mut table, toml_edit::ConflictPolicy::Replace);
+    assert_eq!(conflicts, [["name"], ["deps"], ["a"], ["bin"]]);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+# overridden
+name = "other"
+deps = { b = "2", a = "3" }
+extra = true
+
+[a.sub]
+w = 4
+
+[[bin]]
+name = "second"
+
+[c]
+y = 2
+
+"#]]
+    );
+
+    let mut doc = base.parse::<DocumentMut>().unwrap();
+    let mut table = other.parse::<DocumentMut>().unwrap();
+    let conflicts = doc.append(&mut table, toml_edit::ConflictPolicy::Merge);
+    assert_eq!(conflicts, [vec!["name"], vec!["deps", "a"]]);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+# overridden
+name = "other"
+deps = { a = "3", b = "2" }
+extra = true
+
+[a]
+x = 1
+
+[a.sub]
+w = 4
+
+[[bin]]
+name = "first"
+
+[[bin]]
+name = "second"
+
+[c]
+y = 2
+
+"#]]
+    );
+}
+
+#[test]
+fn test_remove_path() {
+    let input = r#"
+[package]
+name = "app"
+metadata.release.tag = "v1"
+
+[package.metadata.docs]
+all-features = true
+
+[dependencies]
+serde = { features = ["derive"] }
+"#;
+
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    assert!(doc.remove_path(&[], true).is_none());
+    assert!(doc.remove_path(&["package", "missing"], true).is_none());
+    assert!(doc
+        .remove_path(&["package", "name", "inner"], true)
+        .is_none());
+    assert_eq!(doc.to_string(), input);
+
+    let removed = doc
+        .remove_path(&["package", "metadata", "docs", "all-features"], false)
+        .unwrap();
+    assert_eq!(removed.as_bool(), Some(true));
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[package]
+name = "app"
+metadata.release.tag = "v1"
+
+[package.metadata.docs]
+
+[dependencies]
+serde = { features = ["derive"] }
+
+"#]]
+    );
+
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    doc.remove_path(&["package", "metadata", "docs", "all-features"], true)
+        .unwrap();
+    doc.remove_path(&["package", "metadata", "release", "tag"], true)
+        .unwrap();
+    doc.remove_path(&["dependencies", "serde", "features"], true)
+        .unwrap();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+[package]
+name = "app"
+
+[dependencies]
+serde = {}
+
+"#]]
+    );
+}
+
+#[test]
+fn test_dotted_keys_and_headers() {
+    let input = r#"
+x = 1
+
+# about a
+[a] # header
+y = 2
+[a.b.c]
+z = 3
+[[bin]]
+name = "b"
+[bin.meta]
+n = 1
+[empty]
+"#;
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    doc.as_table_mut().collapse_to_dotted(1);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+x = 1
+
+# about a
+[a] # header
+y = 2
+
+[a.b]
+c.z = 3
+[[bin]]
+name = "b"
+meta.n = 1
+[empty]
+
+"#]]
+    );
+
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    doc.as_table_mut().collapse_to_dotted(3);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+x = 1
+
+# about a
+# header
+a.y = 2
+a.b.c.z = 3
+[[bin]]
+name = "b"
+meta.n = 1
+[empty]
+
+"#]]
+    );
+    let collapsed = doc.to_string().parse::<DocumentMut>().unwrap();
+    assert_eq!(collapsed.to_string(), doc.to_string());
+
+    let mut doc = collapsed;
+    doc.as_table_mut().expand_dotted_keys();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+x = 1
+
+[a]
+
+# about a
+# header
+y = 2
+
+[a.b.c]
+z = 3
+[[bin]]
+name = "b"
+
+[bin.meta]
+n = 1
+[empty]
+
+"#]]
+    );
+    let expanded = doc.to_string().parse::<DocumentMut>().unwrap();
+    assert_eq!(expanded.to_string(), doc.to_string());
+    let values = |doc: &DocumentMut| {
+        doc.flatten(toml_edit::FlattenArrays::Index)
+            .map(|(path, value)| (path, value.to_string().trim().to_owned()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        values(&expanded),
+        values(&input.parse::<DocumentMut>().unwrap())
+    );
+}
+
+#[test]
+fn test_from_root_table() {
+    let input = r#"
+# leading
+a = 1 # trailing
+b = [
+  # inside
+  1,
+]
+
+[t] # header
+c = { d = 2 }
+"#;
+    let doc = input.parse::<DocumentMut>().unwrap();
+    let doc = DocumentMut::from_root_table(doc.into_table()).unwrap();
+    assert_eq!(doc.to_string(), input);
+
+    let mut root = Table::new();
+    root.insert("a", value(Value::from(1).decorated(" ", " # c").unwrap()));
+    let doc = DocumentMut::from_root_table(root.clone()).unwrap();
+    assert_eq!(doc.to_string(), "a = 1 # c\n");
+
+    root.insert("a", value(Value::from(1).decorated(" ", " # c\n").unwrap()));
+    let err = DocumentMut::from_root_table(root.clone()).unwrap_err();
+    assert_data_eq!(err.to_string(), str!["newline is not allowed here at `a`"]);
+
+    let mut array = toml_edit::Array::new();
+    array.push_formatted(Value::from(1).decorated("\n", "\n").unwrap());
+    root.insert("a", value(array));
+    let doc = DocumentMut::from_root_table(root.clone()).unwrap();
+    assert_eq!(doc.to_string(), "a = [\n1\n]\n");
+    assert!(doc.verify().is_ok());
+
+    let mut inline = toml_edit::InlineTable::new();
+    inline.insert("x", Value::from(1));
+    root.insert("a", value(inline));
+    root["a"]["y"] = table();
+    let err = DocumentMut::from_root_table(root.clone()).unwrap_err();
+    assert_data_eq!(
+        err.to_string(),
+        str!["inline tables may only contain values at `a.y`"]
+    );
+}
+
+#[test]
+fn test_to_string_with_path() {
+    let doc = r#"
+[package]
+name = "foo"
+x.y = 2 # comment
+
+[package.metadata]
+flag = true
+
+[[bin]]
+k = 1
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    assert_data_eq!(
+        doc["package"]["metadata"]
+            .to_string_with_path("package.metadata")
+            .unwrap(),
+        str![[r#"
+[package.metadata]
+flag = true
+
+"#]]
+    );
+    assert_data_eq!(
+        doc["package"]["x"].to_string_with_path("tool.x").unwrap(),
+        str![[r#"
+[tool.x]
+y = 2 # comment
+
+"#]]
+    );
+    assert_data_eq!(
+        doc["package"]["name"].to_string_with_path("name").unwrap(),
+        str![[r#"
+name = "foo"
+
+"#]]
+    );
+    assert_data_eq!(
+        doc["bin"].to_string_with_path("bin").unwrap(),
+        str![[r#"
+[[bin]]
+k = 1
+
+"#]]
+    );
+    assert_data_eq!(
+        doc["package"]["metadata"].to_string_with_path("").unwrap(),
+        str![[r#"
+flag = true
+
+"#]]
+    );
+    assert!(doc["package"]["name"].to_string_with_path("").is_err());
+}
+
+#[test]
+fn test_rename_quoted_keys() {
+    let mut doc = r#"
+"" = 1
+"a b" = { "c.d" = 2, "Größe" = 3 }
+"tab	stop" = 4
+
+[[bin]]
+"main.rs" = "src/main.rs"
+main_rs = "taken"
+
+[dotted]
+"x y".z = 5
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let reasons = doc
+        .quoted_keys()
+        .iter()
+        .map(|k| {
+            let path = k
+                .path()
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(".");
+            format!("{path}: {:?}", k.reasons())
+        })
+        .collect::<Vec<_>>();
+    assert_data_eq!(
+        reasons.join("\n"),
+        str![[r#"
+: [Empty]
+a b: [Whitespace]
+a b.c.d: [Dot]
+a b.Größe: [NonAscii]
+tab	stop: [Whitespace]
+bin.0.main.rs: [Dot]
+dotted.x y: [Whitespace]
+"#]]
+    );
+
+    let renames = doc.rename_quoted_keys(&toml_edit::Transliteration::new());
+    let renames = renames
+        .iter()
+        .map(|r| format!("{} -> {}", r.from(), r.to()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        renames,
+        [
+            "a b -> a_b",
+            "tab\tstop -> tab_stop",
+            "c.d -> c_d",
+            "Größe -> Grosse",
+            "x y -> x_y"
+        ]
+    );
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+"" = 1
+a_b = { c_d = 2, Grosse = 3 }
+tab_stop = 4
+
+[[bin]]
+"main.rs" = "src/main.rs"
+main_rs = "taken"
+
+[dotted]
+x_y.z = 5
+
+"#]]
+    );
+    assert_eq!(doc.quoted_keys().len(), 2);
+}
+
+#[test]
+fn test_document_builder() {
+    let doc = DocumentMut::builder()
+        .kv("title", "example")
+        .comment("shown in the UI")
+        .table("a", |t| {
+            t.table("b", |t| t.kv("x", 1).comment_above("first\n\nsecond"))
+        })
+        .array_of_tables("item", |t| t.kv("id", 1))
+        .comment("the first")
+        .array_of_tables("item", |t| t.kv("id", 2).blank_line_above())
+        .table("empty", |t| t.comment("no values"))
+        .build();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+title = "example" # shown in the UI
+
+[a.b]
+# first
+#
+# second
+x = 1
+
+[[item]] # the first
+id = 1
+
+[[item]]
+
+id = 2
+
+[empty] # no values
+
+"#]]
+    );
+    let parsed = doc.to_string().parse::<DocumentMut>().unwrap();
+    assert_eq!(parsed.to_string(), doc.to_string());
+}
+
+#[test]
+fn test_table_kind_conversion_losses() {
+    let doc = r#"
+inline = { a = 1 } # note
+empty-inline = {}
+dotted.a = 1
+
+# about the header
+[standard]
+a = 1
+
+[standard.child]
+
+[implicit.child]
+
+[empty]
+
+[[bin]]
+name = "cli"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let kinds = doc
+        .iter()
+        .map(|(key, item)| format!("{key}: {:?}", item.table_kind()))
+        .collect::<Vec<_>>();
+    assert_data_eq!(
+        kinds.join("\n"),
+        str![[r#"
+inline: Some(Inline)
+empty-inline: Some(Inline)
+dotted: Some(DottedImplicit)
+standard: Some(Standard)
+implicit: Some(DottedImplicit)
+empty: Some(Standard)
+bin: Some(ArrayElement)
+"#]]
+    );
+
+    let kinds = [
+        TableKind::Standard,
+        TableKind::ArrayElement,
+        TableKind::Inline,
+        TableKind::DottedImplicit,
+    ];
+    let mut losses = Vec::new();
+    for (key, item) in doc.iter() {
+        for kind in kinds {
+            let reasons = item
+                .conversion_losses(kind)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            if !reasons.is_empty() {
+                losses.push(format!("{key} to {kind:?}: {}", reasons.join(", ")));
+            }
+        }
+    }
+    assert_data_eq!(
+        losses.join("\n"),
+        str![[r#"
+inline to Standard: comments would be dropped
+inline to ArrayElement: comments would be dropped
+inline to DottedImplicit: comments would be dropped
+empty-inline to DottedImplicit: an empty table without a header is not written
+standard to Inline: comments would be dropped, tables under it would lose their headers
+standard to DottedImplicit: comments would be dropped, tables under it would lose their headers
+implicit to Inline: tables under it would lose their headers
+empty to DottedImplicit: an empty table without a header is not written
+"#]]
+    );
+}
+
+#[test]
+fn test_keys_in_collections() {
+    let doc = r#"
+"b" = 1
+a = 2
+'é' = 3
+serde_json = 4
+serde = 5
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let keys = doc
+        .as_table()
+        .iter()
+        .map(|(key, _)| doc.key(key).unwrap().clone())
+        .collect::<std::collections::BTreeSet<Key>>();
+    assert!(keys.contains("b"));
+    assert!(keys.contains(&Key::new("b")));
+    let sorted = keys.iter().map(|key| key.get()).collect::<Vec<_>>();
+    assert_eq!(sorted, ["a", "b", "serde", "serde_json", "é"]);
+
+    let serde = keys
+        .iter()
+        .filter(|key| key.matches_glob("serde*"))
+        .map(|key| key.get())
+        .collect::<Vec<_>>();
+    assert_eq!(serde, ["serde", "serde_json"]);
+
+    let key = keys.first().unwrap().clone();
+    let name = toml_edit::InternalString::from(key);
+    assert_eq!(Key::from(name.clone()), name);
+}
@@ -0,0 +1,407 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::{value, DocumentMut};
+
+#[test]
+fn test_style_profile() {
+    let style = r#"
+[package]
+  name    = 'foo'
+  authors = [
+    'me',
+  ]
+"#
+    .parse::<DocumentMut>()
+    .unwrap()
+    .style_profile();
+    assert_eq!(style.indent(), "  ");
+    assert_eq!(style.array_style(), toml_edit::ArrayStyle::Multiline);
+    assert_eq!(style.string_style(), toml_edit::StringStyle::Literal);
+    assert!(style.align_values());
+
+    let mut doc = r#"
+a = "x"
+longer = [1, 2]
+
+[t]
+# comment
+k = "v"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    style.fmt_document(&mut doc);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+a      = 'x'
+longer = [
+    1,
+    2,
+]
+
+[t]
+# comment
+  k = 'v'
+
+"#]]
+    );
+}
+
+#[test]
+fn test_stable_output() {
+    let doc = r#"
+# comment
+b   =   'lit'   # comment
+"a b" = 1e100
+f = [ 1, 2, ]
+inline = {x=1,y={}}
+[deps.serde]
+version = "1"
+[empty]
+[[bin]]
+name = "a\tb"
+[bin.sub]
+date = 1979-05-27
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    assert_data_eq!(
+        doc.to_stable_string(toml_edit::OutputStyle::V1),
+        str![[r#"
+b = "lit"
+"a b" = 1e100
+f = [1, 2]
+inline = { x = 1, y = {} }
+
+[deps.serde]
+version = "1"
+
+[empty]
+
+[[bin]]
+name = "a\tb"
+
+[bin.sub]
+date = 1979-05-27
+
+"#]]
+    );
+}
+
+#[test]
+fn test_stable_output_floats() {
+    let mut doc = DocumentMut::new();
+    let floats = [
+        0.0,
+        -0.0,
+        1.0,
+        -1.5,
+        0.1,
+        1e-4,
+        9.999e-5,
+        123456789.125,
+        1e15,
+        1e16,
+        -2.5e-7,
+        1e100,
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        5e-324,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NAN,
+        -f64::NAN,
+    ];
+    doc["floats"] = value(floats.into_iter().collect::<toml_edit::Array>());
+    assert_data_eq!(
+        doc.to_stable_string(toml_edit::OutputStyle::V1),
+        str![[r#"
+floats = [0.0, -0.0, 1.0, -1.5, 0.1, 0.0001, 9.999e-5, 123456789.125, 1000000000000000.0, 1e16, -2.5e-7, 1e100, 1.7976931348623157e308, 2.2250738585072014e-308, 5e-324, inf, -inf, nan, -nan]
+
+"#]]
+    );
+}
+
+#[test]
+fn test_stable_output_datetimes() {
+    let doc = r#"
+offset = 1979-05-27 07:32:00.5000-07:30
+utc = 1979-05-27t07:32:00z
+zero = 1979-05-27T00:00:00+00:00
+local = 1979-05-27T07:32:00.000001
+date = 0001-01-01
+time = 07:32:00.123456789
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    assert_data_eq!(
+        doc.to_stable_string(toml_edit::OutputStyle::V1),
+        str![[r#"
+offset = 1979-05-27T07:32:00.5-07:30
+utc = 1979-05-27T07:32:00Z
+zero = 1979-05-27T00:00:00+00:00
+local = 1979-05-27T07:32:00.000001
+date = 0001-01-01
+time = 07:32:00.123456789
+
+"#]]
+    );
+}
+
+#[test]
+fn test_table_header_spacing() {
+    let mut doc = r#"[ dependencies . a ]
+version = "1"
+[[ bin ]]
+name = "foo"
+[target.'cfg(unix)'.dependencies]
+b = "1"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    assert_eq!(
+        doc.table_header(&["dependencies", "a"]).as_deref(),
+        Some("[ dependencies . a ]")
+    );
+    assert_eq!(doc.table_header(&["bin"]).as_deref(), Some("[[ bin ]]"));
+    assert_eq!(doc.table_header(&["dependencies"]), None);
+    assert_eq!(doc.table_header(&["dependencies", "a", "version"]), None);
+
+    doc.normalize_table_headers();
+    let mut keys = doc
+        .table_header_keys_mut(&["target", "cfg(unix)", "dependencies"])
+        .unwrap();
+    keys[2].leaf_decor_mut().set_prefix(" ").unwrap();
+    keys[2].leaf_decor_mut().set_suffix(" ").unwrap();
+
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+[dependencies.a]
+version = "1"
+[[bin]]
+name = "foo"
+[ target.'cfg(unix)'.dependencies ]
+b = "1"
+
+"#]]
+    );
+}
+
+#[test]
+fn test_style_key_quoting() {
+    let input = r#"
+"name" = 'foo'
+"C:\\Users" = 'home'
+["a b"."x"]
+"it's" = { "k" = 1, "q\"" = 2 }
+"#;
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    let mut style = doc.style_profile();
+    assert_eq!(style.key_quoting(), None);
+    style.fmt_document(&mut doc);
+    assert_eq!(doc.to_string(), input);
+
+    style.set_key_quoting(Some(toml_edit::StringStyle::Literal));
+    style.fmt_document(&mut doc);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+name = 'foo'
+'C:\Users' = 'home'
+['a b'.x]
+"it's" = { k = 1, 'q"' = 2 }
+
+"#]]
+    );
+
+    style.set_key_quoting(Some(toml_edit::StringStyle::Basic));
+    style.fmt_document(&mut doc);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+name = 'foo'
+'C:\Users' = 'home'
+["a b".x]
+"it's" = { k = 1, 'q"' = 2 }
+
+"#]]
+    );
+}
+
+#[test]
+fn test_style_max_width() {
+    let input = r#"
+url = "https://example.com/a/very/long/path/that/cannot/be/split/at/all"
+notes = "first line\nsecond line"
+[package]
+metadata.description = "A string that is long enough to need wrapping when it is nested"
+authors = ["Someone With A Long Name <someone.with.a.long.name@example.com>", "Another"]
+"#;
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    let mut style = doc.style_profile();
+    style.set_max_width(Some(40));
+    style.fmt_document(&mut doc);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+url = "https://example.com/a/very/long/path/that/cannot/be/split/at/all"
+notes = """
+first line
+second line"""
+[package]
+metadata.description = """
+A string that is long enough to need \
+wrapping when it is nested"""
+authors = [
+    """
+Someone With A Long Name \
+<someone.with.a.long.name@example.com>""",
+    "Another",
+]
+
+"#]]
+    );
+    assert_eq!(
+        doc.to_string().parse::<DocumentMut>().unwrap().to_string(),
+        doc.to_string()
+    );
+    let original = input.parse::<DocumentMut>().unwrap();
+    assert_eq!(
+        doc["package"]["metadata"]["description"].as_str(),
+        original["package"]["metadata"]["description"].as_str()
+    );
+    assert_eq!(
+        doc["package"]["authors"]
+            .as_array()
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .as_str(),
+        original["package"]["authors"]
+            .as_array()
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .as_str()
+    );
+
+    doc["package"].as_table_mut().unwrap().insert_styled(
+        "summary",
+        value("Short enough words but too many of them to fit"),
+        &style,
+    );
+    assert_eq!(
+        doc["package"]["summary"].as_value().unwrap().to_string(),
+        "\"\"\"\nShort enough words but too many of \\\nthem to fit\"\"\""
+    );
+}
+
+#[test]
+fn test_style_datetimes() {
+    use toml_edit::{DatetimeSeparator, Offset, UtcOffsetStyle};
+
+    let input = r#"
+a = 1979-05-27T07:32:00.5z # utc
+b = 1979-05-27t07:32:00-00:00
+c = 1979-05-27T07:32:00+01:00
+d = 07:32:00
+"#;
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    let mut style = doc.style_profile();
+    assert_eq!(style.datetime_separator(), None);
+    assert_eq!(style.utc_offset_style(), None);
+    style.fmt_document(&mut doc);
+    assert_eq!(doc.to_string(), input);
+
+    style.set_datetime_separator(Some(DatetimeSeparator::Space));
+    style.set_utc_offset_style(Some(UtcOffsetStyle::Numeric));
+    style.fmt_document(&mut doc);
+    doc.insert_styled(
+        "e",
+        value(
+            "2024-01-01T00:00:00Z"
+                .parse::<toml_edit::Datetime>()
+                .unwrap(),
+        ),
+        &style,
+    );
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+a = 1979-05-27 07:32:00.5+00:00 # utc
+b = 1979-05-27 07:32:00+00:00
+c = 1979-05-27 07:32:00+01:00
+d = 07:32:00
+e = 2024-01-01 00:00:00+00:00
+
+"#]]
+    );
+    assert_eq!(
+        doc["a"].as_datetime().unwrap().offset,
+        Some(Offset::Custom { minutes: 0 })
+    );
+
+    let reparsed = doc.to_string().parse::<DocumentMut>().unwrap();
+    for key in ["a", "b", "c", "d", "e"] {
+        assert_eq!(reparsed[key].as_datetime(), doc[key].as_datetime());
+    }
+}
+
+#[test]
+fn test_style_comments() {
+    let input = "#!shebang-like
+[package] #header
+  #The version, bumped by the release tooling whenever a release is made
+version = '1.0.0'
+#   indented = 'example'
+#trailing comment at the end of the document that needs wrapping
+";
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    let mut style = doc.style_profile();
+    style.set_comment_space(Some(toml_edit::CommentSpace::Space));
+    style.set_comment_width(Some(40));
+    style.fmt_document(&mut doc);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+#!shebang-like
+[package] # header
+  # The version, bumped by the release
+  # tooling whenever a release is made
+version = '1.0.0'
+#   indented = 'example'
+# trailing comment at the end of the
+# document that needs wrapping
+
+"#]]
+    );
+    assert_eq!(
+        doc.to_string().parse::<DocumentMut>().unwrap().to_string(),
+        doc.to_string()
+    );
+
+    let mut style = doc.style_profile();
+    style.set_comment_space(Some(toml_edit::CommentSpace::NoSpace));
+    style.fmt_document(&mut doc);
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+#!shebang-like
+[package] #header
+  #The version, bumped by the release
+  #tooling whenever a release is made
+version = '1.0.0'
+#indented = 'example'
+#trailing comment at the end of the
+#document that needs wrapping
+
+"#]]
+    );
+}
@@ -0,0 +1,52 @@
+use toml_edit::{table, value, DocumentMut, Item};
+
+#[test]
+fn test_sync_document() {
+    use toml_edit::sync::SyncDocument;
+
+    fn assert_sync<T: Send + Sync>() {}
+    assert_sync::<SyncDocument>();
+
+    let doc = r#"name = "app"
+
+[server]
+port = 80
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let mut doc = SyncDocument::new(doc);
+    assert_eq!(doc.keys().collect::<Vec<_>>(), ["name", "server"]);
+
+    {
+        let mut server = doc.try_write("server").unwrap();
+        server["port"] = value(8080);
+        assert!(doc.try_write("server").unwrap_err().is_in_use());
+        assert!(doc.try_read("server").unwrap_err().is_in_use());
+        assert_eq!(doc.try_read("name").unwrap().as_str(), Some("app"));
+    }
+    let err = doc.try_write("missing").unwrap_err();
+    assert!(!err.is_in_use());
+    assert_eq!(err.to_string(), "`missing` is not present");
+    assert!(doc.write("missing").is_none());
+
+    std::thread::scope(|s| {
+        for i in 0..4 {
+            let doc = &doc;
+            s.spawn(move || {
+                let mut server = doc.write("server").unwrap();
+                let workers = server.get("workers").and_then(Item::as_integer);
+                let workers = workers.unwrap_or(0);
+                server["workers"] = value(workers + i);
+            });
+        }
+    });
+    assert_eq!(doc.read("server").unwrap()["workers"].as_integer(), Some(6));
+
+    doc.insert("log", table());
+    doc.write("log").unwrap()["level"] = value("debug");
+    assert_eq!(doc.remove("name").unwrap().as_str(), Some("app"));
+    assert_eq!(
+        doc.to_document().to_string(),
+        doc.into_document().to_string()
+    );
+}
@@ -0,0 +1,77 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::Value;
+
+#[test]
+fn test_template() {
+    use toml_edit::template::Template;
+
+    let template = r#"
+# @placeholder string
+name = ""
+server.port = 0 # @placeholder integer
+
+[[bin]]
+# Where the binary is
+#
+# @placeholder array
+paths = []
+"#;
+    let template = Template::new(template.parse().unwrap()).unwrap();
+    let placeholders = template
+        .placeholders()
+        .iter()
+        .map(|p| {
+            format!(
+                "{} {} {:?}",
+                p.key(),
+                p.schema().kind().type_name(),
+                p.schema().description()
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        placeholders,
+        [
+            "name string None",
+            "server.port integer None",
+            "bin[0].paths array Some(\"Where the binary is\")"
+        ]
+    );
+
+    let doc = template
+        .fill([
+            ("name", Value::from("foo")),
+            ("server.port", Value::from(8080)),
+            ("bin[0].paths", Value::from_iter(["src/main.rs"])),
+        ])
+        .unwrap();
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+name = "foo"
+server.port = 8080
+
+[[bin]]
+# Where the binary is
+#
+paths = ["src/main.rs"]
+
+"#]]
+    );
+
+    let err = template.fill([("name", Value::from("foo"))]).unwrap_err();
+    assert_eq!(err.to_string(), "`server.port` has no substitution");
+    let err = template
+        .fill([
+            ("name", Value::from("foo")),
+            ("server.port", Value::from(8080)),
+            ("bin[0].paths", Value::from_iter(["src/main.rs"])),
+            ("version", Value::from("1.0.0")),
+        ])
+        .unwrap_err();
+    assert_eq!(err.to_string(), "`version` is not a placeholder");
+    let err = Template::new("a = 1 # @placeholder int".parse().unwrap()).unwrap_err();
+    assert_eq!(err.to_string(), "`a` has unknown placeholder type `int`");
+}
@@ -0,0 +1,191 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::{value, DocumentMut, Item, Value};
+
+#[test]
+fn test_explicit_string_encodings() {
+    use toml_edit::{EncodeError, Formatted};
+
+    let mut doc = DocumentMut::new();
+    doc["basic"] = value(Value::String(Formatted::basic("a\nb")));
+    doc["ml_basic"] = value(Value::String(Formatted::ml_basic("a\nb")));
+    doc["literal"] = value(Value::String(Formatted::literal(r"C:\Users").unwrap()));
+    doc["ml_literal"] = value(Value::String(Formatted::ml_literal("a''\nb").unwrap()));
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+basic = "a\nb"
+ml_basic = """
+a
+b"""
+literal = 'C:\Users'
+ml_literal = '''
+a''
+b'''
+
+"#]]
+    );
+
+    assert_eq!(
+        Formatted::<String>::literal("a\nb").unwrap_err(),
+        EncodeError::Newline
+    );
+    assert_eq!(
+        Formatted::<String>::ml_literal("a'''b").unwrap_err(),
+        EncodeError::Delimiter("'''")
+    );
+    assert_eq!(
+        Formatted::<String>::ml_literal("a\u{7}").unwrap_err(),
+        EncodeError::ControlCharacter('\u{7}')
+    );
+}
+
+#[test]
+fn test_ml_literal_crlf() {
+    use toml_edit::{EncodeError, Formatted};
+
+    let mut doc = DocumentMut::new();
+    doc["a"] = value(Value::String(
+        Formatted::ml_literal("\r\nline 1\r\nline 2\n").unwrap(),
+    ));
+    let output = doc.to_string();
+    assert_eq!(output, "a = '''\n\r\nline 1\r\nline 2\n'''\n");
+
+    let parsed = output.parse::<DocumentMut>().unwrap();
+    assert_eq!(parsed["a"].as_str(), Some("\r\nline 1\r\nline 2\n"));
+
+    assert_eq!(
+        Formatted::<String>::ml_literal("a\rb").unwrap_err(),
+        EncodeError::ControlCharacter('\r')
+    );
+    assert_eq!(
+        Formatted::<String>::ml_literal("a\r").unwrap_err(),
+        EncodeError::ControlCharacter('\r')
+    );
+    assert_eq!(
+        Formatted::<String>::literal("a\r\nb").unwrap_err(),
+        EncodeError::ControlCharacter('\r')
+    );
+}
+
+#[test]
+fn test_formatted_set_repr() {
+    let mut doc = "a = 0o17 # comment\n".parse::<DocumentMut>().unwrap();
+    let Some(Value::Integer(a)) = doc["a"].as_value_mut() else {
+        panic!("`a` is an integer");
+    };
+    assert_eq!(a.raw(), Some("0o17"));
+    assert_eq!(*a.value(), 15);
+
+    a.set_repr("1_000").unwrap();
+    assert_eq!(*a.value(), 1000);
+    assert!(a.set_repr("true").is_err());
+    assert_eq!(a.raw(), Some("1_000"));
+
+    assert_eq!(doc.to_string(), "a = 1_000 # comment\n");
+}
+
+#[test]
+fn test_value_with_repr() {
+    let mut doc = DocumentMut::new();
+    doc["mode"] = Item::Value(Value::integer_with_repr(0o755, "0o755").unwrap());
+    doc["mask"] = Item::Value(Value::integer_with_repr(5, "0b0000_0101").unwrap());
+    doc["big"] = Item::Value(Value::integer_with_repr(-1_000_000, "-1_000_000").unwrap());
+    doc["limit"] = Item::Value(Value::float_with_repr(f64::INFINITY, "+inf").unwrap());
+    doc["ratio"] = Item::Value(Value::float_with_repr(0.5, "5e-1").unwrap());
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+mode = 0o755
+mask = 0b0000_0101
+big = -1_000_000
+limit = +inf
+ratio = 5e-1
+
+"#]]
+    );
+
+    assert!(Value::integer_with_repr(1, "'1'").is_err());
+    assert!(Value::integer_with_repr(1, "0x").is_err());
+    assert!(Value::float_with_repr(1.0, "1").is_err());
+    assert!(Value::float_with_repr(f64::NAN, "-nan").is_err());
+    let err = Value::float_with_repr(0.1, "0.2").unwrap_err();
+    assert_eq!(err.message(), "`0.2` is 0.2, not 0.1");
+}
+
+#[test]
+fn test_string_kind() {
+    use toml_edit::StringKind;
+
+    let doc = r#"
+bare = "basic"
+"basic" = 'literal'
+'literal' = """multi-line basic"""
+ml = '''multi-line literal'''
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let kinds = doc
+        .iter()
+        .map(|(key, item)| {
+            let key = doc.key(key).unwrap().string_kind();
+            let value = match item.as_value() {
+                Some(Value::String(value)) => value.string_kind(),
+                _ => unreachable!(),
+            };
+            (key, value)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            (None, Some(StringKind::Basic)),
+            (Some(StringKind::Basic), Some(StringKind::Literal)),
+            (Some(StringKind::Literal), Some(StringKind::MlBasic)),
+            (None, Some(StringKind::MlLiteral)),
+        ]
+    );
+
+    assert_eq!(
+        toml_edit::Formatted::new("new".to_owned()).string_kind(),
+        None
+    );
+}
+
+#[test]
+fn test_datetime_components() {
+    let mut doc = r#"
+odt = 1979-05-27t07:32:00.50z # comment
+lt = 07:32:00
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let Some(Value::Datetime(odt)) = doc["odt"].as_value_mut() else {
+        unreachable!()
+    };
+    assert_eq!(odt.year(), Some(1979));
+    assert_eq!(odt.nanosecond(), Some(500_000_000));
+    assert_eq!(odt.offset_minutes(), Some(0));
+    odt.set_year(2024).unwrap();
+    odt.set_nanosecond(250_000_000).unwrap();
+    odt.set_offset_minutes(90).unwrap();
+    assert!(odt.set_day(32).is_err());
+
+    let Some(Value::Datetime(lt)) = doc["lt"].as_value_mut() else {
+        unreachable!()
+    };
+    assert_eq!(lt.year(), None);
+    assert!(lt.set_year(2024).is_err());
+    lt.set_second(59).unwrap();
+
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+
+odt = 2024-05-27t07:32:00.25+01:30 # comment
+lt = 07:32:59
+
+"#]]
+    );
+}
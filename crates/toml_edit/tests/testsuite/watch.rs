@@ -0,0 +1,159 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use toml_edit::watch::{diff, Change, Watcher};
+use toml_edit::DocumentMut;
+
+fn file(name: &str, content: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("watch-{name}.toml"));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+fn summary(changes: &[Change]) -> Vec<String> {
+    changes
+        .iter()
+        .map(|change| {
+            let kind = match change {
+                Change::Added { .. } => "added",
+                Change::Removed { .. } => "removed",
+                Change::Modified { .. } => "modified",
+                _ => unreachable!(),
+            };
+            format!("{kind} {}", change.path_string())
+        })
+        .collect()
+}
+
+#[test]
+fn poll_reports_changes() {
+    let path = file(
+        "changes",
+        "port = 8080\nhost = 'a'\n[[bin]]\nname = 'a'\n[[bin]]\nname = 'b'\n",
+    );
+    let mut watcher = Watcher::new(&path).unwrap();
+    assert_eq!(watcher.path(), path);
+
+    std::fs::write(
+        &path,
+        "port = 8081\ntls = true\n[[bin]]\nname = 'a'\npath = 'a.rs'\n",
+    )
+    .unwrap();
+    let changes = watcher.poll().unwrap();
+    assert_eq!(
+        summary(&changes),
+        [
+            "modified port",
+            "removed host",
+            "added bin[0].path",
+            "removed bin[1]",
+            "added tls"
+        ]
+    );
+    match &changes[0] {
+        Change::Modified { old, new, .. } => {
+            assert_eq!(old.as_integer(), Some(8080));
+            assert_eq!(new.as_integer(), Some(8081));
+        }
+        change => panic!("unexpected {change:?}"),
+    }
+    assert_eq!(watcher.document()["port"].as_integer(), Some(8081));
+
+    // Changes are since the last successful poll
+    assert!(watcher.poll().unwrap().is_empty());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn poll_unchanged() {
+    let path = file("unchanged", "port = 8080  # http\n");
+    let mut watcher = Watcher::new(&path).unwrap();
+    assert!(watcher.poll().unwrap().is_empty());
+
+    std::fs::write(&path, "port = 8080  # http\n").unwrap();
+    assert!(watcher.poll().unwrap().is_empty());
+
+    // Only formatting changed, which is picked up without being reported
+    std::fs::write(&path, "# server\nport = 8_080\n").unwrap();
+    assert!(watcher.poll().unwrap().is_empty());
+    assert_eq!(watcher.document().to_string(), "# server\nport = 8_080\n");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn poll_removed_file() {
+    let path = file("removed", "port = 8080\n");
+    let mut watcher = Watcher::new(&path).unwrap();
+
+    std::fs::remove_file(&path).unwrap();
+    let err = watcher.poll().unwrap_err();
+    assert_eq!(err.path(), path);
+    assert_eq!(err.io_error().unwrap().kind(), std::io::ErrorKind::NotFound);
+    assert!(err.parse_error().is_none());
+    assert_eq!(watcher.document()["port"].as_integer(), Some(8080));
+
+    // Recreating the file is a change from the last good version
+    std::fs::write(&path, "port = 8081\n").unwrap();
+    assert_eq!(summary(&watcher.poll().unwrap()), ["modified port"]);
+    std::fs::remove_file(&path).unwrap();
+
+    let err = Watcher::new(&path).unwrap_err();
+    assert!(err.io_error().is_some());
+}
+
+#[test]
+fn poll_parse_error() {
+    let path = file("parse-error", "port = 8080\n");
+    let mut watcher = Watcher::new(&path).unwrap();
+
+    std::fs::write(&path, "port = ").unwrap();
+    let err = watcher.poll().unwrap_err();
+    assert!(err.parse_error().is_some());
+    assert!(err.io_error().is_none());
+    assert!(err.to_string().starts_with("failed to parse `"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn spawn_calls_back_on_change() {
+    let path = file("spawn", "port = 8080\n");
+    let (send, recv) = std::sync::mpsc::channel();
+    let handle = Watcher::new(&path)
+        .unwrap()
+        .with_interval(Duration::from_millis(10))
+        .spawn(move |result| {
+            // The file may be read while partially written
+            if let Ok((document, changes)) = result {
+                let port = document["port"].as_integer();
+                send.send((port, summary(changes))).unwrap();
+            }
+        });
+
+    std::fs::write(&path, "port = 8081\n").unwrap();
+    let (port, changes) = recv.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(port, Some(8081));
+    assert_eq!(changes, ["modified port"]);
+
+    let watcher = handle.stop();
+    assert_eq!(watcher.document()["port"].as_integer(), Some(8081));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn diff_inline_and_standard_tables() {
+    let old = "[server]\nport = 8080\nhost = 'a'\nargs = [1, 2]\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let new = "server = { port = 8080, args = [1, 3], tls = true }\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let changes = diff(old.as_table(), new.as_table());
+    assert_eq!(
+        summary(&changes),
+        [
+            "removed server.host",
+            "modified server.args",
+            "added server.tls"
+        ]
+    );
+}
@@ -0,0 +1,64 @@
+use snapbox::assert_data_eq;
+use snapbox::str;
+use toml_edit::DocumentMut;
+
+#[test]
+fn test_workspace_inheritance() {
+    let workspace = r#"
+[workspace.package]
+version = "1.2.0"
+
+[workspace.dependencies]
+anyhow = "1.0"
+
+[workspace.lints.rust]
+unsafe_code = "forbid"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let mut manifest = r#"
+[package]
+name = "foo"
+# Released together
+version.workspace = true  # inherited
+
+[dependencies]
+anyhow = { workspace = true, optional = true }
+
+[lints]
+workspace = true
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+
+    let err = toml_edit::workspace::materialize(&mut manifest, || None::<DocumentMut>).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`package.version` is inherited but there is no workspace"
+    );
+
+    assert!(toml_edit::workspace::materialize(&mut manifest, || Some(&workspace)).unwrap());
+    assert_data_eq!(
+        manifest.to_string(),
+        str![[r#"
+
+[package]
+name = "foo"
+# Released together
+version = "1.2.0"  # inherited
+
+[dependencies]
+anyhow = { version = "1.0", optional = true }
+
+[lints.rust]
+unsafe_code = "forbid"
+
+"#]]
+    );
+
+    let resolved = toml_edit::workspace::resolve(&manifest, || -> Option<DocumentMut> {
+        panic!("nothing is inherited")
+    })
+    .unwrap();
+    assert!(matches!(resolved, std::borrow::Cow::Borrowed(_)));
+}
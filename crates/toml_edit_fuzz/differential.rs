@@ -0,0 +1,157 @@
+#![no_main]
+
+use toml_edit::DocumentMut;
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| -> libfuzzer_sys::Corpus {
+    let Ok(data) = std::str::from_utf8(data) else {
+        return libfuzzer_sys::Corpus::Reject;
+    };
+
+    let table = toml::from_str::<toml::Table>(data);
+    let doc = data.parse::<DocumentMut>();
+    let syntax_error = has_syntax_error(data);
+
+    match (&table, &doc) {
+        (Ok(table), Ok(doc)) => {
+            assert!(
+                !syntax_error,
+                "event parser rejected what the others accepted
+
+data:
+```toml
+{data}
+```
+"
+            );
+            let from_doc = to_table(doc.as_table());
+            assert!(
+                same_table(table, &from_doc),
+                "data models differ
+
+data:
+```toml
+{data}
+```
+
+toml:
+```
+{table:#?}
+```
+
+toml_edit:
+```
+{from_doc:#?}
+```
+"
+            );
+        }
+        (Err(_), Err(_)) => {}
+        (Ok(_), Err(err)) => panic!(
+            "only toml_edit rejected: {err}
+
+data:
+```toml
+{data}
+```
+"
+        ),
+        (Err(err), Ok(_)) => panic!(
+            "only toml rejected: {err}
+
+data:
+```toml
+{data}
+```
+"
+        ),
+    }
+    libfuzzer_sys::Corpus::Keep
+});
+
+/// The event parser only checks syntax, so it may accept documents the others reject
+fn has_syntax_error(data: &str) -> bool {
+    use toml_parse::parser::EventKind;
+
+    let mut rejected = false;
+    let mut errors = |_: toml_parse::ParseError| rejected = true;
+    let source = toml_parse::Source::new(data);
+    let tokens = source.lex().into_vec();
+    let mut events = Vec::new();
+    let mut receiver = toml_parse::parser::ValidateWhitespace::new(&mut events, source);
+    toml_parse::parser::parse_document(&tokens, &mut receiver, &mut errors);
+    for event in &events {
+        let raw = source.get(event).expect("event spans are valid");
+        match event.kind() {
+            EventKind::SimpleKey => raw.decode_key(&mut (), &mut errors),
+            EventKind::Scalar => {
+                let _ = raw.decode_scalar(&mut (), &mut errors);
+            }
+            _ => {}
+        }
+    }
+    rejected
+}
+
+fn to_table(table: &dyn toml_edit::TableLike) -> toml::Table {
+    table
+        .iter()
+        .filter_map(|(key, item)| to_value(item).map(|value| (key.to_owned(), value)))
+        .collect()
+}
+
+fn to_value(item: &toml_edit::Item) -> Option<toml::Value> {
+    let value = match item {
+        toml_edit::Item::None => return None,
+        toml_edit::Item::Value(value) => value_to_value(value),
+        toml_edit::Item::Table(table) => toml::Value::Table(to_table(table)),
+        toml_edit::Item::ArrayOfTables(array) => toml::Value::Array(
+            array
+                .iter()
+                .map(|table| toml::Value::Table(to_table(table)))
+                .collect(),
+        ),
+    };
+    Some(value)
+}
+
+fn value_to_value(value: &toml_edit::Value) -> toml::Value {
+    match value {
+        toml_edit::Value::String(v) => toml::Value::String(v.value().clone()),
+        toml_edit::Value::Integer(v) => toml::Value::Integer(*v.value()),
+        toml_edit::Value::Float(v) => toml::Value::Float(*v.value()),
+        toml_edit::Value::Boolean(v) => toml::Value::Boolean(*v.value()),
+        toml_edit::Value::Datetime(v) => toml::Value::Datetime(*v.value()),
+        toml_edit::Value::Array(array) => {
+            toml::Value::Array(array.iter().map(value_to_value).collect())
+        }
+        toml_edit::Value::InlineTable(table) => toml::Value::Table(to_table(table)),
+    }
+}
+
+fn same_table(left: &toml::Table, right: &toml::Table) -> bool {
+    left.len() == right.len()
+        && left.iter().all(|(key, left)| {
+            right
+                .get(key)
+                .map(|right| same_value(left, right))
+                .unwrap_or(false)
+        })
+}
+
+/// Like `==` but with `nan` equal to itself
+fn same_value(left: &toml::Value, right: &toml::Value) -> bool {
+    match (left, right) {
+        (toml::Value::Float(left), toml::Value::Float(right)) => {
+            left.to_bits() == right.to_bits() || (left.is_nan() && right.is_nan())
+        }
+        (toml::Value::Array(left), toml::Value::Array(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .zip(right.iter())
+                    .all(|(left, right)| same_value(left, right))
+        }
+        (toml::Value::Table(left), toml::Value::Table(right)) => same_table(left, right),
+        (left, right) => left == right,
+    }
+}
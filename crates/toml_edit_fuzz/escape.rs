@@ -0,0 +1,44 @@
+#![no_main]
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| -> libfuzzer_sys::Corpus {
+    let Ok(data) = std::str::from_utf8(data) else {
+        return libfuzzer_sys::Corpus::Reject;
+    };
+
+    let escaped = toml_write::escape_basic_string(data);
+    let toml = format!("key = \"{escaped}\"\n");
+    let doc = match toml.parse::<toml_edit::DocumentMut>() {
+        Ok(doc) => doc,
+        Err(err) => panic!(
+            "escaped string did not parse: {err}
+
+data: {data:?}
+toml:
+```toml
+{toml}
+```
+"
+        ),
+    };
+    assert_eq!(
+        doc["key"].as_str(),
+        Some(data),
+        "escaped string decoded differently
+
+toml:
+```toml
+{toml}
+```
+"
+    );
+
+    let toml = format!("{data} = 1\n");
+    let bare = toml.parse::<toml_edit::DocumentMut>();
+    if !toml_write::needs_quoting(data) {
+        assert!(
+            bare.map(|doc| doc.contains_key(data)).unwrap_or(false),
+            "key said not to need quoting was rejected bare: {data:?}"
+        );
+    }
+    libfuzzer_sys::Corpus::Keep
+});
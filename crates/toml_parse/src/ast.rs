@@ -0,0 +1,652 @@
+//! An owned tree of a TOML document's keys and values, each with its [`Span`]
+//!
+//! This is for tools that analyze TOML, like linters, but don't need to edit it: the document is
+//! decoded and assembled into [`Table`]s, checking for duplicate keys and tables, but the
+//! whitespace and comments around each key and value aren't kept.
+//!
+//! With `toml`'s `ast` feature, a [`Table`] converts into a `toml::Table`.
+//!
+//! # Example
+//!
+//! ```
+//! use toml_parse::ast::TableKind;
+//!
+//! let input = r#"
+//! [package]
+//! name = "foo"
+//! metadata.docs = true
+//! "#;
+//! let mut errors = Vec::new();
+//! let doc = toml_parse::ast::parse(input, &mut errors);
+//! assert!(errors.is_empty());
+//!
+//! let package = doc.get("package").unwrap();
+//! assert_eq!(&input[package.span().start()..package.span().end()], "[package]");
+//! let package = package.value().as_table().unwrap();
+//! assert_eq!(package.get("name").unwrap().value().as_str(), Some("foo"));
+//!
+//! let metadata = package.get("metadata").unwrap().value().as_table().unwrap();
+//! assert_eq!(metadata.kind(), TableKind::Dotted);
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::Peekable;
+
+use crate::decoder::ScalarKind;
+use crate::parser::parse_document;
+use crate::parser::Event;
+use crate::parser::EventKind;
+use crate::parser::RecursionGuard;
+use crate::parser::ValidateWhitespace;
+use crate::ErrorSink;
+use crate::ParseError;
+use crate::Source;
+use crate::Span;
+
+/// How deeply arrays and inline tables may be nested
+const LIMIT: u32 = 80;
+
+/// Decode `input` into a tree of its keys and values
+///
+/// Errors are reported to `error`, with as much of the document as could be understood still
+/// returned.  An invalid value is kept as a best guess, like the largest integer for one that
+/// overflowed, while a duplicate key or table is left out, along with the keys and values under
+/// a table header that is.
+pub fn parse(input: &str, error: &mut dyn ErrorSink) -> Table {
+    let source = Source::new(input);
+    let tokens = source.lex().into_vec();
+    let mut events = Vec::with_capacity(tokens.len());
+    let mut receiver = ValidateWhitespace::new(&mut events, source);
+    let mut receiver = RecursionGuard::new(&mut receiver, LIMIT);
+    parse_document(&tokens, &mut receiver, error);
+
+    let mut events = events
+        .iter()
+        .filter(|e| {
+            !matches!(
+                e.kind(),
+                EventKind::Whitespace | EventKind::Comment | EventKind::Newline | EventKind::Error
+            )
+        })
+        .peekable();
+    let mut builder = Builder {
+        source,
+        root: Table::new(TableKind::Root),
+        current: Some(Vec::new()),
+        error,
+    };
+    builder.document(&mut events);
+    builder.root
+}
+
+/// A key, decoded
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Key {
+    name: String,
+    span: Span,
+}
+
+impl Key {
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// The location of the key, including any quotes
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn into_string(self) -> String {
+        self.name
+    }
+}
+
+/// A [`Value`] and where it was defined
+#[derive(Clone, PartialEq, Debug)]
+pub struct Node {
+    value: Value,
+    span: Span,
+}
+
+impl Node {
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Where the value was defined
+    ///
+    /// This is the header of a `[table]` or `[[array]]` element, the key that implied a table,
+    /// like `a` for `a.b = 1`, and otherwise the value itself, including any brackets or quotes.
+    /// For an array of tables, this is its first header.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+}
+
+/// A decoded value
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Datetime(toml_datetime::Datetime),
+    Array(Array),
+    Table(Table),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_datetime(&self) -> Option<&toml_datetime::Datetime> {
+        match self {
+            Value::Datetime(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Array> {
+        match self {
+            Value::Array(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&Table> {
+        match self {
+            Value::Table(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// The values of an array, or the tables of an array of tables
+#[derive(Clone, PartialEq, Debug)]
+pub struct Array {
+    values: Vec<Node>,
+    of_tables: bool,
+}
+
+impl Array {
+    /// Whether this was defined by `[[array]]` headers
+    pub fn is_array_of_tables(&self) -> bool {
+        self.of_tables
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Node> {
+        self.values.get(index)
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Node> {
+        self.values.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn into_vec(self) -> Vec<Node> {
+        self.values
+    }
+}
+
+impl<'a> IntoIterator for &'a Array {
+    type Item = &'a Node;
+    type IntoIter = core::slice::Iter<'a, Node>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Array {
+    type Item = Node;
+    type IntoIter = alloc::vec::IntoIter<Node>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+/// How a [`Table`] was defined
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum TableKind {
+    /// The top-level table of the document
+    Root,
+    /// A `[table]` header or an element of an array of tables
+    Header,
+    /// Only the tables under it were defined, like `a` for `[a.b]`
+    Implicit,
+    /// By dotted keys, like `a` for `a.b = 1`
+    Dotted,
+    /// `{ key = value }`
+    Inline,
+}
+
+/// Keys and their values, in the order they were defined
+#[derive(Clone, PartialEq, Debug)]
+pub struct Table {
+    kind: TableKind,
+    entries: Vec<(Key, Node)>,
+}
+
+impl Table {
+    fn new(kind: TableKind) -> Self {
+        Self {
+            kind,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> TableKind {
+        self.kind
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.as_str() == key)
+            .map(|(_, node)| node)
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, (Key, Node)> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn into_vec(self) -> Vec<(Key, Node)> {
+        self.entries
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|(k, _)| k.as_str() == key)
+    }
+
+    /// The index of `key`, adding it with `default` if it is missing
+    fn position_or_insert(&mut self, key: &Key, default: impl FnOnce() -> Node) -> usize {
+        self.position(key.as_str()).unwrap_or_else(|| {
+            self.entries.push((key.clone(), default()));
+            self.entries.len() - 1
+        })
+    }
+}
+
+impl<'a> IntoIterator for &'a Table {
+    type Item = &'a (Key, Node);
+    type IntoIter = core::slice::Iter<'a, (Key, Node)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Table {
+    type Item = (Key, Node);
+    type IntoIter = alloc::vec::IntoIter<(Key, Node)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+struct Builder<'i, 'e> {
+    source: Source<'i>,
+    root: Table,
+    /// The path of the current table header, or `None` if it was invalid
+    current: Option<Vec<Key>>,
+    error: &'e mut dyn ErrorSink,
+}
+
+impl<'e> Builder<'_, 'e> {
+    fn document<'a>(&mut self, events: &mut Peekable<impl Iterator<Item = &'a Event>>) {
+        while let Some(event) = events.next() {
+            match event.kind() {
+                EventKind::StdTableOpen | EventKind::ArrayTableOpen => {
+                    let is_array = event.kind() == EventKind::ArrayTableOpen;
+                    let mut span = event.span();
+                    let mut keys = Vec::new();
+                    for event in events.by_ref() {
+                        match event.kind() {
+                            EventKind::SimpleKey => keys.push(self.key(event)),
+                            EventKind::StdTableClose | EventKind::ArrayTableClose => {
+                                span = span.append(event.span());
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    let is_open = self.open_table(&keys, span, is_array);
+                    self.current = is_open.then_some(keys);
+                }
+                EventKind::SimpleKey => {
+                    let keys = self.keys(event, events);
+                    let Some(value) = self.value(events) else {
+                        continue;
+                    };
+                    if let Some(path) = &self.current {
+                        if let Some(table) = table_mut(&mut self.root, path) {
+                            insert(table, keys, value, self.error);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Start a `[table]` or `[[array]]` element at `keys`, returning whether it was valid
+    fn open_table(&mut self, keys: &[Key], span: Span, is_array: bool) -> bool {
+        let Some((last, parents)) = keys.split_last() else {
+            return false;
+        };
+
+        let mut table = &mut self.root;
+        for key in parents {
+            let i = table.position_or_insert(key, || Node {
+                value: Value::Table(Table::new(TableKind::Implicit)),
+                span: key.span(),
+            });
+            let parent = match &mut table.entries[i].1.value {
+                Value::Table(child) if child.kind != TableKind::Inline => Some(child),
+                Value::Array(array) if array.of_tables => {
+                    array
+                        .values
+                        .last_mut()
+                        .and_then(|node| match &mut node.value {
+                            Value::Table(child) => Some(child),
+                            _ => None,
+                        })
+                }
+                _ => None,
+            };
+            let Some(parent) = parent else {
+                self.error.report_error(
+                    ParseError::new("cannot extend a value that isn't a table")
+                        .with_unexpected(key.span()),
+                );
+                return false;
+            };
+            table = parent;
+        }
+
+        let header = || Node {
+            value: Value::Table(Table::new(TableKind::Header)),
+            span,
+        };
+        match (table.position(last.as_str()), is_array) {
+            (None, false) => {
+                table.entries.push((last.clone(), header()));
+                true
+            }
+            (None, true) => {
+                let array = Array {
+                    values: alloc::vec![header()],
+                    of_tables: true,
+                };
+                let node = Node {
+                    value: Value::Array(array),
+                    span,
+                };
+                table.entries.push((last.clone(), node));
+                true
+            }
+            (Some(i), false) => {
+                let node = &mut table.entries[i].1;
+                match &mut node.value {
+                    Value::Table(child) if child.kind == TableKind::Implicit => {
+                        child.kind = TableKind::Header;
+                        node.span = span;
+                        true
+                    }
+                    _ => {
+                        self.error
+                            .report_error(ParseError::new("duplicate table").with_unexpected(span));
+                        false
+                    }
+                }
+            }
+            (Some(i), true) => match &mut table.entries[i].1.value {
+                Value::Array(array) if array.of_tables => {
+                    array.values.push(header());
+                    true
+                }
+                _ => {
+                    self.error
+                        .report_error(ParseError::new("duplicate key").with_unexpected(span));
+                    false
+                }
+            },
+        }
+    }
+
+    /// The keys of a `key = value`, up to the `=`
+    fn keys<'a>(
+        &mut self,
+        first: &Event,
+        events: &mut Peekable<impl Iterator<Item = &'a Event>>,
+    ) -> Vec<Key> {
+        let mut keys = alloc::vec![self.key(first)];
+        while let Some(event) = events.next_if(|e| {
+            matches!(
+                e.kind(),
+                EventKind::SimpleKey | EventKind::KeySep | EventKind::KeyValSep
+            )
+        }) {
+            match event.kind() {
+                EventKind::SimpleKey => keys.push(self.key(event)),
+                EventKind::KeyValSep => break,
+                _ => {}
+            }
+        }
+        keys
+    }
+
+    fn key(&mut self, event: &Event) -> Key {
+        let mut name = String::new();
+        if let Some(raw) = self.source.get(event) {
+            raw.decode_key(&mut name, self.error);
+        }
+        Key {
+            name,
+            span: event.span(),
+        }
+    }
+
+    fn value<'a>(
+        &mut self,
+        events: &mut Peekable<impl Iterator<Item = &'a Event>>,
+    ) -> Option<Node> {
+        let event = events.next_if(|e| {
+            matches!(
+                e.kind(),
+                EventKind::Scalar | EventKind::ArrayOpen | EventKind::InlineTableOpen
+            )
+        })?;
+        let mut span = event.span();
+        let value = match event.kind() {
+            EventKind::ArrayOpen => {
+                let mut values = Vec::new();
+                loop {
+                    if let Some(value) = self.value(events) {
+                        values.push(value);
+                        continue;
+                    }
+                    match events.next() {
+                        Some(event) if event.kind() == EventKind::ArrayClose => {
+                            span = span.append(event.span());
+                            break;
+                        }
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+                Value::Array(Array {
+                    values,
+                    of_tables: false,
+                })
+            }
+            EventKind::InlineTableOpen => {
+                let mut table = Table::new(TableKind::Inline);
+                while let Some(event) = events.next() {
+                    match event.kind() {
+                        EventKind::SimpleKey => {
+                            let keys = self.keys(event, events);
+                            if let Some(value) = self.value(events) {
+                                insert(&mut table, keys, value, self.error);
+                            }
+                        }
+                        EventKind::InlineTableClose => {
+                            span = span.append(event.span());
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                Value::Table(table)
+            }
+            _ => self.scalar(event),
+        };
+        Some(Node { value, span })
+    }
+
+    fn scalar(&mut self, event: &Event) -> Value {
+        let span = event.span();
+        let mut decoded = String::new();
+        let kind = match self.source.get(event) {
+            Some(raw) => raw.decode_scalar(&mut decoded, self.error),
+            None => ScalarKind::String,
+        };
+        match kind {
+            ScalarKind::String => Value::String(decoded),
+            ScalarKind::Boolean(value) => Value::Boolean(value),
+            ScalarKind::DateTime => match decoded.parse::<toml_datetime::Datetime>() {
+                Ok(value) => Value::Datetime(value),
+                Err(err) => {
+                    self.error
+                        .report_error(ParseError::new(err.to_string()).with_unexpected(span));
+                    Value::Datetime(toml_datetime::Datetime {
+                        date: None,
+                        time: None,
+                        offset: None,
+                    })
+                }
+            },
+            ScalarKind::Float => {
+                let value = decoded.parse::<f64>().unwrap_or(f64::NAN);
+                let is_symbol = decoded
+                    .trim_start_matches(['+', '-'])
+                    .chars()
+                    .all(|c| c.is_ascii_alphabetic());
+                if value.is_infinite() && !is_symbol {
+                    self.error.report_error(
+                        ParseError::new("floating-point number overflowed").with_unexpected(span),
+                    );
+                }
+                Value::Float(value)
+            }
+            ScalarKind::Integer(radix) => {
+                let value = i64::from_str_radix(&decoded, radix.value()).unwrap_or_else(|_| {
+                    // Assuming the decoder fully validated it, leaving only overflow errors
+                    self.error.report_error(
+                        ParseError::new("integer number overflowed").with_unexpected(span),
+                    );
+                    i64::MAX
+                });
+                Value::Integer(value)
+            }
+        }
+    }
+}
+
+/// The table for the header at `path`, through the last element of any arrays of tables
+fn table_mut<'t>(root: &'t mut Table, path: &[Key]) -> Option<&'t mut Table> {
+    let mut table = root;
+    for key in path {
+        let i = table.position(key.as_str())?;
+        table = match &mut table.entries[i].1.value {
+            Value::Table(child) => child,
+            Value::Array(array) => match &mut array.values.last_mut()?.value {
+                Value::Table(child) => child,
+                _ => return None,
+            },
+            _ => return None,
+        };
+    }
+    Some(table)
+}
+
+/// Add a `key = value` to `table`, creating the tables for dotted keys
+fn insert(table: &mut Table, mut keys: Vec<Key>, value: Node, error: &mut dyn ErrorSink) {
+    let Some(last) = keys.pop() else {
+        return;
+    };
+
+    let mut table = table;
+    for key in &keys {
+        let i = table.position_or_insert(key, || Node {
+            value: Value::Table(Table::new(TableKind::Dotted)),
+            span: key.span(),
+        });
+        table = match &mut table.entries[i].1.value {
+            Value::Table(child)
+                if matches!(child.kind, TableKind::Dotted | TableKind::Implicit) =>
+            {
+                child.kind = TableKind::Dotted;
+                child
+            }
+            _ => {
+                error.report_error(ParseError::new("duplicate key").with_unexpected(key.span()));
+                return;
+            }
+        };
+    }
+
+    if table.position(last.as_str()).is_some() {
+        error.report_error(ParseError::new("duplicate key").with_unexpected(last.span()));
+        return;
+    }
+    table.entries.push((last, value));
+}
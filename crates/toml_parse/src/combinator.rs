@@ -0,0 +1,181 @@
+//! [`winnow`] parsers for embedding TOML in a larger grammar
+//!
+//! Each parser takes any `&str`-based [`Stream`], including [`Partial`][winnow::stream::Partial]
+//! input, and returns the [`Event`]s for what it consumed.  [`Event`] spans are relative to where
+//! the parser started, so [`Parser::with_taken`][winnow::Parser::with_taken] gives the
+//! [`Source`] they index into.
+//!
+//! The TOML is fully validated, including decoding keys and scalars, and any error
+//! backtracks so alternatives can be tried.  For diagnostics, re-parse the taken input with
+//! [`parse_value`][crate::parser::parse_value] or [`parse_document`].
+//!
+//! For [`Partial`][winnow::stream::Partial] input, a value or key that runs into the end of the
+//! available input reports [`Needed::Unknown`] as more input may extend it.
+//!
+//! # Example
+//!
+//! ```
+//! use toml_parse::combinator::value;
+//! use winnow::prelude::*;
+//!
+//! // A DSL with `set <name> <toml-value>` statements
+//! fn statement<'i>(input: &mut &'i str) -> ModalResult<(&'i str, &'i str)> {
+//!     let _ = "set ".parse_next(input)?;
+//!     let name = winnow::ascii::alpha1.parse_next(input)?;
+//!     let _ = ' '.parse_next(input)?;
+//!     let (_events, raw) = value.with_taken().parse_next(input)?;
+//!     Ok((name, raw))
+//! }
+//!
+//! let mut input = "set deps [1, { a = 'b' }]; set other 5";
+//! assert_eq!(statement(&mut input).unwrap(), ("deps", "[1, { a = 'b' }]"));
+//! assert_eq!(input, "; set other 5");
+//! assert!(statement(&mut "set bad 1__2").is_err());
+//! ```
+
+use alloc::vec::Vec;
+
+use winnow::error::Needed;
+use winnow::error::ParserError;
+use winnow::stream::Stream;
+use winnow::stream::StreamIsPartial;
+
+use crate::decoder::ScalarKind;
+use crate::lexer::Token;
+use crate::lexer::TokenKind;
+use crate::parser::parse_document;
+use crate::parser::Event;
+use crate::parser::EventKind;
+use crate::parser::EventReceiver;
+use crate::parser::ValidateWhitespace;
+use crate::ErrorSink;
+use crate::Source;
+
+/// Parse a TOML value, like `[1, 2]` or `"hello"`
+///
+/// Whitespace before the value is not skipped.
+pub fn value<'i, I, E>(input: &mut I) -> Result<Vec<Event>, E>
+where
+    I: Stream<Slice = &'i str> + StreamIsPartial,
+    E: ParserError<I>,
+{
+    prefix(input, crate::parser::parse_value_prefix)
+}
+
+/// Parse a TOML key, like `a."b".c`
+///
+/// Whitespace before the key is not skipped while whitespace after it is consumed.
+pub fn key<'i, I, E>(input: &mut I) -> Result<Vec<Event>, E>
+where
+    I: Stream<Slice = &'i str> + StreamIsPartial,
+    E: ParserError<I>,
+{
+    prefix(input, crate::parser::parse_key_prefix)
+}
+
+/// Parse the rest of the input as a TOML document
+///
+/// As a document has no end marker, [`Partial`][winnow::stream::Partial] input is always
+/// [incomplete][Needed::Unknown].  To embed a document, first find where it ends, like with
+/// [`take_until`][winnow::token::take_until], and parse that with
+/// [`Parser::and_then`][winnow::Parser::and_then].
+pub fn document<'i, I, E>(input: &mut I) -> Result<Vec<Event>, E>
+where
+    I: Stream<Slice = &'i str> + StreamIsPartial,
+    E: ParserError<I>,
+{
+    if input.is_partial() {
+        return Err(E::incomplete(input, Needed::Unknown));
+    }
+
+    let start = input.checkpoint();
+    let available = input.finish();
+    let source = Source::new(available);
+    let tokens = source.lex().into_vec();
+    let mut events = Vec::new();
+    let mut error = None;
+    parse_document(
+        &tokens,
+        &mut ValidateWhitespace::new(&mut |e| events.push(e), source),
+        &mut error,
+    );
+    decode(source, &events, &mut error);
+    if error.is_some() {
+        input.reset(&start);
+        return Err(E::from_input(input));
+    }
+
+    Ok(events)
+}
+
+fn prefix<'i, I, E>(
+    input: &mut I,
+    parse: fn(&[Token], &mut dyn EventReceiver, &mut dyn ErrorSink) -> usize,
+) -> Result<Vec<Event>, E>
+where
+    I: Stream<Slice = &'i str> + StreamIsPartial,
+    E: ParserError<I>,
+{
+    let start = input.checkpoint();
+    let available = input.finish();
+    input.reset(&start);
+    let source = Source::new(available);
+
+    // Values and keys end on the line they start on, unless within an array or inline table
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut terminated = false;
+    for token in source.lex() {
+        match token.kind() {
+            TokenKind::LeftSquareBracket | TokenKind::LeftCurlyBracket => depth += 1,
+            TokenKind::RightSquareBracket | TokenKind::RightCurlyBracket => {
+                depth = depth.saturating_sub(1);
+            }
+            TokenKind::Newline if depth == 0 => terminated = true,
+            _ => {}
+        }
+        tokens.push(token);
+        if terminated {
+            break;
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut error = None;
+    let consumed = parse(
+        &tokens,
+        &mut ValidateWhitespace::new(&mut |e| events.push(e), source),
+        &mut error,
+    );
+    decode(source, &events, &mut error);
+
+    let at_end = tokens[consumed..]
+        .iter()
+        .all(|t| matches!(t.kind(), TokenKind::Whitespace | TokenKind::Eof));
+    if input.is_partial() && !terminated && at_end {
+        return Err(E::incomplete(input, Needed::Unknown));
+    }
+    if error.is_some() || consumed == 0 {
+        return Err(E::from_input(input));
+    }
+
+    let end = tokens[consumed - 1].span().end();
+    let _ = input.next_slice(end);
+    Ok(events)
+}
+
+/// Report errors from the content of keys and scalars
+fn decode(source: Source<'_>, events: &[Event], error: &mut dyn ErrorSink) {
+    for event in events {
+        let Some(raw) = source.get(event) else {
+            continue;
+        };
+        match event.kind() {
+            EventKind::SimpleKey => raw.decode_key(&mut (), error),
+            EventKind::Scalar => {
+                let _: ScalarKind = raw.decode_scalar(&mut (), error);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::lexer::TokenKind;
+use crate::Source;
+use crate::Span;
+
+/// Remove all comments from `input`, without parsing it
+///
+/// The whitespace before each comment is removed with it while line endings are kept, so
+/// line numbers are unchanged.  `#` within strings is left alone.
+///
+/// # Example
+///
+/// ```
+/// let input = "# header\nname = \"#1\"  # trailing\n";
+/// assert_eq!(toml_parse::strip_comments(input), "\nname = \"#1\"\n");
+/// ```
+pub fn strip_comments(input: &str) -> Cow<'_, str> {
+    let source = Source::new(input);
+    let mut output = String::new();
+    let mut copied = 0;
+    let mut whitespace = None;
+    for token in source.lex() {
+        match token.kind() {
+            TokenKind::Whitespace => {
+                whitespace = Some(token.span());
+                continue;
+            }
+            TokenKind::Comment => {
+                let start = whitespace.unwrap_or(token.span()).start();
+                output.push_str(&input[copied..start]);
+                copied = token.span().end();
+            }
+            _ => {}
+        }
+        whitespace = None;
+    }
+    if copied == 0 {
+        Cow::Borrowed(input)
+    } else {
+        output.push_str(&input[copied..]);
+        Cow::Owned(output)
+    }
+}
+
+/// Find all comments in `input`, without parsing it
+///
+/// Each comment starts with its `#` and does not include the line ending.
+///
+/// # Example
+///
+/// ```
+/// let input = "# header\nname = \"#1\"  # trailing\n";
+/// let comments = toml_parse::extract_comments(input);
+/// let comments = comments.iter().map(|(_, c)| *c).collect::<Vec<_>>();
+/// assert_eq!(comments, ["# header", "# trailing"]);
+/// ```
+pub fn extract_comments(input: &str) -> Vec<(Span, &str)> {
+    let source = Source::new(input);
+    source
+        .lex()
+        .filter(|token| token.kind() == TokenKind::Comment)
+        .map(|token| {
+            let span = token.span();
+            (span, &input[span.start()..span.end()])
+        })
+        .collect()
+}
@@ -137,4 +137,7 @@ impl crate::parser::EventReceiver for DebugEventReceiver<'_> {
         self.receiver.error(span, error);
         render_event(span, "<error>", anstyle::AnsiColor::Red.on_default());
     }
+    fn is_aborted(&self) -> bool {
+        self.receiver.is_aborted()
+    }
 }
@@ -0,0 +1,176 @@
+use crate::decoder::Encoding;
+use crate::lexer::ML_BASIC_STRING_DELIM;
+use crate::lexer::QUOTATION_MARK;
+use crate::Raw;
+use crate::Span;
+
+/// An escape sequence within a basic string, see [`Raw::escapes`]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Escape<'i> {
+    raw: &'i str,
+    span: Span,
+    decoded: char,
+    required: bool,
+}
+
+impl<'i> Escape<'i> {
+    /// The escape sequence, like `\u0041`
+    pub fn as_str(&self) -> &'i str {
+        self.raw
+    }
+
+    /// The location of the escape sequence within the [`Source`][crate::Source]
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// The character the escape sequence stands for
+    pub fn decoded(&self) -> char {
+        self.decoded
+    }
+
+    /// Whether the string would change meaning or become invalid without the escape
+    ///
+    /// This is conservative, assuming every other escape in the string is left as-is or only
+    /// the redundant ones are replaced.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Whether the escape can be replaced with [`Escape::decoded`]
+    pub fn is_redundant(&self) -> bool {
+        !self.required
+    }
+}
+
+/// Iterator over the [`Escape`]s in a basic string, see [`Raw::escapes`]
+///
+/// Invalid escape sequences are skipped; they are reported when decoding the string.
+#[derive(Clone, Debug)]
+pub struct Escapes<'i> {
+    body: &'i str,
+    offset: usize,
+    multiline: bool,
+    pos: usize,
+}
+
+impl<'i> Escapes<'i> {
+    pub(crate) fn new(raw: Raw<'i>, encoding: Option<Encoding>, span: Span) -> Self {
+        let s = raw.as_str();
+        let (delim, multiline) = match encoding {
+            Some(Encoding::BasicString) => (QUOTATION_MARK_STR, false),
+            Some(Encoding::MlBasicString) => (ML_BASIC_STRING_DELIM, true),
+            _ => ("", false),
+        };
+        let body = if delim.is_empty() {
+            &s[..0]
+        } else {
+            let body = s.strip_prefix(delim).unwrap_or(s);
+            body.strip_suffix(delim).unwrap_or(body)
+        };
+        let prefix_len = if s.starts_with(delim) { delim.len() } else { 0 };
+        let offset = span.start() + prefix_len;
+        Self {
+            body,
+            offset,
+            multiline,
+            pos: 0,
+        }
+    }
+}
+
+const QUOTATION_MARK_STR: &str = "\"";
+
+impl<'i> Iterator for Escapes<'i> {
+    type Item = Escape<'i>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.pos + self.body[self.pos..].find('\\')?;
+            let rest = &self.body[start + 1..];
+            let (decoded, len) = match rest.as_bytes().first() {
+                Some(b'b') => ('\u{8}', 2),
+                Some(b'f') => ('\u{c}', 2),
+                Some(b'n') => ('\n', 2),
+                Some(b'r') => ('\r', 2),
+                Some(b't') => ('\t', 2),
+                Some(b'\\') => ('\\', 2),
+                Some(b'"') => ('"', 2),
+                Some(b'u') => match hexescape(&rest[1..], 4) {
+                    Some(c) => (c, 6),
+                    None => {
+                        self.pos = start + 2;
+                        continue;
+                    }
+                },
+                Some(b'U') => match hexescape(&rest[1..], 8) {
+                    Some(c) => (c, 10),
+                    None => {
+                        self.pos = start + 2;
+                        continue;
+                    }
+                },
+                // Line ending backslash or an invalid escape
+                Some(_) => {
+                    self.pos = start + 1;
+                    continue;
+                }
+                None => {
+                    self.pos = self.body.len();
+                    return None;
+                }
+            };
+            let end = start + len;
+            self.pos = end;
+
+            let before = &self.body[..start];
+            let after = &self.body[end..];
+            return Some(Escape {
+                raw: &self.body[start..end],
+                span: Span::new_unchecked(self.offset + start, self.offset + end),
+                decoded,
+                required: is_required(decoded, self.multiline, before, after),
+            });
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Escapes<'_> {}
+
+fn hexescape(s: &str, num_digits: usize) -> Option<char> {
+    let digits = s.get(..num_digits)?;
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+}
+
+fn is_required(c: char, multiline: bool, before: &str, after: &str) -> bool {
+    match c {
+        '\\' => true,
+        '"' => {
+            // An unescaped quote could combine with its neighbors into a delimiter
+            !multiline
+                || before.is_empty()
+                || before.ends_with(QUOTATION_MARK as char)
+                || after.is_empty()
+                || after.starts_with(QUOTATION_MARK as char)
+                || after.starts_with('\\')
+        }
+        ' ' | '\t' | '\n' if multiline && after_line_ending_backslash(before) => true,
+        // A newline directly after the opening delimiter is trimmed
+        '\n' if multiline => before.is_empty(),
+        '\t' => false,
+        '\u{0}'..='\u{1f}' | '\u{7f}' => true,
+        _ => false,
+    }
+}
+
+/// Whether whitespace at the end of `before` is trimmed by a line ending backslash
+fn after_line_ending_backslash(before: &str) -> bool {
+    let trimmed = before.trim_end_matches([' ', '\t', '\n', '\r']);
+    let backslashes = trimmed.len() - trimmed.trim_end_matches('\\').len();
+    trimmed.len() != before.len() && backslashes % 2 == 1
+}
@@ -6,18 +6,26 @@
 //! - [`Raw::decode_whitespace`][crate::Raw::decode_whitespace]
 //! - [`Raw::decode_comment`][crate::Raw::decode_comment]
 //! - [`Raw::decode_newline`][crate::Raw::decode_newline]
+//! - [`Raw::escapes`][crate::Raw::escapes]
 
 #[cfg(feature = "alloc")]
 use alloc::borrow::Cow;
 #[cfg(feature = "alloc")]
 use alloc::string::String;
 
+pub(crate) mod escape;
 pub(crate) mod scalar;
 pub(crate) mod string;
+pub(crate) mod swar;
 pub(crate) mod ws;
 
+pub use escape::Escape;
+pub use escape::Escapes;
 pub use scalar::IntegerRadix;
 pub use scalar::ScalarKind;
+#[cfg(feature = "alloc")]
+pub use string::BufferedString;
+pub use ws::CarriageReturn;
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[repr(u8)]
@@ -115,3 +123,30 @@ impl<'s> StringBuilder<'s> for String {
         true
     }
 }
+
+#[cfg(feature = "alloc")]
+impl<'s> StringBuilder<'s> for BufferedString<'s, '_> {
+    fn clear(&mut self) {
+        self.borrowed = "";
+        self.buffer.clear();
+        self.buffered = false;
+    }
+    fn push_str(&mut self, append: &'s str) -> bool {
+        if !self.buffered {
+            if self.borrowed.is_empty() {
+                self.borrowed = append;
+                return true;
+            }
+            self.spill();
+        }
+        self.buffer.push_str(append);
+        true
+    }
+    fn push_char(&mut self, append: char) -> bool {
+        if !self.buffered {
+            self.spill();
+        }
+        self.buffer.push(append);
+        true
+    }
+}
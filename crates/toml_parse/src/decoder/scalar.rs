@@ -1,3 +1,5 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 use winnow::stream::ContainsToken as _;
 use winnow::stream::FindSlice as _;
 use winnow::stream::Offset as _;
@@ -8,6 +10,7 @@ use crate::ErrorSink;
 use crate::Expected;
 use crate::ParseError;
 use crate::Raw;
+use crate::Severity;
 use crate::Span;
 
 const ALLOCATION_ERROR: &str = "could not allocate for string";
@@ -99,6 +102,9 @@ pub(crate) fn decode_unquoted_scalar<'i>(
     let Some(first) = s.as_bytes().first() else {
         return decode_invalid(raw, output, error);
     };
+    if NULLS.iter().any(|null| s.eq_ignore_ascii_case(null)) {
+        return decode_null(raw, output, error);
+    }
     match first {
         // number starts
         b'+' | b'-' => {
@@ -188,12 +194,13 @@ pub(crate) fn decode_sign_prefix<'i>(
                 let expected = &[Expected::Literal(SYMBOL)];
                 let start = value.offset_from(&raw.as_str());
                 let end = start + value.len();
-                error.report_error(
+                error.report_error(with_symbol_fix(
                     ParseError::new(kind.invalid_description())
                         .with_context(Span::new_unchecked(0, raw.len()))
                         .with_expected(expected)
                         .with_unexpected(Span::new_unchecked(start, end)),
-                );
+                    raw,
+                ));
                 decode_as(raw, SYMBOL, kind, output, error)
             } else {
                 decode_as_is(raw, kind, output, error)
@@ -206,12 +213,13 @@ pub(crate) fn decode_sign_prefix<'i>(
                 let expected = &[Expected::Literal(SYMBOL)];
                 let start = value.offset_from(&raw.as_str());
                 let end = start + value.len();
-                error.report_error(
+                error.report_error(with_symbol_fix(
                     ParseError::new(kind.invalid_description())
                         .with_context(Span::new_unchecked(0, raw.len()))
                         .with_expected(expected)
                         .with_unexpected(Span::new_unchecked(start, end)),
-                );
+                    raw,
+                ));
                 decode_as(raw, SYMBOL, kind, output, error)
             } else {
                 decode_as_is(raw, kind, output, error)
@@ -238,22 +246,24 @@ pub(crate) fn decode_zero_prefix<'i>(
         match radix {
             b'x' | b'X' => {
                 if signed {
-                    error.report_error(
+                    error.report_error(with_number_fix(
                         ParseError::new("integers with a radix cannot be signed")
                             .with_context(Span::new_unchecked(0, raw.len()))
                             .with_expected(&[])
                             .with_unexpected(Span::new_unchecked(0, 1)),
-                    );
+                        raw,
+                    ));
                 }
                 if radix == b'X' {
                     let start = value.offset_from(&raw.as_str());
                     let end = start + 2;
-                    error.report_error(
+                    error.report_error(with_number_fix(
                         ParseError::new("radix must be lowercase")
                             .with_context(Span::new_unchecked(0, raw.len()))
                             .with_expected(&[Expected::Literal("0x")])
                             .with_unexpected(Span::new_unchecked(start, end)),
-                    );
+                        raw,
+                    ));
                 }
                 let radix = IntegerRadix::Hex;
                 let kind = ScalarKind::Integer(radix);
@@ -263,22 +273,24 @@ pub(crate) fn decode_zero_prefix<'i>(
             }
             b'o' | b'O' => {
                 if signed {
-                    error.report_error(
+                    error.report_error(with_number_fix(
                         ParseError::new("integers with a radix cannot be signed")
                             .with_context(Span::new_unchecked(0, raw.len()))
                             .with_expected(&[])
                             .with_unexpected(Span::new_unchecked(0, 1)),
-                    );
+                        raw,
+                    ));
                 }
                 if radix == b'O' {
                     let start = value.offset_from(&raw.as_str());
                     let end = start + 2;
-                    error.report_error(
+                    error.report_error(with_number_fix(
                         ParseError::new("radix must be lowercase")
                             .with_context(Span::new_unchecked(0, raw.len()))
                             .with_expected(&[Expected::Literal("0o")])
                             .with_unexpected(Span::new_unchecked(start, end)),
-                    );
+                        raw,
+                    ));
                 }
                 let radix = IntegerRadix::Oct;
                 let kind = ScalarKind::Integer(radix);
@@ -288,22 +300,24 @@ pub(crate) fn decode_zero_prefix<'i>(
             }
             b'b' | b'B' => {
                 if signed {
-                    error.report_error(
+                    error.report_error(with_number_fix(
                         ParseError::new("integers with a radix cannot be signed")
                             .with_context(Span::new_unchecked(0, raw.len()))
                             .with_expected(&[])
                             .with_unexpected(Span::new_unchecked(0, 1)),
-                    );
+                        raw,
+                    ));
                 }
                 if radix == b'B' {
                     let start = value.offset_from(&raw.as_str());
                     let end = start + 2;
-                    error.report_error(
+                    error.report_error(with_number_fix(
                         ParseError::new("radix must be lowercase")
                             .with_context(Span::new_unchecked(0, raw.len()))
                             .with_expected(&[Expected::Literal("0b")])
                             .with_unexpected(Span::new_unchecked(start, end)),
-                    );
+                        raw,
+                    ));
                 }
                 let radix = IntegerRadix::Bin;
                 let kind = ScalarKind::Integer(radix);
@@ -313,22 +327,24 @@ pub(crate) fn decode_zero_prefix<'i>(
             }
             b'd' | b'D' => {
                 if signed {
-                    error.report_error(
+                    error.report_error(with_number_fix(
                         ParseError::new("integers with a radix cannot be signed")
                             .with_context(Span::new_unchecked(0, raw.len()))
                             .with_expected(&[])
                             .with_unexpected(Span::new_unchecked(0, 1)),
-                    );
+                        raw,
+                    ));
                 }
                 let radix = IntegerRadix::Dec;
                 let kind = ScalarKind::Integer(radix);
                 let stream = &value[2..];
-                error.report_error(
+                error.report_error(with_number_fix(
                     ParseError::new("redundant integer number prefix")
                         .with_context(Span::new_unchecked(0, raw.len()))
                         .with_expected(&[])
                         .with_unexpected(Span::new_unchecked(0, 2)),
-                );
+                    raw,
+                ));
                 ensure_radixed_value(stream, raw, radix, error);
                 decode_float_or_integer(stream, raw, kind, output, error)
             }
@@ -387,6 +403,7 @@ pub(crate) fn decode_datetime_or_float_or_integer<'i>(
 /// float-exp-part = [ minus / plus ] zero-prefixable-int
 /// ```
 pub(crate) fn ensure_float<'i>(mut value: &'i str, raw: Raw<'i>, error: &mut dyn ErrorSink) {
+    ensure_float_precision(value, raw, error);
     ensure_dec_uint(&mut value, raw, false, "invalid mantissa", error);
 
     if value.starts_with(".") {
@@ -414,6 +431,37 @@ pub(crate) fn ensure_float<'i>(mut value: &'i str, raw: Raw<'i>, error: &mut dyn
     }
 }
 
+/// Significant digits needed to write any [`f64`], more are rounded away
+const F64_DIGITS: usize = 17;
+
+fn ensure_float_precision(value: &str, raw: Raw<'_>, error: &mut dyn ErrorSink) {
+    let mantissa = value.split(['e', 'E']).next().unwrap_or(value);
+    let digits = mantissa
+        .bytes()
+        .filter(|b| b.is_ascii_digit())
+        .skip_while(|b| *b == b'0');
+    let mut significant = 0;
+    let mut zeros = 0;
+    for digit in digits {
+        if digit == b'0' {
+            zeros += 1;
+        } else {
+            significant += zeros + 1;
+            zeros = 0;
+        }
+    }
+    if F64_DIGITS < significant {
+        let start = value.offset_from(&raw.as_str());
+        let end = start + mantissa.len();
+        error.report_error(
+            ParseError::new("float has more digits than can be represented")
+                .with_context(Span::new_unchecked(0, raw.len()))
+                .with_unexpected(Span::new_unchecked(start, end))
+                .with_severity(Severity::Warning),
+        );
+    }
+}
+
 pub(crate) fn ensure_dec_uint<'i>(
     value: &mut &'i str,
     raw: Raw<'i>,
@@ -437,23 +485,25 @@ pub(crate) fn ensure_dec_uint<'i>(
         0 => {
             let start = start.offset_from(&raw.as_str());
             let end = start;
-            error.report_error(
+            error.report_error(with_number_fix(
                 ParseError::new(invalid_description)
                     .with_context(Span::new_unchecked(0, raw.len()))
                     .with_expected(&[Expected::Description("digits")])
                     .with_unexpected(Span::new_unchecked(start, end)),
-            );
+                raw,
+            ));
         }
         1 => {}
         _ if start.starts_with("0") && !zero_prefix => {
             let start = start.offset_from(&raw.as_str());
             let end = start + 1;
-            error.report_error(
+            error.report_error(with_number_fix(
                 ParseError::new("unexpected leading zero")
                     .with_context(Span::new_unchecked(0, raw.len()))
                     .with_expected(&[])
                     .with_unexpected(Span::new_unchecked(start, end)),
-            );
+                raw,
+            ));
         }
         _ => {}
     }
@@ -463,12 +513,13 @@ pub(crate) fn ensure_no_leading_zero<'i>(value: &'i str, raw: Raw<'i>, error: &m
     if value.starts_with("0") {
         let start = value.offset_from(&raw.as_str());
         let end = start + 1;
-        error.report_error(
+        error.report_error(with_number_fix(
             ParseError::new("unexpected leading zero")
                 .with_context(Span::new_unchecked(0, raw.len()))
                 .with_expected(&[])
                 .with_unexpected(Span::new_unchecked(start, end)),
-        );
+            raw,
+        ));
     }
 }
 
@@ -480,7 +531,7 @@ pub(crate) fn ensure_radixed_value(
 ) {
     let invalid = ['+', '-'];
     let value = if let Some(value) = value.strip_prefix(invalid) {
-        let pos = raw.as_str().find(invalid).unwrap();
+        let pos = raw.as_str().find(invalid).unwrap_or(0);
         error.report_error(
             ParseError::new("unexpected sign")
                 .with_context(Span::new_unchecked(0, raw.len()))
@@ -518,22 +569,24 @@ pub(crate) fn decode_float_or_integer<'i>(
 
     if has_underscore(stream) {
         if stream.starts_with(underscore) {
-            error.report_error(
+            error.report_error(with_number_fix(
                 ParseError::new("`_` may only go between digits")
                     .with_context(Span::new_unchecked(0, raw.len()))
                     .with_expected(&[])
                     .with_unexpected(Span::new_unchecked(0, underscore.len())),
-            );
+                raw,
+            ));
         }
         if 1 < stream.len() && stream.ends_with(underscore) {
             let start = stream.offset_from(&raw.as_str());
             let end = start + stream.len();
-            error.report_error(
+            error.report_error(with_number_fix(
                 ParseError::new("`_` may only go between digits")
                     .with_context(Span::new_unchecked(0, raw.len()))
                     .with_expected(&[])
                     .with_unexpected(Span::new_unchecked(end - underscore.len(), end)),
-            );
+                raw,
+            ));
         }
 
         for part in stream.split(underscore) {
@@ -546,11 +599,12 @@ pub(crate) fn decode_float_or_integer<'i>(
                     let start = part_start - 1;
                     let end = part_start;
                     debug_assert_eq!(&raw.as_str()[start..end], underscore);
-                    error.report_error(
+                    error.report_error(with_number_fix(
                         ParseError::new("`_` may only go between digits")
                             .with_context(Span::new_unchecked(0, raw.len()))
                             .with_unexpected(Span::new_unchecked(start, end)),
-                    );
+                        raw,
+                    ));
                 }
             }
             if 1 < part.len() && part_end < raw.len() {
@@ -559,22 +613,24 @@ pub(crate) fn decode_float_or_integer<'i>(
                     let start = part_end;
                     let end = start + underscore.len();
                     debug_assert_eq!(&raw.as_str()[start..end], underscore);
-                    error.report_error(
+                    error.report_error(with_number_fix(
                         ParseError::new("`_` may only go between digits")
                             .with_context(Span::new_unchecked(0, raw.len()))
                             .with_unexpected(Span::new_unchecked(start, end)),
-                    );
+                        raw,
+                    ));
                 }
             }
 
             if part.is_empty() && part_start != 0 && part_end != raw.len() {
                 let start = part_start;
                 let end = start + 1;
-                error.report_error(
+                error.report_error(with_number_fix(
                     ParseError::new("`_` may only go between digits")
                         .with_context(Span::new_unchecked(0, raw.len()))
                         .with_unexpected(Span::new_unchecked(start, end)),
-                );
+                    raw,
+                ));
             }
 
             if !part.is_empty() && !output.push_str(part) {
@@ -596,6 +652,101 @@ pub(crate) fn decode_float_or_integer<'i>(
     kind
 }
 
+/// Suggest a corrected literal for a near-miss number, like `42` for `042`
+fn with_number_fix(error: ParseError, raw: Raw<'_>) -> ParseError {
+    #[cfg(feature = "alloc")]
+    if let Some(fix) = fix_number(raw.as_str()) {
+        return error.with_fix(fix);
+    }
+    #[cfg(not(feature = "alloc"))]
+    let _ = raw;
+    error
+}
+
+/// Rewrite a number with misplaced `_`, leading zeros, missing digits around `.`, or an
+/// uppercase or redundant radix prefix, if that is all that is wrong with it
+#[cfg(feature = "alloc")]
+fn fix_number(raw: &str) -> Option<String> {
+    let (sign, unsigned) = match raw.as_bytes().first() {
+        Some(b'+' | b'-') => raw.split_at(1),
+        _ => ("", raw),
+    };
+    let mut fixed = String::new();
+    let radix = unsigned.get(..2).map(|prefix| prefix.to_ascii_lowercase());
+    match radix.as_deref() {
+        Some(prefix @ ("0x" | "0o" | "0b")) => {
+            if sign == "-" {
+                return None;
+            }
+            let radix = match prefix {
+                "0x" => IntegerRadix::Hex,
+                "0o" => IntegerRadix::Oct,
+                _ => IntegerRadix::Bin,
+            };
+            fixed.push_str(prefix);
+            push_digits(&mut fixed, &unsigned[2..], radix.validator())?;
+        }
+        _ => {
+            let unsigned = if radix.as_deref() == Some("0d") {
+                &unsigned[2..]
+            } else {
+                unsigned
+            };
+            let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+                Some(i) => (&unsigned[..i], Some(&unsigned[i + 1..])),
+                None => (unsigned, None),
+            };
+            let (int, frac) = match mantissa.split_once('.') {
+                Some((int, frac)) => (int, Some(frac)),
+                None => (mantissa, None),
+            };
+            let is_dec = |c: char| c.is_ascii_digit();
+            fixed.push_str(sign);
+            let int = int.trim_start_matches(['0', '_']);
+            if int.is_empty() {
+                fixed.push('0');
+            } else {
+                push_digits(&mut fixed, int, is_dec)?;
+            }
+            if let Some(frac) = frac {
+                fixed.push('.');
+                if frac.trim_matches('_').is_empty() {
+                    fixed.push('0');
+                } else {
+                    push_digits(&mut fixed, frac, is_dec)?;
+                }
+            }
+            if let Some(exponent) = exponent {
+                fixed.push('e');
+                let (exp_sign, exp_digits) = match exponent.as_bytes().first() {
+                    Some(b'+' | b'-') => exponent.split_at(1),
+                    _ => ("", exponent),
+                };
+                fixed.push_str(exp_sign);
+                push_digits(&mut fixed, exp_digits, is_dec)?;
+            }
+        }
+    }
+    (fixed != raw).then_some(fixed)
+}
+
+/// Copy `digits` with `_` only between digits
+#[cfg(feature = "alloc")]
+fn push_digits(fixed: &mut String, digits: &str, valid: fn(char) -> bool) -> Option<()> {
+    let mut parts = digits.split('_').filter(|part| !part.is_empty()).peekable();
+    parts.peek()?;
+    for (i, part) in parts.enumerate() {
+        if !part.chars().all(valid) {
+            return None;
+        }
+        if i != 0 {
+            fixed.push('_');
+        }
+        fixed.push_str(part);
+    }
+    Some(())
+}
+
 fn is_any_digit(b: u8, kind: ScalarKind) -> bool {
     if kind == ScalarKind::Float {
         is_dec_integer_digit(b)
@@ -655,17 +806,73 @@ pub(crate) fn decode_symbol<'i>(
     error: &mut dyn ErrorSink,
 ) -> ScalarKind {
     if raw.as_str() != symbol {
-        error.report_error(
+        error.report_error(with_symbol_fix(
             ParseError::new(kind.invalid_description())
                 .with_context(Span::new_unchecked(0, raw.len()))
                 .with_expected(expected)
                 .with_unexpected(Span::new_unchecked(0, raw.len())),
-        );
+            raw,
+        ));
     }
 
     decode_as(raw, symbol, kind, output, error)
 }
 
+/// Symbols that only differ from valid ones by case, like `True`
+const SYMBOLS: &[&str] = &[
+    "true", "false", "inf", "+inf", "-inf", "nan", "+nan", "-nan",
+];
+
+/// How other languages spell a missing value
+const NULLS: &[&str] = &["null", "none", "nil"];
+
+/// Explain and suggest the valid spelling of a symbol with the wrong case, like `true` for `True`
+fn with_symbol_fix(error: ParseError, raw: Raw<'_>) -> ParseError {
+    let Some(fix) = SYMBOLS
+        .iter()
+        .find(|symbol| raw.as_str().eq_ignore_ascii_case(symbol))
+    else {
+        return error;
+    };
+    let description = match fix.trim_start_matches(['+', '-']) {
+        "inf" => "`inf` must be lowercase",
+        "nan" => "`nan` must be lowercase",
+        _ => "booleans must be lowercase",
+    };
+    let mut fixed = ParseError::new(description).with_fix(*fix);
+    if let Some(context) = error.context() {
+        fixed = fixed.with_context(context);
+    }
+    if let Some(expected) = error.expected() {
+        fixed = fixed.with_expected(expected);
+    }
+    if let Some(unexpected) = error.unexpected() {
+        fixed = fixed.with_unexpected(unexpected);
+    }
+    fixed
+}
+
+/// Report a value like `null`, which TOML doesn't have, decoding it as a string
+fn decode_null<'i>(
+    raw: Raw<'i>,
+    output: &mut dyn StringBuilder<'i>,
+    error: &mut dyn ErrorSink,
+) -> ScalarKind {
+    error.report_error(
+        ParseError::new("TOML has no null value, leave out the key instead")
+            .with_context(Span::new_unchecked(0, raw.len()))
+            .with_unexpected(Span::new_unchecked(0, raw.len())),
+    );
+
+    output.clear();
+    if !output.push_str(raw.as_str()) {
+        error.report_error(
+            ParseError::new(ALLOCATION_ERROR).with_unexpected(Span::new_unchecked(0, raw.len())),
+        );
+    }
+    ScalarKind::String
+}
+
 pub(crate) fn decode_invalid<'i>(
     raw: Raw<'i>,
     output: &mut dyn StringBuilder<'i>,
@@ -686,3 +893,97 @@ pub(crate) fn decode_invalid<'i>(
     }
     ScalarKind::String
 }
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod test {
+    use super::*;
+
+    #[test]
+    fn number_fixes() {
+        let cases = [
+            ("042", Some("42")),
+            ("-042", Some("-42")),
+            ("000", Some("0")),
+            ("0_42", Some("42")),
+            ("1__0", Some("1_0")),
+            ("_1", Some("1")),
+            ("1_", Some("1")),
+            ("1_.5_", Some("1.5")),
+            ("01.5", Some("1.5")),
+            (".5", Some("0.5")),
+            ("5.e3", Some("5.0e3")),
+            ("1e0_", Some("1e0")),
+            ("0X1F", Some("0x1F")),
+            ("0xdead__beef", Some("0xdead_beef")),
+            ("+0x1", Some("0x1")),
+            ("0d10", Some("10")),
+            // No unambiguous fix
+            ("-0x1", None),
+            ("0x_", None),
+            ("0b12", None),
+            ("1e", None),
+            ("1a_", None),
+        ];
+        for (input, expected) in cases {
+            let mut errors = Vec::new();
+            let raw = Raw::new_unchecked(input, None, Default::default());
+            let _ = decode_unquoted_scalar(raw, &mut (), &mut errors);
+            assert!(!errors.is_empty(), "{input}");
+            let fixes = errors.iter().map(|e| e.fix()).collect::<Vec<_>>();
+            assert!(
+                fixes.iter().all(|fix| *fix == fixes[0]),
+                "{input}: {fixes:?}"
+            );
+            assert_eq!(fixes[0], expected, "{input}");
+
+            if let Some(fix) = expected {
+                let mut errors = Vec::new();
+                let raw = Raw::new_unchecked(fix, None, Default::default());
+                let _ = decode_unquoted_scalar(raw, &mut (), &mut errors);
+                assert_eq!(errors, [], "{input} -> {fix}");
+            }
+        }
+    }
+
+    #[test]
+    fn symbol_fixes() {
+        let cases = [
+            ("True", Some("true")),
+            ("FALSE", Some("false")),
+            ("Inf", Some("inf")),
+            ("-INF", Some("-inf")),
+            ("NaN", Some("nan")),
+            ("+NaN", Some("+nan")),
+            // Not just a matter of case
+            ("yes", None),
+            ("truthy", None),
+            ("infinity", None),
+        ];
+        for (input, expected) in cases {
+            let mut errors = Vec::new();
+            let raw = Raw::new_unchecked(input, None, Default::default());
+            let _ = decode_unquoted_scalar(raw, &mut (), &mut errors);
+            assert_eq!(errors.len(), 1, "{input}: {errors:?}");
+            assert_eq!(errors[0].fix(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn nulls() {
+        for input in ["null", "None", "NIL"] {
+            let mut errors = Vec::new();
+            let mut output = String::new();
+            let raw = Raw::new_unchecked(input, None, Default::default());
+            let kind = decode_unquoted_scalar(raw, &mut output, &mut errors);
+            assert_eq!(kind, ScalarKind::String, "{input}");
+            assert_eq!(output, input);
+            assert_eq!(errors.len(), 1, "{input}: {errors:?}");
+            assert_eq!(
+                errors[0].description(),
+                "TOML has no null value, leave out the key instead"
+            );
+            assert_eq!(errors[0].fix(), None, "{input}");
+        }
+    }
+}
@@ -1,9 +1,12 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 use core::ops::RangeInclusive;
 
 use winnow::stream::ContainsToken as _;
 use winnow::stream::Offset as _;
 use winnow::stream::Stream as _;
 
+use crate::decoder::swar::printable_len;
 use crate::decoder::StringBuilder;
 use crate::lexer::APOSTROPHE;
 use crate::lexer::ML_BASIC_STRING_DELIM;
@@ -62,7 +65,13 @@ pub(crate) fn decode_literal_string<'i>(
         s
     };
 
-    for (i, b) in s.as_bytes().iter().enumerate() {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        i += printable_len(&bytes[i..], &[APOSTROPHE]);
+        let Some(b) = bytes.get(i) else {
+            break;
+        };
         if !LITERAL_CHAR.contains_token(b) {
             let offset = (&s.as_bytes()[i..]).offset_from(&raw.as_bytes());
             error.report_error(
@@ -72,6 +81,7 @@ pub(crate) fn decode_literal_string<'i>(
                     .with_unexpected(Span::new_unchecked(offset, offset)),
             );
         }
+        i += 1;
     }
 
     if !output.push_str(s) {
@@ -141,7 +151,13 @@ pub(crate) fn decode_ml_literal_string<'i>(
         s.trim_end_matches('\'')
     };
 
-    for (i, b) in s.as_bytes().iter().enumerate() {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        i += printable_len(&bytes[i..], &[]);
+        let Some(b) = bytes.get(i) else {
+            break;
+        };
         if *b == b'\'' || *b == b'\n' {
         } else if *b == b'\r' {
             if s.as_bytes().get(i + 1) != Some(&b'\n') {
@@ -162,6 +178,7 @@ pub(crate) fn decode_ml_literal_string<'i>(
                     .with_unexpected(Span::new_unchecked(offset, offset)),
             );
         }
+        i += 1;
     }
 
     if !output.push_str(s) {
@@ -268,10 +285,12 @@ pub(crate) fn decode_basic_string<'i>(
 
 /// `basic-unescaped = wschar / %x21 / %x23-5B / %x5D-7E / non-ascii`
 fn basic_unescaped<'i>(stream: &mut &'i str) -> &'i str {
-    let offset = stream
-        .as_bytes()
-        .offset_for(|b| !BASIC_UNESCAPED.contains_token(b))
-        .unwrap_or(stream.len());
+    let bytes = stream.as_bytes();
+    let printable = printable_len(bytes, &[QUOTATION_MARK, ESCAPE]);
+    let offset = printable
+        + (&bytes[printable..])
+            .offset_for(|b| !BASIC_UNESCAPED.contains_token(b))
+            .unwrap_or(bytes.len() - printable);
     #[cfg(feature = "unsafe")] // SAFETY: BASIC_UNESCAPED ensure `offset` is along UTF-8 boundary
     unsafe {
         stream.next_slice_unchecked(offset)
@@ -641,10 +660,12 @@ fn mlb_escaped_nl(stream: &mut &str, raw: Raw<'_>, error: &mut dyn ErrorSink) {
 /// mlb-unescaped = wschar / %x21 / %x23-5B / %x5D-7E / non-ascii
 /// ```
 fn mlb_unescaped<'i>(stream: &mut &'i str) -> &'i str {
-    let offset = stream
-        .as_bytes()
-        .offset_for(|b| !(MLB_UNESCAPED, b'"', b'\n').contains_token(b))
-        .unwrap_or(stream.len());
+    let bytes = stream.as_bytes();
+    let printable = printable_len(bytes, &[ESCAPE]);
+    let offset = printable
+        + (&bytes[printable..])
+            .offset_for(|b| !(MLB_UNESCAPED, b'"', b'\n').contains_token(b))
+            .unwrap_or(bytes.len() - printable);
     #[cfg(feature = "unsafe")] // SAFETY: BASIC_UNESCAPED ensure `offset` is along UTF-8 boundary
     unsafe {
         stream.next_slice_unchecked(offset)
@@ -734,6 +755,56 @@ const UNQUOTED_CHAR: (
     u8,
 ) = (b'A'..=b'Z', b'a'..=b'z', b'0'..=b'9', b'-', b'_');
 
+/// A [`StringBuilder`] that borrows from the source when possible, falling back to a
+/// caller-provided buffer
+///
+/// Unlike [`Cow`][alloc::borrow::Cow], the buffer's allocation can be reused across many
+/// decoded strings.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct BufferedString<'s, 'b> {
+    pub(super) borrowed: &'s str,
+    pub(super) buffer: &'b mut String,
+    pub(super) buffered: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<'s, 'b> BufferedString<'s, 'b> {
+    /// Decode into `buffer`, clearing any existing content
+    pub fn new(buffer: &'b mut String) -> Self {
+        buffer.clear();
+        Self {
+            borrowed: "",
+            buffer,
+            buffered: false,
+        }
+    }
+
+    /// The decoded string
+    pub fn as_str(&self) -> &str {
+        if self.buffered {
+            self.buffer.as_str()
+        } else {
+            self.borrowed
+        }
+    }
+
+    /// The decoded string, if it could be borrowed from the source without writing to the buffer
+    pub fn borrowed(&self) -> Option<&'s str> {
+        (!self.buffered).then_some(self.borrowed)
+    }
+
+    /// Whether the decoded string could be borrowed from the source
+    pub fn is_borrowed(&self) -> bool {
+        !self.buffered
+    }
+
+    pub(super) fn spill(&mut self) {
+        self.buffer.push_str(self.borrowed);
+        self.buffered = true;
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "std")]
 mod test {
@@ -746,6 +817,67 @@ mod test {
     use snapbox::prelude::*;
     use snapbox::str;
 
+    #[test]
+    fn buffered_string() {
+        let mut buffer = String::new();
+        let cases = [
+            (r#""plain""#, "plain", true),
+            (r#""tab\there""#, "tab\there", false),
+            (r#""""#, "", true),
+            (r#""\u00e9t\u00e9""#, "\u{e9}t\u{e9}", false),
+        ];
+        for (input, expected, expected_borrowed) in cases {
+            let mut error = Vec::new();
+            let mut actual = BufferedString::new(&mut buffer);
+            decode_basic_string(
+                Raw::new_unchecked(input, Some(Encoding::BasicString), Default::default()),
+                &mut actual,
+                &mut error,
+            );
+            assert_eq!(actual.as_str(), expected);
+            assert_eq!(actual.is_borrowed(), expected_borrowed);
+            assert!(error.is_empty());
+        }
+    }
+
+    #[test]
+    fn invalid_char_offsets() {
+        type Decode = for<'i> fn(Raw<'i>, &mut dyn StringBuilder<'i>, &mut dyn ErrorSink);
+        let decoders: [(Encoding, Decode); 4] = [
+            (Encoding::LiteralString, decode_literal_string),
+            (Encoding::MlLiteralString, decode_ml_literal_string),
+            (Encoding::BasicString, decode_basic_string),
+            (Encoding::MlBasicString, decode_ml_basic_string),
+        ];
+        for (encoding, decode) in decoders {
+            let delim = match encoding {
+                Encoding::LiteralString => "'",
+                Encoding::BasicString => "\"",
+                Encoding::MlLiteralString => "'''",
+                Encoding::MlBasicString => "\"\"\"",
+            };
+            for bad in ["\u{7F}", "\u{1}", "\u{1F}"] {
+                for len in [0, 7, 8, 9, 15, 16, 17] {
+                    let content = format!("\u{D7FF}\u{E000}{}", "a".repeat(len));
+                    let input = format!("{delim}{content}{bad}z{delim}");
+                    let mut error = Vec::new();
+                    let mut actual = Cow::Borrowed("");
+                    decode(
+                        Raw::new_unchecked(&input, Some(encoding), Default::default()),
+                        &mut actual,
+                        &mut error,
+                    );
+                    let offset = delim.len() + content.len();
+                    let unexpected = error
+                        .iter()
+                        .map(|e| e.unexpected().map(|s| s.start()))
+                        .collect::<Vec<_>>();
+                    assert_eq!(unexpected, [Some(offset)], "{input:?}");
+                }
+            }
+        }
+    }
+
     #[test]
     fn literal_string() {
         let cases = [
@@ -917,6 +1049,12 @@ trimmed in raw strings.
         unexpected: Some(
             9..9,
         ),
+        severity: Error,
+        fix: None,
+        source_id: SourceId(
+            0,
+        ),
+        allowed_since: None,
     },
 ]
 
@@ -951,6 +1089,12 @@ trailing
         unexpected: Some(
             8..9,
         ),
+        severity: Error,
+        fix: None,
+        source_id: SourceId(
+            0,
+        ),
+        allowed_since: None,
     },
 ]
 
@@ -1089,6 +1233,12 @@ The quick brown \
         unexpected: Some(
             7..7,
         ),
+        severity: Error,
+        fix: None,
+        source_id: SourceId(
+            0,
+        ),
+        allowed_since: None,
     },
 ]
 
@@ -1136,6 +1286,12 @@ The quick brown \
         unexpected: Some(
             6..6,
         ),
+        severity: Error,
+        fix: None,
+        source_id: SourceId(
+            0,
+        ),
+        allowed_since: None,
     },
 ]
 
@@ -1242,6 +1398,12 @@ The quick brown \
         unexpected: Some(
             0..0,
         ),
+        severity: Error,
+        fix: None,
+        source_id: SourceId(
+            0,
+        ),
+        allowed_since: None,
     },
 ]
 
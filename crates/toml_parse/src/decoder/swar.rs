@@ -0,0 +1,100 @@
+//! Validate bytes a word at a time ("SIMD within a register")
+//!
+//! Strings and comments are mostly printable characters, so rather than checking each byte
+//! against the allowed ranges, skip ahead over words that are known to be clean and only check
+//! the bytes of the word that isn't.
+
+const WORD: usize = (u64::BITS / 8) as usize;
+
+const fn splat(b: u8) -> u64 {
+    u64::from_ne_bytes([b; WORD])
+}
+
+/// Whether any byte of `word` is less than `n`, for `n <= 0x80`
+///
+/// Bytes `0x80` and up, like those of non-ASCII characters, never match.
+#[inline(always)]
+fn has_less(word: u64, n: u8) -> bool {
+    word.wrapping_sub(splat(n)) & !word & splat(0x80) != 0
+}
+
+/// Whether any byte of `word` is `b`
+#[inline(always)]
+fn has_byte(word: u64, b: u8) -> bool {
+    has_less(word ^ splat(b), 1)
+}
+
+/// The number of leading bytes of `bytes` that are `%x20-7E / non-ascii` and not in `excluded`
+///
+/// This stops at the start of the first word that might not be, so the caller still needs to
+/// check the bytes after, one at a time.
+#[inline]
+pub(crate) fn printable_len(bytes: &[u8], excluded: &[u8]) -> usize {
+    let mut len = 0;
+    for chunk in bytes.chunks_exact(WORD) {
+        let mut word = [0; WORD];
+        word.copy_from_slice(chunk);
+        let word = u64::from_ne_bytes(word);
+        if has_less(word, 0x20)
+            || has_byte(word, 0x7F)
+            || excluded.iter().any(|b| has_byte(word, *b))
+        {
+            break;
+        }
+        len += WORD;
+    }
+    len
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn printable() {
+        let cases: &[(&[u8], &[u8], usize)] = &[
+            (b"", b"", 0),
+            (b"short", b"", 0),
+            (b"12345678", b"", 8),
+            (b"1234567812345678abc", b"", 16),
+            (b"12345678\t2345678", b"", 8),
+            (b"1234567\n", b"", 0),
+            (b"12345678 !~~~~~~", b"", 16),
+            (b"1234567812345\x7F78", b"", 8),
+            (b"1234567812345\x1F78", b"", 8),
+            (b"1234567812345\x2078", b"", 16),
+            (b"1234567812345'78", b"'", 8),
+            (b"12345678\"2345678", b"\"\\", 8),
+            (b"12345678\\2345678", b"\"\\", 8),
+        ];
+        for (input, excluded, expected) in cases {
+            let actual = printable_len(input, excluded);
+            assert_eq!(
+                actual,
+                *expected,
+                "{:?} excluding {:?}",
+                String::from_utf8_lossy(input),
+                String::from_utf8_lossy(excluded)
+            );
+        }
+    }
+
+    #[test]
+    fn non_ascii() {
+        let cases = [
+            // The edges of what is allowed on either side of the ASCII `DEL`
+            "\u{7E}\u{80}\u{7E}\u{80}\u{7E}",
+            // The last code points before and after the surrogates
+            "\u{D7FF}\u{E000}\u{D7FF}",
+            "\u{10FFFF}\u{10FFFF}\u{10FFFF}",
+            "\u{FFFD}\u{FFFE}\u{FFFF}",
+        ];
+        for case in cases {
+            let len = case.len() - case.len() % WORD;
+            assert_eq!(printable_len(case.as_bytes(), b"'"), len, "{case:?}");
+        }
+
+        let del = "\u{80}\u{80}\u{80}\u{7F}";
+        assert_eq!(printable_len(del.as_bytes(), b""), 0);
+    }
+}
@@ -2,13 +2,38 @@ use core::ops::RangeInclusive;
 
 use winnow::stream::ContainsToken as _;
 
+use crate::decoder::swar::printable_len;
 use crate::lexer::COMMENT_START_SYMBOL;
 use crate::ErrorSink;
 use crate::Expected;
 use crate::ParseError;
 use crate::Raw;
+use crate::Severity;
 use crate::Span;
 
+/// How to handle a carriage return (`\r`) that is not part of a `\r\n` line ending
+///
+/// TOML does not allow these but they show up in files with mixed line endings.  See
+/// [`Lexer::with_carriage_return`][crate::lexer::Lexer::with_carriage_return] and
+/// [`ValidateWhitespace::with_carriage_return`][crate::parser::ValidateWhitespace::with_carriage_return].
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum CarriageReturn {
+    /// Report an error, as required by the TOML specification
+    #[default]
+    Reject,
+    /// Keep it as part of a comment, treating it as a line ending elsewhere
+    Preserve,
+    /// Treat it as a line ending, including within comments
+    Normalize,
+}
+
+impl CarriageReturn {
+    pub(crate) fn is_allowed(self) -> bool {
+        self != Self::Reject
+    }
+}
+
 /// Parse comment
 ///
 /// ```bnf
@@ -20,7 +45,11 @@ use crate::Span;
 ///
 /// comment = comment-start-symbol *non-eol
 /// ```
-pub(crate) fn decode_comment(raw: Raw<'_>, error: &mut dyn ErrorSink) {
+pub(crate) fn decode_comment(
+    raw: Raw<'_>,
+    carriage_return: CarriageReturn,
+    error: &mut dyn ErrorSink,
+) {
     let s = raw.as_bytes();
 
     if s.first() != Some(&COMMENT_START_SYMBOL) {
@@ -32,8 +61,16 @@ pub(crate) fn decode_comment(raw: Raw<'_>, error: &mut dyn ErrorSink) {
         );
     }
 
-    for (i, b) in s.iter().copied().enumerate() {
-        if !NON_EOL.contains_token(b) {
+    let mut i = 0;
+    while i < s.len() {
+        i += printable_len(&s[i..], &[]);
+        let Some(&b) = s.get(i) else {
+            break;
+        };
+        let allowed_cr = b == b'\r' && carriage_return.is_allowed();
+        if allowed_cr {
+            error.report_error(lone_carriage_return(raw, i));
+        } else if !NON_EOL.contains_token(b) {
             error.report_error(
                 ParseError::new("invalid comment character")
                     .with_context(Span::new_unchecked(0, raw.len()))
@@ -41,6 +78,7 @@ pub(crate) fn decode_comment(raw: Raw<'_>, error: &mut dyn ErrorSink) {
                     .with_unexpected(Span::new_unchecked(i, i)),
             );
         }
+        i += 1;
     }
 }
 
@@ -62,15 +100,28 @@ pub(crate) const NON_EOL: (u8, RangeInclusive<u8>, RangeInclusive<u8>) =
 /// newline =  %x0A     ; LF
 /// newline =/ %x0D.0A  ; CRLF
 /// ```
-pub(crate) fn decode_newline(raw: Raw<'_>, error: &mut dyn ErrorSink) {
+pub(crate) fn decode_newline(
+    raw: Raw<'_>,
+    carriage_return: CarriageReturn,
+    error: &mut dyn ErrorSink,
+) {
     let s = raw.as_str();
 
-    if s == "\r" {
+    if s == "\r" && !carriage_return.is_allowed() {
         error.report_error(
             ParseError::new("carriage return must be followed by newline")
                 .with_context(Span::new_unchecked(0, raw.len()))
                 .with_expected(&[Expected::Literal("\n")])
                 .with_unexpected(Span::new_unchecked(raw.len(), raw.len())),
         );
+    } else if s == "\r" {
+        error.report_error(lone_carriage_return(raw, 0));
     }
 }
+
+fn lone_carriage_return(raw: Raw<'_>, index: usize) -> ParseError {
+    ParseError::new("carriage return without newline")
+        .with_context(Span::new_unchecked(0, raw.len()))
+        .with_unexpected(Span::new_unchecked(index, index + 1))
+        .with_severity(Severity::Warning)
+}
@@ -1,3 +1,4 @@
+use crate::SourceId;
 use crate::Span;
 
 pub trait ErrorSink {
@@ -17,16 +18,24 @@ impl ErrorSink for () {
     fn report_error(&mut self, _error: ParseError) {}
 }
 
+/// Keeps the first [`Severity::Error`], ignoring warnings and notes
 impl ErrorSink for Option<ParseError> {
     fn report_error(&mut self, error: ParseError) {
-        self.get_or_insert(error);
+        if error.severity() == Severity::Error {
+            self.get_or_insert(error);
+        }
     }
 }
 
+/// Collects every [`Severity::Error`], ignoring warnings and notes
+///
+/// To see warnings too, report into a closure, like `&mut |e| errors.push(e)`.
 #[cfg(feature = "std")]
 impl ErrorSink for Vec<ParseError> {
     fn report_error(&mut self, error: ParseError) {
-        self.push(error);
+        if error.severity() == Severity::Error {
+            self.push(error);
+        }
     }
 }
 
@@ -37,6 +46,10 @@ pub struct ParseError {
     description: ErrorStr,
     expected: Option<&'static [Expected]>,
     unexpected: Option<Span>,
+    severity: Severity,
+    fix: Option<ErrorStr>,
+    source_id: SourceId,
+    allowed_since: Option<TomlSpec>,
 }
 
 impl ParseError {
@@ -46,6 +59,10 @@ impl ParseError {
             description: description.into(),
             expected: None,
             unexpected: None,
+            severity: Severity::Error,
+            fix: None,
+            source_id: SourceId::default(),
+            allowed_since: None,
         }
     }
 
@@ -64,6 +81,31 @@ impl ParseError {
         self
     }
 
+    /// Downgrade the error to a lint, see [`ParseError::severity`]
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Suggest text to replace the [context][Self::context] with, see [`ParseError::fix`]
+    pub fn with_fix(mut self, fix: impl Into<ErrorStr>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+
+    /// Record which input the spans are in, see [`Source::with_id`][crate::Source::with_id]
+    pub fn with_source_id(mut self, source_id: SourceId) -> Self {
+        self.source_id = source_id;
+        self
+    }
+
+    /// Mark the error as only applying to versions of TOML before `spec`, see
+    /// [`ParseError::allowed_since`]
+    pub fn with_allowed_since(mut self, spec: TomlSpec) -> Self {
+        self.allowed_since = Some(spec);
+        self
+    }
+
     pub fn context(&self) -> Option<Span> {
         self.context
     }
@@ -76,6 +118,33 @@ impl ParseError {
     pub fn unexpected(&self) -> Option<Span> {
         self.unexpected
     }
+    /// How serious the error is, defaulting to [`Severity::Error`]
+    ///
+    /// Anything else was accepted, so the [`Option`] and [`Vec`] sinks drop it.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+    /// What the [context][Self::context] could be replaced with to resolve the error
+    ///
+    /// For example, `42` for the leading zero in `042`, for editors to offer as a quick fix.
+    pub fn fix(&self) -> Option<&str> {
+        self.fix.as_ref().map(|fix| fix.as_ref())
+    }
+    /// Which input the [context][Self::context] and [unexpected][Self::unexpected] spans are in
+    pub fn source_id(&self) -> SourceId {
+        self.source_id
+    }
+    /// The first version of TOML that accepts what this error is about, if any
+    ///
+    /// For example, trailing commas in inline tables are allowed from [`TomlSpec::V1_1Draft`],
+    /// so callers parsing for that version can skip the error.
+    pub fn allowed_since(&self) -> Option<TomlSpec> {
+        self.allowed_since
+    }
+    /// Whether this error still applies when parsing for `spec`
+    pub fn applies_to(&self, spec: TomlSpec) -> bool {
+        self.allowed_since.map(|since| spec < since).unwrap_or(true)
+    }
 
     pub(crate) fn rebase_spans(mut self, offset: usize) -> Self {
         if let Some(context) = self.context.as_mut() {
@@ -88,14 +157,148 @@ impl ParseError {
     }
 }
 
+/// The description followed by what was expected, like ``invalid key, expected `=`, `.` ``
+///
+/// The input isn't known to the error, so the [spans][ParseError::unexpected] are left to the
+/// caller to render.
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.description())?;
+        if let Some(expected) = self.expected() {
+            f.write_str(", expected ")?;
+            if expected.is_empty() {
+                f.write_str("nothing")?;
+            }
+            for (i, expected) in expected.iter().enumerate() {
+                if i != 0 {
+                    f.write_str(", ")?;
+                }
+                expected.fmt(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
 #[cfg(feature = "alloc")]
 type ErrorStr = alloc::borrow::Cow<'static, str>;
 #[cfg(not(feature = "alloc"))]
 type ErrorStr = &'static str;
 
+/// How serious a [`ParseError`] is
+///
+/// Only [`Severity::Error`] means the input is not valid TOML.  The others are for constructs
+/// that were accepted but are likely a mistake, like a lone carriage return with
+/// [`CarriageReturn::Normalize`][crate::decoder::CarriageReturn::Normalize], so they can be
+/// reported as lints without failing the parse.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum Severity {
+    /// The input is invalid
+    #[default]
+    Error,
+    /// The input is valid but may not mean what was intended
+    Warning,
+    /// Additional information about the input
+    Note,
+}
+
+/// A version of the TOML specification, see [`ParseError::allowed_since`]
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[non_exhaustive]
+pub enum TomlSpec {
+    /// [TOML v0.5.0](https://toml.io/en/v0.5.0), without mixed-type arrays
+    V0_5,
+    /// [TOML v1.0.0](https://toml.io/en/v1.0.0)
+    #[default]
+    V1_0,
+    /// The unreleased TOML v1.1.0, with newlines, comments, and trailing commas in inline tables
+    V1_1Draft,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 #[non_exhaustive]
 pub enum Expected {
     Literal(&'static str),
     Description(&'static str),
+    /// A value of one kind, like an integer
+    Value(ValueKind),
+    /// A value of any of these kinds, like [`ValueKind::ALL`] after `=`
+    OneOfValues(&'static [ValueKind]),
+}
+
+/// Renders literals quoted, like `` `=` ``, and everything else by its description
+impl core::fmt::Display for Expected {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Literal("\n") => f.write_str("newline"),
+            Self::Literal("`") => f.write_str("'`'"),
+            Self::Literal(literal) if literal.chars().all(|c| c.is_ascii_control()) => {
+                write!(f, "`{}`", literal.escape_debug())
+            }
+            Self::Literal(literal) => write!(f, "`{literal}`"),
+            Self::Description(description) => f.write_str(description),
+            Self::Value(kind) => f.write_str(kind.description()),
+            Self::OneOfValues(kinds) if *kinds == ValueKind::ALL => f.write_str("value"),
+            Self::OneOfValues(kinds) => {
+                for (i, kind) in kinds.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str(kind.description())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A kind of TOML value, see [`Expected::Value`]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum ValueKind {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    OffsetDateTime,
+    LocalDateTime,
+    LocalDate,
+    LocalTime,
+    Array,
+    InlineTable,
+}
+
+impl ValueKind {
+    /// Every kind of value
+    pub const ALL: &'static [ValueKind] = &[
+        Self::String,
+        Self::Integer,
+        Self::Float,
+        Self::Boolean,
+        Self::OffsetDateTime,
+        Self::LocalDateTime,
+        Self::LocalDate,
+        Self::LocalTime,
+        Self::Array,
+        Self::InlineTable,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::OffsetDateTime => "offset date-time",
+            Self::LocalDateTime => "local date-time",
+            Self::LocalDate => "local date",
+            Self::LocalTime => "local time",
+            Self::Array => "array",
+            Self::InlineTable => "inline table",
+        }
+    }
 }
@@ -0,0 +1,206 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::lexer::Token;
+use crate::lexer::TokenKind;
+use crate::parser::parse_document;
+use crate::parser::parse_key;
+use crate::parser::Event;
+use crate::parser::EventKind;
+use crate::Raw;
+use crate::Source;
+use crate::Span;
+
+/// Find the value for a dotted key `path`, stopping as soon as it is found
+///
+/// Lines are lexed and parsed one at a time so a value near the top of `input`, like
+/// `package.name` in a `Cargo.toml`, is found without processing the rest of the document.
+/// `path` is looked up relative to the root, through table headers, dotted keys, and inline
+/// tables.  For an array of tables, the first match is returned.
+///
+/// For arrays and inline tables, the [`Raw`] is the entire value and has no encoding.
+///
+/// Returns `None` if `path` is not a valid key or is not found.  The document is not
+/// validated so an invalid document may still return a value.
+///
+/// # Example
+///
+/// ```
+/// let input = r#"
+/// [package]
+/// name = "foo"
+/// version = "1.0.0"
+/// "#;
+/// let (raw, span) = toml_parse::find_key(input, "package.name").unwrap();
+/// assert_eq!(raw.as_str(), r#""foo""#);
+/// assert_eq!(&input[span.start()..span.end()], r#""foo""#);
+/// ```
+pub fn find_key<'i>(input: &'i str, path: &str) -> Option<(Raw<'i>, Span)> {
+    let target = parse_path(path)?;
+    let source = Source::new(input);
+
+    let mut table = Vec::new();
+    let mut line = Vec::new();
+    let mut events = Vec::new();
+    let mut depth = 0usize;
+    for token in source.lex() {
+        let end_of_line = match token.kind() {
+            TokenKind::LeftSquareBracket | TokenKind::LeftCurlyBracket => {
+                depth += 1;
+                false
+            }
+            TokenKind::RightSquareBracket | TokenKind::RightCurlyBracket => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+            TokenKind::Newline => depth == 0,
+            TokenKind::Eof => true,
+            _ => false,
+        };
+        line.push(token);
+        if end_of_line {
+            events.clear();
+            parse_document(&line, &mut |e| events.push(e), &mut ());
+            if let Some(found) = find_in_line(source, &events, &mut table, &target) {
+                return Some(found);
+            }
+            line.clear();
+        }
+    }
+    None
+}
+
+fn parse_path(path: &str) -> Option<Vec<Cow<'_, str>>> {
+    let source = Source::new(path);
+    let tokens: Vec<Token> = source.lex().collect();
+    let mut events = Vec::new();
+    let mut errors = None;
+    parse_key(&tokens, &mut |e| events.push(e), &mut errors);
+    if errors.is_some() {
+        return None;
+    }
+    Some(decode_keys(source, &events))
+}
+
+pub(crate) fn decode_keys<'i>(source: Source<'i>, events: &[Event]) -> Vec<Cow<'i, str>> {
+    events
+        .iter()
+        .filter(|e| e.kind() == EventKind::SimpleKey)
+        .map(|e| decode_key(source, e))
+        .collect()
+}
+
+pub(crate) fn decode_key<'i>(source: Source<'i>, event: &Event) -> Cow<'i, str> {
+    let mut key = Cow::Borrowed("");
+    if let Some(raw) = source.get(event) {
+        raw.decode_key(&mut key, &mut ());
+    }
+    key
+}
+
+/// Look for `target` within a single logical line
+fn find_in_line<'i>(
+    source: Source<'i>,
+    events: &[Event],
+    table: &mut Vec<Cow<'i, str>>,
+    target: &[Cow<'_, str>],
+) -> Option<(Raw<'i>, Span)> {
+    let first = events.iter().position(|e| !is_trivia(e.kind()))?;
+    match events[first].kind() {
+        EventKind::StdTableOpen | EventKind::ArrayTableOpen => {
+            let close = events[first..]
+                .iter()
+                .position(|e| {
+                    matches!(
+                        e.kind(),
+                        EventKind::StdTableClose | EventKind::ArrayTableClose
+                    )
+                })
+                .map(|i| first + i)
+                .unwrap_or(events.len());
+            *table = decode_keys(source, &events[first..close]);
+            None
+        }
+        _ => {
+            let mut base = table.clone();
+            find_in_key_values(source, &events[first..], &mut base, target)
+        }
+    }
+}
+
+/// Look for `target` within `key = value` pairs, like a line or the inside of an inline table
+fn find_in_key_values<'i>(
+    source: Source<'i>,
+    events: &[Event],
+    base: &mut Vec<Cow<'i, str>>,
+    target: &[Cow<'_, str>],
+) -> Option<(Raw<'i>, Span)> {
+    let base_len = base.len();
+    let mut i = 0;
+    while i < events.len() {
+        base.truncate(base_len);
+        while i < events.len() && events[i].kind() != EventKind::KeyValSep {
+            if events[i].kind() == EventKind::SimpleKey {
+                base.push(decode_key(source, &events[i]));
+            }
+            i += 1;
+        }
+        i += 1;
+        while i < events.len() && is_trivia(events[i].kind()) {
+            i += 1;
+        }
+        let start = events.get(i)?;
+        let end = match start.kind() {
+            EventKind::ArrayOpen | EventKind::InlineTableOpen => closing(events, i),
+            _ => i,
+        };
+
+        let is_match =
+            base.len() <= target.len() && base.iter().zip(target.iter()).all(|(b, t)| b == t);
+        if is_match {
+            if base.len() == target.len() {
+                let span = Span::new_unchecked(
+                    start.span().start(),
+                    events[end.min(events.len() - 1)].span().end(),
+                );
+                let raw = if start.kind() == EventKind::Scalar {
+                    source.get(start)?
+                } else {
+                    source.get(span)?
+                };
+                return Some((raw, span));
+            } else if start.kind() == EventKind::InlineTableOpen {
+                let inner = &events[(i + 1)..end.min(events.len())];
+                return find_in_key_values(source, inner, base, target);
+            }
+        }
+
+        i = end + 1;
+    }
+    None
+}
+
+/// The index of the event closing the array or inline table opened at `open`
+pub(crate) fn closing(events: &[Event], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, event) in events.iter().enumerate().skip(open) {
+        match event.kind() {
+            EventKind::ArrayOpen | EventKind::InlineTableOpen => depth += 1,
+            EventKind::ArrayClose | EventKind::InlineTableClose => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    events.len()
+}
+
+pub(crate) fn is_trivia(kind: EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Whitespace | EventKind::Comment | EventKind::Newline
+    )
+}
@@ -0,0 +1,166 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::lexer::TokenKind;
+use crate::Source;
+use crate::Span;
+
+/// The leading whitespace of a line, see [`indentation`]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LineIndent {
+    line: usize,
+    span: Span,
+    tabs: usize,
+    spaces: usize,
+}
+
+impl LineIndent {
+    fn new(line: usize, span: Span, raw: &str) -> Self {
+        Self {
+            line,
+            span,
+            tabs: raw.matches('\t').count(),
+            spaces: raw.matches(' ').count(),
+        }
+    }
+
+    /// Zero-based line number
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The location of the indentation, empty when the line is not indented
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Number of tabs in the indentation
+    pub fn tabs(&self) -> usize {
+        self.tabs
+    }
+
+    /// Number of spaces in the indentation
+    pub fn spaces(&self) -> usize {
+        self.spaces
+    }
+
+    /// Whether the indentation has both tabs and spaces
+    pub fn is_mixed(&self) -> bool {
+        self.tabs != 0 && self.spaces != 0
+    }
+
+    fn width(&self, input: &str, tab_width: usize) -> usize {
+        input[self.span.start()..self.span.end()]
+            .chars()
+            .fold(0, |width, c| match c {
+                '\t' => (width / tab_width + 1) * tab_width,
+                _ => width + 1,
+            })
+    }
+}
+
+/// What to indent lines with, see [`reindent`]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum IndentStyle {
+    /// Tabs, with spaces for any remainder narrower than a tab
+    Tabs,
+    Spaces,
+}
+
+/// Report the indentation of each line in `input`, without parsing it
+///
+/// Lines that start within a multi-line string are skipped as their leading whitespace is part
+/// of the string.
+///
+/// # Example
+///
+/// ```
+/// let input = "[a]\n\tb = 1\n  \tc = '''\n    d'''\n";
+/// let mixed = toml_parse::indentation(input)
+///     .into_iter()
+///     .filter(|l| l.is_mixed())
+///     .map(|l| l.line())
+///     .collect::<Vec<_>>();
+/// assert_eq!(mixed, [2]);
+/// ```
+pub fn indentation(input: &str) -> Vec<LineIndent> {
+    let source = Source::new(input);
+    let mut lines = Vec::new();
+    let mut line = 0;
+    let mut line_start = true;
+    for token in source.lex() {
+        let span = token.span();
+        let raw = &input[span.start()..span.end()];
+        match token.kind() {
+            TokenKind::Newline => {
+                if line_start {
+                    lines.push(LineIndent::new(line, span.before(), ""));
+                }
+                line += 1;
+                line_start = true;
+                continue;
+            }
+            TokenKind::Whitespace if line_start => {
+                lines.push(LineIndent::new(line, span, raw));
+            }
+            // An empty last line is not reported
+            TokenKind::Eof => {}
+            _ if line_start => {
+                lines.push(LineIndent::new(line, span.before(), ""));
+            }
+            _ => {}
+        }
+        line += raw.matches('\n').count();
+        line_start = false;
+    }
+    lines
+}
+
+/// Rewrite the indentation of each line in `input` to `style`, without parsing it
+///
+/// Tabs advance to the next multiple of `tab_width` columns.  Multi-line strings are left
+/// alone, see [`indentation`].
+///
+/// # Example
+///
+/// ```
+/// # use toml_parse::IndentStyle;
+/// let input = "[a]\n  \tb = 1\n    c = '''\n    d'''\n";
+/// let output = toml_parse::reindent(input, IndentStyle::Tabs, 4);
+/// assert_eq!(output, "[a]\n\tb = 1\n\tc = '''\n    d'''\n");
+/// ```
+pub fn reindent(input: &str, style: IndentStyle, tab_width: usize) -> Cow<'_, str> {
+    let tab_width = tab_width.max(1);
+    let mut output = String::new();
+    let mut copied = 0;
+    for line in indentation(input) {
+        let width = line.width(input, tab_width);
+        let mut indent = String::new();
+        let spaces = match style {
+            IndentStyle::Tabs => {
+                for _ in 0..width / tab_width {
+                    indent.push('\t');
+                }
+                width % tab_width
+            }
+            IndentStyle::Spaces => width,
+        };
+        for _ in 0..spaces {
+            indent.push(' ');
+        }
+
+        let span = line.span();
+        if input[span.start()..span.end()] != indent {
+            output.push_str(&input[copied..span.start()]);
+            output.push_str(&indent);
+            copied = span.end();
+        }
+    }
+    if copied == 0 {
+        Cow::Borrowed(input)
+    } else {
+        output.push_str(&input[copied..]);
+        Cow::Owned(output)
+    }
+}
@@ -0,0 +1,254 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::decoder::ScalarKind;
+use crate::find::closing;
+use crate::find::decode_key;
+use crate::find::decode_keys;
+use crate::find::is_trivia;
+use crate::parser::parse_document;
+use crate::parser::Event;
+use crate::parser::EventKind;
+use crate::Source;
+use crate::Span;
+
+/// What is defined at an [`IndexEntry`]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum IndexKind {
+    /// A string, boolean, date-time, float, or integer
+    Scalar(ScalarKind),
+    Array,
+    InlineTable,
+    /// A `[table]` header
+    Table,
+    /// An `[[array]]` header
+    ArrayOfTables,
+}
+
+/// A step in an [`IndexEntry::path`]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum IndexSegment<'i> {
+    /// A decoded key
+    Key(Cow<'i, str>),
+    /// The position within an `[[array]]` of tables
+    Index(usize),
+}
+
+impl core::fmt::Display for IndexSegment<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Key(key) => key.fmt(f),
+            Self::Index(index) => index.fmt(f),
+        }
+    }
+}
+
+/// A key defined in a document, see [`index_document`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IndexEntry<'i> {
+    path: Vec<IndexSegment<'i>>,
+    span: Span,
+    kind: IndexKind,
+}
+
+impl<'i> IndexEntry<'i> {
+    /// The full path from the root, with keys decoded and with the position within each
+    /// `[[array]]` of tables along the way
+    pub fn path(&self) -> &[IndexSegment<'i>] {
+        &self.path
+    }
+
+    /// The location of the value, or of the header for tables
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// What is defined, without decoding it
+    pub fn kind(&self) -> IndexKind {
+        self.kind
+    }
+}
+
+/// List what is defined in `input` and where, without decoding values
+///
+/// Each key/value pair, including those within inline tables, and each table header is listed
+/// in document order.  Strings are not unescaped and numbers and date-times are not converted,
+/// only classified.  Array elements are not listed, but paths include the position of tables
+/// within an `[[array]]` of tables.
+///
+/// The document is not validated, so an invalid document may still produce entries.
+///
+/// # Example
+///
+/// ```
+/// use toml_parse::decoder::ScalarKind;
+/// use toml_parse::{IndexKind, IndexSegment};
+///
+/// let input = r#"
+/// [package]
+/// name = "foo"
+/// metadata = { docs = true }
+///
+/// [[bin]]
+/// name = "foo-cli"
+/// "#;
+/// let index = toml_parse::index_document(input);
+/// let summary = index
+///     .iter()
+///     .map(|e| {
+///         let path = e.path().iter().map(|s| s.to_string()).collect::<Vec<_>>();
+///         (path.join("."), e.kind())
+///     })
+///     .collect::<Vec<_>>();
+/// assert_eq!(summary.len(), 6);
+/// assert_eq!(summary[0], ("package".to_owned(), IndexKind::Table));
+/// assert_eq!(summary[2], ("package.metadata".to_owned(), IndexKind::InlineTable));
+/// assert_eq!(summary[5], ("bin.0.name".to_owned(), IndexKind::Scalar(ScalarKind::String)));
+/// assert_eq!(index[5].path()[1], IndexSegment::Index(0));
+/// assert_eq!(&input[index[1].span().start()..index[1].span().end()], r#""foo""#);
+/// ```
+pub fn index_document(input: &str) -> Vec<IndexEntry<'_>> {
+    let source = Source::new(input);
+    let tokens = source.lex().into_vec();
+    let mut events = Vec::with_capacity(tokens.len());
+    parse_document(&tokens, &mut |e| events.push(e), &mut ());
+
+    let mut entries = Vec::new();
+    let mut table = Vec::new();
+    // How many of each `[[array]]` of tables have been seen, by path
+    let mut arrays: Vec<(Vec<IndexSegment<'_>>, usize)> = Vec::new();
+    let mut i = 0;
+    while i < events.len() {
+        match events[i].kind() {
+            EventKind::StdTableOpen | EventKind::ArrayTableOpen => {
+                let kind = if events[i].kind() == EventKind::StdTableOpen {
+                    IndexKind::Table
+                } else {
+                    IndexKind::ArrayOfTables
+                };
+                let close = events[i..]
+                    .iter()
+                    .position(|e| {
+                        matches!(
+                            e.kind(),
+                            EventKind::StdTableClose | EventKind::ArrayTableClose
+                        )
+                    })
+                    .map(|offset| i + offset)
+                    .unwrap_or(events.len() - 1);
+                let keys = decode_keys(source, &events[i..close]);
+                table = array_path(keys, kind == IndexKind::ArrayOfTables, &mut arrays);
+                entries.push(IndexEntry {
+                    path: table.clone(),
+                    span: events[i].span().append(events[close].span()),
+                    kind,
+                });
+                i = close + 1;
+            }
+            EventKind::SimpleKey => {
+                i = index_key_value(source, &events, i, &table, &mut entries);
+            }
+            _ => i += 1,
+        }
+    }
+    entries
+}
+
+/// Turn a header's keys into a path, with the position within the `[[array]]` of tables each
+/// prefix refers to, counting the header itself if `is_array`
+fn array_path<'i>(
+    keys: Vec<Cow<'i, str>>,
+    is_array: bool,
+    arrays: &mut Vec<(Vec<IndexSegment<'i>>, usize)>,
+) -> Vec<IndexSegment<'i>> {
+    let len = keys.len();
+    let mut path = Vec::with_capacity(len);
+    for (i, key) in keys.into_iter().enumerate() {
+        path.push(IndexSegment::Key(key));
+        let count = arrays.iter_mut().find(|(p, _)| *p == path);
+        if i + 1 == len && is_array {
+            let index = match count {
+                Some((_, count)) => {
+                    *count += 1;
+                    *count - 1
+                }
+                None => {
+                    arrays.push((path.clone(), 1));
+                    0
+                }
+            };
+            path.push(IndexSegment::Index(index));
+        } else if let Some((_, count)) = count {
+            let index = *count - 1;
+            path.push(IndexSegment::Index(index));
+        }
+    }
+    path
+}
+
+/// Index the `key = value` pair starting at `start`, returning the index after it
+fn index_key_value<'i>(
+    source: Source<'i>,
+    events: &[Event],
+    start: usize,
+    base: &[IndexSegment<'i>],
+    entries: &mut Vec<IndexEntry<'i>>,
+) -> usize {
+    let mut path = base.to_vec();
+    let mut i = start;
+    while i < events.len() && events[i].kind() != EventKind::KeyValSep {
+        if events[i].kind() == EventKind::SimpleKey {
+            path.push(IndexSegment::Key(decode_key(source, &events[i])));
+        }
+        i += 1;
+    }
+    i += 1;
+    while i < events.len() && is_trivia(events[i].kind()) {
+        i += 1;
+    }
+    let Some(value) = events.get(i) else {
+        return i;
+    };
+
+    let (kind, end) = match value.kind() {
+        EventKind::ArrayOpen => (IndexKind::Array, closing(events, i)),
+        EventKind::InlineTableOpen => (IndexKind::InlineTable, closing(events, i)),
+        EventKind::Scalar => match scalar_kind(source, value) {
+            Some(kind) => (IndexKind::Scalar(kind), i),
+            None => return i,
+        },
+        _ => return i,
+    };
+    let end_span = events[end.min(events.len() - 1)].span();
+    let span = Span::new_unchecked(value.span().start(), end_span.end());
+    if kind == IndexKind::InlineTable {
+        let end = end.min(events.len());
+        entries.push(IndexEntry {
+            path: path.clone(),
+            span,
+            kind,
+        });
+        let mut j = i + 1;
+        while j < end {
+            if events[j].kind() == EventKind::SimpleKey {
+                j = index_key_value(source, &events[..end], j, &path, entries);
+            } else {
+                j += 1;
+            }
+        }
+    } else {
+        entries.push(IndexEntry { path, span, kind });
+    }
+    end + 1
+}
+
+fn scalar_kind(source: Source<'_>, event: &Event) -> Option<ScalarKind> {
+    let raw = source.get(event)?;
+    if raw.as_str().starts_with(['"', '\'']) {
+        // Skip unescaping
+        Some(ScalarKind::String)
+    } else {
+        Some(raw.decode_scalar(&mut (), &mut ()))
+    }
+}
@@ -16,6 +16,7 @@ use winnow::stream::FindSlice as _;
 use winnow::stream::Location;
 use winnow::stream::Stream as _;
 
+use crate::decoder::CarriageReturn;
 use crate::Span;
 
 pub use token::Token;
@@ -27,6 +28,10 @@ pub use token::TokenKind;
 pub struct Lexer<'i> {
     stream: Stream<'i>,
     eof: bool,
+    carriage_return: CarriageReturn,
+    max_token_len: usize,
+    /// Where lexing stopped after a token over `max_token_len`
+    stopped_at: Option<usize>,
 }
 
 impl<'i> Lexer<'i> {
@@ -41,7 +46,33 @@ impl<'i> Lexer<'i> {
             #[cfg(not(feature = "unsafe"))]
             stream.next_slice(offset);
         }
-        Lexer { stream, eof: false }
+        Lexer {
+            stream,
+            eof: false,
+            carriage_return: CarriageReturn::Reject,
+            max_token_len: usize::MAX,
+            stopped_at: None,
+        }
+    }
+
+    /// Choose how to lex a carriage return that is not part of a `\r\n` line ending
+    ///
+    /// With [`CarriageReturn::Preserve`], it is part of a comment it appears in.  Otherwise, it
+    /// is always a [`TokenKind::Newline`].
+    pub fn with_carriage_return(mut self, carriage_return: CarriageReturn) -> Self {
+        self.carriage_return = carriage_return;
+        self
+    }
+
+    /// Stop after a string or [`TokenKind::Atom`] longer than `max_token_len` bytes
+    ///
+    /// The long token is still returned, followed by [`TokenKind::Eof`] at its end, so the rest
+    /// of the input is not lexed nor kept as tokens.  This is for pairing with a
+    /// [`SizeLimit`][crate::parser::SizeLimit] of the same length, which then reports the long
+    /// token, when a document with a value that is too large is rejected anyway.
+    pub fn with_max_token_len(mut self, max_token_len: usize) -> Self {
+        self.max_token_len = max_token_len;
+        self
     }
 
     #[cfg(feature = "alloc")]
@@ -61,6 +92,14 @@ impl Iterator for Lexer<'_> {
     type Item = Token;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(offset) = self.stopped_at {
+            if self.eof {
+                return None;
+            }
+            self.eof = true;
+            let span = Span::new_unchecked(offset, offset);
+            return Some(Token::new(TokenKind::Eof, span));
+        }
         let Some(peek_byte) = self.stream.as_bstr().first() else {
             if self.eof {
                 return None;
@@ -71,7 +110,19 @@ impl Iterator for Lexer<'_> {
                 return Some(Token::new(TokenKind::Eof, span));
             }
         };
-        Some(process_token(*peek_byte, &mut self.stream))
+        let token = process_token(*peek_byte, &mut self.stream, self.carriage_return);
+        let is_value = matches!(
+            token.kind(),
+            TokenKind::Atom
+                | TokenKind::LiteralString
+                | TokenKind::BasicString
+                | TokenKind::MlLiteralString
+                | TokenKind::MlBasicString
+        );
+        if is_value && self.max_token_len < token.span().len() {
+            self.stopped_at = Some(token.span().end());
+        }
+        Some(token)
     }
 }
 
@@ -79,7 +130,7 @@ const BOM: &[u8] = b"\xEF\xBB\xBF";
 
 pub(crate) type Stream<'i> = winnow::stream::LocatingSlice<&'i str>;
 
-fn process_token(peek_byte: u8, stream: &mut Stream<'_>) -> Token {
+fn process_token(peek_byte: u8, stream: &mut Stream<'_>, carriage_return: CarriageReturn) -> Token {
     let token = match peek_byte {
         b'.' => lex_ascii_char(stream, TokenKind::Dot),
         b'=' => lex_ascii_char(stream, TokenKind::Equals),
@@ -90,7 +141,7 @@ fn process_token(peek_byte: u8, stream: &mut Stream<'_>) -> Token {
         b'}' => lex_ascii_char(stream, TokenKind::RightCurlyBracket),
         b' ' => lex_whitespace(stream),
         b'\t' => lex_whitespace(stream),
-        b'#' => lex_comment(stream),
+        b'#' => lex_comment(stream, carriage_return),
         b'\r' => lex_crlf(stream),
         b'\n' => lex_ascii_char(stream, TokenKind::Newline),
         b'\'' => {
@@ -192,14 +243,28 @@ pub(crate) const WSCHAR: (u8, u8) = (b' ', b'\t');
 ///
 /// - `stream` must be UTF-8
 /// - `stream[0] == b'#'`
-fn lex_comment(stream: &mut Stream<'_>) -> Token {
+fn lex_comment(stream: &mut Stream<'_>, carriage_return: CarriageReturn) -> Token {
     let start = stream.current_token_start();
 
-    let offset = stream
-        .as_bytes()
-        .find_slice((b'\r', b'\n'))
-        .map(|s| s.start)
-        .unwrap_or_else(|| stream.eof_offset());
+    let offset = if carriage_return == CarriageReturn::Preserve {
+        let bytes = stream.as_bytes();
+        let offset = bytes
+            .find_slice(b'\n')
+            .map(|s| s.start)
+            .unwrap_or_else(|| stream.eof_offset());
+        // Leave a `\r\n` to be lexed as a newline
+        if offset != 0 && offset < bytes.len() && bytes[offset - 1] == b'\r' {
+            offset - 1
+        } else {
+            offset
+        }
+    } else {
+        stream
+            .as_bytes()
+            .find_slice((b'\r', b'\n'))
+            .map(|s| s.start)
+            .unwrap_or_else(|| stream.eof_offset())
+    };
     #[cfg(feature = "unsafe")] // SAFETY: newlines ensure `offset` is along UTF-8 boundary
     unsafe {
         stream.next_slice_unchecked(offset)
@@ -413,71 +478,48 @@ fn lex_basic_string(stream: &mut Stream<'_>) -> Token {
     #[cfg(not(feature = "unsafe"))]
     stream.next_slice(offset);
 
+    let offset = basic_string_body_len(stream.as_bstr());
+    #[cfg(feature = "unsafe")]
+    // SAFETY: `QUOTATION_MARK` / `ESCAPE` / newline ensure `offset` is along UTF-8 boundary
+    unsafe {
+        stream.next_slice_unchecked(offset)
+    };
+    #[cfg(not(feature = "unsafe"))]
+    stream.next_slice(offset);
+
+    let end = stream.previous_token_end();
+    let span = Span::new_unchecked(start, end);
+    Token::new(TokenKind::BasicString, span)
+}
+
+/// The length of a basic string after its opening quote, up to and including the closing quote
+///
+/// A newline ends the string early, for error recovery.
+///
+/// Escapes are stepped over in the same pass as the search for the closing quote, rather than
+/// searching again after each one, so strings dense with escapes lex at the same rate as any
+/// other.
+fn basic_string_body_len(bytes: &[u8]) -> usize {
+    let mut offset = 0;
     loop {
-        // newline is present for error recovery
-        match stream.as_bstr().find_slice((QUOTATION_MARK, ESCAPE, b'\n')) {
-            Some(span) => {
-                let found = stream.as_bstr()[span.start];
-                if found == QUOTATION_MARK {
-                    let offset = span.end;
-                    #[cfg(feature = "unsafe")]
-                    // SAFETY: `QUOTATION_MARK` ensure `offset` is along UTF-8 boundary
-                    unsafe {
-                        stream.next_slice_unchecked(offset)
-                    };
-                    #[cfg(not(feature = "unsafe"))]
-                    stream.next_slice(offset);
-                    break;
-                } else if found == ESCAPE {
-                    let offset = span.end;
-                    #[cfg(feature = "unsafe")]
-                    // SAFETY: `ESCAPE` / newline ensure `offset` is along UTF-8 boundary
-                    unsafe {
-                        stream.next_slice_unchecked(offset)
-                    };
-                    #[cfg(not(feature = "unsafe"))]
-                    stream.next_slice(offset);
-
-                    let peek = stream.as_bstr().peek_token();
-                    match peek {
-                        Some(ESCAPE) | Some(QUOTATION_MARK) => {
-                            let offset = 1; // ESCAPE / QUOTATION_MARK
-                            #[cfg(feature = "unsafe")]
-                            #[cfg(feature = "unsafe")]
-                            // SAFETY: `ESCAPE` / newline ensure `offset` is along UTF-8 boundary
-                            unsafe {
-                                stream.next_slice_unchecked(offset)
-                            };
-                            #[cfg(not(feature = "unsafe"))]
-                            stream.next_slice(offset);
-                        }
-                        _ => {}
-                    }
-                    continue;
-                } else if found == b'\n' {
-                    let offset = span.start;
-                    #[cfg(feature = "unsafe")]
-                    // SAFETY: newline ensure `offset` is along UTF-8 boundary
-                    unsafe {
-                        stream.next_slice_unchecked(offset)
-                    };
-                    #[cfg(not(feature = "unsafe"))]
-                    stream.next_slice(offset);
-                    break;
-                } else {
-                    unreachable!("found `{found}`");
+        match bytes.get(offset) {
+            Some(&QUOTATION_MARK) => return offset + 1,
+            Some(&ESCAPE) => {
+                offset += 1;
+                if let Some(&ESCAPE) | Some(&QUOTATION_MARK) = bytes.get(offset) {
+                    offset += 1;
                 }
             }
-            None => {
-                stream.finish();
-                break;
+            Some(b'\n') | None => return offset,
+            Some(_) => {
+                let rest = &bytes[offset..];
+                offset += rest
+                    .find_slice((QUOTATION_MARK, ESCAPE, b'\n'))
+                    .map(|span| span.start)
+                    .unwrap_or(rest.len());
             }
         }
     }
-
-    let end = stream.previous_token_end();
-    let span = Span::new_unchecked(start, end);
-    Token::new(TokenKind::BasicString, span)
 }
 
 /// `quotation-mark = %x22            ; "`
@@ -518,56 +560,15 @@ fn lex_ml_basic_string(stream: &mut Stream<'_>) -> Token {
     #[cfg(not(feature = "unsafe"))]
     stream.next_slice(offset);
 
-    loop {
-        // newline is present for error recovery
-        match stream.as_bstr().find_slice((ML_BASIC_STRING_DELIM, "\\")) {
-            Some(span) => {
-                let found = stream.as_bstr()[span.start];
-                if found == QUOTATION_MARK {
-                    let offset = span.end;
-                    #[cfg(feature = "unsafe")]
-                    // SAFETY: `QUOTATION_MARK` ensure `offset` is along UTF-8 boundary
-                    unsafe {
-                        stream.next_slice_unchecked(offset)
-                    };
-                    #[cfg(not(feature = "unsafe"))]
-                    stream.next_slice(offset);
-                    break;
-                } else if found == ESCAPE {
-                    let offset = span.end;
-                    #[cfg(feature = "unsafe")]
-                    // SAFETY: `ESCAPE` ensure `offset` is along UTF-8 boundary
-                    unsafe {
-                        stream.next_slice_unchecked(offset)
-                    };
-                    #[cfg(not(feature = "unsafe"))]
-                    stream.next_slice(offset);
-
-                    let peek = stream.as_bstr().peek_token();
-                    match peek {
-                        Some(ESCAPE) | Some(QUOTATION_MARK) => {
-                            let offset = 1; // ESCAPE / QUOTATION_MARK
-                            #[cfg(feature = "unsafe")]
-                            // SAFETY: `QUOTATION_MARK`/`ESCAPE` ensure `offset` is along UTF-8 boundary
-                            unsafe {
-                                stream.next_slice_unchecked(offset)
-                            };
-                            #[cfg(not(feature = "unsafe"))]
-                            stream.next_slice(offset);
-                        }
-                        _ => {}
-                    }
-                    continue;
-                } else {
-                    unreachable!("found `{found}`");
-                }
-            }
-            None => {
-                stream.finish();
-                break;
-            }
-        }
-    }
+    let offset = ml_basic_string_body_len(stream.as_bstr());
+    #[cfg(feature = "unsafe")]
+    // SAFETY: `QUOTATION_MARK` / `ESCAPE` ensure `offset` is along UTF-8 boundary
+    unsafe {
+        stream.next_slice_unchecked(offset)
+    };
+    #[cfg(not(feature = "unsafe"))]
+    stream.next_slice(offset);
+
     if stream.as_bstr().peek_token() == Some(QUOTATION_MARK) {
         let offset = 1;
         #[cfg(feature = "unsafe")]
@@ -594,6 +595,39 @@ fn lex_ml_basic_string(stream: &mut Stream<'_>) -> Token {
     Token::new(TokenKind::MlBasicString, span)
 }
 
+/// The length of a multi-line basic string after its opening delimiter, up to and including the
+/// closing delimiter
+///
+/// Like [`basic_string_body_len`], this is a single pass.  Quotes after the closing delimiter
+/// are left to the caller.
+fn ml_basic_string_body_len(bytes: &[u8]) -> usize {
+    let mut offset = 0;
+    loop {
+        match bytes.get(offset) {
+            Some(&QUOTATION_MARK) => {
+                if bytes[offset..].starts_with(ML_BASIC_STRING_DELIM.as_bytes()) {
+                    return offset + ML_BASIC_STRING_DELIM.len();
+                }
+                offset += 1;
+            }
+            Some(&ESCAPE) => {
+                offset += 1;
+                if let Some(&ESCAPE) | Some(&QUOTATION_MARK) = bytes.get(offset) {
+                    offset += 1;
+                }
+            }
+            Some(_) => {
+                let rest = &bytes[offset..];
+                offset += rest
+                    .find_slice((QUOTATION_MARK, ESCAPE))
+                    .map(|span| span.start)
+                    .unwrap_or(rest.len());
+            }
+            None => return offset,
+        }
+    }
+}
+
 /// `ml-basic-string-delim = 3quotation-mark`
 pub(crate) const ML_BASIC_STRING_DELIM: &str = "\"\"\"";
 
@@ -181,7 +181,53 @@ Token {
     for (stream, expected_tokens, expected_stream) in cases {
         dbg!(stream);
         let mut stream = Stream::new(stream);
-        let actual_tokens = lex_comment(&mut stream);
+        let actual_tokens = lex_comment(&mut stream, CarriageReturn::Reject);
+        assert_data_eq!(actual_tokens.to_debug(), expected_tokens.raw());
+        let stream = *stream;
+        assert_data_eq!(stream, expected_stream.raw());
+    }
+}
+
+#[test]
+fn test_lex_comment_preserve_cr() {
+    let cases = [
+        (
+            "# content \rcontinue\ntrailing",
+            str![[r#"
+Token {
+    kind: Comment,
+    span: 0..19,
+}
+
+"#]]
+            .raw(),
+            str![[r#"
+
+trailing
+"#]]
+            .raw(),
+        ),
+        (
+            "# content \r\ntrailing",
+            str![[r#"
+Token {
+    kind: Comment,
+    span: 0..10,
+}
+
+"#]]
+            .raw(),
+            str![[r#"
+
+trailing
+"#]]
+            .raw(),
+        ),
+    ];
+    for (stream, expected_tokens, expected_stream) in cases {
+        dbg!(stream);
+        let mut stream = Stream::new(stream);
+        let actual_tokens = lex_comment(&mut stream, CarriageReturn::Preserve);
         assert_data_eq!(actual_tokens.to_debug(), expected_tokens.raw());
         let stream = *stream;
         assert_data_eq!(stream, expected_stream.raw());
@@ -533,6 +579,30 @@ Token {
     span: 0..18,
 }
 
+"#]]
+            .raw(),
+            str![].raw(),
+        ),
+        (
+            r#""\"\\"trailing"#,
+            str![[r#"
+Token {
+    kind: BasicString,
+    span: 0..6,
+}
+
+"#]]
+            .raw(),
+            str!["trailing"].raw(),
+        ),
+        (
+            r#""content\"#,
+            str![[r#"
+Token {
+    kind: BasicString,
+    span: 0..9,
+}
+
 "#]]
             .raw(),
             str![].raw(),
@@ -1698,3 +1768,45 @@ fn bad_comment() {
         .raw(),
     );
 }
+
+#[test]
+fn escape_heavy_strings() {
+    // Strings of little but escapes, including ones left open at the end of the input
+    let len = 100_000;
+    let cases = [
+        (
+            format!(r#""{}""#, "\\\\".repeat(len)),
+            TokenKind::BasicString,
+        ),
+        (
+            format!(r#""{}""#, "\\\"".repeat(len)),
+            TokenKind::BasicString,
+        ),
+        (format!(r#""{}"#, "\\".repeat(len)), TokenKind::BasicString),
+        (
+            format!(r#""""{}""""#, "\\\\".repeat(len)),
+            TokenKind::MlBasicString,
+        ),
+        (
+            format!(r#""""{}""""#, "\\\"\"".repeat(len)),
+            TokenKind::MlBasicString,
+        ),
+        (
+            format!(r#""""{}"#, "\\".repeat(len)),
+            TokenKind::MlBasicString,
+        ),
+    ];
+    for (input, kind) in cases {
+        let tokens = crate::Source::new(&input).lex().into_vec();
+        assert_eq!(
+            tokens,
+            [
+                Token::new(kind, Span::new_unchecked(0, input.len())),
+                Token::new(
+                    TokenKind::Eof,
+                    Span::new_unchecked(input.len(), input.len())
+                ),
+            ]
+        );
+    }
+}
@@ -27,20 +27,58 @@ extern crate alloc;
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "alloc")]
+mod comments;
 #[cfg(feature = "debug")]
 pub(crate) mod debug;
 mod error;
+#[cfg(feature = "alloc")]
+mod find;
+#[cfg(feature = "alloc")]
+mod indent;
+#[cfg(feature = "alloc")]
+mod index;
 mod source;
 
+#[cfg(feature = "ast")]
+pub mod ast;
+#[cfg(feature = "alloc")]
+pub mod combinator;
 pub mod decoder;
 pub mod lexer;
 pub mod parser;
 
+#[cfg(feature = "alloc")]
+pub use comments::extract_comments;
+#[cfg(feature = "alloc")]
+pub use comments::strip_comments;
 pub use error::ErrorSink;
 pub use error::Expected;
 pub use error::ParseError;
+pub use error::Severity;
+pub use error::TomlSpec;
+pub use error::ValueKind;
+#[cfg(feature = "alloc")]
+pub use find::find_key;
+#[cfg(feature = "alloc")]
+pub use indent::indentation;
+#[cfg(feature = "alloc")]
+pub use indent::reindent;
+#[cfg(feature = "alloc")]
+pub use indent::IndentStyle;
+#[cfg(feature = "alloc")]
+pub use indent::LineIndent;
+#[cfg(feature = "alloc")]
+pub use index::index_document;
+#[cfg(feature = "alloc")]
+pub use index::IndexEntry;
+#[cfg(feature = "alloc")]
+pub use index::IndexKind;
+#[cfg(feature = "alloc")]
+pub use index::IndexSegment;
 pub use source::Raw;
 pub use source::Source;
+pub use source::SourceId;
 pub use source::SourceIndex;
 pub use source::Span;
 
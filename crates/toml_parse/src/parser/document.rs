@@ -13,6 +13,8 @@ use crate::lexer::TokenKind;
 use crate::ErrorSink;
 use crate::Expected;
 use crate::ParseError;
+use crate::TomlSpec;
+use crate::ValueKind;
 
 /// Parse lexed tokens into [`Event`][super::Event]s
 pub fn parse_document(
@@ -30,7 +32,9 @@ pub fn parse_document(
     #[cfg(feature = "debug")]
     let error = &mut error;
     document(&mut tokens, receiver, error);
-    eof(&mut tokens, receiver, error);
+    if !receiver.is_aborted() {
+        eof(&mut tokens, receiver, error);
+    }
 }
 
 /// Parse lexed tokens into [`Event`][super::Event]s
@@ -82,6 +86,36 @@ pub fn parse_value(tokens: &[Token], receiver: &mut dyn EventReceiver, error: &m
     eof(&mut tokens, receiver, error);
 }
 
+/// Parse a value at the start of `tokens`, returning how many tokens it took
+///
+/// Unlike [`parse_value`], anything after the value is left for the caller.
+#[cfg(feature = "alloc")]
+pub(crate) fn parse_value_prefix(
+    tokens: &[Token],
+    receiver: &mut dyn EventReceiver,
+    error: &mut dyn ErrorSink,
+) -> usize {
+    let mut tokens = TokenSlice::new(tokens);
+    let start = tokens.checkpoint();
+    value(&mut tokens, receiver, error);
+    tokens.offset_from(&start)
+}
+
+/// Parse a key at the start of `tokens`, returning how many tokens it took
+///
+/// Unlike [`parse_key`], anything after the key is left for the caller.
+#[cfg(feature = "alloc")]
+pub(crate) fn parse_key_prefix(
+    tokens: &[Token],
+    receiver: &mut dyn EventReceiver,
+    error: &mut dyn ErrorSink,
+) -> usize {
+    let mut tokens = TokenSlice::new(tokens);
+    let start = tokens.checkpoint();
+    key(&mut tokens, "invalid key", receiver, error);
+    tokens.offset_from(&start)
+}
+
 type Stream<'i> = TokenSlice<'i, Token>;
 
 /// Parse a TOML Document
@@ -143,6 +177,9 @@ type Stream<'i> = TokenSlice<'i, Token>;
 /// ```
 fn document(tokens: &mut Stream<'_>, receiver: &mut dyn EventReceiver, error: &mut dyn ErrorSink) {
     while let Some(current_token) = tokens.next_token() {
+        if receiver.is_aborted() {
+            break;
+        }
         match current_token.kind() {
             TokenKind::LeftSquareBracket => on_table(tokens, current_token, receiver, error),
             TokenKind::RightSquareBracket => {
@@ -258,31 +295,45 @@ fn on_table(
                         .with_expected(&[Expected::Literal("]")])
                         .with_unexpected(close_token.span().after()),
                 );
+                receiver.array_table_close(close_token.span(), error);
             }
         } else {
             receiver.std_table_close(close_token.span(), error);
             success = true;
         }
-    } else if valid_key {
-        let last_key_token = tokens
-            .previous_tokens()
-            .find(|t| t.kind() != TokenKind::Whitespace)
-            .unwrap_or(open_token);
-        let context = open_token.span().append(last_key_token.span());
+    } else {
+        if valid_key {
+            let last_key_token = tokens
+                .previous_tokens()
+                .find(|t| t.kind() != TokenKind::Whitespace)
+                .unwrap_or(open_token);
+            let context = open_token.span().append(last_key_token.span());
+            if is_array_table {
+                error.report_error(
+                    ParseError::new("unclosed array table")
+                        .with_context(context)
+                        .with_expected(&[Expected::Literal("]]")])
+                        .with_unexpected(last_key_token.span().after()),
+                );
+            } else {
+                error.report_error(
+                    ParseError::new("unclosed table")
+                        .with_context(context)
+                        .with_expected(&[Expected::Literal("]")])
+                        .with_unexpected(last_key_token.span().after()),
+                );
+            }
+        }
+
+        // Keep the keys that were parsed, like `a.b` in `[a.b.`, as an incomplete header
+        let span = tokens
+            .first()
+            .map(|t| t.span().before())
+            .unwrap_or_else(|| open_token.span().after());
         if is_array_table {
-            error.report_error(
-                ParseError::new("unclosed array table")
-                    .with_context(context)
-                    .with_expected(&[Expected::Literal("]]")])
-                    .with_unexpected(last_key_token.span().after()),
-            );
+            receiver.array_table_close(span, error);
         } else {
-            error.report_error(
-                ParseError::new("unclosed table")
-                    .with_context(context)
-                    .with_expected(&[Expected::Literal("]")])
-                    .with_unexpected(last_key_token.span().after()),
-            );
+            receiver.std_table_close(span, error);
         }
     }
 
@@ -633,7 +684,7 @@ fn value(tokens: &mut Stream<'_>, receiver: &mut dyn EventReceiver, error: &mut
         error.report_error(
             ParseError::new("missing value")
                 .with_context(previous_span)
-                .with_expected(&[Expected::Description("value")])
+                .with_expected(&[Expected::OneOfValues(ValueKind::ALL)])
                 .with_unexpected(previous_span.after()),
         );
         return;
@@ -739,6 +790,12 @@ fn on_scalar(
                 if first.kind() == TokenKind::Whitespace && second.kind() == TokenKind::Atom {
                     span = span.append(second.span());
                     let _ = tokens.next_slice(2);
+                    // Fractional seconds of a space-separated datetime
+                    while let Some(extra_token) =
+                        next_token_if(tokens, |k| matches!(k, TokenKind::Dot | TokenKind::Atom))
+                    {
+                        span = span.append(extra_token.span());
+                    }
                 }
             }
             None
@@ -800,7 +857,7 @@ fn on_array_open(
                     error.report_error(
                         ParseError::new("extra comma in array")
                             .with_context(array_open.span())
-                            .with_expected(&[Expected::Description("value")])
+                            .with_expected(&[Expected::OneOfValues(ValueKind::ALL)])
                             .with_unexpected(current_token.span()),
                     );
                     receiver.error(current_token.span(), error);
@@ -815,7 +872,10 @@ fn on_array_open(
                 error.report_error(
                     ParseError::new("unexpected `=` in array")
                         .with_context(array_open.span())
-                        .with_expected(&[Expected::Description("value"), Expected::Literal("]")])
+                        .with_expected(&[
+                            Expected::OneOfValues(ValueKind::ALL),
+                            Expected::Literal("]"),
+                        ])
                         .with_unexpected(current_token.span()),
                 );
                 receiver.error(current_token.span(), error);
@@ -954,7 +1014,7 @@ fn on_inline_table_open(
             match self {
                 State::NeedsKey => &[Expected::Description("key")],
                 State::NeedsEquals => &[Expected::Literal("=")],
-                State::NeedsValue => &[Expected::Description("value")],
+                State::NeedsValue => &[Expected::OneOfValues(ValueKind::ALL)],
                 State::NeedsComma => &[Expected::Literal(",")],
             }
         }
@@ -969,7 +1029,8 @@ fn on_inline_table_open(
                     ParseError::new("comments are unsupported in inline tables")
                         .with_context(inline_table_open.span())
                         .with_expected(&[])
-                        .with_unexpected(current_token.span()),
+                        .with_unexpected(current_token.span())
+                        .with_allowed_since(TomlSpec::V1_1Draft),
                 );
 
                 on_comment(tokens, current_token, receiver, error);
@@ -982,7 +1043,8 @@ fn on_inline_table_open(
                     ParseError::new("newlines are unsupported in inline tables")
                         .with_context(inline_table_open.span())
                         .with_expected(&[])
-                        .with_unexpected(current_token.span()),
+                        .with_unexpected(current_token.span())
+                        .with_allowed_since(TomlSpec::V1_1Draft),
                 );
 
                 receiver.newline(current_token.span(), error);
@@ -1083,7 +1145,8 @@ fn on_inline_table_open(
                         ParseError::new("trailing commas are not supported in inline tables")
                             .with_context(inline_table_open.span())
                             .with_expected(&[])
-                            .with_unexpected(unexpected),
+                            .with_unexpected(unexpected)
+                            .with_allowed_since(TomlSpec::V1_1Draft),
                     );
                 }
                 receiver.inline_table_close(current_token.span(), error);
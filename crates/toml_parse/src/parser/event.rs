@@ -1,3 +1,4 @@
+use crate::decoder::CarriageReturn;
 use crate::decoder::Encoding;
 use crate::ErrorSink;
 use crate::ParseError;
@@ -30,6 +31,12 @@ pub trait EventReceiver {
     fn comment(&mut self, _span: Span, _error: &mut dyn ErrorSink) {}
     fn newline(&mut self, _span: Span, _error: &mut dyn ErrorSink) {}
     fn error(&mut self, _span: Span, _error: &mut dyn ErrorSink) {}
+    /// Returns if parsing should stop
+    ///
+    /// This is checked before each expression, so the line being parsed is finished first.
+    fn is_aborted(&self) -> bool {
+        false
+    }
 }
 
 impl<F> EventReceiver for F
@@ -290,11 +297,43 @@ impl EventReceiver for () {}
 pub struct ValidateWhitespace<'r, 's> {
     receiver: &'r mut dyn EventReceiver,
     source: Source<'s>,
+    carriage_return: CarriageReturn,
 }
 
 impl<'r, 's> ValidateWhitespace<'r, 's> {
     pub fn new(receiver: &'r mut dyn EventReceiver, source: Source<'s>) -> Self {
-        Self { receiver, source }
+        Self {
+            receiver,
+            source,
+            carriage_return: CarriageReturn::Reject,
+        }
+    }
+
+    /// Choose whether a carriage return that is not part of a `\r\n` line ending is an error
+    ///
+    /// This should match [`Lexer::with_carriage_return`][crate::lexer::Lexer::with_carriage_return].
+    pub fn with_carriage_return(mut self, carriage_return: CarriageReturn) -> Self {
+        self.carriage_return = carriage_return;
+        self
+    }
+
+    /// Look up a token's content
+    ///
+    /// Without the `unsafe` feature, a span outside of `source` or off of a UTF-8 boundary is
+    /// reported as an error rather than trusted.
+    fn get(&self, span: Span, error: &mut dyn ErrorSink) -> Option<crate::Raw<'s>> {
+        #[cfg(feature = "unsafe")] // SAFETY: callers must use valid span
+        let raw = Some(unsafe { self.source.get_unchecked(span) });
+        #[cfg(not(feature = "unsafe"))]
+        let raw = self.source.get(span);
+        if raw.is_none() {
+            error.report_error(
+                ParseError::new("invalid token span")
+                    .with_context(span)
+                    .with_unexpected(span),
+            );
+        }
+        raw
     }
 }
 
@@ -339,35 +378,32 @@ impl EventReceiver for ValidateWhitespace<'_, '_> {
         self.receiver.value_sep(span, error);
     }
     fn whitespace(&mut self, span: Span, error: &mut dyn ErrorSink) {
-        #[cfg(feature = "unsafe")] // SAFETY: callers must use valid span
-        let raw = unsafe { self.source.get_unchecked(span) };
-        #[cfg(not(feature = "unsafe"))]
-        let raw = self.source.get(span).expect("token spans are valid");
-        raw.decode_whitespace(error);
+        if let Some(raw) = self.get(span, error) {
+            raw.decode_whitespace(error);
+        }
 
         self.receiver.whitespace(span, error);
     }
     fn comment(&mut self, span: Span, error: &mut dyn ErrorSink) {
-        #[cfg(feature = "unsafe")] // SAFETY: callers must use valid span
-        let raw = unsafe { self.source.get_unchecked(span) };
-        #[cfg(not(feature = "unsafe"))]
-        let raw = self.source.get(span).expect("token spans are valid");
-        raw.decode_comment(error);
+        if let Some(raw) = self.get(span, error) {
+            raw.decode_comment_with(self.carriage_return, error);
+        }
 
         self.receiver.comment(span, error);
     }
     fn newline(&mut self, span: Span, error: &mut dyn ErrorSink) {
-        #[cfg(feature = "unsafe")] // SAFETY: callers must use valid span
-        let raw = unsafe { self.source.get_unchecked(span) };
-        #[cfg(not(feature = "unsafe"))]
-        let raw = self.source.get(span).expect("token spans are valid");
-        raw.decode_newline(error);
+        if let Some(raw) = self.get(span, error) {
+            raw.decode_newline_with(self.carriage_return, error);
+        }
 
         self.receiver.newline(span, error);
     }
     fn error(&mut self, span: Span, error: &mut dyn ErrorSink) {
         self.receiver.error(span, error);
     }
+    fn is_aborted(&self) -> bool {
+        self.receiver.is_aborted()
+    }
 }
 
 pub struct RecursionGuard<'r> {
@@ -462,6 +498,274 @@ impl EventReceiver for RecursionGuard<'_> {
     fn error(&mut self, span: Span, error: &mut dyn ErrorSink) {
         self.receiver.error(span, error);
     }
+    fn is_aborted(&self) -> bool {
+        self.receiver.is_aborted()
+    }
+}
+
+/// Cap the size of values, so a huge value isn't passed on to be decoded
+///
+/// A scalar longer than the limit is reported and passed on as an [`EventKind::Error`], with
+/// [`SizeLimit::is_truncated`] set so callers know values were left out.  Arrays and inline
+/// tables are not limited as a whole, only the values within them.  The error's
+/// [context][ParseError::context] is the whole value and its [unexpected][ParseError::unexpected]
+/// part is what is past the limit.
+///
+/// This only sees tokens once they are lexed.  To not lex the rest of a document after a value
+/// that is too long, also limit the lexer with
+/// [`Lexer::with_max_token_len`][crate::lexer::Lexer::with_max_token_len].
+pub struct SizeLimit<'r> {
+    receiver: &'r mut dyn EventReceiver,
+    max_value_len: usize,
+    max_string_len: usize,
+    truncated: bool,
+}
+
+impl<'r> SizeLimit<'r> {
+    /// Limit every scalar value to `max_value_len` bytes, including quotes
+    pub fn new(receiver: &'r mut dyn EventReceiver, max_value_len: usize) -> Self {
+        Self {
+            receiver,
+            max_value_len,
+            max_string_len: max_value_len,
+            truncated: false,
+        }
+    }
+
+    /// Limit quoted strings, whether values or keys, to `max_string_len` bytes, including quotes
+    ///
+    /// This is capped by the limit on values.
+    pub fn with_max_string_len(mut self, max_string_len: usize) -> Self {
+        self.max_string_len = max_string_len;
+        self
+    }
+
+    /// Whether any key or value was left out for being too large
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    fn within_limit(
+        &mut self,
+        span: Span,
+        max_len: usize,
+        description: &'static str,
+        error: &mut dyn ErrorSink,
+    ) -> bool {
+        if span.len() <= max_len {
+            return true;
+        }
+        self.truncated = true;
+        let past_limit = Span::new_unchecked(span.start() + max_len, span.end());
+        error.report_error(
+            ParseError::new(description)
+                .with_context(span)
+                .with_unexpected(past_limit),
+        );
+        false
+    }
+}
+
+impl EventReceiver for SizeLimit<'_> {
+    fn std_table_open(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.std_table_open(span, error);
+    }
+    fn std_table_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.std_table_close(span, error);
+    }
+    fn array_table_open(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.array_table_open(span, error);
+    }
+    fn array_table_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.array_table_close(span, error);
+    }
+    fn inline_table_open(&mut self, span: Span, error: &mut dyn ErrorSink) -> bool {
+        self.receiver.inline_table_open(span, error)
+    }
+    fn inline_table_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.inline_table_close(span, error);
+    }
+    fn array_open(&mut self, span: Span, error: &mut dyn ErrorSink) -> bool {
+        self.receiver.array_open(span, error)
+    }
+    fn array_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.array_close(span, error);
+    }
+    fn simple_key(&mut self, span: Span, encoding: Option<Encoding>, error: &mut dyn ErrorSink) {
+        if encoding.is_some()
+            && !self.within_limit(span, self.max_string_len, "key is too long", error)
+        {
+            self.receiver.error(span, error);
+            return;
+        }
+        self.receiver.simple_key(span, encoding, error);
+    }
+    fn key_sep(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.key_sep(span, error);
+    }
+    fn key_val_sep(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.key_val_sep(span, error);
+    }
+    fn scalar(&mut self, span: Span, encoding: Option<Encoding>, error: &mut dyn ErrorSink) {
+        let within_limit = if encoding.is_some() {
+            let max_len = self.max_string_len.min(self.max_value_len);
+            self.within_limit(span, max_len, "string is too long", error)
+        } else {
+            self.within_limit(span, self.max_value_len, "value is too long", error)
+        };
+        if !within_limit {
+            self.receiver.error(span, error);
+            return;
+        }
+        self.receiver.scalar(span, encoding, error);
+    }
+    fn value_sep(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.value_sep(span, error);
+    }
+    fn whitespace(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.whitespace(span, error);
+    }
+    fn comment(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.comment(span, error);
+    }
+    fn newline(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.newline(span, error);
+    }
+    fn error(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.error(span, error);
+    }
+    fn is_aborted(&self) -> bool {
+        self.receiver.is_aborted()
+    }
+}
+
+/// Stop parsing once `hook` returns `true` for an [`Event`]
+///
+/// Each event is passed on before `hook` sees it.  Parsing stops before the next expression, so
+/// this is for when only part of a document is needed, like its table headers.
+///
+/// # Example
+///
+/// ```
+/// use toml_parse::parser::{parse_document, AbortHook, Event, EventKind, EventReceiver as _};
+/// use toml_parse::Source;
+///
+/// let source = Source::new("name = 'app'\n[dependencies]\nserde = '1'\n");
+/// let tokens = source.lex().into_vec();
+/// let mut events = Vec::<Event>::new();
+/// let mut hook = AbortHook::new(&mut events, |e: &Event| e.kind() == EventKind::StdTableOpen);
+/// parse_document(&tokens, &mut hook, &mut ());
+/// assert!(hook.is_aborted());
+///
+/// let keys = events.iter().filter(|e| e.kind() == EventKind::SimpleKey).count();
+/// assert_eq!(keys, 2);
+/// ```
+pub struct AbortHook<'r, F> {
+    receiver: &'r mut dyn EventReceiver,
+    hook: F,
+    aborted: bool,
+}
+
+impl<'r, F> AbortHook<'r, F>
+where
+    F: FnMut(&Event) -> bool,
+{
+    pub fn new(receiver: &'r mut dyn EventReceiver, hook: F) -> Self {
+        Self {
+            receiver,
+            hook,
+            aborted: false,
+        }
+    }
+
+    fn on_event(&mut self, kind: EventKind, encoding: Option<Encoding>, span: Span) {
+        if !self.aborted {
+            self.aborted = (self.hook)(&Event {
+                kind,
+                encoding,
+                span,
+            });
+        }
+    }
+}
+
+impl<F> EventReceiver for AbortHook<'_, F>
+where
+    F: FnMut(&Event) -> bool,
+{
+    fn std_table_open(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.std_table_open(span, error);
+        self.on_event(EventKind::StdTableOpen, None, span);
+    }
+    fn std_table_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.std_table_close(span, error);
+        self.on_event(EventKind::StdTableClose, None, span);
+    }
+    fn array_table_open(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.array_table_open(span, error);
+        self.on_event(EventKind::ArrayTableOpen, None, span);
+    }
+    fn array_table_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.array_table_close(span, error);
+        self.on_event(EventKind::ArrayTableClose, None, span);
+    }
+    fn inline_table_open(&mut self, span: Span, error: &mut dyn ErrorSink) -> bool {
+        let allowed = self.receiver.inline_table_open(span, error);
+        self.on_event(EventKind::InlineTableOpen, None, span);
+        allowed
+    }
+    fn inline_table_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.inline_table_close(span, error);
+        self.on_event(EventKind::InlineTableClose, None, span);
+    }
+    fn array_open(&mut self, span: Span, error: &mut dyn ErrorSink) -> bool {
+        let allowed = self.receiver.array_open(span, error);
+        self.on_event(EventKind::ArrayOpen, None, span);
+        allowed
+    }
+    fn array_close(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.array_close(span, error);
+        self.on_event(EventKind::ArrayClose, None, span);
+    }
+    fn simple_key(&mut self, span: Span, encoding: Option<Encoding>, error: &mut dyn ErrorSink) {
+        self.receiver.simple_key(span, encoding, error);
+        self.on_event(EventKind::SimpleKey, encoding, span);
+    }
+    fn key_sep(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.key_sep(span, error);
+        self.on_event(EventKind::KeySep, None, span);
+    }
+    fn key_val_sep(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.key_val_sep(span, error);
+        self.on_event(EventKind::KeyValSep, None, span);
+    }
+    fn scalar(&mut self, span: Span, encoding: Option<Encoding>, error: &mut dyn ErrorSink) {
+        self.receiver.scalar(span, encoding, error);
+        self.on_event(EventKind::Scalar, encoding, span);
+    }
+    fn value_sep(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.value_sep(span, error);
+        self.on_event(EventKind::ValueSep, None, span);
+    }
+    fn whitespace(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.whitespace(span, error);
+        self.on_event(EventKind::Whitespace, None, span);
+    }
+    fn comment(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.comment(span, error);
+        self.on_event(EventKind::Comment, None, span);
+    }
+    fn newline(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.newline(span, error);
+        self.on_event(EventKind::Newline, None, span);
+    }
+    fn error(&mut self, span: Span, error: &mut dyn ErrorSink) {
+        self.receiver.error(span, error);
+        self.on_event(EventKind::Error, None, span);
+    }
+    fn is_aborted(&self) -> bool {
+        self.aborted || self.receiver.is_aborted()
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
@@ -1,16 +1,76 @@
 //! A TOML push [parser][parse_document]
 //!
 //! This takes TOML [tokens][crate::lexer::Token] and [emits][EventReceiver] [events][Event].
+//!
+//! Values are emitted in full, wherever they appear:
+//! - Arrays and inline tables are bracketed by open and close events with their contents,
+//!   including nested arrays and inline tables, in between
+//! - Strings, booleans, numbers, and date-times are each a single [`EventKind::Scalar`] that can
+//!   be classified and decoded with [`Raw::decode_scalar`][crate::Raw::decode_scalar]
+//!
+//! Invalid input still produces as much structure as was parsed, with an error reported.
+//! Something that was never closed, like the header `[a.b.`, gets a close event with an empty
+//! span, after the keys that were parsed, so tools like completion know what the cursor is in.
+//!
+//! # Example
+//!
+//! ```
+//! use toml_parse::decoder::ScalarKind;
+//! use toml_parse::parser::{parse_document, Event, EventKind};
+//! use toml_parse::Source;
+//!
+//! let source = Source::new("[t]\nv = [1, { b = true }, 1979-05-27]\n");
+//! let tokens = source.lex().into_vec();
+//! let mut events = Vec::<Event>::new();
+//! parse_document(&tokens, &mut events, &mut ());
+//!
+//! let summary = events
+//!     .iter()
+//!     .filter(|e| !matches!(e.kind(), EventKind::Whitespace | EventKind::Newline))
+//!     .map(|e| match e.kind() {
+//!         EventKind::Scalar => {
+//!             let raw = source.get(e).unwrap();
+//!             let kind: ScalarKind = raw.decode_scalar(&mut (), &mut ());
+//!             format!("{kind:?}")
+//!         }
+//!         kind => format!("{kind:?}"),
+//!     })
+//!     .collect::<Vec<_>>();
+//! assert_eq!(
+//!     summary,
+//!     [
+//!         "StdTableOpen", "SimpleKey", "StdTableClose",
+//!         "SimpleKey", "KeyValSep",
+//!         "ArrayOpen",
+//!         "Integer(Dec)", "ValueSep",
+//!         "InlineTableOpen", "SimpleKey", "KeyValSep", "Boolean(true)", "InlineTableClose", "ValueSep",
+//!         "DateTime",
+//!         "ArrayClose",
+//!     ]
+//! );
+//! ```
 
 mod document;
 mod event;
+mod trivia;
 
 pub use document::parse_document;
 pub use document::parse_key;
+#[cfg(feature = "alloc")]
+pub(crate) use document::parse_key_prefix;
 pub use document::parse_simple_key;
 pub use document::parse_value;
+#[cfg(feature = "alloc")]
+pub(crate) use document::parse_value_prefix;
+pub use event::AbortHook;
 pub use event::Event;
 pub use event::EventKind;
 pub use event::EventReceiver;
 pub use event::RecursionGuard;
+pub use event::SizeLimit;
 pub use event::ValidateWhitespace;
+pub use trivia::group_trivia;
+pub use trivia::Trivia;
+pub use trivia::TriviaAttachment;
+pub use trivia::TriviaIter;
+pub use trivia::TriviaKind;
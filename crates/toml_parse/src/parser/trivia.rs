@@ -0,0 +1,196 @@
+use super::Event;
+use super::EventKind;
+use crate::Span;
+
+/// Group whitespace, comment, and newline [`Event`]s into [`Trivia`]
+///
+/// This gives a consistent policy for attaching comments to the expressions around them:
+/// - Whitespace and comments after an expression on the same line, up through the newline, are
+///   [trailing][TriviaAttachment::Trailing] that expression
+/// - Full lines of comments or blank lines are grouped together and
+///   [lead][TriviaAttachment::Leading] the next expression
+/// - Whitespace between parts of an expression leads the next part
+///
+/// Any trivia at the end of the document, with no expression after it, is trailing.
+pub fn group_trivia(events: &[Event]) -> TriviaIter<'_> {
+    let last_content = events.iter().rposition(|event| !is_trivia(event.kind()));
+    TriviaIter {
+        events,
+        index: 0,
+        line_has_content: false,
+        last_content,
+    }
+}
+
+/// A group of whitespace-like [`Event`]s, see [`group_trivia`]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Trivia {
+    kind: TriviaKind,
+    attachment: TriviaAttachment,
+    span: Span,
+}
+
+impl Trivia {
+    #[inline(always)]
+    pub fn kind(&self) -> TriviaKind {
+        self.kind
+    }
+
+    #[inline(always)]
+    pub fn attachment(&self) -> TriviaAttachment {
+        self.attachment
+    }
+
+    #[inline(always)]
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum TriviaKind {
+    /// Whitespace and, if it ends a line, the newline
+    Whitespace,
+    /// One or more lines, or the end of a line, containing comments
+    Comment,
+    /// One or more lines containing only whitespace
+    BlankLine,
+}
+
+/// Which expression [`Trivia`] is associated with
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum TriviaAttachment {
+    /// Belongs to the next expression
+    Leading,
+    /// Belongs to the previous expression
+    Trailing,
+}
+
+/// See [`group_trivia`]
+#[derive(Clone, Debug)]
+pub struct TriviaIter<'e> {
+    events: &'e [Event],
+    index: usize,
+    line_has_content: bool,
+    last_content: Option<usize>,
+}
+
+impl TriviaIter<'_> {
+    fn skip_content(&mut self) {
+        while let Some(event) = self.events.get(self.index) {
+            if is_trivia(event.kind()) {
+                break;
+            }
+            self.line_has_content = true;
+            self.index += 1;
+        }
+    }
+
+    /// Scan whitespace and comments up through the next newline
+    ///
+    /// Returns the index after the scanned events, whether a comment was found, and whether the
+    /// scan stopped on a newline.
+    fn scan_line(&self, start: usize) -> (usize, bool, bool) {
+        let mut index = start;
+        let mut has_comment = false;
+        while let Some(event) = self.events.get(index) {
+            match event.kind() {
+                EventKind::Whitespace => {}
+                EventKind::Comment => has_comment = true,
+                EventKind::Newline => return (index + 1, has_comment, true),
+                _ => break,
+            }
+            index += 1;
+        }
+        (index, has_comment, false)
+    }
+
+    fn has_content_after(&self, index: usize) -> bool {
+        matches!(self.last_content, Some(last) if index <= last)
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span {
+        self.events[start]
+            .span()
+            .append(self.events[end - 1].span())
+    }
+}
+
+impl Iterator for TriviaIter<'_> {
+    type Item = Trivia;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.skip_content();
+        let start = self.index;
+        if self.events.len() <= start {
+            return None;
+        }
+
+        let (mut end, has_comment, is_line) = self.scan_line(start);
+        let (kind, attachment) = if self.line_has_content {
+            if is_line {
+                self.line_has_content = false;
+                let kind = if has_comment {
+                    TriviaKind::Comment
+                } else {
+                    TriviaKind::Whitespace
+                };
+                (kind, TriviaAttachment::Trailing)
+            } else if self.has_content_after(end) {
+                (TriviaKind::Whitespace, TriviaAttachment::Leading)
+            } else {
+                let kind = if has_comment {
+                    TriviaKind::Comment
+                } else {
+                    TriviaKind::Whitespace
+                };
+                (kind, TriviaAttachment::Trailing)
+            }
+        } else if is_line {
+            let kind = if has_comment {
+                TriviaKind::Comment
+            } else {
+                TriviaKind::BlankLine
+            };
+            // Group consecutive lines of the same kind
+            loop {
+                let (next_end, next_has_comment, next_is_line) = self.scan_line(end);
+                if !next_is_line || next_has_comment != has_comment {
+                    break;
+                }
+                end = next_end;
+            }
+            let attachment = if self.has_content_after(end) {
+                TriviaAttachment::Leading
+            } else {
+                TriviaAttachment::Trailing
+            };
+            (kind, attachment)
+        } else if self.has_content_after(end) {
+            (TriviaKind::Whitespace, TriviaAttachment::Leading)
+        } else {
+            let kind = if has_comment {
+                TriviaKind::Comment
+            } else {
+                TriviaKind::Whitespace
+            };
+            (kind, TriviaAttachment::Trailing)
+        };
+
+        self.index = end;
+        Some(Trivia {
+            kind,
+            attachment,
+            span: self.span(start, end),
+        })
+    }
+}
+
+impl core::iter::FusedIterator for TriviaIter<'_> {}
+
+fn is_trivia(kind: EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Whitespace | EventKind::Comment | EventKind::Newline
+    )
+}
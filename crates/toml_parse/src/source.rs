@@ -1,3 +1,7 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::decoder::CarriageReturn;
 use crate::decoder::Encoding;
 use crate::decoder::StringBuilder;
 use crate::lexer::Lexer;
@@ -8,11 +12,30 @@ use crate::Expected;
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Source<'i> {
     input: &'i str,
+    id: SourceId,
 }
 
 impl<'i> Source<'i> {
     pub fn new(input: &'i str) -> Self {
-        Self { input }
+        Self {
+            input,
+            id: SourceId::default(),
+        }
+    }
+
+    /// Identify this input among several, like the files of a configuration
+    ///
+    /// [`Span`]s are relative to their input, so diagnostics need this to say which input a span
+    /// is in, see [`ParseError::with_source_id`][crate::ParseError::with_source_id].
+    #[must_use]
+    pub fn with_id(mut self, id: SourceId) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Which input this is, see [`Source::with_id`]
+    pub fn id(&self) -> SourceId {
+        self.id
     }
 
     /// Start lexing the TOML encoded data
@@ -160,22 +183,86 @@ impl<'i> Raw<'i> {
         }
     }
 
+    /// Decode a key, writing into `buffer` only if the key can't be borrowed from the source
+    ///
+    /// Returns the key when it could be borrowed, otherwise it is in `buffer`.
+    #[cfg(feature = "alloc")]
+    pub fn decode_key_into(
+        &self,
+        buffer: &mut String,
+        error: &mut dyn ErrorSink,
+    ) -> Option<&'i str> {
+        let mut output = crate::decoder::BufferedString::new(buffer);
+        self.decode_key(&mut output, error);
+        output.borrowed()
+    }
+
+    /// Decode a scalar, writing into `buffer` only if the value can't be borrowed from the source
+    ///
+    /// Returns the value when it could be borrowed, otherwise it is in `buffer`.
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn decode_scalar_into(
+        &self,
+        buffer: &mut String,
+        error: &mut dyn ErrorSink,
+    ) -> (crate::decoder::scalar::ScalarKind, Option<&'i str>) {
+        let mut output = crate::decoder::BufferedString::new(buffer);
+        let kind = self.decode_scalar(&mut output, error);
+        (kind, output.borrowed())
+    }
+
     pub fn decode_whitespace(&self, _error: &mut dyn ErrorSink) {
         // whitespace is always valid
     }
 
     pub fn decode_comment(&self, error: &mut dyn ErrorSink) {
+        self.decode_comment_with(CarriageReturn::Reject, error);
+    }
+
+    /// Validate a comment, allowing a lone carriage return unless it is
+    /// [`CarriageReturn::Reject`]
+    pub fn decode_comment_with(&self, carriage_return: CarriageReturn, error: &mut dyn ErrorSink) {
         let mut error = |err: crate::ParseError| {
             error.report_error(err.rebase_spans(self.span.start));
         };
-        crate::decoder::ws::decode_comment(*self, &mut error);
+        crate::decoder::ws::decode_comment(*self, carriage_return, &mut error);
     }
 
     pub fn decode_newline(&self, error: &mut dyn ErrorSink) {
+        self.decode_newline_with(CarriageReturn::Reject, error);
+    }
+
+    /// Validate a newline, allowing a lone carriage return unless it is
+    /// [`CarriageReturn::Reject`]
+    pub fn decode_newline_with(&self, carriage_return: CarriageReturn, error: &mut dyn ErrorSink) {
         let mut error = |err: crate::ParseError| {
             error.report_error(err.rebase_spans(self.span.start));
         };
-        crate::decoder::ws::decode_newline(*self, &mut error);
+        crate::decoder::ws::decode_newline(*self, carriage_return, &mut error);
+    }
+
+    /// Audit the escape sequences in a basic or multi-line basic string
+    ///
+    /// Each [`Escape`][crate::decoder::Escape] reports its span within the [`Source`], what it
+    /// decodes to, and whether it is required, allowing formatters to replace redundant escapes,
+    /// like `\u0041` with `A`.  Other encodings have no escapes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let source = toml_parse::Source::new(r#""\u0041\n""#);
+    /// let token = source.lex().next().unwrap();
+    /// let raw = source.get(token).unwrap();
+    /// let escapes = raw.escapes().collect::<Vec<_>>();
+    /// assert_eq!(escapes[0].as_str(), r"\u0041");
+    /// assert_eq!(escapes[0].decoded(), 'A');
+    /// assert!(escapes[0].is_redundant());
+    /// assert_eq!(escapes[1].decoded(), '\n');
+    /// assert!(escapes[1].is_required());
+    /// ```
+    pub fn escapes(&self) -> crate::decoder::Escapes<'i> {
+        crate::decoder::Escapes::new(*self, self.encoding, self.span)
     }
 
     pub fn as_str(&self) -> &'i str {
@@ -195,7 +282,27 @@ impl<'i> Raw<'i> {
     }
 }
 
+/// Identifies a [`Source`] among several, see [`Source::with_id`]
+///
+/// The default is for an input parsed on its own.  What each id refers to, like a file path, is
+/// up to the caller.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceId(u32);
+
+impl SourceId {
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
 /// Location within the [`Source`]
+///
+/// Offsets are in bytes of the UTF-8 input, regardless of platform.  See [`SourceId`] for
+/// telling which input a span is within.
 #[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Span {
     start: usize,
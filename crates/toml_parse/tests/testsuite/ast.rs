@@ -0,0 +1,151 @@
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+
+use toml_parse::ast::Node;
+use toml_parse::ast::Table;
+use toml_parse::ast::Value;
+
+#[track_caller]
+fn t(input: &str, expected: impl IntoData) {
+    let mut errors = Vec::new();
+    let doc = toml_parse::ast::parse(input, &mut errors);
+    let mut actual = String::new();
+    render_table(input, &doc, "", &mut actual);
+    for error in errors {
+        let span = error.unexpected().unwrap_or_default();
+        actual.push_str(&format!(
+            "error: {} {:?}\n",
+            error.description(),
+            &input[span.start()..span.end()]
+        ));
+    }
+    assert_data_eq!(actual, expected.raw());
+}
+
+fn render_table(input: &str, table: &Table, path: &str, output: &mut String) {
+    for (key, node) in table {
+        let path = if path.is_empty() {
+            key.as_str().to_owned()
+        } else {
+            format!("{path}.{}", key.as_str())
+        };
+        render_node(input, node, &path, output);
+    }
+}
+
+fn render_node(input: &str, node: &Node, path: &str, output: &mut String) {
+    let span = node.span();
+    let raw = &input[span.start()..span.end()];
+    match node.value() {
+        Value::Table(table) => {
+            output.push_str(&format!("{path} {:?} {raw:?}\n", table.kind()));
+            render_table(input, table, path, output);
+        }
+        Value::Array(array) => {
+            let kind = if array.is_array_of_tables() {
+                "ArrayOfTables"
+            } else {
+                "Array"
+            };
+            output.push_str(&format!("{path} {kind} {raw:?}\n"));
+            for (i, node) in array.iter().enumerate() {
+                render_node(input, node, &format!("{path}[{i}]"), output);
+            }
+        }
+        value => output.push_str(&format!("{path} = {value:?} {raw:?}\n")),
+    }
+}
+
+#[test]
+fn document() {
+    t(
+        r#"name = "ré"
+"quoted key".x = 0x1F
+arr = [1, { a = 2 }]
+[package]
+meta = { docs = true, x.y = 1.5 }
+when = 1979-05-27
+[[bin]]
+name = 'x'
+[[bin]]
+[a.b]
+[a]
+"#,
+        str![[r#"
+name = String("ré") "\"ré\""
+quoted key Dotted "\"quoted key\""
+quoted key.x = Integer(31) "0x1F"
+arr Array "[1, { a = 2 }]"
+arr[0] = Integer(1) "1"
+arr[1] Inline "{ a = 2 }"
+arr[1].a = Integer(2) "2"
+package Header "[package]"
+package.meta Inline "{ docs = true, x.y = 1.5 }"
+package.meta.docs = Boolean(true) "true"
+package.meta.x Dotted "x"
+package.meta.x.y = Float(1.5) "1.5"
+package.when = Datetime(Datetime { date: Some(Date { year: 1979, month: 5, day: 27 }), time: None, offset: None }) "1979-05-27"
+bin ArrayOfTables "[[bin]]"
+bin[0] Header "[[bin]]"
+bin[0].name = String("x") "'x'"
+bin[1] Header "[[bin]]"
+a Header "[a]"
+a.b Header "[a.b]"
+
+"#]],
+    );
+}
+
+#[test]
+fn redefined() {
+    t(
+        r#"a = 1
+a = 2
+b.c = 1
+b = { d = 1 }
+t = { x = 1 }
+[t.y]
+z = 1
+[d]
+[d]
+[[d]]
+"#,
+        str![[r#"
+a = Integer(1) "1"
+b Dotted "b"
+b.c = Integer(1) "1"
+t Inline "{ x = 1 }"
+t.x = Integer(1) "1"
+d Header "[d]"
+error: duplicate key "a"
+error: duplicate key "b"
+error: cannot extend a value that isn't a table "t"
+error: duplicate table "[d]"
+error: duplicate key "[[d]]"
+
+"#]],
+    );
+}
+
+#[test]
+fn invalid_values() {
+    t(
+        r#"a = 99999999999999999999
+b = "ok"
+c = 
+d = [1, true]
+"#,
+        str![[r#"
+a = Integer(9223372036854775807) "99999999999999999999"
+b = String("ok") "\"ok\""
+c = String("") ""
+d Array "[1, true]"
+d[0] = Integer(1) "1"
+d[1] = Boolean(true) "true"
+error: integer number overflowed "99999999999999999999"
+error: string values must be quoted ""
+
+"#]],
+    );
+}
@@ -0,0 +1,117 @@
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+use winnow::error::ContextError;
+use winnow::error::ErrMode;
+use winnow::stream::Partial;
+use winnow::Parser as _;
+
+use toml_parse::combinator::*;
+
+type Combinator = fn(&mut &'static str) -> Result<Vec<toml_parse::parser::Event>, ContextError>;
+
+#[track_caller]
+fn t(parser: Combinator, input: &'static str, expected: impl IntoData) {
+    let mut rest = input;
+    let actual = match parser.with_taken().parse_next(&mut rest) {
+        Ok((events, taken)) => {
+            let last = events.last().map(|e| e.span().end()).unwrap_or_default();
+            assert_eq!(last, taken.len(), "events must cover what was taken");
+            format!("{taken:?} {rest:?}")
+        }
+        Err(_) => {
+            assert_eq!(rest, input, "errors must not consume input");
+            "error".to_owned()
+        }
+    };
+    assert_data_eq!(actual, expected.raw());
+}
+
+type PartialCombinator =
+    fn(&mut Partial<&'static str>) -> Result<Vec<toml_parse::parser::Event>, ErrMode<ContextError>>;
+
+#[track_caller]
+fn partial(parser: PartialCombinator, input: &'static str, expected: impl IntoData) {
+    let mut rest = Partial::new(input);
+    let actual = match parser.with_taken().parse_next(&mut rest) {
+        Ok((_, taken)) => format!("{taken:?} {:?}", *rest),
+        Err(ErrMode::Incomplete(_)) => "incomplete".to_owned(),
+        Err(_) => "error".to_owned(),
+    };
+    assert_data_eq!(actual, expected.raw());
+}
+
+#[test]
+fn value_scalar() {
+    t(value, "1", str![[r#""1" """#]]);
+    t(value, "'a' # comment", str![[r#""'a'" " # comment""#]]);
+    t(value, "true, false", str![[r#""true" ", false""#]]);
+    t(
+        value,
+        "1979-05-27 07:32:00Z\nnext",
+        str![[r#""1979-05-27 07:32:00Z" "\nnext""#]],
+    );
+    t(value, "1979-05-27 # c", str![[r#""1979-05-27" " # c""#]]);
+}
+
+#[test]
+fn value_nested() {
+    t(
+        value,
+        "[1, [2,\n3]] rest",
+        str![[r#""[1, [2,\n3]]" " rest""#]],
+    );
+    t(
+        value,
+        "{ a = { b = 1 } }]",
+        str![[r#""{ a = { b = 1 } }" "]""#]],
+    );
+}
+
+#[test]
+fn value_invalid() {
+    t(value, "", str!["error"]);
+    t(value, " 1", str!["error"]);
+    t(value, "1__2", str!["error"]);
+    t(value, "[1, 2", str!["error"]);
+    t(value, r#""\q""#, str!["error"]);
+}
+
+#[test]
+fn key_dotted() {
+    t(key, "a.'b'.c = 1", str![[r#""a.'b'.c " "= 1""#]]);
+    t(key, "a", str![[r#""a" """#]]);
+    t(key, "= 1", str!["error"]);
+}
+
+#[test]
+fn document_complete() {
+    t(
+        document,
+        "a = 1\n[t]\nb = [2]\n",
+        str![[r#""a = 1\n[t]\nb = [2]\n" """#]],
+    );
+    t(document, "a = \n", str!["error"]);
+}
+
+#[test]
+fn value_partial() {
+    partial(value, "12", str!["incomplete"]);
+    partial(value, "[1, 2", str!["incomplete"]);
+    partial(value, "'abc", str!["incomplete"]);
+    partial(value, "1979-05-27 ", str!["incomplete"]);
+    partial(value, "12, 3", str![[r#""12" ", 3""#]]);
+    partial(value, "12\n", str![[r#""12" "\n""#]]);
+    partial(value, "1__2, 3", str!["error"]);
+}
+
+#[test]
+fn key_partial() {
+    partial(key, "a.b", str!["incomplete"]);
+    partial(key, "a.b =", str![[r#""a.b " "=""#]]);
+}
+
+#[test]
+fn document_partial() {
+    partial(document, "a = 1\n", str!["incomplete"]);
+}
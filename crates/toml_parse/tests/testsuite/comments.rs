@@ -0,0 +1,64 @@
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+
+const INPUT: &str = "# header
+a = [ # c
+  1, # d
+]
+s = '''
+# not a comment
+'''
+k = \"#\" # e
+";
+
+#[test]
+fn strip_comments() {
+    let actual = toml_parse::strip_comments(INPUT);
+    assert_data_eq!(
+        actual.as_ref(),
+        str![[r##"
+
+a = [
+  1,
+]
+s = '''
+# not a comment
+'''
+k = "#"
+
+"##]]
+        .raw()
+    );
+}
+
+#[test]
+fn strip_no_comments() {
+    let input = "a = '#'\n";
+    assert!(matches!(
+        toml_parse::strip_comments(input),
+        std::borrow::Cow::Borrowed(_)
+    ));
+}
+
+#[test]
+fn extract_comments() {
+    let actual = toml_parse::extract_comments(INPUT)
+        .into_iter()
+        .map(|(span, comment)| {
+            assert_eq!(&INPUT[span.start()..span.end()], comment);
+            format!("{comment:?} {span:?}\n")
+        })
+        .collect::<String>();
+    assert_data_eq!(
+        actual,
+        str![[r##"
+"# header" 0..8
+"# c" 15..18
+"# d" 24..27
+"# e" 66..69
+
+"##]]
+        .raw()
+    );
+}
@@ -0,0 +1,171 @@
+use std::fmt::Write as _;
+
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+
+use toml_parse::parser::*;
+use toml_parse::ErrorSink;
+use toml_parse::ParseError;
+use toml_parse::Severity;
+use toml_parse::Source;
+use toml_parse::Span;
+
+/// Records errors to render them with their spans resolved to lines and columns
+///
+/// The rendering is meant for golden files, so changes to error messages or spans show up in
+/// review.
+#[derive(Debug)]
+struct ErrorRecorder<'i> {
+    input: &'i str,
+    errors: Vec<ParseError>,
+}
+
+impl<'i> ErrorRecorder<'i> {
+    fn new(input: &'i str) -> Self {
+        Self {
+            input,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Lex, parse, and decode `input`, recording every error
+    fn parse(input: &'i str) -> Self {
+        let mut recorder = Self::new(input);
+        let tokens = Source::new(input).lex().into_vec();
+        let mut events = Vec::new();
+        parse_document(&tokens, &mut events, &mut recorder);
+        crate::decode_events(input, &events, &mut recorder);
+        recorder
+    }
+
+    /// Each error as `line:column: severity: message`, followed by the line it is on with the
+    /// unexpected part underlined
+    fn render(&self) -> String {
+        let mut rendered = String::new();
+        if self.errors.is_empty() {
+            rendered.push_str("no errors\n");
+        }
+        for error in &self.errors {
+            let severity = match error.severity() {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                _ => "note",
+            };
+            let span = error
+                .unexpected()
+                .or_else(|| error.context())
+                .unwrap_or_else(|| Span::new_unchecked(0, 0));
+            let (line, column) = self.line_col(span.start());
+            let _ = writeln!(rendered, "{line}:{column}: {severity}: {error}");
+            if let Some(context) = error.context() {
+                let (start_line, start_column) = self.line_col(context.start());
+                let (end_line, end_column) = self.line_col(context.end());
+                let _ = writeln!(
+                    rendered,
+                    "  context: {start_line}:{start_column}-{end_line}:{end_column}"
+                );
+            }
+            if let Some(fix) = error.fix() {
+                let _ = writeln!(rendered, "  fix: {fix:?}");
+            }
+            let (line_start, text) = self.line_text(span.start());
+            let before = escape(&text[..span.start() - line_start]);
+            let underlined = self
+                .input
+                .get(span.start()..span.end())
+                .map(|s| escape(s.split('\n').next().unwrap_or_default()))
+                .unwrap_or_default();
+            let _ = writeln!(rendered, "  | {}", escape(text));
+            let _ = writeln!(
+                rendered,
+                "  | {}{}",
+                " ".repeat(before.chars().count()),
+                "^".repeat(underlined.chars().count().max(1))
+            );
+        }
+        rendered
+    }
+
+    #[track_caller]
+    fn assert(&self, expected: impl IntoData) {
+        assert_data_eq!(self.render(), expected.raw());
+    }
+
+    /// 1-based line and column, counting columns in characters
+    fn line_col(&self, offset: usize) -> (usize, usize) {
+        let before = &self.input.as_bytes()[..offset.min(self.input.len())];
+        let line_start = before
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let line = before.iter().filter(|b| **b == b'\n').count() + 1;
+        let column = String::from_utf8_lossy(&before[line_start..])
+            .chars()
+            .count()
+            + 1;
+        (line, column)
+    }
+
+    /// Where the line `offset` is on starts, and the line without its line ending
+    fn line_text(&self, offset: usize) -> (usize, &'i str) {
+        let offset = offset.min(self.input.len());
+        let start = self.input[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let end = self.input[offset..]
+            .find('\n')
+            .map(|i| offset + i)
+            .unwrap_or(self.input.len());
+        (start, self.input[start..end].trim_end_matches('\r'))
+    }
+}
+
+/// Show control characters as escapes, keeping the rest of the line readable
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_control() && c != '\t' {
+                c.escape_debug().to_string()
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+impl ErrorSink for ErrorRecorder<'_> {
+    fn report_error(&mut self, error: ParseError) {
+        self.errors.push(error);
+    }
+}
+
+/// Check each `tests/fixtures/errors/*.toml` against the `.txt` next to it
+///
+/// Run with `SNAPSHOTS=overwrite` to update the `.txt` files after changing an error.
+#[test]
+fn corpus() {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/errors");
+    let mut inputs = std::fs::read_dir(&root)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some(std::ffi::OsStr::new("toml")))
+        .collect::<Vec<_>>();
+    inputs.sort();
+    assert!(!inputs.is_empty(), "no inputs in {}", root.display());
+    for path in inputs {
+        let input = std::fs::read_to_string(&path).unwrap();
+        let expected = snapbox::Data::read_from(&path.with_extension("txt"), None);
+        ErrorRecorder::parse(&input).assert(expected);
+    }
+}
+
+#[test]
+fn resolves_lines_and_columns() {
+    let recorder = ErrorRecorder::parse("a = 1\nb = 'é\n");
+    recorder.assert(snapbox::str![[r#"
+2:7: error: invalid literal string, expected `'`
+  context: 2:5-2:7
+  | b = 'é
+  |       ^
+
+"#]]);
+}
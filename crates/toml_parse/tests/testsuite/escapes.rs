@@ -0,0 +1,90 @@
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+
+use toml_parse::Source;
+
+#[track_caller]
+fn t(input: &str, expected: impl IntoData) {
+    let source = Source::new(input);
+    let mut actual = String::new();
+    for token in source.lex() {
+        let raw = source.get(token).unwrap();
+        for escape in raw.escapes() {
+            let span = escape.span();
+            assert_eq!(escape.as_str(), &input[span.start()..span.end()]);
+            let status = if escape.is_required() {
+                "required"
+            } else {
+                "redundant"
+            };
+            actual.push_str(&format!(
+                "{:?} {:?} {:?} {status}\n",
+                escape.as_str(),
+                span,
+                escape.decoded()
+            ));
+        }
+    }
+    assert_data_eq!(actual, expected.raw());
+}
+
+#[test]
+fn basic_string() {
+    t(
+        r#""\u0041\t\n\"\\\U0001F600""#,
+        str![[r#"
+"\\u0041" 1..7 'A' redundant
+"\\t" 7..9 '\t' redundant
+"\\n" 9..11 '\n' required
+"\\\"" 11..13 '"' required
+"\\\\" 13..15 '\\' required
+"\\U0001F600" 15..25 '😀' redundant
+
+"#]],
+    );
+}
+
+#[test]
+fn invalid_escapes() {
+    t(
+        r#""\x\u12\u0041""#,
+        str![[r#"
+"\\u0041" 7..13 'A' redundant
+
+"#]],
+    );
+}
+
+#[test]
+fn ml_basic_string_quotes() {
+    t(
+        "\"\"\"\n\\n\\\"x\\\"\\\"y\\\"\"\"\"",
+        str![[r#"
+"\\n" 4..6 '\n' redundant
+"\\\"" 6..8 '"' redundant
+"\\\"" 9..11 '"' required
+"\\\"" 11..13 '"' required
+"\\\"" 14..16 '"' required
+
+"#]],
+    );
+}
+
+#[test]
+fn ml_basic_string_whitespace() {
+    t(
+        "\"\"\"\\nx\\n \\\n  \\u0020y\"\"\"",
+        str![[r#"
+"\\n" 3..5 '\n' required
+"\\n" 6..8 '\n' redundant
+"\\u0020" 13..19 ' ' required
+
+"#]],
+    );
+}
+
+#[test]
+fn literal_string() {
+    t(r#"'\u0041'"#, str![""]);
+}
@@ -0,0 +1,71 @@
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+
+#[track_caller]
+fn t(input: &str, path: &str, expected: impl IntoData) {
+    let actual = match toml_parse::find_key(input, path) {
+        Some((raw, span)) => {
+            assert_eq!(raw.as_str(), &input[span.start()..span.end()]);
+            format!("{:?} {:?}", raw.as_str(), span)
+        }
+        None => "None".to_owned(),
+    };
+    assert_data_eq!(actual, expected.raw());
+}
+
+const INPUT: &str = r#"name = "root"
+deps = { serde = { version = "1.0", features = ["derive"] } }
+arr = [
+  1, # comment
+]
+[package]
+name = "foo" # comment
+a.b = 1979-05-27
+
+[[bin]]
+path = "x"
+"#;
+
+#[test]
+fn root() {
+    t(INPUT, "name", str![[r#""\"root\"" 7..13"#]]);
+}
+
+#[test]
+fn table() {
+    t(INPUT, "package.name", str![[r#""\"foo\"" 118..123"#]]);
+}
+
+#[test]
+fn dotted() {
+    t(INPUT, "package.a.b", str![[r#""1979-05-27" 140..150"#]]);
+}
+
+#[test]
+fn inline_table() {
+    t(INPUT, "deps.serde.version", str![[r#""\"1.0\"" 43..48"#]]);
+    t(
+        INPUT,
+        "deps.serde",
+        str![[r#""{ version = \"1.0\", features = [\"derive\"] }" 31..73"#]],
+    );
+}
+
+#[test]
+fn array() {
+    t(INPUT, "arr", str![[r#""[\n  1, # comment\n]" 82..100"#]]);
+}
+
+#[test]
+fn array_of_tables() {
+    t(INPUT, "bin.path", str![[r#""\"x\"" 167..170"#]]);
+}
+
+#[test]
+fn missing() {
+    t(INPUT, "package", str!["None"]);
+    t(INPUT, "package.version", str!["None"]);
+    t(INPUT, "package.name.first", str!["None"]);
+    t(INPUT, "package..name", str!["None"]);
+}
@@ -0,0 +1,81 @@
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+
+use toml_parse::parser::*;
+use toml_parse::Source;
+
+#[track_caller]
+fn t(input: &str, expected: impl IntoData) {
+    dbg!(input);
+    let doc = Source::new(input);
+    let tokens = doc.lex().into_vec();
+    let mut events = Vec::new();
+    let mut errors = Vec::<toml_parse::ParseError>::new();
+    parse_document(&tokens, &mut events, &mut errors);
+
+    let mut actual = String::new();
+    for trivia in group_trivia(&events) {
+        let span = trivia.span();
+        actual.push_str(&format!(
+            "{:?} {:?} {:?}\n",
+            trivia.kind(),
+            trivia.attachment(),
+            &input[span.start()..span.end()]
+        ));
+    }
+    assert_data_eq!(actual, expected.raw());
+}
+
+#[test]
+fn empty() {
+    t("", str![]);
+}
+
+#[test]
+fn document() {
+    t(
+        "# header
+# more
+
+[t]  # t comment
+  a = 1
+b = [
+  1, # one
+]
+
+# dangling
+",
+        str![[r##"
+Comment Leading "# header\n# more\n"
+BlankLine Leading "\n"
+Comment Trailing "  # t comment\n"
+Whitespace Leading "  "
+Whitespace Leading " "
+Whitespace Leading " "
+Whitespace Trailing "\n"
+Whitespace Leading " "
+Whitespace Leading " "
+Whitespace Trailing "\n"
+Whitespace Leading "  "
+Comment Trailing " # one\n"
+Whitespace Trailing "\n"
+BlankLine Trailing "\n"
+Comment Trailing "# dangling\n"
+
+"##]],
+    );
+}
+
+#[test]
+fn no_trailing_newline() {
+    t(
+        "a = 1 # comment",
+        str![[r##"
+Whitespace Leading " "
+Whitespace Leading " "
+Comment Trailing " # comment"
+
+"##]],
+    );
+}
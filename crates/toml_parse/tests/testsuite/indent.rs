@@ -0,0 +1,95 @@
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+
+use toml_parse::IndentStyle;
+
+const INPUT: &str = "[a]
+\t\tb = [
+\t  1, # c
+\t]
+  s = '''
+\t  not indentation'''
+
+    t = 1
+";
+
+#[test]
+fn indentation() {
+    let actual = toml_parse::indentation(INPUT)
+        .into_iter()
+        .map(|l| {
+            format!(
+                "{} {:?} tabs={} spaces={} mixed={}\n",
+                l.line(),
+                l.span(),
+                l.tabs(),
+                l.spaces(),
+                l.is_mixed()
+            )
+        })
+        .collect::<String>();
+    assert_data_eq!(
+        actual,
+        str![[r#"
+0 0..0 tabs=0 spaces=0 mixed=false
+1 4..6 tabs=2 spaces=0 mixed=false
+2 12..15 tabs=1 spaces=2 mixed=true
+3 22..23 tabs=1 spaces=0 mixed=false
+4 25..27 tabs=0 spaces=2 mixed=false
+6 57..57 tabs=0 spaces=0 mixed=false
+7 58..62 tabs=0 spaces=4 mixed=false
+
+"#]]
+        .raw()
+    );
+}
+
+#[test]
+fn reindent_tabs() {
+    let actual = toml_parse::reindent(INPUT, IndentStyle::Tabs, 4);
+    assert_data_eq!(
+        actual.as_ref(),
+        str![[r#"
+[a]
+		b = [
+	  1, # c
+	]
+  s = '''
+	  not indentation'''
+
+	t = 1
+
+"#]]
+        .raw()
+    );
+}
+
+#[test]
+fn reindent_spaces() {
+    let actual = toml_parse::reindent(INPUT, IndentStyle::Spaces, 2);
+    assert_data_eq!(
+        actual.as_ref(),
+        str![[r#"
+[a]
+    b = [
+    1, # c
+  ]
+  s = '''
+	  not indentation'''
+
+    t = 1
+
+"#]]
+        .raw()
+    );
+}
+
+#[test]
+fn reindent_unchanged() {
+    let input = "[a]\n\tb = 1\n";
+    assert!(matches!(
+        toml_parse::reindent(input, IndentStyle::Tabs, 4),
+        std::borrow::Cow::Borrowed(_)
+    ));
+}
@@ -0,0 +1,99 @@
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+use toml_parse::IndexSegment;
+
+#[track_caller]
+fn t(input: &str, expected: impl IntoData) {
+    let actual = toml_parse::index_document(input)
+        .iter()
+        .map(|e| {
+            let span = e.span();
+            let mut path = String::new();
+            for segment in e.path() {
+                match segment {
+                    IndexSegment::Key(key) if path.is_empty() => path.push_str(key),
+                    IndexSegment::Key(key) => {
+                        path.push('.');
+                        path.push_str(key);
+                    }
+                    IndexSegment::Index(index) => path.push_str(&format!("[{index}]")),
+                }
+            }
+            format!(
+                "{} {:?} {:?}\n",
+                path,
+                e.kind(),
+                &input[span.start()..span.end()]
+            )
+        })
+        .collect::<String>();
+    assert_data_eq!(actual, expected.raw());
+}
+
+#[test]
+fn document() {
+    t(
+        r#"name = "ré"
+"quoted key".x = 0x1F
+arr = [1, { a = 2 }]
+[package]
+meta = { docs = true, x.y = 1.5 }
+when = 1979-05-27
+[[bin]]
+name = 'x'
+"#,
+        str![[r#"
+name Scalar(String) "\"ré\""
+quoted key.x Scalar(Integer(Hex)) "0x1F"
+arr Array "[1, { a = 2 }]"
+package Table "[package]"
+package.meta InlineTable "{ docs = true, x.y = 1.5 }"
+package.meta.docs Scalar(Boolean(true)) "true"
+package.meta.x.y Scalar(Float) "1.5"
+package.when Scalar(DateTime) "1979-05-27"
+bin[0] ArrayOfTables "[[bin]]"
+bin[0].name Scalar(String) "'x'"
+
+"#]],
+    );
+}
+
+#[test]
+fn array_of_tables() {
+    t(
+        r#"[[bin]]
+name = 'a'
+[[bin]]
+name = 'b'
+[bin.meta]
+x = 1
+[[bin.target]]
+y = 1
+[[bin.target]]
+[[bin]]
+[[bin.target]]
+z = 1
+"#,
+        str![[r#"
+bin[0] ArrayOfTables "[[bin]]"
+bin[0].name Scalar(String) "'a'"
+bin[1] ArrayOfTables "[[bin]]"
+bin[1].name Scalar(String) "'b'"
+bin[1].meta Table "[bin.meta]"
+bin[1].meta.x Scalar(Integer(Dec)) "1"
+bin[1].target[0] ArrayOfTables "[[bin.target]]"
+bin[1].target[0].y Scalar(Integer(Dec)) "1"
+bin[1].target[1] ArrayOfTables "[[bin.target]]"
+bin[2] ArrayOfTables "[[bin]]"
+bin[2].target[0] ArrayOfTables "[[bin.target]]"
+bin[2].target[0].z Scalar(Integer(Dec)) "1"
+
+"#]],
+    );
+}
+
+#[test]
+fn empty() {
+    t("", str![]);
+}
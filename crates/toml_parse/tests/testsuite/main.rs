@@ -6,11 +6,20 @@ use snapbox::prelude::*;
 
 use toml_parse::decoder::ScalarKind;
 use toml_parse::parser::*;
-use toml_parse::ErrorSink as _;
+use toml_parse::ErrorSink;
 use toml_parse::ParseError;
 use toml_parse::Source;
-use toml_parse::Span;
 
+#[cfg(feature = "ast")]
+mod ast;
+mod combinator;
+mod comments;
+mod error_corpus;
+mod escapes;
+mod find_key;
+mod group_trivia;
+mod indent;
+mod index_document;
 mod parse_document;
 mod parse_simple_key;
 mod parse_value;
@@ -33,73 +42,7 @@ impl<'i> EventResults<'i> {
 
     #[track_caller]
     pub fn validate(&mut self, expected: impl IntoData) {
-        let doc = Source::new(self.input);
-        for event in &self.events {
-            if event.kind() == EventKind::SimpleKey {
-                let raw = doc.get(event).unwrap();
-                raw.decode_key(&mut (), &mut self.errors);
-            } else if event.kind() == EventKind::Comment {
-                let raw = doc.get(event).unwrap();
-                raw.decode_comment(&mut self.errors);
-            } else if event.kind() == EventKind::Newline {
-                let raw = doc.get(event).unwrap();
-                raw.decode_newline(&mut self.errors);
-            } else if event.kind() == EventKind::Scalar {
-                let raw = doc.get(event).unwrap();
-                let mut value = String::new();
-                let kind = raw.decode_scalar(&mut value, &mut self.errors);
-                dbg!(&value);
-                match kind {
-                    ScalarKind::String => {}
-                    ScalarKind::Boolean(v) => {
-                        let value = value.parse::<bool>();
-                        if value.is_err() {
-                            self.errors.report_error(
-                                ParseError::new("failed to parse bool")
-                                    .with_context(Span::new_unchecked(0, raw.len()))
-                                    .with_unexpected(Span::new_unchecked(0, 2)),
-                            );
-                        } else if value != Ok(v) {
-                            self.errors.report_error(
-                                ParseError::new("mismatched bool value")
-                                    .with_context(Span::new_unchecked(0, raw.len()))
-                                    .with_unexpected(Span::new_unchecked(0, 2)),
-                            );
-                        }
-                    }
-                    ScalarKind::DateTime => {
-                        let value = value.parse::<toml_datetime::Datetime>();
-                        if value.is_err() {
-                            self.errors.report_error(
-                                ParseError::new("failed to parse datetime")
-                                    .with_context(Span::new_unchecked(0, raw.len()))
-                                    .with_unexpected(Span::new_unchecked(0, 2)),
-                            );
-                        }
-                    }
-                    ScalarKind::Float => {
-                        let value = value.parse::<f64>();
-                        if value.is_err() {
-                            self.errors.report_error(
-                                ParseError::new("failed to parse f64")
-                                    .with_context(Span::new_unchecked(0, raw.len()))
-                                    .with_unexpected(Span::new_unchecked(0, 2)),
-                            );
-                        }
-                    }
-                    ScalarKind::Integer(radix) => {
-                        let value = i64::from_str_radix(&value, radix.value());
-                        if value.is_err() {
-                            self.errors.report_error(
-                                ParseError::new("failed to parse i64")
-                                    .with_context(Span::new_unchecked(0, raw.len()))
-                                    .with_unexpected(Span::new_unchecked(0, 2)),
-                            );
-                        }
-                    }
-                }
-            }
-        }
+        decode_events(self.input, &self.events, &mut |e| self.errors.push(e));
 
         assert_data_eq!(self.to_debug(), expected);
         if !self.events.is_empty() {
@@ -132,4 +75,75 @@ impl<'i> EventResults<'i> {
     }
 }
 
+/// Decode the keys and values in `events`, as a parser would, reporting what's invalid
+pub fn decode_events(input: &str, events: &[Event], errors: &mut dyn ErrorSink) {
+    let doc = Source::new(input);
+    for event in events {
+        if event.kind() == EventKind::SimpleKey {
+            let raw = doc.get(event).unwrap();
+            raw.decode_key(&mut (), errors);
+        } else if event.kind() == EventKind::Comment {
+            let raw = doc.get(event).unwrap();
+            raw.decode_comment(errors);
+        } else if event.kind() == EventKind::Newline {
+            let raw = doc.get(event).unwrap();
+            raw.decode_newline(errors);
+        } else if event.kind() == EventKind::Scalar {
+            let raw = doc.get(event).unwrap();
+            let mut value = String::new();
+            let kind = raw.decode_scalar(&mut value, errors);
+            dbg!(&value);
+            match kind {
+                ScalarKind::String => {}
+                ScalarKind::Boolean(v) => {
+                    let value = value.parse::<bool>();
+                    if value.is_err() {
+                        errors.report_error(
+                            ParseError::new("failed to parse bool")
+                                .with_context(event.span())
+                                .with_unexpected(event.span()),
+                        );
+                    } else if value != Ok(v) {
+                        errors.report_error(
+                            ParseError::new("mismatched bool value")
+                                .with_context(event.span())
+                                .with_unexpected(event.span()),
+                        );
+                    }
+                }
+                ScalarKind::DateTime => {
+                    let value = value.parse::<toml_datetime::Datetime>();
+                    if value.is_err() {
+                        errors.report_error(
+                            ParseError::new("failed to parse datetime")
+                                .with_context(event.span())
+                                .with_unexpected(event.span()),
+                        );
+                    }
+                }
+                ScalarKind::Float => {
+                    let value = value.parse::<f64>();
+                    if value.is_err() {
+                        errors.report_error(
+                            ParseError::new("failed to parse f64")
+                                .with_context(event.span())
+                                .with_unexpected(event.span()),
+                        );
+                    }
+                }
+                ScalarKind::Integer(radix) => {
+                    let value = i64::from_str_radix(&value, radix.value());
+                    if value.is_err() {
+                        errors.report_error(
+                            ParseError::new("failed to parse i64")
+                                .with_context(event.span())
+                                .with_unexpected(event.span()),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
 const BOM: &[u8] = b"\xEF\xBB\xBF";
@@ -1,7 +1,11 @@
 use snapbox::file;
 
+use toml_parse::decoder::CarriageReturn;
 use toml_parse::parser::*;
+use toml_parse::ParseError;
+use toml_parse::Severity;
 use toml_parse::Source;
+use toml_parse::Span;
 
 #[track_caller]
 fn t(input: &str, expected: impl snapbox::data::IntoData) {
@@ -28,6 +32,26 @@ fn t_recurse(input: &str, max_depth: u32, expected: impl snapbox::data::IntoData
     actual.validate(expected);
 }
 
+#[track_caller]
+fn t_size_limit(
+    input: &str,
+    max_value_len: usize,
+    max_string_len: usize,
+    expected: impl snapbox::data::IntoData,
+) {
+    dbg!(input);
+    let mut actual = crate::EventResults::new(input);
+
+    let doc = Source::new(input);
+    let tokens = doc.lex().into_vec();
+    let mut limit =
+        SizeLimit::new(&mut actual.events, max_value_len).with_max_string_len(max_string_len);
+    parse_document(&tokens, &mut limit, &mut actual.errors);
+    assert!(limit.is_truncated());
+
+    actual.validate(expected);
+}
+
 #[test]
 fn document_empty() {
     t("", file![_].raw());
@@ -482,3 +506,237 @@ after = [10]
         file![_].raw(),
     );
 }
+
+#[test]
+fn document_preserved_cr_warns() {
+    let input = "a = 1 # old\rcomment\rb = 2\n";
+    let source = Source::new(input);
+    let tokens = source
+        .lex()
+        .with_carriage_return(CarriageReturn::Preserve)
+        .into_vec();
+    let mut events = Vec::<Event>::new();
+    let mut receiver =
+        ValidateWhitespace::new(&mut events, source).with_carriage_return(CarriageReturn::Preserve);
+    let mut errors = Vec::<ParseError>::new();
+    parse_document(&tokens, &mut receiver, &mut |e| errors.push(e));
+
+    let actual = errors
+        .iter()
+        .map(|e| (e.severity(), e.description(), e.unexpected()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        actual,
+        [
+            (
+                Severity::Warning,
+                "carriage return without newline",
+                Some(Span::new_unchecked(11, 12))
+            ),
+            (
+                Severity::Warning,
+                "carriage return without newline",
+                Some(Span::new_unchecked(19, 20))
+            ),
+        ]
+    );
+
+    // Only errors fail the parse
+    let mut events = Vec::<Event>::new();
+    let mut receiver =
+        ValidateWhitespace::new(&mut events, source).with_carriage_return(CarriageReturn::Preserve);
+    let mut error = None::<ParseError>;
+    parse_document(&tokens, &mut receiver, &mut error);
+    assert_eq!(error, None);
+    let mut events = Vec::<Event>::new();
+    let mut receiver =
+        ValidateWhitespace::new(&mut events, source).with_carriage_return(CarriageReturn::Preserve);
+    let mut errors = Vec::<ParseError>::new();
+    parse_document(&tokens, &mut receiver, &mut errors);
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn document_unclosed_table_keeps_keys() {
+    t("[a.b.\nx = 1\n", file![_].raw());
+}
+
+#[test]
+fn document_unclosed_array_table_keeps_keys() {
+    t("[[a.b\nx = 1\n", file![_].raw());
+}
+
+#[test]
+fn document_errors_display() {
+    let input = "a ] 1\nb = ]\n[c\n";
+    let source = Source::new(input);
+    let tokens = source.lex().into_vec();
+    let mut events = Vec::<Event>::new();
+    let mut errors = Vec::<ParseError>::new();
+    parse_document(&tokens, &mut events, &mut errors);
+
+    let errors = errors
+        .into_iter()
+        .map(|e| Box::new(e) as Box<dyn std::error::Error>)
+        .map(|e| format!("{e}\n"))
+        .collect::<String>();
+    snapbox::assert_data_eq!(
+        errors,
+        snapbox::str![[r#"
+key with no value, expected `=`
+missing array opening, expected `[`
+unclosed table, expected `]`
+
+"#]]
+    );
+}
+
+#[test]
+fn value_outside_size_limit() {
+    t_size_limit(
+        "
+key = 123456789
+after = 1
+",
+        4,
+        4,
+        file![_].raw(),
+    );
+}
+
+#[test]
+fn string_outside_size_limit() {
+    t_size_limit(
+        "
+'long key' = 'long value'
+after = 123456789
+",
+        10,
+        4,
+        file![_].raw(),
+    );
+}
+
+#[test]
+fn size_limit_not_met() {
+    let input = "key = 'value'\n";
+    let source = Source::new(input);
+    let tokens = source.lex().into_vec();
+    let mut events = Vec::<Event>::new();
+    let mut errors = Vec::<ParseError>::new();
+    let mut limit = SizeLimit::new(&mut events, 7);
+    parse_document(&tokens, &mut limit, &mut errors);
+    assert!(!limit.is_truncated());
+    assert_eq!(errors, []);
+}
+
+#[test]
+fn source_id() {
+    use toml_parse::SourceId;
+
+    let input = "a\nb = 1\n";
+    let source = Source::new(input);
+    assert_eq!(source.id(), SourceId::default());
+    let source = source.with_id(SourceId::new(3));
+    assert_eq!(source.id().get(), 3);
+    assert_ne!(source, Source::new(input));
+
+    // The parser leaves tagging errors to the sink, which knows the source
+    let tokens = source.lex().into_vec();
+    let mut events = Vec::<Event>::new();
+    let mut errors = Vec::<ParseError>::new();
+    parse_document(&tokens, &mut events, &mut |e: ParseError| {
+        errors.push(e.with_source_id(source.id()));
+    });
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].source_id(), SourceId::new(3));
+    assert_eq!(errors[0].unexpected(), Some(Span::new_unchecked(1, 1)));
+
+    let error = ParseError::new("custom");
+    assert_eq!(error.source_id(), SourceId::default());
+    assert_ne!(error.clone().with_source_id(SourceId::new(1)), error);
+}
+
+#[test]
+fn size_limit_stops_lexer() {
+    let input = "a = 'long value'\nb = 1\n";
+    let source = Source::new(input);
+    let tokens = source.lex().with_max_token_len(4).into_vec();
+    let last = tokens.last().unwrap();
+    assert_eq!(last.kind(), toml_parse::lexer::TokenKind::Eof);
+    assert_eq!(last.span(), Span::new_unchecked(16, 16));
+    assert!(tokens.iter().all(|t| t.span().end() <= 16));
+
+    let mut events = Vec::<Event>::new();
+    let mut errors = Vec::<ParseError>::new();
+    let mut limit = SizeLimit::new(&mut events, 4);
+    parse_document(&tokens, &mut limit, &mut errors);
+    assert!(limit.is_truncated());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].context(), Some(Span::new_unchecked(4, 16)));
+    assert_eq!(errors[0].unexpected(), Some(Span::new_unchecked(8, 16)));
+
+    // Short tokens are not limited
+    let tokens = source.lex().with_max_token_len(12).into_vec();
+    assert_eq!(tokens, source.lex().into_vec());
+}
+
+#[test]
+fn abort_hook_skips_rest() {
+    let input = "a = 1\n[t]\nb = [\n";
+    let source = Source::new(input);
+    let tokens = source.lex().into_vec();
+    let mut events = Vec::<Event>::new();
+    let mut errors = Vec::<ParseError>::new();
+    let mut hook = AbortHook::new(&mut events, |e: &Event| {
+        e.kind() == EventKind::StdTableClose
+    });
+    parse_document(&tokens, &mut hook, &mut errors);
+    assert!(hook.is_aborted());
+
+    let kinds = events
+        .iter()
+        .filter(|e| !matches!(e.kind(), EventKind::Whitespace))
+        .map(|e| e.kind())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            EventKind::SimpleKey,
+            EventKind::KeyValSep,
+            EventKind::Scalar,
+            EventKind::Newline,
+            EventKind::StdTableOpen,
+            EventKind::SimpleKey,
+            EventKind::StdTableClose,
+            EventKind::Newline,
+        ]
+    );
+    assert_eq!(errors, []);
+}
+
+#[test]
+#[cfg(not(feature = "unsafe"))]
+fn document_out_of_range_span_errors() {
+    let source = Source::new("a = 1\n");
+    let mut events = Vec::<Event>::new();
+    let mut receiver = ValidateWhitespace::new(&mut events, source);
+    let mut errors = Vec::<ParseError>::new();
+    receiver.whitespace(Span::new_unchecked(3, 20), &mut errors);
+    receiver.comment(Span::new_unchecked(20, 30), &mut errors);
+    receiver.newline(Span::new_unchecked(5, 7), &mut errors);
+
+    let actual = errors
+        .iter()
+        .map(|e| (e.description(), e.unexpected()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        actual,
+        [
+            ("invalid token span", Some(Span::new_unchecked(3, 20))),
+            ("invalid token span", Some(Span::new_unchecked(20, 30))),
+            ("invalid token span", Some(Span::new_unchecked(5, 7))),
+        ]
+    );
+    assert_eq!(events.len(), 3);
+}
@@ -67,6 +67,10 @@ mod write;
 #[cfg(feature = "alloc")]
 pub use key::ToTomlKey;
 pub use key::WriteTomlKey;
+#[cfg(feature = "alloc")]
+pub use string::escape_basic_string;
+pub use string::needs_quoting;
+pub use string::write_escaped_basic_string;
 pub use string::TomlKey;
 pub use string::TomlKeyBuilder;
 pub use string::TomlString;
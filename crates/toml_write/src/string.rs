@@ -1,3 +1,6 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
 #[derive(Copy, Clone, Debug)]
 pub struct TomlStringBuilder<'s> {
     decoded: &'s str,
@@ -178,6 +181,49 @@ impl crate::WriteTomlKey for TomlKey<'_> {
     }
 }
 
+/// Escape `decoded` for writing between the `"` of a basic string
+///
+/// This is the escaping used by [`TomlStringBuilder::as_basic`] and [`TomlKeyBuilder::as_basic`],
+/// so generated TOML can match what this crate writes.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(toml_write::escape_basic_string("say \"hi\"\n"), r#"say \"hi\"\n"#);
+/// assert_eq!(toml_write::escape_basic_string("\u{7f}"), r#"\u007F"#);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn escape_basic_string(decoded: &str) -> String {
+    let mut escaped = String::with_capacity(decoded.len());
+    write_escaped_basic_string(decoded, &mut escaped).expect("writing to a `String` can't fail");
+    escaped
+}
+
+/// Write `decoded` escaped for a basic string, see [`escape_basic_string`]
+pub fn write_escaped_basic_string<W: core::fmt::Write + ?Sized>(
+    decoded: &str,
+    writer: &mut W,
+) -> core::fmt::Result {
+    write_escaped(decoded, false, writer)
+}
+
+/// Whether `key` has to be quoted rather than written bare
+///
+/// Bare keys may only contain ASCII letters, digits, `-`, and `_`, and can't be empty.
+/// [`TomlKeyBuilder::as_unquoted`] is `None` exactly when this is `true`.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!toml_write::needs_quoting("serde_json"));
+/// assert!(toml_write::needs_quoting("cfg(unix)"));
+/// assert!(toml_write::needs_quoting("café"));
+/// assert!(toml_write::needs_quoting(""));
+/// ```
+pub fn needs_quoting(key: &str) -> bool {
+    !KeyMetrics::calculate(key).unquoted
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[repr(u8)]
 #[allow(clippy::enum_variant_names)]
@@ -220,80 +266,89 @@ fn write_toml_value<W: crate::TomlWrite + ?Sized>(
         writer.newline()?;
     }
     if escaped {
-        // ```bnf
-        // basic-unescaped = wschar / %x21 / %x23-5B / %x5D-7E / non-ascii
-        // wschar =  %x20  ; Space
-        // wschar =/ %x09  ; Horizontal tab
-        // escape = %x5C                   ; \
-        // ```
-        let max_seq_double_quotes = if is_ml { 2 } else { 0 };
-        let mut stream = decoded;
-        while !stream.is_empty() {
-            let mut unescaped_end = 0;
-            let mut escaped = None;
-            let mut seq_double_quotes = 0;
-            for (i, b) in stream.as_bytes().iter().enumerate() {
-                if *b == b'"' {
-                    seq_double_quotes += 1;
-                    if max_seq_double_quotes < seq_double_quotes {
-                        escaped = Some(r#"\""#);
-                        break;
-                    }
-                } else {
-                    seq_double_quotes = 0;
-                }
+        write_escaped(decoded, is_ml, writer)?;
+    } else {
+        write!(writer, "{decoded}")?;
+    }
+    write!(writer, "{delimiter}")?;
+    Ok(())
+}
 
-                match *b {
-                    0x8 => {
-                        escaped = Some(r#"\b"#);
-                        break;
-                    }
-                    0x9 => {
-                        escaped = Some(r#"\t"#);
-                        break;
-                    }
-                    0xa => {
-                        if !is_ml {
-                            escaped = Some(r#"\n"#);
-                            break;
-                        }
-                    }
-                    0xc => {
-                        escaped = Some(r#"\f"#);
-                        break;
-                    }
-                    0xd => {
-                        escaped = Some(r#"\r"#);
-                        break;
-                    }
-                    0x22 => {} // double quote handled earlier
-                    0x5c => {
-                        escaped = Some(r#"\\"#);
-                        break;
-                    }
-                    c if c <= 0x1f || c == 0x7f => {
-                        break;
-                    }
-                    _ => {}
+/// Write `decoded` as the content of a basic string, escaping what can't be written as-is
+fn write_escaped<W: core::fmt::Write + ?Sized>(
+    decoded: &str,
+    is_ml: bool,
+    writer: &mut W,
+) -> core::fmt::Result {
+    // ```bnf
+    // basic-unescaped = wschar / %x21 / %x23-5B / %x5D-7E / non-ascii
+    // wschar =  %x20  ; Space
+    // wschar =/ %x09  ; Horizontal tab
+    // escape = %x5C                   ; \
+    // ```
+    let max_seq_double_quotes = if is_ml { 2 } else { 0 };
+    let mut stream = decoded;
+    while !stream.is_empty() {
+        let mut unescaped_end = 0;
+        let mut escaped = None;
+        let mut seq_double_quotes = 0;
+        for (i, b) in stream.as_bytes().iter().enumerate() {
+            if *b == b'"' {
+                seq_double_quotes += 1;
+                if max_seq_double_quotes < seq_double_quotes {
+                    escaped = Some(r#"\""#);
+                    break;
                 }
-
-                unescaped_end = i + 1;
+            } else {
+                seq_double_quotes = 0;
             }
-            let unescaped = &stream[0..unescaped_end];
-            let escaped_str = escaped.unwrap_or("");
-            let end = unescaped_end + if escaped.is_some() { 1 } else { 0 };
-            stream = &stream[end..];
-            write!(writer, "{unescaped}{escaped_str}")?;
-            if escaped.is_none() && !stream.is_empty() {
-                let b = stream.as_bytes().first().unwrap();
-                write!(writer, "\\u{:04X}", *b as u32)?;
-                stream = &stream[1..];
+
+            match *b {
+                0x8 => {
+                    escaped = Some(r#"\b"#);
+                    break;
+                }
+                0x9 => {
+                    escaped = Some(r#"\t"#);
+                    break;
+                }
+                0xa if !is_ml => {
+                    escaped = Some(r#"\n"#);
+                    break;
+                }
+                0xa => {} // written as-is in multi-line strings
+                0xc => {
+                    escaped = Some(r#"\f"#);
+                    break;
+                }
+                0xd => {
+                    escaped = Some(r#"\r"#);
+                    break;
+                }
+                0x22 => {} // double quote handled earlier
+                0x5c => {
+                    escaped = Some(r#"\\"#);
+                    break;
+                }
+                c if c <= 0x1f || c == 0x7f => {
+                    break;
+                }
+                _ => {}
             }
+
+            unescaped_end = i + 1;
+        }
+        let unescaped = &stream[0..unescaped_end];
+        let escaped_str = escaped.unwrap_or("");
+        let end = unescaped_end + if escaped.is_some() { 1 } else { 0 };
+        stream = &stream[end..];
+        write!(writer, "{unescaped}{escaped_str}")?;
+        if escaped.is_none() && !stream.is_empty() {
+            let b = stream.as_bytes().first().unwrap();
+            write!(writer, "\\u{:04X}", *b as u32)?;
+            stream = &stream[1..];
         }
-    } else {
-        write!(writer, "{decoded}")?;
     }
-    write!(writer, "{delimiter}")?;
     Ok(())
 }
 
@@ -601,4 +601,33 @@ proptest! {
         assert_eq!(*key, decoded);
         assert_eq!(value.as_str().unwrap(), decoded);
     }
+
+    #[test]
+    fn escaped_round_trips(decoded in "\\PC*") {
+        let escaped = toml_write::escape_basic_string(&decoded);
+        assert_eq!(format!("\"{escaped}\""), TomlStringBuilder::new(&decoded).as_basic().to_toml_value());
+
+        let toml = format!("key = \"{escaped}\"");
+        dbg!(&toml);
+        let value = match toml.parse::<toml_old::Value>() {
+            Ok(value) => value,
+            Err(err) => panic!("could not parse: {err}"),
+        };
+        assert_eq!(value["key"].as_str().unwrap(), decoded);
+    }
+
+    #[test]
+    fn needs_quoting_matches_builder(key in "\\PC*") {
+        let unquoted = TomlKeyBuilder::new(&key).as_unquoted();
+        assert_eq!(toml_write::needs_quoting(&key), unquoted.is_none());
+        if !toml_write::needs_quoting(&key) {
+            let toml = format!("{key} = 1");
+            dbg!(&toml);
+            let value = match toml.parse::<toml_old::Value>() {
+                Ok(value) => value,
+                Err(err) => panic!("could not parse: {err}"),
+            };
+            assert!(value.as_table().unwrap().contains_key(&key));
+        }
+    }
 }